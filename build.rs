@@ -25,12 +25,28 @@ fn generate_upstream_tests(testdata_dir: &Path, dest_path: &Path) -> std::io::Re
             let dir_name = path.file_name().unwrap().to_str().unwrap();
             let fn_name = format_ident!("test_{}", dir_name.replace(['.', '-'], "_"));
 
+            // Fixtures with an input tree have annotated expectations; see src/fixture.rs
+            if path.join("input").is_dir() {
+                let test = quote! {
+                    #[tokio::test]
+                    async fn #fn_name() {
+                        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata").join(#dir_name);
+                        let expected = crate::fixture::load_expected(&dir).unwrap();
+                        let actual = crate::fixture::run_fixture(&dir).await.unwrap();
+                        assert_eq!(expected, actual);
+                    }
+                };
+
+                writeln!(w, "{}", test)?;
+                continue;
+            }
+
             let test = quote! {
                 #[tokio::test]
                 async fn #fn_name() {
                     let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testdata").join(#dir_name);
                     let expected: serde_yaml::Value = serde_yaml::from_reader(std::fs::File::open(dir.join("expected.yaml")).unwrap()).unwrap();
-                    let actual: serde_yaml::Value = serde_yaml::to_value(crate::get_upstream_info(&dir, Some(true), Some(false), Some(false), Some(false)).await.unwrap()).unwrap();
+                    let actual: serde_yaml::Value = serde_yaml::to_value(crate::MetadataGuesser::builder().trust(true).build().guess(&dir).await.unwrap()).unwrap();
                     assert_eq!(expected, actual);
                 }
             };
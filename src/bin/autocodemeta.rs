@@ -6,6 +6,13 @@ use std::io::Write;
 use std::path::PathBuf;
 use upstream_ontologist::UpstreamDatum;
 
+#[derive(Serialize)]
+struct Organization {
+    #[serde(rename = "@type")]
+    type_: &'static str,
+    name: String,
+}
+
 #[derive(Serialize, Default)]
 struct SoftwareSourceCode {
     name: Option<String>,
@@ -34,6 +41,7 @@ struct SoftwareSourceCode {
     download_url: Option<String>,
     #[serde(rename = "relatedLink")]
     related_link: HashSet<String>,
+    publisher: Option<Organization>,
 }
 
 fn valid_spdx_identifier(name: &str) -> bool {
@@ -80,13 +88,19 @@ fn codemeta_file_from_upstream_info(data: Vec<UpstreamDatum>) -> SoftwareSourceC
                 result.related_link.insert(r);
             }
             UpstreamDatum::License(l) => {
-                if valid_spdx_identifier(&l) {
+                if valid_spdx_identifier(l.as_str()) {
                     result.license = Some(format!("https://spdx.org/licenses/{}", l));
                 }
             }
             UpstreamDatum::Version(v) => {
                 result.version = Some(v);
             }
+            UpstreamDatum::Organization(o) => {
+                result.publisher = Some(Organization {
+                    type_: "Organization",
+                    name: o,
+                });
+            }
             UpstreamDatum::Documentation(a) => {
                 result.related_link.insert(a);
             }
@@ -142,15 +156,15 @@ async fn main() {
 
     let path = args.path.canonicalize().unwrap();
 
-    let upstream_info = upstream_ontologist::get_upstream_info(
-        path.as_path(),
-        Some(args.trust),
-        Some(!args.disable_net_access),
-        Some(args.consult_external_directory),
-        Some(args.check),
-    )
-    .await
-    .unwrap();
+    let upstream_info = upstream_ontologist::MetadataGuesser::builder()
+        .trust(args.trust)
+        .net_access(!args.disable_net_access)
+        .consult_external_directory(args.consult_external_directory)
+        .check(args.check)
+        .build()
+        .guess(path.as_path())
+        .await
+        .unwrap();
 
     let codemeta = codemeta_file_from_upstream_info(upstream_info.into());
 
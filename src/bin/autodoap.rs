@@ -244,15 +244,15 @@ async fn main() {
 
     let path = args.path.canonicalize().unwrap();
 
-    let upstream_info = upstream_ontologist::get_upstream_info(
-        path.as_path(),
-        Some(args.trust),
-        Some(!args.disable_net_access),
-        Some(args.consult_external_directory),
-        Some(args.check),
-    )
-    .await
-    .unwrap();
+    let upstream_info = upstream_ontologist::MetadataGuesser::builder()
+        .trust(args.trust)
+        .net_access(!args.disable_net_access)
+        .consult_external_directory(args.consult_external_directory)
+        .check(args.check)
+        .build()
+        .guess(path.as_path())
+        .await
+        .unwrap();
 
     let el = doap_file_from_upstream_info(upstream_info.into());
 
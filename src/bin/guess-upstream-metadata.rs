@@ -5,11 +5,35 @@ use std::io::Write;
 use futures::stream::StreamExt;
 use std::path::PathBuf;
 
+#[derive(Debug, Clone, Copy)]
+enum OutputFormat {
+    Yaml,
+    Json,
+    AnnotatedJson,
+    Turtle,
+    JsonLd,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yaml" => Ok(OutputFormat::Yaml),
+            "json" => Ok(OutputFormat::Json),
+            "json-annotated" => Ok(OutputFormat::AnnotatedJson),
+            "turtle" | "ttl" => Ok(OutputFormat::Turtle),
+            "json-ld" | "jsonld" => Ok(OutputFormat::JsonLd),
+            _ => Err(format!("unknown output format: {}", s)),
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version)]
 struct Args {
     /// Whether to allow running code from the package
-    #[clap(long)]
+    #[clap(long, alias = "trust-package")]
     trust: bool,
 
     /// Whether to enable debug logging
@@ -21,10 +45,23 @@ struct Args {
     trace: bool,
 
     /// Do not probe external services
-    #[clap(long)]
+    #[clap(long, overrides_with = "net_access")]
     disable_net_access: bool,
 
-    /// Check guesssed metadata against external sources
+    /// Probe external services (the default)
+    #[clap(long, overrides_with = "disable_net_access")]
+    net_access: bool,
+
+    /// Only report fields that are known with at least this certainty (certain, confident,
+    /// likely, possible)
+    #[clap(long)]
+    minimum_certainty: Option<upstream_ontologist::Certainty>,
+
+    /// Only guess the specified field; can be specified multiple times
+    #[clap(long = "field", value_name = "FIELD")]
+    fields: Vec<String>,
+
+    /// Check guesssed metadata against external sources, and report sources that disagree
     #[clap(long)]
     check: bool,
 
@@ -36,6 +73,11 @@ struct Args {
     #[clap(long)]
     scan: bool,
 
+    /// Emit each datum as a JSON record on its own line (NDJSON) as soon as it is discovered,
+    /// rather than printing the summarized results
+    #[clap(long)]
+    ndjson: bool,
+
     /// Scan specified homepage rather than current directory
     #[clap(long)]
     from_homepage: Option<url::Url>,
@@ -44,9 +86,30 @@ struct Args {
     #[clap(long)]
     from_repology: Option<String>,
 
+    /// Record a test fixture for the sources in the specified directory and exit
+    #[clap(long)]
+    record_fixture: Option<PathBuf>,
+
+    /// Verify an existing debian/upstream/metadata or DOAP file, print the changes that
+    /// should be made to it and exit; the exit code is 1 if there are any, and 2 on error
+    #[clap(long, value_name = "FILE")]
+    verify: Option<PathBuf>,
+
     /// Pull in external (not maintained by upstream) directory data
     #[clap(long)]
     consult_external_directory: bool,
+
+    /// Preferred format for the repository URL (preserve, https, git+https, ssh)
+    #[clap(long, default_value = "preserve")]
+    repository_policy: upstream_ontologist::vcs::RepositoryUrlPolicy,
+
+    /// Strip well-known packaging prefixes (e.g. "python-") from the name, keeping it as an alias
+    #[clap(long)]
+    strip_name_prefixes: bool,
+
+    /// Output format (yaml, json, json-annotated, turtle, json-ld)
+    #[clap(long, default_value = "yaml")]
+    format: OutputFormat,
 }
 
 #[tokio::main]
@@ -67,7 +130,40 @@ async fn main() {
         )
         .init();
 
-    if let Some(from_homepage) = args.from_homepage {
+    if let Some(fixture) = args.record_fixture {
+        match upstream_ontologist::fixture::record_fixture(
+            &args.path.canonicalize().unwrap(),
+            &fixture,
+        )
+        .await
+        {
+            Ok(()) => {
+                log::info!("Recorded fixture in {}", fixture.display());
+            }
+            Err(e) => {
+                eprintln!("Unable to record fixture: {}", e);
+                std::process::exit(1);
+            }
+        }
+    } else if let Some(path) = args.verify {
+        match upstream_ontologist::check::verify_file(&path, Some(!args.disable_net_access)).await {
+            Ok((changes, report)) => {
+                for change in &changes {
+                    println!("{}", change);
+                }
+                for issue in &report.issues {
+                    eprintln!("{}", issue);
+                }
+                if !changes.is_empty() || !report.is_empty() {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Unable to verify {}: {}", path.display(), e);
+                std::process::exit(2);
+            }
+        }
+    } else if let Some(from_homepage) = args.from_homepage {
         for d in upstream_ontologist::homepage::guess_from_homepage(&from_homepage)
             .await
             .unwrap()
@@ -95,7 +191,7 @@ async fn main() {
                 d.origin
             );
         }
-    } else if args.scan {
+    } else if args.scan && !args.ndjson {
         let mut stream = upstream_ontologist::upstream_metadata_stream(
             &args.path.canonicalize().unwrap(),
             Some(args.trust),
@@ -115,15 +211,40 @@ async fn main() {
             );
         }
     } else {
-        let metadata = match upstream_ontologist::guess_upstream_metadata(
-            &args.path.canonicalize().unwrap(),
-            Some(args.trust),
-            Some(!args.disable_net_access),
-            Some(args.consult_external_directory),
-            Some(args.check),
-        )
-        .await
-        {
+        let mut builder = upstream_ontologist::MetadataGuesser::builder()
+            .trust(args.trust)
+            .net_access(!args.disable_net_access)
+            .consult_external_directory(args.consult_external_directory)
+            .check(args.check);
+        if let Some(minimum_certainty) = args.minimum_certainty {
+            builder = builder.minimum_certainty(minimum_certainty);
+        }
+        if !args.fields.is_empty() {
+            let mut fields = upstream_ontologist::FieldSet::empty();
+            for field in &args.fields {
+                if !fields.insert(field) {
+                    eprintln!("Unknown field: {}", field);
+                    std::process::exit(1);
+                }
+            }
+            builder = builder.fields(fields);
+        }
+        let guesser = builder.build();
+        if args.ndjson {
+            match guesser
+                .write_ndjson(&args.path.canonicalize().unwrap(), std::io::stdout().lock())
+                .await
+            {
+                Ok(()) => return,
+                // The consumer is no longer interested
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => return,
+                Err(e) => {
+                    eprintln!("Error writing events: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        let mut metadata = match guesser.guess(&args.path.canonicalize().unwrap()).await {
             Ok(m) => m,
             Err(upstream_ontologist::ProviderError::ParseError(e)) => {
                 eprintln!("Error parsing metadata: {}", e);
@@ -145,12 +266,42 @@ async fn main() {
                 eprintln!("Extraoplation limit exceeded: {}", l);
                 std::process::exit(1);
             }
+            Err(e @ upstream_ontologist::ProviderError::ExtrapolationCycle(_)) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
         };
 
-        let out = serde_yaml::to_value(&metadata).unwrap();
+        // guess() has already fixed up the metadata with the default policy
+        if args.repository_policy != upstream_ontologist::vcs::RepositoryUrlPolicy::default() {
+            upstream_ontologist::fix_upstream_metadata_with_policy(
+                &mut metadata,
+                args.repository_policy,
+                Some(!args.disable_net_access),
+            )
+            .await;
+        }
+
+        if args.strip_name_prefixes {
+            upstream_ontologist::names::strip_name_prefixes(&mut metadata);
+        }
+
+        let out = match args.format {
+            OutputFormat::Yaml => {
+                serde_yaml::to_string(&serde_yaml::to_value(&metadata).unwrap()).unwrap()
+            }
+            OutputFormat::Json => serde_json::to_string_pretty(&metadata.to_json()).unwrap() + "\n",
+            OutputFormat::AnnotatedJson => {
+                serde_json::to_string_pretty(&metadata.to_json_annotated()).unwrap() + "\n"
+            }
+            OutputFormat::Turtle => upstream_ontologist::rdf::to_turtle(&metadata),
+            OutputFormat::JsonLd => {
+                serde_json::to_string_pretty(&upstream_ontologist::rdf::to_jsonld(&metadata))
+                    .unwrap()
+                    + "\n"
+            }
+        };
 
-        std::io::stdout()
-            .write_all(serde_yaml::to_string(&out).unwrap().as_bytes())
-            .unwrap();
+        std::io::stdout().write_all(out.as_bytes()).unwrap();
     }
 }
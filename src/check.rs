@@ -0,0 +1,926 @@
+//! Reporting of problems found while checking upstream metadata.
+//!
+//! [`crate::check_upstream_metadata`] fixes what it can; issues that need a human to look
+//! at them are collected in a [`CheckReport`], e.g. for use by QA tooling.
+
+use crate::version::{compare_versions, Version};
+use crate::{
+    Certainty, Conflict, NetworkPolicy, Origin, ProviderError, ThirdPartyRepository, UpstreamDatum,
+    UpstreamDatumWithMetadata, UpstreamMetadata,
+};
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Policy for [`crate::check_upstream_metadata_with_policy`].
+#[derive(Debug, Clone)]
+pub struct CheckPolicy {
+    /// Only raise the certainty of Homepage if the page mentions the project name or links
+    /// to its repository, rather than for any page that can be retrieved
+    pub confirm_homepage_content: bool,
+
+    /// Whether the checks may access the network; nothing is checked if not
+    pub network: NetworkPolicy,
+}
+
+impl Default for CheckPolicy {
+    fn default() -> Self {
+        Self {
+            confirm_homepage_content: true,
+            network: NetworkPolicy::Online,
+        }
+    }
+}
+
+/// Number of newer releases after which a local version is considered far behind.
+const FAR_BEHIND_RELEASES: usize = 5;
+
+/// An issue found while checking upstream metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckIssue {
+    /// The local version is newer than any released version
+    VersionNewerThanReleases {
+        /// Version found in the package
+        version: String,
+        /// Latest released version
+        latest: String,
+    },
+
+    /// The local version is far behind the latest upstream release
+    VersionFarBehind {
+        /// Version found in the package
+        version: String,
+        /// Latest released version
+        latest: String,
+        /// Number of known releases that are newer than the local version
+        newer_releases: usize,
+    },
+
+    /// Different sources in the package disagree about the license
+    ConflictingLicenses {
+        /// All license statements that were found
+        statements: Vec<LicenseStatement>,
+    },
+
+    /// Different sources in the package disagree about the value of a field
+    ConflictingValues {
+        /// The value that was kept and the ones that were ignored
+        conflict: Conflict,
+    },
+
+    /// Two related fields don't agree with each other
+    Inconsistent {
+        /// The fields involved
+        fields: [&'static str; 2],
+        /// Description of the inconsistency
+        message: String,
+        /// Suggested fix, if one is known
+        suggestion: Option<String>,
+    },
+}
+
+impl std::fmt::Display for CheckIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckIssue::VersionNewerThanReleases { version, latest } => write!(
+                f,
+                "local version {} is newer than any release (latest: {})",
+                version, latest
+            ),
+            CheckIssue::VersionFarBehind {
+                version,
+                latest,
+                newer_releases,
+            } => write!(
+                f,
+                "local version {} is far behind upstream (latest: {}, {} newer releases)",
+                version, latest, newer_releases
+            ),
+            CheckIssue::ConflictingLicenses { statements } => {
+                write!(f, "conflicting license statements: ")?;
+                for (i, statement) in statements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", statement)?;
+                }
+                Ok(())
+            }
+            CheckIssue::ConflictingValues { conflict } => write!(f, "{}", conflict),
+            CheckIssue::Inconsistent {
+                fields: _,
+                message,
+                suggestion,
+            } => {
+                write!(f, "{}", message)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (suggestion: {})", suggestion)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A license as stated by a single source in the package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseStatement {
+    /// The license, as written by the source
+    pub license: String,
+    /// Where the statement was found
+    pub origin: Option<Origin>,
+    pub certainty: Option<Certainty>,
+}
+
+impl std::fmt::Display for LicenseStatement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.origin.as_ref() {
+            Some(origin) => write!(f, "{} (from {})", self.license, origin),
+            None => write!(f, "{}", self.license),
+        }
+    }
+}
+
+/// Split a license statement into its alternatives, normalized for comparison.
+///
+/// "MIT OR Apache-2.0", "Apache-2.0/MIT" and "The MIT License" all result in the same set of
+/// alternatives.
+fn license_alternatives(license: &str) -> std::collections::BTreeSet<String> {
+    let normalized = license
+        .replace(" or ", " OR ")
+        .replace(" | ", " OR ")
+        .replace('/', " OR ");
+    normalized
+        .split(" OR ")
+        .map(|alternative| {
+            alternative
+                .trim()
+                .trim_matches(|c| c == '(' || c == ')')
+                .to_lowercase()
+                .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+                .filter(|w| !w.is_empty() && *w != "the" && *w != "license")
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|alternative| !alternative.is_empty())
+        .collect()
+}
+
+/// Whether two license statements are consistent with each other.
+///
+/// Statements are considered consistent if one offers a subset of the alternatives of the
+/// other; e.g. a LICENSE-MIT file in a project that is "MIT OR Apache-2.0".
+fn licenses_consistent(a: &str, b: &str) -> bool {
+    let a = license_alternatives(a);
+    let b = license_alternatives(b);
+    a.is_subset(&b) || b.is_subset(&a)
+}
+
+/// Collect all license statements from a set of guessed metadata items.
+///
+/// Unlike [`UpstreamMetadata::update`], this keeps every statement rather than just the one
+/// with the highest certainty.
+pub fn license_statements(items: &[UpstreamDatumWithMetadata]) -> Vec<LicenseStatement> {
+    let mut ret: Vec<LicenseStatement> = vec![];
+    for item in items {
+        let license = match &item.datum {
+            UpstreamDatum::License(license) => license.as_str(),
+            _ => continue,
+        };
+        if ret
+            .iter()
+            .any(|s| s.license == *license && s.origin == item.origin)
+        {
+            continue;
+        }
+        ret.push(LicenseStatement {
+            license: license.to_string(),
+            origin: item.origin.clone(),
+            certainty: item.certainty,
+        });
+    }
+    ret
+}
+
+/// Check whether the license statements found in a package agree with each other.
+pub fn check_licenses(statements: &[LicenseStatement]) -> CheckReport {
+    let mut report = CheckReport::new();
+    let conflict = statements.iter().enumerate().any(|(i, a)| {
+        statements[i + 1..]
+            .iter()
+            .any(|b| !licenses_consistent(&a.license, &b.license))
+    });
+    if conflict {
+        report.push(CheckIssue::ConflictingLicenses {
+            statements: statements.to_vec(),
+        });
+    }
+    report
+}
+
+/// Report the fields that sources in the package disagree about.
+///
+/// License is skipped, since [`check_licenses`] knows which license statements are
+/// consistent with each other.
+pub fn check_conflicts(conflicts: &[Conflict]) -> CheckReport {
+    let mut report = CheckReport::new();
+    for conflict in conflicts {
+        if conflict.field() == "License" {
+            continue;
+        }
+        report.push(CheckIssue::ConflictingValues {
+            conflict: conflict.clone(),
+        });
+    }
+    report
+}
+
+/// Whether a bug database URL points at a tracker that is not tied to a repository host.
+///
+/// Projects that use e.g. Bugzilla or Launchpad for bugs commonly host their code
+/// elsewhere, so a different host is not suspicious for these.
+fn is_standalone_tracker(url: &url::Url) -> bool {
+    if url.scheme() == "mailto" {
+        return true;
+    }
+    let host = url.host_str().unwrap_or("");
+    let path = url.path().to_lowercase();
+    host == "bugs.launchpad.net"
+        || host.starts_with("bugs.")
+        || host.starts_with("bugzilla.")
+        || host.starts_with("issues.")
+        || host.starts_with("jira.")
+        || host.starts_with("trac.")
+        || path.contains("bugzilla")
+        || path.contains("show_bug.cgi")
+        || path.contains("/trac")
+        || path.contains("/jira")
+}
+
+/// Compare two URLs, ignoring a trailing slash and ".git" suffix.
+fn same_location(a: &url::Url, b: &url::Url) -> bool {
+    let normalize = |u: &url::Url| {
+        let s = u.as_str().trim_end_matches('/');
+        s.strip_suffix(".git").unwrap_or(s).to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Check whether related fields are consistent with each other.
+///
+/// This flags a Bug-Database on a different host than the Repository (unless it is a
+/// standalone tracker), a Repository-Browse that doesn't belong to the Repository, and a
+/// Homepage that is a Repository-Browse page on a forge where that doesn't make sense.
+pub async fn check_consistency(
+    metadata: &UpstreamMetadata,
+    net_access: Option<bool>,
+) -> CheckReport {
+    let mut report = CheckReport::new();
+    let url = |field: &str| metadata.get(field).and_then(|d| d.datum.to_url());
+    let repository = url("Repository");
+    let browse = url("Repository-Browse");
+
+    if let (Some(repository), Some(bug_database)) = (repository.as_ref(), url("Bug-Database")) {
+        if repository.host_str() != bug_database.host_str() && !is_standalone_tracker(&bug_database)
+        {
+            let suggestion = match crate::find_forge(repository, net_access).await {
+                Some(forge) => forge.bug_database_url_from_repo_url(repository),
+                None => None,
+            };
+            report.push(CheckIssue::Inconsistent {
+                fields: ["Bug-Database", "Repository"],
+                message: format!(
+                    "Bug-Database {} is not on the same host as Repository {}",
+                    bug_database, repository
+                ),
+                suggestion: suggestion.map(|u| format!("set Bug-Database to {}", u)),
+            });
+        }
+    }
+
+    if let (Some(repository), Some(browse)) = (repository.as_ref(), browse.as_ref()) {
+        let expected =
+            crate::vcs::browse_url_from_repo_url(&repository.clone().into(), net_access).await;
+        if let Some(expected) = expected {
+            if !same_location(&expected, browse) {
+                report.push(CheckIssue::Inconsistent {
+                    fields: ["Repository-Browse", "Repository"],
+                    message: format!(
+                        "Repository-Browse {} does not correspond to Repository {}",
+                        browse, repository
+                    ),
+                    suggestion: Some(format!("set Repository-Browse to {}", expected)),
+                });
+            }
+        }
+    }
+
+    if let (Some(homepage), Some(browse)) = (url("Homepage"), browse.as_ref()) {
+        if same_location(&homepage, browse) {
+            if let Some(forge) = crate::find_forge(browse, net_access).await {
+                if !forge.repository_browse_can_be_homepage() {
+                    report.push(CheckIssue::Inconsistent {
+                        fields: ["Homepage", "Repository-Browse"],
+                        message: format!(
+                            "Homepage {} is the repository browser on {}, which is not a homepage",
+                            homepage,
+                            forge.name()
+                        ),
+                        suggestion: Some(
+                            "remove Homepage or set it to the project page".to_string(),
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// Whether `name` appears in `text` as a separate word, ignoring case.
+fn mentions(text: &str, name: &str) -> bool {
+    let text = text.to_lowercase();
+    let name = name.to_lowercase();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(&name).any(|(i, _)| {
+        !text[..i].ends_with(is_word_char) && !text[i + name.len()..].starts_with(is_word_char)
+    })
+}
+
+/// Check whether the contents of a homepage confirm that it belongs to the project.
+///
+/// The page is considered to be confirmed if the project name appears in its title or
+/// body, or if it links to one of the repository URLs.
+pub fn page_confirms_project(
+    html: &str,
+    base: &url::Url,
+    name: Option<&str>,
+    repository_urls: &[url::Url],
+) -> bool {
+    use select::document::Document;
+    use select::predicate::Name;
+    let document = Document::from(html);
+    if let Some(name) = name.filter(|n| !n.is_empty()) {
+        let title = document
+            .find(Name("title"))
+            .map(|n| n.text())
+            .collect::<String>();
+        let body = document
+            .find(Name("body"))
+            .map(|n| n.text())
+            .collect::<String>();
+        if mentions(&title, name) || mentions(&body, name) {
+            return true;
+        }
+    }
+    document
+        .find(Name("a"))
+        .filter_map(|a| a.attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .any(|link| repository_urls.iter().any(|r| same_location(r, &link)))
+}
+
+/// Issues found while checking upstream metadata.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckReport {
+    /// The issues that were found
+    pub issues: Vec<CheckIssue>,
+}
+
+impl CheckReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether any issues were found.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn push(&mut self, issue: CheckIssue) {
+        self.issues.push(issue);
+    }
+
+    pub fn extend(&mut self, other: CheckReport) {
+        self.issues.extend(other.issues);
+    }
+}
+
+/// Compare a local version against the known releases.
+///
+/// # Arguments
+/// * `version`: Version found in the package
+/// * `releases`: Released versions, e.g. from a registry or VCS tags
+pub fn check_version_against_releases(version: &str, releases: &[String]) -> CheckReport {
+    let mut report = CheckReport::new();
+    let latest = match releases
+        .iter()
+        .max_by(|a, b| compare_versions(a.as_str(), b.as_str()))
+    {
+        Some(latest) => latest,
+        None => return report,
+    };
+
+    match compare_versions(version, latest) {
+        Ordering::Greater => report.push(CheckIssue::VersionNewerThanReleases {
+            version: version.to_string(),
+            latest: latest.clone(),
+        }),
+        Ordering::Less => {
+            let mut newer = releases
+                .iter()
+                .filter(|r| compare_versions(r, version) == Ordering::Greater)
+                .collect::<Vec<_>>();
+            newer.sort();
+            newer.dedup();
+            if newer.len() >= FAR_BEHIND_RELEASES
+                || Version::parse(latest).map(|v| v.major())
+                    > Version::parse(version).map(|v| v.major())
+            {
+                report.push(CheckIssue::VersionFarBehind {
+                    version: version.to_string(),
+                    latest: latest.clone(),
+                    newer_releases: newer.len(),
+                });
+            }
+        }
+        Ordering::Equal => {}
+    }
+
+    report
+}
+
+/// Find the registry that the package is published in, and the name it is published under.
+fn registry(
+    metadata: &UpstreamMetadata,
+) -> Option<(Box<dyn ThirdPartyRepository + Send + Sync>, String)> {
+    let archive = metadata.get("Archive")?.datum.as_str()?;
+    let field = |name: &str| {
+        metadata
+            .get(name)
+            .and_then(|d| d.datum.as_str())
+            .map(|s| s.to_string())
+    };
+    let name = field("Name");
+    Some(match archive {
+        #[cfg(feature = "cargo")]
+        "crates.io" => (
+            Box::new(crate::providers::rust::CratesIo::new()),
+            field("Cargo-Crate").or(name)?,
+        ),
+        "Flathub" => (Box::new(crate::providers::flatpak::Flathub::new()), name?),
+        "Hackage" => (
+            Box::new(crate::providers::haskell::Hackage::new()),
+            field("Hackage-Package").or(name)?,
+        ),
+        "Hex" => (Box::new(crate::providers::elixir::Hex::new()), name?),
+        #[cfg(feature = "julia")]
+        "Julia General" => (
+            Box::new(crate::providers::julia::JuliaGeneral::new()),
+            name?,
+        ),
+        "npm" => (Box::new(crate::providers::node::Npm::new()), name?),
+        "NuGet" => (Box::new(crate::providers::nuspec::NuGet::new()), name?),
+        "Packagist" => (
+            Box::new(crate::providers::composer_json::Packagist::new()),
+            name?,
+        ),
+        "PyPI" => (Box::new(crate::providers::python::PyPI::new()), name?),
+        "RubyGems" => (Box::new(crate::providers::ruby::RubyGems::new()), name?),
+        "CPAN" => (Box::new(crate::providers::perl::MetaCpan::new()), name?),
+        "CRAN" => (Box::new(crate::providers::r::Cran::new()), name?),
+        "Bioconductor" => (Box::new(crate::providers::r::Bioconductor::new()), name?),
+        _ => return None,
+    })
+}
+
+/// Cross-check a locally found Version against the registry and the VCS tags.
+///
+/// This will make network connections.
+pub async fn check_version(metadata: &UpstreamMetadata) -> CheckReport {
+    // Only check versions that were found in the package itself
+    let version = match metadata.get("Version") {
+        Some(v) if v.origin.is_some() && v.certainty >= Some(Certainty::Likely) => {
+            match v.datum.as_str() {
+                Some(version) => version.to_string(),
+                None => return CheckReport::new(),
+            }
+        }
+        _ => return CheckReport::new(),
+    };
+
+    let mut releases = vec![];
+
+    if let Some((repository, name)) = registry(metadata) {
+        match repository.guess_metadata(&name).await {
+            Ok(data) => releases.extend(
+                data.into_iter()
+                    .filter(|d| d.field() == "Version")
+                    .filter_map(|d| d.as_str().map(|s| s.to_string())),
+            ),
+            Err(e) => log::debug!(
+                "Unable to retrieve version from {}: {}",
+                repository.name(),
+                e
+            ),
+        }
+    }
+
+    if let Some(url) = metadata.get("Repository").and_then(|r| r.datum.to_url()) {
+        if let Some(tags) = crate::vcs::list_tags(&url).await {
+            releases.extend(
+                tags.iter()
+                    .filter_map(|t| crate::vcs::version_from_tag(t.as_str())),
+            );
+        }
+    }
+
+    check_version_against_releases(&version, &releases)
+}
+
+/// A change to a field of an existing metadata file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldChange {
+    /// The field should be removed, e.g. because the URL in it is invalid
+    Removed(UpstreamDatum),
+    /// The value of the field should be replaced
+    Updated {
+        /// The value in the file
+        old: UpstreamDatum,
+        /// The replacement value
+        new: UpstreamDatum,
+    },
+    /// The field is missing from the file
+    Added(UpstreamDatum),
+}
+
+impl FieldChange {
+    /// The field that is changed.
+    pub fn field(&self) -> &'static str {
+        match self {
+            FieldChange::Removed(datum) | FieldChange::Added(datum) => datum.field(),
+            FieldChange::Updated { new, .. } => new.field(),
+        }
+    }
+}
+
+/// Render a value the way it would appear in a metadata file.
+fn display_value(datum: &UpstreamDatum) -> String {
+    match datum.as_str() {
+        Some(s) => s.to_string(),
+        None => datum.to_json().to_string(),
+    }
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FieldChange::Removed(datum) => {
+                write!(f, "-{}: {}", datum.field(), display_value(datum))
+            }
+            FieldChange::Updated { old, new } => write!(
+                f,
+                "-{}: {}\n+{}: {}",
+                old.field(),
+                display_value(old),
+                new.field(),
+                display_value(new)
+            ),
+            FieldChange::Added(datum) => {
+                write!(f, "+{}: {}", datum.field(), display_value(datum))
+            }
+        }
+    }
+}
+
+/// Compare two sets of metadata, field by field.
+///
+/// Only the first value of each field is compared; certainty and origin are ignored.
+pub fn diff_metadata(old: &UpstreamMetadata, new: &UpstreamMetadata) -> Vec<FieldChange> {
+    let mut ret = vec![];
+    for datum in old.iter() {
+        let field = datum.datum.field();
+        if old.get(field).map(|d| &d.datum) != Some(&datum.datum) {
+            // Not the first value for this field
+            continue;
+        }
+        match new.get(field) {
+            None => ret.push(FieldChange::Removed(datum.datum.clone())),
+            Some(n) if n.datum != datum.datum => ret.push(FieldChange::Updated {
+                old: datum.datum.clone(),
+                new: n.datum.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for datum in new.iter() {
+        let field = datum.datum.field();
+        if old.get(field).is_none() && new.get(field).map(|d| &d.datum) == Some(&datum.datum) {
+            ret.push(FieldChange::Added(datum.datum.clone()));
+        }
+    }
+    ret
+}
+
+/// Read an existing metadata file, either DOAP or debian/upstream/metadata (DEP-12).
+pub fn load_metadata_file(path: &Path) -> Result<UpstreamMetadata, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let is_doap = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("doap") | Some("rdf")
+    ) || text.trim_start().starts_with('<');
+    let data = if is_doap {
+        crate::providers::doap::guess_from_doap(path, false)?
+    } else {
+        crate::providers::debian::guess_from_debian_upstream_metadata(
+            path,
+            &crate::GuesserSettings::default(),
+        )?
+    };
+    Ok(UpstreamMetadata::from_data(data))
+}
+
+/// Verify an existing metadata file.
+///
+/// The metadata in the file is fixed with [`crate::fix_upstream_metadata_with_policy`] and,
+/// if network access is allowed, checked with [`crate::check_upstream_metadata`]. Returns
+/// the changes that should be made to the file, and the issues that need a human to look
+/// at them. The file itself is not modified.
+pub async fn verify_file(
+    path: &Path,
+    net_access: Option<bool>,
+) -> Result<(Vec<FieldChange>, CheckReport), ProviderError> {
+    let old = load_metadata_file(path)?;
+    let mut new = old.clone();
+    crate::fix_upstream_metadata_with_policy(
+        &mut new,
+        crate::vcs::RepositoryUrlPolicy::Preserve,
+        net_access,
+    )
+    .await;
+    let mut report = CheckReport::new();
+    report.extend(
+        crate::check_upstream_metadata_with_policy(
+            &mut new,
+            None,
+            &CheckPolicy {
+                network: NetworkPolicy::from_net_access(net_access.unwrap_or(true)),
+                ..Default::default()
+            },
+        )
+        .await,
+    );
+    Ok((diff_metadata(&old, &new), report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_version_against_releases() {
+        let releases = ["0.9", "1.0", "1.1"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert!(check_version_against_releases("1.1", &releases).is_empty());
+        assert!(check_version_against_releases("1.0", &releases).is_empty());
+        assert_eq!(
+            check_version_against_releases("1.2", &releases).issues,
+            vec![CheckIssue::VersionNewerThanReleases {
+                version: "1.2".to_string(),
+                latest: "1.1".to_string(),
+            }]
+        );
+        assert!(check_version_against_releases("1.2", &[]).is_empty());
+
+        let releases = ["0.9", "1.0", "2.0"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            check_version_against_releases("0.9", &releases).issues,
+            vec![CheckIssue::VersionFarBehind {
+                version: "0.9".to_string(),
+                latest: "2.0".to_string(),
+                newer_releases: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_licenses_consistent() {
+        assert!(licenses_consistent("MIT", "The MIT License"));
+        assert!(licenses_consistent("MIT OR Apache-2.0", "Apache-2.0/MIT"));
+        assert!(licenses_consistent("MIT", "MIT OR Apache-2.0"));
+        assert!(licenses_consistent("BSD 3-Clause License", "BSD-3-Clause"));
+        assert!(!licenses_consistent("MIT OR Apache-2.0", "GPL-3.0+"));
+    }
+
+    #[test]
+    fn test_check_licenses() {
+        let item = |license: &str, path: &str| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::License(license.to_string().into()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(Origin::Path(path.into())),
+        };
+        let items = vec![
+            item("MIT OR Apache-2.0", "Cargo.toml"),
+            item("MIT", "LICENSE-MIT"),
+            item("MIT", "LICENSE-MIT"),
+        ];
+        let statements = license_statements(&items);
+        assert_eq!(statements.len(), 2);
+        assert!(check_licenses(&statements).is_empty());
+
+        let mut items = items;
+        items.push(item("GPL for plugins", "README.md"));
+        let statements = license_statements(&items);
+        let report = check_licenses(&statements);
+        assert_eq!(
+            report.issues,
+            vec![CheckIssue::ConflictingLicenses {
+                statements: statements.clone()
+            }]
+        );
+        assert_eq!(
+            report.issues[0].to_string(),
+            "conflicting license statements: MIT OR Apache-2.0 (from Cargo.toml); MIT (from LICENSE-MIT); GPL for plugins (from README.md)"
+        );
+    }
+
+    #[test]
+    fn test_check_conflicts() {
+        let mut metadata = UpstreamMetadata::new();
+        let mut conflicts = vec![];
+        metadata.update_with_conflicts(
+            vec![
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Homepage("https://example.com/".to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(Origin::Path("Cargo.toml".into())),
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Homepage("https://example.org/".to_string()),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(Origin::Path("README.md".into())),
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::License("MIT".into()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(Origin::Path("Cargo.toml".into())),
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::License("MIT OR Apache-2.0".into()),
+                    certainty: Some(Certainty::Likely),
+                    origin: Some(Origin::Path("README.md".into())),
+                },
+            ]
+            .into_iter(),
+            &mut conflicts,
+        );
+        assert_eq!(conflicts.len(), 2);
+        let report = check_conflicts(&conflicts);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.issues[0].to_string(),
+            "sources disagree about Homepage: using https://example.com/ (certain, from Cargo.toml); ignoring https://example.org/ (possible, from README.md)"
+        );
+    }
+
+    #[test]
+    fn test_page_confirms_project() {
+        let base: url::Url = "https://example.com/".parse().unwrap();
+        let repository: url::Url = "https://github.com/example/blah.git".parse().unwrap();
+        let html = r#"<html><head><title>Blah - a tool for blahing</title></head>
+<body><p>Welcome!</p></body></html>"#;
+        assert!(page_confirms_project(html, &base, Some("blah"), &[]));
+        assert!(!page_confirms_project(html, &base, Some("bla"), &[]));
+        assert!(!page_confirms_project(html, &base, None, &[]));
+
+        let html = r#"<html><head><title>Domain for sale</title></head>
+<body><a href="https://github.com/example/blah/">Source</a></body></html>"#;
+        assert!(!page_confirms_project(html, &base, Some("foo"), &[]));
+        assert!(page_confirms_project(
+            html,
+            &base,
+            Some("foo"),
+            std::slice::from_ref(&repository)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_consistency() {
+        let metadata = |items: &[UpstreamDatum]| {
+            UpstreamMetadata::from_data(
+                items
+                    .iter()
+                    .map(|d| UpstreamDatumWithMetadata {
+                        datum: d.clone(),
+                        certainty: Some(Certainty::Certain),
+                        origin: None,
+                    })
+                    .collect(),
+            )
+        };
+
+        let consistent = metadata(&[
+            UpstreamDatum::Repository("https://github.com/jelmer/dulwich.git".to_string()),
+            UpstreamDatum::RepositoryBrowse("https://github.com/jelmer/dulwich".to_string()),
+            UpstreamDatum::BugDatabase("https://github.com/jelmer/dulwich/issues".to_string()),
+            UpstreamDatum::Homepage("https://github.com/jelmer/dulwich".to_string()),
+        ]);
+        assert!(check_consistency(&consistent, Some(false)).await.is_empty());
+
+        // Projects may use a standalone tracker
+        let bugzilla = metadata(&[
+            UpstreamDatum::Repository("https://github.com/example/foo".to_string()),
+            UpstreamDatum::BugDatabase("https://bugzilla.example.com/".to_string()),
+        ]);
+        assert!(check_consistency(&bugzilla, Some(false)).await.is_empty());
+
+        let inconsistent = metadata(&[
+            UpstreamDatum::Repository("https://github.com/example/foo.git".to_string()),
+            UpstreamDatum::RepositoryBrowse("https://github.com/example/bar".to_string()),
+            UpstreamDatum::BugDatabase("https://gitlab.com/example/foo/issues".to_string()),
+        ]);
+        let report = check_consistency(&inconsistent, Some(false)).await;
+        assert_eq!(
+            report
+                .issues
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                "Bug-Database https://gitlab.com/example/foo/issues is not on the same host as Repository https://github.com/example/foo.git (suggestion: set Bug-Database to https://github.com/example/foo/issues)",
+                "Repository-Browse https://github.com/example/bar does not correspond to Repository https://github.com/example/foo.git (suggestion: set Repository-Browse to https://github.com/example/foo)",
+            ]
+        );
+
+        let sourceforge = metadata(&[
+            UpstreamDatum::RepositoryBrowse("https://sourceforge.net/p/foo/code/".to_string()),
+            UpstreamDatum::Homepage("https://sourceforge.net/p/foo/code".to_string()),
+        ]);
+        let report = check_consistency(&sourceforge, Some(false)).await;
+        assert_eq!(report.issues.len(), 1);
+        assert!(matches!(
+            report.issues[0],
+            CheckIssue::Inconsistent {
+                fields: ["Homepage", "Repository-Browse"],
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_file() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("metadata");
+        std::fs::write(
+            &path,
+            "Name: blah\nRepository: git://github.com/example/blah.git\nSummary: Blah. It blahs.\n",
+        )
+        .unwrap();
+        let (changes, report) = verify_file(&path, Some(false)).await.unwrap();
+        assert!(report.is_empty());
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange::Updated {
+                    old: UpstreamDatum::Repository("git://github.com/example/blah.git".to_string()),
+                    new: UpstreamDatum::Repository(
+                        "https://github.com/example/blah.git".to_string()
+                    ),
+                },
+                FieldChange::Updated {
+                    old: UpstreamDatum::Summary("Blah. It blahs.".to_string()),
+                    new: UpstreamDatum::Summary("Blah".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            changes[1].to_string(),
+            "-Summary: Blah. It blahs.\n+Summary: Blah"
+        );
+    }
+
+    #[test]
+    fn test_diff_metadata() {
+        let old = UpstreamMetadata::from(vec![
+            UpstreamDatum::Name("blah".to_string()),
+            UpstreamDatum::Homepage("https://example.com/".to_string()),
+        ]);
+        let new = UpstreamMetadata::from(vec![
+            UpstreamDatum::Name("blah".to_string()),
+            UpstreamDatum::Wiki("https://example.com/wiki".to_string()),
+        ]);
+        assert_eq!(
+            diff_metadata(&old, &new),
+            vec![
+                FieldChange::Removed(UpstreamDatum::Homepage("https://example.com/".to_string())),
+                FieldChange::Added(UpstreamDatum::Wiki("https://example.com/wiki".to_string())),
+            ]
+        );
+        assert!(diff_metadata(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Data tables that go stale over time: known GitLab and Gitea sites, hosts that are known to
+//! be bad guesses and hosts that projects have migrated away from.
+//!
+//! The defaults are embedded from `data/tables.json`. Updated tables can be placed in
+//! [`crate::dirs::data_dir`], where they are consulted before the embedded defaults.
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const EMBEDDED_TABLES: &str = include_str!("../data/tables.json");
+
+/// Name of the file with updated tables, inside the data directory.
+pub const TABLES_FILENAME: &str = "tables.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DataTables {
+    /// Hosts that run GitLab, but can not be recognized by their name.
+    #[serde(default)]
+    pub known_gitlab_sites: Vec<String>,
+
+    /// Hosts that run Gitea or Forgejo, but can not be recognized by their name.
+    #[serde(default)]
+    pub known_gitea_sites: Vec<String>,
+
+    /// Hosts that are known to be bad guesses, by field name.
+    #[serde(default)]
+    pub bad_hosts: BTreeMap<String, Vec<String>>,
+
+    /// Hosts that have moved, mapping the old host to the new one.
+    #[serde(default)]
+    pub host_migrations: BTreeMap<String, String>,
+}
+
+impl DataTables {
+    /// The tables that are shipped with this version of upstream-ontologist.
+    pub fn embedded() -> Self {
+        serde_json::from_str(EMBEDDED_TABLES).expect("embedded data tables are valid")
+    }
+
+    /// Combine these tables with `defaults`, with entries in `self` taking precedence.
+    pub fn merge(mut self, defaults: DataTables) -> Self {
+        for site in defaults.known_gitlab_sites {
+            if !self.known_gitlab_sites.contains(&site) {
+                self.known_gitlab_sites.push(site);
+            }
+        }
+        for site in defaults.known_gitea_sites {
+            if !self.known_gitea_sites.contains(&site) {
+                self.known_gitea_sites.push(site);
+            }
+        }
+        for (field, hosts) in defaults.bad_hosts {
+            let entry = self.bad_hosts.entry(field).or_default();
+            for host in hosts {
+                if !entry.contains(&host) {
+                    entry.push(host);
+                }
+            }
+        }
+        for (old, new) in defaults.host_migrations {
+            self.host_migrations.entry(old).or_insert(new);
+        }
+        self
+    }
+
+    pub fn is_known_gitlab_site(&self, host: &str) -> bool {
+        self.known_gitlab_sites.iter().any(|s| s == host)
+    }
+
+    pub fn is_known_gitea_site(&self, host: &str) -> bool {
+        self.known_gitea_sites.iter().any(|s| s == host)
+    }
+
+    /// Check whether URLs on `host` are known to be bad guesses for `field`.
+    pub fn is_bad_host(&self, field: &str, host: &str) -> bool {
+        self.bad_hosts
+            .get(field)
+            .is_some_and(|hosts| hosts.iter().any(|h| h == host))
+    }
+
+    /// Find the host that `host` has migrated to, if any.
+    pub fn migrated_host(&self, host: &str) -> Option<&str> {
+        self.host_migrations.get(host).map(String::as_str)
+    }
+}
+
+/// Location of the updated tables, if a data directory is available.
+pub fn updated_tables_path() -> Option<PathBuf> {
+    crate::dirs::data_dir().map(|d| d.join(TABLES_FILENAME))
+}
+
+fn load_tables(path: &Path) -> Option<DataTables> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(e) => {
+            warn!("Unable to read data tables from {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    match serde_json::from_str(&text) {
+        Ok(tables) => Some(tables),
+        Err(e) => {
+            warn!("Ignoring invalid data tables in {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The data tables in use: updated tables, if any, on top of the embedded defaults.
+pub fn tables() -> &'static DataTables {
+    static TABLES: OnceLock<DataTables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let embedded = DataTables::embedded();
+        match updated_tables_path().and_then(|p| load_tables(&p)) {
+            Some(updated) => updated.merge(embedded),
+            None => embedded,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embedded() {
+        let tables = DataTables::embedded();
+        assert!(tables.is_known_gitlab_site("salsa.debian.org"));
+        assert!(tables.is_known_gitea_site("codeberg.org"));
+        assert!(tables.is_bad_host("Repository", "git.gitorious.org"));
+        assert!(!tables.is_bad_host("Homepage", "git.gitorious.org"));
+        assert_eq!(
+            tables.migrated_host("git.openstack.org"),
+            Some("opendev.org")
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let updated: DataTables = serde_json::from_str(
+            r#"{"known-gitlab-sites": ["git.example.com"],
+                "bad-hosts": {"Repository": ["dead.example.com"]},
+                "host-migrations": {"git.openstack.org": "example.com"}}"#,
+        )
+        .unwrap();
+        let tables = updated.merge(DataTables::embedded());
+        assert!(tables.is_known_gitlab_site("git.example.com"));
+        assert!(tables.is_known_gitlab_site("salsa.debian.org"));
+        assert!(tables.is_bad_host("Repository", "dead.example.com"));
+        assert!(tables.is_bad_host("Repository", "anongit.kde.org"));
+        assert_eq!(
+            tables.migrated_host("git.openstack.org"),
+            Some("example.com")
+        );
+    }
+
+    #[test]
+    fn test_load_tables() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join(TABLES_FILENAME);
+        assert_eq!(load_tables(&path), None);
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load_tables(&path), None);
+        std::fs::write(&path, r#"{"known-gitlab-sites": ["git.example.com"]}"#).unwrap();
+        assert!(load_tables(&path)
+            .unwrap()
+            .is_known_gitlab_site("git.example.com"));
+    }
+}
@@ -0,0 +1,351 @@
+//! Updating of debian/upstream/metadata files.
+//!
+//! These files follow [DEP-12](https://dep-team.pages.debian.net/deps/dep12/). They are
+//! maintained by hand, so rather than regenerating them, guessed metadata is merged into
+//! the existing file: the order of fields and comments are preserved, and only fields
+//! whose value changed are rewritten.
+use crate::{Certainty, UpstreamMetadata};
+use lazy_regex::regex_captures;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Fields that are written to debian/upstream/metadata.
+///
+/// Name and Contact are deliberately absent; they belong in debian/copyright.
+pub const FIELDS: &[&str] = &[
+    "Archive",
+    "Bug-Database",
+    "Bug-Submit",
+    "Changelog",
+    "Cite-As",
+    "Documentation",
+    "Donation",
+    "Funding",
+    "Registry",
+    "Repository",
+    "Repository-Browse",
+    "Screenshots",
+    "Security-Contact",
+    "Webservice",
+];
+
+#[derive(Debug)]
+pub enum Error {
+    IoError(std::io::Error),
+    ParseError(serde_yaml::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::IoError(e) => write!(f, "{}", e),
+            Error::ParseError(e) => write!(f, "Invalid YAML: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IoError(e)
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Self {
+        Error::ParseError(e)
+    }
+}
+
+/// A top-level field in the file, with the comments that precede it.
+struct Entry {
+    field: String,
+    comments: Vec<String>,
+    lines: Vec<String>,
+}
+
+/// The lines of a YAML file, split up by top-level field.
+struct Document {
+    header: Vec<String>,
+    entries: Vec<Entry>,
+    trailer: Vec<String>,
+}
+
+impl Document {
+    fn parse(text: &str) -> Self {
+        let mut header = vec![];
+        let mut entries: Vec<Entry> = vec![];
+        // Comments and blank lines are attached to the field that follows them
+        let mut pending = vec![];
+        for line in text.split_inclusive('\n') {
+            if let Some((_, field)) = regex_captures!(r"^([^\s#'\x22\-][^:]*):(?:\s|$)", line) {
+                entries.push(Entry {
+                    field: field.to_string(),
+                    comments: std::mem::take(&mut pending),
+                    lines: vec![line.to_string()],
+                });
+            } else if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                pending.push(line.to_string());
+            } else {
+                let lines = match entries.last_mut() {
+                    Some(entry) => &mut entry.lines,
+                    None => &mut header,
+                };
+                lines.append(&mut pending);
+                lines.push(line.to_string());
+            }
+        }
+        if entries.is_empty() {
+            header.append(&mut pending);
+        }
+        Document {
+            header,
+            entries,
+            trailer: pending,
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut ret = self.header.concat();
+        for entry in &self.entries {
+            if !ret.is_empty() && !ret.ends_with('\n') {
+                ret.push('\n');
+            }
+            ret.push_str(&entry.comments.concat());
+            ret.push_str(&entry.lines.concat());
+        }
+        if !ret.is_empty() && !ret.ends_with('\n') {
+            ret.push('\n');
+        }
+        ret.push_str(&self.trailer.concat());
+        ret
+    }
+}
+
+/// Render a single field as YAML.
+fn render(field: &str, value: Value) -> Result<Vec<String>, Error> {
+    let mut mapping = Mapping::new();
+    mapping.insert(Value::String(field.to_string()), value);
+    Ok(serde_yaml::to_string(&mapping)?
+        .split_inclusive('\n')
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Merge the contents of a DEP-12 file with guessed metadata.
+///
+/// Returns the new contents and the fields that were added or changed.
+pub fn update_text(
+    text: &str,
+    metadata: &UpstreamMetadata,
+    minimum_certainty: Certainty,
+) -> Result<(String, Vec<String>), Error> {
+    let existing: Mapping = if text.trim().is_empty() {
+        Mapping::new()
+    } else {
+        serde_yaml::from_str::<Option<Mapping>>(text)?.unwrap_or_default()
+    };
+    let mut document = Document::parse(text);
+    if document.header.is_empty() && document.entries.is_empty() {
+        document.header.push("---\n".to_string());
+    }
+    let mut changed = vec![];
+    for field in FIELDS {
+        // Multi-valued fields such as Donation are written as a list of all of their values
+        let data = metadata
+            .get_all(field)
+            .filter(|d| d.certainty >= Some(minimum_certainty))
+            .cloned()
+            .collect::<Vec<_>>();
+        let Some(value) = serde_yaml::to_value(UpstreamMetadata::from_data(data))?
+            .get(*field)
+            .cloned()
+        else {
+            continue;
+        };
+        if existing.get(*field) == Some(&value) {
+            continue;
+        }
+        let lines = render(field, value)?;
+        match document.entries.iter_mut().find(|e| e.field == *field) {
+            Some(entry) => entry.lines = lines,
+            None => document.entries.push(Entry {
+                field: field.to_string(),
+                comments: vec![],
+                lines,
+            }),
+        }
+        changed.push(field.to_string());
+    }
+    Ok((document.text(), changed))
+}
+
+/// Merge guessed metadata into a debian/upstream/metadata file.
+///
+/// Only fields in [`FIELDS`] that are known with at least `minimum_certainty` are written.
+/// Fields that are already present with the same value are left alone, as are fields
+/// that were not guessed. The file is created if it does not exist yet, unless there is
+/// nothing to write.
+///
+/// Returns the fields that were added or changed.
+pub fn update_file(
+    path: &Path,
+    metadata: &UpstreamMetadata,
+    minimum_certainty: Certainty,
+) -> Result<Vec<String>, Error> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let (new_text, changed) = update_text(&text, metadata, minimum_certainty)?;
+    if !changed.is_empty() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, new_text)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{UpstreamDatum, UpstreamDatumWithMetadata};
+
+    fn metadata(items: Vec<(UpstreamDatum, Certainty)>) -> UpstreamMetadata {
+        UpstreamMetadata::from_data(
+            items
+                .into_iter()
+                .map(|(datum, certainty)| UpstreamDatumWithMetadata {
+                    datum,
+                    certainty: Some(certainty),
+                    origin: None,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_update_text() {
+        let text = r#"---
+# Upstream moved to GitHub in 2019
+Repository: https://example.com/old.git  # old
+Registry:
+ - Name: PyPI
+   Entry: blah
+# vim: ft=yaml
+"#;
+        let metadata = metadata(vec![
+            (
+                UpstreamDatum::Repository("https://github.com/example/blah.git".to_string()),
+                Certainty::Certain,
+            ),
+            (
+                UpstreamDatum::Registry(vec![("PyPI".to_string(), "blah".to_string())]),
+                Certainty::Certain,
+            ),
+            (
+                UpstreamDatum::BugDatabase("https://github.com/example/blah/issues".to_string()),
+                Certainty::Certain,
+            ),
+            (
+                UpstreamDatum::Documentation("https://blah.readthedocs.io/".to_string()),
+                Certainty::Possible,
+            ),
+            (UpstreamDatum::Name("blah".to_string()), Certainty::Certain),
+        ]);
+        let (new_text, changed) = update_text(text, &metadata, Certainty::Likely).unwrap();
+        assert_eq!(changed, vec!["Bug-Database", "Repository"]);
+        assert_eq!(
+            new_text,
+            r#"---
+# Upstream moved to GitHub in 2019
+Repository: https://github.com/example/blah.git
+Registry:
+ - Name: PyPI
+   Entry: blah
+Bug-Database: https://github.com/example/blah/issues
+# vim: ft=yaml
+"#
+        );
+
+        // Nothing changes the second time around
+        let (newer_text, changed) = update_text(&new_text, &metadata, Certainty::Likely).unwrap();
+        assert!(changed.is_empty());
+        assert_eq!(newer_text, new_text);
+    }
+
+    #[test]
+    fn test_update_text_multi_valued() {
+        let metadata = metadata(vec![
+            (
+                UpstreamDatum::Donation("https://example.com/donate".to_string()),
+                Certainty::Certain,
+            ),
+            (
+                UpstreamDatum::Donation("https://liberapay.com/blah".to_string()),
+                Certainty::Likely,
+            ),
+            (
+                UpstreamDatum::Donation("https://example.com/maybe".to_string()),
+                Certainty::Possible,
+            ),
+        ]);
+        let (text, changed) = update_text("", &metadata, Certainty::Likely).unwrap();
+        assert_eq!(changed, vec!["Donation"]);
+        assert_eq!(
+            text,
+            "---\nDonation:\n- https://example.com/donate\n- https://liberapay.com/blah\n"
+        );
+
+        // Nothing changes the second time around
+        let (new_text, changed) = update_text(&text, &metadata, Certainty::Likely).unwrap();
+        assert!(changed.is_empty());
+        assert_eq!(new_text, text);
+
+        // Both values are read back
+        #[cfg(feature = "providers")]
+        {
+            let td = tempfile::tempdir().unwrap();
+            let path = td.path().join("metadata");
+            std::fs::write(&path, &text).unwrap();
+            let guessed = crate::providers::debian::guess_from_debian_upstream_metadata(
+                &path,
+                &Default::default(),
+            )
+            .unwrap();
+            assert_eq!(
+                guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+                vec![
+                    UpstreamDatum::Donation("https://example.com/donate".to_string()),
+                    UpstreamDatum::Donation("https://liberapay.com/blah".to_string()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn test_update_file() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("debian/upstream/metadata");
+        assert!(update_file(&path, &metadata(vec![]), Certainty::Likely)
+            .unwrap()
+            .is_empty());
+        assert!(!path.exists());
+
+        let metadata = metadata(vec![(
+            UpstreamDatum::Repository("https://github.com/example/blah.git".to_string()),
+            Certainty::Confident,
+        )]);
+        assert_eq!(
+            update_file(&path, &metadata, Certainty::Likely).unwrap(),
+            vec!["Repository"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "---\nRepository: https://github.com/example/blah.git\n"
+        );
+    }
+}
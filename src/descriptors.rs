@@ -0,0 +1,1212 @@
+//! Descriptions of the supported fields and metadata providers.
+//!
+//! These can be used to generate documentation, command-line help or web interfaces,
+//! rather than maintaining separate lists by hand.
+
+#[cfg(feature = "providers")]
+use crate::ThirdPartyRepository;
+
+/// The kind of value stored in a field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Free-form text
+    Text,
+    /// A URL
+    Url,
+    /// A URL or an e-mail address
+    UrlOrEmail,
+    /// A single person
+    Person,
+    /// A list of people
+    People,
+    /// A list of strings
+    List,
+    /// An integer
+    Integer,
+    /// A list of (registry, identifier) pairs
+    Registry,
+}
+
+/// Description of a metadata field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    /// Name of the field, as used in e.g. debian/upstream/metadata
+    pub name: &'static str,
+
+    /// Human-readable description of the field
+    pub description: &'static str,
+
+    /// Kind of value stored in the field
+    pub kind: FieldKind,
+
+    /// Whether several distinct values can be recorded for the field
+    pub multi_valued: bool,
+}
+
+const fn field(name: &'static str, description: &'static str, kind: FieldKind) -> FieldDescriptor {
+    FieldDescriptor {
+        name,
+        description,
+        kind,
+        multi_valued: false,
+    }
+}
+
+impl FieldDescriptor {
+    const fn multi_valued(self) -> Self {
+        FieldDescriptor {
+            multi_valued: true,
+            ..self
+        }
+    }
+}
+
+const SUPPORTED_FIELDS: &[FieldDescriptor] = &[
+    field("Name", "Name of the project", FieldKind::Text),
+    field("Homepage", "URL to project homepage", FieldKind::Url),
+    field(
+        "Repository",
+        "URL to the project's source code repository",
+        FieldKind::Url,
+    ),
+    field(
+        "Repository-Browse",
+        "URL to browse the project's source code repository",
+        FieldKind::Url,
+    ),
+    field(
+        "Repository-Mirror",
+        "URL of a mirror of the project's source code repository",
+        FieldKind::Url,
+    )
+    .multi_valued(),
+    field(
+        "Description",
+        "Long description of the project",
+        FieldKind::Text,
+    ),
+    field(
+        "Summary",
+        "Short summary of the project (one line)",
+        FieldKind::Text,
+    ),
+    field(
+        "License",
+        "License name or SPDX identifier",
+        FieldKind::Text,
+    ),
+    field("Author", "List of authors", FieldKind::People),
+    field("Maintainer", "Maintainer of the project", FieldKind::Person),
+    field(
+        "Organization",
+        "Organization that develops or backs the project",
+        FieldKind::Text,
+    ),
+    field(
+        "Bug-Database",
+        "URL of the project's issue tracker",
+        FieldKind::Url,
+    ),
+    field(
+        "Bug-Submit",
+        "URL to submit a new bug",
+        FieldKind::UrlOrEmail,
+    ),
+    field(
+        "Contact",
+        "URL to the project's contact page or email address",
+        FieldKind::UrlOrEmail,
+    ),
+    field("Cargo-Crate", "Cargo crate name", FieldKind::Text),
+    field(
+        "Security-MD",
+        "Name of the security policy file",
+        FieldKind::Text,
+    ),
+    field(
+        "Security-Contact",
+        "URL to the security page or email address",
+        FieldKind::UrlOrEmail,
+    ),
+    field(
+        "Code-Of-Conduct",
+        "Path to or URL of the code of conduct",
+        FieldKind::Text,
+    ),
+    field(
+        "Contributing",
+        "Path to or URL of the contribution guidelines",
+        FieldKind::Text,
+    ),
+    field("Version", "Last version of the project", FieldKind::Text),
+    field("Keywords", "List of keywords", FieldKind::List),
+    field(
+        "Aliases",
+        "Other names the project is known under",
+        FieldKind::List,
+    ),
+    field("Copyright", "Copyright notice", FieldKind::Text),
+    field(
+        "Documentation",
+        "URL to the project's documentation",
+        FieldKind::Url,
+    ),
+    field(
+        "API-Documentation",
+        "URL to the project's API documentation",
+        FieldKind::Url,
+    ),
+    field("Go-Import-Path", "Go import path", FieldKind::Text),
+    field(
+        "Download",
+        "URL to the project's download page",
+        FieldKind::Url,
+    ),
+    field(
+        "Binary-Download",
+        "URL to a binary (non-source) download, such as an installer or a CI artifact",
+        FieldKind::Url,
+    ),
+    field("Wiki", "URL to the project's wiki", FieldKind::Url),
+    field(
+        "MailingList",
+        "URL to the project's mailing list",
+        FieldKind::Url,
+    ),
+    field(
+        "Chat",
+        "URL of the project's chat channel (IRC, Matrix, Discord, ...)",
+        FieldKind::Url,
+    )
+    .multi_valued(),
+    field(
+        "Translations",
+        "URL of the project on a translation platform (Weblate, Transifex, ...)",
+        FieldKind::Url,
+    ),
+    field(
+        "SourceForge-Project",
+        "SourceForge project name",
+        FieldKind::Text,
+    ),
+    field(
+        "SourceForge-Subproject",
+        "Subproject within the SourceForge project",
+        FieldKind::Text,
+    ),
+    field(
+        "Archive",
+        "Archive the project is published in, e.g. SourceForge or crates.io",
+        FieldKind::Text,
+    ),
+    field("Demo", "URL to a demo instance", FieldKind::Url),
+    field("Pecl-Package", "PHP PECL package name", FieldKind::Text),
+    field("Haskell-Package", "Haskell package name", FieldKind::Text),
+    field("Funding", "URL to the funding page", FieldKind::Url),
+    field("Changelog", "URL to the changelog", FieldKind::Url),
+    field(
+        "Maintenance-Status",
+        "Maintenance status reported by a registry, e.g. \"deprecated\", \"inactive\" or \"yanked\"",
+        FieldKind::Text,
+    ),
+    field(
+        "Debian-ITP",
+        "Debian ITP (Intent To Package) bug number",
+        FieldKind::Integer,
+    ),
+    field(
+        "Screenshots",
+        "List of URLs to screenshots",
+        FieldKind::List,
+    )
+    .multi_valued(),
+    field(
+        "Registry",
+        "Identifiers of the project in registries",
+        FieldKind::Registry,
+    ),
+    field(
+        "Cite-As",
+        "Recommended way to cite the software",
+        FieldKind::Text,
+    ),
+    field("Donation", "Link for donations", FieldKind::Url).multi_valued(),
+    field(
+        "Webservice",
+        "Link to a live instance of the webservice",
+        FieldKind::Url,
+    ),
+    field(
+        "BuildSystem",
+        "Name of the buildsystem used",
+        FieldKind::Text,
+    ),
+];
+
+/// Return descriptions of all supported fields.
+pub fn supported_fields() -> &'static [FieldDescriptor] {
+    SUPPORTED_FIELDS
+}
+
+/// Look up the description of a field by name.
+pub fn field_descriptor(name: &str) -> Option<&'static FieldDescriptor> {
+    SUPPORTED_FIELDS.iter().find(|f| f.name == name)
+}
+
+/// Check whether several distinct values can be recorded for a field.
+///
+/// Other fields hold at most one value; a better guess replaces the existing one.
+pub fn is_multi_valued(name: &str) -> bool {
+    field_descriptor(name).is_some_and(|f| f.multi_valued)
+}
+
+/// Description of a metadata provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderDescriptor {
+    /// Name of the provider
+    pub name: &'static str,
+
+    /// File names (or glob patterns) the provider reads, relative to the project root;
+    /// empty for providers that query remote services
+    pub filenames: Vec<&'static str>,
+
+    /// Fields the provider can supply
+    pub fields: Vec<&'static str>,
+
+    /// Whether the provider needs network access
+    pub needs_network: bool,
+
+    /// Whether the provider only runs (or only provides all of its data) if the package
+    /// contents are trusted, e.g. because it runs code from the package
+    pub needs_trust: bool,
+}
+
+/// Description of a provider that reads files in the project tree.
+///
+/// The files at fixed locations are listed along with their guessers in
+/// [`crate::OLD_STATIC_GUESSERS`], which refers to the provider of each of them; `patterns` are
+/// the files that the provider finds by scanning the tree.
+#[cfg(feature = "providers")]
+pub(crate) struct LocalProvider {
+    pub(crate) name: &'static str,
+    pub(crate) patterns: &'static [&'static str],
+    pub(crate) fields: &'static [&'static str],
+    pub(crate) needs_trust: bool,
+}
+
+/// The providers that read files in the project tree.
+#[cfg(feature = "providers")]
+pub(crate) mod local {
+    use super::LocalProvider;
+
+    pub(crate) const AUTHORS: LocalProvider = LocalProvider {
+        name: "authors",
+        patterns: &[],
+        fields: &["Author"],
+        needs_trust: false,
+    };
+
+    pub(crate) const AUTOCONF: LocalProvider = LocalProvider {
+        name: "autoconf",
+        patterns: &[],
+        fields: &["Name", "Version", "Homepage", "Bug-Submit"],
+        needs_trust: false,
+    };
+
+    pub(crate) const CABAL: LocalProvider = LocalProvider {
+        name: "cabal",
+        patterns: &["*.cabal"],
+        fields: &[
+            "Name",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Maintainer",
+            "Copyright",
+        ],
+        needs_trust: false,
+    };
+
+    #[cfg(feature = "cargo")]
+    pub(crate) const CARGO: LocalProvider = LocalProvider {
+        name: "cargo",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "License",
+            "Author",
+            "Cargo-Crate",
+        ],
+        needs_trust: false,
+    };
+
+    #[cfg(feature = "julia")]
+    pub(crate) const JULIA: LocalProvider = LocalProvider {
+        name: "julia",
+        patterns: &[],
+        fields: &["Name", "Version", "Author", "Archive"],
+        needs_trust: false,
+    };
+
+    pub(crate) const HAXELIB_JSON: LocalProvider = LocalProvider {
+        name: "haxelib.json",
+        patterns: &[],
+        fields: &[
+            "Name", "Homepage", "License", "Keywords", "Summary", "Version", "Author",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const CONDA: LocalProvider = LocalProvider {
+        name: "conda",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Homepage",
+            "License",
+            "Summary",
+            "Description",
+            "Repository",
+            "Documentation",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const COMPOSER_JSON: LocalProvider = LocalProvider {
+        name: "composer.json",
+        patterns: &[],
+        fields: &[
+            "Name", "Version", "Summary", "Homepage", "License", "Keywords",
+        ],
+        needs_trust: false,
+    };
+
+    #[cfg(feature = "debian")]
+    pub(crate) const DEBIAN: LocalProvider = LocalProvider {
+        name: "debian",
+        patterns: &["debian/patches/*.patch"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Contact",
+            "Download",
+            "Archive",
+            "Cargo-Crate",
+            "Go-Import-Path",
+            "Debian-ITP",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const DEBIAN_UPSTREAM_METADATA: LocalProvider = LocalProvider {
+        name: "debian-upstream-metadata",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Archive",
+            "Bug-Database",
+            "Bug-Submit",
+            "Changelog",
+            "Cite-As",
+            "Contact",
+            "Documentation",
+            "Donation",
+            "Funding",
+            "Registry",
+            "Repository",
+            "Repository-Browse",
+            "Screenshots",
+            "Security-Contact",
+            "Webservice",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const DOAP: LocalProvider = LocalProvider {
+        name: "doap",
+        patterns: &["*.doap", "doap_XML_*.xml"],
+        fields: &[
+            "Name",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "Download",
+            "Wiki",
+            "MailingList",
+            "Maintainer",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const FLATPAK: LocalProvider = LocalProvider {
+        name: "flatpak",
+        patterns: &["*.json", "*.yml", "*.yaml"],
+        fields: &["Name", "Archive", "Repository", "Version", "Download"],
+        needs_trust: false,
+    };
+
+    pub(crate) const GEMSPEC: LocalProvider = LocalProvider {
+        name: "gemspec",
+        patterns: &["*.gemspec"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Changelog",
+            "Documentation",
+            "Funding",
+            "MailingList",
+            "Wiki",
+        ],
+        needs_trust: false,
+    };
+
+    #[cfg(feature = "git-config")]
+    pub(crate) const GIT: LocalProvider = LocalProvider {
+        name: "git",
+        patterns: &[],
+        fields: &["Repository"],
+        needs_trust: false,
+    };
+
+    pub(crate) const GO_MOD: LocalProvider = LocalProvider {
+        name: "go.mod",
+        patterns: &[],
+        fields: &["Name", "Repository", "Go-Import-Path"],
+        needs_trust: false,
+    };
+
+    pub(crate) const GRADLE: LocalProvider = LocalProvider {
+        name: "gradle",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "License",
+            "Author",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const INSTALL: LocalProvider = LocalProvider {
+        name: "install",
+        patterns: &[],
+        fields: &["Repository"],
+        needs_trust: false,
+    };
+
+    pub(crate) const MANIFEST_SNIFFING: LocalProvider = LocalProvider {
+        name: "manifest-sniffing",
+        patterns: &["manifest sniffing"],
+        fields: &["Name", "Version", "Homepage", "Repository", "License"],
+        needs_trust: false,
+    };
+
+    pub(crate) const MAVEN: LocalProvider = LocalProvider {
+        name: "maven",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "License",
+            "Organization",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const MESON: LocalProvider = LocalProvider {
+        name: "meson",
+        patterns: &[],
+        fields: &["Name", "Version"],
+        needs_trust: false,
+    };
+
+    pub(crate) const METADATA_JSON: LocalProvider = LocalProvider {
+        name: "metadata.json",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const METAINFO: LocalProvider = LocalProvider {
+        name: "metainfo",
+        patterns: &["*.metainfo.xml", "*.appdata.xml"],
+        fields: &[
+            "Name",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Bug-Database",
+            "License",
+            "Chat",
+            "Translations",
+            "Keywords",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const MIX_EXS: LocalProvider = LocalProvider {
+        name: "mix.exs",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "License",
+            "Changelog",
+            "Documentation",
+            "Archive",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const NIX: LocalProvider = LocalProvider {
+        name: "nix",
+        patterns: &[],
+        fields: &["Summary", "Description", "Homepage", "License", "Changelog"],
+        needs_trust: false,
+    };
+
+    pub(crate) const NUSPEC: LocalProvider = LocalProvider {
+        name: "nuspec",
+        patterns: &["*.nuspec"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "License",
+            "Author",
+            "Copyright",
+            "Archive",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const OPAM: LocalProvider = LocalProvider {
+        name: "opam",
+        patterns: &["*.opam"],
+        fields: &[
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Maintainer",
+            "Documentation",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const PACKAGE_JSON: LocalProvider = LocalProvider {
+        name: "package.json",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Keywords",
+            "Demo",
+            "Organization",
+            "Aliases",
+            "Documentation",
+            "Changelog",
+            "Chat",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const PACKAGE_XML: LocalProvider = LocalProvider {
+        name: "package.xml",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Maintainer",
+            "Pecl-Package",
+            "Archive",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const CHANNEL_XML: LocalProvider = LocalProvider {
+        name: "channel.xml",
+        patterns: &[],
+        fields: &["Homepage", "Summary"],
+        needs_trust: false,
+    };
+
+    pub(crate) const PACKAGE_YAML: LocalProvider = LocalProvider {
+        name: "package.yaml",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "License",
+            "Author",
+            "Maintainer",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const PERL: LocalProvider = LocalProvider {
+        name: "perl",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Copyright",
+            "Download",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const PKGBUILD: LocalProvider = LocalProvider {
+        name: "pkgbuild",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "License",
+            "Repository",
+            "Download",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const PUBSPEC: LocalProvider = LocalProvider {
+        name: "pubspec",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "Documentation",
+        ],
+        needs_trust: false,
+    };
+
+    #[cfg(any(
+        feature = "python-pkginfo",
+        feature = "pyproject-toml",
+        feature = "setup-cfg"
+    ))]
+    pub(crate) const PYTHON: LocalProvider = LocalProvider {
+        name: "python",
+        patterns: &["*.egg-info/PKG-INFO", "*.dist-info/METADATA"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Maintainer",
+            "Contact",
+            "Keywords",
+            "Documentation",
+            "Download",
+            "Funding",
+            "Maintenance-Status",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const SETUP_PY: LocalProvider = LocalProvider {
+        name: "setup.py",
+        patterns: &["setup.py"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Author",
+            "Maintainer",
+            "Keywords",
+            "Download",
+        ],
+        needs_trust: true,
+    };
+
+    #[cfg(feature = "r-description")]
+    pub(crate) const R_DESCRIPTION: LocalProvider = LocalProvider {
+        name: "r-description",
+        patterns: &[],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Maintainer",
+            "Archive",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const README: LocalProvider = LocalProvider {
+        name: "readme",
+        patterns: &["README*", "HACKING*", "CONTRIBUTING*"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Description",
+            "Homepage",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Copyright",
+            "Documentation",
+            "API-Documentation",
+            "Changelog",
+            "Chat",
+            "Translations",
+            "Contributing",
+            "Download",
+            "Archive",
+            "Cargo-Crate",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const SBT: LocalProvider = LocalProvider {
+        name: "sbt",
+        patterns: &["project/*.sbt"],
+        fields: &[
+            "Name",
+            "Version",
+            "Summary",
+            "Homepage",
+            "Repository",
+            "Repository-Browse",
+            "License",
+            "Author",
+        ],
+        needs_trust: false,
+    };
+
+    pub(crate) const COMMUNITY: LocalProvider = LocalProvider {
+        name: "community",
+        patterns: &[
+            "CODE_OF_CONDUCT*",
+            ".github/CODE_OF_CONDUCT*",
+            "docs/CODE_OF_CONDUCT*",
+            ".github/CONTRIBUTING*",
+            "docs/CONTRIBUTING*",
+        ],
+        fields: &["Code-Of-Conduct", "Contributing"],
+        needs_trust: false,
+    };
+
+    pub(crate) const SECURITY_MD: LocalProvider = LocalProvider {
+        name: "security.md",
+        patterns: &["SECURITY.md", ".github/SECURITY.md", "docs/SECURITY.md"],
+        fields: &["Security-MD"],
+        needs_trust: false,
+    };
+
+    pub(crate) const FUNDING: LocalProvider = LocalProvider {
+        name: "funding",
+        patterns: &[],
+        fields: &["Donation"],
+        needs_trust: false,
+    };
+
+    pub(crate) const TRANSIFEX: LocalProvider = LocalProvider {
+        name: "transifex",
+        patterns: &[],
+        fields: &["Translations"],
+        needs_trust: false,
+    };
+
+    pub(crate) const SHELL: LocalProvider = LocalProvider {
+        name: "shell",
+        patterns: &[],
+        fields: &["Repository", "Version", "Download"],
+        needs_trust: false,
+    };
+
+    pub(crate) const SOURCE_HEADERS: LocalProvider = LocalProvider {
+        name: "source-headers",
+        patterns: &["source headers"],
+        fields: &["Copyright"],
+        needs_trust: false,
+    };
+
+    pub(crate) const TRAVIS: LocalProvider = LocalProvider {
+        name: "travis",
+        patterns: &[],
+        fields: &["Go-Import-Path"],
+        needs_trust: false,
+    };
+
+    pub(crate) const WAF: LocalProvider = LocalProvider {
+        name: "waf",
+        patterns: &[],
+        fields: &["Name", "Version"],
+        needs_trust: false,
+    };
+}
+
+/// Providers that only read files that are found by scanning the tree, and so don't appear in
+/// [`crate::OLD_STATIC_GUESSERS`].
+#[cfg(feature = "providers")]
+const SCANNED_PROVIDERS: &[&LocalProvider] = &[
+    &local::CABAL,
+    &local::DOAP,
+    &local::FLATPAK,
+    &local::GEMSPEC,
+    &local::MANIFEST_SNIFFING,
+    &local::METAINFO,
+    &local::NUSPEC,
+    &local::OPAM,
+    &local::SETUP_PY,
+    &local::README,
+    &local::COMMUNITY,
+    &local::SECURITY_MD,
+    &local::SOURCE_HEADERS,
+];
+
+/// The local providers along with the files they read, ordered by name.
+#[cfg(feature = "providers")]
+fn local_providers() -> &'static [(&'static LocalProvider, Vec<&'static str>)] {
+    static PROVIDERS: std::sync::OnceLock<Vec<(&'static LocalProvider, Vec<&'static str>)>> =
+        std::sync::OnceLock::new();
+    PROVIDERS.get_or_init(|| {
+        let mut ret: Vec<(&'static LocalProvider, Vec<&'static str>)> = vec![];
+        for (filename, provider, _) in crate::OLD_STATIC_GUESSERS {
+            match ret.iter_mut().find(|(p, _)| p.name == provider.name) {
+                Some((_, filenames)) => filenames.push(filename),
+                None => ret.push((provider, vec![filename])),
+            }
+        }
+        ret.extend(SCANNED_PROVIDERS.iter().map(|p| (*p, vec![])));
+        for (provider, filenames) in ret.iter_mut() {
+            filenames.extend(provider.patterns);
+        }
+        ret.sort_by_key(|(p, _)| p.name);
+        ret
+    })
+}
+
+#[cfg(feature = "providers")]
+fn matches_filename(pattern: &str, filename: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            filename.len() >= prefix.len() + suffix.len()
+                && filename.starts_with(prefix)
+                && filename.ends_with(suffix)
+        }
+        None => pattern == filename,
+    }
+}
+
+/// Look up the fields that the local provider for `filename` can supply.
+///
+/// Returns None if the file is not read by any known provider.
+#[cfg(feature = "providers")]
+pub(crate) fn local_provider_fields(filename: &str) -> Option<&'static [&'static str]> {
+    // Exact matches take precedence over wildcards, e.g. package.json over *.json
+    local_providers()
+        .iter()
+        .find(|(_, filenames)| filenames.contains(&filename))
+        .or_else(|| {
+            local_providers()
+                .iter()
+                .find(|(_, filenames)| filenames.iter().any(|f| matches_filename(f, filename)))
+        })
+        .map(|(p, _)| p.fields)
+}
+
+/// Patterns of providers that look at every file of a format, rather than at specific files.
+#[cfg(feature = "providers")]
+const GENERIC_PATTERNS: &[&str] = &["*.json", "*.yml", "*.yaml", "*.toml"];
+
+/// Check whether a file is read by a provider that knows its structure.
+///
+/// Providers that look at every file of a format (e.g. Flatpak manifests, which can be any
+/// JSON or YAML file) don't count.
+#[cfg(feature = "providers")]
+pub(crate) fn has_dedicated_provider(filename: &str) -> bool {
+    local_providers()
+        .iter()
+        .flat_map(|(_, filenames)| filenames.iter())
+        .filter(|f| !GENERIC_PATTERNS.contains(f))
+        .any(|f| matches_filename(f, filename))
+}
+
+#[cfg(feature = "providers")]
+fn third_party(repository: &dyn ThirdPartyRepository) -> ProviderDescriptor {
+    ProviderDescriptor {
+        name: repository.name(),
+        filenames: vec![],
+        fields: repository.supported_fields().to_vec(),
+        needs_network: true,
+        needs_trust: false,
+    }
+}
+
+/// Return descriptions of all metadata providers.
+///
+/// This includes both the providers that read files in the project tree and the
+/// third-party repositories that are consulted when network access is allowed.
+#[cfg(feature = "providers")]
+pub fn providers() -> Vec<ProviderDescriptor> {
+    let mut ret = local_providers()
+        .iter()
+        .map(|(p, filenames)| ProviderDescriptor {
+            name: p.name,
+            filenames: filenames.clone(),
+            fields: p.fields.to_vec(),
+            needs_network: false,
+            needs_trust: p.needs_trust,
+        })
+        .collect::<Vec<_>>();
+
+    ret.push(third_party(&crate::providers::arch::Aur::new()));
+    ret.push(third_party(&crate::providers::r::Bioconductor::new()));
+    ret.push(third_party(&crate::providers::r::Cran::new()));
+    #[cfg(feature = "cargo")]
+    ret.push(third_party(&crate::providers::rust::CratesIo::new()));
+    ret.push(third_party(&crate::providers::flatpak::Flathub::new()));
+    ret.push(third_party(&crate::providers::go::GoProxy::new()));
+    ret.push(third_party(&crate::providers::gobo::Gobo::new()));
+    ret.push(third_party(&crate::providers::haskell::Hackage::new()));
+    ret.push(third_party(&crate::providers::elixir::Hex::new()));
+    #[cfg(feature = "julia")]
+    ret.push(third_party(&crate::providers::julia::JuliaGeneral::new()));
+    ret.push(third_party(&crate::providers::perl::MetaCpan::new()));
+    ret.push(third_party(&crate::providers::node::Npm::new()));
+    ret.push(third_party(&crate::providers::nuspec::NuGet::new()));
+    ret.push(third_party(
+        &crate::providers::composer_json::Packagist::new(),
+    ));
+    ret.push(third_party(&crate::providers::php::Pecl::new()));
+    ret.push(third_party(&crate::providers::python::PyPI::new()));
+    ret.push(third_party(&crate::providers::ruby::RubyGems::new()));
+
+    for forge in crate::ForgeRegistry::global().forges() {
+        if !forge.supported_fields().is_empty() {
+            ret.push(ProviderDescriptor {
+                name: forge.name(),
+                filenames: vec![],
+                fields: forge.supported_fields().to_vec(),
+                needs_network: true,
+                needs_trust: false,
+            });
+        }
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_local_provider_fields() {
+        assert!(local_provider_fields("Cargo.toml")
+            .unwrap()
+            .contains(&"Repository"));
+        assert!(local_provider_fields("foo.cabal")
+            .unwrap()
+            .contains(&"Name"));
+        assert!(local_provider_fields("org.example.App.json")
+            .unwrap()
+            .contains(&"Archive"));
+        assert!(!local_provider_fields("package.json")
+            .unwrap()
+            .contains(&"Archive"));
+        assert_eq!(local_provider_fields("nonexistent"), None);
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_has_dedicated_provider() {
+        assert!(has_dedicated_provider("package.json"));
+        assert!(has_dedicated_provider("foo.cabal"));
+        assert!(!has_dedicated_provider("project.json"));
+        assert!(!has_dedicated_provider("nonexistent"));
+    }
+
+    #[test]
+    fn test_supported_fields_unique() {
+        let mut names = supported_fields()
+            .iter()
+            .map(|f| f.name)
+            .collect::<Vec<_>>();
+        names.sort();
+        let len = names.len();
+        names.dedup();
+        assert_eq!(len, names.len());
+        assert_eq!(
+            field_descriptor("Bug-Submit").map(|f| f.kind),
+            Some(FieldKind::UrlOrEmail)
+        );
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_providers_fields_known() {
+        for provider in providers() {
+            for field in provider.fields.iter() {
+                assert!(
+                    field_descriptor(field).is_some(),
+                    "{}: unknown field {}",
+                    provider.name,
+                    field
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_local_providers_read_files() {
+        for (provider, filenames) in local_providers() {
+            assert!(
+                !filenames.is_empty(),
+                "{} is neither in the guesser table nor has any patterns",
+                provider.name
+            );
+        }
+    }
+
+    /// Check that the guessers for the files in the test fixtures only emit the fields that
+    /// their providers are described as supplying, since guessers are skipped based on them.
+    #[cfg(feature = "providers")]
+    #[tokio::test]
+    async fn test_guessers_emit_described_fields() {
+        let settings = crate::GuesserSettings {
+            network: crate::NetworkPolicy::Offline,
+            ..Default::default()
+        };
+        let mut fixtures = std::fs::read_dir("testdata")
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect::<Vec<_>>();
+        fixtures.sort();
+        let mut undescribed = vec![];
+        for fixture in fixtures {
+            for mut guesser in crate::find_guessers(&fixture) {
+                let name = guesser.name().to_string();
+                let basename = name.rsplit('/').next().unwrap_or_default();
+                let Some(fields) =
+                    local_provider_fields(&name).or_else(|| local_provider_fields(basename))
+                else {
+                    continue;
+                };
+                let Ok(results) = guesser.guess(&settings).await else {
+                    continue;
+                };
+                for result in results {
+                    let field = result.datum.field();
+                    if !fields.contains(&field) {
+                        undescribed.push(format!("{}: {}: {}", fixture.display(), name, field));
+                    }
+                }
+            }
+        }
+        undescribed.sort();
+        undescribed.dedup();
+        assert!(
+            undescribed.is_empty(),
+            "fields not listed in provider descriptions:\n{}",
+            undescribed.join("\n")
+        );
+    }
+}
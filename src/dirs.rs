@@ -0,0 +1,238 @@
+//! Locations of the files that upstream-ontologist keeps outside of the project tree.
+//!
+//! Cache, configuration and data directories follow the
+//! [XDG Base Directory Specification](https://specifications.freedesktop.org/basedir-spec/latest/)
+//! on Unix-like systems, and the usual platform locations on macOS and Windows. Each location
+//! can be overridden with one of the environment variables in [`ENVIRONMENT_VARIABLES`].
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// Name of the subdirectory used inside the platform cache and configuration directories.
+pub const APP_NAME: &str = "upstream-ontologist";
+
+/// Environment variable that overrides the cache directory.
+pub const CACHE_DIR_ENV: &str = "UPSTREAM_ONTOLOGIST_CACHE_DIR";
+
+/// Environment variable that overrides the configuration directory.
+pub const CONFIG_DIR_ENV: &str = "UPSTREAM_ONTOLOGIST_CONFIG_DIR";
+
+/// Environment variable that overrides the data directory.
+pub const DATA_DIR_ENV: &str = "UPSTREAM_ONTOLOGIST_DATA_DIR";
+
+/// Environment variable that overrides the path of the credentials file.
+pub const CREDENTIALS_ENV: &str = "UPSTREAM_ONTOLOGIST_CREDENTIALS";
+
+/// An environment variable that influences where files are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvironmentVariable {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// All environment variables that override the default locations.
+pub const ENVIRONMENT_VARIABLES: &[EnvironmentVariable] = &[
+    EnvironmentVariable {
+        name: CACHE_DIR_ENV,
+        description: "Directory for cached HTTP responses and guessing results",
+    },
+    EnvironmentVariable {
+        name: CONFIG_DIR_ENV,
+        description: "Directory with configuration, such as rules files",
+    },
+    EnvironmentVariable {
+        name: DATA_DIR_ENV,
+        description: "Directory for updated data tables, such as known GitLab sites",
+    },
+    EnvironmentVariable {
+        name: CREDENTIALS_ENV,
+        description: "Path of the file with credentials for forge APIs",
+    },
+];
+
+/// Look up a non-empty environment variable.
+fn non_empty(var: &impl Fn(&str) -> Option<OsString>, name: &str) -> Option<PathBuf> {
+    var(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// The platform base directory for caches, before appending [`APP_NAME`].
+fn base_cache_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    if let Some(dir) = non_empty(var, "XDG_CACHE_HOME") {
+        return Some(dir);
+    }
+    if cfg!(windows) {
+        non_empty(var, "LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        non_empty(var, "HOME").map(|home| home.join("Library/Caches"))
+    } else {
+        non_empty(var, "HOME").map(|home| home.join(".cache"))
+    }
+}
+
+/// The platform base directory for configuration, before appending [`APP_NAME`].
+fn base_config_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    if let Some(dir) = non_empty(var, "XDG_CONFIG_HOME") {
+        return Some(dir);
+    }
+    if cfg!(windows) {
+        non_empty(var, "APPDATA")
+    } else if cfg!(target_os = "macos") {
+        non_empty(var, "HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        non_empty(var, "HOME").map(|home| home.join(".config"))
+    }
+}
+
+/// The platform base directory for data, before appending [`APP_NAME`].
+fn base_data_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    if let Some(dir) = non_empty(var, "XDG_DATA_HOME") {
+        return Some(dir);
+    }
+    if cfg!(windows) {
+        non_empty(var, "LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        non_empty(var, "HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        non_empty(var, "HOME").map(|home| home.join(".local/share"))
+    }
+}
+
+fn resolve_cache_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    non_empty(var, CACHE_DIR_ENV).or_else(|| base_cache_dir(var).map(|d| d.join(APP_NAME)))
+}
+
+fn resolve_config_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    non_empty(var, CONFIG_DIR_ENV).or_else(|| base_config_dir(var).map(|d| d.join(APP_NAME)))
+}
+
+fn resolve_data_dir(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    non_empty(var, DATA_DIR_ENV).or_else(|| base_data_dir(var).map(|d| d.join(APP_NAME)))
+}
+
+fn resolve_credentials_path(var: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    non_empty(var, CREDENTIALS_ENV)
+        .or_else(|| resolve_config_dir(var).map(|d| d.join("credentials.toml")))
+}
+
+/// Directory for cached data; safe to remove at any time.
+///
+/// Returns `None` if no suitable location could be determined, in which case callers
+/// should not cache anything.
+pub fn cache_dir() -> Option<PathBuf> {
+    resolve_cache_dir(&|name| std::env::var_os(name))
+}
+
+/// Directory with user configuration.
+pub fn config_dir() -> Option<PathBuf> {
+    resolve_config_dir(&|name| std::env::var_os(name))
+}
+
+/// Directory for data that is neither cached nor configured, such as updated data tables.
+pub fn data_dir() -> Option<PathBuf> {
+    resolve_data_dir(&|name| std::env::var_os(name))
+}
+
+/// Directory for cached HTTP responses.
+pub fn http_cache_dir() -> Option<PathBuf> {
+    cache_dir().map(|d| d.join("http"))
+}
+
+/// Directory for cached guessing results.
+pub fn result_cache_dir() -> Option<PathBuf> {
+    cache_dir().map(|d| d.join("results"))
+}
+
+/// Directory that is scanned for user-provided rules files.
+pub fn rules_dir() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("rules"))
+}
+
+/// Path of the credentials file, which may not exist.
+pub fn credentials_path() -> Option<PathBuf> {
+    resolve_credentials_path(&|name| std::env::var_os(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<OsString> {
+        move |name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| OsString::from(v))
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let var = env(&[
+            ("HOME", "/home/user"),
+            ("XDG_CACHE_HOME", "/xdg/cache"),
+            (CACHE_DIR_ENV, "/srv/cache"),
+            (CONFIG_DIR_ENV, "/srv/config"),
+            (CREDENTIALS_ENV, "/run/secrets/creds.toml"),
+        ]);
+        assert_eq!(resolve_cache_dir(&var), Some(PathBuf::from("/srv/cache")));
+        assert_eq!(resolve_config_dir(&var), Some(PathBuf::from("/srv/config")));
+        assert_eq!(
+            resolve_credentials_path(&var),
+            Some(PathBuf::from("/run/secrets/creds.toml"))
+        );
+    }
+
+    #[test]
+    fn test_xdg() {
+        let var = env(&[
+            ("HOME", "/home/user"),
+            ("XDG_CACHE_HOME", "/xdg/cache"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("XDG_DATA_HOME", "/xdg/data"),
+            (CACHE_DIR_ENV, ""),
+        ]);
+        assert_eq!(
+            resolve_cache_dir(&var),
+            Some(PathBuf::from("/xdg/cache/upstream-ontologist"))
+        );
+        assert_eq!(
+            resolve_config_dir(&var),
+            Some(PathBuf::from("/xdg/config/upstream-ontologist"))
+        );
+        assert_eq!(
+            resolve_data_dir(&var),
+            Some(PathBuf::from("/xdg/data/upstream-ontologist"))
+        );
+        assert_eq!(
+            resolve_credentials_path(&var),
+            Some(PathBuf::from(
+                "/xdg/config/upstream-ontologist/credentials.toml"
+            ))
+        );
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_home_fallback() {
+        let var = env(&[("HOME", "/home/user")]);
+        assert_eq!(
+            resolve_cache_dir(&var),
+            Some(PathBuf::from("/home/user/.cache/upstream-ontologist"))
+        );
+        assert_eq!(
+            resolve_config_dir(&var),
+            Some(PathBuf::from("/home/user/.config/upstream-ontologist"))
+        );
+        assert_eq!(
+            resolve_data_dir(&var),
+            Some(PathBuf::from("/home/user/.local/share/upstream-ontologist"))
+        );
+    }
+
+    #[test]
+    fn test_nothing_set() {
+        let var = env(&[]);
+        assert_eq!(resolve_cache_dir(&var), None);
+        assert_eq!(resolve_config_dir(&var), None);
+        assert_eq!(resolve_data_dir(&var), None);
+        assert_eq!(resolve_credentials_path(&var), None);
+    }
+}
@@ -0,0 +1,247 @@
+//! Classification of download URLs.
+//!
+//! Projects often link to a mix of source tarballs, binary installers and nightly or CI
+//! build artifacts. Only source tarballs are useful for the Download field (e.g. for
+//! generating watch files), so the others are demoted to Binary-Download.
+
+use crate::{UpstreamDatum, UpstreamDatumWithMetadata};
+use url::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadKind {
+    /// A source tarball or archive
+    SourceTarball,
+    /// A binary package or installer, e.g. an .exe or .dmg file
+    Binary,
+    /// A nightly build or an artifact produced by a CI system
+    CiArtifact,
+    /// Some other URL, e.g. a download page
+    Unknown,
+}
+
+const SOURCE_ARCHIVE_EXTENSIONS: &[&str] = &[
+    ".tar.gz",
+    ".tar.bz2",
+    ".tar.xz",
+    ".tar.lz",
+    ".tar.lzma",
+    ".tar.zst",
+    ".tgz",
+    ".tbz2",
+    ".txz",
+    ".tar",
+    ".zip",
+    ".7z",
+];
+
+const BINARY_EXTENSIONS: &[&str] = &[
+    ".exe",
+    ".msi",
+    ".msix",
+    ".appx",
+    ".dmg",
+    ".pkg",
+    ".appimage",
+    ".deb",
+    ".rpm",
+    ".apk",
+    ".snap",
+    ".flatpak",
+    ".whl",
+    ".jar",
+    ".nupkg",
+    ".gem",
+    ".ipa",
+];
+
+/// Markers in file names that indicate a platform-specific binary build.
+const BINARY_MARKERS: &[&str] = &[
+    "linux-x86_64",
+    "linux-amd64",
+    "linux-arm64",
+    "linux-aarch64",
+    "linux64",
+    "win32",
+    "win64",
+    "windows",
+    "macos",
+    "darwin",
+    "osx",
+    "x86_64",
+    "amd64",
+    "aarch64",
+    "arm64",
+    "i686",
+    "-bin.",
+    "_bin.",
+    "-binary",
+];
+
+/// Classify a download URL.
+pub fn classify_download_url(url: &Url) -> DownloadKind {
+    let host = url.host_str().unwrap_or("");
+    let path = url.path().to_lowercase();
+
+    // Nightly builds and CI artifacts
+    if host == "nightly.link"
+        || host.ends_with("circle-artifacts.com")
+        || host == "ci.appveyor.com"
+        || (host == "dev.azure.com" && path.contains("/_apis/build/"))
+        || path.contains("/-/jobs/")
+        || path.contains("/actions/runs/")
+        || path.contains("/lastsuccessfulbuild/artifact/")
+        || path.contains("/artifacts/")
+        || path.contains("/nightly/")
+        || path.contains("/nightlies/")
+        || path.contains("/snapshots/")
+    {
+        return DownloadKind::CiArtifact;
+    }
+
+    let basename = path.rsplit('/').next().unwrap_or("");
+
+    if BINARY_EXTENSIONS.iter().any(|ext| basename.ends_with(ext)) {
+        return DownloadKind::Binary;
+    }
+
+    if SOURCE_ARCHIVE_EXTENSIONS
+        .iter()
+        .any(|ext| basename.ends_with(ext))
+    {
+        if basename.contains("src") || basename.contains("source") {
+            return DownloadKind::SourceTarball;
+        }
+        if BINARY_MARKERS.iter().any(|m| basename.contains(m)) {
+            return DownloadKind::Binary;
+        }
+        if basename.contains("nightly") {
+            return DownloadKind::CiArtifact;
+        }
+        return DownloadKind::SourceTarball;
+    }
+
+    DownloadKind::Unknown
+}
+
+/// Demote Download data that point at binaries or CI artifacts to Binary-Download.
+pub fn demote_binary_download(mut datum: UpstreamDatumWithMetadata) -> UpstreamDatumWithMetadata {
+    if let UpstreamDatum::Download(ref s) = datum.datum {
+        if let Ok(url) = Url::parse(s) {
+            match classify_download_url(&url) {
+                DownloadKind::Binary | DownloadKind::CiArtifact => {
+                    log::debug!("Demoting {} to Binary-Download", s);
+                    datum.datum = UpstreamDatum::BinaryDownload(s.clone());
+                }
+                DownloadKind::SourceTarball | DownloadKind::Unknown => {}
+            }
+        }
+    }
+    datum
+}
+
+/// Check whether a new Download URL should be preferred over an existing one
+/// with the same certainty.
+pub(crate) fn is_preferred_download(new: &UpstreamDatum, current: &UpstreamDatum) -> bool {
+    let kind = |datum: &UpstreamDatum| match datum {
+        UpstreamDatum::Download(s) => Url::parse(s)
+            .map(|u| classify_download_url(&u))
+            .unwrap_or(DownloadKind::Unknown),
+        _ => DownloadKind::Unknown,
+    };
+    kind(new) == DownloadKind::SourceTarball && kind(current) != DownloadKind::SourceTarball
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify(url: &str) -> DownloadKind {
+        classify_download_url(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn test_classify_download_url() {
+        assert_eq!(
+            DownloadKind::SourceTarball,
+            classify("https://example.com/releases/foo-1.0.tar.gz")
+        );
+        assert_eq!(
+            DownloadKind::SourceTarball,
+            classify("https://github.com/foo/bar/archive/refs/tags/v1.0.zip")
+        );
+        assert_eq!(
+            DownloadKind::SourceTarball,
+            classify("https://example.com/foo-1.0-src.zip")
+        );
+        assert_eq!(
+            DownloadKind::Binary,
+            classify("https://example.com/foo-1.0-linux-x86_64.tar.gz")
+        );
+        assert_eq!(
+            DownloadKind::Binary,
+            classify("https://example.com/FooSetup-1.0.exe")
+        );
+        assert_eq!(
+            DownloadKind::CiArtifact,
+            classify("https://gitlab.com/foo/bar/-/jobs/1234/artifacts/download")
+        );
+        assert_eq!(
+            DownloadKind::CiArtifact,
+            classify("https://example.com/nightly/foo-latest.tar.gz")
+        );
+        assert_eq!(
+            DownloadKind::Unknown,
+            classify("https://example.com/download/")
+        );
+    }
+
+    #[test]
+    fn test_demote_binary_download() {
+        let datum = UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Download("https://example.com/foo.dmg".to_string()),
+            certainty: None,
+            origin: None,
+        };
+        assert_eq!(
+            UpstreamDatum::BinaryDownload("https://example.com/foo.dmg".to_string()),
+            demote_binary_download(datum).datum
+        );
+    }
+
+    #[test]
+    fn test_update_prefers_source_tarball() {
+        let mut metadata = vec![];
+        crate::update_from_guesses(
+            &mut metadata,
+            vec![
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Download("https://example.com/download/".to_string()),
+                    certainty: Some(crate::Certainty::Likely),
+                    origin: None,
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Download(
+                        "https://example.com/foo-1.0-win64.zip".to_string(),
+                    ),
+                    certainty: Some(crate::Certainty::Likely),
+                    origin: None,
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Download(
+                        "https://example.com/foo-1.0.tar.gz".to_string(),
+                    ),
+                    certainty: Some(crate::Certainty::Likely),
+                    origin: None,
+                },
+            ]
+            .into_iter(),
+        );
+        assert_eq!(
+            metadata.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Download("https://example.com/foo-1.0.tar.gz".to_string()),
+                UpstreamDatum::BinaryDownload("https://example.com/foo-1.0-win64.zip".to_string()),
+            ]
+        );
+    }
+}
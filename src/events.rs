@@ -0,0 +1,203 @@
+//! Stream of metadata discovery events.
+//!
+//! Rather than waiting for all guessers to finish, every datum is reported as soon as it is
+//! discovered. Events serialize to a single line of JSON each (NDJSON), so they can be fed
+//! into other tools incrementally.
+
+use crate::{GuesserSettings, MetadataGuesser, Origin, ProviderError, UpstreamDatumWithMetadata};
+use futures::stream::{Stream, StreamExt};
+use serde::Serialize;
+use std::path::Path;
+
+/// A single discovered datum.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DatumEvent {
+    /// Name of the field, e.g. "Repository"
+    pub field: String,
+
+    /// Value of the field
+    pub value: serde_json::Value,
+
+    /// Certainty of the guess, if known
+    pub certainty: Option<String>,
+
+    /// Where the datum was found, e.g. a path or URL; a list if it was found in several places
+    pub origin: Option<Origin>,
+
+    /// Name of the guesser that produced the datum
+    pub provider: Option<String>,
+
+    /// Time at which the datum was discovered, in RFC 3339 format
+    pub timestamp: String,
+}
+
+impl DatumEvent {
+    /// Create an event for a datum that was just discovered.
+    pub fn new(datum: &UpstreamDatumWithMetadata, provider: Option<&str>) -> Self {
+        Self {
+            field: datum.datum.field().to_string(),
+            value: datum.datum.to_json(),
+            certainty: datum.certainty.map(|c| c.to_string()),
+            origin: datum.origin.clone(),
+            provider: provider.map(|p| p.to_string()),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Serialize the event as a single line of JSON, without trailing newline.
+    pub fn to_ndjson(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+/// Stream discovery events for the project at `path`.
+///
+/// Errors from individual guessers are passed through, so a failing guesser does not
+/// end the stream.
+pub fn event_stream(
+    path: &Path,
+    settings: &GuesserSettings,
+) -> impl Stream<Item = Result<DatumEvent, ProviderError>> {
+    let guessers = crate::find_guessers(path);
+
+    crate::stream_with_provider(path, settings, guessers)
+        .map(|(provider, res)| res.map(|datum| DatumEvent::new(&datum, Some(&provider))))
+}
+
+/// Write discovery events for the project at `path` as NDJSON, one line per datum.
+///
+/// Each line is flushed as soon as the datum is discovered.
+pub async fn write_ndjson_events(
+    path: &Path,
+    settings: &GuesserSettings,
+    w: impl std::io::Write,
+) -> std::io::Result<()> {
+    write_ndjson(event_stream(path, settings), w).await
+}
+
+/// Write events from a stream as NDJSON, flushing after each line.
+async fn write_ndjson(
+    events: impl Stream<Item = Result<DatumEvent, ProviderError>>,
+    mut w: impl std::io::Write,
+) -> std::io::Result<()> {
+    let mut events = Box::pin(events);
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(event) => {
+                writeln!(w, "{}", event.to_ndjson())?;
+                w.flush()?;
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+impl MetadataGuesser {
+    /// Stream discovery events for the project at `path`.
+    ///
+    /// Unlike [`event_stream`], only data for the selected fields and with at least the
+    /// minimum certainty are reported. The data are reported as guessed, i.e. without the
+    /// summarizing, checking and extending done by [`MetadataGuesser::guess`].
+    pub fn event_stream(
+        &self,
+        path: &Path,
+    ) -> impl Stream<Item = Result<DatumEvent, ProviderError>> + '_ {
+        let guessers = crate::filter_guessers(self.registry.find_guessers(path), &self.fields);
+        crate::stream_with_provider(path, &self.settings(), guessers).filter_map(
+            move |(provider, res)| {
+                futures::future::ready(match res {
+                    Ok(datum)
+                        if !self.fields.contains(datum.datum.field())
+                            || !self.meets_minimum_certainty(datum.certainty) =>
+                    {
+                        None
+                    }
+                    res => Some(res.map(|datum| DatumEvent::new(&datum, Some(&provider)))),
+                })
+            },
+        )
+    }
+
+    /// Write discovery events for the project at `path` as NDJSON, one line per datum.
+    ///
+    /// See [`MetadataGuesser::event_stream`]. Each line is flushed as soon as the datum is
+    /// discovered.
+    pub async fn write_ndjson(&self, path: &Path, w: impl std::io::Write) -> std::io::Result<()> {
+        write_ndjson(self.event_stream(path), w).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Certainty, Origin, Person, UpstreamDatum};
+
+    #[test]
+    fn test_datum_event() {
+        let event = DatumEvent::new(
+            &UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Maintainer(Person {
+                    name: Some("Jane Doe".to_string()),
+                    email: Some("jane@example.com".to_string()),
+                    url: None,
+                }),
+                certainty: Some(Certainty::Likely),
+                origin: Some(Origin::Other("AUTHORS".to_string())),
+            },
+            Some("AUTHORS"),
+        );
+        let value: serde_json::Value = serde_json::from_str(&event.to_ndjson()).unwrap();
+        assert_eq!(value["field"], "Maintainer");
+        assert_eq!(
+            value["value"],
+            serde_json::json!({"name": "Jane Doe", "email": "jane@example.com"})
+        );
+        assert_eq!(value["certainty"], "likely");
+        assert_eq!(value["origin"], "AUTHORS");
+        assert_eq!(value["provider"], "AUTHORS");
+        assert!(!event.to_ndjson().contains('\n'));
+    }
+
+    #[tokio::test]
+    async fn test_write_ndjson_events() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(td.path().join("configure.ac"), "AC_INIT([foo], [1.0])\n").unwrap();
+        let mut out = Vec::new();
+        write_ndjson_events(td.path(), &GuesserSettings::default(), &mut out)
+            .await
+            .unwrap();
+        let events = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .filter(|e| e["provider"] == "configure.ac")
+            .map(|e| (e["field"].clone(), e["value"].clone()))
+            .collect::<Vec<_>>();
+        assert!(events.contains(&("Name".into(), "foo".into())));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_guesser_write_ndjson() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(td.path().join("configure.ac"), "AC_INIT([foo], [1.0])\n").unwrap();
+        let mut fields = crate::FieldSet::empty();
+        fields.insert("Version");
+        let guesser = MetadataGuesser::builder()
+            .net_access(false)
+            .fields(fields)
+            .build();
+        let mut out = Vec::new();
+        guesser.write_ndjson(td.path(), &mut out).await.unwrap();
+        let events = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .collect::<Vec<_>>();
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| e["field"] == "Version"));
+        assert!(events
+            .iter()
+            .any(|e| e["provider"] == "configure.ac" && e["value"] == "1.0"));
+    }
+}
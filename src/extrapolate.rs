@@ -226,6 +226,36 @@ async fn extrapolate_bug_database_from_repository(
     )
 }
 
+async fn extrapolate_mailing_list_from_repository(
+    upstream_metadata: &UpstreamMetadata,
+    net_access: bool,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let old_value = upstream_metadata.get("Repository").unwrap();
+
+    let url = match old_value.datum.to_url() {
+        Some(url) => url,
+        None => return Ok(vec![]),
+    };
+
+    Ok(
+        if let Some(list_url) =
+            crate::guess_mailing_list_url_from_repo_url(&url, Some(net_access)).await
+        {
+            // Lists are named after the repository by convention only
+            vec![UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::MailingList(list_url.to_string()),
+                certainty: Some(
+                    std::cmp::min(old_value.certainty, Some(Certainty::Possible))
+                        .unwrap_or(Certainty::Possible),
+                ),
+                origin: old_value.origin.clone(),
+            }]
+        } else {
+            vec![]
+        },
+    )
+}
+
 async fn extrapolate_bug_submit_from_bug_db(
     upstream_metadata: &UpstreamMetadata,
     net_access: bool,
@@ -311,6 +341,52 @@ async fn extrapolate_repository_from_download(
     })
 }
 
+async fn extrapolate_version_from_download(
+    upstream_metadata: &UpstreamMetadata,
+    net_access: bool,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    if !net_access {
+        return Ok(vec![]);
+    }
+    let download = upstream_metadata.get("Download").unwrap();
+
+    let Some(url) = download.datum.to_url() else {
+        warn!("Download field is not a URL");
+        return Ok(vec![]);
+    };
+
+    let likely = std::cmp::min(download.certainty, Some(Certainty::Likely));
+    let (directory, name, download_certainty) = if let Some((directory, name)) =
+        crate::listing::gnu_download_directory(&url)
+    {
+        // A tarball is preferred over a directory with the same certainty
+        (directory, Some(name), download.certainty)
+    } else if crate::download::classify_download_url(&url) == crate::download::DownloadKind::Unknown
+    {
+        // This may be a download page that lists tarballs
+        (url, upstream_metadata.name().map(|n| n.to_string()), likely)
+    } else {
+        return Ok(vec![]);
+    };
+
+    let Some(release) = crate::listing::scan_directory(&directory, name.as_deref()).await? else {
+        return Ok(vec![]);
+    };
+
+    Ok(vec![
+        UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Version(release.version),
+            certainty: Some(likely.unwrap_or(Certainty::Likely)),
+            origin: Some(directory.clone().into()),
+        },
+        UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Download(release.url.to_string()),
+            certainty: download_certainty,
+            origin: Some(directory.into()),
+        },
+    ])
+}
+
 async fn extrapolate_name_from_repository(
     upstream_metadata: &UpstreamMetadata,
     net_access: bool,
@@ -391,11 +467,114 @@ async fn extrapolate_contact_from_maintainer(
 
     Ok(vec![UpstreamDatumWithMetadata {
         datum: UpstreamDatum::Contact(maintainer.datum.as_person().unwrap().to_string()),
-        certainty: maintainer.certainty,
+        certainty: Some(
+            std::cmp::min(maintainer.certainty, Some(Certainty::Likely))
+                .unwrap_or(Certainty::Likely),
+        ),
         origin: maintainer.origin.clone(),
     }])
 }
 
+async fn extrapolate_contact_from_author(
+    upstream_metadata: &UpstreamMetadata,
+    _net_access: bool,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    // The maintainer is a better contact than any of the authors, and an explicit contact
+    // is better than either
+    if upstream_metadata.contains_key("Maintainer") || upstream_metadata.contains_key("Contact") {
+        return Ok(vec![]);
+    }
+
+    let authors = upstream_metadata.get("Author").unwrap();
+
+    let email = match &authors.datum {
+        UpstreamDatum::Author(authors) => authors.iter().find_map(|a| a.email.as_deref()),
+        _ => None,
+    };
+
+    // The authors are not necessarily the people to contact about the project as a whole,
+    // so this is only an address rather than someone's name
+    Ok(if let Some(email) = email {
+        vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Contact(format!("mailto:{}", email)),
+            certainty: Some(
+                std::cmp::min(authors.certainty, Some(Certainty::Likely))
+                    .unwrap_or(Certainty::Likely),
+            ),
+            origin: authors.origin.clone(),
+        }]
+    } else {
+        vec![]
+    })
+}
+
+async fn extrapolate_contact_from_repository(
+    upstream_metadata: &UpstreamMetadata,
+    net_access: bool,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    if !net_access
+        || upstream_metadata.contains_key("Contact")
+        || upstream_metadata.contains_key("Maintainer")
+        || upstream_metadata
+            .author()
+            .map(|authors| authors.iter().any(|a| a.email.is_some()))
+            .unwrap_or(false)
+    {
+        return Ok(vec![]);
+    }
+
+    let repository = upstream_metadata.get("Repository").unwrap();
+    let url = match repository.datum.to_url() {
+        Some(url) => url,
+        None => return Ok(vec![]),
+    };
+
+    Ok(
+        if let Some(discussions_url) = crate::guess_discussions_url_from_repo_url(&url).await {
+            vec![UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Contact(discussions_url.to_string()),
+                certainty: Some(
+                    std::cmp::min(repository.certainty, Some(Certainty::Likely))
+                        .unwrap_or(Certainty::Likely),
+                ),
+                origin: repository.origin.clone(),
+            }]
+        } else {
+            vec![]
+        },
+    )
+}
+
+async fn extrapolate_organization_from_repository(
+    upstream_metadata: &UpstreamMetadata,
+    net_access: bool,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    if !net_access {
+        return Ok(vec![]);
+    }
+
+    let repository = upstream_metadata.get("Repository").unwrap();
+    let url = match repository.datum.to_url() {
+        Some(url) => url,
+        None => return Ok(vec![]),
+    };
+
+    Ok(
+        if let Some(organization) = crate::guess_organization_from_repo_url(&url).await {
+            vec![UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Organization(organization),
+                certainty: Some(
+                    std::cmp::min(repository.certainty, Some(Certainty::Likely))
+                        .unwrap_or(Certainty::Likely),
+                ),
+                origin: repository.origin.clone(),
+            }]
+        } else {
+            vec![]
+        },
+    )
+}
+
 async fn consult_homepage(
     upstream_metadata: &UpstreamMetadata,
     net_access: bool,
@@ -468,6 +647,13 @@ const EXTRAPOLATIONS: &[Extrapolation] = &[
             Box::pin(async move { extrapolate_bug_database_from_repository(&us, na).await })
         },
     },
+    Extrapolation {
+        from_fields: &["Repository"],
+        to_fields: &["MailingList"],
+        cb: |us, na| {
+            Box::pin(async move { extrapolate_mailing_list_from_repository(&us, na).await })
+        },
+    },
     Extrapolation {
         from_fields: &["Bug-Database"],
         to_fields: &["Bug-Submit"],
@@ -483,6 +669,11 @@ const EXTRAPOLATIONS: &[Extrapolation] = &[
         to_fields: &["Repository"],
         cb: |us, na| Box::pin(async move { extrapolate_repository_from_download(&us, na).await }),
     },
+    Extrapolation {
+        from_fields: &["Download"],
+        to_fields: &["Version", "Download"],
+        cb: |us, na| Box::pin(async move { extrapolate_version_from_download(&us, na).await }),
+    },
     Extrapolation {
         from_fields: &["Repository"],
         to_fields: &["Name"],
@@ -500,6 +691,23 @@ const EXTRAPOLATIONS: &[Extrapolation] = &[
         to_fields: &["Contact"],
         cb: |us, na| Box::pin(async move { extrapolate_contact_from_maintainer(&us, na).await }),
     },
+    Extrapolation {
+        from_fields: &["Author"],
+        to_fields: &["Contact"],
+        cb: |us, na| Box::pin(async move { extrapolate_contact_from_author(&us, na).await }),
+    },
+    Extrapolation {
+        from_fields: &["Repository"],
+        to_fields: &["Contact"],
+        cb: |us, na| Box::pin(async move { extrapolate_contact_from_repository(&us, na).await }),
+    },
+    Extrapolation {
+        from_fields: &["Repository"],
+        to_fields: &["Organization"],
+        cb: |us, na| {
+            Box::pin(async move { extrapolate_organization_from_repository(&us, na).await })
+        },
+    },
     Extrapolation {
         from_fields: &["Homepage"],
         to_fields: &["Bug-Database", "Repository"],
@@ -507,6 +715,212 @@ const EXTRAPOLATIONS: &[Extrapolation] = &[
     },
 ];
 
+/// A group of extrapolations that are run together.
+#[derive(Debug, PartialEq, Eq)]
+struct Stage {
+    /// Indexes into the list of extrapolations, in declaration order
+    extrapolations: Vec<usize>,
+
+    /// Whether the extrapolations feed into each other, in which case they have to be
+    /// repeated until they no longer change anything
+    cyclic: bool,
+}
+
+/// Check whether the output of one extrapolation is used as input by another.
+fn feeds_into(a: &Extrapolation, b: &Extrapolation) -> bool {
+    a.to_fields.iter().any(|f| b.from_fields.contains(f))
+}
+
+/// Compute the stages in which to run extrapolations.
+///
+/// The extrapolations form a dependency graph, with an edge from A to B if A produces a
+/// field that B consumes. Each strongly connected component of that graph becomes a stage,
+/// and the stages are returned in topological order, so that every extrapolation runs after
+/// the extrapolations that can provide its input.
+fn compute_stages(extrapolations: &[Extrapolation]) -> Vec<Stage> {
+    // Tarjan's strongly connected components algorithm
+    struct State {
+        index: Vec<Option<usize>>,
+        lowlink: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        next_index: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    fn visit(v: usize, edges: &[Vec<usize>], state: &mut State) {
+        state.index[v] = Some(state.next_index);
+        state.lowlink[v] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        for &w in &edges[v] {
+            match state.index[w] {
+                None => {
+                    visit(w, edges, state);
+                    state.lowlink[v] = std::cmp::min(state.lowlink[v], state.lowlink[w]);
+                }
+                Some(index) if state.on_stack[w] => {
+                    state.lowlink[v] = std::cmp::min(state.lowlink[v], index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.lowlink[v]) == state.index[v] {
+            let mut component = vec![];
+            loop {
+                let w = state.stack.pop().unwrap();
+                state.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let n = extrapolations.len();
+    let edges = (0..n)
+        .map(|a| {
+            (0..n)
+                .filter(|&b| feeds_into(&extrapolations[a], &extrapolations[b]))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut state = State {
+        index: vec![None; n],
+        lowlink: vec![0; n],
+        on_stack: vec![false; n],
+        stack: vec![],
+        next_index: 0,
+        components: vec![],
+    };
+
+    for v in 0..n {
+        if state.index[v].is_none() {
+            visit(v, &edges, &mut state);
+        }
+    }
+
+    // Tarjan's algorithm finds the components in reverse topological order.
+    state
+        .components
+        .into_iter()
+        .rev()
+        .map(|mut component| {
+            component.sort();
+            let cyclic = component.len() > 1 || edges[component[0]].contains(&component[0]);
+            Stage {
+                extrapolations: component,
+                cyclic,
+            }
+        })
+        .collect()
+}
+
+/// Run a single extrapolation, returning whether it changed anything.
+async fn run_extrapolation(
+    upstream_metadata: &mut UpstreamMetadata,
+    extrapolation: &Extrapolation,
+    net_access: bool,
+) -> Result<bool, ProviderError> {
+    let from_fields = extrapolation.from_fields;
+    let to_fields = extrapolation.to_fields;
+    let cb = extrapolation.cb;
+    let from_values = from_fields
+        .iter()
+        .map(|f| upstream_metadata.get(f))
+        .collect::<Vec<_>>();
+    if !from_values.iter().all(|v| v.is_some()) {
+        log::trace!(
+            "Not enough values for extrapolation from {:?} to {:?}",
+            from_fields,
+            to_fields
+        );
+        return Ok(false);
+    }
+
+    let from_values = from_values
+        .iter()
+        .map(|v| v.unwrap().clone())
+        .collect::<Vec<_>>();
+
+    let from_certainty = from_values.iter().map(|v| v.certainty).min().unwrap();
+
+    let old_to_values: std::collections::HashMap<_, _> = to_fields
+        .iter()
+        .filter_map(|f| upstream_metadata.get(f).map(|v| (f, v.clone())))
+        .collect();
+
+    assert!(old_to_values.values().all(|v| v.certainty.is_some()));
+
+    // If any of the to_fields already exist in old_to_values with a better or same
+    // certainty, then we don't need to extrapolate.
+    if to_fields.iter().all(|f| {
+        old_to_values
+            .get(f)
+            .map(|v| v.certainty >= from_certainty)
+            .unwrap_or(false)
+    }) {
+        log::trace!(
+            "Not extrapolating from {:?} to {:?} because of certainty ({:?} >= {:?})",
+            from_fields,
+            to_fields,
+            old_to_values
+                .values()
+                .map(|v| v.certainty)
+                .collect::<Vec<_>>(),
+            from_certainty
+        );
+        return Ok(false);
+    }
+
+    let extra_upstream_metadata = cb(upstream_metadata.clone(), net_access).await?;
+    let changes = upstream_metadata.update(extra_upstream_metadata.into_iter());
+
+    if changes.is_empty() {
+        return Ok(false);
+    }
+
+    log::debug!(
+        "Extrapolating ({:?} ⇒ {:?}) from ({:?})",
+        old_to_values
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k, v.datum))
+            .collect::<Vec<_>>(),
+        changes
+            .iter()
+            .map(|d| format!("{}: {}", d.datum.field(), d.datum))
+            .collect::<Vec<_>>(),
+        from_values
+            .iter()
+            .map(|v| format!(
+                "{}: {} ({})",
+                v.datum.field(),
+                v.datum,
+                v.certainty
+                    .map_or_else(|| "unknown".to_string(), |c| c.to_string())
+            ))
+            .collect::<Vec<_>>()
+    );
+    Ok(true)
+}
+
+/// Extrapolate fields from other fields.
+///
+/// Extrapolations are run in dependency order. Extrapolations that feed into each other
+/// (e.g. Repository and Repository-Browse) are repeated until they no longer change
+/// anything; if they end up back in a state they have been in before, an
+/// [`ProviderError::ExtrapolationCycle`] error is returned.
+///
+/// # Arguments
+/// * `upstream_metadata`: Metadata to extend
+/// * `net_access`: Whether to allow net access
+/// * `iteration_limit`: Maximum number of rounds for a set of interdependent extrapolations
 pub async fn extrapolate_fields(
     upstream_metadata: &mut UpstreamMetadata,
     net_access: bool,
@@ -514,104 +928,228 @@ pub async fn extrapolate_fields(
 ) -> Result<(), ProviderError> {
     let iteration_limit = iteration_limit.unwrap_or(DEFAULT_ITERATION_LIMIT);
 
-    let mut changed = true;
-    let mut iterations = 0;
-
-    while changed {
-        changed = false;
-
-        iterations += 1;
-
-        if iterations > iteration_limit {
-            return Err(ProviderError::ExtrapolationLimitExceeded(iteration_limit));
+    for stage in compute_stages(EXTRAPOLATIONS) {
+        if !stage.cyclic {
+            for i in stage.extrapolations {
+                run_extrapolation(upstream_metadata, &EXTRAPOLATIONS[i], net_access).await?;
+            }
+            continue;
         }
 
-        for extrapolation in EXTRAPOLATIONS {
-            let from_fields = extrapolation.from_fields;
-            let to_fields = extrapolation.to_fields;
-            let cb = extrapolation.cb;
-            let from_values = from_fields
-                .iter()
-                .map(|f| upstream_metadata.get(f))
-                .collect::<Vec<_>>();
-            if !from_values.iter().all(|v| v.is_some()) {
-                log::trace!(
-                    "Not enough values for extrapolation from {:?} to {:?}",
-                    from_fields,
-                    to_fields
-                );
-                continue;
-            }
+        let mut fields = stage
+            .extrapolations
+            .iter()
+            .flat_map(|i| EXTRAPOLATIONS[*i].to_fields.iter())
+            .copied()
+            .collect::<Vec<_>>();
+        fields.sort();
+        fields.dedup();
 
-            let from_values = from_values
+        let snapshot = |upstream_metadata: &UpstreamMetadata| {
+            fields
                 .iter()
-                .map(|v| v.unwrap().clone())
-                .collect::<Vec<_>>();
+                .map(|f| upstream_metadata.get(f).cloned())
+                .collect::<Vec<_>>()
+        };
 
-            let from_certainties = from_fields
-                .iter()
-                .map(|f| upstream_metadata.get(f).unwrap().certainty)
-                .collect::<Vec<_>>();
+        let mut seen = vec![snapshot(upstream_metadata)];
+        let mut iterations = 0;
 
-            let from_certainty = *from_certainties.iter().min().unwrap();
+        loop {
+            iterations += 1;
 
-            let old_to_values: std::collections::HashMap<_, _> = to_fields
-                .iter()
-                .filter_map(|f| upstream_metadata.get(f).map(|v| (f, v.clone())))
-                .collect();
-
-            assert!(old_to_values.values().all(|v| v.certainty.is_some()));
-
-            // If any of the to_fields already exist in old_to_values with a better or same
-            // certainty, then we don't need to extrapolate.
-            if to_fields.iter().all(|f| {
-                old_to_values
-                    .get(f)
-                    .map(|v| v.certainty >= from_certainty)
-                    .unwrap_or(false)
-            }) {
-                log::trace!(
-                    "Not extrapolating from {:?} to {:?} because of certainty ({:?} >= {:?})",
-                    from_fields,
-                    to_fields,
-                    old_to_values
-                        .values()
-                        .map(|v| v.certainty)
-                        .collect::<Vec<_>>(),
-                    from_certainty
-                );
-                continue;
+            if iterations > iteration_limit {
+                return Err(ProviderError::ExtrapolationLimitExceeded(iteration_limit));
+            }
+
+            let mut changed = false;
+            for i in stage.extrapolations.iter() {
+                changed |=
+                    run_extrapolation(upstream_metadata, &EXTRAPOLATIONS[*i], net_access).await?;
+            }
+
+            if !changed {
+                break;
             }
 
-            let extra_upstream_metadata = cb(upstream_metadata.clone(), net_access).await?;
-            let changes = upstream_metadata.update(extra_upstream_metadata.into_iter());
-
-            if !changes.is_empty() {
-                log::debug!(
-                    "Extrapolating ({:?} ⇒ {:?}) from ({:?})",
-                    old_to_values
-                        .iter()
-                        .map(|(k, v)| format!("{}: {}", k, v.datum))
-                        .collect::<Vec<_>>(),
-                    changes
-                        .iter()
-                        .map(|d| format!("{}: {}", d.datum.field(), d.datum))
-                        .collect::<Vec<_>>(),
-                    from_values
-                        .iter()
-                        .map(|v| format!(
-                            "{}: {} ({})",
-                            v.datum.field(),
-                            v.datum,
-                            v.certainty
-                                .map_or_else(|| "unknown".to_string(), |c| c.to_string())
-                        ))
-                        .collect::<Vec<_>>()
-                );
-                changed = true;
+            let current = snapshot(upstream_metadata);
+            if seen.contains(&current) {
+                return Err(ProviderError::ExtrapolationCycle(
+                    fields.iter().map(|f| f.to_string()).collect(),
+                ));
             }
+            seen.push(current);
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_stages_chain() {
+        let noop: ExtrapolationCallback = |_, _| Box::pin(async { Ok(vec![]) });
+        // Declared in reverse order of dependencies
+        let extrapolations = [
+            Extrapolation {
+                from_fields: &["Bug-Submit"],
+                to_fields: &["Contact"],
+                cb: noop,
+            },
+            Extrapolation {
+                from_fields: &["Bug-Database"],
+                to_fields: &["Bug-Submit"],
+                cb: noop,
+            },
+            Extrapolation {
+                from_fields: &["Repository"],
+                to_fields: &["Bug-Database"],
+                cb: noop,
+            },
+        ];
+        assert_eq!(
+            compute_stages(&extrapolations),
+            vec![
+                Stage {
+                    extrapolations: vec![2],
+                    cyclic: false
+                },
+                Stage {
+                    extrapolations: vec![1],
+                    cyclic: false
+                },
+                Stage {
+                    extrapolations: vec![0],
+                    cyclic: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_stages() {
+        let stages = compute_stages(EXTRAPOLATIONS);
+        assert_eq!(
+            stages.iter().map(|s| s.extrapolations.len()).sum::<usize>(),
+            EXTRAPOLATIONS.len()
+        );
+
+        let position = |from: &str, to: &str| {
+            stages
+                .iter()
+                .position(|s| {
+                    s.extrapolations.iter().any(|i| {
+                        EXTRAPOLATIONS[*i].from_fields == [from]
+                            && EXTRAPOLATIONS[*i].to_fields == [to]
+                    })
+                })
+                .unwrap()
+        };
+
+        // Repository and Repository-Browse can be derived from each other
+        assert_eq!(
+            position("Repository", "Repository-Browse"),
+            position("Repository-Browse", "Repository")
+        );
+        assert!(stages[position("Repository", "Repository-Browse")].cyclic);
+
+        // The name is derived from the repository, but never the other way around
+        assert!(position("Repository", "Name") > position("Repository", "Repository-Browse"));
+        assert!(!stages[position("Repository", "Name")].cyclic);
+
+        // Bugs-Database is only copied to Bug-Database, so that happens first
+        assert!(position("Bugs-Database", "Bug-Database") < position("Bug-Database", "Bug-Submit"));
+    }
+
+    #[tokio::test]
+    async fn test_extrapolate_chain() {
+        let mut metadata = UpstreamMetadata::from_data(vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Repository("https://github.com/jelmer/dulwich".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        }]);
+        extrapolate_fields(&mut metadata, false, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.bug_database(),
+            Some("https://github.com/jelmer/dulwich/issues")
+        );
+        assert_eq!(
+            metadata.bug_submit(),
+            Some("https://github.com/jelmer/dulwich/issues/new")
+        );
+        assert_eq!(metadata.name(), Some("dulwich"));
+    }
+
+    #[tokio::test]
+    async fn test_extrapolate_contact() {
+        let author = |name: &str, email: Option<&str>| crate::Person {
+            name: Some(name.to_string()),
+            email: email.map(|e| e.to_string()),
+            url: None,
+        };
+        let mut metadata = UpstreamMetadata::from_data(vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Author(vec![
+                author("Joe Author", None),
+                author("Jane Author", Some("jane@example.com")),
+            ]),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        }]);
+        extrapolate_fields(&mut metadata, false, None)
+            .await
+            .unwrap();
+        let contact = metadata.get("Contact").unwrap();
+        assert_eq!(
+            contact.datum,
+            UpstreamDatum::Contact("mailto:jane@example.com".to_string())
+        );
+        assert_eq!(contact.certainty, Some(Certainty::Likely));
+
+        // An explicit contact is left alone
+        let mut metadata = UpstreamMetadata::from_data(vec![
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Contact("Joe Contact <joe@example.com>".to_string()),
+                certainty: Some(Certainty::Certain),
+                origin: None,
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Maintainer(author("Jane Author", Some("jane@example.com"))),
+                certainty: Some(Certainty::Certain),
+                origin: None,
+            },
+        ]);
+        extrapolate_fields(&mut metadata, false, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.get("Contact").unwrap().datum,
+            UpstreamDatum::Contact("Joe Contact <joe@example.com>".to_string())
+        );
+
+        // ... even if the authors are more certain
+        let mut metadata = UpstreamMetadata::from_data(vec![
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Contact("Joe Contact <joe@example.com>".to_string()),
+                certainty: Some(Certainty::Possible),
+                origin: None,
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Author(vec![author("Jane Author", Some("jane@example.com"))]),
+                certainty: Some(Certainty::Certain),
+                origin: None,
+            },
+        ]);
+        extrapolate_fields(&mut metadata, false, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.get("Contact").unwrap().datum,
+            UpstreamDatum::Contact("Joe Contact <joe@example.com>".to_string())
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Selection of the metadata fields a caller is interested in.
+//!
+//! Guessing all metadata for a project can be expensive, especially when network access is
+//! allowed. Callers that only need a few fields can pass a [`FieldSet`], so that providers
+//! that can't supply any of them are skipped entirely.
+
+use crate::descriptors::supported_fields;
+
+/// A set of metadata fields, stored as a bitmask over [`supported_fields`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldSet(u128);
+
+impl FieldSet {
+    /// The set of all fields, including ones that are not described in [`supported_fields`].
+    pub const fn all() -> Self {
+        Self(u128::MAX)
+    }
+
+    /// The empty set.
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    fn bit(field: &str) -> Option<u128> {
+        supported_fields()
+            .iter()
+            .position(|f| f.name == field)
+            .map(|i| 1 << i)
+    }
+
+    /// Whether this set contains all fields.
+    pub fn is_all(&self) -> bool {
+        self.0 == u128::MAX
+    }
+
+    /// Whether this set contains no fields.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Add a field to the set.
+    ///
+    /// Returns false if the field is not known.
+    pub fn insert(&mut self, field: &str) -> bool {
+        match Self::bit(field) {
+            Some(bit) => {
+                self.0 |= bit;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check whether the set contains a field.
+    pub fn contains(&self, field: &str) -> bool {
+        match Self::bit(field) {
+            Some(bit) => self.0 & bit != 0,
+            None => self.is_all(),
+        }
+    }
+
+    /// Check whether the set contains any of the given fields.
+    pub fn intersects(&self, fields: &[&str]) -> bool {
+        fields.iter().any(|f| self.contains(f))
+    }
+
+    /// Return the subset of `fields` that is contained in this set.
+    pub fn filter<'a>(&self, fields: &[&'a str]) -> Vec<&'a str> {
+        fields
+            .iter()
+            .filter(|f| self.contains(f))
+            .copied()
+            .collect()
+    }
+
+    /// Iterate over the names of the fields in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> + '_ {
+        supported_fields()
+            .iter()
+            .filter(|f| self.contains(f.name))
+            .map(|f| f.name)
+    }
+}
+
+impl Default for FieldSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for FieldSet {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut ret = Self::empty();
+        for field in iter {
+            if !ret.insert(field) {
+                log::warn!("Unknown field {}, ignoring", field);
+            }
+        }
+        ret
+    }
+}
+
+impl std::ops::BitOr for FieldSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_fields_fit() {
+        assert!(supported_fields().len() < 128);
+    }
+
+    #[test]
+    fn test_field_set() {
+        let fields = ["Homepage", "Repository"].into_iter().collect::<FieldSet>();
+        assert!(fields.contains("Homepage"));
+        assert!(!fields.contains("Name"));
+        assert!(!fields.contains("X-Unknown"));
+        assert!(fields.intersects(&["Name", "Repository"]));
+        assert!(!fields.intersects(&["Name", "Version"]));
+        assert_eq!(
+            fields.filter(&["Name", "Repository", "Homepage"]),
+            vec!["Repository", "Homepage"]
+        );
+        assert_eq!(
+            fields.iter().collect::<Vec<_>>(),
+            vec!["Homepage", "Repository"]
+        );
+        assert!(FieldSet::all().contains("X-Unknown"));
+        assert!(FieldSet::empty().is_empty());
+    }
+}
@@ -0,0 +1,201 @@
+//! Data-driven test fixtures.
+//!
+//! A fixture is a directory with an `input/` tree and an `expected.yaml` file that lists
+//! the metadata guessed for that tree, annotated with the certainty of each value and
+//! where it was found:
+//!
+//! ```yaml
+//! Name:
+//!   value: dulwich
+//!   certainty: certain
+//!   origin: setup.cfg
+//! ```
+//!
+//! Fixtures in `testdata/` are turned into tests by the build script. New fixtures can be
+//! recorded from a real package with [`record_fixture`], which copies just the files that
+//! providers read.
+use crate::{MetadataGuesser, Origin, ProviderError, UpstreamMetadata};
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+
+/// Name of the directory in a fixture that holds the package tree.
+pub const INPUT_DIR: &str = "input";
+
+/// Name of the file in a fixture that holds the annotated expected metadata.
+pub const EXPECTED_FILE: &str = "expected.yaml";
+
+/// Files larger than this are not copied into a fixture.
+const MAX_FILE_SIZE: u64 = 256 * 1024;
+
+/// Directories that are never copied into a fixture.
+const SKIPPED_DIRECTORIES: &[&str] = &["target", "node_modules", "vendor", "build", "dist"];
+
+/// Make the paths in an origin relative to the root of the input tree.
+fn relative_origin(origin: &Origin, root: &Path) -> Origin {
+    match origin {
+        Origin::Path(path) => Origin::Path(
+            path.strip_prefix(root)
+                .or_else(|_| path.strip_prefix("."))
+                .unwrap_or(path)
+                .to_path_buf(),
+        ),
+        Origin::Multiple(origins) => {
+            Origin::Multiple(origins.iter().map(|o| relative_origin(o, root)).collect())
+        }
+        origin => origin.clone(),
+    }
+}
+
+/// Convert guessed metadata into the annotated form used in `expected.yaml`.
+///
+/// If there are several values for a field, they are listed in the order in which they
+/// were guessed.
+pub fn annotate(metadata: &UpstreamMetadata, root: &Path) -> Mapping {
+    let mut ret = Mapping::new();
+    for datum in metadata.iter() {
+        let mut entry = Mapping::new();
+        entry.insert("value".into(), serde_yaml::to_value(&datum.datum).unwrap());
+        if let Some(certainty) = datum.certainty {
+            entry.insert("certainty".into(), certainty.to_string().into());
+        }
+        if let Some(origin) = datum.origin.as_ref() {
+            entry.insert(
+                "origin".into(),
+                serde_yaml::to_value(relative_origin(origin, root)).unwrap(),
+            );
+        }
+        let key = Value::String(datum.datum.field().to_string());
+        match ret.get_mut(&key) {
+            None => {
+                ret.insert(key, Value::Mapping(entry));
+            }
+            Some(Value::Sequence(entries)) => entries.push(Value::Mapping(entry)),
+            Some(existing) => {
+                *existing = Value::Sequence(vec![existing.clone(), Value::Mapping(entry)]);
+            }
+        }
+    }
+    ret
+}
+
+/// Guess the metadata for the input tree of a fixture, in annotated form.
+///
+/// The package is trusted, but no network access is allowed so that fixtures are
+/// reproducible.
+pub async fn run_fixture(fixture: &Path) -> Result<Mapping, ProviderError> {
+    let input = fixture.join(INPUT_DIR).canonicalize()?;
+    let metadata = MetadataGuesser::builder()
+        .trust(true)
+        .net_access(false)
+        .build()
+        .guess(&input)
+        .await?;
+    Ok(annotate(&metadata, &input))
+}
+
+/// Load the annotated expected metadata of a fixture.
+pub fn load_expected(fixture: &Path) -> Result<Mapping, ProviderError> {
+    let file = std::fs::File::open(fixture.join(EXPECTED_FILE))?;
+    serde_yaml::from_reader(file).map_err(|e| ProviderError::ParseError(e.to_string()))
+}
+
+/// Find the files in a package tree that are read by a provider.
+fn provider_files(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut ret = vec![];
+    let mut todo = vec![PathBuf::new()];
+    while let Some(relpath) = todo.pop() {
+        for entry in std::fs::read_dir(root.join(&relpath))? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let child = relpath.join(&name);
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                    todo.push(child);
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            if crate::descriptors::local_provider_fields(&child.to_string_lossy())
+                .or_else(|| crate::descriptors::local_provider_fields(&name))
+                .is_none()
+            {
+                continue;
+            }
+            if entry.metadata()?.len() > MAX_FILE_SIZE {
+                log::debug!("Not copying {}: too large", child.display());
+                continue;
+            }
+            ret.push(child);
+        }
+    }
+    ret.sort();
+    Ok(ret)
+}
+
+/// Record a new fixture from a package tree.
+///
+/// The files that providers read are copied into the `input/` directory of `fixture`,
+/// and the metadata guessed from that copy is written to `expected.yaml`. The recorded
+/// expectations should be reviewed before the fixture is committed.
+pub async fn record_fixture(source: &Path, fixture: &Path) -> Result<(), ProviderError> {
+    let input = fixture.join(INPUT_DIR);
+    if input.exists() {
+        return Err(ProviderError::Other(format!(
+            "{} already exists",
+            input.display()
+        )));
+    }
+    for relpath in provider_files(source)? {
+        let dest = input.join(&relpath);
+        std::fs::create_dir_all(dest.parent().unwrap())?;
+        std::fs::copy(source.join(&relpath), &dest)?;
+    }
+    std::fs::create_dir_all(&input)?;
+    let annotated = run_fixture(fixture).await?;
+    let text =
+        serde_yaml::to_string(&annotated).map_err(|e| ProviderError::Other(e.to_string()))?;
+    std::fs::write(fixture.join(EXPECTED_FILE), text)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_fixture() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("debian/upstream")).unwrap();
+        std::fs::create_dir_all(source.path().join("src")).unwrap();
+        std::fs::write(
+            source.path().join("debian/upstream/metadata"),
+            "Repository: https://github.com/example/blah.git\n",
+        )
+        .unwrap();
+        std::fs::write(source.path().join("src/main.c"), "int main() {}\n").unwrap();
+
+        let fixture = tempfile::tempdir().unwrap();
+        record_fixture(source.path(), fixture.path()).await.unwrap();
+        assert!(fixture
+            .path()
+            .join("input/debian/upstream/metadata")
+            .exists());
+        assert!(!fixture.path().join("input/src/main.c").exists());
+
+        let expected = load_expected(fixture.path()).unwrap();
+        assert_eq!(
+            expected.get("Repository"),
+            Some(&serde_yaml::from_str::<Value>(
+                "value: https://github.com/example/blah.git\ncertainty: certain\norigin: debian/upstream/metadata\n"
+            )
+            .unwrap())
+        );
+        assert_eq!(run_fixture(fixture.path()).await.unwrap(), expected);
+
+        // Existing fixtures are not overwritten
+        assert!(record_fixture(source.path(), fixture.path()).await.is_err());
+    }
+}
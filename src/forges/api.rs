@@ -0,0 +1,288 @@
+//! Access to the JSON APIs of forges.
+//!
+//! [`ApiClient`] sends requests through [`crate::http::send`], so that they are authenticated
+//! with the [`crate::http::credentials::Credentials`] for the host, cached and retried when
+//! rate limited, and follows the `Link` headers that GitHub, GitLab and Gitea use for
+//! pagination.
+use crate::{http, HTTPJSONError};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, LINK};
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use url::Url;
+
+/// Timeout for API requests, unless configured otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum number of pages retrieved by [`ApiClient::get_paginated`].
+const MAX_PAGES: usize = 10;
+
+/// Client for the JSON API of a forge.
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: Url,
+    headers: HeaderMap,
+    timeout: Duration,
+}
+
+impl ApiClient {
+    /// A client for the API at `base_url`; paths are resolved relative to it.
+    pub fn new(mut base_url: Url) -> Self {
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        Self {
+            base_url,
+            headers,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// The API of github.com.
+    pub fn github() -> Self {
+        Self::new("https://api.github.com/".parse().unwrap()).github_headers()
+    }
+
+    /// The API of a GitHub Enterprise instance.
+    pub fn github_enterprise(host: &str) -> Result<Self, url::ParseError> {
+        Ok(Self::new(format!("https://{}/api/v3/", host).parse()?).github_headers())
+    }
+
+    fn github_headers(self) -> Self {
+        self.header(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        )
+        .header(
+            HeaderName::from_static("x-github-api-version"),
+            HeaderValue::from_static("2022-11-28"),
+        )
+    }
+
+    /// The API of a GitLab instance.
+    pub fn gitlab(host: &str) -> Result<Self, url::ParseError> {
+        Ok(Self::new(format!("https://{}/api/v4/", host).parse()?))
+    }
+
+    /// The API of a Gitea or Forgejo instance.
+    pub fn gitea(host: &str) -> Result<Self, url::ParseError> {
+        Ok(Self::new(format!("https://{}/api/v1/", host).parse()?))
+    }
+
+    /// The REST API of Launchpad.
+    pub fn launchpad() -> Self {
+        Self::new("https://api.launchpad.net/devel/".parse().unwrap())
+    }
+
+    /// The REST API of SourceForge.
+    pub fn sourceforge() -> Self {
+        Self::new("https://sourceforge.net/rest/".parse().unwrap())
+    }
+
+    /// Send a header with every request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Set the timeout for each request, including retrieving the response.
+    ///
+    /// Requests that are retried because they were rate limited get the full timeout for
+    /// every attempt.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The base URL that paths are resolved relative to.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// The URL for a path, relative to the base URL.
+    ///
+    /// Path elements with slashes in them, such as GitLab project paths, have to be
+    /// percent-encoded by the caller.
+    pub fn url(&self, path: &str) -> Result<Url, url::ParseError> {
+        self.base_url.join(path.trim_start_matches('/'))
+    }
+
+    async fn fetch(
+        &self,
+        url: &Url,
+        body: Option<&serde_json::Value>,
+    ) -> Result<reqwest::Response, HTTPJSONError> {
+        if !http::settings().can_fetch() {
+            return Err(HTTPJSONError::Offline(url.clone()));
+        }
+
+        let client = http::build_client()
+            .default_headers(self.headers.clone())
+            .build()
+            .map_err(HTTPJSONError::HTTPError)?;
+
+        let request = match body {
+            Some(body) => client.post(url.clone()).json(body),
+            None => client.get(url.clone()),
+        };
+
+        // The timeout applies to each attempt, so that waiting for rate limits to reset is
+        // governed by the retry budget alone
+        let response = http::send(request.timeout(self.timeout))
+            .await
+            .map_err(|e| {
+                if e.is_timeout() {
+                    HTTPJSONError::Timeout(self.timeout)
+                } else {
+                    HTTPJSONError::HTTPError(e)
+                }
+            })?;
+
+        if response.status() == reqwest::StatusCode::GATEWAY_TIMEOUT
+            && !http::settings().network.allows_network()
+        {
+            // Not in the cache
+            return Err(HTTPJSONError::Offline(response.url().clone()));
+        }
+
+        if !response.status().is_success() {
+            return Err(HTTPJSONError::Error {
+                url: response.url().clone(),
+                status: response.status().as_u16(),
+                response,
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Retrieve and decode the JSON document at a URL.
+    pub async fn get_url<T: DeserializeOwned>(&self, url: &Url) -> Result<T, HTTPJSONError> {
+        self.fetch(url, None)
+            .await?
+            .json()
+            .await
+            .map_err(HTTPJSONError::HTTPError)
+    }
+
+    /// Retrieve and decode the JSON document at a path, relative to the base URL.
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, HTTPJSONError> {
+        let url = self
+            .url(path)
+            .map_err(|_| HTTPJSONError::InvalidPath(path.to_string()))?;
+        self.get_url(&url).await
+    }
+
+    /// Post a JSON document to a path, relative to the base URL, and decode the response.
+    pub async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<T, HTTPJSONError> {
+        let url = self
+            .url(path)
+            .map_err(|_| HTTPJSONError::InvalidPath(path.to_string()))?;
+        self.fetch(&url, Some(body))
+            .await?
+            .json()
+            .await
+            .map_err(HTTPJSONError::HTTPError)
+    }
+
+    /// Retrieve all items of a paginated list, following the `Link` headers.
+    ///
+    /// At most `limit` items are returned, and at most a handful of pages are retrieved.
+    pub async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<T>, HTTPJSONError> {
+        let mut url = Some(
+            self.url(path)
+                .map_err(|_| HTTPJSONError::InvalidPath(path.to_string()))?,
+        );
+        let mut items = Vec::new();
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let response = self.fetch(&page_url, None).await?;
+            url = next_link(response.headers());
+            let page: Vec<T> = response.json().await.map_err(HTTPJSONError::HTTPError)?;
+            items.extend(page);
+            if let Some(limit) = limit.filter(|limit| items.len() >= *limit) {
+                items.truncate(limit);
+                break;
+            }
+        }
+        Ok(items)
+    }
+}
+
+/// The URL of the next page, from a `Link` header as described in RFC 8288.
+fn next_link(headers: &HeaderMap) -> Option<Url> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .find_map(|link| {
+            let (target, params) = link.trim().split_once(';')?;
+            let is_next = params.split(';').any(|p| {
+                p.trim()
+                    .strip_prefix("rel=")
+                    .is_some_and(|rel| rel.trim_matches('"').split(' ').any(|r| r == "next"))
+            });
+            if !is_next {
+                return None;
+            }
+            target
+                .trim()
+                .strip_prefix('<')?
+                .strip_suffix('>')?
+                .parse()
+                .ok()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let client = ApiClient::gitlab("salsa.debian.org").unwrap();
+        assert_eq!(
+            client.url("projects/jelmer%2Fdulwich").unwrap().as_str(),
+            "https://salsa.debian.org/api/v4/projects/jelmer%2Fdulwich"
+        );
+        let client = ApiClient::new("https://example.com/api".parse().unwrap());
+        assert_eq!(
+            client.url("/repos/foo").unwrap().as_str(),
+            "https://example.com/api/repos/foo"
+        );
+    }
+
+    #[test]
+    fn test_next_link() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(next_link(&headers), None);
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                "<https://api.github.com/repositories/1/tags?page=2>; rel=\"next\", \
+                 <https://api.github.com/repositories/1/tags?page=5>; rel=\"last\"",
+            ),
+        );
+        assert_eq!(
+            next_link(&headers).unwrap().as_str(),
+            "https://api.github.com/repositories/1/tags?page=2"
+        );
+        headers.insert(
+            LINK,
+            HeaderValue::from_static("<https://gitlab.com/api/v4/x?page=1>; rel=\"first\""),
+        );
+        assert_eq!(next_link(&headers), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! Issue trackers that accept bug reports by email.
+//!
+//! For these trackers the Bug-Submit field is a mailto: address rather than a web form,
+//! e.g. the Debian bug tracking system, GNU debbugs and rt.cpan.org.
+use crate::Forge;
+use url::Url;
+
+/// A debbugs instance, such as the Debian bug tracking system.
+pub struct Debbugs {
+    /// Host of the web interface
+    pub web_host: &'static str,
+
+    /// Domain that bug reports are mailed to
+    pub email_domain: &'static str,
+
+    /// Path of the script that lists the bugs in a package
+    pub pkgreport_path: &'static str,
+
+    /// Mailing lists that are connected to the tracker, as (list, package) pairs
+    pub lists: &'static [(&'static str, &'static str)],
+}
+
+/// The Debian bug tracking system.
+pub const DEBIAN: Debbugs = Debbugs {
+    web_host: "bugs.debian.org",
+    email_domain: "bugs.debian.org",
+    pkgreport_path: "/cgi-bin/pkgreport.cgi",
+    lists: &[],
+};
+
+/// The GNU bug tracker, which receives the reports sent to many bug-* lists at gnu.org.
+pub const GNU: Debbugs = Debbugs {
+    web_host: "debbugs.gnu.org",
+    email_domain: "debbugs.gnu.org",
+    pkgreport_path: "/cgi/pkgreport.cgi",
+    lists: &[
+        ("bug-automake@gnu.org", "automake"),
+        ("bug-coreutils@gnu.org", "coreutils"),
+        ("bug-diffutils@gnu.org", "diffutils"),
+        ("bug-gnu-emacs@gnu.org", "emacs"),
+        ("bug-grep@gnu.org", "grep"),
+        ("bug-guile@gnu.org", "guile"),
+        ("bug-guix@gnu.org", "guix"),
+        ("bug-gzip@gnu.org", "gzip"),
+        ("bug-libtool@gnu.org", "libtool"),
+        ("bug-parted@gnu.org", "parted"),
+        ("bug-sed@gnu.org", "sed"),
+    ],
+};
+
+impl Debbugs {
+    fn submit_address(&self) -> String {
+        format!("submit@{}", self.email_domain)
+    }
+
+    /// Find the package a bug database URL or submission address is for.
+    fn package(&self, url: &Url) -> Option<String> {
+        if url.scheme() == "mailto" {
+            let address = url.path().to_lowercase();
+            return self
+                .lists
+                .iter()
+                .find(|(list, _)| *list == address)
+                .map(|(_, package)| package.to_string());
+        }
+        if url.host_str()? != self.web_host {
+            return None;
+        }
+        let segments = url.path_segments()?.collect::<Vec<_>>();
+        match segments.as_slice() {
+            // e.g. /cgi-bin/pkgreport.cgi?src=dpkg or /cgi/pkgreport.cgi?package=emacs
+            [_, "pkgreport.cgi"] => url
+                .query_pairs()
+                .find(|(k, _)| k == "package" || k == "src" || k == "pkg")
+                .map(|(_, v)| v.to_string()),
+            // e.g. /dpkg or /src:dpkg, but not /123456 which is a single bug
+            [package] if !package.is_empty() && !package.bytes().all(|b| b.is_ascii_digit()) => {
+                Some(package.strip_prefix("src:").unwrap_or(package).to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a mailto: URL is an address that this tracker accepts bug reports on.
+    pub fn accepts(&self, url: &Url) -> bool {
+        if url.scheme() != "mailto" {
+            return false;
+        }
+        let address = url.path().to_lowercase();
+        address == self.submit_address() || self.lists.iter().any(|(list, _)| *list == address)
+    }
+
+    fn bug_database_url(&self, package: &str) -> Option<Url> {
+        Url::parse(&format!(
+            "https://{}{}?package={}",
+            self.web_host, self.pkgreport_path, package
+        ))
+        .ok()
+    }
+}
+
+/// The host of a tracker URL, or the domain of a mailto: address.
+fn tracker_host(url: &Url) -> Option<String> {
+    if url.scheme() == "mailto" {
+        Some(url.path().rsplit_once('@')?.1.to_lowercase())
+    } else {
+        Some(url.host_str()?.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl Forge for Debbugs {
+    fn handles_url(&self, url: &Url) -> bool {
+        tracker_host(url).is_some_and(|host| host == self.web_host || host == self.email_domain)
+            || self.accepts(url)
+    }
+
+    fn name(&self) -> &'static str {
+        "debbugs"
+    }
+
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        false
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        self.bug_database_url(&self.package(url)?)
+    }
+
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        if self.accepts(url) {
+            return Some(url.clone());
+        }
+        let package = self.package(url)?;
+        let address = self
+            .lists
+            .iter()
+            .find(|(_, p)| *p == package)
+            .map_or_else(|| self.submit_address(), |(list, _)| list.to_string());
+        Url::parse(&format!("mailto:{}", address)).ok()
+    }
+}
+
+/// A Request Tracker instance that creates tickets from mail to bug-<queue>@<domain>.
+pub struct RequestTracker {
+    /// Host of the web interface
+    pub web_host: &'static str,
+
+    /// Domain that bug reports are mailed to
+    pub email_domain: &'static str,
+}
+
+/// The CPAN Request Tracker, which has a queue for every distribution.
+pub const CPAN_RT: RequestTracker = RequestTracker {
+    web_host: "rt.cpan.org",
+    email_domain: "rt.cpan.org",
+};
+
+impl RequestTracker {
+    /// Find the queue a bug database URL or submission address is for.
+    fn queue(&self, url: &Url) -> Option<String> {
+        if url.scheme() == "mailto" {
+            let (local, domain) = url.path().rsplit_once('@')?;
+            if !domain.eq_ignore_ascii_case(self.email_domain) {
+                return None;
+            }
+            return local.strip_prefix("bug-").map(|q| q.to_string());
+        }
+        if url.host_str()? != self.web_host {
+            return None;
+        }
+        // e.g. /Dist/Display.html?Name=Moose or /Public/Dist/Display.html?Queue=Moose
+        if !url.path().ends_with("/Dist/Display.html") {
+            return None;
+        }
+        url.query_pairs()
+            .find(|(k, _)| k == "Name" || k == "Queue")
+            .map(|(_, v)| v.to_string())
+    }
+
+    /// Whether a mailto: URL is an address that this tracker accepts bug reports on.
+    pub fn accepts(&self, url: &Url) -> bool {
+        url.scheme() == "mailto" && self.queue(url).is_some()
+    }
+}
+
+#[async_trait::async_trait]
+impl Forge for RequestTracker {
+    fn handles_url(&self, url: &Url) -> bool {
+        tracker_host(url).is_some_and(|host| host == self.web_host || host == self.email_domain)
+    }
+
+    fn name(&self) -> &'static str {
+        "rt"
+    }
+
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        false
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        Url::parse(&format!(
+            "https://{}/Dist/Display.html?Name={}",
+            self.web_host,
+            self.queue(url)?
+        ))
+        .ok()
+    }
+
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        Url::parse(&format!(
+            "mailto:bug-{}@{}",
+            self.queue(url)?,
+            self.email_domain
+        ))
+        .ok()
+    }
+}
+
+/// Find the email-based tracker that a bug database URL or submission address belongs to.
+pub fn find_email_tracker(url: &Url) -> Option<Box<dyn Forge>> {
+    if DEBIAN.handles_url(url) {
+        return Some(Box::new(DEBIAN));
+    }
+    if GNU.handles_url(url) {
+        return Some(Box::new(GNU));
+    }
+    if CPAN_RT.handles_url(url) {
+        return Some(Box::new(CPAN_RT));
+    }
+    None
+}
+
+/// Whether a mailto: URL is the bug submission address of a known email-based tracker.
+pub fn is_bug_submit_address(url: &Url) -> bool {
+    DEBIAN.accepts(url) || GNU.accepts(url) || CPAN_RT.accepts(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_debian() {
+        let forge = find_email_tracker(&url("https://bugs.debian.org/src:dpkg")).unwrap();
+        assert_eq!(forge.name(), "debbugs");
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(&url("https://bugs.debian.org/src:dpkg")),
+            Some(url("mailto:submit@bugs.debian.org"))
+        );
+        assert_eq!(
+            forge.bug_database_url_from_bug_submit_url(&url(
+                "https://bugs.debian.org/cgi-bin/pkgreport.cgi?src=dpkg"
+            )),
+            Some(url(
+                "https://bugs.debian.org/cgi-bin/pkgreport.cgi?package=dpkg"
+            ))
+        );
+        // A single bug, rather than the bug database
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(&url("https://bugs.debian.org/123456")),
+            None
+        );
+        // The package is not part of the address
+        assert_eq!(
+            forge.bug_database_url_from_bug_submit_url(&url("mailto:submit@bugs.debian.org")),
+            None
+        );
+        assert!(is_bug_submit_address(&url("mailto:submit@bugs.debian.org")));
+    }
+
+    #[test]
+    fn test_gnu() {
+        let submit = url("mailto:bug-gnu-emacs@gnu.org");
+        let forge = find_email_tracker(&submit).unwrap();
+        assert_eq!(
+            forge.bug_database_url_from_bug_submit_url(&submit),
+            Some(url(
+                "https://debbugs.gnu.org/cgi/pkgreport.cgi?package=emacs"
+            ))
+        );
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(&url(
+                "https://debbugs.gnu.org/cgi/pkgreport.cgi?package=emacs"
+            )),
+            Some(submit.clone())
+        );
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(&url(
+                "https://debbugs.gnu.org/cgi/pkgreport.cgi?package=gnus"
+            )),
+            Some(url("mailto:submit@debbugs.gnu.org"))
+        );
+        assert!(is_bug_submit_address(&submit));
+        // Plain mailing lists at gnu.org are not necessarily connected to debbugs
+        assert!(find_email_tracker(&url("mailto:bug-tar@gnu.org")).is_none());
+        assert!(!is_bug_submit_address(&url("mailto:bug-tar@gnu.org")));
+    }
+
+    #[test]
+    fn test_cpan_rt() {
+        let submit = url("mailto:bug-Moose@rt.cpan.org");
+        let forge = find_email_tracker(&submit).unwrap();
+        assert_eq!(forge.name(), "rt");
+        assert_eq!(
+            forge.bug_database_url_from_bug_submit_url(&submit),
+            Some(url("https://rt.cpan.org/Dist/Display.html?Name=Moose"))
+        );
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(&url(
+                "https://rt.cpan.org/Public/Dist/Display.html?Name=Moose"
+            )),
+            Some(submit.clone())
+        );
+        assert!(is_bug_submit_address(&submit));
+        assert!(!is_bug_submit_address(&url("mailto:jelmer@rt.cpan.org")));
+    }
+}
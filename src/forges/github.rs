@@ -0,0 +1,167 @@
+//! Metadata of GitHub repositories, retrieved with a single GraphQL query.
+//!
+//! The GraphQL API only accepts authenticated requests, so this is only used if there are
+//! [`crate::http::credentials::Credentials`] for github.com.
+use crate::forges::api::ApiClient;
+use crate::{HTTPJSONError, UpstreamDatum};
+use serde::Deserialize;
+
+const REPOSITORY_QUERY: &str = r#"query($owner: String!, $name: String!) {
+  repository(owner: $owner, name: $name) {
+    url
+    description
+    homepageUrl
+    isArchived
+    hasIssuesEnabled
+    licenseInfo { spdxId }
+    latestRelease { tagName }
+    fundingLinks { url }
+  }
+}"#;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<Data>,
+    #[serde(default)]
+    errors: Vec<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Data {
+    repository: Option<Repository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Error {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Repository {
+    url: String,
+    description: Option<String>,
+    homepage_url: Option<String>,
+    is_archived: bool,
+    has_issues_enabled: bool,
+    license_info: Option<LicenseInfo>,
+    latest_release: Option<Release>,
+    #[serde(default)]
+    funding_links: Vec<FundingLink>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseInfo {
+    spdx_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Release {
+    tag_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FundingLink {
+    url: String,
+}
+
+impl Repository {
+    fn into_data(self) -> Vec<UpstreamDatum> {
+        let mut ret = Vec::new();
+        if let Some(description) = self.description.filter(|d| !d.trim().is_empty()) {
+            ret.push(UpstreamDatum::Summary(description.trim().to_string()));
+        }
+        if let Some(homepage) = self.homepage_url.filter(|h| !h.is_empty()) {
+            ret.push(UpstreamDatum::Homepage(homepage));
+        }
+        // NOASSERTION is used for licenses that GitHub doesn't recognize
+        if let Some(spdx_id) = self
+            .license_info
+            .and_then(|l| l.spdx_id)
+            .filter(|id| id != "NOASSERTION")
+        {
+            ret.push(UpstreamDatum::License(spdx_id.into()));
+        }
+        if let Some(version) = self
+            .latest_release
+            .and_then(|r| crate::vcs::version_from_tag(&r.tag_name))
+        {
+            ret.push(UpstreamDatum::Version(version));
+        }
+        for link in self.funding_links {
+            ret.push(UpstreamDatum::Donation(link.url));
+        }
+        // Archived repositories don't accept new issues
+        if self.has_issues_enabled && !self.is_archived {
+            ret.push(UpstreamDatum::BugDatabase(format!("{}/issues", self.url)));
+        }
+        ret
+    }
+}
+
+/// Retrieve the metadata of a repository, or `None` if it does not exist.
+pub async fn repository_metadata(
+    owner: &str,
+    name: &str,
+) -> Result<Option<Vec<UpstreamDatum>>, HTTPJSONError> {
+    let body = serde_json::json!({
+        "query": REPOSITORY_QUERY,
+        "variables": {"owner": owner, "name": name},
+    });
+    let response: Response = ApiClient::github().post("graphql", &body).await?;
+    for error in &response.errors {
+        log::debug!("GraphQL error for {}/{}: {}", owner, name, error.message);
+    }
+    Ok(response
+        .data
+        .and_then(|d| d.repository)
+        .map(Repository::into_data))
+}
+
+/// Whether GraphQL queries can be made, i.e. whether there is a token for github.com.
+pub fn graphql_available() -> bool {
+    crate::http::credentials::Credentials::global()
+        .get("github.com")
+        .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let response: Response = serde_json::from_str(
+            r#"{"data": {"repository": {
+                "url": "https://github.com/jelmer/dulwich",
+                "description": "Pure-Python Git implementation\n",
+                "homepageUrl": "https://www.dulwich.io/",
+                "isArchived": false,
+                "hasIssuesEnabled": true,
+                "licenseInfo": {"spdxId": "NOASSERTION"},
+                "latestRelease": {"tagName": "dulwich-0.22.7"},
+                "fundingLinks": [{"url": "https://github.com/sponsors/jelmer"}]
+            }}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response.data.unwrap().repository.unwrap().into_data(),
+            vec![
+                UpstreamDatum::Summary("Pure-Python Git implementation".to_string()),
+                UpstreamDatum::Homepage("https://www.dulwich.io/".to_string()),
+                UpstreamDatum::Version("0.22.7".to_string()),
+                UpstreamDatum::Donation("https://github.com/sponsors/jelmer".to_string()),
+                UpstreamDatum::BugDatabase("https://github.com/jelmer/dulwich/issues".to_string()),
+            ]
+        );
+
+        let response: Response = serde_json::from_str(
+            r#"{"data": {"repository": null}, "errors": [{"type": "NOT_FOUND", "message": "Could not resolve to a Repository"}]}"#,
+        )
+        .unwrap();
+        assert!(response.data.unwrap().repository.is_none());
+        assert_eq!(response.errors.len(), 1);
+    }
+}
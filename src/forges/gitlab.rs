@@ -0,0 +1,133 @@
+//! Metadata of GitLab projects, retrieved from the REST API.
+use crate::forges::api::ApiClient;
+use crate::{HTTPJSONError, UpstreamDatum};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    web_url: String,
+    description: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    wiki_enabled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+impl Project {
+    fn into_data(self) -> Vec<UpstreamDatum> {
+        let mut ret = Vec::new();
+        if let Some(description) = self.description.filter(|d| !d.trim().is_empty()) {
+            ret.push(UpstreamDatum::Summary(description.trim().to_string()));
+        }
+        if !self.topics.is_empty() {
+            ret.push(UpstreamDatum::Keywords(self.topics));
+        }
+        if self.wiki_enabled {
+            ret.push(UpstreamDatum::Wiki(format!(
+                "{}/-/wikis/home",
+                self.web_url
+            )));
+        }
+        ret.push(UpstreamDatum::RepositoryBrowse(self.web_url));
+        ret
+    }
+}
+
+/// The path of the project that a URL on a GitLab instance belongs to, e.g. "group/project".
+///
+/// Projects can be in nested groups, so everything up to the "-" that separates the project
+/// path from the page in the project is included.
+pub fn project_path(url: &Url) -> Option<String> {
+    let segments = url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .take_while(|s| *s != "-")
+        .collect::<Vec<_>>();
+    if segments.len() < 2 {
+        return None;
+    }
+    let path = segments.join("/");
+    Some(path.strip_suffix(".git").unwrap_or(&path).to_string())
+}
+
+/// Retrieve the metadata of the project at `url`, or `None` if it does not exist.
+pub async fn project_metadata(url: &Url) -> Result<Option<Vec<UpstreamDatum>>, HTTPJSONError> {
+    let (Some(host), Some(path)) = (url.host_str(), project_path(url)) else {
+        return Ok(None);
+    };
+    let client =
+        ApiClient::gitlab(host).map_err(|_| HTTPJSONError::InvalidPath(url.to_string()))?;
+    let id = utf8_percent_encode(&path, NON_ALPHANUMERIC).to_string();
+    let project: Project = match client.get(&format!("projects/{}", id)).await {
+        Ok(project) => project,
+        Err(HTTPJSONError::Error { status: 404, .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut ret = project.into_data();
+
+    // Releases are listed most recent first
+    match client
+        .get::<Vec<Release>>(&format!("projects/{}/releases?per_page=1", id))
+        .await
+    {
+        Ok(releases) => ret.extend(
+            releases
+                .into_iter()
+                .find_map(|r| crate::vcs::version_from_tag(&r.tag_name))
+                .map(UpstreamDatum::Version),
+        ),
+        Err(e) => log::debug!("Unable to retrieve releases of {}: {}", path, e),
+    }
+    Ok(Some(ret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_path() {
+        let path = |url: &str| project_path(&url.parse().unwrap());
+        assert_eq!(
+            path("https://salsa.debian.org/jelmer/dulwich.git"),
+            Some("jelmer/dulwich".to_string())
+        );
+        assert_eq!(
+            path("https://gitlab.com/group/subgroup/project/-/issues"),
+            Some("group/subgroup/project".to_string())
+        );
+        assert_eq!(path("https://gitlab.com/group"), None);
+    }
+
+    #[test]
+    fn test_parse_project() {
+        let project: Project = serde_json::from_str(
+            r#"{
+                "id": 1,
+                "web_url": "https://gitlab.com/example/project",
+                "description": "An example project ",
+                "topics": ["rust", "metadata"],
+                "readme_url": "https://gitlab.com/example/project/-/blob/main/README.md",
+                "avatar_url": null,
+                "wiki_enabled": true
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            project.into_data(),
+            vec![
+                UpstreamDatum::Summary("An example project".to_string()),
+                UpstreamDatum::Keywords(vec!["rust".to_string(), "metadata".to_string()]),
+                UpstreamDatum::Wiki("https://gitlab.com/example/project/-/wikis/home".to_string()),
+                UpstreamDatum::RepositoryBrowse("https://gitlab.com/example/project".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,326 @@
+//! Metadata of Launchpad projects, retrieved from the REST API.
+#[cfg(feature = "net")]
+use crate::forges::api::ApiClient;
+#[cfg(feature = "net")]
+use crate::{HTTPJSONError, UpstreamDatum};
+#[cfg(feature = "net")]
+use serde::Deserialize;
+use url::Url;
+
+/// The fields that Launchpad can provide for a project.
+pub const FIELDS: &[&str] = &[
+    "Name",
+    "Summary",
+    "Homepage",
+    "Wiki",
+    "Download",
+    "SourceForge-Project",
+    "Repository",
+    "Repository-Browse",
+    "Bug-Database",
+];
+
+/// Path elements on launchpad.net that are not project names.
+const RESERVED_NAMES: &[&str] = &["bugs", "projects", "people", "ubuntu", "debian"];
+
+/// The name of the Launchpad project that a URL belongs to.
+///
+/// This handles the project pages on all Launchpad sites (e.g.
+/// "https://bugs.launchpad.net/dulwich") as well as Git repositories and Bazaar branches,
+/// which can be owned by a person ("https://code.launchpad.net/~jelmer/dulwich/trunk").
+pub fn project_from_url(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if host != "launchpad.net" && !host.ends_with(".launchpad.net") {
+        return None;
+    }
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty()).peekable();
+    // Branches and personal repositories are prefixed by the owner
+    if segments.peek()?.starts_with('~') {
+        segments.next();
+    }
+    let name = segments.next()?;
+    let name = name.strip_suffix(".git").unwrap_or(name);
+    if name.starts_with('+') || name.starts_with('~') || RESERVED_NAMES.contains(&name) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// The URL of the bug tracker of a project.
+pub fn bug_database_url(project: &str) -> Url {
+    let mut url: Url = "https://bugs.launchpad.net/".parse().unwrap();
+    url.path_segments_mut().unwrap().push(project);
+    url
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+pub(crate) struct Project {
+    pub(crate) name: String,
+    display_name: Option<String>,
+    summary: Option<String>,
+    homepage_url: Option<String>,
+    wiki_url: Option<String>,
+    download_url: Option<String>,
+    sourceforge_project: Option<String>,
+    #[serde(default)]
+    pub(crate) official_bugs: bool,
+    #[serde(default)]
+    official_codehosting: bool,
+    vcs: Option<String>,
+    development_focus_link: Option<Url>,
+}
+
+#[cfg(feature = "net")]
+impl Project {
+    fn into_data(self) -> Vec<UpstreamDatum> {
+        let nonempty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+        let mut ret = Vec::new();
+        if let Some(display_name) = nonempty(self.display_name) {
+            ret.push(UpstreamDatum::Name(display_name));
+        }
+        if let Some(summary) = nonempty(self.summary) {
+            ret.push(UpstreamDatum::Summary(summary.trim().to_string()));
+        }
+        if let Some(homepage) = nonempty(self.homepage_url) {
+            ret.push(UpstreamDatum::Homepage(homepage));
+        }
+        if let Some(wiki) = nonempty(self.wiki_url) {
+            ret.push(UpstreamDatum::Wiki(wiki));
+        }
+        if let Some(download) = nonempty(self.download_url) {
+            ret.push(UpstreamDatum::Download(download));
+        }
+        if let Some(sourceforge_project) = nonempty(self.sourceforge_project) {
+            ret.push(UpstreamDatum::SourceForgeProject(sourceforge_project));
+        }
+        if self.official_bugs {
+            ret.push(UpstreamDatum::BugDatabase(
+                bug_database_url(&self.name).to_string(),
+            ));
+        }
+        ret
+    }
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct GitRepository {
+    git_https_url: String,
+    web_link: String,
+    code_import_link: Option<Url>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct Series {
+    branch_link: Option<Url>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct Branch {
+    bzr_identity: String,
+    web_link: String,
+    code_import_link: Option<Url>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct CodeImport {
+    url: Option<String>,
+}
+
+/// Retrieve a project, or `None` if it does not exist.
+#[cfg(feature = "net")]
+pub(crate) async fn project(name: &str) -> Result<Option<Project>, HTTPJSONError> {
+    let client = ApiClient::launchpad();
+    let mut url = client.base_url().clone();
+    url.path_segments_mut().unwrap().pop_if_empty().push(name);
+    match client.get_url(&url).await {
+        Ok(project) => Ok(Some(project)),
+        Err(HTTPJSONError::Error { status: 404, .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// The repository of a project, for imports the location it was imported from.
+#[cfg(feature = "net")]
+async fn repository_data(project: &Project) -> Result<Vec<UpstreamDatum>, HTTPJSONError> {
+    let client = ApiClient::launchpad();
+    let (code_import_link, repository, browse) = match project.vcs.as_deref() {
+        Some("Git") => {
+            let mut url = client.url("+git").unwrap();
+            url.query_pairs_mut()
+                .append_pair("ws.op", "getByPath")
+                .append_pair("path", &project.name);
+            // The default repository of the project, if there is one
+            let Some(repository) = client.get_url::<Option<GitRepository>>(&url).await? else {
+                return Ok(vec![]);
+            };
+            (
+                repository.code_import_link,
+                repository.git_https_url,
+                repository.web_link,
+            )
+        }
+        Some("Bazaar") => {
+            let Some(series_link) = project.development_focus_link.as_ref() else {
+                return Ok(vec![]);
+            };
+            let series: Series = client.get_url(series_link).await?;
+            let Some(branch_link) = series.branch_link else {
+                return Ok(vec![]);
+            };
+            let branch: Branch = client.get_url(&branch_link).await?;
+            (
+                branch.code_import_link,
+                branch.bzr_identity,
+                branch.web_link,
+            )
+        }
+        Some(vcs) => {
+            log::debug!("Unknown version control system on Launchpad: {}", vcs);
+            return Ok(vec![]);
+        }
+        None => return Ok(vec![]),
+    };
+
+    if let Some(code_import_link) = code_import_link {
+        let code_import: CodeImport = client.get_url(&code_import_link).await?;
+        return Ok(code_import
+            .url
+            .map(UpstreamDatum::Repository)
+            .into_iter()
+            .collect());
+    }
+    if project.official_codehosting {
+        return Ok(vec![
+            UpstreamDatum::Repository(repository),
+            UpstreamDatum::RepositoryBrowse(browse),
+        ]);
+    }
+    Ok(vec![])
+}
+
+/// Retrieve the metadata of a project, or `None` if it does not exist.
+#[cfg(feature = "net")]
+pub async fn project_metadata(name: &str) -> Result<Option<Vec<UpstreamDatum>>, HTTPJSONError> {
+    let Some(project) = project(name).await? else {
+        return Ok(None);
+    };
+    let repository = match repository_data(&project).await {
+        Ok(repository) => repository,
+        Err(e) => {
+            log::debug!("Unable to find the repository of {}: {}", name, e);
+            vec![]
+        }
+    };
+    let mut ret = project.into_data();
+    ret.extend(repository);
+    Ok(Some(ret))
+}
+
+/// Find the Launchpad project that a source package in a distribution is packaged from.
+///
+/// Returns `None` if the package is not linked to a project.
+#[cfg(feature = "net")]
+pub async fn project_from_source_package(
+    distribution: &str,
+    suite: &str,
+    package: &str,
+) -> Result<Option<String>, HTTPJSONError> {
+    #[derive(Deserialize)]
+    struct SourcePackage {
+        productseries_link: Option<Url>,
+    }
+    #[derive(Deserialize)]
+    struct ProductSeries {
+        project_link: Url,
+    }
+
+    let client = ApiClient::launchpad();
+    let mut url = client.base_url().clone();
+    url.path_segments_mut().unwrap().pop_if_empty().extend([
+        distribution,
+        suite,
+        "+source",
+        package,
+    ]);
+    let source_package: SourcePackage = match client.get_url(&url).await {
+        Ok(source_package) => source_package,
+        Err(HTTPJSONError::Error { status: 404, .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let Some(productseries_link) = source_package.productseries_link else {
+        return Ok(None);
+    };
+    let series: ProductSeries = client.get_url(&productseries_link).await?;
+    Ok(series
+        .project_link
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .map(|s| s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_from_url() {
+        let project = |url: &str| project_from_url(&url.parse().unwrap());
+        assert_eq!(
+            project("https://launchpad.net/dulwich"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(
+            project("https://bugs.launchpad.net/dulwich/+bugs"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(
+            project("https://git.launchpad.net/dulwich"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(
+            project("https://git.launchpad.net/~jelmer/dulwich/+git/dulwich"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(
+            project("bzr+ssh://bazaar.launchpad.net/~jelmer/dulwich/trunk"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(project("https://bugs.launchpad.net/bugs/123"), None);
+        assert_eq!(project("https://launchpad.net/~jelmer"), None);
+        assert_eq!(project("https://github.com/jelmer/dulwich"), None);
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_parse_project() {
+        let project: Project = serde_json::from_str(
+            r#"{
+                "name": "dulwich",
+                "display_name": "Dulwich",
+                "summary": "Pure-Python Git implementation\n",
+                "homepage_url": "https://www.dulwich.io/",
+                "wiki_url": null,
+                "download_url": "",
+                "sourceforge_project": null,
+                "official_bugs": true,
+                "official_codehosting": false,
+                "vcs": "Git"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            project.into_data(),
+            vec![
+                UpstreamDatum::Name("Dulwich".to_string()),
+                UpstreamDatum::Summary("Pure-Python Git implementation".to_string()),
+                UpstreamDatum::Homepage("https://www.dulwich.io/".to_string()),
+                UpstreamDatum::BugDatabase("https://bugs.launchpad.net/dulwich".to_string()),
+            ]
+        );
+    }
+}
@@ -1 +1,11 @@
+#[cfg(feature = "net")]
+pub mod api;
+pub mod email;
+#[cfg(feature = "net")]
+pub mod github;
+#[cfg(feature = "net")]
+pub mod gitlab;
+pub mod launchpad;
+pub mod registry;
 pub mod sourceforge;
+pub mod sourcehut;
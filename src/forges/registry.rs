@@ -0,0 +1,447 @@
+//! Registration of the forges that URLs are matched against.
+//!
+//! The module-level helpers such as [`crate::find_forge`] and
+//! [`crate::guess_bug_database_url_from_repo_url`] use the global registry, which has the
+//! default set of forges unless another registry was installed with
+//! [`ForgeRegistry::install`]:
+//!
+//! ```
+//! let mut registry = upstream_ontologist::ForgeRegistry::new();
+//! // Self-hosted GitLab and Forgejo instances, which are not recognized without probing them
+//! registry.add_gitlab_host("git.example.com");
+//! registry.add_gitea_host("forge.example.com");
+//! registry.install().ok();
+//! ```
+use crate::{CanonicalizeError, Certainty, Forge, GitLab, Gitea, UpstreamDatumWithMetadata};
+use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
+use url::Url;
+
+/// The set of forges that URLs are matched against.
+#[derive(Clone)]
+pub struct ForgeRegistry {
+    forges: Vec<Arc<dyn Forge>>,
+    gitlab_hosts: HashSet<String>,
+    gitea_hosts: HashSet<String>,
+    probe_hosts: bool,
+}
+
+static GLOBAL: OnceLock<ForgeRegistry> = OnceLock::new();
+
+impl ForgeRegistry {
+    /// A registry with the default forges.
+    pub fn new() -> Self {
+        Self {
+            forges: vec![
+                Arc::new(crate::forges::email::DEBIAN),
+                Arc::new(crate::forges::email::GNU),
+                Arc::new(crate::forges::email::CPAN_RT),
+                Arc::new(crate::SourceForge::new()),
+                Arc::new(crate::Launchpad::new()),
+                Arc::new(crate::GitHub::new()),
+                Arc::new(crate::Gitee::new()),
+                Arc::new(crate::Bitbucket::new()),
+                Arc::new(crate::Osdn::new()),
+                Arc::new(crate::SourceHut::new()),
+                Arc::new(GitLab::new()),
+                Arc::new(Gitea::new()),
+            ],
+            gitlab_hosts: HashSet::new(),
+            gitea_hosts: HashSet::new(),
+            probe_hosts: true,
+        }
+    }
+
+    /// A registry without any forges.
+    pub fn empty() -> Self {
+        Self {
+            forges: vec![],
+            gitlab_hosts: HashSet::new(),
+            gitea_hosts: HashSet::new(),
+            probe_hosts: false,
+        }
+    }
+
+    /// The registered forges, in the order they are matched against URLs.
+    pub fn forges(&self) -> impl Iterator<Item = &dyn Forge> {
+        self.forges.iter().map(|f| f.as_ref())
+    }
+
+    /// The registry used by the module-level helpers.
+    pub fn global() -> &'static ForgeRegistry {
+        GLOBAL.get_or_init(ForgeRegistry::new)
+    }
+
+    /// Use this registry for the module-level helpers.
+    ///
+    /// This has to happen before any of them are used. If a registry is already in use, it
+    /// is kept and this one is returned.
+    pub fn install(self) -> Result<(), ForgeRegistry> {
+        GLOBAL.set(self)
+    }
+
+    /// Register a forge.
+    ///
+    /// Registered forges take precedence over the ones that were registered before them. The
+    /// forge is found for the URLs for which [`Forge::handles_url`] returns true.
+    pub fn register(&mut self, forge: Box<dyn Forge>) {
+        self.forges.insert(0, Arc::from(forge));
+    }
+
+    /// Treat `hostname` as a GitLab instance, without probing it.
+    pub fn add_gitlab_host(&mut self, hostname: &str) {
+        self.gitlab_hosts.insert(hostname.to_lowercase());
+    }
+
+    /// Treat `hostname` as a Gitea or Forgejo instance, without probing it.
+    pub fn add_gitea_host(&mut self, hostname: &str) {
+        self.gitea_hosts.insert(hostname.to_lowercase());
+    }
+
+    /// Find the forge that hosts a URL.
+    ///
+    /// If `net_access` is allowed, unknown hosts are probed to see if they run GitLab or Gitea.
+    pub async fn find(&self, url: &Url, net_access: Option<bool>) -> Option<Box<dyn Forge>> {
+        if let Some(forge) = self.forges.iter().find(|f| f.handles_url(url)) {
+            return Some(Box::new(forge.clone()));
+        }
+        let host = url.host_str()?;
+        if self.gitlab_hosts.contains(host) {
+            return Some(Box::new(GitLab::new()));
+        }
+        if self.gitea_hosts.contains(host) {
+            return Some(Box::new(Gitea::new()));
+        }
+        #[cfg(feature = "net")]
+        if self.probe_hosts && net_access.unwrap_or(false) {
+            if crate::vcs::probe_gitlab_host(host).await {
+                return Some(Box::new(GitLab::new()));
+            }
+            if crate::vcs::probe_gitea_host(host).await {
+                return Some(Box::new(Gitea::new()));
+            }
+        }
+        #[cfg(not(feature = "net"))]
+        let _ = net_access;
+        None
+    }
+
+    pub async fn check_bug_database_canonical(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Result<Url, CanonicalizeError> {
+        if let Some(forge) = self.find(url, net_access).await {
+            forge
+                .bug_database_url_from_bug_submit_url(url)
+                .ok_or(CanonicalizeError::Unverifiable(
+                    url.clone(),
+                    "no bug database URL found".to_string(),
+                ))
+        } else {
+            Err(CanonicalizeError::Unverifiable(
+                url.clone(),
+                "unknown forge".to_string(),
+            ))
+        }
+    }
+
+    pub async fn check_bug_submit_url_canonical(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Result<Url, CanonicalizeError> {
+        if url.scheme() == "mailto" {
+            // Addresses of trackers that operate over email can't be probed, but are known
+            return if crate::forges::email::is_bug_submit_address(url) {
+                Ok(url.clone())
+            } else {
+                Err(CanonicalizeError::Unverifiable(
+                    url.clone(),
+                    "unknown bug submission address".to_string(),
+                ))
+            };
+        }
+        if let Some(forge) = self.find(url, net_access).await {
+            forge
+                .bug_submit_url_from_bug_database_url(url)
+                .ok_or(CanonicalizeError::Unverifiable(
+                    url.clone(),
+                    "no bug submit URL found".to_string(),
+                ))
+        } else {
+            Err(CanonicalizeError::Unverifiable(
+                url.clone(),
+                "unknown forge".to_string(),
+            ))
+        }
+    }
+
+    pub async fn bug_submit_url_from_bug_database_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .bug_submit_url_from_bug_database_url(url)
+    }
+
+    pub async fn bug_database_url_from_bug_submit_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .bug_database_url_from_bug_submit_url(url)
+    }
+
+    pub async fn guess_bug_database_url_from_repo_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .bug_database_url_from_repo_url(url)
+    }
+
+    pub async fn repo_url_from_merge_request_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .repo_url_from_merge_request_url(url)
+    }
+
+    pub async fn bug_database_from_issue_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .bug_database_from_issue_url(url)
+    }
+
+    /// Find the URL of the discussion forum for a repository.
+    ///
+    /// This will make network connections.
+    pub async fn guess_discussions_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        self.find(url, Some(true))
+            .await?
+            .discussions_url_from_repo_url(url)
+            .await
+    }
+
+    pub async fn guess_mirror_source_from_repo_url(&self, url: &Url) -> Option<Url> {
+        self.find(url, Some(true))
+            .await?
+            .mirror_source_from_repo_url(url)
+            .await
+    }
+
+    /// Find the name of the organization that owns a repository.
+    ///
+    /// This will make network connections.
+    pub async fn guess_organization_from_repo_url(&self, url: &Url) -> Option<String> {
+        self.find(url, Some(true))
+            .await?
+            .organization_from_repo_url(url)
+            .await
+    }
+
+    pub async fn guess_mailing_list_url_from_repo_url(
+        &self,
+        url: &Url,
+        net_access: Option<bool>,
+    ) -> Option<Url> {
+        self.find(url, net_access)
+            .await?
+            .mailing_list_url_from_repo_url(url)
+    }
+}
+
+impl Default for ForgeRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for ForgeRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ForgeRegistry")
+            .field(
+                "forges",
+                &self.forges.iter().map(|f| f.name()).collect::<Vec<_>>(),
+            )
+            .field("gitlab_hosts", &self.gitlab_hosts)
+            .field("gitea_hosts", &self.gitea_hosts)
+            .field("probe_hosts", &self.probe_hosts)
+            .finish()
+    }
+}
+
+/// Forges in a registry are shared between the lookups that find them.
+#[async_trait::async_trait]
+impl Forge for Arc<dyn Forge> {
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        (**self).repository_browse_can_be_homepage()
+    }
+
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn handles_url(&self, url: &Url) -> bool {
+        (**self).handles_url(url)
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        (**self).bug_database_url_from_bug_submit_url(url)
+    }
+
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        (**self).bug_submit_url_from_bug_database_url(url)
+    }
+
+    async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
+        (**self).check_bug_database_canonical(url).await
+    }
+
+    async fn check_bug_submit_url_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
+        (**self).check_bug_submit_url_canonical(url).await
+    }
+
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        (**self).bug_database_from_issue_url(url)
+    }
+
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        (**self).bug_database_url_from_repo_url(url)
+    }
+
+    fn repo_url_from_merge_request_url(&self, url: &Url) -> Option<Url> {
+        (**self).repo_url_from_merge_request_url(url)
+    }
+
+    async fn discussions_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        (**self).discussions_url_from_repo_url(url).await
+    }
+
+    async fn mirror_source_from_repo_url(&self, url: &Url) -> Option<Url> {
+        (**self).mirror_source_from_repo_url(url).await
+    }
+
+    async fn organization_from_repo_url(&self, url: &Url) -> Option<String> {
+        (**self).organization_from_repo_url(url).await
+    }
+
+    fn pages_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        (**self).pages_url_from_repo_url(url)
+    }
+
+    fn mailing_list_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        (**self).mailing_list_url_from_repo_url(url)
+    }
+
+    fn project_from_repo_url(&self, url: &Url) -> Option<String> {
+        (**self).project_from_repo_url(url)
+    }
+
+    async fn extend_metadata(
+        &self,
+        metadata: &mut Vec<UpstreamDatumWithMetadata>,
+        project: &str,
+        max_certainty: Option<Certainty>,
+    ) {
+        (**self)
+            .extend_metadata(metadata, project, max_certainty)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Forgejo;
+
+    impl Forge for Forgejo {
+        fn handles_url(&self, url: &Url) -> bool {
+            url.host_str() == Some("git.example.com")
+        }
+
+        fn name(&self) -> &'static str {
+            "Forgejo"
+        }
+
+        fn repository_browse_can_be_homepage(&self) -> bool {
+            true
+        }
+
+        fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+            url.join("issues").ok()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find() {
+        let registry = ForgeRegistry::new();
+        let url: Url = "https://github.com/jelmer/dulwich".parse().unwrap();
+        assert_eq!(
+            registry.find(&url, Some(false)).await.unwrap().name(),
+            "GitHub"
+        );
+        let url: Url = "mailto:submit@bugs.debian.org".parse().unwrap();
+        assert_eq!(
+            registry.find(&url, Some(false)).await.unwrap().name(),
+            "debbugs"
+        );
+        let url: Url = "https://codeberg.org/forgejo/forgejo".parse().unwrap();
+        assert_eq!(
+            registry.find(&url, Some(false)).await.unwrap().name(),
+            "Gitea"
+        );
+        let url: Url = "https://git.example.com/foo/".parse().unwrap();
+        assert!(registry.find(&url, Some(false)).await.is_none());
+        assert!(ForgeRegistry::empty()
+            .find(
+                &"https://github.com/jelmer/dulwich".parse().unwrap(),
+                Some(false)
+            )
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_register() {
+        let url: Url = "https://git.example.com/foo/".parse().unwrap();
+        let mut registry = ForgeRegistry::new();
+        registry.register(Box::new(Forgejo));
+        assert_eq!(
+            registry
+                .guess_bug_database_url_from_repo_url(&url, Some(false))
+                .await,
+            Some("https://git.example.com/foo/issues".parse().unwrap())
+        );
+
+        let mut registry = ForgeRegistry::empty();
+        registry.add_gitlab_host("git.example.com");
+        assert_eq!(
+            registry.find(&url, Some(false)).await.unwrap().name(),
+            "GitLab"
+        );
+
+        // Host names in URLs are lowercased when they are parsed
+        let mut registry = ForgeRegistry::empty();
+        registry.add_gitea_host("Git.Example.com");
+        assert_eq!(
+            registry.find(&url, Some(false)).await.unwrap().name(),
+            "Gitea"
+        );
+    }
+}
@@ -1,37 +1,108 @@
-use crate::check_bug_database_canonical;
-use crate::UpstreamDatum;
-use crate::{load_json_url, HTTPJSONError};
+#[cfg(feature = "net")]
+use crate::forges::api::ApiClient;
+#[cfg(feature = "net")]
+use crate::{check_bug_database_canonical, HTTPJSONError, UpstreamDatum};
 use lazy_regex::regex;
+#[cfg(feature = "net")]
 use log::{debug, error, warn};
-use reqwest::Url;
+#[cfg(feature = "net")]
+use url::Url;
 
+#[cfg(feature = "net")]
 async fn get_sf_metadata(project: &str) -> Option<serde_json::Value> {
-    let url = format!("https://sourceforge.net/rest/p/{}", project);
-    match load_json_url(&Url::parse(url.as_str()).unwrap(), None).await {
+    let path = format!("p/{}", project);
+    match ApiClient::sourceforge().get(&path).await {
         Ok(data) => Some(data),
         Err(HTTPJSONError::Error { status, .. }) if status == reqwest::StatusCode::NOT_FOUND => {
             None
         }
-        r => panic!("Unexpected result from {}: {:?}", url, r),
+        r => panic!("Unexpected result from {}: {:?}", path, r),
     }
 }
 
+/// Mount points of tools that are part of a project itself, rather than of a subproject.
+const STANDARD_MOUNT_POINTS: &[&str] = &[
+    "activity",
+    "blog",
+    "bugs",
+    "bzr",
+    "code",
+    "cvs",
+    "discussion",
+    "donate",
+    "feature-requests",
+    "files",
+    "git",
+    "hg",
+    "home",
+    "mailman",
+    "news",
+    "patches",
+    "reviews",
+    "screenshots",
+    "summary",
+    "support",
+    "support-requests",
+    "svn",
+    "tickets",
+    "web",
+    "wiki",
+];
+
+/// Check whether a tool belongs to a subproject, based on its mount point or label.
+#[cfg(feature = "net")]
+fn tool_matches_subproject(tool: &serde_json::Value, subproject: &str) -> bool {
+    let mount_point = tool.get("mount_point").and_then(|x| x.as_str());
+    let mount_label = tool.get("mount_label").and_then(|x| x.as_str());
+    mount_point.is_some_and(|m| {
+        m == subproject
+            || m.strip_prefix(subproject)
+                .is_some_and(|r| r.starts_with('-'))
+    }) || mount_label.is_some_and(|l| l.eq_ignore_ascii_case(subproject))
+}
+
+#[cfg(feature = "net")]
 async fn parse_sf_json(
     data: serde_json::Value,
     project: &str,
     subproject: Option<&str>,
 ) -> Vec<UpstreamDatum> {
     let mut results = Vec::new();
-    if let Some(name) = data.get("name").and_then(|name| name.as_str()) {
-        results.push(UpstreamDatum::Name(name.to_string()));
-    }
-    if let Some(external_homepage) = data.get("external_homepage").and_then(|url| url.as_str()) {
-        results.push(UpstreamDatum::Homepage(external_homepage.to_string()));
+
+    // A subproject named after the project is just the project
+    let subproject = subproject.filter(|s| *s != project);
+    let subproject_tools = match (subproject, data.get("tools").and_then(|t| t.as_array())) {
+        (Some(subproject), Some(tools)) => tools
+            .iter()
+            .filter(|tool| tool_matches_subproject(tool, subproject))
+            .collect::<Vec<_>>(),
+        _ => vec![],
+    };
+
+    if let Some(subproject) = subproject.filter(|_| !subproject_tools.is_empty()) {
+        // The project name, homepage and support URL describe the umbrella project
+        results.push(UpstreamDatum::SourceForgeSubproject(subproject.to_string()));
+        if let Some(url) = subproject_tools
+            .iter()
+            .find(|tool| tool.get("name").and_then(|n| n.as_str()) == Some("tickets"))
+            .and_then(|tool| tool.get("url"))
+            .and_then(|url| url.as_str())
+        {
+            results.push(UpstreamDatum::BugDatabase(url.to_string()));
+        }
+    } else {
+        if let Some(name) = data.get("name").and_then(|name| name.as_str()) {
+            results.push(UpstreamDatum::Name(name.to_string()));
+        }
+        if let Some(external_homepage) = data.get("external_homepage").and_then(|url| url.as_str())
+        {
+            results.push(UpstreamDatum::Homepage(external_homepage.to_string()));
+        }
     }
     if let Some(preferred_support_url) = data
         .get("preferred_support_url")
         .and_then(|url| url.as_str())
-        .filter(|x| !x.is_empty())
+        .filter(|x| !x.is_empty() && subproject_tools.is_empty())
     {
         let preferred_support_url =
             Url::parse(preferred_support_url).expect("preferred_support_url is not a valid URL");
@@ -82,7 +153,9 @@ async fn parse_sf_json(
     if vcs_tools.len() > 1 && subproject.is_some() {
         let new_vcs_tools = vcs_tools
             .iter()
-            .filter(|tool| tool.1 == subproject)
+            .filter(|tool| {
+                tool.1 == subproject || tool_matches_subproject(&tool.2, subproject.unwrap())
+            })
             .cloned()
             .collect::<Vec<_>>();
         if !new_vcs_tools.is_empty() {
@@ -145,11 +218,121 @@ async fn parse_sf_json(
     results
 }
 
+/// Fields of the project as a whole, from the tools it has enabled and its screenshots.
+#[cfg(feature = "net")]
+fn parse_sf_project_extras(data: &serde_json::Value) -> Vec<UpstreamDatum> {
+    let mut results = Vec::new();
+    let tool_url = |name: &str| {
+        data.get("tools")
+            .and_then(|t| t.as_array())?
+            .iter()
+            .find(|tool| tool.get("name").and_then(|n| n.as_str()) == Some(name))
+            .and_then(|tool| tool.get("url"))
+            .and_then(|url| url.as_str())
+            .map(|url| url.to_string())
+    };
+    if let Some(url) = tool_url("wiki") {
+        results.push(UpstreamDatum::Wiki(url));
+    }
+    if let Some(url) = tool_url("mailman") {
+        results.push(UpstreamDatum::MailingList(url));
+    }
+    let screenshots = data
+        .get("screenshots")
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get("url").and_then(|url| url.as_str()))
+        .map(|url| url.to_string())
+        .collect::<Vec<_>>();
+    if !screenshots.is_empty() {
+        results.push(UpstreamDatum::Screenshots(screenshots));
+    }
+    results
+}
+
+/// The download links of the files in a SourceForge files RSS feed, newest first.
+#[cfg(feature = "net")]
+fn parse_files_rss(text: &str) -> Vec<String> {
+    regex!(r"(?s)<item>.*?<link>\s*(?:<!\[CDATA\[)?\s*([^<\]\s]+)")
+        .captures_iter(text)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// The download page of a project, if it has released any files.
+///
+/// Every project has a files page, so the RSS feed of its files is used to check whether
+/// there is anything on it.
+#[cfg(feature = "net")]
+async fn download_page(project: &str) -> Option<String> {
+    let url = format!("https://sourceforge.net/projects/{}/rss?path=/", project);
+    let client = crate::http::build_client().build().ok()?;
+    let response = match crate::http::send(client.get(&url)).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            debug!("Unable to retrieve {}: {}", url, response.status());
+            return None;
+        }
+        Err(e) => {
+            debug!("Unable to retrieve {}: {}", url, e);
+            return None;
+        }
+    };
+    let text = response.text().await.ok()?;
+    if parse_files_rss(&text).is_empty() {
+        debug!("No files released for {}", project);
+        return None;
+    }
+    Some(format!(
+        "https://sourceforge.net/projects/{}/files/",
+        project
+    ))
+}
+
+/// The version of the latest release of a project, from the file that SourceForge
+/// offers as its default download.
+#[cfg(feature = "net")]
+async fn latest_release_version(project: &str) -> Option<String> {
+    let url: Url = format!(
+        "https://sourceforge.net/projects/{}/best_release.json",
+        project
+    )
+    .parse()
+    .ok()?;
+    let data: serde_json::Value = match ApiClient::sourceforge().get_url(&url).await {
+        Ok(data) => data,
+        Err(e) => {
+            debug!(
+                "Unable to retrieve the latest release of {}: {}",
+                project, e
+            );
+            return None;
+        }
+    };
+    let filename = data.get("release")?.get("filename")?.as_str()?;
+    crate::version::version_from_tarball_name(filename.rsplit('/').next()?)
+}
+
+#[cfg(feature = "net")]
 pub async fn guess_from_sf(sf_project: &str, subproject: Option<&str>) -> Vec<UpstreamDatum> {
     let mut results = Vec::new();
     match get_sf_metadata(sf_project).await {
         Some(data) => {
-            results.extend(parse_sf_json(data, sf_project, subproject).await);
+            results.extend(parse_sf_json(data.clone(), sf_project, subproject).await);
+            // Subprojects don't have their own wiki, mailing lists or releases
+            if !results
+                .iter()
+                .any(|d| matches!(d, UpstreamDatum::SourceForgeSubproject(..)))
+            {
+                results.extend(parse_sf_project_extras(&data));
+                results.extend(download_page(sf_project).await.map(UpstreamDatum::Download));
+                results.extend(
+                    latest_release_version(sf_project)
+                        .await
+                        .map(UpstreamDatum::Version),
+                );
+            }
         }
         None => {
             debug!("No SourceForge metadata found for {}", sf_project);
@@ -158,20 +341,56 @@ pub async fn guess_from_sf(sf_project: &str, subproject: Option<&str>) -> Vec<Up
     results
 }
 
-pub fn extract_sf_project_name(url: &str) -> Option<String> {
+/// Extract the SourceForge project name, and the subproject if any, from a URL.
+///
+/// Subprojects are directories under the project's files (e.g.
+/// `https://sourceforge.net/projects/gnuwin32/files/coreutils/`) or tools that aren't
+/// part of every project (e.g. `https://sourceforge.net/p/foo/foo-gui/`).
+pub fn extract_sf_project_name(url: &str) -> Option<(String, Option<String>)> {
+    if let Some((_, project, subproject)) = lazy_regex::regex_captures!(
+        r"https?://sourceforge\.net/projects/([^/]+)/files/([^/?#]+)",
+        url
+    ) {
+        // Release directories and download links are not subprojects
+        let is_subproject =
+            subproject != "latest" && !lazy_regex::regex_is_match!(r"^v?[0-9]", subproject);
+        return Some((
+            project.to_string(),
+            Some(subproject.to_string()).filter(|_| is_subproject),
+        ));
+    }
+
+    if let Some((_, project, mount_point)) =
+        lazy_regex::regex_captures!(r"https?://sourceforge\.net/p/([^/]+)/([^/?#]+)", url)
+    {
+        let is_subproject = mount_point != project && !STANDARD_MOUNT_POINTS.contains(&mount_point);
+        return Some((
+            project.to_string(),
+            Some(mount_point.to_string()).filter(|_| is_subproject),
+        ));
+    }
+
     let projects_regex = regex!(r"https?://sourceforge\.net/(projects|p)/([^/]+)");
     if let Some(captures) = projects_regex.captures(url) {
-        return captures.get(2).map(|m| m.as_str().to_string());
+        return captures.get(2).map(|m| (m.as_str().to_string(), None));
     }
 
     let sf_regex = regex!(r"https?://(.*).(sf|sourceforge).(net|io)/.*");
     if let Some(captures) = sf_regex.captures(url) {
-        return captures.get(1).map(|m| m.as_str().to_string());
+        return captures.get(1).map(|m| (m.as_str().to_string(), None));
     }
 
     None
 }
 
+/// URL of the files directory of a subproject.
+pub fn subproject_files_url(project: &str, subproject: &str) -> String {
+    format!(
+        "https://sourceforge.net/projects/{}/files/{}/",
+        project, subproject
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,4 +457,107 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn test_parse_sf_json_subproject() {
+        let data = serde_json::json!({
+            "name": "Foo Suite",
+            "external_homepage": "https://foo.sourceforge.io/",
+            "preferred_support_url": "",
+            "tools": [
+                {"name": "git", "mount_point": "code", "mount_label": "Code",
+                 "url": "https://sourceforge.net/p/foo/code/",
+                 "clone_url_https_anon": "https://git.code.sf.net/p/foo/code"},
+                {"name": "git", "mount_point": "foo-gui", "mount_label": "foo-gui",
+                 "url": "https://sourceforge.net/p/foo/foo-gui/",
+                 "clone_url_https_anon": "https://git.code.sf.net/p/foo/foo-gui"},
+                {"name": "tickets", "mount_point": "foo-gui-bugs", "mount_label": "GUI Bugs",
+                 "url": "https://sourceforge.net/p/foo/foo-gui-bugs/"},
+            ],
+        });
+        assert_eq!(
+            parse_sf_json(data.clone(), "foo", Some("foo-gui")).await,
+            vec![
+                UpstreamDatum::SourceForgeSubproject("foo-gui".to_string()),
+                UpstreamDatum::BugDatabase(
+                    "https://sourceforge.net/p/foo/foo-gui-bugs/".to_string()
+                ),
+                UpstreamDatum::Repository("https://git.code.sf.net/p/foo/foo-gui".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_sf_json(data, "foo", Some("foo")).await,
+            vec![
+                UpstreamDatum::Name("Foo Suite".to_string()),
+                UpstreamDatum::Homepage("https://foo.sourceforge.io/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sf_project_extras() {
+        let data: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/hg-diff.json")).unwrap();
+        assert_eq!(
+            parse_sf_project_extras(&data),
+            vec![UpstreamDatum::Screenshots(vec![
+                "https://sourceforge.net/p/hg-diff/screenshot/hg-diff-small.png".to_string()
+            ]),]
+        );
+        let data: serde_json::Value =
+            serde_json::from_str(include_str!("../testdata/zsh.json")).unwrap();
+        assert_eq!(
+            parse_sf_project_extras(&data),
+            vec![UpstreamDatum::MailingList(
+                "https://sourceforge.net/p/zsh/mailman/".to_string()
+            ),]
+        );
+    }
+
+    #[test]
+    fn test_parse_files_rss() {
+        assert_eq!(
+            parse_files_rss(include_str!("../testdata/zsh-files.rss")),
+            vec![
+                "https://sourceforge.net/projects/zsh/files/zsh/5.9/zsh-5.9.tar.xz/download",
+                "https://sourceforge.net/projects/zsh/files/zsh-doc/5.9/zsh-5.9-doc.tar.xz/download",
+            ]
+        );
+        assert!(parse_files_rss(
+            "<rss><channel><link>https://sourceforge.net</link></channel></rss>"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_extract_sf_project_name() {
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/projects/gtab/"),
+            Some(("gtab".to_string(), None))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/projects/gnuwin32/files/coreutils/"),
+            Some(("gnuwin32".to_string(), Some("coreutils".to_string())))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/projects/gtab/files/1.2/"),
+            Some(("gtab".to_string(), None))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/projects/gtab/files/latest/download"),
+            Some(("gtab".to_string(), None))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/p/foo/foo-gui/ci/master/tree/"),
+            Some(("foo".to_string(), Some("foo-gui".to_string())))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://sourceforge.net/p/zsh/bugs/"),
+            Some(("zsh".to_string(), None))
+        );
+        assert_eq!(
+            extract_sf_project_name("https://zsh.sourceforge.io/Doc/"),
+            Some(("zsh".to_string(), None))
+        );
+    }
 }
@@ -0,0 +1,122 @@
+//! URLs of SourceHut projects, and checks against the GraphQL API of its tracker.
+//!
+//! Each SourceHut service runs on its own host (git.sr.ht, todo.sr.ht, lists.sr.ht, ...), and
+//! resources are named after their owner, e.g. `https://git.sr.ht/~sircmpwn/scdoc`. The
+//! GraphQL APIs only accept authenticated requests, so checks require
+//! [`crate::http::credentials::Credentials`] for sr.ht.
+#[cfg(feature = "net")]
+use crate::forges::api::ApiClient;
+#[cfg(feature = "net")]
+use crate::HTTPJSONError;
+#[cfg(feature = "net")]
+use serde::Deserialize;
+use url::Url;
+
+/// Hosts of the SourceHut services.
+pub const HOSTS: &[&str] = &[
+    "sr.ht",
+    "git.sr.ht",
+    "hg.sr.ht",
+    "todo.sr.ht",
+    "lists.sr.ht",
+    "builds.sr.ht",
+    "man.sr.ht",
+];
+
+/// The owner (without the leading "~") and name of the resource that a URL points at.
+pub fn owner_and_name(url: &Url) -> Option<(String, String)> {
+    if !HOSTS.contains(&url.host_str()?) {
+        return None;
+    }
+    let mut segments = url.path_segments()?;
+    let owner = segments.next()?.strip_prefix('~')?;
+    let name = segments.next().filter(|s| !s.is_empty())?;
+    if owner.is_empty() {
+        return None;
+    }
+    Some((
+        owner.to_string(),
+        name.strip_suffix(".git").unwrap_or(name).to_string(),
+    ))
+}
+
+/// The URL of a resource of `owner` on one of the services, e.g. "todo" for the tracker.
+pub fn service_url(service: &str, owner: &str, name: &str) -> Url {
+    let mut url: Url = format!("https://{}.sr.ht/", service).parse().unwrap();
+    url.path_segments_mut()
+        .unwrap()
+        .pop_if_empty()
+        .extend([format!("~{}", owner).as_str(), name]);
+    url
+}
+
+#[cfg(feature = "net")]
+const TRACKER_QUERY: &str = r#"query($owner: String!, $name: String!) {
+  user(username: $owner) {
+    tracker(name: $name) { name }
+  }
+}"#;
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct Response {
+    data: Option<Data>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct Data {
+    user: Option<User>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct User {
+    tracker: Option<Tracker>,
+}
+
+#[cfg(feature = "net")]
+#[derive(Debug, Deserialize)]
+struct Tracker {
+    name: String,
+}
+
+/// Find the name of the tracker of `owner` on todo.sr.ht, or `None` if it does not exist.
+#[cfg(feature = "net")]
+pub async fn tracker_name(owner: &str, name: &str) -> Result<Option<String>, HTTPJSONError> {
+    let body = serde_json::json!({
+        "query": TRACKER_QUERY,
+        "variables": {"owner": owner, "name": name},
+    });
+    let client = ApiClient::new("https://todo.sr.ht/".parse().unwrap());
+    let response: Response = client.post("query", &body).await?;
+    Ok(response
+        .data
+        .and_then(|d| d.user)
+        .and_then(|u| u.tracker)
+        .map(|t| t.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_and_name() {
+        let parse = |url: &str| owner_and_name(&url.parse().unwrap());
+        assert_eq!(
+            parse("https://git.sr.ht/~sircmpwn/scdoc"),
+            Some(("sircmpwn".to_string(), "scdoc".to_string()))
+        );
+        assert_eq!(
+            parse("https://todo.sr.ht/~sircmpwn/scdoc/12"),
+            Some(("sircmpwn".to_string(), "scdoc".to_string()))
+        );
+        assert_eq!(parse("https://git.sr.ht/~sircmpwn"), None);
+        assert_eq!(parse("https://github.com/~sircmpwn/scdoc"), None);
+        assert_eq!(
+            service_url("lists", "sircmpwn", "scdoc").as_str(),
+            "https://lists.sr.ht/~sircmpwn/scdoc"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Browse URLs for git repositories that are served by a generic web frontend, such as cgit
+//! or gitweb, rather than by a forge.
+//!
+//! A handful of sites are known to run one of these; on other hosts the usual locations are
+//! probed, and recognized by the generator that the frontends announce in their pages.
+use crate::vcs::VcsLocation;
+use url::Url;
+
+/// Self-hosted sites that serve repositories with cgit, using the clone URL as browse URL.
+const KNOWN_CGIT_SITES: &[&str] = &[
+    "git.kernel.org",
+    "git.zx2c4.com",
+    "git.yoctoproject.org",
+    "git.netfilter.org",
+];
+
+/// Self-hosted sites that serve repositories with gitweb.
+///
+/// Entries are (host, path to gitweb, path to clone URLs).
+const KNOWN_GITWEB_SITES: &[(&str, &str, &str)] = &[
+    ("sourceware.org", "git", "git"),
+    ("git.postgresql.org", "gitweb", "git"),
+    ("git.tukaani.org", "", ""),
+];
+
+/// A web frontend that shows a repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frontend {
+    /// cgit, showing the repository at this URL
+    Cgit { repository: Url },
+
+    /// gitweb, at `script`, showing the repository as `project`
+    Gitweb { script: Url, project: String },
+}
+
+impl Frontend {
+    /// The URL for browsing a location in the repository.
+    pub fn browse_url(&self, location: &VcsLocation) -> Url {
+        match self {
+            Frontend::Cgit { repository } => {
+                let mut url = repository.clone();
+                if let Some(subpath) = location.subpath.as_deref() {
+                    if let Ok(mut segments) = url.path_segments_mut() {
+                        segments
+                            .pop_if_empty()
+                            .push("tree")
+                            .extend(subpath.split('/'));
+                    }
+                }
+                if let Some(branch) = location.branch.as_deref() {
+                    url.query_pairs_mut().append_pair("h", branch);
+                }
+                url
+            }
+            Frontend::Gitweb { script, project } => {
+                // gitweb separates parameters with ';' rather than '&'
+                let mut query = format!("p={}", project);
+                if let Some(subpath) = location.subpath.as_deref() {
+                    query.push_str(&format!(";a=tree;f={}", subpath));
+                } else {
+                    query.push_str(";a=summary");
+                }
+                if let Some(branch) = location.branch.as_deref() {
+                    query.push_str(&format!(";hb={}", branch));
+                }
+                let mut url = script.clone();
+                url.set_query(Some(&query));
+                url
+            }
+        }
+    }
+
+    #[cfg(feature = "net")]
+    fn name(&self) -> &'static str {
+        match self {
+            Frontend::Cgit { .. } => "cgit",
+            Frontend::Gitweb { .. } => "gitweb",
+        }
+    }
+}
+
+/// The frontend for a repository on a site that is known to run one.
+pub fn known_frontend(repo_url: &Url) -> Option<Frontend> {
+    let host = repo_url.host_str()?;
+    if let Some((_, gitweb_path, clone_path)) =
+        KNOWN_GITWEB_SITES.iter().find(|(h, _, _)| *h == host)
+    {
+        let path = repo_url.path().trim_start_matches('/');
+        let project = if clone_path.is_empty() {
+            path
+        } else {
+            path.strip_prefix(clone_path)?.trim_start_matches('/')
+        };
+        if project.is_empty() {
+            return None;
+        }
+        let mut script = repo_url.clone();
+        script.set_scheme("https").ok()?;
+        script.set_path(&if gitweb_path.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{}/", gitweb_path)
+        });
+        script.set_query(None);
+        return Some(Frontend::Gitweb {
+            script,
+            project: project.to_string(),
+        });
+    }
+    let segments = repo_url.path_segments()?.collect::<Vec<_>>();
+    if KNOWN_CGIT_SITES.contains(&host) || segments.contains(&"cgit") {
+        return Some(Frontend::Cgit {
+            repository: repo_url.clone(),
+        });
+    }
+    None
+}
+
+/// The frontends that may show a repository on an arbitrary host, most likely first.
+///
+/// cgit is commonly served from the clone URL itself, or from "/cgit/" for repositories that
+/// are cloned from "/git/"; gitweb is commonly served from "/gitweb/".
+#[cfg(feature = "net")]
+fn candidate_frontends(repo_url: &Url) -> Vec<Frontend> {
+    // The scheme can't be changed in place between git:// and https://
+    let Some(Ok(base)) = repo_url
+        .host_str()
+        .map(|host| format!("https://{}{}", host, repo_url.path()).parse::<Url>())
+    else {
+        return vec![];
+    };
+    let path = base.path().trim_matches('/').to_string();
+    if path.is_empty() {
+        return vec![];
+    }
+    let project = path.strip_prefix("git/").unwrap_or(&path);
+
+    let mut candidates = vec![Frontend::Cgit {
+        repository: base.clone(),
+    }];
+    if project != path {
+        let mut repository = base.clone();
+        repository.set_path(&format!("/cgit/{}", project));
+        candidates.push(Frontend::Cgit { repository });
+    }
+    let mut script = base.clone();
+    script.set_path("/gitweb/");
+    candidates.push(Frontend::Gitweb {
+        script,
+        project: project.to_string(),
+    });
+    candidates
+}
+
+/// The frontend that generated a page, from its generator meta tag.
+#[cfg(feature = "net")]
+fn page_generator(html: &str) -> Option<&str> {
+    let (_, generator) = lazy_regex::regex_captures!(
+        r#"(?i)<meta\s+name=["']generator["']\s+content=["'](cgit|gitweb)\b"#,
+        html
+    )?;
+    Some(generator)
+}
+
+/// Find the frontend that shows a repository, by probing the usual locations on its host.
+///
+/// This will make network connections.
+#[cfg(feature = "net")]
+pub async fn probe_frontend(repo_url: &Url) -> Option<Frontend> {
+    let client = crate::http::build_client().build().ok()?;
+    let root = VcsLocation {
+        url: repo_url.clone(),
+        branch: None,
+        subpath: None,
+    };
+    for candidate in candidate_frontends(repo_url) {
+        let url = candidate.browse_url(&root);
+        let response = match crate::http::send(client.get(url.clone())).await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                log::debug!("No {} at {}: {}", candidate.name(), url, response.status());
+                continue;
+            }
+            Err(e) => {
+                log::debug!("Unable to probe {}: {}", url, e);
+                // Other locations on the same host are unlikely to do better
+                return None;
+            }
+        };
+        let Ok(text) = response.text().await else {
+            continue;
+        };
+        if page_generator(&text).is_some_and(|g| g.eq_ignore_ascii_case(candidate.name())) {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+#[cfg(feature = "net")]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_frontends() {
+        let candidates =
+            candidate_frontends(&"git://git.example.com/git/tools/foo.git".parse().unwrap());
+        assert_eq!(
+            candidates,
+            vec![
+                Frontend::Cgit {
+                    repository: "https://git.example.com/git/tools/foo.git".parse().unwrap()
+                },
+                Frontend::Cgit {
+                    repository: "https://git.example.com/cgit/tools/foo.git"
+                        .parse()
+                        .unwrap()
+                },
+                Frontend::Gitweb {
+                    script: "https://git.example.com/gitweb/".parse().unwrap(),
+                    project: "tools/foo.git".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            candidates[2]
+                .browse_url(&VcsLocation {
+                    url: "https://git.example.com/git/tools/foo.git".parse().unwrap(),
+                    branch: Some("main".to_string()),
+                    subpath: Some("doc".to_string()),
+                })
+                .as_str(),
+            "https://git.example.com/gitweb/?p=tools/foo.git;a=tree;f=doc;hb=main"
+        );
+        assert!(candidate_frontends(&"https://git.example.com/".parse().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_page_generator() {
+        assert_eq!(
+            page_generator("<head><meta name='generator' content='cgit v1.2.3'/>"),
+            Some("cgit")
+        );
+        assert_eq!(
+            page_generator(r#"<meta name="generator" content="gitweb/2.39.2 git/2.39.2"/>"#),
+            Some("gitweb")
+        );
+        assert_eq!(
+            page_generator(r#"<meta name="generator" content="Hugo 0.121"/>"#),
+            None
+        );
+    }
+}
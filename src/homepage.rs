@@ -1,16 +1,32 @@
 use crate::{Certainty, Origin, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
 
 use select::document::Document;
-use select::predicate::Name;
+use select::node::Node;
+use select::predicate::{Attr, Name, Predicate};
+
+/// The schema.org types that describe a software project.
+const SOFTWARE_TYPES: &[&str] = &["SoftwareApplication", "SoftwareSourceCode"];
 
 pub async fn guess_from_homepage(
     url: &url::Url,
 ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
     let client = crate::http::build_client().build().unwrap();
-    let response = client.get(url.clone()).send().await?;
+    let response = crate::http::send(client.get(url.clone())).await?;
 
     let body = response.text().await?;
-    Ok(guess_from_page(&body, url))
+    let mut ret = guess_from_page(&body, url);
+    ret.extend(guess_from_structured_data(&body, url));
+    // Document is not Send, so the OpenGraph URLs are collected before resolving them
+    for og_url in opengraph_urls(&body, url) {
+        if let Some(repo_url) = crate::vcs::guess_repo_from_url(&og_url, Some(false)).await {
+            ret.push(UpstreamDatumWithMetadata {
+                origin: Some(Origin::Url(url.clone())),
+                datum: UpstreamDatum::Repository(repo_url),
+                certainty: Some(Certainty::Likely),
+            });
+        }
+    }
+    Ok(ret)
 }
 
 fn guess_from_page(text: &str, basehref: &url::Url) -> Vec<UpstreamDatumWithMetadata> {
@@ -53,6 +69,196 @@ fn guess_from_page(text: &str, basehref: &url::Url) -> Vec<UpstreamDatumWithMeta
     result
 }
 
+/// The license that a URL refers to; SPDX license pages are mapped to their identifier.
+fn license_from_url(url: &str) -> crate::license::License {
+    let id = url
+        .strip_prefix("https://spdx.org/licenses/")
+        .or_else(|| url.strip_prefix("http://spdx.org/licenses/"))
+        .map(|id| id.trim_end_matches(".html").trim_end_matches(".json"));
+    match id {
+        Some(id) if !id.is_empty() && !id.contains('/') => id.into(),
+        _ => url.into(),
+    }
+}
+
+/// Extract the project metadata from a schema.org JSON-LD object.
+fn guess_from_json_ld(
+    value: &serde_json::Value,
+    basehref: &url::Url,
+) -> Vec<UpstreamDatumWithMetadata> {
+    let mut result = Vec::new();
+    match value {
+        serde_json::Value::Array(items) => {
+            for item in items {
+                result.extend(guess_from_json_ld(item, basehref));
+            }
+            return result;
+        }
+        serde_json::Value::Object(object) => {
+            if let Some(graph) = object.get("@graph") {
+                result.extend(guess_from_json_ld(graph, basehref));
+            }
+        }
+        _ => return result,
+    }
+
+    let is_software = match value.get("@type") {
+        Some(serde_json::Value::String(t)) => SOFTWARE_TYPES.contains(&t.as_str()),
+        Some(serde_json::Value::Array(types)) => types
+            .iter()
+            .any(|t| t.as_str().is_some_and(|t| SOFTWARE_TYPES.contains(&t))),
+        _ => false,
+    };
+    if !is_software {
+        return result;
+    }
+
+    // Properties are either plain URLs or objects with a url (or a name, for licenses)
+    let url_of = |key: &str| -> Option<String> {
+        let url = match value.get(key)? {
+            serde_json::Value::String(s) => s.as_str(),
+            serde_json::Value::Object(o) => o
+                .get("url")
+                .or_else(|| o.get("@id"))
+                .and_then(|u| u.as_str())?,
+            _ => return None,
+        };
+        basehref.join(url).ok().map(|u| u.to_string())
+    };
+
+    let mut push = |datum| {
+        result.push(UpstreamDatumWithMetadata {
+            origin: Some(Origin::Url(basehref.clone())),
+            datum,
+            certainty: Some(Certainty::Likely),
+        })
+    };
+
+    if let Some(repository) = url_of("codeRepository") {
+        push(UpstreamDatum::Repository(repository));
+    }
+    if let Some(bug_database) = url_of("issueTracker") {
+        push(UpstreamDatum::BugDatabase(bug_database));
+    }
+    if let Some(documentation) = url_of("softwareHelp").or_else(|| url_of("documentation")) {
+        push(UpstreamDatum::Documentation(documentation));
+    }
+    match value.get("license") {
+        Some(serde_json::Value::String(license)) if license.contains("://") => {
+            push(UpstreamDatum::License(license_from_url(license)));
+        }
+        Some(serde_json::Value::String(license)) if !license.trim().is_empty() => {
+            push(UpstreamDatum::License(license.trim().into()));
+        }
+        Some(serde_json::Value::Object(license)) => {
+            if let Some(url) = license.get("url").and_then(|u| u.as_str()) {
+                push(UpstreamDatum::License(license_from_url(url)));
+            } else if let Some(name) = license.get("name").and_then(|n| n.as_str()) {
+                push(UpstreamDatum::License(name.trim().into()));
+            }
+        }
+        _ => {}
+    }
+    result
+}
+
+/// Extract the project metadata from a schema.org microdata item.
+fn guess_from_microdata(item: Node, basehref: &url::Url) -> Vec<UpstreamDatumWithMetadata> {
+    let mut result = Vec::new();
+    for element in item.find(Attr("itemprop", ())) {
+        let Some(value) = element.attr("href").or_else(|| element.attr("content")) else {
+            continue;
+        };
+        let Ok(url) = basehref.join(value.trim()) else {
+            continue;
+        };
+        for prop in element.attr("itemprop").unwrap().split_whitespace() {
+            let datum = match prop {
+                "codeRepository" => UpstreamDatum::Repository(url.to_string()),
+                "issueTracker" => UpstreamDatum::BugDatabase(url.to_string()),
+                "softwareHelp" => UpstreamDatum::Documentation(url.to_string()),
+                "license" => UpstreamDatum::License(license_from_url(url.as_str())),
+                _ => continue,
+            };
+            result.push(UpstreamDatumWithMetadata {
+                origin: Some(Origin::Url(basehref.clone())),
+                datum,
+                certainty: Some(Certainty::Likely),
+            });
+        }
+    }
+    result
+}
+
+/// Extract the project metadata that a page declares in a machine-readable way.
+///
+/// This covers schema.org descriptions of the software in JSON-LD and microdata, as well as
+/// `<link>` elements for the bug tracker, repository and license.
+fn guess_from_structured_data(text: &str, basehref: &url::Url) -> Vec<UpstreamDatumWithMetadata> {
+    let document = Document::from(text);
+
+    let mut result = Vec::new();
+
+    for script in document.find(Name("script").and(Attr("type", "application/ld+json"))) {
+        match serde_json::from_str::<serde_json::Value>(&script.text()) {
+            Ok(value) => result.extend(guess_from_json_ld(&value, basehref)),
+            Err(e) => log::debug!("Invalid JSON-LD on {}: {}", basehref, e),
+        }
+    }
+
+    for item in document.find(Attr("itemscope", ())) {
+        let is_software = item.attr("itemtype").is_some_and(|itemtype| {
+            itemtype.split_whitespace().any(|t| {
+                SOFTWARE_TYPES.iter().any(|s| {
+                    t.trim_end_matches('/')
+                        .ends_with(&format!("schema.org/{}", s))
+                })
+            })
+        });
+        if is_software {
+            result.extend(guess_from_microdata(item, basehref));
+        }
+    }
+
+    for link in document.find(Name("link")) {
+        let (Some(rel), Some(href)) = (link.attr("rel"), link.attr("href")) else {
+            continue;
+        };
+        let Ok(url) = basehref.join(href.trim()) else {
+            continue;
+        };
+        for rel in rel.split_whitespace() {
+            let datum = match rel.to_lowercase().as_str() {
+                "issues" => UpstreamDatum::BugDatabase(url.to_string()),
+                "vcs-git" => UpstreamDatum::Repository(url.to_string()),
+                "license" => UpstreamDatum::License(license_from_url(url.as_str())),
+                _ => continue,
+            };
+            result.push(UpstreamDatumWithMetadata {
+                origin: Some(Origin::Url(basehref.clone())),
+                datum,
+                certainty: Some(Certainty::Likely),
+            });
+        }
+    }
+
+    result
+}
+
+/// The URLs that a page refers to in its OpenGraph tags.
+fn opengraph_urls(text: &str, basehref: &url::Url) -> Vec<url::Url> {
+    let document = Document::from(text);
+    document
+        .find(Name("meta"))
+        .filter(|meta| {
+            meta.attr("property")
+                .is_some_and(|p| p == "og:url" || p == "og:see_also")
+        })
+        .filter_map(|meta| meta.attr("content"))
+        .filter_map(|content| basehref.join(content.trim()).ok())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -93,4 +299,80 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_guess_from_structured_data() {
+        let basehref = url::Url::parse("https://example.com/").unwrap();
+        let text = r#"
+            <html>
+                <head>
+                    <link rel="issues" href="/issues">
+                    <link rel="vcs-git" href="https://git.example.com/foo.git" title="foo">
+                    <script type="application/ld+json">
+                    {
+                        "@context": "https://schema.org",
+                        "@graph": [
+                            {"@type": "WebSite", "url": "https://example.com/"},
+                            {
+                                "@type": "SoftwareSourceCode",
+                                "codeRepository": "https://github.com/owner/foo",
+                                "softwareHelp": {"@type": "CreativeWork", "url": "/docs/"},
+                                "license": "https://spdx.org/licenses/MIT"
+                            }
+                        ]
+                    }
+                    </script>
+                </head>
+                <body>
+                    <div itemscope itemtype="https://schema.org/SoftwareApplication">
+                        <a itemprop="license" href="https://www.gnu.org/licenses/gpl-3.0.html">GPL</a>
+                    </div>
+                    <div itemscope itemtype="https://schema.org/Person">
+                        <a itemprop="codeRepository" href="https://github.com/owner">Me</a>
+                    </div>
+                </body>
+            </html>
+        "#;
+        let datum = |datum| UpstreamDatumWithMetadata {
+            origin: Some(Origin::Url(basehref.clone())),
+            datum,
+            certainty: Some(Certainty::Likely),
+        };
+        assert_eq!(
+            guess_from_structured_data(text, &basehref),
+            vec![
+                datum(UpstreamDatum::Repository(
+                    "https://github.com/owner/foo".to_string()
+                )),
+                datum(UpstreamDatum::Documentation(
+                    "https://example.com/docs/".to_string()
+                )),
+                datum(UpstreamDatum::License("MIT".into())),
+                datum(UpstreamDatum::License(
+                    "https://www.gnu.org/licenses/gpl-3.0.html".into()
+                )),
+                datum(UpstreamDatum::BugDatabase(
+                    "https://example.com/issues".to_string()
+                )),
+                datum(UpstreamDatum::Repository(
+                    "https://git.example.com/foo.git".to_string()
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_opengraph_urls() {
+        let basehref = url::Url::parse("https://example.com/").unwrap();
+        let text = r#"
+            <head>
+                <meta property="og:title" content="foo">
+                <meta property="og:url" content="https://github.com/owner/foo">
+            </head>
+        "#;
+        assert_eq!(
+            opengraph_urls(text, &basehref),
+            vec![url::Url::parse("https://github.com/owner/foo").unwrap()]
+        );
+    }
 }
@@ -0,0 +1,175 @@
+//! On-disk cache of HTTP responses.
+//!
+//! Successful responses to GET requests are stored in [`crate::dirs::http_cache_dir`], keyed by
+//! the URL and the headers that select the representation. Stored responses are reused without
+//! contacting the server until they are older than the TTL, after which they are revalidated
+//! using the `ETag` and `Last-Modified` headers the server sent.
+use reqwest::header::{
+    HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE,
+    IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::ResponseBuilderExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use url::Url;
+
+/// A response as it is kept on disk, apart from its body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    url: Url,
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Seconds since the epoch at which the response was retrieved or last revalidated
+    stored: u64,
+}
+
+/// A stored response.
+#[derive(Debug, Clone)]
+pub(crate) struct Stored {
+    entry: Entry,
+    body: Vec<u8>,
+}
+
+impl Stored {
+    pub(crate) fn new(url: Url, status: u16, headers: &HeaderMap, body: Vec<u8>) -> Self {
+        Self {
+            entry: Entry {
+                url,
+                status,
+                headers: headers
+                    .iter()
+                    .filter_map(|(n, v)| Some((n.to_string(), v.to_str().ok()?.to_string())))
+                    .collect(),
+                stored: now(),
+            },
+            body,
+        }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.entry
+            .headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether the response can be used without revalidating it.
+    pub(crate) fn is_fresh(&self, ttl: Duration) -> bool {
+        let age = now().saturating_sub(self.entry.stored);
+        Duration::from_secs(age) < ttl
+    }
+
+    /// Make `request` conditional on the stored response having changed.
+    pub(crate) fn add_validators(&self, request: &mut reqwest::Request) {
+        let headers = request.headers_mut();
+        if let Some(etag) = self.header(ETAG.as_str()) {
+            if let Ok(value) = HeaderValue::from_str(etag) {
+                headers.insert(IF_NONE_MATCH, value);
+            }
+        }
+        if let Some(last_modified) = self.header(LAST_MODIFIED.as_str()) {
+            if let Ok(value) = HeaderValue::from_str(last_modified) {
+                headers.insert(IF_MODIFIED_SINCE, value);
+            }
+        }
+    }
+
+    pub(crate) fn into_response(self) -> reqwest::Response {
+        let mut builder = http::Response::builder()
+            .status(self.entry.status)
+            .url(self.entry.url);
+        for (name, value) in &self.entry.headers {
+            builder = builder.header(name, value);
+        }
+        builder.body(self.body).unwrap().into()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// A directory with stored responses.
+#[derive(Debug, Clone)]
+pub(crate) struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The cache in [`crate::dirs::http_cache_dir`].
+    pub(crate) fn open() -> Option<Self> {
+        crate::dirs::http_cache_dir().map(Self::new)
+    }
+
+    /// Whether the response to a request can be stored.
+    pub(crate) fn is_cacheable(request: &reqwest::Request) -> bool {
+        request.method() == reqwest::Method::GET && request.body().is_none()
+    }
+
+    /// The key under which the response to a request is stored.
+    pub(crate) fn key(request: &reqwest::Request) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(request.url().as_str());
+        for name in [ACCEPT, AUTHORIZATION] {
+            hasher.update(b"\n");
+            if let Some(value) = request.headers().get(name) {
+                hasher.update(value.as_bytes());
+            }
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    fn body_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.body", key))
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<Stored> {
+        let entry = std::fs::read(self.entry_path(key)).ok()?;
+        let entry: Entry = serde_json::from_slice(&entry).ok()?;
+        let body = std::fs::read(self.body_path(key)).ok()?;
+        Some(Stored { entry, body })
+    }
+
+    fn write_entry(&self, key: &str, entry: &Entry) -> std::io::Result<()> {
+        // Write to a temporary file first, so that readers never see a partial entry
+        let path = self.entry_path(key);
+        let tmp_path = path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec(entry)?)?;
+        std::fs::rename(&tmp_path, &path)
+    }
+
+    pub(crate) fn put(&self, key: &str, stored: &Stored) -> std::io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.body_path(key), &stored.body)?;
+        self.write_entry(key, &stored.entry)
+    }
+
+    /// Record that a stored response was revalidated.
+    pub(crate) fn refresh(&self, key: &str, stored: &mut Stored) -> std::io::Result<()> {
+        stored.entry.stored = now();
+        self.write_entry(key, &stored.entry)
+    }
+}
+
+/// Whether the server allows a response to be stored.
+pub(crate) fn may_store(headers: &HeaderMap) -> bool {
+    !headers
+        .get_all(CACHE_CONTROL)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|d| d.trim().eq_ignore_ascii_case("no-store"))
+}
@@ -0,0 +1,303 @@
+//! Credentials for forge APIs.
+//!
+//! Tokens are read from the environment and from the credentials file at
+//! [`crate::dirs::credentials_path`], and sent with every request to the host they are for.
+//! The credentials file lists tokens per host:
+//!
+//! ```toml
+//! [hosts."github.example.com"]
+//! kind = "github"
+//! token = "ghp_..."
+//!
+//! [hosts."gitlab.example.com"]
+//! kind = "gitlab"
+//! token = "glpat-..."
+//!
+//! [hosts."codeberg.org"]
+//! kind = "gitea"
+//! token = "..."
+//!
+//! [hosts."sr.ht"]
+//! kind = "sourcehut"
+//! token = "..."
+//! ```
+//!
+//! Tokens from the environment take precedence over those in the file:
+//!
+//! * `GITHUB_TOKEN` or `GH_TOKEN` for github.com
+//! * `GH_ENTERPRISE_TOKEN` for the GitHub Enterprise host in `GH_HOST`
+//! * `GITLAB_TOKEN` for gitlab.com
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// The API a token is for, which determines how it is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    /// GitHub or GitHub Enterprise
+    GitHub,
+    /// GitLab, using a personal, group or project access token
+    GitLab,
+    /// Gitea or Forgejo
+    Gitea,
+    /// SourceHut, using a personal access token for all of its services
+    SourceHut,
+}
+
+/// A token for the API of a host.
+#[derive(Clone, PartialEq, Eq, Deserialize)]
+pub struct Credential {
+    pub kind: ForgeKind,
+    pub token: String,
+}
+
+impl Credential {
+    /// The value of the Authorization header to send.
+    fn authorization(&self) -> String {
+        match self.kind {
+            ForgeKind::GitHub | ForgeKind::GitLab | ForgeKind::SourceHut => {
+                format!("Bearer {}", self.token)
+            }
+            ForgeKind::Gitea => format!("token {}", self.token),
+        }
+    }
+}
+
+// Don't include tokens in logs
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credential")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+pub enum CredentialsError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+}
+
+impl std::fmt::Display for CredentialsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialsError::Io(e) => write!(f, "Unable to read credentials: {}", e),
+            CredentialsError::Parse(e) => write!(f, "Unable to parse credentials: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CredentialsError {}
+
+impl From<std::io::Error> for CredentialsError {
+    fn from(e: std::io::Error) -> Self {
+        CredentialsError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for CredentialsError {
+    fn from(e: toml::de::Error) -> Self {
+        CredentialsError::Parse(e)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CredentialsFile {
+    #[serde(default)]
+    hosts: HashMap<String, Credential>,
+}
+
+/// Tokens for the APIs of hosts.
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    hosts: HashMap<String, Credential>,
+}
+
+static GLOBAL: OnceLock<Credentials> = OnceLock::new();
+
+/// Hosts that serve the API or content of another host, and accept its tokens.
+const HOST_ALIASES: &[(&str, &str)] = &[
+    ("api.github.com", "github.com"),
+    ("raw.githubusercontent.com", "github.com"),
+    ("git.sr.ht", "sr.ht"),
+    ("hg.sr.ht", "sr.ht"),
+    ("todo.sr.ht", "sr.ht"),
+    ("lists.sr.ht", "sr.ht"),
+];
+
+impl Credentials {
+    /// No credentials.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read credentials from a file.
+    pub fn from_file(path: &Path) -> Result<Self, CredentialsError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// The credentials from the environment and the credentials file.
+    ///
+    /// A credentials file that can't be read is ignored, with a warning.
+    pub fn load() -> Self {
+        let mut credentials = match crate::dirs::credentials_path() {
+            Some(path) if path.exists() => Self::from_file(&path).unwrap_or_else(|e| {
+                log::warn!("{}: {}", path.display(), e);
+                Self::new()
+            }),
+            _ => Self::new(),
+        };
+        credentials.add_from_env(&|name| std::env::var(name).ok());
+        credentials
+    }
+
+    fn add_from_env(&mut self, var: &impl Fn(&str) -> Option<String>) {
+        let var = |name| var(name).filter(|v| !v.is_empty());
+        let github = |token| Credential {
+            kind: ForgeKind::GitHub,
+            token,
+        };
+        if let Some(token) = var("GITHUB_TOKEN").or_else(|| var("GH_TOKEN")) {
+            self.add("github.com", github(token));
+        }
+        if let (Some(host), Some(token)) = (var("GH_HOST"), var("GH_ENTERPRISE_TOKEN")) {
+            self.add(&host, github(token));
+        }
+        if let Some(token) = var("GITLAB_TOKEN") {
+            self.add(
+                "gitlab.com",
+                Credential {
+                    kind: ForgeKind::GitLab,
+                    token,
+                },
+            );
+        }
+    }
+
+    /// The credentials used for requests made by upstream-ontologist.
+    ///
+    /// These are loaded with [`Credentials::load`] unless others were installed with
+    /// [`Credentials::install`].
+    pub fn global() -> &'static Credentials {
+        GLOBAL.get_or_init(Credentials::load)
+    }
+
+    /// Use these credentials for requests made by upstream-ontologist.
+    ///
+    /// This has to happen before any requests are made. If credentials are already in use,
+    /// they are kept and these are returned.
+    pub fn install(self) -> Result<(), Credentials> {
+        GLOBAL.set(self)
+    }
+
+    /// Set the credential for a host, replacing any existing one.
+    pub fn add(&mut self, host: &str, credential: Credential) {
+        self.hosts.insert(host.to_lowercase(), credential);
+    }
+
+    /// The credential for a host.
+    pub fn get(&self, host: &str) -> Option<&Credential> {
+        let host = host.to_lowercase();
+        let host = HOST_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == host)
+            .map_or(host.as_str(), |(_, h)| h);
+        self.hosts.get(host)
+    }
+
+    /// Add the credential for the host of a request to it, unless it already has one.
+    pub fn authorize(&self, request: &mut reqwest::Request) {
+        if request.url().scheme() != "https"
+            || request
+                .headers()
+                .contains_key(reqwest::header::AUTHORIZATION)
+        {
+            return;
+        }
+        let Some(credential) = request.url().host_str().and_then(|h| self.get(h)) else {
+            return;
+        };
+        match reqwest::header::HeaderValue::from_str(&credential.authorization()) {
+            Ok(mut value) => {
+                value.set_sensitive(true);
+                request
+                    .headers_mut()
+                    .insert(reqwest::header::AUTHORIZATION, value);
+            }
+            Err(e) => log::warn!("Invalid token for {}: {}", request.url(), e),
+        }
+    }
+}
+
+impl std::str::FromStr for Credentials {
+    type Err = CredentialsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: CredentialsFile = toml::from_str(s)?;
+        let mut credentials = Self::new();
+        for (host, credential) in file.hosts {
+            credentials.add(&host, credential);
+        }
+        Ok(credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let credentials: Credentials = r#"
+[hosts."GitLab.example.com"]
+kind = "gitlab"
+token = "glpat-1"
+
+[hosts."codeberg.org"]
+kind = "gitea"
+token = "2"
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            credentials.get("gitlab.example.com").unwrap().kind,
+            ForgeKind::GitLab
+        );
+        assert_eq!(
+            credentials.get("codeberg.org").unwrap().authorization(),
+            "token 2"
+        );
+        assert!(credentials.get("github.com").is_none());
+        assert!("[hosts.\"x\"]\nkind = \"svn\"\ntoken = \"3\"\n"
+            .parse::<Credentials>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_authorize() {
+        let mut credentials = Credentials::new();
+        credentials.add_from_env(&|name| match name {
+            "GITHUB_TOKEN" => Some("ghp_1".to_string()),
+            "GITLAB_TOKEN" => Some(String::new()),
+            _ => None,
+        });
+        assert!(credentials.get("gitlab.com").is_none());
+
+        let authorization = |url: &str| {
+            let mut request = reqwest::Request::new(reqwest::Method::GET, url.parse().unwrap());
+            credentials.authorize(&mut request);
+            request
+                .headers()
+                .get(reqwest::header::AUTHORIZATION)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+        assert_eq!(
+            authorization("https://api.github.com/repos/jelmer/dulwich").as_deref(),
+            Some("Bearer ghp_1")
+        );
+        assert_eq!(authorization("http://github.com/jelmer/dulwich"), None);
+        assert_eq!(authorization("https://gitlab.com/foo/bar"), None);
+    }
+}
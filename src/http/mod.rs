@@ -0,0 +1,386 @@
+use crate::NetworkPolicy;
+use futures::{Stream, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+
+#[cfg(feature = "http-cache")]
+mod cache;
+pub mod credentials;
+mod ratelimit;
+
+// Too aggressive?
+const DEFAULT_URLLIB_TIMEOUT: u64 = 3;
+
+/// Environment variable that enables the on-disk cache of HTTP responses.
+///
+/// Set it to the number of seconds for which responses are used without checking whether they
+/// changed, or to "1" to use [`DEFAULT_CACHE_TTL`].
+pub const HTTP_CACHE_ENV: &str = "UPSTREAM_ONTOLOGIST_HTTP_CACHE";
+
+/// How long cached responses are used without revalidating them, unless configured otherwise.
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a request may wait for rate limits to reset, unless configured otherwise.
+pub const DEFAULT_RETRY_BUDGET: Duration = Duration::from_secs(60);
+
+/// Whether responses are cached on disk.
+///
+/// Responses are stored in [`crate::dirs::http_cache_dir`]. Caching requires the
+/// `http-cache` feature; without it, responses are never cached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheMode {
+    /// Cache responses if [`HTTP_CACHE_ENV`] is set
+    #[default]
+    FromEnvironment,
+    /// Don't cache responses
+    Disabled,
+    /// Cache responses, and use them without revalidating them for `ttl`
+    Enabled { ttl: Duration },
+}
+
+impl CacheMode {
+    /// The mode configured in [`HTTP_CACHE_ENV`].
+    pub fn from_env() -> Self {
+        Self::parse_env(std::env::var(HTTP_CACHE_ENV).ok().as_deref())
+    }
+
+    fn parse_env(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            None | Some("" | "0" | "off" | "no") => CacheMode::Disabled,
+            Some("1" | "on" | "yes") => CacheMode::Enabled {
+                ttl: DEFAULT_CACHE_TTL,
+            },
+            Some(v) => match v.parse() {
+                Ok(secs) => CacheMode::Enabled {
+                    ttl: Duration::from_secs(secs),
+                },
+                Err(_) => {
+                    log::warn!("Ignoring invalid value for {}: {}", HTTP_CACHE_ENV, v);
+                    CacheMode::Disabled
+                }
+            },
+        }
+    }
+
+    /// How long cached responses are used for, or `None` if responses are not cached.
+    pub fn ttl(&self) -> Option<Duration> {
+        match self {
+            CacheMode::FromEnvironment => Self::from_env().ttl(),
+            CacheMode::Disabled => None,
+            CacheMode::Enabled { ttl } => Some(*ttl),
+        }
+    }
+}
+
+/// Settings for the HTTP requests made by the current task.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestSettings {
+    /// Whether requests may be sent
+    pub network: NetworkPolicy,
+
+    /// Timeout for individual requests; defaults to a few seconds
+    pub timeout: Option<Duration>,
+
+    /// Whether responses are cached on disk
+    pub cache: CacheMode,
+
+    /// How long a request may wait for rate limits to reset before it is retried; defaults to
+    /// [`DEFAULT_RETRY_BUDGET`]
+    pub retry_budget: Option<Duration>,
+}
+
+impl RequestSettings {
+    fn retry_budget(&self) -> Duration {
+        self.retry_budget.unwrap_or(DEFAULT_RETRY_BUDGET)
+    }
+
+    /// Whether requests can be answered, either from the network or from the cache.
+    pub fn can_fetch(&self) -> bool {
+        match self.network {
+            NetworkPolicy::Online => true,
+            NetworkPolicy::CacheOnly => cfg!(feature = "http-cache") && self.cache.ttl().is_some(),
+            NetworkPolicy::Offline => false,
+        }
+    }
+}
+
+tokio::task_local! {
+    static SETTINGS: RequestSettings;
+}
+
+/// Run a future with the specified request settings.
+///
+/// Providers and forges don't get the settings passed in explicitly, so they are kept
+/// for the duration of the future instead.
+pub async fn with_settings<F: Future>(settings: RequestSettings, f: F) -> F::Output {
+    SETTINGS.scope(settings, f).await
+}
+
+/// Run a stream with the specified request settings; see [`with_settings`].
+pub fn stream_with_settings<S: Stream>(
+    settings: RequestSettings,
+    stream: S,
+) -> impl Stream<Item = S::Item> {
+    let mut stream = Box::pin(stream);
+    futures::stream::poll_fn(move |cx| SETTINGS.sync_scope(settings, || stream.poll_next_unpin(cx)))
+}
+
+/// The request settings for the current task.
+pub fn settings() -> RequestSettings {
+    SETTINGS.try_with(|s| *s).unwrap_or_default()
+}
+
+pub fn build_client() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(crate::USER_AGENT)
+        .timeout(
+            settings()
+                .timeout
+                .unwrap_or(Duration::from_secs(DEFAULT_URLLIB_TIMEOUT)),
+        )
+}
+
+/// Send a request, using the on-disk cache if it is enabled in the current [`settings`].
+///
+/// If the network policy is [`NetworkPolicy::Offline`], or [`NetworkPolicy::CacheOnly`] for
+/// responses that are not in the cache, requests get a "504 Gateway Timeout" response, like
+/// in HTTP caches.
+///
+/// Requests to hosts that there are [`credentials::Credentials`] for are authenticated.
+///
+/// Requests that are rate limited are retried once the server allows it, if that is within
+/// the retry budget. Otherwise, the "429 Too Many Requests" or "403 Forbidden" response is
+/// returned.
+pub async fn send(request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+    let settings = settings();
+    let (client, request) = request.build_split();
+    let mut request = request?;
+    if !settings.can_fetch() {
+        log::debug!("Not fetching {}: network access is disabled", request.url());
+        return Ok(unavailable(request.url()));
+    }
+    credentials::Credentials::global().authorize(&mut request);
+    #[cfg(feature = "http-cache")]
+    if let (Some(ttl), Some(cache)) = (settings.cache.ttl(), cache::Cache::open()) {
+        return send_cached(&client, request, &cache, ttl, &settings).await;
+    }
+    ratelimit::execute(&client, request, settings.retry_budget()).await
+}
+
+/// The response for requests that can't be answered when the network can't be used.
+///
+/// This is what HTTP caches do for requests with `Cache-Control: only-if-cached`.
+fn unavailable(url: &url::Url) -> reqwest::Response {
+    use reqwest::ResponseBuilderExt;
+    http::Response::builder()
+        .status(reqwest::StatusCode::GATEWAY_TIMEOUT)
+        .url(url.clone())
+        .body(Vec::new())
+        .unwrap()
+        .into()
+}
+
+#[cfg(feature = "http-cache")]
+async fn send_cached(
+    client: &reqwest::Client,
+    mut request: reqwest::Request,
+    cache: &cache::Cache,
+    ttl: Duration,
+    settings: &RequestSettings,
+) -> reqwest::Result<reqwest::Response> {
+    let network = settings.network;
+    if !cache::Cache::is_cacheable(&request) {
+        return ratelimit::execute(client, request, settings.retry_budget()).await;
+    }
+    let key = cache::Cache::key(&request);
+    let stored = cache.get(&key);
+    match stored.as_ref() {
+        Some(stored) if network != NetworkPolicy::Online || stored.is_fresh(ttl) => {
+            log::debug!("Using cached response for {}", request.url());
+            return Ok(stored.clone().into_response());
+        }
+        Some(stored) => stored.add_validators(&mut request),
+        None if network != NetworkPolicy::Online => {
+            return Ok(unavailable(request.url()));
+        }
+        None => {}
+    }
+
+    let response = ratelimit::execute(client, request, settings.retry_budget()).await?;
+    if let (reqwest::StatusCode::NOT_MODIFIED, Some(mut stored)) = (response.status(), stored) {
+        log::debug!("Cached response for {} is still valid", response.url());
+        if let Err(e) = cache.refresh(&key, &mut stored) {
+            log::debug!("Unable to update HTTP cache: {}", e);
+        }
+        return Ok(stored.into_response());
+    }
+    if response.status() != reqwest::StatusCode::OK || !cache::may_store(response.headers()) {
+        return Ok(response);
+    }
+
+    let stored = cache::Stored::new(
+        response.url().clone(),
+        response.status().as_u16(),
+        &response.headers().clone(),
+        response.bytes().await?.to_vec(),
+    );
+    if let Err(e) = cache.put(&key, &stored) {
+        log::debug!("Unable to write to HTTP cache: {}", e);
+    }
+    Ok(stored.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_settings() {
+        assert_eq!(settings().network, NetworkPolicy::Online);
+        let network = with_settings(
+            RequestSettings {
+                network: NetworkPolicy::Offline,
+                ..Default::default()
+            },
+            async { settings().network },
+        )
+        .await;
+        assert_eq!(network, NetworkPolicy::Offline);
+    }
+
+    #[tokio::test]
+    async fn test_send_offline() {
+        let (url, mut requests) =
+            serve(|_, _| "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 0\r\n\r\n").await;
+        let client = reqwest::Client::new();
+        let settings = RequestSettings {
+            network: NetworkPolicy::Offline,
+            ..Default::default()
+        };
+        let response = with_settings(settings, send(client.get(url.clone())))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::GATEWAY_TIMEOUT);
+        let statuses = stream_with_settings(
+            settings,
+            futures::stream::once(send(client.get(url.clone()))),
+        )
+        .map(|r| r.unwrap().status())
+        .collect::<Vec<_>>()
+        .await;
+        assert_eq!(statuses, vec![reqwest::StatusCode::GATEWAY_TIMEOUT]);
+        assert!(requests.try_recv().is_err());
+
+        let response = send(client.get(url)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(requests.recv().await.unwrap(), "HTTP/1.1 200 OK");
+    }
+
+    #[test]
+    fn test_cache_mode_from_env() {
+        assert_eq!(CacheMode::parse_env(None), CacheMode::Disabled);
+        assert_eq!(CacheMode::parse_env(Some("0")), CacheMode::Disabled);
+        assert_eq!(
+            CacheMode::parse_env(Some("1")).ttl(),
+            Some(DEFAULT_CACHE_TTL)
+        );
+        assert_eq!(
+            CacheMode::parse_env(Some("600")).ttl(),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(CacheMode::parse_env(Some("often")), CacheMode::Disabled);
+    }
+
+    /// Serve the responses returned by `respond` for the number of the request and the
+    /// lowercased request.
+    ///
+    /// Returns the URL and a receiver for the status lines of the responses that were sent.
+    pub(super) async fn serve(
+        respond: fn(usize, &str) -> &'static str,
+    ) -> (url::Url, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/data.json", listener.local_addr().unwrap());
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut i = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let response = respond(i, &String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                i += 1;
+                tx.send(response.lines().next().unwrap().to_string())
+                    .unwrap();
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url.parse().unwrap(), rx)
+    }
+
+    #[cfg(feature = "http-cache")]
+    #[tokio::test]
+    async fn test_send_cached() {
+        let td = tempfile::tempdir().unwrap();
+        let cache = cache::Cache::new(td.path().to_path_buf());
+        // Serve a response with an ETag, and "304 Not Modified" to requests that have it
+        let (url, mut requests) = serve(|_, request| {
+            if request.contains("if-none-match: \"v1\"") {
+                "HTTP/1.1 304 Not Modified\r\nconnection: close\r\netag: \"v1\"\r\ncontent-length: 0\r\n\r\n"
+            } else {
+                "HTTP/1.1 200 OK\r\nconnection: close\r\netag: \"v1\"\r\ncontent-type: application/json\r\ncontent-length: 11\r\n\r\n{\"a\": true}"
+            }
+        })
+        .await;
+        let client = reqwest::Client::new();
+        let fetch = |ttl, network| {
+            let request = client.get(url.clone()).build().unwrap();
+            let (client, cache) = (&client, &cache);
+            async move {
+                let settings = RequestSettings {
+                    network,
+                    ..Default::default()
+                };
+                let response = send_cached(client, request, cache, ttl, &settings)
+                    .await
+                    .unwrap();
+                (response.status().as_u16(), response.text().await.unwrap())
+            }
+        };
+        let body = "{\"a\": true}".to_string();
+
+        assert_eq!(
+            fetch(Duration::ZERO, NetworkPolicy::Online).await,
+            (200, body.clone())
+        );
+        assert_eq!(requests.recv().await.unwrap(), "HTTP/1.1 200 OK");
+
+        // Stale responses are revalidated
+        assert_eq!(
+            fetch(Duration::ZERO, NetworkPolicy::Online).await,
+            (200, body.clone())
+        );
+        assert_eq!(requests.recv().await.unwrap(), "HTTP/1.1 304 Not Modified");
+
+        // Fresh responses, and any stored responses when the network can't be used, are
+        // returned without contacting the server
+        assert_eq!(
+            fetch(DEFAULT_CACHE_TTL, NetworkPolicy::Online).await,
+            (200, body.clone())
+        );
+        assert_eq!(
+            fetch(Duration::ZERO, NetworkPolicy::CacheOnly).await,
+            (200, body)
+        );
+        assert!(requests.try_recv().is_err());
+
+        let request = client.get(url.join("other").unwrap()).build().unwrap();
+        let settings = RequestSettings {
+            network: NetworkPolicy::CacheOnly,
+            ..Default::default()
+        };
+        let response = send_cached(&client, request, &cache, DEFAULT_CACHE_TTL, &settings)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::GATEWAY_TIMEOUT);
+    }
+}
@@ -0,0 +1,229 @@
+//! Per-host rate limiting, and retrying of requests that were rate limited.
+//!
+//! When a server responds with "429 Too Many Requests", or with "403 Forbidden" and either
+//! `Retry-After` or `X-RateLimit-Remaining: 0` like GitHub and GitLab do, the request is
+//! retried once the server allows it again, as long as that is within the retry budget. Later
+//! requests to the same host wait until then as well, rather than being rejected again.
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{ResponseBuilderExt, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Maximum number of times a request is retried.
+const MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry, if the server doesn't say how long to wait.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Hosts that asked not to be contacted until a specific time.
+fn blocked() -> &'static Mutex<HashMap<String, Instant>> {
+    static BLOCKED: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+    BLOCKED.get_or_init(Default::default)
+}
+
+fn host_key(url: &url::Url) -> Option<String> {
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default().unwrap_or(0)
+    ))
+}
+
+/// How long to wait before sending requests to a host.
+fn blocked_for(host: &str) -> Option<Duration> {
+    let mut blocked = blocked().lock().unwrap();
+    let until = *blocked.get(host)?;
+    let now = Instant::now();
+    if until <= now {
+        blocked.remove(host);
+        None
+    } else {
+        Some(until - now)
+    }
+}
+
+fn block(host: &str, delay: Duration) {
+    let until = Instant::now() + delay;
+    let mut blocked = blocked().lock().unwrap();
+    let entry = blocked.entry(host.to_string()).or_insert(until);
+    *entry = (*entry).max(until);
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok().map(str::trim)
+}
+
+/// If a response indicates that the client is rate limited, how long to wait before retrying.
+///
+/// `attempt` is the number of retries so far, and determines the delay if the server doesn't
+/// specify one.
+fn rate_limit_delay(status: StatusCode, headers: &HeaderMap, attempt: u32) -> Option<Duration> {
+    let retry_after = header(headers, RETRY_AFTER.as_str());
+    let exhausted = header(headers, "x-ratelimit-remaining") == Some("0")
+        || header(headers, "ratelimit-remaining") == Some("0");
+    let rate_limited = status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && (exhausted || retry_after.is_some()));
+    if !rate_limited {
+        return None;
+    }
+    if let Some(secs) = retry_after.and_then(|v| v.parse().ok()) {
+        return Some(Duration::from_secs(secs));
+    }
+    // GitHub uses X-RateLimit-Reset and GitLab RateLimit-Reset, both in seconds since the
+    // epoch
+    let reset = header(headers, "x-ratelimit-reset")
+        .or_else(|| header(headers, "ratelimit-reset"))
+        .and_then(|v| v.parse::<u64>().ok());
+    if let Some(reset) = reset {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        return Some(Duration::from_secs(reset.saturating_sub(now)));
+    }
+    Some(INITIAL_BACKOFF * 2u32.pow(attempt))
+}
+
+/// The response for requests to hosts that are known to reject them at the moment.
+fn rate_limited(url: &url::Url, delay: Duration) -> reqwest::Response {
+    http::Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header(RETRY_AFTER, delay.as_secs().max(1))
+        .url(url.clone())
+        .body(Vec::new())
+        .unwrap()
+        .into()
+}
+
+/// Send a request, retrying it if it was rate limited.
+///
+/// At most `budget` is spent waiting for the server to allow requests again. If the server
+/// asks for longer than that, the rate limited response is returned.
+pub(crate) async fn execute(
+    client: &reqwest::Client,
+    mut request: reqwest::Request,
+    budget: Duration,
+) -> reqwest::Result<reqwest::Response> {
+    let Some(host) = host_key(request.url()) else {
+        return client.execute(request).await;
+    };
+    let mut spent = Duration::ZERO;
+    let mut attempt = 0;
+    loop {
+        if let Some(delay) = blocked_for(&host) {
+            if spent + delay > budget {
+                log::debug!("Not sending request to rate limited host {}", host);
+                return Ok(rate_limited(request.url(), delay));
+            }
+            tokio::time::sleep(delay).await;
+            spent += delay;
+        }
+
+        let retry = request.try_clone();
+        let response = client.execute(request).await?;
+        let Some(delay) = rate_limit_delay(response.status(), response.headers(), attempt) else {
+            return Ok(response);
+        };
+        block(&host, delay);
+        match retry {
+            Some(retry) if attempt < MAX_RETRIES && spent + delay <= budget => {
+                log::info!("Rate limited by {}, retrying in {}s", host, delay.as_secs());
+                request = retry;
+                attempt += 1;
+            }
+            _ => {
+                log::warn!(
+                    "Rate limited by {}, not retrying for {}s",
+                    host,
+                    delay.as_secs()
+                );
+                return Ok(response);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(values: &[(&str, &str)]) -> HeaderMap {
+        values
+            .iter()
+            .map(|(n, v)| (n.parse().unwrap(), v.parse().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_rate_limit_delay() {
+        assert_eq!(
+            rate_limit_delay(StatusCode::OK, &headers(&[("retry-after", "5")]), 0),
+            None
+        );
+        assert_eq!(
+            rate_limit_delay(StatusCode::FORBIDDEN, &headers(&[]), 0),
+            None
+        );
+        assert_eq!(
+            rate_limit_delay(
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers(&[("retry-after", "5")]),
+                0
+            ),
+            Some(Duration::from_secs(5))
+        );
+        let reset = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + 60;
+        let delay = rate_limit_delay(
+            StatusCode::FORBIDDEN,
+            &headers(&[
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", &reset.to_string()),
+            ]),
+            0,
+        )
+        .unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60));
+        assert_eq!(
+            rate_limit_delay(StatusCode::TOO_MANY_REQUESTS, &headers(&[]), 2),
+            Some(Duration::from_secs(4))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_execute() {
+        let (url, mut responses) = super::super::tests::serve(|i, _| {
+            if i == 0 {
+                "HTTP/1.1 429 Too Many Requests\r\nconnection: close\r\nretry-after: 0\r\ncontent-length: 0\r\n\r\n"
+            } else if i == 1 {
+                "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 2\r\n\r\nok"
+            } else {
+                "HTTP/1.1 429 Too Many Requests\r\nconnection: close\r\nretry-after: 3600\r\ncontent-length: 0\r\n\r\n"
+            }
+        })
+        .await;
+        let client = reqwest::Client::new();
+        let fetch = |budget| execute(&client, client.get(url.clone()).build().unwrap(), budget);
+
+        let response = fetch(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(response.text().await.unwrap(), "ok");
+        assert_eq!(
+            responses.recv().await.unwrap(),
+            "HTTP/1.1 429 Too Many Requests"
+        );
+        assert_eq!(responses.recv().await.unwrap(), "HTTP/1.1 200 OK");
+
+        // Waiting for an hour is over budget
+        let response = fetch(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(responses.recv().await.is_some());
+
+        // The host isn't contacted again until it allows requests
+        let response = fetch(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(responses.try_recv().is_err());
+    }
+}
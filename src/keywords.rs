@@ -0,0 +1,305 @@
+//! Mapping of free-form keywords to and from controlled vocabularies.
+//!
+//! Keywords are collected from many sources and are free-form. Some consumers need terms
+//! from a controlled vocabulary instead, such as
+//! [AppStream categories](https://specifications.freedesktop.org/menu-spec/latest/category-registry.html)
+//! or [debtags](https://wiki.debian.org/Debtags). The mapping is necessarily lossy: keywords
+//! that have no equivalent in the vocabulary are dropped.
+
+/// A controlled vocabulary that keywords can be mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Vocabulary {
+    /// AppStream (freedesktop.org menu) category IDs, e.g. "Game"
+    AppStream,
+    /// Debian tags, e.g. "use::gameplaying"
+    Debtags,
+}
+
+impl std::fmt::Display for Vocabulary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Vocabulary::AppStream => write!(f, "appstream"),
+            Vocabulary::Debtags => write!(f, "debtags"),
+        }
+    }
+}
+
+impl std::str::FromStr for Vocabulary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "appstream" => Ok(Vocabulary::AppStream),
+            "debtags" => Ok(Vocabulary::Debtags),
+            _ => Err(format!("unknown vocabulary: {}", s)),
+        }
+    }
+}
+
+struct KeywordMapping {
+    /// Keywords that map to the terms below; the first is used when mapping back
+    keywords: &'static [&'static str],
+    appstream: Option<&'static str>,
+    debtags: Option<&'static str>,
+}
+
+impl KeywordMapping {
+    fn term(&self, vocabulary: Vocabulary) -> Option<&'static str> {
+        match vocabulary {
+            Vocabulary::AppStream => self.appstream,
+            Vocabulary::Debtags => self.debtags,
+        }
+    }
+}
+
+const fn mapping(
+    keywords: &'static [&'static str],
+    appstream: Option<&'static str>,
+    debtags: Option<&'static str>,
+) -> KeywordMapping {
+    KeywordMapping {
+        keywords,
+        appstream,
+        debtags,
+    }
+}
+
+const MAPPINGS: &[KeywordMapping] = &[
+    mapping(
+        &["game", "games", "gaming"],
+        Some("Game"),
+        Some("use::gameplaying"),
+    ),
+    mapping(&["education", "learning"], Some("Education"), None),
+    mapping(&["science", "scientific"], Some("Science"), None),
+    mapping(
+        &["mathematics", "math", "maths"],
+        Some("Math"),
+        Some("field::mathematics"),
+    ),
+    mapping(&["statistics"], None, Some("field::statistics")),
+    mapping(&["astronomy"], Some("Astronomy"), Some("field::astronomy")),
+    mapping(&["physics"], Some("Physics"), Some("field::physics")),
+    mapping(&["chemistry"], Some("Chemistry"), Some("field::chemistry")),
+    mapping(
+        &["biology", "bioinformatics"],
+        Some("Biology"),
+        Some("field::biology"),
+    ),
+    mapping(
+        &["geography", "gis"],
+        Some("Geography"),
+        Some("field::geography"),
+    ),
+    mapping(&["maps"], Some("Maps"), None),
+    mapping(
+        &["finance", "accounting"],
+        Some("Finance"),
+        Some("field::finance"),
+    ),
+    mapping(&["office", "productivity"], Some("Office"), None),
+    mapping(&["spreadsheet"], Some("Spreadsheet"), None),
+    mapping(&["word-processor"], Some("WordProcessor"), None),
+    mapping(&["development", "programming"], Some("Development"), None),
+    mapping(&["ide"], Some("IDE"), Some("devel::ide")),
+    mapping(
+        &["text-editor", "editor"],
+        Some("TextEditor"),
+        Some("devel::editor"),
+    ),
+    mapping(
+        &["debugger", "debugging"],
+        Some("Debugger"),
+        Some("devel::debugger"),
+    ),
+    mapping(&["compiler"], None, Some("devel::compiler")),
+    mapping(
+        &["build-tool", "build-system"],
+        Some("Building"),
+        Some("devel::buildtools"),
+    ),
+    mapping(
+        &["version-control", "vcs"],
+        Some("RevisionControl"),
+        Some("devel::rcs"),
+    ),
+    mapping(&["testing"], None, Some("devel::testing-qa")),
+    mapping(&["library"], None, Some("devel::library")),
+    mapping(
+        &["documentation"],
+        Some("Documentation"),
+        Some("devel::doc"),
+    ),
+    mapping(
+        &["i18n", "localization", "translation"],
+        Some("Translation"),
+        Some("devel::i18n"),
+    ),
+    mapping(&["database"], Some("Database"), Some("works-with::db")),
+    mapping(&["graphics"], Some("Graphics"), Some("works-with::image")),
+    mapping(&["3d"], Some("3DGraphics"), None),
+    mapping(&["photography", "photo"], Some("Photography"), None),
+    mapping(
+        &["audio", "sound"],
+        Some("Audio"),
+        Some("works-with::audio"),
+    ),
+    mapping(&["music"], Some("Music"), None),
+    mapping(&["video"], Some("Video"), Some("works-with::video")),
+    mapping(&["multimedia"], Some("AudioVideo"), None),
+    mapping(&["network", "networking"], Some("Network"), None),
+    mapping(
+        &["web-browser", "browser"],
+        Some("WebBrowser"),
+        Some("web::browser"),
+    ),
+    mapping(&["email", "mail"], Some("Email"), Some("mail::user-agent")),
+    mapping(&["chat", "messaging"], Some("Chat"), None),
+    mapping(&["irc"], Some("IRCClient"), Some("protocol::irc")),
+    mapping(&["p2p", "peer-to-peer"], Some("P2P"), None),
+    mapping(&["security"], Some("Security"), None),
+    mapping(
+        &["cryptography", "crypto", "encryption"],
+        None,
+        Some("security::cryptography"),
+    ),
+    mapping(&["authentication"], None, Some("security::authentication")),
+    mapping(&["firewall"], None, Some("security::firewall")),
+    mapping(&["emulator", "emulation"], Some("Emulator"), None),
+    mapping(
+        &["terminal", "terminal-emulator"],
+        Some("TerminalEmulator"),
+        Some("x11::terminal"),
+    ),
+    mapping(&["file-manager"], Some("FileManager"), None),
+    mapping(
+        &["archive", "compression"],
+        Some("Archiving"),
+        Some("works-with::archive"),
+    ),
+    mapping(&["monitoring"], Some("Monitor"), Some("admin::monitoring")),
+    mapping(
+        &["package-manager", "packaging"],
+        Some("PackageManager"),
+        Some("admin::package-management"),
+    ),
+    mapping(&["backup"], None, Some("admin::backup")),
+    mapping(&["accessibility", "a11y"], Some("Accessibility"), None),
+    mapping(&["system"], Some("System"), None),
+    mapping(
+        &["utility", "utilities"],
+        Some("Utility"),
+        Some("scope::utility"),
+    ),
+    mapping(
+        &["cli", "command-line"],
+        None,
+        Some("interface::commandline"),
+    ),
+];
+
+/// Normalize a keyword for lookup, so that e.g. "Text Editor" and "text_editor" match.
+fn normalize(keyword: &str) -> String {
+    keyword
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Map free-form keywords to terms from a controlled vocabulary.
+///
+/// Keywords without an equivalent in the vocabulary are dropped; the order of the first
+/// occurrence of each term is preserved.
+pub fn keywords_as<S: AsRef<str>>(keywords: &[S], vocabulary: Vocabulary) -> Vec<String> {
+    let mut ret: Vec<String> = vec![];
+    for keyword in keywords {
+        let keyword = normalize(keyword.as_ref());
+        let term = MAPPINGS
+            .iter()
+            .find(|m| m.keywords.contains(&keyword.as_str()))
+            .and_then(|m| m.term(vocabulary));
+        if let Some(term) = term {
+            if !ret.iter().any(|t| t == term) {
+                ret.push(term.to_string());
+            }
+        }
+    }
+    ret
+}
+
+/// Map terms from a controlled vocabulary back to free-form keywords.
+///
+/// Terms that are not known are dropped.
+pub fn keywords_from<S: AsRef<str>>(terms: &[S], vocabulary: Vocabulary) -> Vec<String> {
+    let mut ret: Vec<String> = vec![];
+    for term in terms {
+        let keyword = MAPPINGS
+            .iter()
+            .find(|m| m.term(vocabulary) == Some(term.as_ref().trim()))
+            .map(|m| m.keywords[0]);
+        if let Some(keyword) = keyword {
+            if !ret.iter().any(|k| k == keyword) {
+                ret.push(keyword.to_string());
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords_as() {
+        let keywords = ["Games", "text_editor", "rust", "Text Editor", "VCS"];
+        assert_eq!(
+            keywords_as(&keywords, Vocabulary::AppStream),
+            vec!["Game", "TextEditor", "RevisionControl"]
+        );
+        assert_eq!(
+            keywords_as(&keywords, Vocabulary::Debtags),
+            vec!["use::gameplaying", "devel::editor", "devel::rcs"]
+        );
+        assert_eq!(
+            keywords_as(&["education"], Vocabulary::Debtags),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_keywords_from() {
+        assert_eq!(
+            keywords_from(&["Game", "Unknown", "TextEditor"], Vocabulary::AppStream),
+            vec!["game", "text-editor"]
+        );
+        assert_eq!(
+            keywords_from(&["devel::rcs", "role::program"], Vocabulary::Debtags),
+            vec!["version-control"]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for m in MAPPINGS {
+            for vocabulary in [Vocabulary::AppStream, Vocabulary::Debtags] {
+                if let Some(term) = m.term(vocabulary) {
+                    assert_eq!(
+                        keywords_as(&keywords_from(&[term], vocabulary), vocabulary),
+                        vec![term]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_vocabulary_from_str() {
+        assert_eq!("debtags".parse(), Ok(Vocabulary::Debtags));
+        assert_eq!(Vocabulary::AppStream.to_string(), "appstream");
+        assert!("foo".parse::<Vocabulary>().is_err());
+    }
+}
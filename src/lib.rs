@@ -1,7 +1,32 @@
+//! Gather metadata about upstream software projects.
+//!
+//! # API stability
+//!
+//! The public API is split into two tiers:
+//!
+//! * **Stable**: the items at the top of the crate, such as [`UpstreamDatum`],
+//!   [`UpstreamMetadata`], [`Certainty`], [`Origin`], [`Person`] and the functions that guess,
+//!   check and fix metadata; their serialized form; and the [`check`], [`dep12`],
+//!   [`descriptors`], [`fieldset`], [`license`], [`names`], [`rdf`], [`registry`], [`vcs`]
+//!   and [`version`] modules. These follow [SemVer](https://semver.org/), which is checked with
+//!   [cargo-semver-checks](https://github.com/obi1kenobi/cargo-semver-checks) before every
+//!   release.
+//! * **Experimental**: all other modules, including the individual providers and forges.
+//!   These may change in any release. They are hidden from the SemVer check by building it
+//!   with `--cfg semver_checks`.
+//!
+//! Items that are only available with some cargo features are marked as such in the
+//! documentation.
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+#[cfg(feature = "providers")]
 use futures::stream::StreamExt;
+#[cfg(feature = "providers")]
 use futures::Stream;
 use lazy_regex::regex;
+#[cfg(feature = "net")]
 use log::{debug, warn};
+#[cfg(feature = "net")]
 use percent_encoding::utf8_percent_encode;
 #[cfg(feature = "pyo3")]
 use pyo3::{
@@ -9,28 +34,91 @@ use pyo3::{
     prelude::*,
     types::PyDict,
 };
-use reqwest::header::HeaderMap;
 use serde::ser::SerializeSeq;
+#[cfg(feature = "opam")]
 use std::cmp::Ordering;
+#[cfg(feature = "providers")]
 use std::pin::Pin;
 use std::str::FromStr;
 
+#[cfg(feature = "providers")]
 use std::fs::File;
+#[cfg(feature = "providers")]
 use std::io::Read;
-use std::path::{Path, PathBuf};
+#[cfg(feature = "providers")]
+use std::path::Path;
+use std::path::PathBuf;
 use url::Url;
 
+#[cfg(feature = "net")]
 static USER_AGENT: &str = concat!("upstream-ontologist/", env!("CARGO_PKG_VERSION"));
 
+#[cfg(feature = "providers")]
+pub mod check;
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod data;
+pub mod dep12;
+pub mod descriptors;
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod dirs;
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod download;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod events;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod extrapolate;
+pub mod fieldset;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod fixture;
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod forges;
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod git_web;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod homepage;
+#[cfg(feature = "net")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod http;
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod keywords;
+pub mod license;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod listing;
+pub mod names;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod providers;
+pub mod rdf;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod readme;
+#[cfg(feature = "providers")]
+pub mod registry;
+#[cfg(feature = "providers")]
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod repology;
 pub mod vcs;
+#[cfg_attr(semver_checks, doc(hidden))]
 pub mod vcs_command;
+pub mod version;
+#[cfg(feature = "watch")]
+#[cfg_attr(semver_checks, doc(hidden))]
+pub mod watch;
+
+#[cfg(feature = "providers")]
+pub use check::{CheckIssue, CheckPolicy, CheckReport};
+#[cfg(feature = "providers")]
+pub use descriptors::providers;
+pub use descriptors::{supported_fields, FieldDescriptor, ProviderDescriptor};
+pub use fieldset::FieldSet;
+pub use forges::registry::ForgeRegistry;
+#[cfg(feature = "providers")]
+pub use registry::GuesserRegistry;
 
 #[cfg(test)]
 mod upstream_tests {
@@ -50,11 +138,60 @@ pub enum Certainty {
     Certain,
 }
 
+/// How to resolve conflicting values in [`UpstreamMetadata::merge`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the value with the higher certainty, and the existing value on ties
+    #[default]
+    PreferHigherCertainty,
+    /// Keep the value with the higher certainty, and the merged value on ties
+    PreferOtherOnTie,
+    /// Only add fields that are missing
+    FillMissing,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Origin {
     Path(PathBuf),
     Url(url::Url),
     Other(String),
+    /// The same value was found or confirmed in several places
+    ///
+    /// The origins are in the order in which they were found, so the first is the place the
+    /// value was originally found and the others are the sources that confirmed it.
+    Multiple(Vec<Origin>),
+}
+
+impl Origin {
+    /// Iterate over the individual origins in the chain.
+    ///
+    /// This yields just `self` for anything but [`Origin::Multiple`].
+    pub fn iter(&self) -> std::slice::Iter<'_, Origin> {
+        match self {
+            Origin::Multiple(origins) => origins.iter(),
+            origin => std::slice::from_ref(origin).iter(),
+        }
+    }
+
+    /// Combine two origins of the same value.
+    pub fn combine(a: Option<Origin>, b: Option<Origin>) -> Option<Origin> {
+        let flatten = |o: Option<Origin>| match o {
+            Some(Origin::Multiple(origins)) => origins,
+            Some(o) => vec![o],
+            None => vec![],
+        };
+        let mut origins = flatten(a);
+        for origin in flatten(b) {
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        match origins.len() {
+            0 => None,
+            1 => origins.pop(),
+            _ => Some(Origin::Multiple(origins)),
+        }
+    }
 }
 
 impl std::fmt::Display for Origin {
@@ -63,6 +200,15 @@ impl std::fmt::Display for Origin {
             Origin::Path(path) => write!(f, "{}", path.display()),
             Origin::Url(url) => write!(f, "{}", url),
             Origin::Other(s) => write!(f, "{}", s),
+            Origin::Multiple(origins) => write!(
+                f,
+                "{}",
+                origins
+                    .iter()
+                    .map(|o| o.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
@@ -85,6 +231,19 @@ impl From<url::Url> for Origin {
     }
 }
 
+/// Origins serialize as a string, or as a list of strings for [`Origin::Multiple`].
+impl serde::ser::Serialize for Origin {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        match self {
+            Origin::Multiple(origins) => serializer.collect_seq(origins),
+            origin => serializer.collect_str(origin),
+        }
+    }
+}
+
 #[cfg(feature = "pyo3")]
 impl ToPyObject for Origin {
     fn to_object(&self, py: Python) -> PyObject {
@@ -92,6 +251,7 @@ impl ToPyObject for Origin {
             Origin::Path(path) => path.to_str().unwrap().to_object(py),
             Origin::Url(url) => url.to_string().to_object(py),
             Origin::Other(s) => s.to_object(py),
+            Origin::Multiple(origins) => origins.to_object(py),
         }
     }
 }
@@ -103,6 +263,7 @@ impl IntoPy<PyObject> for Origin {
             Origin::Path(path) => path.to_str().unwrap().to_object(py),
             Origin::Url(url) => url.to_string().to_object(py),
             Origin::Other(s) => s.to_object(py),
+            Origin::Multiple(origins) => origins.to_object(py),
         }
     }
 }
@@ -110,12 +271,21 @@ impl IntoPy<PyObject> for Origin {
 #[cfg(feature = "pyo3")]
 impl FromPyObject<'_> for Origin {
     fn extract_bound(ob: &Bound<PyAny>) -> PyResult<Self> {
-        if let Ok(path) = ob.extract::<PathBuf>() {
+        if let Some(url) = ob
+            .extract::<String>()
+            .ok()
+            .filter(|s| s.contains("://"))
+            .and_then(|s| url::Url::parse(&s).ok())
+        {
+            Ok(Origin::Url(url))
+        } else if let Ok(path) = ob.extract::<PathBuf>() {
             Ok(Origin::Path(path))
         } else if let Ok(s) = ob.extract::<String>() {
             Ok(Origin::Other(s))
+        } else if let Ok(origins) = ob.extract::<Vec<Origin>>() {
+            Ok(Origin::Multiple(origins))
         } else {
-            Err(PyTypeError::new_err("expected str or Path"))
+            Err(PyTypeError::new_err("expected str, Path or list"))
         }
     }
 }
@@ -238,6 +408,44 @@ impl<'a> serde::de::Deserialize<'a> for Person {
     }
 }
 
+/// Suffixes that mark a name as belonging to a legal entity rather than a person.
+const ORGANIZATION_SUFFIXES: &[&str] = &[
+    "inc",
+    "llc",
+    "ltd",
+    "limited",
+    "gmbh",
+    "ag",
+    "corp",
+    "corporation",
+    "foundation",
+    "b.v",
+    "bv",
+    "s.a",
+    "sas",
+    "co",
+    "plc",
+    "oy",
+    "ab",
+    "e.v",
+];
+
+impl Person {
+    /// Check whether this "person" is actually an organization, e.g. "Example, Inc.".
+    pub fn is_organization(&self) -> bool {
+        let name = match self.name.as_ref() {
+            Some(name) => name.trim().trim_end_matches('.').to_lowercase(),
+            None => return false,
+        };
+        match name.rsplit_once([' ', ',']) {
+            Some((rest, suffix)) => {
+                !rest.trim().is_empty() && ORGANIZATION_SUFFIXES.contains(&suffix)
+            }
+            None => false,
+        }
+    }
+}
+
 impl std::fmt::Display for Person {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name.as_ref().unwrap_or(&"".to_string()))?;
@@ -358,22 +566,28 @@ impl FromPyObject<'_> for Person {
 pub enum UpstreamDatum {
     /// Name of the project
     Name(String),
+    /// Other names the project is known under, e.g. packaging names
+    Aliases(Vec<String>),
     /// URL to project homepage
     Homepage(String),
     /// URL to the project's source code repository
     Repository(String),
     /// URL to browse the project's source code repository
     RepositoryBrowse(String),
+    /// URL of a mirror of the repository
+    RepositoryMirror(String),
     /// Long description of the project
     Description(String),
     /// Short summary of the project (one line)
     Summary(String),
-    /// License name or SPDX identifier
-    License(String),
+    /// License, as stated upstream and normalized to an SPDX expression
+    License(license::License),
     /// List of authors
     Author(Vec<Person>),
     /// List of maintainers
     Maintainer(Person),
+    /// Organization that develops or backs the project
+    Organization(String),
     /// URL of the project's issue tracker
     BugDatabase(String),
     /// URL to submit a new bug
@@ -386,6 +600,10 @@ pub enum UpstreamDatum {
     SecurityMD(String),
     /// URL to the security page or email address
     SecurityContact(String),
+    /// Path to (or URL of) the code of conduct
+    CodeOfConduct(String),
+    /// Path to (or URL of) the contribution guidelines
+    Contributing(String),
     /// Last version of the project
     Version(String),
     /// List of keywords
@@ -400,12 +618,20 @@ pub enum UpstreamDatum {
     GoImportPath(String),
     /// URL to the project's download page
     Download(String),
+    /// URL to a binary (non-source) download, such as an installer or a CI artifact
+    BinaryDownload(String),
     /// URL to the project's wiki
     Wiki(String),
     /// URL to the project's mailing list
     MailingList(String),
+    /// URL of a chat channel, e.g. an IRC channel, Matrix room or Discord invite
+    Chat(String),
+    /// URL of the project on a translation platform, e.g. Weblate or Transifex
+    Translations(String),
     /// SourceForge project name
     SourceForgeProject(String),
+    /// Subproject within the SourceForge project, e.g. a separate repository or files directory
+    SourceForgeSubproject(String),
     Archive(String),
     /// URL to a demo instance
     Demo(String),
@@ -415,6 +641,8 @@ pub enum UpstreamDatum {
     Funding(String),
     /// URL to the changelog
     Changelog(String),
+    /// Maintenance status as reported by a registry, e.g. "deprecated" or "yanked", optionally followed by a colon and an explanation
+    MaintenanceStatus(String),
     /// Haskell package name
     HaskellPackage(String),
     /// Debian ITP (Intent To Package) bug number
@@ -447,7 +675,236 @@ fn known_bad_url(value: &str) -> bool {
     false
 }
 
+/// Pastebins and code snippet hosts, which are almost never the upstream of a project.
+///
+/// Each rule is a host and optionally a string that the path has to contain.
+const SNIPPET_URL_RULES: &[(&str, Option<&str>)] = &[
+    ("gist.github.com", None),
+    ("gist.githubusercontent.com", None),
+    ("bitbucket.org", Some("/snippets/")),
+    ("gitlab.com", Some("/snippets/")),
+    ("pastebin.com", None),
+    ("paste.debian.net", None),
+    ("paste.ubuntu.com", None),
+    ("dpaste.com", None),
+    ("dpaste.org", None),
+    ("hastebin.com", None),
+    ("termbin.com", None),
+    ("bpa.st", None),
+    ("paste.rs", None),
+    ("ix.io", None),
+    ("sprunge.us", None),
+];
+
+fn is_snippet_url(url: &Url) -> bool {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    SNIPPET_URL_RULES.iter().any(|(rule_host, path)| {
+        *rule_host == host && path.is_none_or(|path| url.path().contains(path))
+    })
+}
+
+/// Chat services, in the same format as [`SNIPPET_URL_RULES`].
+const CHAT_URL_RULES: &[(&str, Option<&str>)] = &[
+    ("matrix.to", None),
+    ("app.element.io", None),
+    ("discord.gg", None),
+    ("discord.com", Some("/invite/")),
+    ("discordapp.com", Some("/invite/")),
+    ("gitter.im", None),
+    ("app.gitter.im", None),
+    ("join.slack.com", None),
+    ("web.libera.chat", None),
+    ("webchat.oftc.net", None),
+    ("t.me", None),
+    ("zulip.com", None),
+];
+
+/// Check whether a URL points at a chat channel, such as an IRC channel or Matrix room.
+pub fn is_chat_url(url: &Url) -> bool {
+    if matches!(url.scheme(), "irc" | "ircs" | "matrix" | "xmpp") {
+        return true;
+    }
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    // Per-project Slack workspaces and Zulip instances
+    if host.ends_with(".slack.com") || host.ends_with(".zulipchat.com") {
+        return true;
+    }
+    CHAT_URL_RULES.iter().any(|(rule_host, path)| {
+        *rule_host == host && path.is_none_or(|path| url.path().contains(path))
+    })
+}
+
+/// Translation platforms, in the same format as [`SNIPPET_URL_RULES`].
+const TRANSLATION_URL_RULES: &[(&str, Option<&str>)] = &[
+    ("hosted.weblate.org", Some("/engage/")),
+    ("hosted.weblate.org", Some("/projects/")),
+    ("app.transifex.com", None),
+    ("transifex.com", Some("/projects/")),
+    ("crowdin.com", Some("/project/")),
+    ("translate.fedoraproject.org", Some("/projects/")),
+    ("l10n.gnome.org", Some("/module/")),
+    ("translations.launchpad.net", None),
+    ("poeditor.com", Some("/join/")),
+];
+
+/// Check whether a URL points at a project on a translation platform.
+pub fn is_translation_url(url: &Url) -> bool {
+    let host = match url.host_str() {
+        Some(host) => host,
+        None => return false,
+    };
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    TRANSLATION_URL_RULES.iter().any(|(rule_host, path)| {
+        *rule_host == host && path.is_none_or(|path| url.path().starts_with(path))
+    })
+}
+
 impl UpstreamDatum {
+    /// Construct a datum from the name of a field and its value.
+    ///
+    /// Values are converted where that is unambiguous, e.g. a string for Maintainer is
+    /// parsed as a person and a single string for Keywords becomes a list.
+    pub fn from_field_str(field: &str, value: DatumValue) -> Result<Self, UnknownField> {
+        let field = descriptors::field_descriptor(field)
+            .map(|d| d.name)
+            .ok_or_else(|| UnknownField::Field(field.to_string()))?;
+        match field {
+            "Summary" => value.into_string().map(UpstreamDatum::Summary),
+            "Description" => value.into_string().map(UpstreamDatum::Description),
+            "Name" => value.into_string().map(UpstreamDatum::Name),
+            "Homepage" => value.into_string().map(UpstreamDatum::Homepage),
+            "Repository" => value.into_string().map(UpstreamDatum::Repository),
+            "Repository-Browse" => value.into_string().map(UpstreamDatum::RepositoryBrowse),
+            "License" => value
+                .into_string()
+                .map(|s| UpstreamDatum::License(s.into())),
+            "Author" => value.into_people().map(UpstreamDatum::Author),
+            "Bug-Database" => value.into_string().map(UpstreamDatum::BugDatabase),
+            "Bug-Submit" => value.into_string().map(UpstreamDatum::BugSubmit),
+            "Contact" => value.into_string().map(UpstreamDatum::Contact),
+            "Organization" => value.into_string().map(UpstreamDatum::Organization),
+            "Cargo-Crate" => value.into_string().map(UpstreamDatum::CargoCrate),
+            "Security-MD" => value.into_string().map(UpstreamDatum::SecurityMD),
+            "Security-Contact" => value.into_string().map(UpstreamDatum::SecurityContact),
+            "Code-Of-Conduct" => value.into_string().map(UpstreamDatum::CodeOfConduct),
+            "Contributing" => value.into_string().map(UpstreamDatum::Contributing),
+            "Version" => value.into_string().map(UpstreamDatum::Version),
+            "Keywords" => value.into_strings().map(UpstreamDatum::Keywords),
+            "Aliases" => value.into_strings().map(UpstreamDatum::Aliases),
+            "Maintainer" => value.into_person().map(UpstreamDatum::Maintainer),
+            "Copyright" => value.into_string().map(UpstreamDatum::Copyright),
+            "Documentation" => value.into_string().map(UpstreamDatum::Documentation),
+            "API-Documentation" => value.into_string().map(UpstreamDatum::APIDocumentation),
+            "Go-Import-Path" => value.into_string().map(UpstreamDatum::GoImportPath),
+            "Download" => value.into_string().map(UpstreamDatum::Download),
+            "Binary-Download" => value.into_string().map(UpstreamDatum::BinaryDownload),
+            "Wiki" => value.into_string().map(UpstreamDatum::Wiki),
+            "Repository-Mirror" => value.into_string().map(UpstreamDatum::RepositoryMirror),
+            "MailingList" => value.into_string().map(UpstreamDatum::MailingList),
+            "Chat" => value.into_string().map(UpstreamDatum::Chat),
+            "Translations" => value.into_string().map(UpstreamDatum::Translations),
+            "SourceForge-Project" => value.into_string().map(UpstreamDatum::SourceForgeProject),
+            "SourceForge-Subproject" => value
+                .into_string()
+                .map(UpstreamDatum::SourceForgeSubproject),
+            "Archive" => value.into_string().map(UpstreamDatum::Archive),
+            "Demo" => value.into_string().map(UpstreamDatum::Demo),
+            "Pecl-Package" => value.into_string().map(UpstreamDatum::PeclPackage),
+            "Haskell-Package" => value.into_string().map(UpstreamDatum::HaskellPackage),
+            "Funding" => value.into_string().map(UpstreamDatum::Funding),
+            "Changelog" => value.into_string().map(UpstreamDatum::Changelog),
+            "Maintenance-Status" => value.into_string().map(UpstreamDatum::MaintenanceStatus),
+            "Debian-ITP" => value.into_integer().map(UpstreamDatum::DebianITP),
+            "Screenshots" => value.into_strings().map(UpstreamDatum::Screenshots),
+            "Registry" => value.into_pairs().map(UpstreamDatum::Registry),
+            "Cite-As" => value.into_string().map(UpstreamDatum::CiteAs),
+            "Donation" => value.into_string().map(UpstreamDatum::Donation),
+            "Webservice" => value.into_string().map(UpstreamDatum::Webservice),
+            "BuildSystem" => value.into_string().map(UpstreamDatum::BuildSystem),
+            _ => return Err(UnknownField::Field(field.to_string())),
+        }
+        .map_err(|value| UnknownField::Value { field, value })
+    }
+
+    /// Construct the data for a field from its value.
+    ///
+    /// Unlike [`UpstreamDatum::from_field_str`], this accepts a list of values for a
+    /// multi-valued field such as Donation, and returns one datum for each of them.
+    pub fn from_field_values(field: &str, value: DatumValue) -> Result<Vec<Self>, UnknownField> {
+        match Self::from_field_str(field, value) {
+            Err(UnknownField::Value {
+                field,
+                value: DatumValue::Strings(values),
+            }) if descriptors::is_multi_valued(field) => values
+                .into_iter()
+                .map(|value| Self::from_field_str(field, value.into()))
+                .collect(),
+            datum => datum.map(|datum| vec![datum]),
+        }
+    }
+
+    /// The value of this datum, independent of the field.
+    pub fn value(&self) -> DatumValue {
+        match self {
+            UpstreamDatum::Summary(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Description(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Name(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Homepage(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Repository(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::RepositoryBrowse(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::License(l) => DatumValue::String(l.as_str().to_string()),
+            UpstreamDatum::Author(v) => DatumValue::People(v.clone()),
+            UpstreamDatum::BugDatabase(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::BugSubmit(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Contact(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Organization(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::CargoCrate(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::SecurityMD(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::SecurityContact(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::CodeOfConduct(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Contributing(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Version(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Keywords(v) => DatumValue::Strings(v.clone()),
+            UpstreamDatum::Aliases(v) => DatumValue::Strings(v.clone()),
+            UpstreamDatum::Maintainer(v) => DatumValue::Person(v.clone()),
+            UpstreamDatum::Copyright(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Documentation(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::APIDocumentation(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::GoImportPath(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Download(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::BinaryDownload(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Wiki(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::RepositoryMirror(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::MailingList(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Chat(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Translations(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::SourceForgeProject(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::SourceForgeSubproject(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Archive(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Demo(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::PeclPackage(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::HaskellPackage(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Funding(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Changelog(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::MaintenanceStatus(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::DebianITP(v) => DatumValue::Integer(*v),
+            UpstreamDatum::Screenshots(v) => DatumValue::Strings(v.clone()),
+            UpstreamDatum::Registry(v) => DatumValue::Pairs(v.clone()),
+            UpstreamDatum::CiteAs(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Donation(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::Webservice(v) => DatumValue::String(v.clone()),
+            UpstreamDatum::BuildSystem(v) => DatumValue::String(v.clone()),
+        }
+    }
+
     pub fn field(&self) -> &'static str {
         match self {
             UpstreamDatum::Summary(..) => "Summary",
@@ -461,26 +918,36 @@ impl UpstreamDatum {
             UpstreamDatum::BugDatabase(..) => "Bug-Database",
             UpstreamDatum::BugSubmit(..) => "Bug-Submit",
             UpstreamDatum::Contact(..) => "Contact",
+            UpstreamDatum::Organization(..) => "Organization",
             UpstreamDatum::CargoCrate(..) => "Cargo-Crate",
             UpstreamDatum::SecurityMD(..) => "Security-MD",
             UpstreamDatum::SecurityContact(..) => "Security-Contact",
+            UpstreamDatum::CodeOfConduct(..) => "Code-Of-Conduct",
+            UpstreamDatum::Contributing(..) => "Contributing",
             UpstreamDatum::Version(..) => "Version",
             UpstreamDatum::Keywords(..) => "Keywords",
+            UpstreamDatum::Aliases(..) => "Aliases",
             UpstreamDatum::Maintainer(..) => "Maintainer",
             UpstreamDatum::Copyright(..) => "Copyright",
             UpstreamDatum::Documentation(..) => "Documentation",
             UpstreamDatum::APIDocumentation(..) => "API-Documentation",
             UpstreamDatum::GoImportPath(..) => "Go-Import-Path",
             UpstreamDatum::Download(..) => "Download",
+            UpstreamDatum::BinaryDownload(..) => "Binary-Download",
             UpstreamDatum::Wiki(..) => "Wiki",
+            UpstreamDatum::RepositoryMirror(..) => "Repository-Mirror",
             UpstreamDatum::MailingList(..) => "MailingList",
+            UpstreamDatum::Chat(..) => "Chat",
+            UpstreamDatum::Translations(..) => "Translations",
             UpstreamDatum::SourceForgeProject(..) => "SourceForge-Project",
+            UpstreamDatum::SourceForgeSubproject(..) => "SourceForge-Subproject",
             UpstreamDatum::Archive(..) => "Archive",
             UpstreamDatum::Demo(..) => "Demo",
             UpstreamDatum::PeclPackage(..) => "Pecl-Package",
             UpstreamDatum::HaskellPackage(..) => "Haskell-Package",
             UpstreamDatum::Funding(..) => "Funding",
             UpstreamDatum::Changelog(..) => "Changelog",
+            UpstreamDatum::MaintenanceStatus(..) => "Maintenance-Status",
             UpstreamDatum::DebianITP(..) => "Debian-ITP",
             UpstreamDatum::Screenshots(..) => "Screenshots",
             UpstreamDatum::Registry(..) => "Registry",
@@ -499,21 +966,29 @@ impl UpstreamDatum {
             UpstreamDatum::RepositoryBrowse(s) => Some(s),
             UpstreamDatum::Description(s) => Some(s),
             UpstreamDatum::Summary(s) => Some(s),
-            UpstreamDatum::License(s) => Some(s),
+            UpstreamDatum::License(l) => Some(l.as_str()),
             UpstreamDatum::BugDatabase(s) => Some(s),
             UpstreamDatum::BugSubmit(s) => Some(s),
             UpstreamDatum::Contact(s) => Some(s),
+            UpstreamDatum::Organization(s) => Some(s),
             UpstreamDatum::CargoCrate(s) => Some(s),
             UpstreamDatum::SecurityMD(s) => Some(s),
             UpstreamDatum::SecurityContact(s) => Some(s),
+            UpstreamDatum::CodeOfConduct(s) => Some(s),
+            UpstreamDatum::Contributing(s) => Some(s),
             UpstreamDatum::Version(s) => Some(s),
             UpstreamDatum::Documentation(s) => Some(s),
             UpstreamDatum::APIDocumentation(s) => Some(s),
             UpstreamDatum::GoImportPath(s) => Some(s),
             UpstreamDatum::Download(s) => Some(s),
+            UpstreamDatum::BinaryDownload(s) => Some(s),
             UpstreamDatum::Wiki(s) => Some(s),
+            UpstreamDatum::RepositoryMirror(s) => Some(s),
             UpstreamDatum::MailingList(s) => Some(s),
+            UpstreamDatum::Chat(s) => Some(s),
+            UpstreamDatum::Translations(s) => Some(s),
             UpstreamDatum::SourceForgeProject(s) => Some(s),
+            UpstreamDatum::SourceForgeSubproject(s) => Some(s),
             UpstreamDatum::Archive(s) => Some(s),
             UpstreamDatum::Demo(s) => Some(s),
             UpstreamDatum::PeclPackage(s) => Some(s),
@@ -521,9 +996,11 @@ impl UpstreamDatum {
             UpstreamDatum::Author(..) => None,
             UpstreamDatum::Maintainer(..) => None,
             UpstreamDatum::Keywords(..) => None,
+            UpstreamDatum::Aliases(..) => None,
             UpstreamDatum::Copyright(c) => Some(c),
             UpstreamDatum::Funding(f) => Some(f),
             UpstreamDatum::Changelog(c) => Some(c),
+            UpstreamDatum::MaintenanceStatus(s) => Some(s),
             UpstreamDatum::Screenshots(..) => None,
             UpstreamDatum::DebianITP(_c) => None,
             UpstreamDatum::CiteAs(c) => Some(c),
@@ -546,17 +1023,25 @@ impl UpstreamDatum {
             UpstreamDatum::BugDatabase(s) => Some(s.parse().ok()?),
             UpstreamDatum::BugSubmit(s) => Some(s.parse().ok()?),
             UpstreamDatum::Contact(..) => None,
+            UpstreamDatum::Organization(..) => None,
             UpstreamDatum::CargoCrate(s) => Some(s.parse().ok()?),
             UpstreamDatum::SecurityMD(..) => None,
             UpstreamDatum::SecurityContact(..) => None,
+            UpstreamDatum::CodeOfConduct(s) => s.parse().ok(),
+            UpstreamDatum::Contributing(s) => s.parse().ok(),
             UpstreamDatum::Version(..) => None,
             UpstreamDatum::Documentation(s) => Some(s.parse().ok()?),
             UpstreamDatum::APIDocumentation(s) => Some(s.parse().ok()?),
             UpstreamDatum::GoImportPath(_s) => None,
             UpstreamDatum::Download(s) => Some(s.parse().ok()?),
+            UpstreamDatum::BinaryDownload(s) => Some(s.parse().ok()?),
             UpstreamDatum::Wiki(s) => Some(s.parse().ok()?),
+            UpstreamDatum::RepositoryMirror(s) => Some(s.parse().ok()?),
             UpstreamDatum::MailingList(s) => Some(s.parse().ok()?),
+            UpstreamDatum::Chat(s) => Some(s.parse().ok()?),
+            UpstreamDatum::Translations(s) => Some(s.parse().ok()?),
             UpstreamDatum::SourceForgeProject(s) => Some(s.parse().ok()?),
+            UpstreamDatum::SourceForgeSubproject(..) => None,
             UpstreamDatum::Archive(s) => Some(s.parse().ok()?),
             UpstreamDatum::Demo(s) => Some(s.parse().ok()?),
             UpstreamDatum::PeclPackage(_s) => None,
@@ -564,9 +1049,11 @@ impl UpstreamDatum {
             UpstreamDatum::Author(..) => None,
             UpstreamDatum::Maintainer(..) => None,
             UpstreamDatum::Keywords(..) => None,
+            UpstreamDatum::Aliases(..) => None,
             UpstreamDatum::Copyright(..) => None,
             UpstreamDatum::Funding(s) => Some(s.parse().ok()?),
             UpstreamDatum::Changelog(s) => Some(s.parse().ok()?),
+            UpstreamDatum::MaintenanceStatus(..) => None,
             UpstreamDatum::Screenshots(..) => None,
             UpstreamDatum::DebianITP(_c) => None,
             UpstreamDatum::Registry(_r) => None,
@@ -594,10 +1081,10 @@ impl UpstreamDatum {
                     Ok(url) => url,
                     Err(_) => return false,
                 };
-                if url.host_str() == Some("bugzilla.gnome.org") {
-                    return true;
-                }
-                if url.host_str() == Some("bugs.freedesktop.org") {
+                if url
+                    .host_str()
+                    .is_some_and(|host| data::tables().is_bad_host(self.field(), host))
+                {
                     return true;
                 }
                 if url.path().ends_with("/sign_in") {
@@ -612,10 +1099,13 @@ impl UpstreamDatum {
                     Ok(url) => url,
                     Err(_) => return false,
                 };
-                if url.host_str() == Some("anongit.kde.org") {
+                if url
+                    .host_str()
+                    .is_some_and(|host| data::tables().is_bad_host(self.field(), host))
+                {
                     return true;
                 }
-                if url.host_str() == Some("git.gitorious.org") {
+                if is_snippet_url(&url) {
                     return true;
                 }
                 if url.path().ends_with("/sign_in") {
@@ -634,6 +1124,9 @@ impl UpstreamDatum {
                 if url.host_str() == Some("rubygems.org") {
                     return true;
                 }
+                if is_snippet_url(&url) {
+                    return true;
+                }
             }
             UpstreamDatum::RepositoryBrowse(s) => {
                 if known_bad_url(s) {
@@ -643,7 +1136,13 @@ impl UpstreamDatum {
                     Ok(url) => url,
                     Err(_) => return false,
                 };
-                if url.host_str() == Some("cgit.kde.org") {
+                if url
+                    .host_str()
+                    .is_some_and(|host| data::tables().is_bad_host(self.field(), host))
+                {
+                    return true;
+                }
+                if is_snippet_url(&url) {
                     return true;
                 }
                 if url.path().ends_with("/sign_in") {
@@ -700,17 +1199,27 @@ impl std::fmt::Display for UpstreamDatum {
             UpstreamDatum::BugDatabase(s) => write!(f, "BugDatabase: {}", s),
             UpstreamDatum::BugSubmit(s) => write!(f, "BugSubmit: {}", s),
             UpstreamDatum::Contact(s) => write!(f, "Contact: {}", s),
+            UpstreamDatum::Organization(s) => write!(f, "Organization: {}", s),
             UpstreamDatum::CargoCrate(s) => write!(f, "CargoCrate: {}", s),
             UpstreamDatum::SecurityMD(s) => write!(f, "SecurityMD: {}", s),
             UpstreamDatum::SecurityContact(s) => write!(f, "SecurityContact: {}", s),
+            UpstreamDatum::CodeOfConduct(s) => write!(f, "CodeOfConduct: {}", s),
+            UpstreamDatum::Contributing(s) => write!(f, "Contributing: {}", s),
             UpstreamDatum::Version(s) => write!(f, "Version: {}", s),
             UpstreamDatum::Documentation(s) => write!(f, "Documentation: {}", s),
             UpstreamDatum::APIDocumentation(s) => write!(f, "API-Documentation: {}", s),
             UpstreamDatum::GoImportPath(s) => write!(f, "GoImportPath: {}", s),
             UpstreamDatum::Download(s) => write!(f, "Download: {}", s),
+            UpstreamDatum::BinaryDownload(s) => write!(f, "Binary-Download: {}", s),
             UpstreamDatum::Wiki(s) => write!(f, "Wiki: {}", s),
+            UpstreamDatum::RepositoryMirror(s) => write!(f, "Repository-Mirror: {}", s),
             UpstreamDatum::MailingList(s) => write!(f, "MailingList: {}", s),
+            UpstreamDatum::Chat(s) => write!(f, "Chat: {}", s),
+            UpstreamDatum::Translations(s) => write!(f, "Translations: {}", s),
             UpstreamDatum::SourceForgeProject(s) => write!(f, "SourceForgeProject: {}", s),
+            UpstreamDatum::SourceForgeSubproject(s) => {
+                write!(f, "SourceForgeSubproject: {}", s)
+            }
             UpstreamDatum::Archive(s) => write!(f, "Archive: {}", s),
             UpstreamDatum::Demo(s) => write!(f, "Demo: {}", s),
             UpstreamDatum::PeclPackage(s) => write!(f, "PeclPackage: {}", s),
@@ -739,6 +1248,9 @@ impl std::fmt::Display for UpstreamDatum {
                         .join(", ")
                 )
             }
+            UpstreamDatum::Aliases(aliases) => {
+                write!(f, "Aliases: {}", aliases.join(", "))
+            }
             UpstreamDatum::Copyright(s) => {
                 write!(f, "Copyright: {}", s)
             }
@@ -748,6 +1260,9 @@ impl std::fmt::Display for UpstreamDatum {
             UpstreamDatum::Changelog(s) => {
                 write!(f, "Changelog: {}", s)
             }
+            UpstreamDatum::MaintenanceStatus(s) => {
+                write!(f, "Maintenance-Status: {}", s)
+            }
             UpstreamDatum::DebianITP(s) => {
                 write!(f, "DebianITP: {}", s)
             }
@@ -781,6 +1296,150 @@ impl std::fmt::Display for UpstreamDatum {
     }
 }
 
+/// The value of an [`UpstreamDatum`], independent of the field it is for.
+///
+/// This is used to construct data from external sources that identify fields by name; see
+/// [`UpstreamDatum::from_field_str`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DatumValue {
+    /// A single string, e.g. for Name or Homepage
+    String(String),
+    /// A list of strings, e.g. for Keywords
+    Strings(Vec<String>),
+    /// A single person, e.g. for Maintainer
+    Person(Person),
+    /// A list of people, e.g. for Author
+    People(Vec<Person>),
+    /// An integer, e.g. for Debian-ITP
+    Integer(i32),
+    /// A list of pairs, e.g. (registry name, entry) for Registry
+    Pairs(Vec<(String, String)>),
+}
+
+impl From<String> for DatumValue {
+    fn from(s: String) -> Self {
+        DatumValue::String(s)
+    }
+}
+
+impl From<&str> for DatumValue {
+    fn from(s: &str) -> Self {
+        DatumValue::String(s.to_string())
+    }
+}
+
+impl From<Vec<String>> for DatumValue {
+    fn from(v: Vec<String>) -> Self {
+        DatumValue::Strings(v)
+    }
+}
+
+impl From<Person> for DatumValue {
+    fn from(p: Person) -> Self {
+        DatumValue::Person(p)
+    }
+}
+
+impl From<Vec<Person>> for DatumValue {
+    fn from(v: Vec<Person>) -> Self {
+        DatumValue::People(v)
+    }
+}
+
+impl From<i32> for DatumValue {
+    fn from(i: i32) -> Self {
+        DatumValue::Integer(i)
+    }
+}
+
+impl DatumValue {
+    fn kind(&self) -> &'static str {
+        match self {
+            DatumValue::String(..) => "a string",
+            DatumValue::Strings(..) => "a list of strings",
+            DatumValue::Person(..) => "a person",
+            DatumValue::People(..) => "a list of people",
+            DatumValue::Integer(..) => "an integer",
+            DatumValue::Pairs(..) => "a list of pairs",
+        }
+    }
+
+    fn into_string(self) -> Result<String, Self> {
+        match self {
+            DatumValue::String(s) => Ok(s),
+            DatumValue::Integer(i) => Ok(i.to_string()),
+            v => Err(v),
+        }
+    }
+
+    fn into_strings(self) -> Result<Vec<String>, Self> {
+        match self {
+            DatumValue::Strings(v) => Ok(v),
+            DatumValue::String(s) => Ok(vec![s]),
+            v => Err(v),
+        }
+    }
+
+    fn into_person(self) -> Result<Person, Self> {
+        match self {
+            DatumValue::Person(p) => Ok(p),
+            DatumValue::String(s) => Ok(Person::from(s.as_str())),
+            v => Err(v),
+        }
+    }
+
+    fn into_people(self) -> Result<Vec<Person>, Self> {
+        match self {
+            DatumValue::People(v) => Ok(v),
+            DatumValue::Person(p) => Ok(vec![p]),
+            DatumValue::Strings(v) => Ok(v.iter().map(|s| Person::from(s.as_str())).collect()),
+            DatumValue::String(s) => Ok(vec![Person::from(s.as_str())]),
+            v => Err(v),
+        }
+    }
+
+    fn into_integer(self) -> Result<i32, Self> {
+        match self {
+            DatumValue::Integer(i) => Ok(i),
+            DatumValue::String(s) => s.parse().map_err(|_| DatumValue::String(s)),
+            v => Err(v),
+        }
+    }
+
+    fn into_pairs(self) -> Result<Vec<(String, String)>, Self> {
+        match self {
+            DatumValue::Pairs(v) => Ok(v),
+            DatumValue::Strings(v) if v.is_empty() => Ok(vec![]),
+            v => Err(v),
+        }
+    }
+}
+
+/// Error constructing an [`UpstreamDatum`] from a field name and a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnknownField {
+    /// There is no field with this name
+    Field(String),
+    /// The field exists, but the value has a type that can not be used for it
+    Value {
+        field: &'static str,
+        value: DatumValue,
+    },
+}
+
+impl std::fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownField::Field(field) => write!(f, "Unknown field: {}", field),
+            UnknownField::Value { field, value } => {
+                write!(f, "Unexpected value for {}: {}", field, value.kind())
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnknownField {}
+
 impl serde::ser::Serialize for UpstreamDatum {
     fn serialize<S: serde::ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         match self {
@@ -790,21 +1449,29 @@ impl serde::ser::Serialize for UpstreamDatum {
             UpstreamDatum::RepositoryBrowse(s) => serializer.serialize_str(s),
             UpstreamDatum::Description(s) => serializer.serialize_str(s),
             UpstreamDatum::Summary(s) => serializer.serialize_str(s),
-            UpstreamDatum::License(s) => serializer.serialize_str(s),
+            UpstreamDatum::License(l) => serializer.serialize_str(l.as_str()),
             UpstreamDatum::BugDatabase(s) => serializer.serialize_str(s),
             UpstreamDatum::BugSubmit(s) => serializer.serialize_str(s),
             UpstreamDatum::Contact(s) => serializer.serialize_str(s),
+            UpstreamDatum::Organization(s) => serializer.serialize_str(s),
             UpstreamDatum::CargoCrate(s) => serializer.serialize_str(s),
             UpstreamDatum::SecurityMD(s) => serializer.serialize_str(s),
             UpstreamDatum::SecurityContact(s) => serializer.serialize_str(s),
+            UpstreamDatum::CodeOfConduct(s) => serializer.serialize_str(s),
+            UpstreamDatum::Contributing(s) => serializer.serialize_str(s),
             UpstreamDatum::Version(s) => serializer.serialize_str(s),
             UpstreamDatum::Documentation(s) => serializer.serialize_str(s),
             UpstreamDatum::APIDocumentation(s) => serializer.serialize_str(s),
             UpstreamDatum::GoImportPath(s) => serializer.serialize_str(s),
             UpstreamDatum::Download(s) => serializer.serialize_str(s),
+            UpstreamDatum::BinaryDownload(s) => serializer.serialize_str(s),
             UpstreamDatum::Wiki(s) => serializer.serialize_str(s),
+            UpstreamDatum::RepositoryMirror(s) => serializer.serialize_str(s),
             UpstreamDatum::MailingList(s) => serializer.serialize_str(s),
+            UpstreamDatum::Chat(s) => serializer.serialize_str(s),
+            UpstreamDatum::Translations(s) => serializer.serialize_str(s),
             UpstreamDatum::SourceForgeProject(s) => serializer.serialize_str(s),
+            UpstreamDatum::SourceForgeSubproject(s) => serializer.serialize_str(s),
             UpstreamDatum::Archive(s) => serializer.serialize_str(s),
             UpstreamDatum::Demo(s) => serializer.serialize_str(s),
             UpstreamDatum::PeclPackage(s) => serializer.serialize_str(s),
@@ -823,9 +1490,17 @@ impl serde::ser::Serialize for UpstreamDatum {
                 }
                 seq.end()
             }
+            UpstreamDatum::Aliases(aliases) => {
+                let mut seq = serializer.serialize_seq(Some(aliases.len()))?;
+                for a in aliases {
+                    seq.serialize_element(a)?;
+                }
+                seq.end()
+            }
             UpstreamDatum::Copyright(s) => serializer.serialize_str(s),
             UpstreamDatum::Funding(s) => serializer.serialize_str(s),
             UpstreamDatum::Changelog(s) => serializer.serialize_str(s),
+            UpstreamDatum::MaintenanceStatus(s) => serializer.serialize_str(s),
             UpstreamDatum::DebianITP(s) => serializer.serialize_i32(*s),
             UpstreamDatum::HaskellPackage(p) => serializer.serialize_str(p),
             UpstreamDatum::Screenshots(s) => {
@@ -903,6 +1578,17 @@ impl UpstreamMetadata {
         self.0.iter_mut().find(|d| d.datum.field() == field)
     }
 
+    /// All values recorded for a field.
+    ///
+    /// Multi-valued fields (see [`descriptors::is_multi_valued`]) can have several values,
+    /// e.g. multiple Donation links; other fields have at most one.
+    pub fn get_all<'a>(
+        &'a self,
+        field: &'a str,
+    ) -> impl Iterator<Item = &'a UpstreamDatumWithMetadata> + 'a {
+        self.0.iter().filter(move |d| d.datum.field() == field)
+    }
+
     pub fn insert(&mut self, datum: UpstreamDatumWithMetadata) {
         self.0.push(datum);
     }
@@ -922,6 +1608,72 @@ impl UpstreamMetadata {
         update_from_guesses(&mut self.0, new_items)
     }
 
+    /// Like [`UpstreamMetadata::update`], but also record sources that disagree.
+    ///
+    /// See [`update_from_guesses_with_conflicts`].
+    pub fn update_with_conflicts(
+        &mut self,
+        new_items: impl Iterator<Item = UpstreamDatumWithMetadata>,
+        conflicts: &mut Vec<Conflict>,
+    ) -> Vec<UpstreamDatumWithMetadata> {
+        update_from_guesses_with_conflicts(&mut self.0, new_items, conflicts)
+    }
+
+    /// Merge another set of metadata into this one.
+    ///
+    /// Per field, the value with the higher certainty is kept. If both sets have the same
+    /// value for a field, the origins of both are retained. For multi-valued fields, values
+    /// that are not present yet are added rather than replacing the existing ones.
+    ///
+    /// # Arguments
+    /// * `other`: Metadata to merge, e.g. a fresh scan or data from a registry
+    /// * `policy`: How to resolve conflicts
+    ///
+    /// # Returns
+    /// The data that was added or changed
+    pub fn merge(
+        &mut self,
+        other: UpstreamMetadata,
+        policy: MergePolicy,
+    ) -> Vec<UpstreamDatumWithMetadata> {
+        let mut changed = vec![];
+        for datum in other.0 {
+            let current = if descriptors::is_multi_valued(datum.datum.field()) {
+                self.0.iter_mut().find(|d| d.datum == datum.datum)
+            } else {
+                self.get_mut(datum.datum.field())
+            };
+            let current = match current {
+                Some(current) => current,
+                None => {
+                    changed.push(datum.clone());
+                    self.0.push(datum);
+                    continue;
+                }
+            };
+            if current.datum == datum.datum {
+                let certainty = std::cmp::max(current.certainty, datum.certainty);
+                let origin = Origin::combine(current.origin.clone(), datum.origin);
+                if certainty != current.certainty || origin != current.origin {
+                    current.certainty = certainty;
+                    current.origin = origin;
+                    changed.push(current.clone());
+                }
+                continue;
+            }
+            let replace = match policy {
+                MergePolicy::PreferHigherCertainty => datum.certainty > current.certainty,
+                MergePolicy::PreferOtherOnTie => datum.certainty >= current.certainty,
+                MergePolicy::FillMissing => false,
+            };
+            if replace {
+                changed.push(datum.clone());
+                *current = datum;
+            }
+        }
+        changed
+    }
+
     pub fn remove(&mut self, field: &str) -> Option<UpstreamDatumWithMetadata> {
         let index = self.0.iter().position(|d| d.datum.field() == field)?;
         Some(self.0.remove(index))
@@ -931,6 +1683,18 @@ impl UpstreamMetadata {
         self.get("Name").and_then(|d| d.datum.as_str())
     }
 
+    /// The name of the project in a particular ecosystem, e.g. for registry lookups.
+    ///
+    /// Ecosystem-specific fields such as Cargo-Crate are preferred over Name.
+    pub fn name_for(&self, ecosystem: names::Ecosystem) -> Option<String> {
+        let name = ecosystem
+            .name_field()
+            .and_then(|f| self.get(f))
+            .or_else(|| self.get("Name"))
+            .and_then(|d| d.datum.as_str())?;
+        Some(ecosystem.normalize_name(name))
+    }
+
     pub fn homepage(&self) -> Option<&str> {
         self.get("Homepage").and_then(|d| d.datum.as_str())
     }
@@ -981,6 +1745,10 @@ impl UpstreamMetadata {
         self.get("Contact").and_then(|d| d.datum.as_str())
     }
 
+    pub fn organization(&self) -> Option<&str> {
+        self.get("Organization").and_then(|d| d.datum.as_str())
+    }
+
     pub fn cargo_crate(&self) -> Option<&str> {
         self.get("Cargo-Crate").and_then(|d| d.datum.as_str())
     }
@@ -993,6 +1761,14 @@ impl UpstreamMetadata {
         self.get("Security-Contact").and_then(|d| d.datum.as_str())
     }
 
+    pub fn code_of_conduct(&self) -> Option<&str> {
+        self.get("Code-Of-Conduct").and_then(|d| d.datum.as_str())
+    }
+
+    pub fn contributing(&self) -> Option<&str> {
+        self.get("Contributing").and_then(|d| d.datum.as_str())
+    }
+
     pub fn version(&self) -> Option<&str> {
         self.get("Version").and_then(|d| d.datum.as_str())
     }
@@ -1004,6 +1780,22 @@ impl UpstreamMetadata {
         })
     }
 
+    /// The keywords, mapped to terms from a controlled vocabulary.
+    ///
+    /// See [`keywords::keywords_as`].
+    pub fn keywords_as(&self, vocabulary: keywords::Vocabulary) -> Vec<String> {
+        self.keywords()
+            .map(|k| keywords::keywords_as(k, vocabulary))
+            .unwrap_or_default()
+    }
+
+    pub fn aliases(&self) -> Option<&Vec<String>> {
+        self.get("Aliases").map(|d| match &d.datum {
+            UpstreamDatum::Aliases(aliases) => aliases,
+            _ => unreachable!(),
+        })
+    }
+
     pub fn documentation(&self) -> Option<&str> {
         self.get("Documentation").and_then(|d| d.datum.as_str())
     }
@@ -1020,19 +1812,40 @@ impl UpstreamMetadata {
         self.get("Download").and_then(|d| d.datum.as_str())
     }
 
+    pub fn binary_download(&self) -> Option<&str> {
+        self.get("Binary-Download").and_then(|d| d.datum.as_str())
+    }
+
     pub fn wiki(&self) -> Option<&str> {
         self.get("Wiki").and_then(|d| d.datum.as_str())
     }
 
+    pub fn repository_mirror(&self) -> Option<&str> {
+        self.get("Repository-Mirror").and_then(|d| d.datum.as_str())
+    }
+
     pub fn mailing_list(&self) -> Option<&str> {
         self.get("MailingList").and_then(|d| d.datum.as_str())
     }
 
+    pub fn chat(&self) -> Option<&str> {
+        self.get("Chat").and_then(|d| d.datum.as_str())
+    }
+
+    pub fn translations(&self) -> Option<&str> {
+        self.get("Translations").and_then(|d| d.datum.as_str())
+    }
+
     pub fn sourceforge_project(&self) -> Option<&str> {
         self.get("SourceForge-Project")
             .and_then(|d| d.datum.as_str())
     }
 
+    pub fn sourceforge_subproject(&self) -> Option<&str> {
+        self.get("SourceForge-Subproject")
+            .and_then(|d| d.datum.as_str())
+    }
+
     pub fn archive(&self) -> Option<&str> {
         self.get("Archive").and_then(|d| d.datum.as_str())
     }
@@ -1057,6 +1870,11 @@ impl UpstreamMetadata {
         self.get("Changelog").and_then(|d| d.datum.as_str())
     }
 
+    pub fn maintenance_status(&self) -> Option<&str> {
+        self.get("Maintenance-Status")
+            .and_then(|d| d.datum.as_str())
+    }
+
     pub fn debian_itp(&self) -> Option<i32> {
         self.get("Debian-ITP").and_then(|d| match &d.datum {
             UpstreamDatum::DebianITP(itp) => Some(*itp),
@@ -1163,6 +1981,108 @@ impl From<UpstreamMetadata> for Vec<UpstreamDatum> {
     }
 }
 
+/// Convert a YAML value into JSON.
+///
+/// Tags, such as the `!Person` tag on people, are dropped.
+fn yaml_to_json(value: serde_yaml::Value) -> serde_json::Value {
+    match value {
+        serde_yaml::Value::Null => serde_json::Value::Null,
+        serde_yaml::Value::Bool(b) => serde_json::Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into()
+            } else if let Some(u) = n.as_u64() {
+                u.into()
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map_or(serde_json::Value::Null, serde_json::Value::Number)
+            }
+        }
+        serde_yaml::Value::String(s) => serde_json::Value::String(s),
+        serde_yaml::Value::Sequence(items) => items.into_iter().map(yaml_to_json).collect(),
+        serde_yaml::Value::Mapping(map) => serde_json::Value::Object(
+            map.into_iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s,
+                        k => serde_yaml::to_string(&k)
+                            .unwrap_or_default()
+                            .trim_end()
+                            .to_string(),
+                    };
+                    (key, yaml_to_json(v))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+impl UpstreamDatum {
+    /// Convert the value of this datum to JSON.
+    pub fn to_json(&self) -> serde_json::Value {
+        yaml_to_json(serde_yaml::to_value(self).unwrap())
+    }
+}
+
+impl UpstreamMetadata {
+    /// Convert to YAML, as used in e.g. debian/upstream/metadata.
+    ///
+    /// This is the same as serializing with [`serde_yaml`]; people are tagged with `!Person`.
+    pub fn to_yaml(&self) -> serde_yaml::Value {
+        serde_yaml::to_value(self).unwrap()
+    }
+
+    /// Convert to a JSON object that maps field names to values.
+    pub fn to_json(&self) -> serde_json::Value {
+        yaml_to_json(self.to_yaml())
+    }
+
+    /// Convert to JSON, including the certainty and origin of each field.
+    ///
+    /// The values are in a "metadata" object, as returned by [`UpstreamMetadata::to_json`].
+    /// The "certainty" and "origin" objects map field names to the certainty and origin of
+    /// their value; fields for which these are not known are omitted.
+    pub fn to_json_annotated(&self) -> serde_json::Value {
+        let mut certainties = serde_json::Map::new();
+        let mut origins: Vec<(&str, Option<Origin>)> = vec![];
+        for datum in &self.0 {
+            let field = datum.datum.field();
+            if let Some(certainty) = datum.certainty {
+                let known = certainties
+                    .get(field)
+                    .and_then(|c| c.as_str())
+                    .and_then(|c| c.parse::<Certainty>().ok());
+                if known.is_none_or(|known| certainty > known) {
+                    certainties.insert(field.to_string(), certainty.to_string().into());
+                }
+            }
+            match origins.iter_mut().find(|(f, _)| *f == field) {
+                Some((_, origin)) => {
+                    *origin = Origin::combine(origin.take(), datum.origin.clone());
+                }
+                None => origins.push((field, datum.origin.clone())),
+            }
+        }
+        let origins = origins
+            .into_iter()
+            .filter_map(|(field, origin)| {
+                Some((field.to_string(), serde_json::to_value(origin?).ok()?))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        serde_json::json!({
+            "metadata": self.to_json(),
+            "certainty": certainties,
+            "origin": origins,
+        })
+    }
+}
+
+/// Serializes to a mapping from field names to values.
+///
+/// This is geared towards YAML, and e.g. uses YAML tags for people; use
+/// [`UpstreamMetadata::to_json`] for other formats.
 impl serde::ser::Serialize for UpstreamMetadata {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1170,10 +2090,30 @@ impl serde::ser::Serialize for UpstreamMetadata {
     {
         let mut map = serde_yaml::Mapping::new();
         for datum in &self.0 {
-            map.insert(
-                serde_yaml::Value::String(datum.datum.field().to_string()),
-                serde_yaml::to_value(datum).unwrap(),
-            );
+            let field = datum.datum.field();
+            let key = serde_yaml::Value::String(field.to_string());
+            let value = serde_yaml::to_value(datum).unwrap();
+            if !descriptors::is_multi_valued(field) {
+                map.insert(key, value);
+                continue;
+            }
+            // Multi-valued fields are always written as a list, combining all of their values
+            let values = match value {
+                serde_yaml::Value::Sequence(values) => values,
+                value => vec![value],
+            };
+            match map.get_mut(&key) {
+                Some(serde_yaml::Value::Sequence(existing)) => {
+                    for v in values {
+                        if !existing.contains(&v) {
+                            existing.push(v);
+                        }
+                    }
+                }
+                _ => {
+                    map.insert(key, serde_yaml::Value::Sequence(values));
+                }
+            }
         }
         map.serialize(serializer)
     }
@@ -1220,6 +2160,7 @@ pub trait UpstreamDataProvider {
     ) -> dyn Iterator<Item = (UpstreamDatum, Certainty)>;
 }
 
+#[cfg(feature = "net")]
 #[derive(Debug)]
 pub enum HTTPJSONError {
     HTTPError(reqwest::Error),
@@ -1229,8 +2170,13 @@ pub enum HTTPJSONError {
         status: u16,
         response: reqwest::Response,
     },
+    /// The request was not sent, since the network policy does not allow it
+    Offline(Url),
+    /// An API path that can't be resolved relative to the base URL
+    InvalidPath(String),
 }
 
+#[cfg(feature = "net")]
 impl std::fmt::Display for HTTPJSONError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -1241,61 +2187,25 @@ impl std::fmt::Display for HTTPJSONError {
                 status,
                 response: _,
             } => write!(f, "HTTP error {} for {}:", status, url,),
+            HTTPJSONError::Offline(url) => write!(f, "No network access to retrieve {}", url),
+            HTTPJSONError::InvalidPath(path) => write!(f, "Invalid API path {}", path),
         }
     }
 }
 
+#[cfg(feature = "net")]
 pub async fn load_json_url(
     http_url: &Url,
     timeout: Option<std::time::Duration>,
 ) -> Result<serde_json::Value, HTTPJSONError> {
-    let mut headers = HeaderMap::new();
-    headers.insert(reqwest::header::ACCEPT, "application/json".parse().unwrap());
-
-    if let Some(hostname) = http_url.host_str() {
-        if hostname == "github.com" || hostname == "raw.githubusercontent.com" {
-            if let Ok(token) = std::env::var("GITHUB_TOKEN") {
-                headers.insert(
-                    reqwest::header::WWW_AUTHENTICATE,
-                    format!("Bearer {}", token).parse().unwrap(),
-                );
-            }
-        }
-    }
-
-    let client = crate::http::build_client()
-        .default_headers(headers)
-        .build()
-        .map_err(HTTPJSONError::HTTPError)?;
-
-    let http_url: reqwest::Url = Into::<String>::into(http_url.clone()).parse().unwrap();
-
-    let request = client
-        .get(http_url)
-        .build()
-        .map_err(HTTPJSONError::HTTPError)?;
-
-    let timeout = timeout.unwrap_or(std::time::Duration::from_secs(30));
-
-    let response = tokio::time::timeout(timeout, client.execute(request))
-        .await
-        .map_err(|_| HTTPJSONError::Timeout(timeout))?
-        .map_err(HTTPJSONError::HTTPError)?;
-
-    if !response.status().is_success() {
-        return Err(HTTPJSONError::Error {
-            url: response.url().clone(),
-            status: response.status().as_u16(),
-            response,
-        });
+    let mut client = forges::api::ApiClient::new(http_url.clone());
+    if let Some(timeout) = timeout {
+        client = client.timeout(timeout);
     }
-
-    let json_contents: serde_json::Value =
-        response.json().await.map_err(HTTPJSONError::HTTPError)?;
-
-    Ok(json_contents)
+    client.get_url(http_url).await
 }
 
+#[cfg(feature = "providers")]
 fn xmlparse_simplify_namespaces(path: &Path, namespaces: &[&str]) -> Option<xmltree::Element> {
     let namespaces = namespaces
         .iter()
@@ -1309,6 +2219,7 @@ fn xmlparse_simplify_namespaces(path: &Path, namespaces: &[&str]) -> Option<xmlt
     Some(tree)
 }
 
+#[cfg(feature = "providers")]
 fn simplify_namespaces(element: &mut xmltree::Element, namespaces: &[String]) {
     use xmltree::XMLNode;
     element.prefix = None;
@@ -1328,7 +2239,27 @@ pub enum CanonicalizeError {
     RateLimited(Url),
 }
 
+#[cfg(feature = "net")]
 pub async fn check_url_canonical(url: &Url) -> Result<Url, CanonicalizeError> {
+    fetch_url_canonical(url)
+        .await
+        .map(|response| response.url().clone())
+}
+
+/// Check that a URL is canonical, and retrieve the contents of the page it points at.
+#[cfg(feature = "net")]
+pub async fn check_url_canonical_with_body(url: &Url) -> Result<(Url, String), CanonicalizeError> {
+    let response = fetch_url_canonical(url).await?;
+    let canonical_url = response.url().clone();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| CanonicalizeError::Unverifiable(url.clone(), format!("HTTP error {}", e)))?;
+    Ok((canonical_url, body))
+}
+
+#[cfg(feature = "net")]
+async fn fetch_url_canonical(url: &Url) -> Result<reqwest::Response, CanonicalizeError> {
     if url.scheme() != "http" && url.scheme() != "https" {
         return Err(CanonicalizeError::Unverifiable(
             url.clone(),
@@ -1336,17 +2267,23 @@ pub async fn check_url_canonical(url: &Url) -> Result<Url, CanonicalizeError> {
         ));
     }
 
+    if !http::settings().can_fetch() {
+        return Err(CanonicalizeError::Unverifiable(
+            url.clone(),
+            "no network access".to_string(),
+        ));
+    }
+
     let client = crate::http::build_client()
         .build()
         .map_err(|e| CanonicalizeError::Unverifiable(url.clone(), format!("HTTP error {}", e)))?;
 
-    let response =
-        client.get(url.clone()).send().await.map_err(|e| {
-            CanonicalizeError::Unverifiable(url.clone(), format!("HTTP error {}", e))
-        })?;
-
+    let response = http::send(client.get(url.clone()))
+        .await
+        .map_err(|e| CanonicalizeError::Unverifiable(url.clone(), format!("HTTP error {}", e)))?;
+
     match response.status() {
-        status if status.is_success() => Ok(response.url().clone()),
+        status if status.is_success() => Ok(response),
         status if status == reqwest::StatusCode::TOO_MANY_REQUESTS => {
             Err(CanonicalizeError::RateLimited(url.clone()))
         }
@@ -1379,6 +2316,14 @@ pub trait Forge: Send + Sync {
 
     fn name(&self) -> &'static str;
 
+    /// Whether URLs on this forge can be recognized from the URL alone.
+    ///
+    /// This is used by [`ForgeRegistry`] to find the forge for a URL; forges that don't
+    /// implement it are never found.
+    fn handles_url(&self, _url: &Url) -> bool {
+        false
+    }
+
     fn bug_database_url_from_bug_submit_url(&self, _url: &Url) -> Option<Url> {
         None
     }
@@ -1413,6 +2358,48 @@ pub trait Forge: Send + Sync {
         None
     }
 
+    /// Find the URL of the discussion forum for a repository, if it has one enabled.
+    async fn discussions_url_from_repo_url(&self, _url: &Url) -> Option<Url> {
+        None
+    }
+
+    /// If the repository is a mirror, find the URL of the repository it mirrors.
+    async fn mirror_source_from_repo_url(&self, _url: &Url) -> Option<Url> {
+        None
+    }
+
+    /// Find the name of the organization that owns a repository, if it is not owned by a user.
+    async fn organization_from_repo_url(&self, _url: &Url) -> Option<String> {
+        None
+    }
+
+    /// Find the URL of the static site (e.g. GitHub Pages) that may be served for a repository.
+    ///
+    /// This does not check whether the site actually exists.
+    fn pages_url_from_repo_url(&self, _url: &Url) -> Option<Url> {
+        None
+    }
+
+    /// Find the URL of the mailing list that may be used for a repository.
+    ///
+    /// This does not check whether the list actually exists.
+    fn mailing_list_url_from_repo_url(&self, _url: &Url) -> Option<Url> {
+        None
+    }
+
+    /// The name of the project that a repository belongs to, as passed to
+    /// [`Forge::extend_metadata`].
+    ///
+    /// Returns `None` if the forge has no metadata for projects by their repository.
+    fn project_from_repo_url(&self, _url: &Url) -> Option<String> {
+        None
+    }
+
+    /// The fields that [`Forge::extend_metadata`] can fill in.
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[]
+    }
+
     async fn extend_metadata(
         &self,
         _metadata: &mut Vec<UpstreamDatumWithMetadata>,
@@ -1434,10 +2421,33 @@ impl GitHub {
     pub fn new() -> Self {
         Self
     }
+
+    /// Look up the repository at a URL through the GitHub API.
+    ///
+    /// Returns the owner and name of the repository along with the API's description of it.
+    #[cfg(feature = "net")]
+    async fn load_repository(
+        url: &Url,
+    ) -> Option<((&str, &str), Result<serde_json::Value, HTTPJSONError>)> {
+        let path = url.path_segments()?.take(2).collect::<Vec<&str>>();
+        if path.len() != 2 {
+            return None;
+        }
+        let (owner, repo) = (path[0], path[1].strip_suffix(".git").unwrap_or(path[1]));
+
+        let data = forges::api::ApiClient::github()
+            .get(&format!("repos/{}/{}", owner, repo))
+            .await;
+        Some(((owner, repo), data))
+    }
 }
 
 #[async_trait::async_trait]
 impl Forge for GitHub {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str() == Some("github.com")
+    }
+
     fn name(&self) -> &'static str {
         "GitHub"
     }
@@ -1481,6 +2491,7 @@ impl Forge for GitHub {
         Some(url)
     }
 
+    #[cfg(feature = "net")]
     async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
         assert_eq!(url.host(), Some(url::Host::Domain("github.com")));
         let path_elements = url.path_segments().unwrap().collect::<Vec<_>>();
@@ -1498,21 +2509,22 @@ impl Forge for GitHub {
             ));
         }
 
-        let api_url = Url::parse(&format!(
-            "https://api.github.com/repos/{}/{}",
-            path_elements[0], path_elements[1]
-        ))
-        .unwrap();
-
-        let response = match reqwest::get(api_url).await {
-            Ok(response) => response,
-            Err(e) if e.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+        let data: serde_json::Value = match forges::api::ApiClient::github()
+            .get(&format!("repos/{}/{}", path_elements[0], path_elements[1]))
+            .await
+        {
+            Ok(data) => data,
+            Err(e @ HTTPJSONError::Error { status: 404, .. }) => {
                 return Err(CanonicalizeError::InvalidUrl(
                     url.clone(),
                     format!("Project does not exist {}", e),
                 ));
             }
-            Err(e) if e.status() == Some(reqwest::StatusCode::FORBIDDEN) => {
+            Err(
+                e @ HTTPJSONError::Error {
+                    status: 403 | 429, ..
+                },
+            ) => {
                 // Probably rate limited
                 warn!("Unable to verify bug database URL {}: {}", url, e);
                 return Err(CanonicalizeError::RateLimited(url.clone()));
@@ -1524,12 +2536,6 @@ impl Forge for GitHub {
                 ));
             }
         };
-        let data = response.json::<serde_json::Value>().await.map_err(|e| {
-            CanonicalizeError::Unverifiable(
-                url.clone(),
-                format!("Unable to verify bug database URL: {}", e),
-            )
-        })?;
 
         if data["has_issues"].as_bool() != Some(true) {
             return Err(CanonicalizeError::InvalidUrl(
@@ -1568,6 +2574,7 @@ impl Forge for GitHub {
         Ok(url)
     }
 
+    #[cfg(feature = "net")]
     async fn check_bug_submit_url_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
         let mut path_segments = url.path_segments().unwrap().collect::<Vec<_>>();
         path_segments.pop();
@@ -1609,6 +2616,78 @@ impl Forge for GitHub {
         Some(with_path_segments(&url, path.as_slice()).unwrap())
     }
 
+    #[cfg(feature = "net")]
+    async fn discussions_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        let ((owner, repo), data) = Self::load_repository(url).await?;
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Unable to check discussions for {}: {}", url, e);
+                return None;
+            }
+        };
+        if data["has_discussions"].as_bool() != Some(true) {
+            return None;
+        }
+
+        Url::parse(&format!(
+            "https://github.com/{}/{}/discussions",
+            owner, repo
+        ))
+        .ok()
+    }
+
+    #[cfg(feature = "net")]
+    async fn mirror_source_from_repo_url(&self, url: &Url) -> Option<Url> {
+        let (_, data) = Self::load_repository(url).await?;
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                debug!("Unable to check whether {} is a mirror: {}", url, e);
+                return None;
+            }
+        };
+        // mirror_url is only set for repositories that GitHub itself mirrors
+        if let Some(mirror_url) = data["mirror_url"].as_str() {
+            return mirror_url.parse().ok();
+        }
+        data["description"]
+            .as_str()
+            .and_then(vcs::mirror_source_from_text)
+            .and_then(|u| u.parse().ok())
+    }
+
+    #[cfg(feature = "net")]
+    async fn organization_from_repo_url(&self, url: &Url) -> Option<String> {
+        let owner = url.path_segments()?.next()?;
+        if owner.is_empty() {
+            return None;
+        }
+
+        // Users and organizations share a namespace, but only organizations exist here
+        let data: serde_json::Value = match forges::api::ApiClient::github()
+            .get(&format!("orgs/{}", owner))
+            .await
+        {
+            Ok(data) => data,
+            Err(HTTPJSONError::Error { status: 404, .. }) => return None,
+            Err(e) => {
+                debug!(
+                    "Unable to check whether {} is an organization: {}",
+                    owner, e
+                );
+                return None;
+            }
+        };
+        Some(
+            data["name"]
+                .as_str()
+                .filter(|n| !n.is_empty())
+                .unwrap_or(owner)
+                .to_string(),
+        )
+    }
+
     fn repo_url_from_merge_request_url(&self, url: &Url) -> Option<Url> {
         let path_elements = url
             .path_segments()
@@ -1621,8 +2700,83 @@ impl Forge for GitHub {
         url.set_scheme("https").expect("valid scheme");
         Some(with_path_segments(&url, &path_elements[0..2]).unwrap())
     }
+
+    fn pages_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        let mut segments = url.path_segments()?;
+        let owner = segments.next()?.to_lowercase();
+        let repo = segments.next()?;
+        let repo = repo.strip_suffix(".git").unwrap_or(repo);
+        if owner.is_empty() || repo.is_empty() {
+            return None;
+        }
+        // This repository holds the site of the user or organization itself
+        if repo.eq_ignore_ascii_case(&format!("{}.github.io", owner)) {
+            return None;
+        }
+        Url::parse(&format!("https://{}.github.io/{}/", owner, repo)).ok()
+    }
+
+    fn project_from_repo_url(&self, url: &Url) -> Option<String> {
+        let mut segments = url.path_segments()?;
+        let owner = segments.next().filter(|s| !s.is_empty())?;
+        let repo = segments.next().filter(|s| !s.is_empty())?;
+        Some(format!(
+            "{}/{}",
+            owner,
+            repo.strip_suffix(".git").unwrap_or(repo)
+        ))
+    }
+
+    #[cfg(feature = "net")]
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Summary",
+            "Homepage",
+            "License",
+            "Version",
+            "Donation",
+            "Bug-Database",
+        ]
+    }
+
+    /// Fill in the fields that GitHub knows about, using a single GraphQL query.
+    ///
+    /// `project` is the path of the repository, e.g. "jelmer/dulwich".
+    #[cfg(feature = "net")]
+    async fn extend_metadata(
+        &self,
+        metadata: &mut Vec<UpstreamDatumWithMetadata>,
+        project: &str,
+        max_certainty: Option<Certainty>,
+    ) {
+        let Some((owner, name)) = project.split_once('/') else {
+            return;
+        };
+        if !crate::forges::github::graphql_available() {
+            debug!(
+                "No token for github.com, not querying metadata of {}",
+                project
+            );
+            return;
+        }
+        extend_from_external_guesser(metadata, max_certainty, self.supported_fields(), || async {
+            match crate::forges::github::repository_metadata(owner, name).await {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    debug!("GitHub repository {} does not exist", project);
+                    vec![]
+                }
+                Err(e) => {
+                    debug!("Unable to query GitHub for {}: {}", project, e);
+                    vec![]
+                }
+            }
+        })
+        .await
+    }
 }
 
+#[cfg(feature = "net")]
 static DEFAULT_ASCII_SET: percent_encoding::AsciiSet = percent_encoding::CONTROLS
     .add(b'/')
     .add(b'?')
@@ -1645,6 +2799,12 @@ impl GitLab {
 
 #[async_trait::async_trait]
 impl Forge for GitLab {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str().is_some_and(|host| {
+            crate::data::tables().is_known_gitlab_site(host) || host.starts_with("gitlab.")
+        })
+    }
+
     fn name(&self) -> &'static str {
         "GitLab"
     }
@@ -1653,6 +2813,53 @@ impl Forge for GitLab {
         true
     }
 
+    /// GitLab is hosted on many sites, so projects are identified by their URL.
+    #[cfg(feature = "net")]
+    fn project_from_repo_url(&self, url: &Url) -> Option<String> {
+        let path = crate::forges::gitlab::project_path(url)?;
+        Some(format!("https://{}/{}", url.host_str()?, path))
+    }
+
+    #[cfg(feature = "net")]
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Summary",
+            "Keywords",
+            "Wiki",
+            "Repository-Browse",
+            "Version",
+        ]
+    }
+
+    /// Fill in the fields that GitLab knows about.
+    ///
+    /// `project` is the URL of the project, e.g. "https://salsa.debian.org/jelmer/dulwich".
+    #[cfg(feature = "net")]
+    async fn extend_metadata(
+        &self,
+        metadata: &mut Vec<UpstreamDatumWithMetadata>,
+        project: &str,
+        max_certainty: Option<Certainty>,
+    ) {
+        let Ok(url) = project.parse::<Url>() else {
+            return;
+        };
+        extend_from_external_guesser(metadata, max_certainty, self.supported_fields(), || async {
+            match crate::forges::gitlab::project_metadata(&url).await {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    debug!("GitLab project {} does not exist", project);
+                    vec![]
+                }
+                Err(e) => {
+                    debug!("Unable to query GitLab for {}: {}", project, e);
+                    vec![]
+                }
+            }
+        })
+        .await
+    }
+
     fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
         let mut path_elements = url
             .path_segments()
@@ -1691,6 +2898,7 @@ impl Forge for GitLab {
         Some(url)
     }
 
+    #[cfg(feature = "net")]
     async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
         let host = url
             .host()
@@ -1710,17 +2918,17 @@ impl Forge for GitLab {
 
         let proj = path_elements.join("/");
         let proj_segment = utf8_percent_encode(proj.as_str(), &DEFAULT_ASCII_SET);
-        let api_url = Url::parse(&format!(
-            "https://{}/api/v4/projects/{}",
-            host, proj_segment
-        ))
-        .map_err(|_| {
-            CanonicalizeError::InvalidUrl(
+        let client = forges::api::ApiClient::gitlab(&host.to_string()).map_err(|_| {
+            CanonicalizeError::InvalidUrl(url.clone(), "GitLab URL with invalid host".to_string())
+        })?;
+        match client
+            .get::<serde_json::Value>(&format!("projects/{}", proj_segment))
+            .await
+        {
+            Err(HTTPJSONError::InvalidPath(_)) => Err(CanonicalizeError::InvalidUrl(
                 url.clone(),
                 "GitLab URL with invalid project path".to_string(),
-            )
-        })?;
-        match load_json_url(&api_url, None).await {
+            )),
             Ok(data) => {
                 // issues_enabled is only provided when the user is authenticated,
                 // so if we're not then we just fall back to checking the canonical URL
@@ -1760,6 +2968,7 @@ impl Forge for GitLab {
         }
     }
 
+    #[cfg(feature = "net")]
     async fn check_bug_submit_url_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
         let path_elements = url
             .path_segments()
@@ -1835,8 +3044,35 @@ impl Forge for GitLab {
         }
         Some(with_path_segments(url, &path_elements[0..path_elements.len() - 2]).unwrap())
     }
+
+    fn pages_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        // Self-hosted instances have their own Pages domain, if any
+        if url.host_str()? != "gitlab.com" {
+            return None;
+        }
+        let mut segments = url
+            .path_segments()?
+            .take_while(|s| *s != "-")
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>();
+        if segments.len() < 2 {
+            return None;
+        }
+        let group = segments.remove(0).to_lowercase();
+        let last = segments.len() - 1;
+        segments[last] = segments[last]
+            .strip_suffix(".git")
+            .unwrap_or(segments[last]);
+        Url::parse(&format!(
+            "https://{}.gitlab.io/{}/",
+            group,
+            segments.join("/")
+        ))
+        .ok()
+    }
 }
 
+#[cfg(feature = "providers")]
 pub fn guess_from_travis_yml(
     path: &Path,
     _settings: &GuesserSettings,
@@ -1864,6 +3100,7 @@ pub fn guess_from_travis_yml(
     Ok(ret)
 }
 
+#[cfg(feature = "providers")]
 pub fn guess_from_environment() -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError>
 {
     let mut results = Vec::new();
@@ -1895,14 +3132,139 @@ fn set_datum(metadata: &mut Vec<UpstreamDatumWithMetadata>, datum: UpstreamDatum
     }
 }
 
+/// Sources that disagreed about the value of a single-valued field.
+///
+/// Only one value is kept per field, so without this the other values would be dropped
+/// silently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The value that was kept
+    pub kept: UpstreamDatumWithMetadata,
+
+    /// The other values that were found, in the order they were discarded
+    pub discarded: Vec<UpstreamDatumWithMetadata>,
+}
+
+impl Conflict {
+    /// Name of the field the sources disagree about.
+    pub fn field(&self) -> &'static str {
+        self.kept.datum.field()
+    }
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let describe = |d: &UpstreamDatumWithMetadata| {
+            let value = d
+                .datum
+                .as_str()
+                .map_or_else(|| d.datum.to_string(), |s| s.to_string());
+            let certainty = d
+                .certainty
+                .map_or_else(|| "unknown".to_string(), |c| c.to_string());
+            match d.origin.as_ref() {
+                Some(origin) => format!("{} ({}, from {})", value, certainty, origin),
+                None => format!("{} ({})", value, certainty),
+            }
+        };
+        write!(
+            f,
+            "sources disagree about {}: using {}",
+            self.field(),
+            describe(&self.kept)
+        )?;
+        for d in &self.discarded {
+            write!(f, "; ignoring {}", describe(d))?;
+        }
+        Ok(())
+    }
+}
+
+/// Record a disagreement between `kept` and `discarded` in `conflicts`.
+fn record_conflict(
+    conflicts: &mut Vec<Conflict>,
+    kept: &UpstreamDatumWithMetadata,
+    discarded: UpstreamDatumWithMetadata,
+) {
+    match conflicts
+        .iter_mut()
+        .find(|c| c.field() == kept.datum.field())
+    {
+        Some(conflict) => {
+            if conflict.kept != *kept {
+                let previous = std::mem::replace(&mut conflict.kept, kept.clone());
+                conflict.discarded.push(previous);
+            }
+            if conflict.discarded.iter().all(|d| *d != discarded) {
+                conflict.discarded.push(discarded);
+            }
+            conflict.discarded.retain(|d| d != kept);
+        }
+        None => conflicts.push(Conflict {
+            kept: kept.clone(),
+            discarded: vec![discarded],
+        }),
+    }
+}
+
+/// Record new guesses, returning the ones that were added or replaced an existing value.
+///
+/// For fields with a single value, a guess replaces the current value if it is more certain.
+/// For multi-valued fields, values that were not seen before are appended, and known values
+/// are upgraded if the new guess is more certain.
 pub fn update_from_guesses(
     metadata: &mut Vec<UpstreamDatumWithMetadata>,
     new_items: impl Iterator<Item = UpstreamDatumWithMetadata>,
+) -> Vec<UpstreamDatumWithMetadata> {
+    update_from_guesses_with_conflicts(metadata, new_items, &mut vec![])
+}
+
+/// Like [`update_from_guesses`], but also record values that disagree with each other.
+///
+/// Conflicts are accumulated in `conflicts`, with at most one entry per field, so the same
+/// vector can be passed to several calls.
+pub fn update_from_guesses_with_conflicts(
+    metadata: &mut Vec<UpstreamDatumWithMetadata>,
+    new_items: impl Iterator<Item = UpstreamDatumWithMetadata>,
+    conflicts: &mut Vec<Conflict>,
 ) -> Vec<UpstreamDatumWithMetadata> {
     let mut changed = vec![];
     for datum in new_items {
-        let current_datum = find_datum(metadata, datum.datum.field());
-        if current_datum.is_none() || datum.certainty > current_datum.unwrap().certainty {
+        let datum = crate::download::demote_binary_download(datum);
+        if let Some(current) = metadata.iter_mut().find(|d| d.datum == datum.datum) {
+            // Keep track of all the places that confirm the value
+            current.origin = Origin::combine(current.origin.take(), datum.origin);
+            if datum.certainty > current.certainty {
+                current.certainty = datum.certainty;
+                changed.push(current.clone());
+            }
+            continue;
+        }
+        if descriptors::is_multi_valued(datum.datum.field()) {
+            changed.push(datum.clone());
+            metadata.push(datum);
+            continue;
+        }
+        let current_datum = find_datum(metadata, datum.datum.field()).cloned();
+        let replace = match current_datum.as_ref() {
+            None => true,
+            Some(current) => {
+                datum.certainty > current.certainty
+                    || (datum.certainty == current.certainty
+                        && crate::download::is_preferred_download(&datum.datum, &current.datum))
+            }
+        };
+        match current_datum {
+            Some(current) if current.datum != datum.datum => {
+                if replace {
+                    record_conflict(conflicts, &datum, current);
+                } else {
+                    record_conflict(conflicts, &current, datum.clone());
+                }
+            }
+            _ => {}
+        }
+        if replace {
             changed.push(datum.clone());
             set_datum(metadata, datum);
         }
@@ -1910,6 +3272,7 @@ pub fn update_from_guesses(
     changed
 }
 
+#[cfg(feature = "net")]
 fn possible_fields_missing(
     upstream_metadata: &[UpstreamDatumWithMetadata],
     fields: &[&str],
@@ -1925,6 +3288,7 @@ fn possible_fields_missing(
     false
 }
 
+#[cfg(feature = "net")]
 async fn extend_from_external_guesser<
     F: Fn() -> Fut,
     Fut: std::future::Future<Output = Vec<UpstreamDatum>>,
@@ -1968,6 +3332,10 @@ impl SourceForge {
 
 #[async_trait::async_trait]
 impl Forge for SourceForge {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str() == Some("sourceforge.net")
+    }
+
     fn name(&self) -> &'static str {
         "SourceForge"
     }
@@ -1987,25 +3355,61 @@ impl Forge for SourceForge {
         with_path_segments(url, &["p", project, "bugs"]).ok()
     }
 
+    #[cfg(feature = "net")]
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "Bug-Database",
+            "Download",
+            "SourceForge-Subproject",
+            "Wiki",
+            "MailingList",
+            "Screenshots",
+            "Version",
+        ]
+    }
+
+    #[cfg(feature = "net")]
     async fn extend_metadata(
         &self,
         metadata: &mut Vec<UpstreamDatumWithMetadata>,
         project: &str,
         max_certainty: Option<Certainty>,
     ) {
-        let subproject = find_datum(metadata, "Name").and_then(|f| match f.datum {
-            UpstreamDatum::Name(ref name) => Some(name.to_string()),
-            _ => None,
+        let explicit_subproject =
+            find_datum(metadata, "SourceForge-Subproject").and_then(|f| match f.datum {
+                UpstreamDatum::SourceForgeSubproject(ref name) => Some(name.to_string()),
+                _ => None,
+            });
+        // Without an explicit subproject, the name may still help to pick a repository
+        let subproject = explicit_subproject.clone().or_else(|| {
+            find_datum(metadata, "Name").and_then(|f| match f.datum {
+                UpstreamDatum::Name(ref name) => Some(name.to_string()),
+                _ => None,
+            })
         });
 
-        extend_from_external_guesser(
-            metadata,
-            max_certainty,
-            &["Homepage", "Name", "Repository", "Bug-Database"],
-            || async {
-                crate::forges::sourceforge::guess_from_sf(project, subproject.as_deref()).await
-            },
-        )
+        extend_from_external_guesser(metadata, max_certainty, self.supported_fields(), || async {
+            let mut results =
+                crate::forges::sourceforge::guess_from_sf(project, subproject.as_deref()).await;
+            // A subproject without its own tools is a directory in the project's files
+            if let Some(explicit_subproject) = explicit_subproject.as_deref() {
+                if !results
+                    .iter()
+                    .any(|d| matches!(d, UpstreamDatum::SourceForgeSubproject(..)))
+                {
+                    results.push(UpstreamDatum::Download(
+                        crate::forges::sourceforge::subproject_files_url(
+                            project,
+                            explicit_subproject,
+                        ),
+                    ));
+                }
+            }
+            results
+        })
         .await
     }
 }
@@ -2024,7 +3428,13 @@ impl Launchpad {
     }
 }
 
+#[async_trait::async_trait]
 impl Forge for Launchpad {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| host == "launchpad.net" || host.ends_with(".launchpad.net"))
+    }
+
     fn name(&self) -> &'static str {
         "launchpad"
     }
@@ -2053,456 +3463,1086 @@ impl Forge for Launchpad {
 
         with_path_segments(url, &[project, "+filebug"]).ok()
     }
-}
 
-pub async fn find_forge(url: &Url, net_access: Option<bool>) -> Option<Box<dyn Forge>> {
-    if url.host_str()? == "sourceforge.net" {
-        return Some(Box::new(SourceForge::new()));
+    #[cfg(feature = "net")]
+    async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
+        if url.host_str() != Some("bugs.launchpad.net") {
+            return Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Not a Launchpad bug tracker URL".to_string(),
+            ));
+        }
+        let name = crate::forges::launchpad::project_from_url(url).ok_or_else(|| {
+            CanonicalizeError::InvalidUrl(url.clone(), "Launchpad URL without project".to_string())
+        })?;
+        match crate::forges::launchpad::project(&name).await {
+            Ok(Some(project)) if project.official_bugs => {
+                Ok(crate::forges::launchpad::bug_database_url(&project.name))
+            }
+            Ok(Some(_)) => Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Project does not use Launchpad for bugs".to_string(),
+            )),
+            Ok(None) => Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Project does not exist".to_string(),
+            )),
+            Err(e) => Err(CanonicalizeError::Unverifiable(
+                url.clone(),
+                format!("Unable to verify bug database URL: {}", e),
+            )),
+        }
     }
 
-    if url.host_str()?.ends_with(".launchpad.net") {
-        return Some(Box::new(Launchpad::new()));
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        crate::forges::launchpad::project_from_url(url)
+            .map(|project| crate::forges::launchpad::bug_database_url(&project))
     }
 
-    if url.host_str()? == "github.com" {
-        return Some(Box::new(GitHub::new()));
+    fn project_from_repo_url(&self, url: &Url) -> Option<String> {
+        crate::forges::launchpad::project_from_url(url)
     }
 
-    if vcs::is_gitlab_site(url.host_str()?, net_access).await {
-        return Some(Box::new(GitLab::new()));
+    #[cfg(feature = "net")]
+    fn supported_fields(&self) -> &'static [&'static str] {
+        crate::forges::launchpad::FIELDS
     }
 
-    None
-}
-
-pub async fn check_bug_database_canonical(
-    url: &Url,
-    net_access: Option<bool>,
-) -> Result<Url, CanonicalizeError> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge
-            .bug_database_url_from_bug_submit_url(url)
-            .ok_or(CanonicalizeError::Unverifiable(
-                url.clone(),
-                "no bug database URL found".to_string(),
-            ))
-    } else {
-        Err(CanonicalizeError::Unverifiable(
-            url.clone(),
-            "unknown forge".to_string(),
-        ))
+    /// Fill in the fields that Launchpad knows about.
+    ///
+    /// `project` is the name of the project on Launchpad, e.g. "dulwich".
+    #[cfg(feature = "net")]
+    async fn extend_metadata(
+        &self,
+        metadata: &mut Vec<UpstreamDatumWithMetadata>,
+        project: &str,
+        max_certainty: Option<Certainty>,
+    ) {
+        extend_from_external_guesser(metadata, max_certainty, self.supported_fields(), || async {
+            match crate::forges::launchpad::project_metadata(project).await {
+                Ok(Some(data)) => data,
+                Ok(None) => {
+                    debug!("Launchpad project {} does not exist", project);
+                    vec![]
+                }
+                Err(e) => {
+                    debug!("Unable to query Launchpad for {}: {}", project, e);
+                    vec![]
+                }
+            }
+        })
+        .await
     }
 }
 
-pub async fn bug_submit_url_from_bug_database_url(
-    url: &Url,
-    net_access: Option<bool>,
-) -> Option<Url> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge.bug_submit_url_from_bug_database_url(url)
-    } else {
-        None
+/// Bug database URLs for forges that keep issues at `/<owner>/<repository>/issues`.
+mod owner_repo_issues {
+    use super::{with_path_segments, Url};
+
+    pub fn bug_database_url_from_bug_submit_url(url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 3 || path_elements.len() > 4 || path_elements[2] != "issues" {
+            return None;
+        }
+        if path_elements.len() == 4 && !["new", ""].contains(&path_elements[3]) {
+            return None;
+        }
+        let mut url = url.clone();
+        url.set_scheme("https").ok()?;
+        with_path_segments(&url, &path_elements[0..3]).ok()
     }
-}
 
-pub async fn bug_database_url_from_bug_submit_url(
-    url: &Url,
-    net_access: Option<bool>,
-) -> Option<Url> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge.bug_database_url_from_bug_submit_url(url)
-    } else {
-        None
+    pub fn bug_submit_url_from_bug_database_url(url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() != 3 || path_elements[2] != "issues" {
+            return None;
+        }
+        let mut url = url.clone();
+        url.set_scheme("https").ok()?;
+        with_path_segments(&url, &[path_elements[0], path_elements[1], "issues", "new"]).ok()
     }
-}
 
-pub async fn guess_bug_database_url_from_repo_url(
-    url: &Url,
-    net_access: Option<bool>,
-) -> Option<Url> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge.bug_database_url_from_repo_url(url)
-    } else {
-        None
+    pub fn bug_database_from_issue_url(url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 3 || path_elements[2] != "issues" {
+            return None;
+        }
+        let mut url = url.clone();
+        url.set_scheme("https").ok()?;
+        with_path_segments(&url, &path_elements[0..3]).ok()
     }
-}
 
-pub async fn repo_url_from_merge_request_url(url: &Url, net_access: Option<bool>) -> Option<Url> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge.repo_url_from_merge_request_url(url)
-    } else {
-        None
+    pub fn bug_database_url_from_repo_url(url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.take(2).collect::<Vec<_>>();
+        if path_elements.len() != 2 || path_elements[1].is_empty() {
+            return None;
+        }
+        let mut url = url.clone();
+        url.set_scheme("https").ok()?;
+        with_path_segments(
+            &url,
+            &[
+                path_elements[0],
+                path_elements[1].trim_end_matches(".git"),
+                "issues",
+            ],
+        )
+        .ok()
     }
 }
 
-pub async fn bug_database_from_issue_url(url: &Url, net_access: Option<bool>) -> Option<Url> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge.bug_database_from_issue_url(url)
-    } else {
-        None
-    }
-}
+pub struct Gitee;
 
-pub async fn check_bug_submit_url_canonical(
-    url: &Url,
-    net_access: Option<bool>,
-) -> Result<Url, CanonicalizeError> {
-    if let Some(forge) = find_forge(url, net_access).await {
-        forge
-            .bug_submit_url_from_bug_database_url(url)
-            .ok_or(CanonicalizeError::Unverifiable(
-                url.clone(),
-                "no bug submit URL found".to_string(),
-            ))
-    } else {
-        Err(CanonicalizeError::Unverifiable(
-            url.clone(),
-            "unknown forge".to_string(),
-        ))
+impl Default for Gitee {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-pub fn extract_pecl_package_name(url: &str) -> Option<String> {
-    let pecl_regex = regex!(r"https?://pecl\.php\.net/package/(.*)");
-    if let Some(captures) = pecl_regex.captures(url) {
-        return captures.get(1).map(|m| m.as_str().to_string());
+impl Gitee {
+    pub fn new() -> Self {
+        Self
     }
-    None
 }
 
-pub fn extract_hackage_package(url: &str) -> Option<String> {
-    let hackage_regex = regex!(r"https?://hackage\.haskell\.org/package/([^/]+)/.*");
-    if let Some(captures) = hackage_regex.captures(url) {
-        return captures.get(1).map(|m| m.as_str().to_string());
+impl Forge for Gitee {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str() == Some("gitee.com")
     }
-    None
-}
 
-/// Obtain metadata from a URL related to the project
-pub fn metadata_from_url(url: &str, origin: &Origin) -> Vec<UpstreamDatumWithMetadata> {
-    let mut results = Vec::new();
-    if let Some(sf_project) = crate::forges::sourceforge::extract_sf_project_name(url) {
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::SourceForgeProject(sf_project),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::Archive("SourceForge".to_string()),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
+    fn name(&self) -> &'static str {
+        "Gitee"
     }
 
-    if let Some(pecl_package) = extract_pecl_package_name(url) {
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::PeclPackage(pecl_package),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::Archive("Pecl".to_string()),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        true
     }
 
-    if let Some(haskell_package) = extract_hackage_package(url) {
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::HaskellPackage(haskell_package),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
-        results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::Archive("Hackage".to_string()),
-            certainty: Some(Certainty::Certain),
-            origin: Some(origin.clone()),
-        });
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_bug_submit_url(url)
     }
-    results
-}
 
-pub async fn get_repology_metadata(srcname: &str, repo: Option<&str>) -> Option<serde_json::Value> {
-    let repo = repo.unwrap_or("debian_unstable");
-    let url = format!(
-        "https://repology.org/tools/project-by?repo={}&name_type=srcname'
-           '&target_page=api_v1_project&name={}",
-        repo, srcname
-    );
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_submit_url_from_bug_database_url(url)
+    }
 
-    match load_json_url(&Url::parse(url.as_str()).unwrap(), None).await {
-        Ok(json) => Some(json),
-        Err(HTTPJSONError::Error { status: 404, .. }) => None,
-        Err(e) => {
-            debug!("Failed to load repology metadata: {:?}", e);
-            None
-        }
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_from_issue_url(url)
     }
-}
 
-pub fn guess_from_path(
-    path: &Path,
-    _settings: &GuesserSettings,
-) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
-    let basename = path.file_name().and_then(|s| s.to_str());
-    let mut ret = Vec::new();
-    if let Some(basename_str) = basename {
-        let re = regex!(r"(.*)-([0-9.]+)");
-        if let Some(captures) = re.captures(basename_str) {
-            if let Some(name) = captures.get(1) {
-                ret.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::Name(name.as_str().to_string()),
-                    certainty: Some(Certainty::Possible),
-                    origin: Some(path.into()),
-                });
-            }
-            if let Some(version) = captures.get(2) {
-                ret.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::Version(version.as_str().to_string()),
-                    certainty: Some(Certainty::Possible),
-                    origin: Some(path.into()),
-                });
-            }
-        } else {
-            ret.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::Name(basename_str.to_string()),
-                certainty: Some(Certainty::Possible),
-                origin: Some(path.into()),
-            });
-        }
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_repo_url(url)
     }
-    Ok(ret)
 }
 
-#[cfg(feature = "pyo3")]
-impl FromPyObject<'_> for UpstreamDatum {
-    fn extract_bound(obj: &Bound<PyAny>) -> PyResult<Self> {
-        let (field, val): (String, Bound<PyAny>) = if let Ok((field, val)) =
-            obj.extract::<(String, Bound<PyAny>)>()
-        {
-            (field, val)
-        } else if let Ok(datum) = obj.getattr("datum") {
-            let field = datum.getattr("field")?.extract::<String>()?;
-            let val = datum.getattr("value")?;
-            (field, val)
-        } else if obj.hasattr("field")? && obj.hasattr("value")? {
-            let field = obj.getattr("field")?.extract::<String>()?;
-            let val = obj.getattr("value")?;
-            (field, val)
-        } else {
-            return Err(PyTypeError::new_err((
-                format!("Expected a tuple of (field, value) or an object with field and value attributesm, found {:?}", obj),
-            )));
-        };
+/// Gitea, and its fork Forgejo, which is used by Codeberg.
+pub struct Gitea;
 
-        match field.as_str() {
-            "Name" => Ok(UpstreamDatum::Name(val.extract::<String>()?)),
-            "Version" => Ok(UpstreamDatum::Version(val.extract::<String>()?)),
-            "Homepage" => Ok(UpstreamDatum::Homepage(val.extract::<String>()?)),
-            "Bug-Database" => Ok(UpstreamDatum::BugDatabase(val.extract::<String>()?)),
-            "Bug-Submit" => Ok(UpstreamDatum::BugSubmit(val.extract::<String>()?)),
-            "Contact" => Ok(UpstreamDatum::Contact(val.extract::<String>()?)),
-            "Repository" => Ok(UpstreamDatum::Repository(val.extract::<String>()?)),
-            "Repository-Browse" => Ok(UpstreamDatum::RepositoryBrowse(val.extract::<String>()?)),
-            "License" => Ok(UpstreamDatum::License(val.extract::<String>()?)),
-            "Description" => Ok(UpstreamDatum::Description(val.extract::<String>()?)),
-            "Summary" => Ok(UpstreamDatum::Summary(val.extract::<String>()?)),
-            "Cargo-Crate" => Ok(UpstreamDatum::CargoCrate(val.extract::<String>()?)),
-            "Security-MD" => Ok(UpstreamDatum::SecurityMD(val.extract::<String>()?)),
-            "Security-Contact" => Ok(UpstreamDatum::SecurityContact(val.extract::<String>()?)),
-            "Keywords" => Ok(UpstreamDatum::Keywords(val.extract::<Vec<String>>()?)),
-            "Copyright" => Ok(UpstreamDatum::Copyright(val.extract::<String>()?)),
-            "Documentation" => Ok(UpstreamDatum::Documentation(val.extract::<String>()?)),
-            "API-Documentation" => Ok(UpstreamDatum::APIDocumentation(val.extract::<String>()?)),
-            "Go-Import-Path" => Ok(UpstreamDatum::GoImportPath(val.extract::<String>()?)),
-            "Download" => Ok(UpstreamDatum::Download(val.extract::<String>()?)),
-            "Wiki" => Ok(UpstreamDatum::Wiki(val.extract::<String>()?)),
-            "MailingList" => Ok(UpstreamDatum::MailingList(val.extract::<String>()?)),
-            "Funding" => Ok(UpstreamDatum::Funding(val.extract::<String>()?)),
-            "SourceForge-Project" => {
-                Ok(UpstreamDatum::SourceForgeProject(val.extract::<String>()?))
-            }
-            "Archive" => Ok(UpstreamDatum::Archive(val.extract::<String>()?)),
-            "Demo" => Ok(UpstreamDatum::Demo(val.extract::<String>()?)),
-            "Pecl-Package" => Ok(UpstreamDatum::PeclPackage(val.extract::<String>()?)),
-            "Haskell-Package" => Ok(UpstreamDatum::HaskellPackage(val.extract::<String>()?)),
-            "Author" => Ok(UpstreamDatum::Author(val.extract::<Vec<Person>>()?)),
-            "Maintainer" => Ok(UpstreamDatum::Maintainer(val.extract::<Person>()?)),
-            "Changelog" => Ok(UpstreamDatum::Changelog(val.extract::<String>()?)),
-            "Screenshots" => Ok(UpstreamDatum::Screenshots(val.extract::<Vec<String>>()?)),
-            "Cite-As" => Ok(UpstreamDatum::CiteAs(val.extract::<String>()?)),
-            "Registry" => {
-                let v = val.extract::<Vec<Bound<PyAny>>>()?;
-                let mut registry = Vec::new();
-                for item in v {
-                    let name = item.get_item("Name")?.extract::<String>()?;
-                    let entry = item.get_item("Entry")?.extract::<String>()?;
-                    registry.push((name, entry));
-                }
-                Ok(UpstreamDatum::Registry(registry))
-            }
-            "Donation" => Ok(UpstreamDatum::Donation(val.extract::<String>()?)),
-            "Webservice" => Ok(UpstreamDatum::Webservice(val.extract::<String>()?)),
-            "BuildSystem" => Ok(UpstreamDatum::BuildSystem(val.extract::<String>()?)),
-            _ => Err(PyRuntimeError::new_err(format!("Unknown field: {}", field))),
-        }
+impl Default for Gitea {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-#[cfg(feature = "pyo3")]
-impl ToPyObject for UpstreamDatum {
-    fn to_object(&self, py: Python) -> PyObject {
-        (
-            self.field().to_string(),
-            match self {
-                UpstreamDatum::Name(n) => n.into_py(py),
-                UpstreamDatum::Version(v) => v.into_py(py),
-                UpstreamDatum::Contact(c) => c.into_py(py),
-                UpstreamDatum::Summary(s) => s.into_py(py),
-                UpstreamDatum::License(l) => l.into_py(py),
-                UpstreamDatum::Homepage(h) => h.into_py(py),
-                UpstreamDatum::Description(d) => d.into_py(py),
-                UpstreamDatum::BugDatabase(b) => b.into_py(py),
-                UpstreamDatum::BugSubmit(b) => b.into_py(py),
-                UpstreamDatum::Repository(r) => r.into_py(py),
-                UpstreamDatum::RepositoryBrowse(r) => r.into_py(py),
-                UpstreamDatum::SecurityMD(s) => s.into_py(py),
-                UpstreamDatum::SecurityContact(s) => s.into_py(py),
-                UpstreamDatum::CargoCrate(c) => c.into_py(py),
-                UpstreamDatum::Keywords(ks) => ks.to_object(py),
-                UpstreamDatum::Copyright(c) => c.into_py(py),
-                UpstreamDatum::Documentation(a) => a.into_py(py),
-                UpstreamDatum::APIDocumentation(a) => a.into_py(py),
-                UpstreamDatum::GoImportPath(ip) => ip.into_py(py),
-                UpstreamDatum::Archive(a) => a.into_py(py),
-                UpstreamDatum::Demo(d) => d.into_py(py),
-                UpstreamDatum::Maintainer(m) => m.to_object(py),
-                UpstreamDatum::Author(a) => a.to_object(py),
-                UpstreamDatum::Wiki(w) => w.into_py(py),
-                UpstreamDatum::Download(d) => d.into_py(py),
-                UpstreamDatum::MailingList(m) => m.into_py(py),
-                UpstreamDatum::SourceForgeProject(m) => m.into_py(py),
-                UpstreamDatum::PeclPackage(p) => p.into_py(py),
-                UpstreamDatum::Funding(p) => p.into_py(py),
-                UpstreamDatum::Changelog(c) => c.into_py(py),
-                UpstreamDatum::HaskellPackage(p) => p.into_py(py),
-                UpstreamDatum::DebianITP(i) => i.into_py(py),
-                UpstreamDatum::Screenshots(s) => s.to_object(py),
-                UpstreamDatum::CiteAs(s) => s.to_object(py),
-                UpstreamDatum::Registry(r) => r
-                    .iter()
-                    .map(|(name, entry)| {
-                        let dict = PyDict::new_bound(py);
-                        dict.set_item("Name", name).unwrap();
-                        dict.set_item("Entry", entry).unwrap();
-                        dict.into()
-                    })
-                    .collect::<Vec<PyObject>>()
-                    .to_object(py),
-                UpstreamDatum::Donation(d) => d.to_object(py),
-                UpstreamDatum::Webservice(w) => w.to_object(py),
-                UpstreamDatum::BuildSystem(b) => b.to_object(py),
-            },
-        )
-            .to_object(py)
+impl Gitea {
+    pub fn new() -> Self {
+        Self
     }
 }
 
-#[cfg(feature = "pyo3")]
-impl FromPyObject<'_> for UpstreamDatumWithMetadata {
-    fn extract_bound(obj: &Bound<PyAny>) -> PyResult<Self> {
-        let certainty = obj.getattr("certainty")?.extract::<Option<String>>()?;
-        let origin = obj.getattr("origin")?.extract::<Option<Origin>>()?;
-        let datum = if obj.hasattr("datum")? {
-            obj.getattr("datum")?.extract::<UpstreamDatum>()
-        } else {
-            obj.extract::<UpstreamDatum>()
-        }?;
-
-        Ok(UpstreamDatumWithMetadata {
-            datum,
-            certainty: certainty.map(|s| s.parse().unwrap()),
-            origin,
+#[async_trait::async_trait]
+impl Forge for Gitea {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str().is_some_and(|host| {
+            crate::data::tables().is_known_gitea_site(host)
+                || host.starts_with("gitea.")
+                || host.starts_with("forgejo.")
         })
     }
-}
-
-#[derive(Debug)]
-pub enum ProviderError {
-    ParseError(String),
-    IoError(std::io::Error),
-    Other(String),
-    HttpJsonError(HTTPJSONError),
-    ExtrapolationLimitExceeded(usize),
-}
 
-impl std::fmt::Display for ProviderError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            ProviderError::ParseError(e) => write!(f, "Parse error: {}", e),
-            ProviderError::IoError(e) => write!(f, "IO error: {}", e),
-            ProviderError::Other(e) => write!(f, "Other error: {}", e),
-            ProviderError::HttpJsonError(e) => write!(f, "HTTP JSON error: {}", e),
-            ProviderError::ExtrapolationLimitExceeded(e) => {
-                write!(f, "Extrapolation limit exceeded: {}", e)
-            }
-        }
+    fn name(&self) -> &'static str {
+        "Gitea"
     }
-}
 
-impl std::error::Error for ProviderError {}
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        true
+    }
 
-impl From<HTTPJSONError> for ProviderError {
-    fn from(e: HTTPJSONError) -> Self {
-        ProviderError::HttpJsonError(e)
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_bug_submit_url(url)
     }
-}
 
-impl From<std::io::Error> for ProviderError {
-    fn from(e: std::io::Error) -> Self {
-        ProviderError::IoError(e)
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_submit_url_from_bug_database_url(url)
     }
-}
 
-impl From<reqwest::Error> for ProviderError {
-    fn from(e: reqwest::Error) -> Self {
-        ProviderError::Other(e.to_string())
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_from_issue_url(url)
     }
-}
 
-#[cfg(feature = "pyo3")]
-pyo3::create_exception!(
-    upstream_ontologist,
-    ParseError,
-    pyo3::exceptions::PyException
-);
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_repo_url(url)
+    }
 
-#[cfg(feature = "pyo3")]
-impl From<ProviderError> for PyErr {
-    fn from(e: ProviderError) -> PyErr {
-        match e {
-            ProviderError::IoError(e) => e.into(),
-            ProviderError::ParseError(e) => ParseError::new_err((e,)),
-            ProviderError::Other(e) => PyRuntimeError::new_err((e,)),
-            ProviderError::HttpJsonError(e) => PyRuntimeError::new_err((e.to_string(),)),
-            ProviderError::ExtrapolationLimitExceeded(e) => {
-                PyRuntimeError::new_err((e.to_string(),))
-            }
+    fn repo_url_from_merge_request_url(&self, url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 4 || path_elements[2] != "pulls" {
+            return None;
         }
+        with_path_segments(url, &path_elements[0..2]).ok()
     }
-}
 
-#[derive(Debug, Default, Clone)]
-pub struct GuesserSettings {
+    #[cfg(feature = "net")]
+    async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| CanonicalizeError::InvalidUrl(url.clone(), "no host".to_string()))?;
+        let path_elements = url.path_segments().unwrap().collect::<Vec<_>>();
+        if path_elements.len() != 3 || path_elements[2] != "issues" {
+            return Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Gitea URL with missing path elements".to_string(),
+            ));
+        }
+        let client = forges::api::ApiClient::gitea(host).map_err(|_| {
+            CanonicalizeError::InvalidUrl(url.clone(), "Gitea URL with invalid host".to_string())
+        })?;
+        let data: serde_json::Value = match client
+            .get(&format!("repos/{}/{}", path_elements[0], path_elements[1]))
+            .await
+        {
+            Ok(data) => data,
+            Err(e @ HTTPJSONError::Error { status: 404, .. }) => {
+                return Err(CanonicalizeError::InvalidUrl(
+                    url.clone(),
+                    format!("Project does not exist {}", e),
+                ));
+            }
+            Err(e) => {
+                return Err(CanonicalizeError::Unverifiable(
+                    url.clone(),
+                    format!("Unable to verify bug database URL: {}", e),
+                ));
+            }
+        };
+        if data["has_issues"].as_bool() == Some(false) {
+            return Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Project does not have issues enabled".to_string(),
+            ));
+        }
+        // Repositories that have been renamed are redirected to their new location
+        match data["html_url"].as_str().map(|u| u.parse::<Url>()) {
+            Some(Ok(html_url)) => with_path_segments(
+                &html_url,
+                &html_url
+                    .path_segments()
+                    .unwrap()
+                    .chain(std::iter::once("issues"))
+                    .collect::<Vec<_>>(),
+            )
+            .map_err(|_| {
+                CanonicalizeError::InvalidUrl(url.clone(), "invalid html_url".to_string())
+            }),
+            _ => Ok(url.clone()),
+        }
+    }
+}
+
+pub struct SourceHut;
+
+impl Default for SourceHut {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SourceHut {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl Forge for SourceHut {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str()
+            .is_some_and(|host| forges::sourcehut::HOSTS.contains(&host))
+    }
+
+    fn name(&self) -> &'static str {
+        "SourceHut"
+    }
+
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        true
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        self.bug_database_from_issue_url(url)
+    }
+
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        if url.host_str()? != "todo.sr.ht" {
+            return None;
+        }
+        let (owner, name) = forges::sourcehut::owner_and_name(url)?;
+        Some(forges::sourcehut::service_url("todo", &owner, &name))
+    }
+
+    /// Trackers are usually named after the repository, but this is only a convention.
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        if !["git.sr.ht", "hg.sr.ht"].contains(&url.host_str()?) {
+            return None;
+        }
+        let (owner, name) = forges::sourcehut::owner_and_name(url)?;
+        Some(forges::sourcehut::service_url("todo", &owner, &name))
+    }
+
+    fn mailing_list_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        if !["git.sr.ht", "hg.sr.ht"].contains(&url.host_str()?) {
+            return None;
+        }
+        let (owner, name) = forges::sourcehut::owner_and_name(url)?;
+        Some(forges::sourcehut::service_url("lists", &owner, &name))
+    }
+
+    #[cfg(feature = "net")]
+    async fn check_bug_database_canonical(&self, url: &Url) -> Result<Url, CanonicalizeError> {
+        let (owner, name) = forges::sourcehut::owner_and_name(url)
+            .filter(|_| url.host_str() == Some("todo.sr.ht"))
+            .ok_or_else(|| {
+                CanonicalizeError::InvalidUrl(
+                    url.clone(),
+                    "SourceHut URL with missing path elements".to_string(),
+                )
+            })?;
+        match forges::sourcehut::tracker_name(&owner, &name).await {
+            Ok(Some(name)) => Ok(forges::sourcehut::service_url("todo", &owner, &name)),
+            Ok(None) => Err(CanonicalizeError::InvalidUrl(
+                url.clone(),
+                "Tracker does not exist".to_string(),
+            )),
+            Err(e) => Err(CanonicalizeError::Unverifiable(
+                url.clone(),
+                format!("Unable to verify bug database URL: {}", e),
+            )),
+        }
+    }
+}
+
+pub struct Bitbucket;
+
+impl Default for Bitbucket {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bitbucket {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Forge for Bitbucket {
+    fn handles_url(&self, url: &Url) -> bool {
+        url.host_str() == Some("bitbucket.org")
+    }
+
+    fn name(&self) -> &'static str {
+        "Bitbucket"
+    }
+
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        true
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_bug_submit_url(url)
+    }
+
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_submit_url_from_bug_database_url(url)
+    }
+
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_from_issue_url(url)
+    }
+
+    fn bug_database_url_from_repo_url(&self, url: &Url) -> Option<Url> {
+        owner_repo_issues::bug_database_url_from_repo_url(url)
+    }
+}
+
+/// OSDN, which keeps tickets per project at `/projects/<project>/ticket/`.
+pub struct Osdn;
+
+impl Default for Osdn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Osdn {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Forge for Osdn {
+    fn handles_url(&self, url: &Url) -> bool {
+        matches!(url.host_str(), Some("osdn.net") | Some("osdn.jp"))
+    }
+
+    fn name(&self) -> &'static str {
+        "OSDN"
+    }
+
+    fn repository_browse_can_be_homepage(&self) -> bool {
+        false
+    }
+
+    fn bug_database_url_from_bug_submit_url(&self, url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 3
+            || path_elements[0] != "projects"
+            || path_elements[2] != "ticket"
+            || !["newticket", ""].contains(path_elements.get(3).unwrap_or(&""))
+        {
+            return None;
+        }
+        with_path_segments(url, &["projects", path_elements[1], "ticket", ""]).ok()
+    }
+
+    fn bug_submit_url_from_bug_database_url(&self, url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 3 || path_elements[0] != "projects" || path_elements[2] != "ticket"
+        {
+            return None;
+        }
+        with_path_segments(url, &["projects", path_elements[1], "ticket", "newticket"]).ok()
+    }
+
+    fn bug_database_from_issue_url(&self, url: &Url) -> Option<Url> {
+        let path_elements = url.path_segments()?.collect::<Vec<_>>();
+        if path_elements.len() < 4 || path_elements[0] != "projects" || path_elements[2] != "ticket"
+        {
+            return None;
+        }
+        with_path_segments(url, &["projects", path_elements[1], "ticket", ""]).ok()
+    }
+}
+
+/// Extract the OSDN project name from a URL.
+pub fn extract_osdn_project_name(url: &str) -> Option<String> {
+    lazy_regex::regex_captures!(
+        r"^https?://(?:www\.)?osdn\.(?:net|jp)/projects/([^/?#]+)",
+        url
+    )
+    .map(|(_, project)| project.to_string())
+    .or_else(|| {
+        lazy_regex::regex_captures!(r"^https?://([^./]+)\.osdn\.(?:io|jp)/", url)
+            .filter(|(_, project)| *project != "www")
+            .map(|(_, project)| project.to_string())
+    })
+}
+
+/// Find the forge that hosts a URL, using the global [`ForgeRegistry`].
+pub async fn find_forge(url: &Url, net_access: Option<bool>) -> Option<Box<dyn Forge>> {
+    ForgeRegistry::global().find(url, net_access).await
+}
+
+pub async fn check_bug_database_canonical(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Result<Url, CanonicalizeError> {
+    ForgeRegistry::global()
+        .check_bug_database_canonical(url, net_access)
+        .await
+}
+
+pub async fn bug_submit_url_from_bug_database_url(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Option<Url> {
+    ForgeRegistry::global()
+        .bug_submit_url_from_bug_database_url(url, net_access)
+        .await
+}
+
+pub async fn bug_database_url_from_bug_submit_url(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Option<Url> {
+    ForgeRegistry::global()
+        .bug_database_url_from_bug_submit_url(url, net_access)
+        .await
+}
+
+pub async fn guess_bug_database_url_from_repo_url(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Option<Url> {
+    ForgeRegistry::global()
+        .guess_bug_database_url_from_repo_url(url, net_access)
+        .await
+}
+
+/// Find the URL of the discussion forum for a repository.
+///
+/// This will make network connections.
+pub async fn guess_discussions_url_from_repo_url(url: &Url) -> Option<Url> {
+    ForgeRegistry::global()
+        .guess_discussions_url_from_repo_url(url)
+        .await
+}
+
+pub async fn guess_mirror_source_from_repo_url(url: &Url) -> Option<Url> {
+    ForgeRegistry::global()
+        .guess_mirror_source_from_repo_url(url)
+        .await
+}
+
+/// Find the name of the organization that owns a repository.
+///
+/// This will make network connections.
+pub async fn guess_organization_from_repo_url(url: &Url) -> Option<String> {
+    ForgeRegistry::global()
+        .guess_organization_from_repo_url(url)
+        .await
+}
+
+pub async fn guess_mailing_list_url_from_repo_url(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Option<Url> {
+    ForgeRegistry::global()
+        .guess_mailing_list_url_from_repo_url(url, net_access)
+        .await
+}
+
+/// Prefer the canonical repository over a mirror of it.
+///
+/// If Repository refers to a mirror, it is replaced with the repository that is mirrored,
+/// and the mirror is recorded as Repository-Mirror.
+pub async fn prefer_canonical_repository(upstream_metadata: &mut UpstreamMetadata) {
+    let repository = match upstream_metadata.get("Repository") {
+        Some(repository) => repository.clone(),
+        None => return,
+    };
+    let url = match repository.datum.to_url() {
+        Some(url) => url,
+        None => return,
+    };
+    let canonical_url = match guess_mirror_source_from_repo_url(&url).await {
+        Some(canonical_url) if canonical_url != url => canonical_url,
+        _ => return,
+    };
+    log::debug!("{} is a mirror of {}", url, canonical_url);
+    if !upstream_metadata.contains_key("Repository-Mirror") {
+        upstream_metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::RepositoryMirror(url.to_string()),
+            certainty: repository.certainty,
+            origin: repository.origin.clone(),
+        });
+    }
+    if let Some(repository) = upstream_metadata.get_mut("Repository") {
+        repository.datum = UpstreamDatum::Repository(canonical_url.to_string());
+    }
+}
+
+/// Check whether a source tree contains documentation that may be published as a site.
+#[cfg(feature = "providers")]
+fn has_documentation_sources(path: &Path) -> bool {
+    path.join("docs").is_dir()
+        || ["mkdocs.yml", "mkdocs.yaml"]
+            .iter()
+            .any(|f| path.join(f).is_file())
+        || ["doc/conf.py", "doc/source/conf.py", "docs/source/conf.py"]
+            .iter()
+            .any(|f| path.join(f).is_file())
+}
+
+/// Set Documentation to the forge's Pages site for the repository, if it exists.
+///
+/// This is only attempted for source trees with documentation in them (a docs/ folder, or
+/// configuration for mkdocs or Sphinx), since most projects don't publish a site.
+#[cfg(feature = "providers")]
+pub async fn probe_pages_documentation(upstream_metadata: &mut UpstreamMetadata, path: &Path) {
+    if upstream_metadata.contains_key("Documentation") || !has_documentation_sources(path) {
+        return;
+    }
+    let Some(repository) = upstream_metadata.get("Repository").cloned() else {
+        return;
+    };
+    let Some(url) = repository.datum.to_url() else {
+        return;
+    };
+    let Some(pages_url) = find_forge(&url, Some(true))
+        .await
+        .and_then(|forge| forge.pages_url_from_repo_url(&url))
+    else {
+        return;
+    };
+    let client = match crate::http::build_client().build() {
+        Ok(client) => client,
+        Err(e) => {
+            debug!("Unable to create HTTP client: {}", e);
+            return;
+        }
+    };
+    let response = match http::send(client.get(pages_url.clone())).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            debug!("No pages at {}: {}", pages_url, response.status());
+            return;
+        }
+        Err(e) => {
+            debug!("Unable to probe {}: {}", pages_url, e);
+            return;
+        }
+    };
+    // Follow redirects, e.g. to a custom domain
+    let documentation_url = response.url().clone();
+    upstream_metadata.insert(UpstreamDatumWithMetadata {
+        datum: UpstreamDatum::Documentation(documentation_url.to_string()),
+        certainty: Some(
+            std::cmp::min(repository.certainty, Some(Certainty::Likely))
+                .unwrap_or(Certainty::Likely),
+        ),
+        origin: Some(Origin::Url(pages_url)),
+    });
+}
+
+pub async fn repo_url_from_merge_request_url(url: &Url, net_access: Option<bool>) -> Option<Url> {
+    ForgeRegistry::global()
+        .repo_url_from_merge_request_url(url, net_access)
+        .await
+}
+
+pub async fn bug_database_from_issue_url(url: &Url, net_access: Option<bool>) -> Option<Url> {
+    ForgeRegistry::global()
+        .bug_database_from_issue_url(url, net_access)
+        .await
+}
+
+pub async fn check_bug_submit_url_canonical(
+    url: &Url,
+    net_access: Option<bool>,
+) -> Result<Url, CanonicalizeError> {
+    ForgeRegistry::global()
+        .check_bug_submit_url_canonical(url, net_access)
+        .await
+}
+
+pub fn extract_pecl_package_name(url: &str) -> Option<String> {
+    let pecl_regex = regex!(r"https?://pecl\.php\.net/package/(.*)");
+    if let Some(captures) = pecl_regex.captures(url) {
+        return captures.get(1).map(|m| m.as_str().to_string());
+    }
+    None
+}
+
+pub fn extract_hackage_package(url: &str) -> Option<String> {
+    let hackage_regex = regex!(r"https?://hackage\.haskell\.org/package/([^/]+)/.*");
+    if let Some(captures) = hackage_regex.captures(url) {
+        return captures.get(1).map(|m| m.as_str().to_string());
+    }
+    None
+}
+
+/// Obtain metadata from a URL related to the project
+pub fn metadata_from_url(url: &str, origin: &Origin) -> Vec<UpstreamDatumWithMetadata> {
+    let mut results = Vec::new();
+    if let Some((sf_project, sf_subproject)) =
+        crate::forges::sourceforge::extract_sf_project_name(url)
+    {
+        if let Some(sf_subproject) = sf_subproject {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::SourceForgeSubproject(sf_subproject),
+                certainty: Some(Certainty::Likely),
+                origin: Some(origin.clone()),
+            });
+        }
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::SourceForgeProject(sf_project),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Archive("SourceForge".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+    }
+
+    if let Some(pecl_package) = extract_pecl_package_name(url) {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::PeclPackage(pecl_package),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Archive("Pecl".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+    }
+
+    if extract_osdn_project_name(url).is_some() {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Archive("OSDN".to_string()),
+            certainty: Some(Certainty::Likely),
+            origin: Some(origin.clone()),
+        });
+    }
+
+    if let Some(haskell_package) = extract_hackage_package(url) {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::HaskellPackage(haskell_package),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Archive("Hackage".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(origin.clone()),
+        });
+    }
+    results
+}
+
+#[cfg(feature = "net")]
+pub async fn get_repology_metadata(srcname: &str, repo: Option<&str>) -> Option<serde_json::Value> {
+    let repo = repo.unwrap_or("debian_unstable");
+    let mut url = Url::parse("https://repology.org/tools/project-by").unwrap();
+    url.query_pairs_mut()
+        .append_pair("repo", repo)
+        .append_pair("name_type", "srcname")
+        .append_pair("target_page", "api_v1_project")
+        .append_pair("name", srcname);
+
+    match load_json_url(&url, None).await {
+        Ok(json) => Some(json),
+        Err(HTTPJSONError::Error { status: 404, .. }) => None,
+        Err(e) => {
+            debug!("Failed to load repology metadata: {:?}", e);
+            None
+        }
+    }
+}
+
+#[cfg(feature = "providers")]
+pub fn guess_from_path(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let basename = path.file_name().and_then(|s| s.to_str());
+    let mut ret = Vec::new();
+    if let Some(basename_str) = basename {
+        let re = regex!(r"(.*)-([0-9.]+)");
+        if let Some(captures) = re.captures(basename_str) {
+            if let Some(name) = captures.get(1) {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Name(name.as_str().to_string()),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+            if let Some(version) = captures.get(2) {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Version(version.as_str().to_string()),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+        } else {
+            ret.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Name(basename_str.to_string()),
+                certainty: Some(Certainty::Possible),
+                origin: Some(path.into()),
+            });
+        }
+    }
+    Ok(ret)
+}
+
+#[cfg(feature = "pyo3")]
+impl FromPyObject<'_> for UpstreamDatum {
+    fn extract_bound(obj: &Bound<PyAny>) -> PyResult<Self> {
+        let (field, val): (String, Bound<PyAny>) = if let Ok((field, val)) =
+            obj.extract::<(String, Bound<PyAny>)>()
+        {
+            (field, val)
+        } else if let Ok(datum) = obj.getattr("datum") {
+            let field = datum.getattr("field")?.extract::<String>()?;
+            let val = datum.getattr("value")?;
+            (field, val)
+        } else if obj.hasattr("field")? && obj.hasattr("value")? {
+            let field = obj.getattr("field")?.extract::<String>()?;
+            let val = obj.getattr("value")?;
+            (field, val)
+        } else {
+            return Err(PyTypeError::new_err((
+                format!("Expected a tuple of (field, value) or an object with field and value attributesm, found {:?}", obj),
+            )));
+        };
+
+        UpstreamDatum::from_field_str(&field, val.extract::<DatumValue>()?)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl ToPyObject for UpstreamDatum {
+    fn to_object(&self, py: Python) -> PyObject {
+        (self.field().to_string(), self.value()).to_object(py)
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl FromPyObject<'_> for DatumValue {
+    fn extract_bound(obj: &Bound<PyAny>) -> PyResult<Self> {
+        if let Ok(s) = obj.extract::<String>() {
+            return Ok(DatumValue::String(s));
+        }
+        if let Ok(i) = obj.extract::<i32>() {
+            return Ok(DatumValue::Integer(i));
+        }
+        if let Ok(items) = obj.extract::<Vec<Bound<PyAny>>>() {
+            if let Ok(strings) = items
+                .iter()
+                .map(|item| item.extract::<String>())
+                .collect::<PyResult<Vec<_>>>()
+            {
+                return Ok(DatumValue::Strings(strings));
+            }
+            // Registry entries are dictionaries with a Name and an Entry
+            if let Ok(pairs) = items
+                .iter()
+                .map(|item| {
+                    Ok((
+                        item.get_item("Name")?.extract::<String>()?,
+                        item.get_item("Entry")?.extract::<String>()?,
+                    ))
+                })
+                .collect::<PyResult<Vec<_>>>()
+            {
+                return Ok(DatumValue::Pairs(pairs));
+            }
+            return Ok(DatumValue::People(
+                items
+                    .iter()
+                    .map(|item| item.extract::<Person>())
+                    .collect::<PyResult<Vec<_>>>()?,
+            ));
+        }
+        Ok(DatumValue::Person(obj.extract::<Person>()?))
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl ToPyObject for DatumValue {
+    fn to_object(&self, py: Python) -> PyObject {
+        match self {
+            DatumValue::String(s) => s.to_object(py),
+            DatumValue::Strings(v) => v.to_object(py),
+            DatumValue::Person(p) => p.to_object(py),
+            DatumValue::People(v) => v.to_object(py),
+            DatumValue::Integer(i) => i.to_object(py),
+            DatumValue::Pairs(v) => v
+                .iter()
+                .map(|(name, entry)| {
+                    let dict = PyDict::new_bound(py);
+                    dict.set_item("Name", name).unwrap();
+                    dict.set_item("Entry", entry).unwrap();
+                    dict.into()
+                })
+                .collect::<Vec<PyObject>>()
+                .to_object(py),
+        }
+    }
+}
+
+#[cfg(feature = "pyo3")]
+impl FromPyObject<'_> for UpstreamDatumWithMetadata {
+    fn extract_bound(obj: &Bound<PyAny>) -> PyResult<Self> {
+        let certainty = obj.getattr("certainty")?.extract::<Option<String>>()?;
+        let origin = obj.getattr("origin")?.extract::<Option<Origin>>()?;
+        let datum = if obj.hasattr("datum")? {
+            obj.getattr("datum")?.extract::<UpstreamDatum>()
+        } else {
+            obj.extract::<UpstreamDatum>()
+        }?;
+
+        Ok(UpstreamDatumWithMetadata {
+            datum,
+            certainty: certainty.map(|s| s.parse().unwrap()),
+            origin,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ProviderError {
+    ParseError(String),
+    IoError(std::io::Error),
+    Other(String),
+    #[cfg(feature = "net")]
+    HttpJsonError(HTTPJSONError),
+    ExtrapolationLimitExceeded(usize),
+    ExtrapolationCycle(Vec<String>),
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ProviderError::ParseError(e) => write!(f, "Parse error: {}", e),
+            ProviderError::IoError(e) => write!(f, "IO error: {}", e),
+            ProviderError::Other(e) => write!(f, "Other error: {}", e),
+            #[cfg(feature = "net")]
+            ProviderError::HttpJsonError(e) => write!(f, "HTTP JSON error: {}", e),
+            ProviderError::ExtrapolationLimitExceeded(e) => {
+                write!(f, "Extrapolation limit exceeded: {}", e)
+            }
+            ProviderError::ExtrapolationCycle(fields) => {
+                write!(f, "Extrapolation cycle between {}", fields.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+#[cfg(feature = "net")]
+impl From<HTTPJSONError> for ProviderError {
+    fn from(e: HTTPJSONError) -> Self {
+        ProviderError::HttpJsonError(e)
+    }
+}
+
+impl From<std::io::Error> for ProviderError {
+    fn from(e: std::io::Error) -> Self {
+        ProviderError::IoError(e)
+    }
+}
+
+#[cfg(feature = "net")]
+impl From<reqwest::Error> for ProviderError {
+    fn from(e: reqwest::Error) -> Self {
+        ProviderError::Other(e.to_string())
+    }
+}
+
+#[cfg(feature = "pyo3")]
+pyo3::create_exception!(
+    upstream_ontologist,
+    ParseError,
+    pyo3::exceptions::PyException
+);
+
+#[cfg(feature = "pyo3")]
+impl From<ProviderError> for PyErr {
+    fn from(e: ProviderError) -> PyErr {
+        match e {
+            ProviderError::IoError(e) => e.into(),
+            ProviderError::ParseError(e) => ParseError::new_err((e,)),
+            ProviderError::Other(e) => PyRuntimeError::new_err((e,)),
+            #[cfg(feature = "net")]
+            ProviderError::HttpJsonError(e) => PyRuntimeError::new_err((e.to_string(),)),
+            ProviderError::ExtrapolationLimitExceeded(e) => {
+                PyRuntimeError::new_err((e.to_string(),))
+            }
+            ProviderError::ExtrapolationCycle(_) => PyRuntimeError::new_err((e.to_string(),)),
+        }
+    }
+}
+
+/// Whether network access is allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NetworkPolicy {
+    /// Never access the network
+    Offline,
+    /// Only use responses that were retrieved before
+    CacheOnly,
+    /// Access the network as needed
+    #[default]
+    Online,
+}
+
+impl NetworkPolicy {
+    /// The policy corresponding to a plain net_access flag.
+    pub fn from_net_access(net_access: bool) -> Self {
+        if net_access {
+            NetworkPolicy::Online
+        } else {
+            NetworkPolicy::Offline
+        }
+    }
+
+    /// Whether new requests may be sent.
+    pub fn allows_network(&self) -> bool {
+        *self == NetworkPolicy::Online
+    }
+}
+
+impl std::fmt::Display for NetworkPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            NetworkPolicy::Offline => write!(f, "offline"),
+            NetworkPolicy::CacheOnly => write!(f, "cache-only"),
+            NetworkPolicy::Online => write!(f, "online"),
+        }
+    }
+}
+
+impl FromStr for NetworkPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "offline" => Ok(NetworkPolicy::Offline),
+            "cache-only" => Ok(NetworkPolicy::CacheOnly),
+            "online" => Ok(NetworkPolicy::Online),
+            _ => Err(format!("unknown network policy: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GuesserSettings {
     pub trust_package: bool,
+
+    /// Maximum number of source files to scan for copyright headers; defaults to
+    /// [`GuesserSettings::max_files`], or
+    /// [`providers::source_headers::DEFAULT_MAX_FILES`] if that is not set either
+    pub max_copyright_files: Option<usize>,
+
+    /// Whether guessers may access the network
+    pub network: NetworkPolicy,
+
+    /// Timeout for individual HTTP requests; defaults to a few seconds
+    pub http_timeout: Option<std::time::Duration>,
+
+    /// Stop guessing after this much time; guessers that are running are not interrupted
+    pub deadline: Option<std::time::Duration>,
+
+    /// Maximum number of files to scan, for guessers that scan several files
+    pub max_files: Option<usize>,
+
+    /// Files larger than this many bytes are not read
+    pub max_file_size: Option<u64>,
 }
 
+#[cfg(feature = "providers")]
 pub struct UpstreamMetadataGuesser {
     pub name: std::path::PathBuf,
     pub guess:
         Box<dyn FnOnce(&GuesserSettings) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError>>,
 }
 
+#[cfg(feature = "providers")]
 impl std::fmt::Debug for UpstreamMetadataGuesser {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UpstreamMetadataGuesser")
@@ -2511,6 +4551,7 @@ impl std::fmt::Debug for UpstreamMetadataGuesser {
     }
 }
 
+#[cfg(feature = "providers")]
 type OldAsyncGuesser = fn(
     PathBuf,
     GuesserSettings,
@@ -2521,156 +4562,436 @@ type OldAsyncGuesser = fn(
     >,
 >;
 
-const OLD_STATIC_GUESSERS: &[(&str, OldAsyncGuesser)] = &[
+#[cfg(feature = "providers")]
+const OLD_STATIC_GUESSERS: &[(&str, &descriptors::LocalProvider, OldAsyncGuesser)] = &[
     #[cfg(feature = "debian")]
-    ("debian/watch", |path, settings| {
-        Box::pin(async move {
-            crate::providers::debian::guess_from_debian_watch(&path, &settings).await
-        })
-    }),
+    (
+        "debian/watch",
+        &descriptors::local::DEBIAN,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::debian::guess_from_debian_watch(&path, &settings).await
+            })
+        },
+    ),
     #[cfg(feature = "debian")]
-    ("debian/control", |path, settings| {
-        Box::pin(
-            async move { crate::providers::debian::guess_from_debian_control(&path, &settings) },
-        )
-    }),
+    (
+        "debian/control",
+        &descriptors::local::DEBIAN,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::debian::guess_from_debian_control(&path, &settings)
+            })
+        },
+    ),
     #[cfg(feature = "debian")]
-    ("debian/changelog", |path, settings| {
-        Box::pin(async move {
-            crate::providers::debian::guess_from_debian_changelog(&path, &settings).await
-        })
-    }),
+    (
+        "debian/changelog",
+        &descriptors::local::DEBIAN,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::debian::guess_from_debian_changelog(&path, &settings).await
+            })
+        },
+    ),
     #[cfg(feature = "debian")]
-    ("debian/rules", |path, settings| {
-        Box::pin(async move { crate::providers::debian::guess_from_debian_rules(&path, &settings) })
-    }),
+    (
+        "debian/rules",
+        &descriptors::local::DEBIAN,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::debian::guess_from_debian_rules(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "debian/upstream/metadata",
+        &descriptors::local::DEBIAN_UPSTREAM_METADATA,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::debian::guess_from_debian_upstream_metadata(&path, &settings)
+            })
+        },
+    ),
     #[cfg(feature = "python-pkginfo")]
-    ("PKG-INFO", |path, settings| {
+    ("PKG-INFO", &descriptors::local::PYTHON, |path, settings| {
         Box::pin(
             async move { crate::providers::python::guess_from_pkg_info(&path, &settings).await },
         )
     }),
-    ("package.json", |path, settings| {
-        Box::pin(async move {
-            crate::providers::package_json::guess_from_package_json(&path, &settings)
-        })
-    }),
-    ("composer.json", |path, settings| {
-        Box::pin(async move {
-            crate::providers::composer_json::guess_from_composer_json(&path, &settings)
-        })
-    }),
-    ("package.xml", |path, settings| {
-        Box::pin(
-            async move { crate::providers::package_xml::guess_from_package_xml(&path, &settings) },
-        )
-    }),
-    ("package.yaml", |path, settings| {
-        Box::pin(async move {
-            crate::providers::package_yaml::guess_from_package_yaml(&path, &settings)
-        })
-    }),
+    (
+        "package.json",
+        &descriptors::local::PACKAGE_JSON,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::package_json::guess_from_package_json(&path, &settings)
+            })
+        },
+    ),
+    (
+        "haxelib.json",
+        &descriptors::local::HAXELIB_JSON,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::haxe::guess_from_haxelib_json(&path, &settings) },
+            )
+        },
+    ),
+    (
+        ".github/FUNDING.yml",
+        &descriptors::local::FUNDING,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::funding::guess_from_funding_yml(&path, &settings) },
+            )
+        },
+    ),
+    (
+        ".tx/config",
+        &descriptors::local::TRANSIFEX,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::transifex::guess_from_tx_config(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "recipe/meta.yaml",
+        &descriptors::local::CONDA,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::conda::guess_from_conda_meta_yaml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "conda/meta.yaml",
+        &descriptors::local::CONDA,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::conda::guess_from_conda_meta_yaml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "conda.recipe/meta.yaml",
+        &descriptors::local::CONDA,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::conda::guess_from_conda_meta_yaml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "composer.json",
+        &descriptors::local::COMPOSER_JSON,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::composer_json::guess_from_composer_json(&path, &settings)
+            })
+        },
+    ),
+    (
+        "package.xml",
+        &descriptors::local::PACKAGE_XML,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::package_xml::guess_from_package_xml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "package2.xml",
+        &descriptors::local::PACKAGE_XML,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::package_xml::guess_from_package_xml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "channel.xml",
+        &descriptors::local::CHANNEL_XML,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::package_xml::guess_from_channel_xml(&path, &settings)
+            })
+        },
+    ),
+    (
+        "package.yaml",
+        &descriptors::local::PACKAGE_YAML,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::package_yaml::guess_from_package_yaml(&path, &settings)
+            })
+        },
+    ),
     #[cfg(feature = "dist-ini")]
-    ("dist.ini", |path, settings| {
+    ("dist.ini", &descriptors::local::PERL, |path, settings| {
         Box::pin(async move { crate::providers::perl::guess_from_dist_ini(&path, &settings) })
     }),
     #[cfg(feature = "debian")]
-    ("debian/copyright", |path, settings| {
-        Box::pin(async move {
-            crate::providers::debian::guess_from_debian_copyright(&path, &settings).await
-        })
-    }),
-    ("META.json", |path, settings| {
-        Box::pin(async move { crate::providers::perl::guess_from_meta_json(&path, &settings) })
-    }),
-    ("MYMETA.json", |path, settings| {
+    (
+        "debian/copyright",
+        &descriptors::local::DEBIAN,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::debian::guess_from_debian_copyright(&path, &settings).await
+            })
+        },
+    ),
+    ("META.json", &descriptors::local::PERL, |path, settings| {
         Box::pin(async move { crate::providers::perl::guess_from_meta_json(&path, &settings) })
     }),
-    ("META.yml", |path, settings| {
+    (
+        "MYMETA.json",
+        &descriptors::local::PERL,
+        |path, settings| {
+            Box::pin(async move { crate::providers::perl::guess_from_meta_json(&path, &settings) })
+        },
+    ),
+    ("META.yml", &descriptors::local::PERL, |path, settings| {
         Box::pin(async move { crate::providers::perl::guess_from_meta_yml(&path, &settings) })
     }),
-    ("MYMETA.yml", |path, settings| {
+    ("MYMETA.yml", &descriptors::local::PERL, |path, settings| {
         Box::pin(async move { crate::providers::perl::guess_from_meta_yml(&path, &settings) })
     }),
-    ("configure", |path, settings| {
-        Box::pin(async move { crate::providers::autoconf::guess_from_configure(&path, &settings) })
-    }),
+    (
+        "configure",
+        &descriptors::local::AUTOCONF,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::autoconf::guess_from_configure(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "configure.ac",
+        &descriptors::local::AUTOCONF,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::autoconf::guess_from_configure_ac(&path, &settings)
+            })
+        },
+    ),
+    (
+        "configure.in",
+        &descriptors::local::AUTOCONF,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::autoconf::guess_from_configure_ac(&path, &settings)
+            })
+        },
+    ),
     #[cfg(feature = "r-description")]
-    ("DESCRIPTION", |path, settings| {
-        Box::pin(
-            async move { crate::providers::r::guess_from_r_description(&path, &settings).await },
-        )
-    }),
+    (
+        "DESCRIPTION",
+        &descriptors::local::R_DESCRIPTION,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::r::guess_from_r_description(&path, &settings).await
+            })
+        },
+    ),
     #[cfg(feature = "cargo")]
-    ("Cargo.toml", |path, settings| {
-        Box::pin(async move { crate::providers::rust::guess_from_cargo(&path, &settings) })
-    }),
-    ("pom.xml", |path, settings| {
+    (
+        "Cargo.toml",
+        &descriptors::local::CARGO,
+        |path, settings| {
+            Box::pin(async move { crate::providers::rust::guess_from_cargo(&path, &settings) })
+        },
+    ),
+    #[cfg(feature = "julia")]
+    (
+        "Project.toml",
+        &descriptors::local::JULIA,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::julia::guess_from_project_toml(&path, &settings) },
+            )
+        },
+    ),
+    #[cfg(feature = "julia")]
+    (
+        "JuliaProject.toml",
+        &descriptors::local::JULIA,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::julia::guess_from_project_toml(&path, &settings) },
+            )
+        },
+    ),
+    ("pom.xml", &descriptors::local::MAVEN, |path, settings| {
         Box::pin(async move { crate::providers::maven::guess_from_pom_xml(&path, &settings) })
     }),
-    #[cfg(feature = "git-config")]
-    (".git/config", |path, settings| {
-        Box::pin(async move { crate::providers::git::guess_from_git_config(&path, &settings) })
+    (
+        "build.gradle",
+        &descriptors::local::GRADLE,
+        |path, settings| {
+            Box::pin(async move { crate::providers::gradle::guess_from_gradle(&path, &settings) })
+        },
+    ),
+    (
+        "build.gradle.kts",
+        &descriptors::local::GRADLE,
+        |path, settings| {
+            Box::pin(async move { crate::providers::gradle::guess_from_gradle(&path, &settings) })
+        },
+    ),
+    ("build.sbt", &descriptors::local::SBT, |path, settings| {
+        Box::pin(async move { crate::providers::sbt::guess_from_sbt(&path, &settings) })
     }),
-    ("debian/get-orig-source.sh", |path, settings| {
-        Box::pin(async move { crate::vcs_command::guess_from_get_orig_source(&path, &settings) })
+    ("mix.exs", &descriptors::local::MIX_EXS, |path, settings| {
+        Box::pin(async move { crate::providers::elixir::guess_from_mix_exs(&path, &settings) })
     }),
-    #[cfg(feature = "pyproject-toml")]
-    ("pyproject.toml", |path, settings| {
+    #[cfg(feature = "git-config")]
+    (".git/config", &descriptors::local::GIT, |path, settings| {
         Box::pin(
-            async move { crate::providers::python::guess_from_pyproject_toml(&path, &settings) },
+            async move { crate::providers::git::guess_from_git_config(&path, &settings).await },
         )
     }),
-    #[cfg(feature = "setup-cfg")]
-    ("setup.cfg", |path, settings| {
-        Box::pin(
-            async move { crate::providers::python::guess_from_setup_cfg(&path, &settings).await },
-        )
+    (
+        "debian/get-orig-source.sh",
+        &descriptors::local::SHELL,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::shell::guess_from_shell_script(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "debian/orig-tar.sh",
+        &descriptors::local::SHELL,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::shell::guess_from_shell_script(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "release.sh",
+        &descriptors::local::SHELL,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::shell::guess_from_shell_script(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "scripts/release.sh",
+        &descriptors::local::SHELL,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::shell::guess_from_shell_script(&path, &settings) },
+            )
+        },
+    ),
+    ("Makefile", &descriptors::local::SHELL, |path, settings| {
+        Box::pin(async move { crate::providers::shell::guess_from_makefile(&path, &settings) })
     }),
-    ("go.mod", |path, settings| {
+    #[cfg(feature = "pyproject-toml")]
+    (
+        "pyproject.toml",
+        &descriptors::local::PYTHON,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::python::guess_from_pyproject_toml(&path, &settings)
+            })
+        },
+    ),
+    #[cfg(feature = "setup-cfg")]
+    (
+        "setup.cfg",
+        &descriptors::local::PYTHON,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::python::guess_from_setup_cfg(&path, &settings).await
+            })
+        },
+    ),
+    ("go.mod", &descriptors::local::GO_MOD, |path, settings| {
         Box::pin(async move { crate::providers::go::guess_from_go_mod(&path, &settings) })
     }),
-    ("Makefile.PL", |path, settings| {
-        Box::pin(async move { crate::providers::perl::guess_from_makefile_pl(&path, &settings) })
-    }),
-    ("wscript", |path, settings| {
+    (
+        "Makefile.PL",
+        &descriptors::local::PERL,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::perl::guess_from_makefile_pl(&path, &settings) },
+            )
+        },
+    ),
+    ("wscript", &descriptors::local::WAF, |path, settings| {
         Box::pin(async move { crate::providers::waf::guess_from_wscript(&path, &settings) })
     }),
-    ("AUTHORS", |path, settings| {
+    ("AUTHORS", &descriptors::local::AUTHORS, |path, settings| {
         Box::pin(async move { crate::providers::authors::guess_from_authors(&path, &settings) })
     }),
-    ("INSTALL", |path, settings| {
+    ("INSTALL", &descriptors::local::INSTALL, |path, settings| {
         Box::pin(async move { crate::providers::guess_from_install(&path, &settings).await })
     }),
-    ("pubspec.yaml", |path, settings| {
-        Box::pin(
-            async move { crate::providers::pubspec::guess_from_pubspec_yaml(&path, &settings) },
-        )
-    }),
-    ("pubspec.yml", |path, settings| {
-        Box::pin(
-            async move { crate::providers::pubspec::guess_from_pubspec_yaml(&path, &settings) },
-        )
-    }),
-    ("meson.build", |path, settings| {
-        Box::pin(async move { crate::providers::meson::guess_from_meson(&path, &settings) })
-    }),
-    ("metadata.json", |path, settings| {
-        Box::pin(async move {
-            crate::providers::metadata_json::guess_from_metadata_json(&path, &settings)
-        })
+    (
+        "pubspec.yaml",
+        &descriptors::local::PUBSPEC,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::pubspec::guess_from_pubspec_yaml(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "pubspec.yml",
+        &descriptors::local::PUBSPEC,
+        |path, settings| {
+            Box::pin(
+                async move { crate::providers::pubspec::guess_from_pubspec_yaml(&path, &settings) },
+            )
+        },
+    ),
+    (
+        "PKGBUILD",
+        &descriptors::local::PKGBUILD,
+        |path, settings| {
+            Box::pin(async move { crate::providers::arch::guess_from_pkgbuild(&path, &settings) })
+        },
+    ),
+    (
+        "meson.build",
+        &descriptors::local::MESON,
+        |path, settings| {
+            Box::pin(async move { crate::providers::meson::guess_from_meson(&path, &settings) })
+        },
+    ),
+    (
+        "metadata.json",
+        &descriptors::local::METADATA_JSON,
+        |path, settings| {
+            Box::pin(async move {
+                crate::providers::metadata_json::guess_from_metadata_json(&path, &settings)
+            })
+        },
+    ),
+    ("flake.nix", &descriptors::local::NIX, |path, settings| {
+        Box::pin(async move { crate::providers::nix::guess_from_flake_nix(&path, &settings) })
     }),
-    (".travis.yml", |path, settings| {
-        Box::pin(async move { crate::guess_from_travis_yml(&path, &settings) })
+    ("default.nix", &descriptors::local::NIX, |path, settings| {
+        Box::pin(async move { crate::providers::nix::guess_from_default_nix(&path, &settings) })
     }),
+    (
+        ".travis.yml",
+        &descriptors::local::TRAVIS,
+        |path, settings| Box::pin(async move { crate::guess_from_travis_yml(&path, &settings) }),
+    ),
 ];
 
+#[cfg(feature = "providers")]
 fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
     let mut candidates: Vec<Box<dyn Guesser>> = Vec::new();
 
     let path = path.canonicalize().unwrap();
 
-    for (name, cb) in OLD_STATIC_GUESSERS {
+    for (name, _, cb) in OLD_STATIC_GUESSERS {
         let subpath = path.join(name);
         if subpath.exists() {
             candidates.push(Box::new(PathGuesser {
@@ -2681,6 +5002,36 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         }
     }
 
+    if let Some(&name) = crate::providers::community::CODE_OF_CONDUCT_FILENAMES
+        .iter()
+        .find(|name| path.join(name).exists())
+    {
+        candidates.push(Box::new(PathGuesser {
+            name: name.to_string(),
+            subpath: path.join(name),
+            cb: Box::new(move |p, s| {
+                Box::pin(async move {
+                    crate::providers::community::guess_from_code_of_conduct(name, &p, &s)
+                })
+            }),
+        }));
+    }
+
+    if let Some(&name) = crate::providers::community::CONTRIBUTING_FILENAMES
+        .iter()
+        .find(|name| path.join(name).exists())
+    {
+        candidates.push(Box::new(PathGuesser {
+            name: name.to_string(),
+            subpath: path.join(name),
+            cb: Box::new(move |p, s| {
+                Box::pin(async move {
+                    crate::providers::community::guess_from_contributing(name, &p, &s)
+                })
+            }),
+        }));
+    }
+
     for name in ["SECURITY.md", ".github/SECURITY.md", "docs/SECURITY.md"].iter() {
         if path.join(name).exists() {
             let subpath = path.join(name);
@@ -2697,6 +5048,8 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         }
     }
 
+    #[cfg(feature = "pyo3")]
+    #[cfg_attr(not(feature = "python-pkginfo"), allow(unused_mut))]
     let mut found_pkg_info = path.join("PKG-INFO").exists();
     #[cfg(feature = "python-pkginfo")]
     for entry in std::fs::read_dir(&path).unwrap() {
@@ -2712,7 +5065,10 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
                     )
                 }),
             }));
-            found_pkg_info = true;
+            #[cfg(feature = "pyo3")]
+            {
+                found_pkg_info = true;
+            }
         } else if filename.ends_with(".dist-info") {
             candidates.push(Box::new(PathGuesser {
                 name: format!("{}/METADATA", filename),
@@ -2723,7 +5079,10 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
                     )
                 }),
             }));
-            found_pkg_info = true;
+            #[cfg(feature = "pyo3")]
+            {
+                found_pkg_info = true;
+            }
         }
     }
 
@@ -2815,11 +5174,9 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         .unwrap()
         .filter_map(|entry| {
             let entry = entry.unwrap();
-            if entry
-                .file_name()
-                .to_string_lossy()
-                .ends_with(".metainfo.xml")
-            {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            // .appdata.xml is the legacy name, still common in Flathub packaging
+            if filename.ends_with(".metainfo.xml") || filename.ends_with(".appdata.xml") {
                 Some(entry.file_name())
             } else {
                 None
@@ -2845,6 +5202,36 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         );
     }
 
+    let mut flatpak_filenames = std::fs::read_dir(&path)
+        .unwrap()
+        .filter_map(|entry| {
+            let entry = entry.unwrap();
+            if crate::providers::flatpak::is_flatpak_manifest(&entry.path()) {
+                Some(entry.file_name())
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if flatpak_filenames.len() == 1 {
+        let flatpak_filename = flatpak_filenames.remove(0);
+        candidates.push(Box::new(PathGuesser {
+            name: flatpak_filename.to_string_lossy().to_string(),
+            subpath: path.join(&flatpak_filename),
+            cb: Box::new(|p, s| {
+                Box::pin(
+                    async move { crate::providers::flatpak::guess_from_flatpak_manifest(&p, &s) },
+                )
+            }),
+        }));
+    } else if flatpak_filenames.len() > 1 {
+        log::warn!(
+            "Multiple Flatpak manifests found: {:?}, ignoring all.",
+            flatpak_filenames
+        );
+    }
+
     let mut cabal_filenames = std::fs::read_dir(&path)
         .unwrap()
         .filter_map(|entry| {
@@ -2918,6 +5305,33 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         }));
     }
 
+    let mut sbt_filenames = std::fs::read_dir(path.join("project"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let entry = entry.unwrap();
+                    if entry.file_name().to_string_lossy().ends_with(".sbt") {
+                        Some(entry.file_name())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    sbt_filenames.sort();
+
+    for sbt_filename in sbt_filenames {
+        let name = format!("project/{}", sbt_filename.to_string_lossy());
+        candidates.push(Box::new(PathGuesser {
+            subpath: path.join(&name),
+            name,
+            cb: Box::new(|path, s| {
+                Box::pin(async move { crate::providers::sbt::guess_from_sbt(&path, &s) })
+            }),
+        }));
+    }
+
     let mut nuspec_filenames = std::fs::read_dir(&path)
         .unwrap()
         .filter_map(|entry| {
@@ -3013,6 +5427,26 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
         }));
     }
 
+    candidates.push(Box::new(PathGuesser {
+        name: "manifest sniffing".to_string(),
+        subpath: path.clone(),
+        cb: Box::new(|p, s| {
+            Box::pin(
+                async move { crate::providers::manifest::guess_from_unknown_manifests(&p, &s) },
+            )
+        }),
+    }));
+
+    candidates.push(Box::new(PathGuesser {
+        name: "source headers".to_string(),
+        subpath: path.clone(),
+        cb: Box::new(|p, s| {
+            Box::pin(
+                async move { crate::providers::source_headers::guess_from_source_headers(&p, &s) },
+            )
+        }),
+    }));
+
     candidates.push(Box::new(EnvironmentGuesser::new()));
     candidates.push(Box::new(PathGuesser {
         name: ".".to_string(),
@@ -3023,80 +5457,322 @@ fn find_guessers(path: &std::path::Path) -> Vec<Box<dyn Guesser>> {
     candidates
 }
 
+/// Drop guessers for files whose provider can't supply any of `fields`.
+///
+/// Guessers that are not described in [`descriptors::providers`] are always kept.
+#[cfg(feature = "providers")]
+pub(crate) fn filter_guessers(
+    guessers: Vec<Box<dyn Guesser>>,
+    fields: &FieldSet,
+) -> Vec<Box<dyn Guesser>> {
+    if fields.is_all() {
+        return guessers;
+    }
+    guessers
+        .into_iter()
+        .filter(|g| {
+            let basename = g.name().rsplit('/').next().unwrap_or_default();
+            match descriptors::local_provider_fields(g.name())
+                .or_else(|| descriptors::local_provider_fields(basename))
+            {
+                Some(provider_fields) => fields.intersects(provider_fields),
+                None => true,
+            }
+        })
+        .collect()
+}
+
+#[cfg(feature = "providers")]
 pub(crate) fn stream(
     path: &Path,
     config: &GuesserSettings,
-    mut guessers: Vec<Box<dyn Guesser>>,
+    guessers: Vec<Box<dyn Guesser>>,
 ) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> {
+    stream_with_provider(path, config, guessers).map(|(_, res)| res)
+}
+
+/// Like [`stream`], but also return the name of the guesser that produced each result.
+#[cfg(feature = "providers")]
+pub(crate) fn stream_with_provider(
+    path: &Path,
+    config: &GuesserSettings,
+    mut guessers: Vec<Box<dyn Guesser>>,
+) -> impl Stream<Item = (String, Result<UpstreamDatumWithMetadata, ProviderError>)> {
     // For each of the guessers, stream from the guessers in parallel (using Guesser::stream
     // rather than Guesser::guess) and then return the results.
     let abspath = std::env::current_dir().unwrap().join(path);
+    let deadline = config.deadline.map(|d| std::time::Instant::now() + d);
+    let before_deadline = move || deadline.is_none_or(|d| std::time::Instant::now() < d);
 
     // Create streams for each of the guessers. Call stream on each one of them, manipulate
     let streams = guessers.iter_mut().map(move |guesser| {
         let abspath = abspath.clone();
         let config = config.clone();
-        let stream = guesser.stream(&config);
         let guesser_name = guesser.name().to_string();
+        let stream = if before_deadline() {
+            guesser.stream(&config)
+        } else {
+            log::debug!("Deadline passed, not running {}", guesser_name);
+            futures::stream::empty().boxed()
+        };
         stream.map(move |res| {
-            res.map({
+            let res = res.map({
                 let abspath = abspath.clone();
                 let guesser_name = guesser_name.clone();
                 move |mut v| {
                     rewrite_upstream_datum(&guesser_name, &mut v, &abspath);
                     v
                 }
-            })
+            });
+            (guesser_name.clone(), res)
         })
     });
 
-    // Combine the streams into a single stream.
-    futures::stream::select_all(streams)
+    // Combine the streams into a single stream.
+    futures::stream::select_all(streams)
+        .take_while(move |_| futures::future::ready(before_deadline()))
+}
+
+#[cfg(feature = "providers")]
+fn rewrite_upstream_datum(
+    guesser_name: &str,
+    datum: &mut UpstreamDatumWithMetadata,
+    abspath: &std::path::Path,
+) {
+    log::trace!("{}: {:?}", guesser_name, datum);
+    datum.origin = match datum.origin.take() {
+        Some(Origin::Multiple(origins)) => Some(Origin::Multiple(
+            origins
+                .into_iter()
+                .map(|o| relative_origin(o, abspath))
+                .collect(),
+        )),
+        Some(origin) => Some(relative_origin(origin, abspath)),
+        None => Some(Origin::Other(guesser_name.to_string())),
+    };
+}
+
+/// Make a path origin relative to the root of the tree, if it is inside it.
+#[cfg(feature = "providers")]
+fn relative_origin(origin: Origin, abspath: &std::path::Path) -> Origin {
+    if let Origin::Path(p) = &origin {
+        if let Ok(suffix) = p.strip_prefix(abspath) {
+            if suffix.to_str().unwrap().is_empty() {
+                return Origin::Path(PathBuf::from_str(".").unwrap());
+            } else {
+                return Origin::Path(PathBuf::from_str(".").unwrap().join(suffix));
+            }
+        }
+    }
+    origin
+}
+
+#[cfg(feature = "providers")]
+pub fn upstream_metadata_stream(
+    path: &std::path::Path,
+    trust_package: Option<bool>,
+) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> {
+    let trust_package = trust_package.unwrap_or(false);
+
+    let guessers = find_guessers(path);
+
+    stream(
+        path,
+        &GuesserSettings {
+            trust_package,
+            ..Default::default()
+        },
+        guessers,
+    )
+}
+
+#[cfg(feature = "providers")]
+#[deprecated(since = "0.2.2", note = "use MetadataGuesser::extend instead")]
+pub async fn extend_upstream_metadata(
+    upstream_metadata: &mut UpstreamMetadata,
+    path: &std::path::Path,
+    minimum_certainty: Option<Certainty>,
+    net_access: Option<bool>,
+    consult_external_directory: Option<bool>,
+) -> Result<(), ProviderError> {
+    extend_upstream_metadata_with_fields(
+        upstream_metadata,
+        path,
+        minimum_certainty,
+        net_access,
+        consult_external_directory,
+        &FieldSet::all(),
+    )
+    .await
+}
+
+/// A step in extending upstream metadata from other sources.
+#[cfg(feature = "providers")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExtendStep {
+    /// Derive Archive and SourceForge-Project from SourceForge URLs
+    SourceForgeProject,
+    /// Look the project up in package registries; needs net access
+    Registries,
+    /// Consult external (user-maintained) directories; needs net access and
+    /// [`ExtendPipeline::consult_external_directory`]
+    ExternalDirectories,
+    /// Prefer the canonical repository over mirrors; needs net access
+    CanonicalRepository,
+    /// Ask the forge that hosts the repository about the project; needs net access
+    Forge,
+    /// Extrapolate fields from other fields
+    Extrapolation,
+    /// Probe the forge's Pages site for documentation; needs net access
+    PagesDocumentation,
+}
+
+#[cfg(feature = "providers")]
+impl ExtendStep {
+    /// All steps, in the order in which they are run by default.
+    pub const ALL: &'static [ExtendStep] = &[
+        ExtendStep::SourceForgeProject,
+        ExtendStep::Registries,
+        ExtendStep::ExternalDirectories,
+        ExtendStep::CanonicalRepository,
+        ExtendStep::Forge,
+        ExtendStep::Extrapolation,
+        ExtendStep::PagesDocumentation,
+    ];
+}
+
+/// Configuration for [`extend_upstream_metadata_with_pipeline`].
+#[cfg(feature = "providers")]
+#[derive(Debug, Clone)]
+pub struct ExtendPipeline {
+    /// Steps to run, in order
+    pub steps: Vec<ExtendStep>,
+
+    /// Minimum certainty of data to add
+    pub minimum_certainty: Certainty,
+
+    /// Whether to allow net access
+    pub net_access: bool,
+
+    /// Whether to pull in data from external (user-maintained) directories
+    pub consult_external_directory: bool,
+
+    /// Only consult sources that can supply these fields
+    pub fields: FieldSet,
 }
 
-fn rewrite_upstream_datum(
-    guesser_name: &str,
-    datum: &mut UpstreamDatumWithMetadata,
-    abspath: &std::path::Path,
-) {
-    log::trace!("{}: {:?}", guesser_name, datum);
-    datum.origin = datum
-        .origin
-        .clone()
-        .or(Some(Origin::Other(guesser_name.to_string())));
-    if let Some(Origin::Path(p)) = datum.origin.as_ref() {
-        if let Ok(suffix) = p.strip_prefix(abspath) {
-            if suffix.to_str().unwrap().is_empty() {
-                datum.origin = Some(Origin::Path(PathBuf::from_str(".").unwrap()));
-            } else {
-                datum.origin = Some(Origin::Path(PathBuf::from_str(".").unwrap().join(suffix)));
-            }
+#[cfg(feature = "providers")]
+impl Default for ExtendPipeline {
+    fn default() -> Self {
+        Self {
+            steps: ExtendStep::ALL.to_vec(),
+            minimum_certainty: Certainty::Confident,
+            net_access: false,
+            consult_external_directory: false,
+            fields: FieldSet::all(),
         }
     }
 }
 
-pub fn upstream_metadata_stream(
-    path: &std::path::Path,
-    trust_package: Option<bool>,
-) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> {
-    let trust_package = trust_package.unwrap_or(false);
-
-    let guessers = find_guessers(path);
-
-    stream(path, &GuesserSettings { trust_package }, guessers)
-}
-
-pub async fn extend_upstream_metadata(
+/// Extend upstream metadata, only consulting external sources that can supply `fields`.
+#[cfg(feature = "providers")]
+pub async fn extend_upstream_metadata_with_fields(
     upstream_metadata: &mut UpstreamMetadata,
     path: &std::path::Path,
     minimum_certainty: Option<Certainty>,
     net_access: Option<bool>,
     consult_external_directory: Option<bool>,
+    fields: &FieldSet,
+) -> Result<(), ProviderError> {
+    extend_upstream_metadata_with_pipeline(
+        upstream_metadata,
+        path,
+        &ExtendPipeline {
+            steps: ExtendStep::ALL.to_vec(),
+            minimum_certainty: minimum_certainty.unwrap_or(Certainty::Confident),
+            net_access: net_access.unwrap_or(false),
+            consult_external_directory: consult_external_directory.unwrap_or(false),
+            fields: *fields,
+        },
+    )
+    .await
+}
+
+/// Extend upstream metadata by running the steps of a pipeline.
+///
+/// Steps that need net access are skipped if it is not allowed.
+#[cfg(feature = "providers")]
+pub async fn extend_upstream_metadata_with_pipeline(
+    upstream_metadata: &mut UpstreamMetadata,
+    path: &std::path::Path,
+    pipeline: &ExtendPipeline,
 ) -> Result<(), ProviderError> {
-    let net_access = net_access.unwrap_or(false);
-    let consult_external_directory = consult_external_directory.unwrap_or(false);
-    let minimum_certainty = minimum_certainty.unwrap_or(Certainty::Confident);
+    let net_access = pipeline.net_access;
+    let fields = &pipeline.fields;
+    for step in &pipeline.steps {
+        match step {
+            ExtendStep::SourceForgeProject => derive_sourceforge_project(upstream_metadata),
+            ExtendStep::Registries if net_access => {
+                extend_from_registries(upstream_metadata, path, pipeline.minimum_certainty, fields)
+                    .await?
+            }
+            ExtendStep::ExternalDirectories
+                if net_access && pipeline.consult_external_directory =>
+            {
+                extend_from_external_directories(
+                    upstream_metadata,
+                    path,
+                    pipeline.minimum_certainty,
+                    fields,
+                )
+                .await?
+            }
+            ExtendStep::CanonicalRepository
+                if net_access && fields.intersects(&["Repository", "Repository-Mirror"]) =>
+            {
+                prefer_canonical_repository(upstream_metadata).await
+            }
+            ExtendStep::Forge if net_access => extend_from_forge(upstream_metadata).await,
+            ExtendStep::Extrapolation => {
+                crate::extrapolate::extrapolate_fields(upstream_metadata, net_access, None).await?
+            }
+            ExtendStep::PagesDocumentation if net_access && fields.contains("Documentation") => {
+                probe_pages_documentation(upstream_metadata, path).await
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Fill in fields from the forge that hosts the repository.
+///
+/// This will make network connections.
+#[cfg(feature = "providers")]
+pub async fn extend_from_forge(upstream_metadata: &mut UpstreamMetadata) {
+    let Some(repository) = upstream_metadata.get("Repository").cloned() else {
+        return;
+    };
+    let Some(url) = repository.datum.to_url() else {
+        return;
+    };
+    let Some(forge) = find_forge(&url, Some(true)).await else {
+        return;
+    };
+    let Some(project) = forge.project_from_repo_url(&url) else {
+        return;
+    };
+    forge
+        .extend_metadata(
+            upstream_metadata.mut_items(),
+            &project,
+            repository.certainty,
+        )
+        .await;
+}
 
+/// Derive Archive and SourceForge-Project from SourceForge URLs.
+pub fn derive_sourceforge_project(upstream_metadata: &mut UpstreamMetadata) {
     // TODO(jelmer): Use EXTRAPOLATE_FNS mechanism for this?
     for field in [
         "Homepage",
@@ -3111,13 +5787,22 @@ pub async fn extend_upstream_metadata(
             None => continue,
         };
 
-        if let Some(project) =
+        if let Some((project, subproject)) =
             crate::forges::sourceforge::extract_sf_project_name(value.datum.as_str().unwrap())
         {
             let certainty = Some(
                 std::cmp::min(Some(Certainty::Likely), value.certainty)
                     .unwrap_or(Certainty::Likely),
             );
+            if let Some(subproject) = subproject {
+                if !upstream_metadata.contains_key("SourceForge-Subproject") {
+                    upstream_metadata.insert(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::SourceForgeSubproject(subproject),
+                        certainty,
+                        origin: Some(Origin::Other(format!("derived from {}", field))),
+                    });
+                }
+            }
             upstream_metadata.insert(UpstreamDatumWithMetadata {
                 datum: UpstreamDatum::Archive("SourceForge".to_string()),
                 certainty,
@@ -3131,12 +5816,22 @@ pub async fn extend_upstream_metadata(
             break;
         }
     }
+}
 
+/// Look the project up in the package registries that it is published in.
+///
+/// This will make network connections.
+#[cfg(feature = "providers")]
+pub async fn extend_from_registries(
+    upstream_metadata: &mut UpstreamMetadata,
+    path: &std::path::Path,
+    minimum_certainty: Certainty,
+    fields: &FieldSet,
+) -> Result<(), ProviderError> {
     let archive = upstream_metadata.get("Archive");
     if archive.is_some()
         && archive.unwrap().datum.as_str().unwrap() == "SourceForge"
         && upstream_metadata.contains_key("SourceForge-Project")
-        && net_access
     {
         let sf_project = upstream_metadata
             .get("SourceForge-Project")
@@ -3159,7 +5854,6 @@ pub async fn extend_upstream_metadata(
     if archive.is_some()
         && archive.unwrap().datum.as_str().unwrap() == "Hackage"
         && upstream_metadata.contains_key("Hackage-Package")
-        && net_access
     {
         let hackage_package = upstream_metadata
             .get("Hackage-Package")
@@ -3171,21 +5865,22 @@ pub async fn extend_upstream_metadata(
         let hackage_certainty = archive.unwrap().certainty;
 
         crate::providers::haskell::Hackage::new()
-            .extend_metadata(
+            .extend_metadata_fields(
                 upstream_metadata.mut_items(),
                 hackage_package.as_str(),
                 hackage_certainty,
+                fields,
             )
             .await
             .unwrap();
     }
 
+    #[cfg(feature = "cargo")]
     let archive = upstream_metadata.get("Archive");
     #[cfg(feature = "cargo")]
     if archive.is_some()
         && archive.unwrap().datum.as_str().unwrap() == "crates.io"
         && upstream_metadata.contains_key("Cargo-Crate")
-        && net_access
     {
         let cargo_crate = upstream_metadata
             .get("Cargo-Crate")
@@ -3196,10 +5891,50 @@ pub async fn extend_upstream_metadata(
             .to_string();
         let crates_io_certainty = upstream_metadata.get("Archive").unwrap().certainty;
         crate::providers::rust::CratesIo::new()
-            .extend_metadata(
+            .extend_metadata_fields(
                 upstream_metadata.mut_items(),
                 cargo_crate.as_str(),
                 crates_io_certainty,
+                fields,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "julia")]
+    if let (Some(archive), Some(julia_package)) =
+        (upstream_metadata.get("Archive"), upstream_metadata.name())
+    {
+        if archive.datum.as_str() == Some("Julia General") {
+            let julia_package = julia_package.to_string();
+            let julia_certainty = archive.certainty;
+            crate::providers::julia::JuliaGeneral::new()
+                .extend_metadata_fields(
+                    upstream_metadata.mut_items(),
+                    julia_package.as_str(),
+                    julia_certainty,
+                    fields,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    let archive = upstream_metadata.get("Archive");
+    if archive.is_some()
+        && archive.unwrap().datum.as_str().unwrap() == "Flathub"
+        && upstream_metadata
+            .name()
+            .map(crate::providers::flatpak::is_app_id)
+            .unwrap_or(false)
+    {
+        let app_id = upstream_metadata.name().unwrap().to_string();
+        crate::providers::flatpak::Flathub::new()
+            .extend_metadata_fields(
+                upstream_metadata.mut_items(),
+                app_id.as_str(),
+                Some(Certainty::Likely),
+                fields,
             )
             .await
             .unwrap();
@@ -3207,75 +5942,302 @@ pub async fn extend_upstream_metadata(
 
     let archive = upstream_metadata.get("Archive");
     if archive.is_some()
-        && archive.unwrap().datum.as_str().unwrap() == "Pecl"
-        && upstream_metadata.contains_key("Pecl-Package")
-        && net_access
+        && archive.unwrap().datum.as_str().unwrap() == "NuGet"
+        && upstream_metadata.contains_key("Name")
     {
-        let pecl_package = upstream_metadata
-            .get("Pecl-Package")
-            .unwrap()
-            .datum
-            .as_str()
-            .unwrap()
-            .to_string();
-        let pecl_certainty = upstream_metadata.get("Archive").unwrap().certainty;
+        let nuget_package = upstream_metadata.name().unwrap().to_string();
+        let nuget_certainty = upstream_metadata.get("Archive").unwrap().certainty;
+        crate::providers::nuspec::NuGet::new()
+            .extend_metadata_fields(
+                upstream_metadata.mut_items(),
+                nuget_package.as_str(),
+                nuget_certainty,
+                fields,
+            )
+            .await
+            .unwrap();
+    }
+
+    // A Pecl-Package is enough to look the package up, even without a local package.xml
+    if upstream_metadata.contains_key("Pecl-Package") {
+        let pecl_package = upstream_metadata.name_for(names::Ecosystem::Pecl).unwrap();
+        let pecl_certainty = upstream_metadata.get("Pecl-Package").unwrap().certainty;
         crate::providers::php::Pecl::new()
-            .extend_metadata(
+            .extend_metadata_fields(
                 upstream_metadata.mut_items(),
                 pecl_package.as_str(),
                 pecl_certainty,
+                fields,
             )
             .await
             .unwrap();
     }
 
-    #[cfg(feature = "debian")]
-    if net_access && consult_external_directory {
-        // TODO(jelmer): Don't assume debian/control exists
-        let package = match debian_control::Control::from_file_relaxed(path.join("debian/control"))
-        {
-            Ok((control, _)) => control.source().and_then(|s| s.name()),
-            Err(_) => None,
-        };
-
-        if let Some(package) = package {
-            #[cfg(feature = "launchpad")]
-            extend_from_lp(
+    let archive = upstream_metadata.get("Archive");
+    let npm_package = if archive.is_some()
+        && archive.unwrap().datum.as_str().unwrap() == "npm"
+        && upstream_metadata.contains_key("Name")
+    {
+        upstream_metadata
+            .name()
+            .map(|n| (n.to_string(), archive.unwrap().certainty))
+    } else {
+        crate::providers::node::npm_package_name(&path.join("package.json"))
+            .map(|n| (n, Some(Certainty::Likely)))
+    };
+    if let Some((npm_package, npm_certainty)) = npm_package {
+        crate::providers::node::Npm::new()
+            .extend_metadata_fields(
                 upstream_metadata.mut_items(),
-                minimum_certainty,
-                package.as_str(),
-                None,
-                None,
+                npm_package.as_str(),
+                npm_certainty,
+                fields,
             )
-            .await;
-            crate::providers::arch::Aur::new()
-                .extend_metadata(
+            .await
+            .unwrap();
+    }
+
+    if crate::providers::python::is_python_project(path) {
+        if let Some(name) = upstream_metadata.name_for(names::Ecosystem::PyPI) {
+            crate::providers::python::PyPI::new()
+                .extend_metadata_fields(
+                    upstream_metadata.mut_items(),
+                    name.as_str(),
+                    Some(Certainty::Likely),
+                    fields,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    if crate::providers::ruby::has_gemspec(path) {
+        if let Some(name) = upstream_metadata.name_for(names::Ecosystem::RubyGems) {
+            crate::providers::ruby::RubyGems::new()
+                .extend_metadata_fields(
                     upstream_metadata.mut_items(),
-                    package.as_str(),
-                    Some(minimum_certainty),
+                    name.as_str(),
+                    Some(Certainty::Likely),
+                    fields,
                 )
                 .await
                 .unwrap();
-            crate::providers::gobo::Gobo::new()
-                .extend_metadata(
+        }
+    }
+
+    if path.join("composer.json").exists() {
+        // Packagist package names are of the form vendor/package
+        if let Some(name) = upstream_metadata
+            .name()
+            .filter(|n| n.contains('/'))
+            .map(|n| n.to_string())
+        {
+            crate::providers::composer_json::Packagist::new()
+                .extend_metadata_fields(
                     upstream_metadata.mut_items(),
-                    package.as_str(),
-                    Some(minimum_certainty),
+                    name.as_str(),
+                    Some(Certainty::Likely),
+                    fields,
                 )
                 .await
                 .unwrap();
-            extend_from_repology(
+        }
+    }
+
+    if let Some(import_path) = upstream_metadata.go_import_path().map(|p| p.to_string()) {
+        crate::providers::go::GoProxy::new()
+            .extend_metadata_fields(
                 upstream_metadata.mut_items(),
-                minimum_certainty,
-                package.as_str(),
+                import_path.as_str(),
+                Some(Certainty::Likely),
+                fields,
             )
-            .await;
+            .await
+            .unwrap();
+    }
+
+    if crate::providers::perl::is_perl_dist(path) {
+        if let Some(name) = upstream_metadata.name_for(names::Ecosystem::Cpan) {
+            crate::providers::perl::MetaCpan::new()
+                .extend_metadata_fields(
+                    upstream_metadata.mut_items(),
+                    name.as_str(),
+                    Some(Certainty::Likely),
+                    fields,
+                )
+                .await
+                .unwrap();
+        }
+    }
+
+    let archive = upstream_metadata
+        .get("Archive")
+        .and_then(|a| a.datum.as_str())
+        .map(|a| a.to_string());
+    if let (Some(archive), Some(name)) = (archive, upstream_metadata.name().map(|n| n.to_string()))
+    {
+        match archive.as_str() {
+            "CRAN" => {
+                crate::providers::r::Cran::new()
+                    .extend_metadata_fields(
+                        upstream_metadata.mut_items(),
+                        name.as_str(),
+                        Some(minimum_certainty),
+                        fields,
+                    )
+                    .await
+                    .unwrap();
+            }
+            "Bioconductor" => {
+                crate::providers::r::Bioconductor::new()
+                    .extend_metadata_fields(
+                        upstream_metadata.mut_items(),
+                        name.as_str(),
+                        Some(minimum_certainty),
+                        fields,
+                    )
+                    .await
+                    .unwrap();
+            }
+            _ => {}
         }
     }
-    crate::extrapolate::extrapolate_fields(upstream_metadata, net_access, None).await?;
+
+    let archive = upstream_metadata.get("Archive");
+    if archive.is_some()
+        && archive.unwrap().datum.as_str().unwrap() == "Hex"
+        && upstream_metadata.contains_key("Name")
+    {
+        let hex_package = upstream_metadata
+            .get("Name")
+            .unwrap()
+            .datum
+            .as_str()
+            .unwrap()
+            .to_string();
+        let hex_certainty = upstream_metadata.get("Archive").unwrap().certainty;
+        crate::providers::elixir::Hex::new()
+            .extend_metadata_fields(
+                upstream_metadata.mut_items(),
+                hex_package.as_str(),
+                hex_certainty,
+                fields,
+            )
+            .await
+            .unwrap();
+    }
+    Ok(())
+}
+
+/// Consult external (not maintained by upstream) directories, such as repology.
+///
+/// Distribution directories are consulted using the Debian source package name if there is
+/// one, and the project Name otherwise. Repology is also consulted using the names the
+/// project has in package registries.
+/// This will make network connections.
+#[cfg(feature = "providers")]
+pub async fn extend_from_external_directories(
+    upstream_metadata: &mut UpstreamMetadata,
+    path: &std::path::Path,
+    minimum_certainty: Certainty,
+    fields: &FieldSet,
+) -> Result<(), ProviderError> {
+    let mut repology_identifiers = vec![];
+
+    #[cfg(feature = "debian")]
+    let debian_package =
+        match debian_control::Control::from_file_relaxed(path.join("debian/control")) {
+            Ok((control, _)) => control.source().and_then(|s| s.name()),
+            Err(_) => None,
+        };
+    #[cfg(not(feature = "debian"))]
+    let debian_package: Option<String> = None;
+
+    let package = debian_package
+        .clone()
+        .or_else(|| upstream_metadata.name().map(|n| n.to_string()));
+
+    if let Some(package) = package {
+        #[cfg(feature = "external-directories")]
+        extend_from_distribution_directories(
+            upstream_metadata,
+            package.as_str(),
+            minimum_certainty,
+            fields,
+        )
+        .await;
+        #[cfg(not(feature = "external-directories"))]
+        log::debug!(
+            "Not consulting distribution directories for {}: external-directories feature disabled",
+            package
+        );
+    }
+
+    if let Some(package) = debian_package {
+        repology_identifiers.push(("debian_unstable", package));
+    }
+
+    repology_identifiers.extend(crate::providers::repology::repology_identifiers(
+        upstream_metadata,
+        path,
+    ));
+    extend_from_repology(
+        upstream_metadata.mut_items(),
+        minimum_certainty,
+        fields,
+        &repology_identifiers,
+    )
+    .await;
     Ok(())
 }
 
+/// Consult distribution directories (Launchpad, the AUR and GoboLinux) for a package name.
+///
+/// Unlike [`extend_from_external_directories`], this does not look at the source tree, so it
+/// can be used by any caller that knows the name of the package.
+/// This will make network connections.
+#[cfg(feature = "external-directories")]
+pub async fn extend_from_distribution_directories(
+    upstream_metadata: &mut UpstreamMetadata,
+    package: &str,
+    minimum_certainty: Certainty,
+    fields: &FieldSet,
+) {
+    #[cfg(feature = "launchpad")]
+    extend_from_lp(
+        upstream_metadata.mut_items(),
+        minimum_certainty,
+        fields,
+        package,
+        None,
+        None,
+    )
+    .await;
+    if let Err(e) = crate::providers::arch::Aur::new()
+        .extend_metadata_fields(
+            upstream_metadata.mut_items(),
+            package,
+            Some(minimum_certainty),
+            fields,
+        )
+        .await
+    {
+        log::warn!("Unable to consult the AUR for {}: {}", package, e);
+    }
+    if let Err(e) = crate::providers::gobo::Gobo::new()
+        .extend_metadata_fields(
+            upstream_metadata.mut_items(),
+            package,
+            Some(minimum_certainty),
+            fields,
+        )
+        .await
+    {
+        log::warn!("Unable to consult GoboLinux for {}: {}", package, e);
+    }
+}
+
+#[cfg(feature = "providers")]
 #[async_trait::async_trait]
 pub trait ThirdPartyRepository {
     fn name(&self) -> &'static str;
@@ -3287,19 +6249,69 @@ pub trait ThirdPartyRepository {
         metadata: &mut Vec<UpstreamDatumWithMetadata>,
         name: &str,
         min_certainty: Option<Certainty>,
+    ) -> Result<(), ProviderError> {
+        self.extend_metadata_fields(metadata, name, min_certainty, &FieldSet::all())
+            .await
+    }
+
+    /// Like [`ThirdPartyRepository::extend_metadata`], but only consider the given fields.
+    ///
+    /// The repository is not consulted at all if it can't supply any of the fields.
+    async fn extend_metadata_fields(
+        &self,
+        metadata: &mut Vec<UpstreamDatumWithMetadata>,
+        name: &str,
+        min_certainty: Option<Certainty>,
+        fields: &FieldSet,
     ) -> Result<(), ProviderError> {
         if min_certainty.is_some() && min_certainty.unwrap() > self.max_supported_certainty() {
             // Don't bother if we can't meet minimum certainty
             return Ok(());
         }
 
-        extend_from_external_guesser(
-            metadata,
-            Some(self.max_supported_certainty()),
-            self.supported_fields(),
-            || async { self.guess_metadata(name).await.unwrap() },
-        )
-        .await;
+        let wanted = fields.filter(self.supported_fields());
+        if wanted.is_empty() {
+            return Ok(());
+        }
+
+        let max_certainty = self.max_supported_certainty();
+        if !possible_fields_missing(metadata, &wanted, max_certainty) {
+            return Ok(());
+        }
+
+        // If the project is not known under its primary name, try its aliases
+        let mut candidates = vec![name.to_string()];
+        candidates.extend(crate::names::name_candidates(name, metadata));
+        for candidate in candidates {
+            let found = match self.guess_metadata(&candidate).await {
+                Ok(found) => found,
+                Err(e) => {
+                    log::debug!("Unable to find {} on {}: {}", candidate, self.name(), e);
+                    continue;
+                }
+            };
+            if found.is_empty() {
+                continue;
+            }
+            let origin = if candidate == name {
+                None
+            } else {
+                log::debug!("Found {} on {} as {}", name, self.name(), candidate);
+                Some(Origin::Other(format!("{} (as {})", self.name(), candidate)))
+            };
+            update_from_guesses(
+                metadata,
+                found
+                    .into_iter()
+                    .filter(|d| fields.contains(d.field()))
+                    .map(|datum| UpstreamDatumWithMetadata {
+                        datum,
+                        certainty: Some(max_certainty),
+                        origin: origin.clone(),
+                    }),
+            );
+            break;
+        }
 
         Ok(())
     }
@@ -3307,42 +6319,59 @@ pub trait ThirdPartyRepository {
     async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError>;
 }
 
-#[cfg(feature = "launchpad")]
+#[cfg(all(feature = "launchpad", feature = "external-directories"))]
 async fn extend_from_lp(
     upstream_metadata: &mut Vec<UpstreamDatumWithMetadata>,
     minimum_certainty: Certainty,
+    fields: &FieldSet,
     package: &str,
     distribution: Option<&str>,
     suite: Option<&str>,
 ) {
     // The set of fields that Launchpad can possibly provide:
-    let lp_fields = &["Homepage", "Repository", "Name", "Download"][..];
+    let lp_fields = fields.filter(crate::forges::launchpad::FIELDS);
     let lp_certainty = Certainty::Possible;
 
-    if lp_certainty < minimum_certainty {
+    if lp_certainty < minimum_certainty
+        || lp_fields.is_empty()
+        || !possible_fields_missing(upstream_metadata, &lp_fields, lp_certainty)
+    {
         // Don't bother talking to launchpad if we're not
         // speculating.
         return;
     }
 
-    extend_from_external_guesser(upstream_metadata, Some(lp_certainty), lp_fields, || async {
-        crate::providers::launchpad::guess_from_launchpad(package, distribution, suite)
-            .await
-            .unwrap()
-    })
-    .await
+    let Some(project) =
+        crate::providers::launchpad::find_project(package, distribution, suite).await
+    else {
+        return;
+    };
+
+    // Collect separately, so that only the requested fields are added
+    let mut found = Vec::new();
+    Launchpad::new()
+        .extend_metadata(&mut found, &project, Some(lp_certainty))
+        .await;
+    update_from_guesses(
+        upstream_metadata,
+        found
+            .into_iter()
+            .filter(|d| fields.contains(d.datum.field())),
+    );
 }
 
+#[cfg(feature = "providers")]
 async fn extend_from_repology(
     upstream_metadata: &mut Vec<UpstreamDatumWithMetadata>,
     minimum_certainty: Certainty,
-    source_package: &str,
+    fields: &FieldSet,
+    identifiers: &[(&str, String)],
 ) {
     // The set of fields that repology can possibly provide:
-    let repology_fields = &["Homepage", "License", "Summary", "Download"][..];
+    let repology_fields = fields.filter(&["Homepage", "License", "Summary", "Download"]);
     let certainty = Certainty::Confident;
 
-    if certainty < minimum_certainty {
+    if certainty < minimum_certainty || repology_fields.is_empty() || identifiers.is_empty() {
         // Don't bother talking to repology if we're not speculating.
         return;
     }
@@ -3350,21 +6379,53 @@ async fn extend_from_repology(
     extend_from_external_guesser(
         upstream_metadata,
         Some(certainty),
-        repology_fields,
+        &repology_fields,
         || async {
-            crate::providers::repology::guess_from_repology(source_package)
-                .await
-                .unwrap()
+            // Use the first identifier that repology knows about
+            for (repo, name) in identifiers {
+                match crate::providers::repology::guess_from_repology_repo(Some(repo), name).await {
+                    Ok(data) if !data.is_empty() => {
+                        return data
+                            .into_iter()
+                            .filter(|d| fields.contains(d.field()))
+                            .collect();
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::debug!("Unable to look up {} in {}: {}", name, repo, e),
+                }
+            }
+            vec![]
         },
     )
     .await
 }
 
-/// Fix existing upstream metadata.
-pub async fn fix_upstream_metadata(upstream_metadata: &mut UpstreamMetadata) {
+/// Fix existing upstream metadata.
+pub async fn fix_upstream_metadata(upstream_metadata: &mut UpstreamMetadata) {
+    fix_upstream_metadata_with_policy(
+        upstream_metadata,
+        crate::vcs::RepositoryUrlPolicy::default(),
+        Some(false),
+    )
+    .await
+}
+
+/// Fix existing upstream metadata, rewriting the repository URL according to a policy.
+///
+/// # Arguments
+/// * `upstream_metadata`: Metadata to fix
+/// * `repository_policy`: Preferred format for the Repository URL
+/// * `net_access`: Whether to allow net access while converting the repository URL
+pub async fn fix_upstream_metadata_with_policy(
+    upstream_metadata: &mut UpstreamMetadata,
+    repository_policy: crate::vcs::RepositoryUrlPolicy,
+    net_access: Option<bool>,
+) {
     if let Some(repository) = upstream_metadata.get_mut("Repository") {
         let url = crate::vcs::sanitize_url(repository.datum.as_str().unwrap()).await;
-        repository.datum = UpstreamDatum::Repository(url.to_string());
+        let url =
+            crate::vcs::apply_repository_url_policy(&url, repository_policy, net_access).await;
+        repository.datum = UpstreamDatum::Repository(url);
     }
 
     if let Some(summary) = upstream_metadata.get_mut("Summary") {
@@ -3373,6 +6434,12 @@ pub async fn fix_upstream_metadata(upstream_metadata: &mut UpstreamMetadata) {
         let s = s.trim_end().trim_end_matches('.');
         summary.datum = UpstreamDatum::Summary(s.to_string());
     }
+
+    if let Some(license) = upstream_metadata.get_mut("License") {
+        if let UpstreamDatum::License(license) = &mut license.datum {
+            license.normalize();
+        }
+    }
 }
 
 /// Summarize the upstream metadata into a dictionary.
@@ -3383,12 +6450,34 @@ pub async fn fix_upstream_metadata(upstream_metadata: &mut UpstreamMetadata) {
 /// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
 /// * `net_access`: Whether to allow net access
 /// * `consult_external_directory`: Whether to pull in data from external (user-maintained) directories.
+#[cfg(feature = "providers")]
 pub async fn summarize_upstream_metadata(
     metadata_items: impl Stream<Item = UpstreamDatumWithMetadata>,
     path: &std::path::Path,
     net_access: Option<bool>,
     consult_external_directory: Option<bool>,
     check: Option<bool>,
+) -> Result<UpstreamMetadata, ProviderError> {
+    summarize_upstream_metadata_with_fields(
+        metadata_items,
+        path,
+        net_access,
+        consult_external_directory,
+        check,
+        &FieldSet::all(),
+    )
+    .await
+}
+
+/// Summarize the upstream metadata into a dictionary, only including `fields`.
+#[cfg(feature = "providers")]
+pub async fn summarize_upstream_metadata_with_fields(
+    metadata_items: impl Stream<Item = UpstreamDatumWithMetadata>,
+    path: &std::path::Path,
+    net_access: Option<bool>,
+    consult_external_directory: Option<bool>,
+    check: Option<bool>,
+    fields: &FieldSet,
 ) -> Result<UpstreamMetadata, ProviderError> {
     let check = check.unwrap_or(false);
     let mut upstream_metadata = UpstreamMetadata::new();
@@ -3403,140 +6492,715 @@ pub async fn summarize_upstream_metadata(
         }
     });
 
-    let metadata_items = metadata_items.collect::<Vec<_>>().await;
+    let metadata_items = metadata_items.collect::<Vec<_>>().await;
+
+    // Only a single License is kept below, so look for disagreements first
+    let license_statements = check::license_statements(&metadata_items);
+
+    let mut conflicts = vec![];
+    upstream_metadata.update_with_conflicts(metadata_items.into_iter(), &mut conflicts);
+
+    extend_upstream_metadata_with_fields(
+        &mut upstream_metadata,
+        path,
+        None,
+        net_access,
+        consult_external_directory,
+        fields,
+    )
+    .await?;
+
+    if check {
+        let mut report = check_upstream_metadata_with_policy(
+            &mut upstream_metadata,
+            None,
+            &CheckPolicy {
+                network: NetworkPolicy::from_net_access(net_access.unwrap_or(false)),
+                ..Default::default()
+            },
+        )
+        .await;
+        report.extend(check::check_licenses(&license_statements));
+        report.extend(check::check_conflicts(&conflicts));
+        report.extend(check::check_consistency(&upstream_metadata, net_access).await);
+        for issue in report.issues {
+            log::warn!("{}", issue);
+        }
+    }
+
+    fix_upstream_metadata(&mut upstream_metadata).await;
+
+    if !fields.is_all() {
+        upstream_metadata
+            .mut_items()
+            .retain(|d| fields.contains(d.datum.field()));
+    }
+
+    // Sort by name
+    upstream_metadata.sort();
+
+    Ok(upstream_metadata)
+}
+
+/// Guess upstream metadata items, in no particular order.
+///
+/// # Arguments
+/// * `path`: Path to the package
+/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
+/// * `minimum_certainty`: Minimum certainty of guesses to return
+#[cfg(feature = "providers")]
+#[deprecated(since = "0.2.2", note = "use MetadataGuesser::stream instead")]
+pub fn guess_upstream_metadata_items(
+    path: &std::path::Path,
+    trust_package: Option<bool>,
+    minimum_certainty: Option<Certainty>,
+) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> {
+    let items = upstream_metadata_stream(path, trust_package);
+
+    items.filter_map(move |e| async move {
+        match e {
+            Err(e) => Some(Err(e)),
+            Ok(UpstreamDatumWithMetadata {
+                datum,
+                certainty,
+                origin,
+            }) => {
+                if minimum_certainty.is_some() && certainty < minimum_certainty {
+                    None
+                } else {
+                    Some(Ok(UpstreamDatumWithMetadata {
+                        datum,
+                        certainty,
+                        origin,
+                    }))
+                }
+            }
+        }
+    })
+}
+
+/// Guess the upstream metadata dictionary.
+#[cfg(feature = "providers")]
+#[deprecated(since = "0.2.2", note = "use MetadataGuesser::guess instead")]
+pub async fn get_upstream_info(
+    path: &std::path::Path,
+    trust_package: Option<bool>,
+    net_access: Option<bool>,
+    consult_external_directory: Option<bool>,
+    check: Option<bool>,
+) -> Result<UpstreamMetadata, ProviderError> {
+    MetadataGuesser::from_options(trust_package, net_access, consult_external_directory, check)
+        .build()
+        .guess(path)
+        .await
+}
+
+/// Guess the upstream metadata dictionary.
+///
+/// # Arguments
+/// * `path`: Path to the package
+/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
+/// * `net_access`: Whether to allow net access
+/// * `consult_external_directory`: Whether to pull in data from external (user-maintained) directories.
+#[cfg(feature = "providers")]
+#[deprecated(since = "0.2.2", note = "use MetadataGuesser::guess instead")]
+pub async fn guess_upstream_metadata(
+    path: &std::path::Path,
+    trust_package: Option<bool>,
+    net_access: Option<bool>,
+    consult_external_directory: Option<bool>,
+    check: Option<bool>,
+) -> Result<UpstreamMetadata, ProviderError> {
+    MetadataGuesser::from_options(trust_package, net_access, consult_external_directory, check)
+        .build()
+        .guess(path)
+        .await
+}
+
+/// Guess the upstream metadata dictionary, only for the given fields.
+///
+/// Providers that can't supply any of the fields are skipped entirely.
+///
+/// # Arguments
+/// * `path`: Path to the package
+/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
+/// * `net_access`: Whether to allow net access
+/// * `consult_external_directory`: Whether to pull in data from external (user-maintained) directories.
+/// * `fields`: Fields to guess
+#[deprecated(
+    since = "0.2.2",
+    note = "use MetadataGuesser::guess with MetadataGuesserBuilder::fields instead"
+)]
+#[cfg(feature = "providers")]
+pub async fn guess_upstream_metadata_with_fields(
+    path: &std::path::Path,
+    trust_package: Option<bool>,
+    net_access: Option<bool>,
+    consult_external_directory: Option<bool>,
+    check: Option<bool>,
+    fields: &FieldSet,
+) -> Result<UpstreamMetadata, ProviderError> {
+    MetadataGuesser::from_options(trust_package, net_access, consult_external_directory, check)
+        .fields(*fields)
+        .build()
+        .guess(path)
+        .await
+}
+
+/// Guesses upstream metadata for a package.
+///
+/// Use [`MetadataGuesser::builder`] to configure what the guesser is allowed to do:
+///
+/// ```no_run
+/// # async fn example() -> Result<(), upstream_ontologist::ProviderError> {
+/// let guesser = upstream_ontologist::MetadataGuesser::builder()
+///     .net_access(true)
+///     .trust(false)
+///     .minimum_certainty(upstream_ontologist::Certainty::Likely)
+///     .build();
+/// let metadata = guesser.guess(std::path::Path::new(".")).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "providers")]
+#[derive(Debug, Clone, Default)]
+pub struct MetadataGuesser {
+    trust_package: bool,
+    network: Option<NetworkPolicy>,
+    http_timeout: Option<std::time::Duration>,
+    http_cache: http::CacheMode,
+    retry_budget: Option<std::time::Duration>,
+    deadline: Option<std::time::Duration>,
+    max_files: Option<usize>,
+    max_file_size: Option<u64>,
+    consult_external_directory: bool,
+    check: bool,
+    minimum_certainty: Option<Certainty>,
+    fields: FieldSet,
+    extend_steps: Option<Vec<ExtendStep>>,
+    registry: GuesserRegistry,
+}
+
+/// Builder for [`MetadataGuesser`].
+#[cfg(feature = "providers")]
+#[derive(Debug, Clone, Default)]
+pub struct MetadataGuesserBuilder(MetadataGuesser);
+
+#[cfg(feature = "providers")]
+impl MetadataGuesserBuilder {
+    /// Whether to trust the package contents and i.e. run executables in it
+    pub fn trust(mut self, trust_package: bool) -> Self {
+        self.0.trust_package = trust_package;
+        self
+    }
+
+    /// Whether to allow net access
+    pub fn net_access(mut self, net_access: bool) -> Self {
+        self.0.network = Some(NetworkPolicy::from_net_access(net_access));
+        self
+    }
+
+    /// Whether to allow net access, or only use cached responses; no net access if not set
+    pub fn network_policy(mut self, network: NetworkPolicy) -> Self {
+        self.0.network = Some(network);
+        self
+    }
+
+    /// Timeout for individual HTTP requests
+    pub fn http_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.0.http_timeout = Some(timeout);
+        self
+    }
+
+    /// Whether to cache HTTP responses on disk; defaults to [`http::HTTP_CACHE_ENV`]
+    pub fn http_cache(mut self, cache: http::CacheMode) -> Self {
+        self.0.http_cache = cache;
+        self
+    }
+
+    /// How long HTTP requests may wait for rate limits to reset; defaults to
+    /// [`http::DEFAULT_RETRY_BUDGET`]
+    pub fn retry_budget(mut self, budget: std::time::Duration) -> Self {
+        self.0.retry_budget = Some(budget);
+        self
+    }
+
+    /// Stop guessing from the files in the package after this much time
+    pub fn deadline(mut self, deadline: std::time::Duration) -> Self {
+        self.0.deadline = Some(deadline);
+        self
+    }
+
+    /// Maximum number of files to scan, for guessers that scan several files
+    pub fn max_files(mut self, max_files: usize) -> Self {
+        self.0.max_files = Some(max_files);
+        self
+    }
+
+    /// Don't read files larger than this many bytes
+    pub fn max_file_size(mut self, max_file_size: u64) -> Self {
+        self.0.max_file_size = Some(max_file_size);
+        self
+    }
+
+    /// Whether to pull in data from external (user-maintained) directories
+    pub fn consult_external_directory(mut self, consult_external_directory: bool) -> Self {
+        self.0.consult_external_directory = consult_external_directory;
+        self
+    }
+
+    /// Whether to check the guessed metadata against external sources
+    pub fn check(mut self, check: bool) -> Self {
+        self.0.check = check;
+        self
+    }
+
+    /// Minimum certainty of guesses to return
+    pub fn minimum_certainty(mut self, minimum_certainty: Certainty) -> Self {
+        self.0.minimum_certainty = Some(minimum_certainty);
+        self
+    }
+
+    /// Only guess the given fields; providers that can't supply any of them are skipped
+    pub fn fields(mut self, fields: FieldSet) -> Self {
+        self.0.fields = fields;
+        self
+    }
+
+    /// Steps to run in [`MetadataGuesser::extend`]; all steps if not set
+    pub fn extend_steps(mut self, steps: &[ExtendStep]) -> Self {
+        self.0.extend_steps = Some(steps.to_vec());
+        self
+    }
+
+    /// Guessers to run; the default guessers if not set
+    pub fn registry(mut self, registry: GuesserRegistry) -> Self {
+        self.0.registry = registry;
+        self
+    }
+
+    pub fn build(self) -> MetadataGuesser {
+        self.0
+    }
+}
+
+#[cfg(feature = "providers")]
+impl MetadataGuesser {
+    pub fn builder() -> MetadataGuesserBuilder {
+        MetadataGuesserBuilder::default()
+    }
+
+    /// Builder with the settings of the deprecated positional-argument functions.
+    fn from_options(
+        trust_package: Option<bool>,
+        net_access: Option<bool>,
+        consult_external_directory: Option<bool>,
+        check: Option<bool>,
+    ) -> MetadataGuesserBuilder {
+        Self::builder()
+            .trust(trust_package.unwrap_or(false))
+            .net_access(net_access.unwrap_or(false))
+            .consult_external_directory(consult_external_directory.unwrap_or(false))
+            .check(check.unwrap_or(false))
+    }
+
+    fn network(&self) -> NetworkPolicy {
+        self.network.unwrap_or(NetworkPolicy::Offline)
+    }
+
+    fn settings(&self) -> GuesserSettings {
+        GuesserSettings {
+            trust_package: self.trust_package,
+            network: self.network(),
+            http_timeout: self.http_timeout,
+            deadline: self.deadline,
+            max_files: self.max_files,
+            max_file_size: self.max_file_size,
+            ..Default::default()
+        }
+    }
+
+    fn request_settings(&self) -> http::RequestSettings {
+        http::RequestSettings {
+            network: self.network(),
+            timeout: self.http_timeout,
+            cache: self.http_cache,
+            retry_budget: self.retry_budget,
+        }
+    }
+
+    fn meets_minimum_certainty(&self, certainty: Option<Certainty>) -> bool {
+        self.minimum_certainty.is_none() || certainty >= self.minimum_certainty
+    }
+
+    /// Guess the upstream metadata dictionary.
+    ///
+    /// # Arguments
+    /// * `path`: Path to the package
+    pub async fn guess(&self, path: &std::path::Path) -> Result<UpstreamMetadata, ProviderError> {
+        http::with_settings(self.request_settings(), self.guess_inner(path)).await
+    }
+
+    async fn guess_inner(&self, path: &std::path::Path) -> Result<UpstreamMetadata, ProviderError> {
+        let guessers = filter_guessers(self.registry.find_guessers(path), &self.fields);
+        let metadata_items = stream(path, &self.settings(), guessers);
+
+        let metadata_items = metadata_items.filter_map(|x| async {
+            match x {
+                Ok(x) => Some(x),
+                Err(e) => {
+                    log::error!("{}", e);
+                    None
+                }
+            }
+        });
+        let mut metadata = summarize_upstream_metadata_with_fields(
+            metadata_items,
+            path,
+            Some(self.request_settings().can_fetch()),
+            Some(self.consult_external_directory),
+            Some(self.check),
+            &self.fields,
+        )
+        .await?;
+
+        if self.minimum_certainty.is_some() {
+            metadata
+                .mut_items()
+                .retain(|d| self.meets_minimum_certainty(d.certainty));
+        }
+
+        Ok(metadata)
+    }
+
+    /// Guess upstream metadata items, in no particular order.
+    ///
+    /// # Arguments
+    /// * `path`: Path to the package
+    pub fn stream(
+        &self,
+        path: &std::path::Path,
+    ) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> + '_ {
+        let guessers = filter_guessers(self.registry.find_guessers(path), &self.fields);
+        http::stream_with_settings(
+            self.request_settings(),
+            stream(path, &self.settings(), guessers).filter(move |e| {
+                futures::future::ready(match e {
+                    Ok(item) => self.meets_minimum_certainty(item.certainty),
+                    Err(_) => true,
+                })
+            }),
+        )
+    }
+
+    /// Extend existing upstream metadata from external sources.
+    ///
+    /// # Arguments
+    /// * `metadata`: Metadata to extend
+    /// * `path`: Path to the package
+    pub async fn extend(
+        &self,
+        metadata: &mut UpstreamMetadata,
+        path: &std::path::Path,
+    ) -> Result<(), ProviderError> {
+        let pipeline = ExtendPipeline {
+            steps: self
+                .extend_steps
+                .clone()
+                .unwrap_or_else(|| ExtendStep::ALL.to_vec()),
+            minimum_certainty: self.minimum_certainty.unwrap_or(Certainty::Confident),
+            net_access: self.request_settings().can_fetch(),
+            consult_external_directory: self.consult_external_directory,
+            fields: self.fields,
+        };
+        http::with_settings(
+            self.request_settings(),
+            extend_upstream_metadata_with_pipeline(metadata, path, &pipeline),
+        )
+        .await
+    }
+}
+
+/// Collect the guesses of a set of guessers, logging errors.
+#[cfg(feature = "providers")]
+async fn collect_guesses(
+    path: &Path,
+    settings: &GuesserSettings,
+    guessers: Vec<Box<dyn Guesser>>,
+) -> Vec<UpstreamDatumWithMetadata> {
+    stream(path, settings, guessers)
+        .filter_map(|x| async {
+            match x {
+                Ok(x) if x.datum.known_bad_guess() => {
+                    log::debug!("Excluding known bad item {:?}", x);
+                    None
+                }
+                Ok(x) => Some(x),
+                Err(e) => {
+                    log::error!("{}", e);
+                    None
+                }
+            }
+        })
+        .collect()
+        .await
+}
+
+/// Update previously guessed metadata after some files in the tree have changed.
+///
+/// Only the guessers that read the changed files are run again; files that no provider
+/// reads are ignored. Values that came from the changed files are dropped, and if the
+/// changed files no longer provide a value for such a field, the other guessers that can
+/// provide it are run again as well.
+///
+/// This does not access the network, not even for guessers that would otherwise do so;
+/// call [`extend_upstream_metadata`] afterwards to consult external sources.
+///
+/// # Arguments
+/// * `metadata`: Metadata previously guessed for the tree
+/// * `path`: Path to the root of the tree
+/// * `changed_paths`: Files that were added, modified or removed, relative to `path` or absolute
+/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
+///
+/// # Returns
+/// The fields that were affected by the changes
+#[cfg(feature = "providers")]
+pub async fn refresh(
+    metadata: &mut UpstreamMetadata,
+    path: &Path,
+    changed_paths: &[PathBuf],
+    trust_package: Option<bool>,
+) -> Result<FieldSet, ProviderError> {
+    let settings = GuesserSettings {
+        trust_package: trust_package.unwrap_or(false),
+        network: NetworkPolicy::Offline,
+        ..Default::default()
+    };
+    let request_settings = http::RequestSettings {
+        network: NetworkPolicy::Offline,
+        ..Default::default()
+    };
+    let root = path.canonicalize()?;
+
+    let changed = changed_paths
+        .iter()
+        .filter_map(|p| {
+            let relpath = if p.is_absolute() {
+                p.strip_prefix(&root)
+                    .or_else(|_| p.strip_prefix(path))
+                    .ok()?
+            } else {
+                p.as_path()
+            };
+            let name = relpath.to_string_lossy().to_string();
+            let basename = relpath.file_name()?.to_string_lossy();
+            if descriptors::local_provider_fields(&name)
+                .or_else(|| descriptors::local_provider_fields(&basename))
+                .is_none()
+            {
+                log::debug!("No provider reads {}, ignoring", name);
+                return None;
+            }
+            Some(name)
+        })
+        .collect::<Vec<_>>();
+
+    let mut affected = FieldSet::empty();
+    if changed.is_empty() {
+        return Ok(affected);
+    }
+
+    // Drop values that were found in the changed files; origins are usually relative to the
+    // root of the tree, see rewrite_upstream_datum
+    let changed_origins = changed
+        .iter()
+        .flat_map(|n| [Path::new(".").join(n), root.join(n), path.join(n)])
+        .collect::<Vec<_>>();
+    let is_changed = |o: &Origin| matches!(o, Origin::Path(p) if changed_origins.contains(p));
+    metadata.mut_items().retain_mut(|d| {
+        let keep = match d.origin.as_mut() {
+            Some(Origin::Multiple(origins)) => {
+                origins.retain(|o| !is_changed(o));
+                if origins.len() == 1 {
+                    d.origin = origins.pop();
+                }
+                d.origin.is_some()
+            }
+            Some(origin) => !is_changed(origin),
+            None => true,
+        };
+        if !keep {
+            affected.insert(d.datum.field());
+        }
+        keep
+    });
+
+    let guessers = find_guessers(&root)
+        .into_iter()
+        .filter(|g| changed.iter().any(|n| n == g.name()))
+        .collect::<Vec<_>>();
+    let mut items =
+        http::with_settings(request_settings, collect_guesses(path, &settings, guessers)).await;
+    for item in items.iter() {
+        affected.insert(item.datum.field());
+    }
 
-    upstream_metadata.update(metadata_items.into_iter());
+    // Fields that the changed files no longer provide may be provided by other files
+    let missing = affected
+        .iter()
+        .filter(|f| !metadata.contains_key(f) && !items.iter().any(|i| i.datum.field() == *f))
+        .collect::<FieldSet>();
+    if !missing.is_empty() {
+        let guessers = filter_guessers(find_guessers(&root), &missing)
+            .into_iter()
+            .filter(|g| !changed.iter().any(|n| n == g.name()))
+            .collect::<Vec<_>>();
+        items.extend(
+            http::with_settings(request_settings, collect_guesses(path, &settings, guessers))
+                .await
+                .into_iter()
+                .filter(|i| missing.contains(i.datum.field())),
+        );
+    }
 
-    extend_upstream_metadata(
-        &mut upstream_metadata,
-        path,
-        None,
-        net_access,
-        consult_external_directory,
-    )
-    .await?;
+    metadata.update(items.into_iter());
 
-    if check {
-        check_upstream_metadata(&mut upstream_metadata, None).await;
-    }
+    Ok(affected)
+}
 
-    fix_upstream_metadata(&mut upstream_metadata).await;
+/// Policy for [`UpstreamMetadata::enrich`].
+#[cfg(feature = "providers")]
+#[derive(Debug, Clone)]
+pub struct EnrichPolicy {
+    /// Whether to trust the package contents and i.e. run executables in it
+    pub trust_package: bool,
 
-    // Sort by name
-    upstream_metadata.sort();
+    /// Whether to allow net access
+    pub net_access: bool,
 
-    Ok(upstream_metadata)
-}
+    /// Whether to pull in data from external (user-maintained) directories
+    pub consult_external_directory: bool,
 
-/// Guess upstream metadata items, in no particular order.
-///
-/// # Arguments
-/// * `path`: Path to the package
-/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
-/// * `minimum_certainty`: Minimum certainty of guesses to return
-pub fn guess_upstream_metadata_items(
-    path: &std::path::Path,
-    trust_package: Option<bool>,
-    minimum_certainty: Option<Certainty>,
-) -> impl Stream<Item = Result<UpstreamDatumWithMetadata, ProviderError>> {
-    let items = upstream_metadata_stream(path, trust_package);
+    /// Fields that are known with at least this certainty are left alone
+    pub sufficient_certainty: Certainty,
 
-    items.filter_map(move |e| async move {
-        match e {
-            Err(e) => Some(Err(e)),
-            Ok(UpstreamDatumWithMetadata {
-                datum,
-                certainty,
-                origin,
-            }) => {
-                if minimum_certainty.is_some() && certainty < minimum_certainty {
-                    None
-                } else {
-                    Some(Ok(UpstreamDatumWithMetadata {
-                        datum,
-                        certainty,
-                        origin,
-                    }))
-                }
-            }
-        }
-    })
+    /// Fields to enrich; all fields if not set
+    pub fields: Option<Vec<String>>,
 }
 
-pub async fn get_upstream_info(
-    path: &std::path::Path,
-    trust_package: Option<bool>,
-    net_access: Option<bool>,
-    consult_external_directory: Option<bool>,
-    check: Option<bool>,
-) -> Result<UpstreamMetadata, ProviderError> {
-    let metadata_items = upstream_metadata_stream(path, trust_package);
+#[cfg(feature = "providers")]
+impl Default for EnrichPolicy {
+    fn default() -> Self {
+        Self {
+            trust_package: false,
+            net_access: false,
+            consult_external_directory: false,
+            sufficient_certainty: Certainty::Certain,
+            fields: None,
+        }
+    }
+}
 
-    let metadata_items = metadata_items.filter_map(|x| async {
-        match x {
-            Ok(x) => Some(x),
-            Err(e) => {
-                log::error!("{}", e);
-                None
+#[cfg(feature = "providers")]
+impl UpstreamMetadata {
+    fn needs_enrichment(&self, field: &str, policy: &EnrichPolicy) -> bool {
+        if let Some(fields) = policy.fields.as_ref() {
+            if !fields.iter().any(|f| f == field) {
+                return false;
             }
         }
-    });
+        match self.get(field) {
+            Some(datum) => datum.certainty < Some(policy.sufficient_certainty),
+            None => true,
+        }
+    }
 
-    summarize_upstream_metadata(
-        metadata_items,
-        path,
-        net_access,
-        consult_external_directory,
-        check,
-    )
-    .await
-}
+    fn is_sufficient(&self, policy: &EnrichPolicy) -> bool {
+        match policy.fields.as_ref() {
+            Some(fields) => fields.iter().all(|f| !self.needs_enrichment(f, policy)),
+            None => false,
+        }
+    }
 
-/// Guess the upstream metadata dictionary.
-///
-/// # Arguments
-/// * `path`: Path to the package
-/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
-/// * `net_access`: Whether to allow net access
-/// * `consult_external_directory`: Whether to pull in data from external (user-maintained) directories.
-pub async fn guess_upstream_metadata(
-    path: &std::path::Path,
-    trust_package: Option<bool>,
-    net_access: Option<bool>,
-    consult_external_directory: Option<bool>,
-    check: Option<bool>,
-) -> Result<UpstreamMetadata, ProviderError> {
-    let metadata_items = guess_upstream_metadata_items(path, trust_package, None);
+    /// Enrich partially known upstream metadata.
+    ///
+    /// This is a targeted alternative to [`guess_upstream_metadata`] for callers that
+    /// already know some of the metadata. Fields that are already known with sufficient
+    /// certainty are left alone, and guessing stops early once all requested fields are known.
+    ///
+    /// # Arguments
+    /// * `path`: Path to the package
+    /// * `policy`: Policy describing which fields to enrich and what may be consulted
+    ///
+    /// # Returns
+    /// The data that was added or changed
+    pub async fn enrich(
+        &mut self,
+        path: &std::path::Path,
+        policy: &EnrichPolicy,
+    ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+        let mut changed = vec![];
+        if self.is_sufficient(policy) {
+            return Ok(changed);
+        }
 
-    let metadata_items = metadata_items.filter_map(|x| async {
-        match x {
-            Ok(x) => Some(x),
-            Err(e) => {
-                log::error!("{}", e);
-                None
+        let fields = match policy.fields.as_ref() {
+            Some(fields) => fields.iter().map(|f| f.as_str()).collect(),
+            None => FieldSet::all(),
+        };
+        let settings = GuesserSettings {
+            trust_package: policy.trust_package,
+            ..Default::default()
+        };
+        let guessers = filter_guessers(find_guessers(path), &fields);
+        let mut items = Box::pin(stream(path, &settings, guessers));
+        while let Some(item) = items.next().await {
+            let item = match item {
+                Ok(item) => item,
+                Err(e) => {
+                    log::error!("{}", e);
+                    continue;
+                }
+            };
+            if item.datum.known_bad_guess() {
+                log::debug!("Excluding known bad item {:?}", item);
+                continue;
+            }
+            if !self.needs_enrichment(item.datum.field(), policy) {
+                continue;
+            }
+            changed.extend(self.update(std::iter::once(item)));
+            if self.is_sufficient(policy) {
+                return Ok(changed);
             }
         }
-    });
-    summarize_upstream_metadata(
-        metadata_items,
-        path,
-        net_access,
-        consult_external_directory,
-        check,
-    )
-    .await
+
+        let before = self.clone();
+        extend_upstream_metadata_with_fields(
+            self,
+            path,
+            None,
+            Some(policy.net_access),
+            Some(policy.consult_external_directory),
+            &fields,
+        )
+        .await?;
+        for datum in self.iter() {
+            if before.get(datum.datum.field()) != Some(datum) {
+                changed.push(datum.clone());
+            }
+        }
+
+        Ok(changed)
+    }
 }
 
+#[cfg(feature = "providers")]
 pub async fn verify_screenshots(urls: &[&str]) -> Vec<(String, Option<bool>)> {
     let mut ret = Vec::new();
+    let client = http::build_client().build().unwrap();
     for url in urls {
-        let mut request = reqwest::Request::new(reqwest::Method::GET, url.parse().unwrap());
-        request.headers_mut().insert(
-            reqwest::header::USER_AGENT,
-            reqwest::header::HeaderValue::from_static(USER_AGENT),
-        );
-
-        match reqwest::Client::new().execute(request).await {
+        match http::send(client.get(*url)).await {
             Ok(response) => {
                 let status = response.status();
                 if status.is_success() {
@@ -3560,10 +7224,29 @@ pub async fn verify_screenshots(urls: &[&str]) -> Vec<(String, Option<bool>)> {
 /// Check upstream metadata.
 ///
 /// This will make network connections, etc.
+///
+/// Problems that can't be fixed automatically are returned in a [`CheckReport`].
+#[cfg(feature = "providers")]
 pub async fn check_upstream_metadata(
     upstream_metadata: &mut UpstreamMetadata,
     version: Option<&str>,
-) {
+) -> CheckReport {
+    check_upstream_metadata_with_policy(upstream_metadata, version, &CheckPolicy::default()).await
+}
+
+/// Check upstream metadata, using the specified policy.
+///
+/// See [`check_upstream_metadata`].
+#[cfg(feature = "providers")]
+pub async fn check_upstream_metadata_with_policy(
+    upstream_metadata: &mut UpstreamMetadata,
+    version: Option<&str>,
+    policy: &CheckPolicy,
+) -> CheckReport {
+    if !policy.network.allows_network() {
+        log::debug!("Not checking upstream metadata: no network access");
+        return CheckReport::new();
+    }
     let repository = upstream_metadata.get_mut("Repository");
     if let Some(repository) = repository {
         match vcs::check_repository_url_canonical(repository.datum.to_url().unwrap(), version).await
@@ -3599,12 +7282,31 @@ pub async fn check_upstream_metadata(
             }
         }
     }
+    let name = upstream_metadata.name().map(|n| n.to_string());
+    let repository_urls = ["Repository", "Repository-Browse"]
+        .iter()
+        .filter_map(|f| upstream_metadata.get(f).and_then(|d| d.datum.to_url()))
+        .collect::<Vec<_>>();
     let homepage = upstream_metadata.get_mut("Homepage");
     if let Some(homepage) = homepage {
-        match check_url_canonical(&homepage.datum.to_url().unwrap()).await {
-            Ok(canonical_url) => {
+        match check_url_canonical_with_body(&homepage.datum.to_url().unwrap()).await {
+            Ok((canonical_url, body)) => {
                 homepage.datum = UpstreamDatum::Homepage(canonical_url.to_string());
-                if homepage.certainty >= Some(Certainty::Likely) {
+                // A page that can be retrieved is not necessarily about the project, e.g.
+                // if the domain has lapsed
+                let confirmed = !policy.confirm_homepage_content
+                    || check::page_confirms_project(
+                        &body,
+                        &canonical_url,
+                        name.as_deref(),
+                        &repository_urls,
+                    );
+                if !confirmed {
+                    log::debug!(
+                        "Homepage {} does not mention the project, not raising certainty",
+                        canonical_url
+                    );
+                } else if homepage.certainty >= Some(Certainty::Likely) {
                     homepage.certainty = Some(Certainty::Certain);
                 }
             }
@@ -3699,10 +7401,18 @@ pub async fn check_upstream_metadata(
         }
         screenshots.as_mut().unwrap().datum = UpstreamDatum::Screenshots(newvalue);
     }
+
+    check::check_version(upstream_metadata).await
 }
 
+/// A source of metadata for a source tree, such as a file in it.
+///
+/// See [`GuesserRegistry`] for how to add guessers.
+#[cfg(feature = "providers")]
 #[async_trait::async_trait]
-pub(crate) trait Guesser {
+pub trait Guesser {
+    /// Name of the guesser, typically the path of the file it reads relative to the root of
+    /// the source tree.
     fn name(&self) -> &str;
 
     /// Guess metadata from a given path.
@@ -3724,6 +7434,7 @@ pub(crate) trait Guesser {
     }
 }
 
+#[cfg(feature = "providers")]
 pub struct PathGuesser {
     name: String,
     subpath: std::path::PathBuf,
@@ -3741,6 +7452,7 @@ pub struct PathGuesser {
     >,
 }
 
+#[cfg(feature = "providers")]
 #[async_trait::async_trait]
 impl Guesser for PathGuesser {
     fn name(&self) -> &str {
@@ -3751,89 +7463,573 @@ impl Guesser for PathGuesser {
         &mut self,
         settings: &GuesserSettings,
     ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+        if let Some(max_file_size) = settings.max_file_size {
+            if let Ok(metadata) = std::fs::metadata(&self.subpath) {
+                if metadata.is_file() && metadata.len() > max_file_size {
+                    log::debug!(
+                        "Not reading {}: larger than {} bytes",
+                        self.subpath.display(),
+                        max_file_size
+                    );
+                    return Ok(vec![]);
+                }
+            }
+        }
         (self.cb)(self.subpath.clone(), settings.clone()).await
     }
 }
 
+#[cfg(feature = "providers")]
 pub struct EnvironmentGuesser;
 
+#[cfg(feature = "providers")]
 impl EnvironmentGuesser {
     pub fn new() -> Self {
         Self
     }
 }
 
+#[cfg(feature = "providers")]
 impl Default for EnvironmentGuesser {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[async_trait::async_trait]
-impl Guesser for EnvironmentGuesser {
-    fn name(&self) -> &str {
-        "environment"
+#[cfg(feature = "providers")]
+#[async_trait::async_trait]
+impl Guesser for EnvironmentGuesser {
+    fn name(&self) -> &str {
+        "environment"
+    }
+
+    async fn guess(
+        &mut self,
+        _settings: &GuesserSettings,
+    ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+        crate::guess_from_environment()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upstream_metadata() {
+        let mut data = UpstreamMetadata::new();
+        assert_eq!(data.len(), 0);
+
+        data.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Homepage("https://example.com".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            data.get("Homepage").unwrap().datum.as_str().unwrap(),
+            "https://example.com"
+        );
+
+        assert_eq!(data.homepage(), Some("https://example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_bug_database_url_from_bug_submit_url() {
+        let url = Url::parse("https://bugs.launchpad.net/bugs/+filebug").unwrap();
+        assert_eq!(
+            bug_database_url_from_bug_submit_url(&url, None)
+                .await
+                .unwrap(),
+            Url::parse("https://bugs.launchpad.net/bugs").unwrap()
+        );
+
+        let url = Url::parse("https://github.com/dulwich/dulwich/issues/new").unwrap();
+
+        assert_eq!(
+            bug_database_url_from_bug_submit_url(&url, None)
+                .await
+                .unwrap(),
+            Url::parse("https://github.com/dulwich/dulwich/issues").unwrap()
+        );
+
+        let url = Url::parse("https://sourceforge.net/p/dulwich/bugs/new").unwrap();
+
+        assert_eq!(
+            bug_database_url_from_bug_submit_url(&url, None)
+                .await
+                .unwrap(),
+            Url::parse("https://sourceforge.net/p/dulwich/bugs").unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enrich() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(
+            td.path().join("configure.ac"),
+            "AC_INIT([foo], [1.2], [], [], [https://example.com/foo])\n",
+        )
+        .unwrap();
+
+        let mut metadata = UpstreamMetadata::from_data(vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("bar".to_string()),
+            certainty: Some(Certainty::Likely),
+            origin: None,
+        }]);
+        let policy = EnrichPolicy {
+            sufficient_certainty: Certainty::Likely,
+            ..Default::default()
+        };
+        let changed = metadata.enrich(td.path(), &policy).await.unwrap();
+        assert_eq!(metadata.name(), Some("bar"));
+        assert_eq!(metadata.version(), Some("1.2"));
+        assert_eq!(metadata.homepage(), Some("https://example.com/foo"));
+        assert!(changed.iter().all(|d| d.datum.field() != "Name"));
+
+        // Nothing is done if all requested fields are already known
+        let mut metadata = UpstreamMetadata::from_data(vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("bar".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        }]);
+        let policy = EnrichPolicy {
+            fields: Some(vec!["Name".to_string()]),
+            ..Default::default()
+        };
+        assert_eq!(metadata.enrich(td.path(), &policy).await.unwrap(), vec![]);
+        assert_eq!(metadata.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_guess_with_fields() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(
+            td.path().join("configure.ac"),
+            "AC_INIT([foo], [1.2], [], [], [https://example.com/foo])\n",
+        )
+        .unwrap();
+        std::fs::write(td.path().join("AUTHORS"), "Jane Doe <jane@example.com>\n").unwrap();
+
+        let fields = ["Version"].into_iter().collect::<FieldSet>();
+        let guessers = filter_guessers(find_guessers(td.path()), &fields);
+        assert!(guessers.iter().any(|g| g.name() == "configure.ac"));
+        assert!(!guessers.iter().any(|g| g.name() == "AUTHORS"));
+
+        let metadata = MetadataGuesser::builder()
+            .fields(fields)
+            .build()
+            .guess(td.path())
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata.iter().map(|d| d.datum.field()).collect::<Vec<_>>(),
+            vec!["Version"]
+        );
+        assert_eq!(metadata.version(), Some("1.2"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let datum = |datum, certainty, origin: &str| UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(Origin::Other(origin.to_string())),
+        };
+        let mut metadata = UpstreamMetadata::from_data(vec![
+            datum(
+                UpstreamDatum::Name("foo".to_string()),
+                Certainty::Likely,
+                "cache",
+            ),
+            datum(
+                UpstreamDatum::Version("1.0".to_string()),
+                Certainty::Certain,
+                "cache",
+            ),
+        ]);
+        let fresh = UpstreamMetadata::from_data(vec![
+            datum(
+                UpstreamDatum::Name("foo".to_string()),
+                Certainty::Certain,
+                "setup.py",
+            ),
+            datum(
+                UpstreamDatum::Version("0.9".to_string()),
+                Certainty::Likely,
+                "setup.py",
+            ),
+            datum(
+                UpstreamDatum::Homepage("https://example.com/".to_string()),
+                Certainty::Likely,
+                "setup.py",
+            ),
+        ]);
+        let changed = metadata.merge(fresh, MergePolicy::default());
+        assert_eq!(changed.len(), 2);
+        assert_eq!(
+            metadata.get("Name").unwrap(),
+            &UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Name("foo".to_string()),
+                certainty: Some(Certainty::Certain),
+                origin: Some(Origin::Multiple(vec![
+                    Origin::Other("cache".to_string()),
+                    Origin::Other("setup.py".to_string()),
+                ])),
+            }
+        );
+        assert_eq!(metadata.version(), Some("1.0"));
+        assert_eq!(metadata.homepage(), Some("https://example.com/"));
+
+        let mut metadata = UpstreamMetadata::from_data(vec![datum(
+            UpstreamDatum::Version("1.0".to_string()),
+            Certainty::Likely,
+            "cache",
+        )]);
+        let fresh = UpstreamMetadata::from_data(vec![datum(
+            UpstreamDatum::Version("1.1".to_string()),
+            Certainty::Likely,
+            "setup.py",
+        )]);
+        metadata.merge(fresh.clone(), MergePolicy::FillMissing);
+        assert_eq!(metadata.version(), Some("1.0"));
+        metadata.merge(fresh, MergePolicy::PreferOtherOnTie);
+        assert_eq!(metadata.version(), Some("1.1"));
+    }
+
+    #[test]
+    fn test_from_field_str() {
+        assert_eq!(
+            UpstreamDatum::from_field_str("Name", "foo".into()),
+            Ok(UpstreamDatum::Name("foo".to_string()))
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Maintainer", "Jane <jane@example.com>".into()),
+            Ok(UpstreamDatum::Maintainer(Person {
+                name: Some("Jane".to_string()),
+                email: Some("jane@example.com".to_string()),
+                url: None
+            }))
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Keywords", "rust".into()),
+            Ok(UpstreamDatum::Keywords(vec!["rust".to_string()]))
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Debian-ITP", "123".into()),
+            Ok(UpstreamDatum::DebianITP(123))
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Bogus", "foo".into()),
+            Err(UnknownField::Field("Bogus".to_string()))
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Name", 1.into())
+                .unwrap()
+                .as_str(),
+            Some("1")
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_str("Name", vec!["a".to_string()].into()),
+            Err(UnknownField::Value {
+                field: "Name",
+                value: DatumValue::Strings(vec!["a".to_string()])
+            })
+        );
+
+        // Values round-trip
+        let datum = UpstreamDatum::Registry(vec![("Debian".to_string(), "foo".to_string())]);
+        assert_eq!(
+            UpstreamDatum::from_field_str(datum.field(), datum.value()),
+            Ok(datum)
+        );
     }
 
-    async fn guess(
-        &mut self,
-        _settings: &GuesserSettings,
-    ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
-        crate::guess_from_environment()
+    #[test]
+    fn test_from_field_values() {
+        let donations = vec![
+            UpstreamDatum::Donation("https://example.com/a".to_string()),
+            UpstreamDatum::Donation("https://example.com/b".to_string()),
+        ];
+        let value = vec![
+            "https://example.com/a".to_string(),
+            "https://example.com/b".to_string(),
+        ];
+        assert_eq!(
+            UpstreamDatum::from_field_values("Donation", value.clone().into()),
+            Ok(donations)
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_values("Chat", "https://example.com/chat".into()),
+            Ok(vec![UpstreamDatum::Chat(
+                "https://example.com/chat".to_string()
+            )])
+        );
+        // Screenshots holds a list itself
+        assert_eq!(
+            UpstreamDatum::from_field_values("Screenshots", value.clone().into()),
+            Ok(vec![UpstreamDatum::Screenshots(value.clone())])
+        );
+        assert_eq!(
+            UpstreamDatum::from_field_values("Name", value.clone().into()),
+            Err(UnknownField::Value {
+                field: "Name",
+                value: DatumValue::Strings(value)
+            })
+        );
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn test_upstream_metadata() {
-        let mut data = UpstreamMetadata::new();
-        assert_eq!(data.len(), 0);
+    fn test_multi_valued() {
+        let donation = |url: &str, certainty| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Donation(url.to_string()),
+            certainty: Some(certainty),
+            origin: None,
+        };
+        let mut metadata = UpstreamMetadata::new();
+        let changed = metadata.update(
+            vec![
+                donation("https://example.com/a", Certainty::Likely),
+                donation("https://example.com/b", Certainty::Certain),
+                donation("https://example.com/a", Certainty::Certain),
+                donation("https://example.com/b", Certainty::Possible),
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Name("foo".to_string()),
+                    certainty: Some(Certainty::Likely),
+                    origin: None,
+                },
+                UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Name("bar".to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: None,
+                },
+            ]
+            .into_iter(),
+        );
+        assert_eq!(changed.len(), 5);
+        assert_eq!(
+            metadata.get_all("Donation").cloned().collect::<Vec<_>>(),
+            vec![
+                donation("https://example.com/a", Certainty::Certain),
+                donation("https://example.com/b", Certainty::Certain),
+            ]
+        );
+        assert_eq!(metadata.get_all("Name").count(), 1);
+        assert_eq!(metadata.name(), Some("bar"));
+
+        metadata.merge(
+            UpstreamMetadata::from_data(vec![donation(
+                "https://example.com/c",
+                Certainty::Possible,
+            )]),
+            MergePolicy::default(),
+        );
+        assert_eq!(metadata.get_all("Donation").count(), 3);
 
-        data.insert(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::Homepage("https://example.com".to_string()),
-            certainty: Some(Certainty::Certain),
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Screenshots(vec!["https://example.com/1.png".to_string()]),
+            certainty: None,
+            origin: None,
+        });
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Screenshots(vec![
+                "https://example.com/1.png".to_string(),
+                "https://example.com/2.png".to_string(),
+            ]),
+            certainty: None,
             origin: None,
         });
-
-        assert_eq!(data.len(), 1);
         assert_eq!(
-            data.get("Homepage").unwrap().datum.as_str().unwrap(),
-            "https://example.com"
+            serde_yaml::to_string(&metadata).unwrap(),
+            "Donation:\n- https://example.com/a\n- https://example.com/b\n- https://example.com/c\nName: bar\nScreenshots:\n- https://example.com/1.png\n- https://example.com/2.png\n"
         );
 
-        assert_eq!(data.homepage(), Some("https://example.com"));
+        let metadata = UpstreamMetadata::from_data(vec![donation(
+            "https://example.com/a",
+            Certainty::Certain,
+        )]);
+        assert_eq!(
+            serde_yaml::to_string(&metadata).unwrap(),
+            "Donation:\n- https://example.com/a\n"
+        );
     }
 
-    #[tokio::test]
-    async fn test_bug_database_url_from_bug_submit_url() {
-        let url = Url::parse("https://bugs.launchpad.net/bugs/+filebug").unwrap();
+    #[test]
+    fn test_to_json() {
+        let metadata = UpstreamMetadata::from_data(vec![
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Name("foo".to_string()),
+                certainty: Some(Certainty::Certain),
+                origin: Some(Origin::Path("Cargo.toml".into())),
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Author(vec![Person {
+                    name: Some("Jane Doe".to_string()),
+                    email: Some("jane@example.com".to_string()),
+                    url: None,
+                }]),
+                certainty: Some(Certainty::Likely),
+                origin: None,
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Registry(vec![("PyPI".to_string(), "foo".to_string())]),
+                certainty: None,
+                origin: None,
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::DebianITP(1234),
+                certainty: None,
+                origin: None,
+            },
+        ]);
+        let values = serde_json::json!({
+            "Name": "foo",
+            "Author": [{"name": "Jane Doe", "email": "jane@example.com"}],
+            "Registry": [{"Name": "PyPI", "Entry": "foo"}],
+            "Debian-ITP": 1234,
+        });
+        assert_eq!(metadata.to_json(), values);
         assert_eq!(
-            bug_database_url_from_bug_submit_url(&url, None)
-                .await
-                .unwrap(),
-            Url::parse("https://bugs.launchpad.net/bugs").unwrap()
+            metadata.to_json_annotated(),
+            serde_json::json!({
+                "metadata": values,
+                "certainty": {"Name": "certain", "Author": "likely"},
+                "origin": {"Name": "Cargo.toml"},
+            })
+        );
+        assert_eq!(
+            serde_json::to_string(&metadata.to_json()).unwrap(),
+            r#"{"Author":[{"email":"jane@example.com","name":"Jane Doe"}],"Debian-ITP":1234,"Name":"foo","Registry":[{"Entry":"foo","Name":"PyPI"}]}"#
         );
+    }
 
-        let url = Url::parse("https://github.com/dulwich/dulwich/issues/new").unwrap();
+    #[test]
+    fn test_update_with_conflicts() {
+        let homepage = |url: &str, certainty, origin: &str| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Homepage(url.to_string()),
+            certainty: Some(certainty),
+            origin: Some(Origin::Other(origin.to_string())),
+        };
+        let mut metadata = UpstreamMetadata::new();
+        let mut conflicts = vec![];
+        metadata.update_with_conflicts(
+            vec![
+                homepage("https://example.com/a", Certainty::Likely, "README"),
+                homepage("https://example.com/a", Certainty::Possible, "doap"),
+                homepage("https://example.com/b", Certainty::Possible, "setup.py"),
+            ]
+            .into_iter(),
+            &mut conflicts,
+        );
+        // The value that was kept was confirmed by a second source
+        let kept = UpstreamDatumWithMetadata {
+            origin: Some(Origin::Multiple(vec![
+                Origin::Other("README".to_string()),
+                Origin::Other("doap".to_string()),
+            ])),
+            ..homepage("https://example.com/a", Certainty::Likely, "README")
+        };
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                kept: kept.clone(),
+                discarded: vec![homepage(
+                    "https://example.com/b",
+                    Certainty::Possible,
+                    "setup.py"
+                )],
+            }]
+        );
 
+        metadata.update_with_conflicts(
+            std::iter::once(homepage(
+                "https://example.com/c",
+                Certainty::Certain,
+                "Cargo.toml",
+            )),
+            &mut conflicts,
+        );
+        assert_eq!(metadata.homepage(), Some("https://example.com/c"));
+        assert_eq!(conflicts.len(), 1);
         assert_eq!(
-            bug_database_url_from_bug_submit_url(&url, None)
-                .await
-                .unwrap(),
-            Url::parse("https://github.com/dulwich/dulwich/issues").unwrap()
+            conflicts[0].kept,
+            homepage("https://example.com/c", Certainty::Certain, "Cargo.toml")
+        );
+        assert_eq!(
+            conflicts[0].discarded,
+            vec![
+                homepage("https://example.com/b", Certainty::Possible, "setup.py"),
+                kept,
+            ]
         );
+    }
 
-        let url = Url::parse("https://sourceforge.net/p/dulwich/bugs/new").unwrap();
+    #[test]
+    fn test_origin_chain() {
+        let repository = |certainty, origin: Origin| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Repository("https://github.com/jelmer/example".to_string()),
+            certainty: Some(certainty),
+            origin: Some(origin),
+        };
+        let api_url: url::Url = "https://api.github.com/repos/jelmer/example"
+            .parse()
+            .unwrap();
+        let mut metadata = UpstreamMetadata::new();
+        metadata.update(
+            vec![
+                repository(Certainty::Likely, Origin::Path("README.md".into())),
+                repository(Certainty::Certain, Origin::Url(api_url.clone())),
+                repository(Certainty::Possible, Origin::Path("README.md".into())),
+            ]
+            .into_iter(),
+        );
+        let datum = metadata.get("Repository").unwrap();
+        assert_eq!(datum.certainty, Some(Certainty::Certain));
+        let origin = datum.origin.as_ref().unwrap();
+        assert_eq!(
+            origin.iter().collect::<Vec<_>>(),
+            vec![&Origin::Path("README.md".into()), &Origin::Url(api_url)]
+        );
+        assert_eq!(
+            serde_json::to_value(origin).unwrap(),
+            serde_json::json!(["README.md", "https://api.github.com/repos/jelmer/example"])
+        );
+        assert_eq!(
+            serde_json::to_value(Origin::Other("environment".to_string())).unwrap(),
+            serde_json::json!("environment")
+        );
+    }
 
+    #[cfg(feature = "providers")]
+    #[test]
+    fn test_rewrite_upstream_datum() {
+        let mut datum = UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("example".to_string()),
+            certainty: None,
+            origin: Some(Origin::Multiple(vec![
+                Origin::Path("/srv/example/setup.py".into()),
+                Origin::Path("/srv/example".into()),
+                Origin::Other("pypi".to_string()),
+            ])),
+        };
+        rewrite_upstream_datum("setup.py", &mut datum, Path::new("/srv/example"));
         assert_eq!(
-            bug_database_url_from_bug_submit_url(&url, None)
-                .await
-                .unwrap(),
-            Url::parse("https://sourceforge.net/p/dulwich/bugs").unwrap()
+            datum.origin,
+            Some(Origin::Multiple(vec![
+                Origin::Path("./setup.py".into()),
+                Origin::Path(".".into()),
+                Origin::Other("pypi".to_string()),
+            ]))
         );
+
+        let mut datum = UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("example".to_string()),
+            certainty: None,
+            origin: None,
+        };
+        rewrite_upstream_datum("setup.py", &mut datum, Path::new("/srv/example"));
+        assert_eq!(datum.origin, Some(Origin::Other("setup.py".to_string())));
     }
 
     #[test]
@@ -3863,4 +8059,389 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_person_is_organization() {
+        assert!(Person::from("Example, Inc.").is_organization());
+        assert!(Person::from("Example GmbH <info@example.com>").is_organization());
+        assert!(Person::from("The Example Foundation").is_organization());
+        assert!(!Person::from("Foo Bar <foo@example.com>").is_organization());
+        assert!(!Person::from("Inc").is_organization());
+        assert!(!Person::from("foo@example.com").is_organization());
+    }
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(
+            td.path().join("pubspec.yaml"),
+            "name: foo\nversion: 1.0\nhomepage: https://example.com/foo\n",
+        )
+        .unwrap();
+        let mut metadata = UpstreamMetadata::new();
+        metadata.update(
+            MetadataGuesser::default()
+                .stream(td.path())
+                .filter_map(|x| async { x.ok() })
+                .collect::<Vec<_>>()
+                .await
+                .into_iter(),
+        );
+        assert_eq!(metadata.version(), Some("1.0"));
+
+        std::fs::write(td.path().join("pubspec.yaml"), "name: foo\nversion: 1.1\n").unwrap();
+        std::fs::write(td.path().join("main.dart"), "").unwrap();
+        let affected = refresh(
+            &mut metadata,
+            td.path(),
+            &[PathBuf::from("pubspec.yaml"), PathBuf::from("main.dart")],
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(metadata.version(), Some("1.1"));
+        assert_eq!(metadata.homepage(), None);
+        assert!(affected.contains("Version"));
+        assert!(affected.contains("Homepage"));
+
+        let affected = refresh(
+            &mut metadata,
+            td.path(),
+            &[PathBuf::from("main.dart")],
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(affected.is_empty());
+    }
+
+    struct FakeRegistry;
+
+    #[async_trait::async_trait]
+    impl ThirdPartyRepository for FakeRegistry {
+        fn name(&self) -> &'static str {
+            "Fake"
+        }
+
+        fn supported_fields(&self) -> &'static [&'static str] {
+            &["Homepage"]
+        }
+
+        fn max_supported_certainty(&self) -> Certainty {
+            Certainty::Likely
+        }
+
+        async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+            Ok(match name {
+                "foo-bar" => vec![UpstreamDatum::Homepage(
+                    "https://example.com/foo-bar".to_string(),
+                )],
+                _ => vec![],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extend_metadata_alias_fallback() {
+        let mut metadata = vec![];
+        FakeRegistry
+            .extend_metadata(&mut metadata, "foo_bar", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            metadata,
+            vec![UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Homepage("https://example.com/foo-bar".to_string()),
+                certainty: Some(Certainty::Likely),
+                origin: Some(Origin::Other("Fake (as foo-bar)".to_string())),
+            }]
+        );
+
+        let mut metadata = vec![];
+        FakeRegistry
+            .extend_metadata(&mut metadata, "unknown", None)
+            .await
+            .unwrap();
+        assert!(metadata.is_empty());
+    }
+
+    #[test]
+    fn test_known_bad_snippet_urls() {
+        for url in [
+            "https://gist.github.com/jelmer/0123456789abcdef",
+            "https://bitbucket.org/snippets/jelmer/abcd",
+            "https://gitlab.com/jelmer/foo/-/snippets/1234",
+            "https://gitlab.com/snippets/1234",
+            "https://pastebin.com/abcdef",
+            "https://www.pastebin.com/abcdef",
+            "https://paste.debian.net/123456/",
+        ] {
+            assert!(
+                UpstreamDatum::Repository(url.to_string()).known_bad_guess(),
+                "{}",
+                url
+            );
+            assert!(
+                UpstreamDatum::Homepage(url.to_string()).known_bad_guess(),
+                "{}",
+                url
+            );
+        }
+        for url in [
+            "https://github.com/jelmer/gist",
+            "https://bitbucket.org/jelmer/snippets",
+            "https://gitlab.com/jelmer/foo",
+        ] {
+            assert!(
+                !UpstreamDatum::Repository(url.to_string()).known_bad_guess(),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_chat_url() {
+        for url in [
+            "irc://irc.libera.chat/#dulwich",
+            "https://matrix.to/#/#dulwich:matrix.org",
+            "https://discord.gg/abcdef",
+            "https://discord.com/invite/abcdef",
+            "https://gitter.im/dulwich/community",
+            "https://dulwich.slack.com/",
+        ] {
+            assert!(is_chat_url(&url.parse().unwrap()), "{}", url);
+        }
+        for url in [
+            "https://discord.com/developers",
+            "https://github.com/jelmer/dulwich",
+            "https://slack.com/",
+        ] {
+            assert!(!is_chat_url(&url.parse().unwrap()), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_forge_sourcehut() {
+        let forge = find_forge(
+            &"https://git.sr.ht/~sircmpwn/scdoc".parse().unwrap(),
+            Some(false),
+        )
+        .await
+        .unwrap();
+        assert_eq!(forge.name(), "SourceHut");
+        assert_eq!(
+            forge.bug_database_url_from_repo_url(
+                &"https://git.sr.ht/~sircmpwn/scdoc".parse().unwrap()
+            ),
+            Some("https://todo.sr.ht/~sircmpwn/scdoc".parse().unwrap())
+        );
+        assert_eq!(
+            forge.mailing_list_url_from_repo_url(
+                &"https://git.sr.ht/~sircmpwn/scdoc".parse().unwrap()
+            ),
+            Some("https://lists.sr.ht/~sircmpwn/scdoc".parse().unwrap())
+        );
+        assert_eq!(
+            forge.bug_database_from_issue_url(
+                &"https://todo.sr.ht/~sircmpwn/scdoc/12".parse().unwrap()
+            ),
+            Some("https://todo.sr.ht/~sircmpwn/scdoc".parse().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_forge_gitee_osdn_bitbucket() {
+        let forge = find_forge(&"https://gitee.com/foo/bar".parse().unwrap(), Some(false))
+            .await
+            .unwrap();
+        assert_eq!(forge.name(), "Gitee");
+        assert_eq!(
+            forge.bug_database_url_from_repo_url(&"https://gitee.com/foo/bar.git".parse().unwrap()),
+            Some("https://gitee.com/foo/bar/issues".parse().unwrap())
+        );
+        assert_eq!(
+            forge.bug_submit_url_from_bug_database_url(
+                &"https://gitee.com/foo/bar/issues".parse().unwrap()
+            ),
+            Some("https://gitee.com/foo/bar/issues/new".parse().unwrap())
+        );
+
+        let forge = find_forge(
+            &"https://bitbucket.org/foo/bar/issues/12".parse().unwrap(),
+            Some(false),
+        )
+        .await
+        .unwrap();
+        assert_eq!(forge.name(), "Bitbucket");
+        assert_eq!(
+            forge.bug_database_from_issue_url(
+                &"https://bitbucket.org/foo/bar/issues/12".parse().unwrap()
+            ),
+            Some("https://bitbucket.org/foo/bar/issues".parse().unwrap())
+        );
+
+        let forge = find_forge(
+            &"https://osdn.net/projects/foo/ticket/newticket"
+                .parse()
+                .unwrap(),
+            Some(false),
+        )
+        .await
+        .unwrap();
+        assert_eq!(forge.name(), "OSDN");
+        assert_eq!(
+            forge.bug_database_url_from_bug_submit_url(
+                &"https://osdn.net/projects/foo/ticket/newticket"
+                    .parse()
+                    .unwrap()
+            ),
+            Some("https://osdn.net/projects/foo/ticket/".parse().unwrap())
+        );
+        assert_eq!(
+            extract_osdn_project_name("https://osdn.net/projects/foo/releases/"),
+            Some("foo".to_string())
+        );
+        assert_eq!(
+            extract_osdn_project_name("https://foo.osdn.jp/"),
+            Some("foo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_pages_url_from_repo_url() {
+        let url = |s: &str| s.parse::<Url>().unwrap();
+        assert_eq!(
+            GitHub.pages_url_from_repo_url(&url("https://github.com/Jelmer/dulwich.git")),
+            Some(url("https://jelmer.github.io/dulwich/"))
+        );
+        assert_eq!(
+            GitHub.pages_url_from_repo_url(&url("https://github.com/jelmer/jelmer.github.io")),
+            None
+        );
+        assert_eq!(
+            GitLab.pages_url_from_repo_url(&url("https://gitlab.com/foo/sub/bar/-/tree/main")),
+            Some(url("https://foo.gitlab.io/sub/bar/"))
+        );
+        assert_eq!(
+            GitLab.pages_url_from_repo_url(&url("https://salsa.debian.org/foo/bar")),
+            None
+        );
+        let td = tempfile::tempdir().unwrap();
+        assert!(!has_documentation_sources(td.path()));
+        std::fs::write(td.path().join("mkdocs.yml"), "site_name: foo\n").unwrap();
+        assert!(has_documentation_sources(td.path()));
+    }
+
+    #[test]
+    fn test_is_translation_url() {
+        for url in [
+            "https://hosted.weblate.org/engage/dulwich/",
+            "https://hosted.weblate.org/projects/dulwich/main/",
+            "https://app.transifex.com/dulwich/dulwich/",
+            "https://crowdin.com/project/dulwich",
+        ] {
+            assert!(is_translation_url(&url.parse().unwrap()), "{}", url);
+        }
+        for url in [
+            "https://hosted.weblate.org/widgets/dulwich/-/svg-badge.svg",
+            "https://crowdin.com/",
+            "https://github.com/jelmer/dulwich",
+        ] {
+            assert!(!is_translation_url(&url.parse().unwrap()), "{}", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extend_pipeline_steps() {
+        let td = tempfile::tempdir().unwrap();
+        let mut metadata = UpstreamMetadata::new();
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Homepage("https://sourceforge.net/projects/foo/".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+
+        // Only extrapolation; the SourceForge project is not derived
+        let guesser = MetadataGuesser::builder()
+            .extend_steps(&[ExtendStep::Extrapolation])
+            .build();
+        guesser.extend(&mut metadata, td.path()).await.unwrap();
+        assert!(!metadata.contains_key("SourceForge-Project"));
+
+        let guesser = MetadataGuesser::builder()
+            .extend_steps(&[ExtendStep::SourceForgeProject])
+            .build();
+        guesser.extend(&mut metadata, td.path()).await.unwrap();
+        assert_eq!(
+            metadata
+                .get("SourceForge-Project")
+                .and_then(|d| d.datum.as_str()),
+            Some("foo")
+        );
+        assert_eq!(
+            metadata.get("Archive").and_then(|d| d.datum.as_str()),
+            Some("SourceForge")
+        );
+    }
+
+    #[test]
+    fn test_network_policy() {
+        for policy in [
+            NetworkPolicy::Offline,
+            NetworkPolicy::CacheOnly,
+            NetworkPolicy::Online,
+        ] {
+            assert_eq!(policy.to_string().parse::<NetworkPolicy>(), Ok(policy));
+        }
+        assert!(NetworkPolicy::Online.allows_network());
+        assert!(!NetworkPolicy::CacheOnly.allows_network());
+        assert!("sometimes".parse::<NetworkPolicy>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_guesser_limits() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(td.path().join("configure.ac"), "AC_INIT([foo], [1.0])\n").unwrap();
+        let guess = |guesser: MetadataGuesser| {
+            let path = td.path().to_path_buf();
+            async move { guesser.guess(&path).await.unwrap() }
+        };
+
+        let metadata = guess(MetadataGuesser::builder().build()).await;
+        assert_eq!(
+            metadata.get("Version").and_then(|d| d.datum.as_str()),
+            Some("1.0")
+        );
+
+        let metadata = guess(MetadataGuesser::builder().max_file_size(10).build()).await;
+        assert!(metadata.get("Version").is_none());
+
+        let metadata = guess(
+            MetadataGuesser::builder()
+                .deadline(std::time::Duration::ZERO)
+                .build(),
+        )
+        .await;
+        assert!(metadata.get("Version").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_check_offline() {
+        let mut metadata = UpstreamMetadata::from(vec![UpstreamDatum::Homepage(
+            "https://example.com/".to_string(),
+        )]);
+        let orig = metadata.clone();
+        let report = check_upstream_metadata_with_policy(
+            &mut metadata,
+            None,
+            &CheckPolicy {
+                network: NetworkPolicy::Offline,
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(report.is_empty());
+        assert_eq!(metadata, orig);
+    }
 }
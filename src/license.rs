@@ -0,0 +1,530 @@
+//! Normalization of license statements to SPDX expressions.
+//!
+//! Providers report licenses the way the upstream metadata states them, e.g. "GPL v2 or
+//! later" or "BSD 3-Clause". These are normalized to
+//! [SPDX license expressions](https://spdx.github.io/spdx-spec/v2.3/SPDX-license-expressions/)
+//! where that can be done unambiguously; the original text is kept alongside.
+use lazy_regex::regex;
+
+/// A license statement, as found upstream and (if possible) as an SPDX expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct License {
+    /// The license as stated upstream
+    pub raw: String,
+
+    /// The equivalent SPDX license expression, if known
+    pub spdx: Option<String>,
+}
+
+impl License {
+    /// Create a license statement that has not been normalized yet.
+    pub fn new(raw: impl Into<String>) -> Self {
+        Self {
+            raw: raw.into(),
+            spdx: None,
+        }
+    }
+
+    /// The license as stated upstream.
+    pub fn raw(&self) -> &str {
+        &self.raw
+    }
+
+    /// The equivalent SPDX license expression, if known.
+    pub fn spdx(&self) -> Option<&str> {
+        self.spdx.as_deref()
+    }
+
+    /// The SPDX expression if known, otherwise the license as stated upstream.
+    pub fn as_str(&self) -> &str {
+        self.spdx.as_deref().unwrap_or(&self.raw)
+    }
+
+    /// Determine the SPDX expression for the license, if it isn't known yet.
+    pub fn normalize(&mut self) {
+        if self.spdx.is_none() {
+            self.spdx = normalize(&self.raw);
+        }
+    }
+}
+
+impl From<String> for License {
+    fn from(raw: String) -> Self {
+        Self::new(raw)
+    }
+}
+
+impl From<&str> for License {
+    fn from(raw: &str) -> Self {
+        Self::new(raw)
+    }
+}
+
+impl std::fmt::Display for License {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// SPDX license identifiers that are recognized.
+const LICENSE_IDS: &[&str] = &[
+    "0BSD",
+    "AFL-3.0",
+    "AGPL-1.0-only",
+    "AGPL-1.0-or-later",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-1.0",
+    "Apache-1.1",
+    "Apache-2.0",
+    "APSL-2.0",
+    "Artistic-1.0",
+    "Artistic-1.0-Perl",
+    "Artistic-2.0",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "BSD-4-Clause",
+    "BSL-1.0",
+    "CC-BY-3.0",
+    "CC-BY-4.0",
+    "CC-BY-SA-3.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CDDL-1.1",
+    "CECILL-2.1",
+    "ECL-2.0",
+    "EPL-1.0",
+    "EPL-2.0",
+    "EUPL-1.1",
+    "EUPL-1.2",
+    "FTL",
+    "GFDL-1.2-only",
+    "GFDL-1.2-or-later",
+    "GFDL-1.3-only",
+    "GFDL-1.3-or-later",
+    "GPL-1.0-only",
+    "GPL-1.0-or-later",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "HPND",
+    "ISC",
+    "LGPL-2.0-only",
+    "LGPL-2.0-or-later",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "LPPL-1.3c",
+    "MIT",
+    "MIT-0",
+    "MPL-1.0",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MS-PL",
+    "MS-RL",
+    "NCSA",
+    "ODbL-1.0",
+    "OFL-1.1",
+    "OpenSSL",
+    "PHP-3.01",
+    "PostgreSQL",
+    "PSF-2.0",
+    "Python-2.0",
+    "Ruby",
+    "Unlicense",
+    "UPL-1.0",
+    "Vim",
+    "W3C",
+    "WTFPL",
+    "X11",
+    "Zlib",
+    "ZPL-2.1",
+];
+
+/// SPDX license exception identifiers that are recognized.
+const EXCEPTION_IDS: &[&str] = &[
+    "Autoconf-exception-3.0",
+    "Bison-exception-2.2",
+    "Classpath-exception-2.0",
+    "Font-exception-2.0",
+    "GCC-exception-3.1",
+    "LLVM-exception",
+    "OpenSSL-exception",
+];
+
+/// Common names of licenses, as keys produced by [`alias_key`].
+///
+/// The GNU licenses are listed without the -only/-or-later suffix, which is added
+/// depending on whether the statement allows later versions.
+const ALIASES: &[(&str, &str)] = &[
+    ("mit", "MIT"),
+    ("expat", "MIT"),
+    ("mit-x11", "MIT"),
+    ("x11", "X11"),
+    ("isc", "ISC"),
+    ("zlib", "Zlib"),
+    ("zlib-libpng", "Zlib"),
+    ("unlicense", "Unlicense"),
+    ("wtfpl", "WTFPL"),
+    ("0bsd", "0BSD"),
+    ("apache", "Apache-2.0"),
+    ("apache-2", "Apache-2.0"),
+    ("apache-2.0", "Apache-2.0"),
+    ("apache2", "Apache-2.0"),
+    ("asl-2", "Apache-2.0"),
+    ("asl-2.0", "Apache-2.0"),
+    ("apache-1.1", "Apache-1.1"),
+    ("bsd-2-clause", "BSD-2-Clause"),
+    ("bsd-2", "BSD-2-Clause"),
+    ("bsd2", "BSD-2-Clause"),
+    ("simplified-bsd", "BSD-2-Clause"),
+    ("freebsd", "BSD-2-Clause"),
+    ("bsd-3-clause", "BSD-3-Clause"),
+    ("bsd-3", "BSD-3-Clause"),
+    ("bsd3", "BSD-3-Clause"),
+    ("new-bsd", "BSD-3-Clause"),
+    ("modified-bsd", "BSD-3-Clause"),
+    ("revised-bsd", "BSD-3-Clause"),
+    ("bsd-4-clause", "BSD-4-Clause"),
+    ("bsd-4", "BSD-4-Clause"),
+    ("boost", "BSL-1.0"),
+    ("boost-1.0", "BSL-1.0"),
+    ("bsl-1.0", "BSL-1.0"),
+    ("cc0", "CC0-1.0"),
+    ("cc0-1.0", "CC0-1.0"),
+    ("public-domain-cc0", "CC0-1.0"),
+    ("mpl-1.1", "MPL-1.1"),
+    ("mpl-2", "MPL-2.0"),
+    ("mpl-2.0", "MPL-2.0"),
+    ("mpl2", "MPL-2.0"),
+    ("mozilla-2.0", "MPL-2.0"),
+    ("epl-1.0", "EPL-1.0"),
+    ("epl-2.0", "EPL-2.0"),
+    ("eclipse-2.0", "EPL-2.0"),
+    ("artistic-2", "Artistic-2.0"),
+    ("artistic-2.0", "Artistic-2.0"),
+    ("artistic-1.0", "Artistic-1.0"),
+    ("perl", "Artistic-1.0-Perl OR GPL-1.0-or-later"),
+    ("perl-5", "Artistic-1.0-Perl OR GPL-1.0-or-later"),
+    ("perl5", "Artistic-1.0-Perl OR GPL-1.0-or-later"),
+    ("python", "Python-2.0"),
+    ("psf", "PSF-2.0"),
+    ("psf-2.0", "PSF-2.0"),
+    ("ruby", "Ruby"),
+    ("php-3.01", "PHP-3.01"),
+    ("postgresql", "PostgreSQL"),
+    ("vim", "Vim"),
+    ("gpl-1", "GPL-1.0"),
+    ("gpl-1.0", "GPL-1.0"),
+    ("gpl-2", "GPL-2.0"),
+    ("gpl-2.0", "GPL-2.0"),
+    ("gpl2", "GPL-2.0"),
+    ("gpl-3", "GPL-3.0"),
+    ("gpl-3.0", "GPL-3.0"),
+    ("gpl3", "GPL-3.0"),
+    ("lgpl-2", "LGPL-2.0"),
+    ("lgpl-2.0", "LGPL-2.0"),
+    ("lgpl-2.1", "LGPL-2.1"),
+    ("lgpl2.1", "LGPL-2.1"),
+    ("lgpl-3", "LGPL-3.0"),
+    ("lgpl-3.0", "LGPL-3.0"),
+    ("lgpl3", "LGPL-3.0"),
+    ("agpl-3", "AGPL-3.0"),
+    ("agpl-3.0", "AGPL-3.0"),
+    ("agpl3", "AGPL-3.0"),
+    ("gfdl-1.2", "GFDL-1.2"),
+    ("gfdl-1.3", "GFDL-1.3"),
+];
+
+/// Look up an SPDX license or exception identifier, ignoring case.
+fn canonical_id(ids: &[&'static str], id: &str) -> Option<&'static str> {
+    ids.iter().find(|i| i.eq_ignore_ascii_case(id)).copied()
+}
+
+/// Whether an identifier refers to a license that isn't on the SPDX license list.
+fn is_license_ref(id: &str) -> bool {
+    let id = match id.split_once(':') {
+        Some((document, id)) if document.starts_with("DocumentRef-") => id,
+        Some(_) => return false,
+        None => id,
+    };
+    id.strip_prefix("LicenseRef-").is_some_and(|rest| {
+        !rest.is_empty()
+            && rest
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    })
+}
+
+/// Split an SPDX expression into identifiers, operators and parentheses.
+fn tokenize(expr: &str) -> Vec<&str> {
+    regex!(r"[()]|[^\s()]+")
+        .find_iter(expr)
+        .map(|m| m.as_str())
+        .collect()
+}
+
+/// Recursive descent parser for SPDX license expressions, which renders the expression
+/// with the canonical spelling of identifiers and operators.
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn operator(&self, op: &str) -> bool {
+        self.peek().is_some_and(|t| t.eq_ignore_ascii_case(op))
+    }
+
+    // compound := and ("OR" and)*
+    fn compound(&mut self) -> Option<String> {
+        let mut ret = self.and()?;
+        while self.operator("OR") {
+            self.pos += 1;
+            ret = format!("{} OR {}", ret, self.and()?);
+        }
+        Some(ret)
+    }
+
+    // and := term ("AND" term)*
+    fn and(&mut self) -> Option<String> {
+        let mut ret = self.term()?;
+        while self.operator("AND") {
+            self.pos += 1;
+            ret = format!("{} AND {}", ret, self.term()?);
+        }
+        Some(ret)
+    }
+
+    // term := "(" compound ")" | simple ("WITH" exception)?
+    fn term(&mut self) -> Option<String> {
+        if self.peek() == Some("(") {
+            self.pos += 1;
+            let inner = self.compound()?;
+            return (self.next() == Some(")")).then(|| format!("({})", inner));
+        }
+        let token = self.next()?;
+        let (id, plus) = match token.strip_suffix('+') {
+            Some(id) => (id, "+"),
+            None => (token, ""),
+        };
+        let mut ret = if let Some(id) = canonical_id(LICENSE_IDS, id) {
+            format!("{}{}", id, plus)
+        } else if is_license_ref(token) {
+            token.to_string()
+        } else {
+            return None;
+        };
+        if self.operator("WITH") {
+            self.pos += 1;
+            let exception = canonical_id(EXCEPTION_IDS, self.next()?)?;
+            ret = format!("{} WITH {}", ret, exception);
+        }
+        Some(ret)
+    }
+}
+
+/// Parse an SPDX license expression, returning it with canonical spelling.
+fn parse_expression(expr: &str) -> Option<String> {
+    let mut parser = Parser {
+        tokens: tokenize(expr),
+        pos: 0,
+    };
+    let ret = parser.compound()?;
+    (parser.pos == parser.tokens.len()).then_some(ret)
+}
+
+/// Check whether a string is a valid SPDX license expression.
+///
+/// Only licenses and exceptions on the SPDX lists that this module knows about, or
+/// LicenseRef- identifiers, are accepted.
+pub fn is_valid_expression(expr: &str) -> bool {
+    parse_expression(expr).is_some()
+}
+
+/// Reduce a free-text license name to a key for looking it up in [`ALIASES`].
+fn alias_key(name: &str) -> String {
+    let name = name.to_lowercase().replace("licence", "license");
+    let name = regex!(r"\baffero general public\b").replace_all(&name, "agpl");
+    let name = regex!(r"\b(?:lesser|library) general public\b").replace_all(&name, "lgpl");
+    let name = regex!(r"\bgeneral public\b").replace_all(&name, "gpl");
+    let name = regex!(r"\bfree documentation\b").replace_all(&name, "gfdl");
+    let name = regex!(r"\bmozilla public\b").replace_all(&name, "mpl");
+    // "GPLv2", "version 2" and "v2.0" all just mean version 2
+    let name = regex!(r"\bv(\d)").replace_all(&name, " $1");
+    let name = regex!(r"([a-z])v(\d)").replace_all(&name, "$1-$2");
+    name.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .filter(|w| !w.is_empty() && !["the", "gnu", "license", "version", "clause"].contains(w))
+        .collect::<Vec<_>>()
+        .join("-")
+        .replace("-only", "")
+}
+
+/// Normalize the name of a single license, e.g. "GNU GPLv2 or later".
+fn normalize_single(name: &str) -> Option<String> {
+    let name = name.trim().trim_end_matches('.');
+    if let Some(id) = parse_expression(name) {
+        return Some(id);
+    }
+    let (name, later) = if let Some(c) =
+        regex!(r"(?i)^(.*?)\s*(?:\+|,?\s*or\s+(?:any\s+)?later(?:\s+version)?|-or-later)$")
+            .captures(name)
+    {
+        (c[1].to_string(), true)
+    } else if let Some(c) = regex!(r"^(.*?)\s*\(>=\s*([\d.]+)\)$").captures(name) {
+        // R style, e.g. "GPL (>= 3)"
+        (format!("{} {}", &c[1], &c[2]), true)
+    } else {
+        (name.to_string(), false)
+    };
+    let mut key = alias_key(&name);
+    // e.g. BSD-3-Clause is reduced to "bsd-3" by alias_key
+    if key.starts_with("bsd-") && !key.contains('.') {
+        key = key.trim_end_matches("-clause").to_string();
+    }
+    let (_, id) = ALIASES.iter().find(|(alias, _)| *alias == key)?;
+    if ["GPL-", "LGPL-", "AGPL-", "GFDL-"]
+        .iter()
+        .any(|prefix| id.starts_with(prefix))
+    {
+        Some(format!(
+            "{}-{}",
+            id,
+            if later { "or-later" } else { "only" }
+        ))
+    } else if later {
+        // The "+" operator only applies to a single license
+        (!id.contains(' ')).then(|| format!("{}+", id))
+    } else {
+        Some(id.to_string())
+    }
+}
+
+/// Normalize a free-text license statement to an SPDX license expression.
+///
+/// Returns `None` if the statement can not be mapped unambiguously, e.g. for "BSD" or
+/// "GPL" without a version.
+pub fn normalize(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    if let Some(expr) = normalize_single(text) {
+        return Some(expr);
+    }
+    // Statements that list several licenses, e.g. "GPL-2 | GPL-3". Comma-separated lists are
+    // left alone, since they are as often meant as a conjunction as as a choice.
+    for (separator, operator) in [
+        (regex!(r"(?i)\s+or\s+|\s*\|\s*"), "OR"),
+        (regex!(r"(?i)\s+(?:and|&)\s+"), "AND"),
+    ] {
+        let parts = separator.split(text).collect::<Vec<_>>();
+        if parts.len() < 2 {
+            continue;
+        }
+        let parts = parts
+            .into_iter()
+            .map(|part| {
+                normalize_single(part).map(|expr| {
+                    if expr.contains(' ') {
+                        format!("({})", expr)
+                    } else {
+                        expr
+                    }
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        return Some(parts.join(&format!(" {} ", operator)));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_expression() {
+        assert!(is_valid_expression("MIT"));
+        assert!(is_valid_expression("MIT OR Apache-2.0"));
+        assert!(is_valid_expression(
+            "(GPL-2.0-or-later WITH Classpath-exception-2.0) AND LicenseRef-foo"
+        ));
+        assert!(is_valid_expression("Apache-2.0+"));
+        assert!(!is_valid_expression("GPL"));
+        assert!(!is_valid_expression("MIT OR"));
+        assert!(!is_valid_expression("(MIT"));
+        assert!(!is_valid_expression("MIT WITH MIT"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("mit").as_deref(), Some("MIT"));
+        assert_eq!(
+            normalize("mit or apache-2.0").as_deref(),
+            Some("MIT OR Apache-2.0")
+        );
+        assert_eq!(
+            normalize("GPL v2 or later").as_deref(),
+            Some("GPL-2.0-or-later")
+        );
+        assert_eq!(
+            normalize("GNU GPLv2 or later").as_deref(),
+            Some("GPL-2.0-or-later")
+        );
+        assert_eq!(normalize("GPL-2+").as_deref(), Some("GPL-2.0-or-later"));
+        assert_eq!(normalize("GPL-3").as_deref(), Some("GPL-3.0-only"));
+        assert_eq!(normalize("GPL (>= 3)").as_deref(), Some("GPL-3.0-or-later"));
+        assert_eq!(
+            normalize("GNU Lesser General Public License v2.1").as_deref(),
+            Some("LGPL-2.1-only")
+        );
+        assert_eq!(
+            normalize("The Apache License, Version 2.0").as_deref(),
+            Some("Apache-2.0")
+        );
+        assert_eq!(normalize("BSD 2-clause").as_deref(), Some("BSD-2-Clause"));
+        assert_eq!(normalize("BSD3").as_deref(), Some("BSD-3-Clause"));
+        assert_eq!(
+            normalize("perl_5").as_deref(),
+            Some("Artistic-1.0-Perl OR GPL-1.0-or-later")
+        );
+        assert_eq!(
+            normalize("GPL-2 | GPL-3").as_deref(),
+            Some("GPL-2.0-only OR GPL-3.0-only")
+        );
+        assert_eq!(
+            normalize("perl_5 or MIT").as_deref(),
+            Some("(Artistic-1.0-Perl OR GPL-1.0-or-later) OR MIT")
+        );
+        assert_eq!(normalize("perl_5, MIT"), None);
+        assert_eq!(normalize("BSD"), None);
+        assert_eq!(normalize("GPL"), None);
+        assert_eq!(normalize("MIT + file LICENSE"), None);
+        assert_eq!(normalize(""), None);
+    }
+
+    #[test]
+    fn test_license() {
+        let mut license = License::from("GPL v2 or later");
+        assert_eq!(license.as_str(), "GPL v2 or later");
+        license.normalize();
+        assert_eq!(license.raw(), "GPL v2 or later");
+        assert_eq!(license.spdx(), Some("GPL-2.0-or-later"));
+        assert_eq!(license.to_string(), "GPL-2.0-or-later");
+    }
+}
@@ -0,0 +1,273 @@
+//! Finding the latest release in a directory listing of release tarballs.
+//!
+//! This is similar to what uscan does for directory-style watch files: the listing is
+//! scraped for links to `<name>-<version>.<ext>` tarballs, and the one with the highest
+//! version wins. Besides the GNU and Savannah download directories, this works for any
+//! download page that is an HTML index of tarballs.
+use crate::version::compare_versions;
+use crate::ProviderError;
+use select::document::Document;
+use select::predicate::Name;
+use std::cmp::Ordering;
+use url::Url;
+
+/// A release tarball found in a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version of the release, e.g. "2.12"
+    pub version: String,
+
+    /// URL of the tarball
+    pub url: Url,
+}
+
+/// Find the download directory that a GNU or Savannah download URL points at.
+///
+/// Returns the URL of the directory and the name of the package, e.g.
+/// `https://ftp.gnu.org/gnu/hello/` and "hello" for `ftp://ftp.gnu.org/gnu/hello`.
+pub fn gnu_download_directory(url: &Url) -> Option<(Url, String)> {
+    let segments = url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    let (host, package) = match (url.host_str()?, segments.as_slice()) {
+        ("ftp.gnu.org" | "ftpmirror.gnu.org", ["gnu", package, ..]) => ("ftp.gnu.org", package),
+        (
+            host @ ("download.savannah.gnu.org" | "download.savannah.nongnu.org"),
+            ["releases", package, ..],
+        ) => (host, package),
+        _ => return None,
+    };
+    // Directory URLs may lack the trailing slash, but files have an extension
+    if segments.last()?.contains('.') {
+        return None;
+    }
+    let directory = Url::parse(&format!("https://{}/{}/", host, segments.join("/"))).ok()?;
+    Some((directory, package.to_string()))
+}
+
+/// Extensions of release tarballs, in order of preference.
+const TARBALL_EXTENSIONS: &[&str] = &[
+    ".tar.gz", ".tar.xz", ".tar.bz2", ".tar.lz", ".tar.zst", ".tgz", ".zip",
+];
+
+/// Split the file name of a tarball into the name of the package and the version.
+fn split_tarball_name(filename: &str) -> Option<(&str, &str)> {
+    let stem = TARBALL_EXTENSIONS
+        .iter()
+        .find_map(|ext| filename.strip_suffix(ext))?;
+    // The version is the part after the first dash that is followed by a digit, so that
+    // package names can contain dashes
+    let (i, _) = stem
+        .match_indices('-')
+        .find(|(i, _)| stem[i + 1..].starts_with(|c: char| c.is_ascii_digit()))?;
+    Some((&stem[..i], &stem[i + 1..]))
+}
+
+/// Find the tarballs of a package in the HTML of a directory listing.
+///
+/// The name of the package is matched case-insensitively. If it is not known, the
+/// tarballs of the package that has the most tarballs in the listing are returned.
+pub fn parse_listing(html: &str, base: &Url, name: Option<&str>) -> Vec<Release> {
+    let mut found: Vec<(String, Release)> = vec![];
+    for href in Document::from(html)
+        .find(Name("a"))
+        .filter_map(|a| a.attr("href"))
+    {
+        let Ok(url) = base.join(href) else {
+            continue;
+        };
+        let Some(filename) = url.path_segments().and_then(|mut s| s.next_back()) else {
+            continue;
+        };
+        let Some((package, version)) = split_tarball_name(filename) else {
+            continue;
+        };
+        if name.is_some_and(|name| !name.eq_ignore_ascii_case(package)) {
+            continue;
+        }
+        let release = Release {
+            version: version.to_string(),
+            url: url.clone(),
+        };
+        found.push((package.to_string(), release));
+    }
+    let name = match name {
+        Some(name) => name.to_string(),
+        None => {
+            let mut counts: Vec<(&str, usize)> = vec![];
+            for (package, _) in &found {
+                match counts.iter_mut().find(|(p, _)| p == package) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((package, 1)),
+                }
+            }
+            match counts
+                .into_iter()
+                .reduce(|a, b| if b.1 > a.1 { b } else { a })
+            {
+                Some((package, _)) => package.to_string(),
+                None => return vec![],
+            }
+        }
+    };
+    found
+        .into_iter()
+        .filter(|(package, _)| package.eq_ignore_ascii_case(&name))
+        .map(|(_, release)| release)
+        .collect()
+}
+
+/// Pick the release with the highest version.
+///
+/// If there are several tarballs for that version, the one with the preferred extension
+/// is used.
+pub fn latest_release(releases: Vec<Release>) -> Option<Release> {
+    let rank = |release: &Release| {
+        TARBALL_EXTENSIONS
+            .iter()
+            .position(|ext| release.url.path().ends_with(ext))
+    };
+    releases.into_iter().reduce(|best, release| {
+        match compare_versions(&release.version, &best.version) {
+            Ordering::Greater => release,
+            Ordering::Equal if rank(&release) < rank(&best) => release,
+            _ => best,
+        }
+    })
+}
+
+/// Fetch a directory listing and find the latest release of a package in it.
+///
+/// Returns `None` if the URL does not point at an HTML page, or if there are no
+/// tarballs in it.
+pub async fn scan_directory(
+    directory: &Url,
+    name: Option<&str>,
+) -> Result<Option<Release>, ProviderError> {
+    let client = crate::http::build_client().build()?;
+    let response = crate::http::send(client.get(directory.clone())).await?;
+    if !response.status().is_success() {
+        log::debug!("Unable to list {}: {}", directory, response.status());
+        return Ok(None);
+    }
+    let is_html = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    if !is_html {
+        return Ok(None);
+    }
+    // Relative links are relative to the URL after redirects, e.g. to add a trailing slash
+    let base = response.url().clone();
+    let body = response.text().await?;
+    Ok(latest_release(parse_listing(&body, &base, name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gnu_download_directory() {
+        assert_eq!(
+            gnu_download_directory(&"ftp://ftp.gnu.org/gnu/hello".parse().unwrap()),
+            Some((
+                "https://ftp.gnu.org/gnu/hello/".parse().unwrap(),
+                "hello".to_string()
+            ))
+        );
+        assert_eq!(
+            gnu_download_directory(
+                &"https://download.savannah.gnu.org/releases/acl/"
+                    .parse()
+                    .unwrap()
+            ),
+            Some((
+                "https://download.savannah.gnu.org/releases/acl/"
+                    .parse()
+                    .unwrap(),
+                "acl".to_string()
+            ))
+        );
+        assert_eq!(
+            gnu_download_directory(
+                &"https://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz"
+                    .parse()
+                    .unwrap()
+            ),
+            None
+        );
+        assert_eq!(
+            gnu_download_directory(&"https://example.com/gnu/hello/".parse().unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_latest_release() {
+        let html = r#"<html><body><pre>
+<a href="../">Parent Directory</a>
+<a href="hello-2.9.tar.gz">hello-2.9.tar.gz</a>
+<a href="hello-2.9.tar.gz.sig">hello-2.9.tar.gz.sig</a>
+<a href="hello-2.10.tar.gz">hello-2.10.tar.gz</a>
+<a href="hello-2.12.tar.gz">hello-2.12.tar.gz</a>
+<a href="hello-2.12.tar.xz">hello-2.12.tar.xz</a>
+<a href="hello-latest.tar.gz">hello-latest.tar.gz</a>
+<a href="hello-world-1.0.tar.gz">hello-world-1.0.tar.gz</a>
+</pre></body></html>"#;
+        let base: Url = "https://ftp.gnu.org/gnu/hello/".parse().unwrap();
+        let releases = parse_listing(html, &base, Some("hello"));
+        assert_eq!(
+            releases
+                .iter()
+                .map(|r| r.version.as_str())
+                .collect::<Vec<_>>(),
+            vec!["2.9", "2.10", "2.12", "2.12"]
+        );
+        assert_eq!(
+            latest_release(releases),
+            Some(Release {
+                version: "2.12".to_string(),
+                url: "https://ftp.gnu.org/gnu/hello/hello-2.12.tar.gz"
+                    .parse()
+                    .unwrap(),
+            })
+        );
+        assert_eq!(latest_release(vec![]), None);
+    }
+
+    #[test]
+    fn test_parse_listing_without_name() {
+        let html = r#"<ul>
+<li><a href="/downloads/">Up</a></li>
+<li><a href="foo-bar-0.9.tar.gz">foo-bar-0.9.tar.gz</a></li>
+<li><a href="foo-bar-1.0.tar.bz2">foo-bar-1.0.tar.bz2</a></li>
+<li><a href="https://example.com/other/foo-bar-1.0rc1.zip">foo-bar-1.0rc1.zip</a></li>
+<li><a href="libfoo-3.0.tar.gz">libfoo-3.0.tar.gz</a></li>
+<li><a href="changes-1.0.txt">changes-1.0.txt</a></li>
+</ul>"#;
+        let base: Url = "https://example.com/downloads/foo/".parse().unwrap();
+        let releases = parse_listing(html, &base, None);
+        assert_eq!(
+            releases.iter().map(|r| r.url.as_str()).collect::<Vec<_>>(),
+            vec![
+                "https://example.com/downloads/foo/foo-bar-0.9.tar.gz",
+                "https://example.com/downloads/foo/foo-bar-1.0.tar.bz2",
+                "https://example.com/other/foo-bar-1.0rc1.zip",
+            ]
+        );
+        assert_eq!(
+            latest_release(releases).map(|r| r.version),
+            Some("1.0".to_string())
+        );
+        assert_eq!(
+            parse_listing(html, &base, Some("libfoo"))
+                .into_iter()
+                .map(|r| r.version)
+                .collect::<Vec<_>>(),
+            vec!["3.0"]
+        );
+    }
+}
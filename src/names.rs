@@ -0,0 +1,318 @@
+//! Ecosystem-specific naming conventions.
+//!
+//! The upstream Name of a project is not always the identifier that a package registry uses
+//! for it; e.g. PyPI normalizes names according to PEP 503 and CPAN distributions use dashes
+//! where the module name has double colons.
+
+/// A packaging ecosystem with its own rules for project names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// Rust crates on crates.io
+    Cargo,
+    /// Python packages on PyPI
+    PyPI,
+    /// JavaScript packages on npm
+    Npm,
+    /// Ruby gems on rubygems.org
+    RubyGems,
+    /// Haskell packages on Hackage
+    Hackage,
+    /// Perl distributions on CPAN
+    Cpan,
+    /// PHP extensions on PECL
+    Pecl,
+    /// Debian source packages
+    Debian,
+}
+
+impl std::str::FromStr for Ecosystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cargo" | "crates.io" => Ok(Ecosystem::Cargo),
+            "pypi" | "python" => Ok(Ecosystem::PyPI),
+            "npm" => Ok(Ecosystem::Npm),
+            "rubygems" | "gem" => Ok(Ecosystem::RubyGems),
+            "hackage" => Ok(Ecosystem::Hackage),
+            "cpan" => Ok(Ecosystem::Cpan),
+            "pecl" => Ok(Ecosystem::Pecl),
+            "debian" => Ok(Ecosystem::Debian),
+            _ => Err(format!("unknown ecosystem: {}", s)),
+        }
+    }
+}
+
+impl Ecosystem {
+    /// The field that holds the ecosystem-specific name, if there is one.
+    pub fn name_field(&self) -> Option<&'static str> {
+        match self {
+            Ecosystem::Cargo => Some("Cargo-Crate"),
+            Ecosystem::Hackage => Some("Haskell-Package"),
+            Ecosystem::Pecl => Some("Pecl-Package"),
+            _ => None,
+        }
+    }
+
+    /// Normalize a name according to the rules of this ecosystem.
+    pub fn normalize_name(&self, name: &str) -> String {
+        let name = name.trim();
+        match self {
+            // crates.io treats dashes and underscores as equivalent, and ignores case
+            Ecosystem::Cargo => name.to_lowercase().replace('_', "-"),
+            // PEP 503: runs of -, _ and . are equivalent
+            Ecosystem::PyPI => {
+                let mut ret = String::with_capacity(name.len());
+                for c in name.chars() {
+                    if matches!(c, '-' | '_' | '.') {
+                        if !ret.ends_with('-') {
+                            ret.push('-');
+                        }
+                    } else {
+                        ret.extend(c.to_lowercase());
+                    }
+                }
+                ret
+            }
+            // npm names are lowercase; scopes ("@scope/name") are kept
+            Ecosystem::Npm => name.to_lowercase().replace(' ', "-"),
+            // Gem and Hackage names are case sensitive
+            Ecosystem::RubyGems | Ecosystem::Hackage => name.replace(' ', "-"),
+            Ecosystem::Cpan => name.replace("::", "-"),
+            Ecosystem::Pecl => name.to_lowercase(),
+            // Debian policy 5.6.1: lowercase letters, digits, plus, minus and periods
+            Ecosystem::Debian => name
+                .to_lowercase()
+                .chars()
+                .map(|c| match c {
+                    '_' | ' ' | ':' => '-',
+                    c => c,
+                })
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                .collect::<String>()
+                .replace("--", "-"),
+        }
+    }
+}
+
+/// Prefixes that distributions and language packagers add to upstream project names.
+///
+/// Longer prefixes come first, so that e.g. "python3-" wins over "python-".
+const PACKAGING_PREFIXES: &[&str] = &[
+    "python3-", "python-", "py3-", "perl-", "ruby-", "node-", "golang-", "rust-", "r-cran-",
+    "r-bioc-", "haskell-", "php-", "lua-", "ocaml-", "elpa-", "emacs-",
+];
+
+/// Strip a well-known packaging prefix (or Debian's "lib...-perl" wrapping) from a name.
+///
+/// Returns None if the name does not look like a packaging name.
+pub fn strip_packaging_prefix(name: &str) -> Option<String> {
+    // Debian names Perl modules lib<dist>-perl
+    if let Some(dist) = name
+        .strip_prefix("lib")
+        .and_then(|n| n.strip_suffix("-perl"))
+    {
+        if !dist.is_empty() {
+            return Some(dist.to_string());
+        }
+    }
+    PACKAGING_PREFIXES
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .filter(|stripped| !stripped.is_empty())
+        .map(|stripped| stripped.to_string())
+}
+
+/// Strip well-known packaging prefixes from the Name.
+///
+/// The original name is recorded in Aliases, so that it can still be used to look the project
+/// up in registries that use the prefixed name.
+pub fn strip_name_prefixes(metadata: &mut crate::UpstreamMetadata) {
+    let name = match metadata.get_mut("Name") {
+        Some(name) => name,
+        None => return,
+    };
+    let original = match name.datum.as_str() {
+        Some(original) => original.to_string(),
+        None => return,
+    };
+    let stripped = match strip_packaging_prefix(&original) {
+        Some(stripped) => stripped,
+        None => return,
+    };
+    log::debug!("Stripping packaging prefix from name {}", original);
+    name.datum = crate::UpstreamDatum::Name(stripped);
+    let certainty = name.certainty;
+    let origin = name.origin.clone();
+
+    match metadata.get_mut("Aliases") {
+        Some(crate::UpstreamDatumWithMetadata {
+            datum: crate::UpstreamDatum::Aliases(aliases),
+            ..
+        }) => {
+            if !aliases.contains(&original) {
+                aliases.push(original);
+            }
+        }
+        _ => metadata.insert(crate::UpstreamDatumWithMetadata {
+            datum: crate::UpstreamDatum::Aliases(vec![original]),
+            certainty,
+            origin,
+        }),
+    }
+}
+
+/// Alternative names to try when looking a project up under `name` fails.
+///
+/// This includes underscore/dash variants, the lowercased name, the name with packaging
+/// prefixes stripped, any recorded Aliases and the last component of the Go import path.
+/// `name` itself is not included.
+pub fn name_candidates(name: &str, metadata: &[crate::UpstreamDatumWithMetadata]) -> Vec<String> {
+    let mut candidates = vec![
+        name.replace('_', "-"),
+        name.replace('-', "_"),
+        name.to_lowercase(),
+    ];
+    candidates.extend(strip_packaging_prefix(name));
+    for item in metadata {
+        match &item.datum {
+            crate::UpstreamDatum::Aliases(aliases) => candidates.extend(aliases.iter().cloned()),
+            crate::UpstreamDatum::GoImportPath(path) => candidates.extend(
+                path.trim_end_matches('/')
+                    .rsplit('/')
+                    .next()
+                    .map(String::from),
+            ),
+            _ => {}
+        }
+    }
+    let mut ret: Vec<String> = vec![];
+    for candidate in candidates {
+        if candidate != name && !candidate.is_empty() && !ret.contains(&candidate) {
+            ret.push(candidate);
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(Ecosystem::Cargo.normalize_name("Serde_JSON"), "serde-json");
+        assert_eq!(
+            Ecosystem::PyPI.normalize_name("Zope.Interface__x"),
+            "zope-interface-x"
+        );
+        assert_eq!(Ecosystem::Npm.normalize_name("@Types/Node"), "@types/node");
+        assert_eq!(
+            Ecosystem::RubyGems.normalize_name("ActiveSupport"),
+            "ActiveSupport"
+        );
+        assert_eq!(Ecosystem::Cpan.normalize_name("Foo::Bar"), "Foo-Bar");
+        assert_eq!(
+            Ecosystem::Debian.normalize_name("Foo::Bar_baz"),
+            "foo-bar-baz"
+        );
+    }
+
+    #[test]
+    fn test_name_for() {
+        use crate::{Certainty, UpstreamDatum, UpstreamDatumWithMetadata, UpstreamMetadata};
+        let mut metadata = UpstreamMetadata::new();
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("Foo_Bar".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+        assert_eq!(
+            metadata.name_for(Ecosystem::PyPI),
+            Some("foo-bar".to_string())
+        );
+        assert_eq!(
+            metadata.name_for(Ecosystem::Cargo),
+            Some("foo-bar".to_string())
+        );
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::CargoCrate("foo_bar_rs".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+        assert_eq!(
+            metadata.name_for(Ecosystem::Cargo),
+            Some("foo-bar-rs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_strip_packaging_prefix() {
+        assert_eq!(
+            strip_packaging_prefix("python3-dulwich"),
+            Some("dulwich".to_string())
+        );
+        assert_eq!(
+            strip_packaging_prefix("r-cran-ggplot2"),
+            Some("ggplot2".to_string())
+        );
+        assert_eq!(
+            strip_packaging_prefix("libfoo-bar-perl"),
+            Some("foo-bar".to_string())
+        );
+        assert_eq!(strip_packaging_prefix("dulwich"), None);
+        assert_eq!(strip_packaging_prefix("python-"), None);
+    }
+
+    #[test]
+    fn test_strip_name_prefixes() {
+        use crate::{Certainty, UpstreamDatum, UpstreamDatumWithMetadata, UpstreamMetadata};
+        let mut metadata = UpstreamMetadata::new();
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("python-dateutil".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+        strip_name_prefixes(&mut metadata);
+        assert_eq!(metadata.name(), Some("dateutil"));
+        assert_eq!(
+            metadata.aliases(),
+            Some(&vec!["python-dateutil".to_string()])
+        );
+
+        // Nothing to strip
+        strip_name_prefixes(&mut metadata);
+        assert_eq!(metadata.name(), Some("dateutil"));
+        assert_eq!(metadata.aliases().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_name_candidates() {
+        use crate::{UpstreamDatum, UpstreamDatumWithMetadata};
+        assert_eq!(name_candidates("foo", &[]), Vec::<String>::new());
+        assert_eq!(
+            name_candidates("Foo_Bar", &[]),
+            vec!["Foo-Bar".to_string(), "foo_bar".to_string()]
+        );
+        let metadata = [
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Aliases(vec!["python-foo".to_string()]),
+                certainty: None,
+                origin: None,
+            },
+            UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::GoImportPath("github.com/example/go-foo".to_string()),
+                certainty: None,
+                origin: None,
+            },
+        ];
+        assert_eq!(
+            name_candidates("python-foo", &metadata),
+            vec![
+                "python_foo".to_string(),
+                "foo".to_string(),
+                "go-foo".to_string()
+            ]
+        );
+    }
+}
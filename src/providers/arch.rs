@@ -1,7 +1,11 @@
-use crate::{vcs, UpstreamDatum, USER_AGENT};
+use crate::{
+    vcs, Certainty, GuesserSettings, Origin, ProviderError, UpstreamDatum,
+    UpstreamDatumWithMetadata, USER_AGENT,
+};
 use log::{debug, error};
 use std::collections::HashMap;
 use std::io::BufRead;
+use std::path::Path;
 
 pub fn parse_pkgbuild_variables(file: &str) -> HashMap<String, Vec<String>> {
     let reader = std::io::Cursor::new(file);
@@ -12,28 +16,24 @@ pub fn parse_pkgbuild_variables(file: &str) -> HashMap<String, Vec<String>> {
 
     for line in reader.lines() {
         let line = line.expect("Failed to read line");
-        if let Some(existing_line) = existing.take() {
-            let line = [&existing_line[..existing_line.len() - 2], &line].concat();
-            existing = Some(line);
-            continue;
-        }
+        let line = match existing.take() {
+            Some(existing_line) => existing_line + &line,
+            None => line,
+        };
 
-        if line.ends_with("\\\n") {
-            existing = Some(line[..line.len() - 2].to_owned());
-            continue;
-        }
-
-        if line.starts_with('\t') || line.starts_with(' ') || line.starts_with('#') {
+        if let Some(line) = line.strip_suffix('\\') {
+            existing = Some(line.to_owned());
             continue;
         }
 
         if let Some((key, mut value)) = keep.take() {
+            value.push(' ');
             value.push_str(&line);
-            if line.trim_end().ends_with(')') {
-                let value_parts = match shlex::split(value.as_str()) {
+            if let Some(value) = value.trim_end().strip_suffix(')') {
+                let value_parts = match shlex::split(value) {
                     Some(value_parts) => value_parts,
                     None => {
-                        error!("Failed to split value: {}", value.as_str());
+                        error!("Failed to split value: {}", value);
                         continue;
                     }
                 };
@@ -44,6 +44,10 @@ pub fn parse_pkgbuild_variables(file: &str) -> HashMap<String, Vec<String>> {
             continue;
         }
 
+        if line.starts_with('\t') || line.starts_with(' ') || line.starts_with('#') {
+            continue;
+        }
+
         if let Some((key, value)) = line.split_once('=') {
             if let Some(value) = value.strip_prefix('(') {
                 if value.trim_end().ends_with(')') {
@@ -75,6 +79,127 @@ pub fn parse_pkgbuild_variables(file: &str) -> HashMap<String, Vec<String>> {
     variables
 }
 
+/// Substitute references to other PKGBUILD variables in a value.
+///
+/// Only plain references (`$name` and `${name}`) are expanded; nothing is ever executed.
+fn expand_pkgbuild_variables(value: &str, variables: &HashMap<String, Vec<String>>) -> String {
+    let mut value = value.to_owned();
+    if !value.contains('$') {
+        return value;
+    }
+    // Substitute longer names first, so that e.g. $pkgname doesn't clobber $pkgname_short
+    let mut names = variables.keys().collect::<Vec<_>>();
+    names.sort_by_key(|k| std::cmp::Reverse(k.len()));
+    for k in names {
+        let v = variables[k].join(" ");
+        value = value.replace(format!("${{{}}}", k).as_str(), v.as_str());
+        value = value.replace(format!("${}", k).as_str(), v.as_str());
+    }
+    value
+}
+
+/// Return the value of a PKGBUILD variable, with references to other variables expanded.
+///
+/// Returns None if the value can not be determined without running the PKGBUILD, e.g.
+/// because it uses command substitution or parameter expansion.
+fn pkgbuild_value(value: &str, variables: &HashMap<String, Vec<String>>) -> Option<String> {
+    let value = expand_pkgbuild_variables(value, variables);
+    if value.contains('$') || value.contains('`') {
+        debug!("Unable to expand PKGBUILD value: {}", value);
+        return None;
+    }
+    Some(value)
+}
+
+/// Strip the suffix that marks an Arch package as building from a VCS checkout, if any.
+fn strip_vcs_package_suffix(name: &str) -> Option<&str> {
+    vcs::VCSES
+        .iter()
+        .find_map(|vcs| name.strip_suffix(format!("-{}", vcs).as_str()))
+}
+
+pub fn guess_from_pkgbuild(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let variables = parse_pkgbuild_variables(&text);
+    let origin = Some(Origin::Path(path.into()));
+
+    let mut results = Vec::new();
+    let mut push = |datum| {
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(Certainty::Likely),
+            origin: origin.clone(),
+        })
+    };
+
+    let get = |key: &str| -> Option<Vec<String>> {
+        variables.get(key).map(|values| {
+            values
+                .iter()
+                .filter_map(|v| pkgbuild_value(v, &variables))
+                .collect()
+        })
+    };
+
+    // Split packages list all their packages in pkgname, but share pkgbase
+    let pkgname = get("pkgbase")
+        .or_else(|| get("pkgname"))
+        .and_then(|v| v.into_iter().next());
+    let is_vcs_package = pkgname
+        .as_deref()
+        .and_then(strip_vcs_package_suffix)
+        .is_some();
+
+    if let Some(pkgname) = pkgname.as_deref() {
+        push(UpstreamDatum::Name(
+            strip_vcs_package_suffix(pkgname)
+                .unwrap_or(pkgname)
+                .to_owned(),
+        ));
+    }
+
+    // The pkgver of VCS packages is generated from the checkout, and not an upstream release
+    if !is_vcs_package {
+        if let Some(pkgver) = get("pkgver").and_then(|v| v.into_iter().next()) {
+            push(UpstreamDatum::Version(pkgver));
+        }
+    }
+
+    if let Some(pkgdesc) = get("pkgdesc").and_then(|v| v.into_iter().next()) {
+        push(UpstreamDatum::Summary(pkgdesc));
+    }
+
+    if let Some(url) = get("url").and_then(|v| v.into_iter().next()) {
+        push(UpstreamDatum::Homepage(url));
+    }
+
+    if let Some(licenses) = get("license") {
+        if !licenses.is_empty() {
+            push(UpstreamDatum::License(licenses.join(" AND ").into()));
+        }
+    }
+
+    for source in get("source").unwrap_or_default() {
+        let url = match source.split_once("::") {
+            Some((_unique_name, url)) => url,
+            None => source.as_str(),
+        };
+        let stripped = vcs::strip_vcs_prefixes(url);
+        if stripped != url {
+            // Drop fragments like #branch=main or #tag=v1.0
+            let stripped = stripped.split_once('#').map_or(stripped, |(u, _)| u);
+            push(UpstreamDatum::Repository(stripped.to_owned()));
+        } else if url.contains("://") {
+            push(UpstreamDatum::Download(url.to_owned()));
+        }
+    }
+
+    Ok(results)
+}
+
 pub async fn guess_from_aur(package: &str) -> Vec<UpstreamDatum> {
     let mut variables = HashMap::new();
 
@@ -91,7 +216,7 @@ pub async fn guess_from_aur(package: &str) -> Vec<UpstreamDatum> {
             .unwrap();
 
         debug!("Requesting {}", url);
-        let response = client.get(&url).send().await;
+        let response = crate::http::send(client.get(&url)).await;
 
         match response {
             Ok(response) => {
@@ -126,13 +251,7 @@ pub async fn guess_from_aur(package: &str) -> Vec<UpstreamDatum> {
                 if value.is_empty() {
                     continue;
                 }
-                let mut value = value[0].to_owned();
-                if value.contains("${") {
-                    for (k, v) in variables.iter() {
-                        value = value.replace(format!("${{{}}}", k).as_str(), v.join(" ").as_str());
-                        value = value.replace(format!("${}", k).as_str(), v.join(" ").as_str());
-                    }
-                }
+                let value = expand_pkgbuild_variables(&value[0], &variables);
                 let url = match value.split_once("::") {
                     Some((_unique_name, url)) => url,
                     None => value.as_str(),
@@ -188,3 +307,40 @@ impl crate::ThirdPartyRepository for Aur {
         Ok(guess_from_aur(name).await)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_from_pkgbuild() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("PKGBUILD");
+        std::fs::write(
+            &path,
+            r#"_pkgname=foo
+pkgname=foo-git
+pkgver=r12.abcdef
+pkgdesc='Foo, but from git'
+url="https://example.com/$_pkgname"
+license=('GPL-3.0-or-later'
+         'MIT')
+source=("git+https://example.com/git/${_pkgname}.git#branch=main"
+        "https://example.com/$_pkgname-$(date).patch"
+        'foo.desktop')
+"#,
+        )
+        .unwrap();
+        let results = guess_from_pkgbuild(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|r| r.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("foo".to_string()),
+                UpstreamDatum::Summary("Foo, but from git".to_string()),
+                UpstreamDatum::Homepage("https://example.com/foo".to_string()),
+                UpstreamDatum::License("GPL-3.0-or-later AND MIT".into()),
+                UpstreamDatum::Repository("https://example.com/git/foo.git".to_string()),
+            ]
+        );
+    }
+}
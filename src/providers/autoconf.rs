@@ -16,6 +16,32 @@ fn is_email_address(email: &str) -> bool {
     false
 }
 
+fn bug_report_certainty(value: &str) -> Option<Certainty> {
+    if value == "BUG-REPORT-ADDRESS" {
+        None
+    } else if is_email_address(value) {
+        // Downgrade the trustworthiness of this field for most
+        // upstreams if it contains an e-mail address. Most
+        // upstreams seem to just set this to some random address,
+        // and then forget about it.
+        Some(Certainty::Possible)
+    } else if value.contains("mailing list") {
+        // Downgrade the trustworthiness of this field if
+        // it contains a mailing list
+        Some(Certainty::Possible)
+    } else {
+        match Url::parse(value) {
+            Ok(parsed_url) if parsed_url.path().trim_end_matches('/') != "" => {
+                Some(Certainty::Certain)
+            }
+            // It seems unlikely that the bug submit URL lives at
+            // the root.
+            Ok(_) => Some(Certainty::Possible),
+            Err(_) => None,
+        }
+    }
+}
+
 pub fn guess_from_configure(
     path: &std::path::Path,
     _settings: &GuesserSettings,
@@ -79,28 +105,7 @@ pub fn guess_from_configure(
                 });
             }
             "PACKAGE_BUGREPORT" => {
-                let certainty = if value == "BUG-REPORT-ADDRESS" {
-                    None
-                } else if is_email_address(value) {
-                    // Downgrade the trustworthiness of this field for most
-                    // upstreams if it contains an e-mail address. Most
-                    // upstreams seem to just set this to some random address,
-                    // and then forget about it.
-                    Some(Certainty::Possible)
-                } else if value.contains("mailing list") {
-                    // Downgrade the trustworthiness of this field if
-                    // it contains a mailing list
-                    Some(Certainty::Possible)
-                } else {
-                    let parsed_url = Url::parse(value).expect("Failed to parse URL");
-                    if parsed_url.path().trim_end_matches('/') != "" {
-                        Some(Certainty::Certain)
-                    } else {
-                        // It seems unlikely that the bug submit URL lives at
-                        // the root.
-                        Some(Certainty::Possible)
-                    }
-                };
+                let certainty = bug_report_certainty(value);
 
                 if certainty.is_some() {
                     results.push(UpstreamDatumWithMetadata {
@@ -124,3 +129,285 @@ pub fn guess_from_configure(
     }
     Ok(results)
 }
+
+/// Split the arguments of an m4 macro invocation.
+///
+/// `text` should start just after the opening parenthesis. Returns the
+/// (unquoted, trimmed) arguments, or None if the closing parenthesis could
+/// not be found.
+fn split_m4_args(text: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote_depth = 0;
+    let mut paren_depth = 0;
+    for c in text.chars() {
+        match c {
+            '[' => {
+                if quote_depth > 0 {
+                    current.push(c);
+                }
+                quote_depth += 1;
+            }
+            ']' if quote_depth > 0 => {
+                quote_depth -= 1;
+                if quote_depth > 0 {
+                    current.push(c);
+                }
+            }
+            '(' if quote_depth == 0 => {
+                paren_depth += 1;
+                current.push(c);
+            }
+            ')' if quote_depth == 0 && paren_depth > 0 => {
+                paren_depth -= 1;
+                current.push(c);
+            }
+            ')' if quote_depth == 0 => {
+                args.push(current.trim().to_string());
+                return Some(args);
+            }
+            ',' if quote_depth == 0 && paren_depth == 0 => {
+                args.push(current.trim().to_string());
+                current = String::new();
+            }
+            '\n' if quote_depth == 0 => {
+                current.push(' ');
+            }
+            _ => current.push(c),
+        }
+    }
+    None
+}
+
+/// Find all invocations of the m4 macro `name` in `text`.
+fn find_m4_macro_calls(text: &str, name: &str) -> Vec<Vec<String>> {
+    let mut ret = Vec::new();
+    let needle = format!("{}(", name);
+    let mut offset = 0;
+    while let Some(idx) = text[offset..].find(&needle) {
+        let start = offset + idx;
+        offset = start + needle.len();
+        // Make sure we matched the whole macro name
+        if text[..start]
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_')
+        {
+            continue;
+        }
+        // Skip commented out lines
+        let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+        let prefix = text[line_start..start].trim_start();
+        if prefix.starts_with('#') || prefix.starts_with("dnl") {
+            continue;
+        }
+        if let Some(args) = split_m4_args(&text[offset..]) {
+            ret.push(args);
+        }
+    }
+    ret
+}
+
+fn is_literal(value: &str) -> bool {
+    !value.is_empty() && !value.contains('$') && !value.contains('(') && !value.contains('`')
+}
+
+fn strip_shell_quotes(value: &str) -> &str {
+    let value = value.trim();
+    for q in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(q) && value.ends_with(q) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Guess upstream metadata from an autoconf input file (`configure.ac` or `configure.in`).
+///
+/// This parses the `AC_INIT` macro as well as explicit definitions of `PACKAGE_URL` and
+/// `PACKAGE_BUGREPORT`, so that metadata is available before autoreconf has been run.
+pub fn guess_from_configure_ac(
+    path: &std::path::Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+
+    let mut results = Vec::new();
+
+    let mut push = |datum: UpstreamDatum, certainty: Option<Certainty>| {
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty,
+            origin: Some(path.into()),
+        });
+    };
+
+    let push_bug_report = |value: &str, push: &mut dyn FnMut(UpstreamDatum, Option<Certainty>)| {
+        if let Some(certainty) = bug_report_certainty(value) {
+            push(UpstreamDatum::BugSubmit(value.to_string()), Some(certainty));
+        }
+    };
+
+    if let Some(args) = find_m4_macro_calls(&text, "AC_INIT").into_iter().next() {
+        let mut args = args.into_iter();
+        if let Some(name) = args.next().filter(|v| is_literal(v)) {
+            push(UpstreamDatum::Name(name), Some(Certainty::Certain));
+        }
+        if let Some(version) = args.next().filter(|v| is_literal(v)) {
+            push(UpstreamDatum::Version(version), Some(Certainty::Certain));
+        }
+        if let Some(bug_report) = args.next().filter(|v| is_literal(v)) {
+            push_bug_report(&bug_report, &mut push);
+        }
+        if let Some(tarname) = args.next().filter(|v| is_literal(v)) {
+            push(UpstreamDatum::Name(tarname), Some(Certainty::Likely));
+        }
+        if let Some(url) = args.next().filter(|v| is_literal(v)) {
+            push(UpstreamDatum::Homepage(url), Some(Certainty::Certain));
+        }
+    }
+
+    for macro_name in ["AC_DEFINE", "AC_DEFINE_UNQUOTED", "AC_SUBST"] {
+        for args in find_m4_macro_calls(&text, macro_name) {
+            let (key, value) = match args.as_slice() {
+                [key, value, ..] => (key.as_str(), strip_shell_quotes(value)),
+                _ => continue,
+            };
+            if !is_literal(value) {
+                continue;
+            }
+            match key {
+                "PACKAGE_URL" => push(
+                    UpstreamDatum::Homepage(value.to_string()),
+                    Some(Certainty::Certain),
+                ),
+                "PACKAGE_BUGREPORT" => push_bug_report(value, &mut push),
+                _ => {}
+            }
+        }
+    }
+
+    for macro_name in ["AC_PACKAGE_URL", "AC_PACKAGE_BUGREPORT"] {
+        for args in find_m4_macro_calls(&text, macro_name) {
+            let value = match args.first() {
+                Some(value) if is_literal(value) => value.as_str(),
+                _ => continue,
+            };
+            if macro_name == "AC_PACKAGE_URL" {
+                push(
+                    UpstreamDatum::Homepage(value.to_string()),
+                    Some(Certainty::Certain),
+                );
+            } else {
+                push_bug_report(value, &mut push);
+            }
+        }
+    }
+
+    // Plain shell assignments, e.g. PACKAGE_URL="https://example.com/"
+    for line in text.lines() {
+        let (key, value) = match line.trim().split_once('=') {
+            Some((key, value)) => (key.trim(), strip_shell_quotes(value)),
+            None => continue,
+        };
+        if !is_literal(value) {
+            continue;
+        }
+        match key {
+            "PACKAGE_URL" => push(
+                UpstreamDatum::Homepage(value.to_string()),
+                Some(Certainty::Likely),
+            ),
+            "PACKAGE_BUGREPORT" => push_bug_report(value, &mut push),
+            _ => {}
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_m4_args() {
+        assert_eq!(
+            split_m4_args("[GNU Hello], [2.12], [bug-hello@gnu.org])\n"),
+            Some(vec![
+                "GNU Hello".to_string(),
+                "2.12".to_string(),
+                "bug-hello@gnu.org".to_string()
+            ])
+        );
+        assert_eq!(
+            split_m4_args("foo, m4_esyscmd([echo 1.0]))"),
+            Some(vec!["foo".to_string(), "m4_esyscmd(echo 1.0)".to_string()])
+        );
+        assert_eq!(split_m4_args("[foo], [bar"), None);
+    }
+
+    #[test]
+    fn test_guess_from_configure_ac() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("configure.ac");
+        std::fs::write(
+            &path,
+            r#"dnl AC_INIT([ignored], [0.0])
+AC_INIT([GNU Hello], [2.12],
+        [https://savannah.gnu.org/bugs/?group=hello], [hello],
+        [https://www.gnu.org/software/hello/])
+AC_CONFIG_SRCDIR([src/hello.c])
+AM_INIT_AUTOMAKE
+"#,
+        )
+        .unwrap();
+        let results = guess_from_configure_ac(&path, &GuesserSettings::default()).unwrap();
+        let data = results.into_iter().map(|d| d.datum).collect::<Vec<_>>();
+        assert_eq!(
+            data,
+            vec![
+                UpstreamDatum::Name("GNU Hello".to_string()),
+                UpstreamDatum::Version("2.12".to_string()),
+                UpstreamDatum::BugSubmit("https://savannah.gnu.org/bugs/?group=hello".to_string()),
+                UpstreamDatum::Name("hello".to_string()),
+                UpstreamDatum::Homepage("https://www.gnu.org/software/hello/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guess_from_configure_ac_defines() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("configure.in");
+        std::fs::write(
+            &path,
+            r#"AC_INIT(foo, m4_esyscmd([build-aux/git-version-gen .tarball-version]))
+AC_DEFINE([PACKAGE_URL], ["https://example.com/foo"], [Project homepage])
+PACKAGE_BUGREPORT="foo-bugs@example.com"
+"#,
+        )
+        .unwrap();
+        let results = guess_from_configure_ac(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|d| (d.datum, d.certainty))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    UpstreamDatum::Name("foo".to_string()),
+                    Some(Certainty::Certain)
+                ),
+                (
+                    UpstreamDatum::Homepage("https://example.com/foo".to_string()),
+                    Some(Certainty::Certain)
+                ),
+                (
+                    UpstreamDatum::BugSubmit("foo-bugs@example.com".to_string()),
+                    Some(Certainty::Possible)
+                ),
+            ]
+        );
+    }
+}
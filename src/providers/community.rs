@@ -0,0 +1,53 @@
+//! Community health files, such as the code of conduct and contribution guidelines.
+//!
+//! See <https://docs.github.com/en/communities/setting-up-your-project-for-healthy-contributions>
+
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+
+/// Locations of the code of conduct, in order of preference.
+pub const CODE_OF_CONDUCT_FILENAMES: &[&str] = &[
+    "CODE_OF_CONDUCT.md",
+    "CODE_OF_CONDUCT.rst",
+    "CODE_OF_CONDUCT.txt",
+    "CODE_OF_CONDUCT",
+    ".github/CODE_OF_CONDUCT.md",
+    "docs/CODE_OF_CONDUCT.md",
+    "docs/CODE_OF_CONDUCT.rst",
+];
+
+/// Locations of the contribution guidelines, in order of preference.
+pub const CONTRIBUTING_FILENAMES: &[&str] = &[
+    "CONTRIBUTING.md",
+    "CONTRIBUTING.rst",
+    "CONTRIBUTING.txt",
+    "CONTRIBUTING",
+    ".github/CONTRIBUTING.md",
+    "docs/CONTRIBUTING.md",
+    "docs/CONTRIBUTING.rst",
+];
+
+pub fn guess_from_code_of_conduct(
+    name: &str,
+    path: &std::path::Path,
+    _settings: &GuesserSettings,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    Ok(vec![UpstreamDatumWithMetadata {
+        datum: UpstreamDatum::CodeOfConduct(name.to_string()),
+        certainty: Some(Certainty::Certain),
+        origin: Some(path.into()),
+    }])
+}
+
+pub fn guess_from_contributing(
+    name: &str,
+    path: &std::path::Path,
+    _settings: &GuesserSettings,
+) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    Ok(vec![UpstreamDatumWithMetadata {
+        datum: UpstreamDatum::Contributing(name.to_string()),
+        certainty: Some(Certainty::Certain),
+        origin: Some(path.into()),
+    }])
+}
@@ -47,7 +47,7 @@ pub fn guess_from_composer_json(
             }
             "license" => {
                 upstream_data.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(value.as_str().unwrap().to_string()),
+                    datum: UpstreamDatum::License(value.as_str().unwrap().to_string().into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 });
@@ -90,3 +90,204 @@ pub fn guess_from_composer_json(
 
     Ok(upstream_data)
 }
+
+#[derive(serde::Deserialize)]
+pub struct PackagistSource {
+    pub url: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct PackagistFunding {
+    pub url: Option<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+pub struct PackagistSupport {
+    pub issues: Option<String>,
+    pub source: Option<String>,
+    pub docs: Option<String>,
+    pub wiki: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct PackagistVersion {
+    pub name: String,
+    pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub version: Option<String>,
+    #[serde(default)]
+    pub license: Vec<String>,
+    pub source: Option<PackagistSource>,
+    #[serde(default)]
+    pub support: PackagistSupport,
+    #[serde(default)]
+    pub funding: Vec<PackagistFunding>,
+}
+
+#[derive(serde::Deserialize)]
+pub struct PackagistPackages {
+    // Versions are listed newest first; later entries only contain changed keys
+    pub packages: std::collections::HashMap<String, Vec<serde_json::Value>>,
+}
+
+impl PackagistPackages {
+    /// Return the latest version of a package.
+    pub fn latest(&mut self, package: &str) -> Result<Option<PackagistVersion>, serde_json::Error> {
+        match self
+            .packages
+            .remove(package)
+            .and_then(|versions| versions.into_iter().next())
+        {
+            Some(latest) => serde_json::from_value(latest).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+fn parse_packagist_version(package: &PackagistVersion) -> Vec<UpstreamDatum> {
+    let mut results = vec![UpstreamDatum::Name(package.name.clone())];
+
+    if let Some(description) = package.description.as_ref().filter(|d| !d.is_empty()) {
+        results.push(UpstreamDatum::Summary(description.clone()));
+    }
+
+    if let Some(homepage) = package.homepage.as_ref().filter(|h| !h.is_empty()) {
+        results.push(UpstreamDatum::Homepage(homepage.clone()));
+    }
+
+    if let Some(version) = package.version.as_ref() {
+        results.push(UpstreamDatum::Version(
+            version.strip_prefix('v').unwrap_or(version).to_string(),
+        ));
+    }
+
+    if !package.license.is_empty() {
+        results.push(UpstreamDatum::License(package.license.join(" OR ").into()));
+    }
+
+    if let Some(url) = package.source.as_ref().and_then(|s| s.url.as_ref()) {
+        results.push(UpstreamDatum::Repository(url.clone()));
+    }
+
+    if let Some(source) = package.support.source.as_ref() {
+        // Packagist points at the tree of the released version, e.g. .../tree/3.8.1
+        let source = lazy_regex::regex_captures!(r"^(.+?)(?:/-)?/tree/[^/]+/?$", source)
+            .map_or(source.as_str(), |(_, browse)| browse);
+        results.push(UpstreamDatum::RepositoryBrowse(source.to_string()));
+    }
+
+    if let Some(issues) = package.support.issues.as_ref() {
+        results.push(UpstreamDatum::BugDatabase(issues.clone()));
+    }
+
+    if let Some(docs) = package.support.docs.as_ref() {
+        results.push(UpstreamDatum::Documentation(docs.clone()));
+    }
+
+    if let Some(wiki) = package.support.wiki.as_ref() {
+        results.push(UpstreamDatum::Wiki(wiki.clone()));
+    }
+
+    if let Some(funding) = package.funding.iter().find_map(|f| f.url.as_ref()) {
+        results.push(UpstreamDatum::Funding(funding.clone()));
+    }
+
+    results
+}
+
+pub async fn guess_from_packagist(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!("https://repo.packagist.org/p2/{}.json", package)
+        .parse()
+        .unwrap();
+    let data = match crate::load_json_url(&url, None).await {
+        Ok(data) => data,
+        Err(crate::HTTPJSONError::Error { status: 404, .. }) => {
+            log::warn!("Package {} not found on Packagist", package);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut packages: PackagistPackages = serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid Packagist data: {}", e)))?;
+    match packages
+        .latest(package)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid Packagist data: {}", e)))?
+    {
+        Some(latest) => Ok(parse_packagist_version(&latest)),
+        None => Ok(Vec::new()),
+    }
+}
+
+pub struct Packagist;
+
+impl Default for Packagist {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Packagist {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Packagist {
+    fn name(&self) -> &'static str {
+        "Packagist"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Name",
+            "Summary",
+            "Homepage",
+            "Version",
+            "License",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "Documentation",
+            "Wiki",
+            "Funding",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_packagist(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_packagist_version() {
+        let data = include_str!("../testdata/packagist.json");
+        let mut packages: PackagistPackages = serde_json::from_str(data).unwrap();
+        let latest = packages.latest("monolog/monolog").unwrap().unwrap();
+        assert_eq!(
+            parse_packagist_version(&latest),
+            vec![
+                UpstreamDatum::Name("monolog/monolog".to_string()),
+                UpstreamDatum::Summary(
+                    "Sends your logs to files, sockets, inboxes, databases and various web services"
+                        .to_string()
+                ),
+                UpstreamDatum::Homepage("https://github.com/Seldaek/monolog".to_string()),
+                UpstreamDatum::Version("3.8.1".to_string()),
+                UpstreamDatum::License("MIT".into()),
+                UpstreamDatum::Repository("https://github.com/Seldaek/monolog.git".to_string()),
+                UpstreamDatum::RepositoryBrowse("https://github.com/Seldaek/monolog".to_string()),
+                UpstreamDatum::BugDatabase("https://github.com/Seldaek/monolog/issues".to_string()),
+                UpstreamDatum::Funding("https://github.com/Seldaek".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Conda recipes, described by a meta.yaml file.
+//!
+//! Recipes are Jinja templates that render to YAML; rather than implementing Jinja, simple
+//! `{% set %}` variables are substituted and any other expressions are dropped.
+//!
+//! See <https://docs.conda.io/projects/conda-build/en/stable/resources/define-metadata.html>
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(serde::Deserialize, Default)]
+struct Package {
+    name: Option<serde_yaml::Value>,
+    version: Option<serde_yaml::Value>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct About {
+    home: Option<String>,
+    license: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    dev_url: Option<String>,
+    doc_url: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct Recipe {
+    #[serde(default)]
+    package: Package,
+    #[serde(default)]
+    about: About,
+}
+
+/// Render the Jinja bits of a recipe well enough that it parses as YAML.
+fn render_template(text: &str) -> String {
+    let variables: HashMap<&str, &str> =
+        lazy_regex::regex!(r#"\{%-?\s*set\s+(\w+)\s*=\s*["']([^"']*)["']\s*-?%\}"#)
+            .captures_iter(text)
+            .map(|c| {
+                let (_, [name, value]) = c.extract();
+                (name, value)
+            })
+            .collect();
+
+    let text = lazy_regex::regex_replace_all!(r"\{%.*?%\}", text, "");
+    lazy_regex::regex_replace_all!(r"\{\{(.*?)\}\}", &text, |_, expr: &str| {
+        let mut parts = expr.split('|').map(str::trim);
+        let value = match parts.next().and_then(|name| variables.get(name)) {
+            Some(value) => value.to_string(),
+            None => return String::new(),
+        };
+        parts.fold(value, |value, filter| match filter {
+            "lower" => value.to_lowercase(),
+            "upper" => value.to_uppercase(),
+            _ => value,
+        })
+    })
+    .into_owned()
+}
+
+/// Convert a scalar YAML value to a string, ignoring empty values.
+fn scalar_to_string(value: serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        serde_yaml::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+pub fn guess_from_conda_meta_yaml(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let recipe: Recipe = serde_yaml::from_str(&render_template(&text))
+        .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    let mut upstream_data: Vec<UpstreamDatumWithMetadata> = Vec::new();
+
+    if let Some(name) = recipe.package.name.and_then(scalar_to_string) {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name(name),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+    if let Some(version) = recipe.package.version.and_then(scalar_to_string) {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Version(version),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+
+    let about = recipe.about;
+    let fields = [
+        (
+            about.home,
+            UpstreamDatum::Homepage as fn(String) -> UpstreamDatum,
+        ),
+        (about.license, |l| UpstreamDatum::License(l.into())),
+        (about.summary, UpstreamDatum::Summary),
+        (about.description, UpstreamDatum::Description),
+        (about.dev_url, UpstreamDatum::Repository),
+        (about.doc_url, UpstreamDatum::Documentation),
+    ];
+    for (value, datum) in fields {
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            upstream_data.push(UpstreamDatumWithMetadata {
+                datum: datum(value.trim().to_string()),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+        }
+    }
+
+    Ok(upstream_data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        assert_eq!(
+            render_template(
+                "{% set name = \"Foo\" %}\npackage:\n  name: {{ name|lower }}\n  sha: {{ hash }}\n"
+            ),
+            "\npackage:\n  name: foo\n  sha: \n"
+        );
+    }
+
+    #[test]
+    fn test_guess_from_conda_meta_yaml() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("meta.yaml");
+        std::fs::write(
+            &path,
+            r#"{% set version = "2.1.0" %}
+
+package:
+  name: foo
+  version: {{ version }}
+
+source:
+  url: https://pypi.io/packages/source/f/foo/foo-{{ version }}.tar.gz
+  sha256: {{ sha256 }}
+
+requirements:
+  host:
+    - python >=3.8  # [not win]
+
+about:
+  home: https://example.com/foo
+  license: BSD-3-Clause
+  summary: Frobnicates the bar
+  dev_url: https://example.com/foo.git
+  doc_url:
+"#,
+        )
+        .unwrap();
+        let ret = guess_from_conda_meta_yaml(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("foo".to_string()),
+                UpstreamDatum::Version("2.1.0".to_string()),
+                UpstreamDatum::Homepage("https://example.com/foo".to_string()),
+                UpstreamDatum::License("BSD-3-Clause".into()),
+                UpstreamDatum::Summary("Frobnicates the bar".to_string()),
+                UpstreamDatum::Repository("https://example.com/foo.git".to_string()),
+            ]
+        );
+    }
+}
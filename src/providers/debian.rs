@@ -1,23 +1,24 @@
 use crate::{
-    bug_database_from_issue_url, repo_url_from_merge_request_url, Certainty, GuesserSettings,
-    Origin, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
+    bug_database_from_issue_url, repo_url_from_merge_request_url, Certainty, DatumValue,
+    GuesserSettings, Origin, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
 };
 use lazy_regex::regex_captures;
 use log::debug;
 use std::fs::File;
 use std::io::BufRead;
+#[cfg(feature = "debian")]
 use std::io::Read;
 use std::path::Path;
 use url::Url;
 
 pub async fn guess_from_debian_patch(
     path: &Path,
-    _settings: &GuesserSettings,
+    settings: &GuesserSettings,
 ) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
     let file = File::open(path)?;
     let reader = std::io::BufReader::new(file);
 
-    let net_access = None;
+    let net_access = Some(settings.network.allows_network());
 
     let mut upstream_data: Vec<UpstreamDatumWithMetadata> = Vec::new();
 
@@ -138,7 +139,7 @@ pub fn metadata_from_itp_bug_body(
                     }
                     "License" => {
                         results.push(UpstreamDatumWithMetadata {
-                            datum: UpstreamDatum::License(value.to_string()),
+                            datum: UpstreamDatum::License(value.to_string().into()),
                             certainty: Some(Certainty::Confident),
                             origin: origin.clone(),
                         });
@@ -180,6 +181,36 @@ pub fn metadata_from_itp_bug_body(
     Ok(results)
 }
 
+#[test]
+fn test_is_packaging_vcs_url() {
+    assert!(is_packaging_vcs_url(
+        "https://salsa.debian.org/python-team/packages/dulwich.git"
+    ));
+    assert!(is_packaging_vcs_url(
+        "git@salsa.debian.org:jelmer/dulwich.git"
+    ));
+    assert!(is_packaging_vcs_url(
+        "https://github.com/jelmer/dulwich-debian"
+    ));
+    assert!(is_packaging_vcs_url("https://github.com/debian/dulwich"));
+    assert!(!is_packaging_vcs_url(
+        "https://salsa.debian.org/upstream/dulwich.git"
+    ));
+    assert!(!is_packaging_vcs_url(
+        "https://github.com/jelmer/dulwich.git"
+    ));
+    assert!(is_packaging_vcs_url(
+        "https://git.launchpad.net/ubuntu/+source/dulwich"
+    ));
+    assert!(is_packaging_vcs_url(
+        "https://git.launchpad.net/~ubuntu-core-dev/ubuntu-seeds/+git/ubuntu"
+    ));
+    assert!(!is_packaging_vcs_url("https://git.launchpad.net/dulwich"));
+    assert!(!is_packaging_vcs_url(
+        "https://git.launchpad.net/~jelmer/dulwich/+git/dulwich"
+    ));
+}
+
 #[test]
 fn test_metadata_from_itp_bug_body() {
     assert_eq!(
@@ -205,7 +236,7 @@ fn test_metadata_from_itp_bug_body() {
                 origin: None,
             },
             UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License("GPL".to_string()),
+                datum: UpstreamDatum::License("GPL".into()),
                 certainty: Some(Certainty::Confident),
                 origin: None,
             },
@@ -273,7 +304,7 @@ fn read_changelog_first_entry(
 #[cfg(feature = "debian")]
 pub async fn guess_from_debian_changelog(
     path: &Path,
-    _settings: &GuesserSettings,
+    settings: &GuesserSettings,
 ) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
     let (package, version, change_lines) = read_changelog_first_entry(path)?;
 
@@ -346,7 +377,9 @@ pub async fn guess_from_debian_changelog(
             origin: Some(path.into()),
         });
 
-        ret.extend(guess_from_itp_bug(itp)?);
+        if settings.network.allows_network() {
+            ret.extend(guess_from_itp_bug(itp)?);
+        }
     }
 
     Ok(ret)
@@ -401,7 +434,7 @@ pub fn parse_debcargo_source_name(
 #[cfg(feature = "debian")]
 pub fn guess_from_debian_rules(
     path: &Path,
-    _settings: &GuesserSettings,
+    settings: &GuesserSettings,
 ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
     let f = std::fs::File::open(path)?;
     let mf = makefile_lossless::Makefile::read_relaxed(f)
@@ -435,9 +468,70 @@ pub fn guess_from_debian_rules(
         });
     }
 
+    // Recipes like get-orig-source often fetch the upstream sources
+    ret.extend(crate::providers::shell::guess_from_makefile(
+        path, settings,
+    )?);
+
     Ok(ret)
 }
 
+/// Hosts that are used for Debian and Ubuntu packaging repositories.
+const PACKAGING_VCS_HOSTS: &[&str] = &[
+    "salsa.debian.org",
+    "anonscm.debian.org",
+    "git.debian.org",
+    "alioth.debian.org",
+];
+
+/// Launchpad hosts, which host both upstream and Ubuntu packaging repositories.
+const LAUNCHPAD_VCS_HOSTS: &[&str] = &["git.launchpad.net", "code.launchpad.net"];
+
+/// Check whether a Vcs-Git or Vcs-Browser URL refers to a packaging repository.
+///
+/// Repositories on the Debian packaging hosts are considered packaging repositories, unless they
+/// live in an "upstream" namespace. On Launchpad, only repositories for Ubuntu source packages
+/// ("+source") or owned by Ubuntu teams ("~ubuntu-*") are considered packaging repositories.
+/// Elsewhere, repositories with names like "foo-debian" or "pkg-foo" or in a "debian" namespace
+/// are considered packaging repositories.
+pub fn is_packaging_vcs_url(url: &str) -> bool {
+    let url = match Url::parse(url) {
+        Ok(url) => url,
+        // rcp-style URLs like "git@salsa.debian.org:foo/bar.git"
+        Err(_) => match url
+            .split_once(':')
+            .and_then(|(host, path)| Url::parse(&format!("ssh://{}/{}", host, path)).ok())
+        {
+            Some(url) => url,
+            None => return false,
+        },
+    };
+    let segments = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if segments.contains(&"upstream") {
+        return false;
+    }
+    if url
+        .host_str()
+        .is_some_and(|h| PACKAGING_VCS_HOSTS.contains(&h))
+    {
+        return true;
+    }
+    if url
+        .host_str()
+        .is_some_and(|h| LAUNCHPAD_VCS_HOSTS.contains(&h))
+    {
+        return segments.contains(&"+source")
+            || segments.first().is_some_and(|s| s.starts_with("~ubuntu-"));
+    }
+    segments.iter().any(|s| {
+        let s = s.strip_suffix(".git").unwrap_or(s);
+        s == "debian" || s.ends_with("-debian") || s.starts_with("pkg-")
+    })
+}
+
 #[cfg(feature = "debian")]
 pub fn guess_from_debian_control(
     path: &Path,
@@ -495,6 +589,32 @@ pub fn guess_from_debian_control(
         }
     }
 
+    if is_native == Some(false) {
+        // The Vcs-* fields usually refer to the packaging repository, but sometimes packaging
+        // is done in a branch of the upstream repository.
+        if let Some(vcs_git) = source.vcs_git() {
+            // Strip the branch and subdirectory, e.g. "https://example.com/foo -b debian/main"
+            let url = vcs_git.split_whitespace().next().unwrap_or_default();
+            if !url.is_empty() && !is_packaging_vcs_url(url) {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Repository(url.to_string()),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+        }
+
+        if let Some(vcs_browser) = source.vcs_browser() {
+            if !is_packaging_vcs_url(&vcs_browser) {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::RepositoryBrowse(vcs_browser),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+        }
+    }
+
     let binaries = control.binaries().collect::<Vec<_>>();
 
     let certainty = if binaries.len() == 1 && is_native == Some(true) {
@@ -629,7 +749,9 @@ pub async fn guess_from_debian_copyright(
                 .collect::<std::collections::HashSet<_>>();
             if referenced_licenses.len() == 1 {
                 ret.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(referenced_licenses.into_iter().next().unwrap()),
+                    datum: UpstreamDatum::License(
+                        referenced_licenses.into_iter().next().unwrap().into(),
+                    ),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 });
@@ -781,6 +903,144 @@ pub fn debian_is_native(path: &Path) -> std::io::Result<Option<bool>> {
     Ok(None)
 }
 
+/// Convert a value from a DEP-12 file into a [`DatumValue`].
+fn dep12_value(value: serde_yaml::Value) -> Option<DatumValue> {
+    use serde_yaml::Value;
+    let person = |value: Value| match value {
+        Value::Tagged(tagged) => serde_yaml::from_value::<Person>(tagged.value).ok(),
+        Value::Mapping(..) => serde_yaml::from_value::<Person>(value).ok(),
+        _ => None,
+    };
+    match value {
+        Value::String(s) => Some(DatumValue::String(s)),
+        Value::Number(n) => match n.as_i64().and_then(|i| i32::try_from(i).ok()) {
+            Some(i) => Some(DatumValue::Integer(i)),
+            None => Some(DatumValue::String(n.to_string())),
+        },
+        Value::Sequence(items) => {
+            if items.iter().all(|i| i.is_string()) {
+                return Some(DatumValue::Strings(
+                    items
+                        .into_iter()
+                        .filter_map(|i| i.as_str().map(|s| s.to_string()))
+                        .collect(),
+                ));
+            }
+            // Registry entries are mappings with a Name and an Entry
+            let pairs = items
+                .iter()
+                .map(|i| {
+                    Some((
+                        i.get("Name")?.as_str()?.to_string(),
+                        i.get("Entry")?.as_str()?.to_string(),
+                    ))
+                })
+                .collect::<Option<Vec<_>>>();
+            if let Some(pairs) = pairs {
+                return Some(DatumValue::Pairs(pairs));
+            }
+            items
+                .into_iter()
+                .map(|i| match i {
+                    Value::String(s) => Some(Person::from(s.as_str())),
+                    i => person(i),
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(DatumValue::People)
+        }
+        value @ (Value::Tagged(..) | Value::Mapping(..)) => person(value).map(DatumValue::Person),
+        Value::Null | Value::Bool(..) => None,
+    }
+}
+
+/// Read an existing debian/upstream/metadata (DEP-12) file.
+///
+/// The fields in it have been curated by the packager, so they are taken to be certain;
+/// this makes it possible to only fill in the fields that are missing.
+pub fn guess_from_debian_upstream_metadata(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let file = File::open(path)?;
+    let data: serde_yaml::Mapping =
+        serde_yaml::from_reader(file).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+    let mut ret = vec![];
+    for (key, value) in data {
+        let Some(field) = key.as_str() else {
+            continue;
+        };
+        let Some(value) = dep12_value(value) else {
+            debug!("Ignoring empty or unsupported value for {}", field);
+            continue;
+        };
+        match UpstreamDatum::from_field_values(field, value) {
+            Ok(data) => ret.extend(data.into_iter().map(|datum| UpstreamDatumWithMetadata {
+                datum,
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            })),
+            Err(e) => debug!("Ignoring field in {}: {}", path.display(), e),
+        }
+    }
+    Ok(ret)
+}
+
+#[cfg(feature = "debian")]
+#[test]
+fn test_guess_from_debian_rules() {
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("rules");
+    std::fs::write(
+        &path,
+        "#!/usr/bin/make -f\n\nDEB_UPSTREAM_GIT = https://github.com/example/foo\n\n%:\n\tdh $@\n\nget-orig-source:\n\twget https://example.com/dl/foo-1.2.tar.gz\n",
+    )
+    .unwrap();
+    let guessed = guess_from_debian_rules(&path, &GuesserSettings::default()).unwrap();
+    assert_eq!(
+        guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+        vec![
+            UpstreamDatum::Repository("https://github.com/example/foo".to_string()),
+            UpstreamDatum::Version("1.2".to_string()),
+            UpstreamDatum::Download("https://example.com/dl/foo-1.2.tar.gz".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_guess_from_debian_upstream_metadata() {
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("metadata");
+    std::fs::write(
+        &path,
+        r#"---
+Bug-Database: https://github.com/jelmer/dulwich/issues
+Contact: Jelmer Vernooij <jelmer@jelmer.uk>
+Registry:
+- Name: PyPI
+  Entry: dulwich
+Repository: https://github.com/jelmer/dulwich.git
+Reference:
+  Title: A reference
+Debian-ITP: 12345
+"#,
+    )
+    .unwrap();
+    let guessed = guess_from_debian_upstream_metadata(&path, &GuesserSettings::default()).unwrap();
+    assert!(guessed
+        .iter()
+        .all(|d| d.certainty == Some(Certainty::Certain)));
+    assert_eq!(
+        guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+        vec![
+            UpstreamDatum::BugDatabase("https://github.com/jelmer/dulwich/issues".to_string()),
+            UpstreamDatum::Contact("Jelmer Vernooij <jelmer@jelmer.uk>".to_string()),
+            UpstreamDatum::Registry(vec![("PyPI".to_string(), "dulwich".to_string())]),
+            UpstreamDatum::Repository("https://github.com/jelmer/dulwich.git".to_string()),
+            UpstreamDatum::DebianITP(12345),
+        ]
+    );
+}
+
 #[cfg(test)]
 mod watch_tests {
     use super::*;
@@ -827,3 +1087,30 @@ https://github.com/jelmer/dulwich/tags/dulwich-(.*).tar.gz
         );
     }
 }
+
+#[test]
+fn test_guess_from_debian_upstream_metadata_multi_valued() {
+    let metadata = crate::UpstreamMetadata::from_data(
+        [
+            UpstreamDatum::Donation("https://example.com/donate".to_string()),
+            UpstreamDatum::Donation("https://liberapay.com/example".to_string()),
+            UpstreamDatum::RepositoryMirror("https://example.com/mirror.git".to_string()),
+            UpstreamDatum::Chat("ircs://irc.libera.chat/example".to_string()),
+        ]
+        .into_iter()
+        .map(|datum| UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        })
+        .collect(),
+    );
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("metadata");
+    std::fs::write(&path, serde_yaml::to_string(&metadata).unwrap()).unwrap();
+    let guessed = guess_from_debian_upstream_metadata(&path, &GuesserSettings::default()).unwrap();
+    assert_eq!(
+        guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+        metadata.iter().map(|d| d.datum.clone()).collect::<Vec<_>>()
+    );
+}
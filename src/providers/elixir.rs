@@ -0,0 +1,303 @@
+//! Support for Elixir projects (mix.exs) and the Hex package registry.
+//!
+//! Documentation: https://hex.pm/docs/publish
+
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Find the value of a string keyword in a keyword list, e.g. `version: "1.0.0"`.
+fn keyword_string(text: &str, key: &str) -> Option<String> {
+    let re = regex::Regex::new(&format!(r#"(?m)(?:^|[\s\[,]){}:\s*"([^"]*)""#, key)).unwrap();
+    re.captures(text)
+        .map(|c| c.get(1).unwrap().as_str().to_string())
+}
+
+/// Find the value of a module attribute, e.g. `@version "1.0.0"`.
+fn module_attribute(text: &str, name: &str) -> Option<String> {
+    let re = regex::Regex::new(&format!(r#"(?m)^\s*@{}\s+"([^"]*)""#, name)).unwrap();
+    re.captures(text)
+        .map(|c| c.get(1).unwrap().as_str().to_string())
+}
+
+/// Find the value of a keyword that is either a string or a module attribute reference.
+fn keyword_value(text: &str, key: &str) -> Option<String> {
+    if let Some(value) = keyword_string(text, key) {
+        return Some(value);
+    }
+    let re = regex::Regex::new(&format!(r"(?m)(?:^|[\s\[,]){}:\s*@(\w+)", key)).unwrap();
+    let attribute = re.captures(text)?.get(1)?.as_str().to_string();
+    module_attribute(text, &attribute)
+}
+
+pub fn guess_from_mix_exs(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    // Strip comments
+    let text =
+        lazy_regex::regex!(r##"(?m)^((?:[^"#\n]|"[^"\n]*")*)#.*$"##).replace_all(&text, "$1");
+
+    let mut results = Vec::new();
+    let mut push = |datum, certainty| {
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        });
+    };
+
+    // The package name defaults to the application name
+    let package_name = keyword_value(&text, "name").filter(|n| !n.contains(' '));
+    if let Some(name) = package_name {
+        push(UpstreamDatum::Name(name), Certainty::Certain);
+    } else if let Some((_, app)) = lazy_regex::regex_captures!(r"\bapp:\s*:(\w+)", &text) {
+        push(UpstreamDatum::Name(app.to_string()), Certainty::Certain);
+    }
+
+    if let Some(version) = keyword_value(&text, "version") {
+        push(UpstreamDatum::Version(version), Certainty::Certain);
+    }
+
+    if let Some(description) = keyword_value(&text, "description") {
+        push(UpstreamDatum::Summary(description), Certainty::Certain);
+    }
+
+    if let Some(homepage) = keyword_value(&text, "homepage_url") {
+        push(UpstreamDatum::Homepage(homepage), Certainty::Certain);
+    }
+
+    if let Some(source_url) = keyword_value(&text, "source_url") {
+        push(UpstreamDatum::Repository(source_url), Certainty::Likely);
+    }
+
+    if let Some((_, licenses)) = lazy_regex::regex_captures!(r"\blicenses:\s*\[([^\]]*)\]", &text) {
+        let licenses = lazy_regex::regex!(r#""([^"]+)""#)
+            .captures_iter(licenses)
+            .map(|c| c.get(1).unwrap().as_str())
+            .collect::<Vec<_>>();
+        if !licenses.is_empty() {
+            push(
+                UpstreamDatum::License(licenses.join(" OR ").into()),
+                Certainty::Certain,
+            );
+        }
+    }
+
+    if let Some((_, links)) = lazy_regex::regex_captures!(r"\blinks:\s*%\{([^}]*)\}", &text) {
+        for c in lazy_regex::regex!(r#""([^"]+)"\s*=>\s*"([^"]+)""#).captures_iter(links) {
+            let url = c.get(2).unwrap().as_str().to_string();
+            match c.get(1).unwrap().as_str().to_lowercase().as_str() {
+                "github" | "gitlab" | "source" | "repository" => {
+                    push(UpstreamDatum::Repository(url), Certainty::Likely)
+                }
+                "changelog" => push(UpstreamDatum::Changelog(url), Certainty::Likely),
+                "homepage" | "website" => push(UpstreamDatum::Homepage(url), Certainty::Likely),
+                "docs" | "documentation" => {
+                    push(UpstreamDatum::Documentation(url), Certainty::Likely)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Projects with package metadata are published on Hex
+    if lazy_regex::regex_is_match!(r"\bpackage:\s", &text) {
+        push(UpstreamDatum::Archive("Hex".to_string()), Certainty::Likely);
+    }
+
+    Ok(results)
+}
+
+#[derive(Deserialize)]
+pub struct HexMeta {
+    pub description: Option<String>,
+    #[serde(default)]
+    pub licenses: Vec<String>,
+    #[serde(default)]
+    pub links: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+pub struct HexPackage {
+    pub name: String,
+    pub meta: HexMeta,
+    pub html_url: Option<String>,
+    pub docs_html_url: Option<String>,
+    pub latest_stable_version: Option<String>,
+    pub latest_version: Option<String>,
+}
+
+fn parse_hex_package(package: &HexPackage) -> Vec<UpstreamDatum> {
+    let mut results = vec![UpstreamDatum::Name(package.name.clone())];
+
+    if let Some(description) = package.meta.description.as_ref() {
+        results.push(UpstreamDatum::Description(description.clone()));
+    }
+
+    if !package.meta.licenses.is_empty() {
+        results.push(UpstreamDatum::License(
+            package.meta.licenses.join(" OR ").into(),
+        ));
+    }
+
+    if let Some(version) = package
+        .latest_stable_version
+        .as_ref()
+        .or(package.latest_version.as_ref())
+    {
+        results.push(UpstreamDatum::Version(version.clone()));
+    }
+
+    for (name, url) in package.meta.links.iter() {
+        match name.to_lowercase().as_str() {
+            "github" | "gitlab" | "source" | "repository" => {
+                results.push(UpstreamDatum::Repository(url.clone()))
+            }
+            "homepage" | "website" => results.push(UpstreamDatum::Homepage(url.clone())),
+            _ => {}
+        }
+    }
+
+    if let Some(docs_url) = package.docs_html_url.as_ref() {
+        results.push(UpstreamDatum::APIDocumentation(docs_url.clone()));
+    }
+
+    results
+}
+
+pub async fn guess_from_hex(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!("https://hex.pm/api/packages/{}", package)
+        .parse()
+        .unwrap();
+    let data = match crate::load_json_url(&url, None).await {
+        Ok(data) => data,
+        Err(crate::HTTPJSONError::Error { status: 404, .. }) => {
+            log::warn!("Package {} not found on Hex", package);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let package: HexPackage = serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid Hex package data: {}", e)))?;
+    Ok(parse_hex_package(&package))
+}
+
+pub struct Hex;
+
+impl Default for Hex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hex {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Hex {
+    fn name(&self) -> &'static str {
+        "Hex"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "License",
+            "Version",
+            "Description",
+            "API-Documentation",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_hex(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_package() {
+        let data = include_str!("../testdata/hex.json");
+        let package: HexPackage = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            parse_hex_package(&package),
+            vec![
+                UpstreamDatum::Name("jason".to_string()),
+                UpstreamDatum::Description(
+                    "A blazing fast JSON parser and generator in pure Elixir.".to_string()
+                ),
+                UpstreamDatum::License("Apache-2.0".into()),
+                UpstreamDatum::Version("1.4.4".to_string()),
+                UpstreamDatum::Repository("https://github.com/michalmuskala/jason".to_string()),
+                UpstreamDatum::APIDocumentation("https://hexdocs.pm/jason/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guess_from_mix_exs() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("mix.exs");
+        std::fs::write(
+            &path,
+            r#"defmodule Jason.Mixfile do
+  use Mix.Project
+
+  @source_url "https://github.com/michalmuskala/jason"
+  @version "1.4.4"
+
+  def project() do
+    [
+      app: :jason,
+      version: @version,
+      elixir: "~> 1.4",
+      # description: "old description",
+      description: "A blazing fast JSON parser and generator in pure Elixir.",
+      source_url: @source_url,
+      package: package()
+    ]
+  end
+
+  defp package() do
+    [
+      maintainers: ["Michał Muskała"],
+      licenses: ["Apache-2.0"],
+      links: %{"GitHub" => @source_url, "Changelog" => "https://hexdocs.pm/jason/changelog.html"}
+    ]
+  end
+end
+"#,
+        )
+        .unwrap();
+        let results = guess_from_mix_exs(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("jason".to_string()),
+                UpstreamDatum::Version("1.4.4".to_string()),
+                UpstreamDatum::Summary(
+                    "A blazing fast JSON parser and generator in pure Elixir.".to_string()
+                ),
+                UpstreamDatum::Repository("https://github.com/michalmuskala/jason".to_string()),
+                UpstreamDatum::License("Apache-2.0".into()),
+                UpstreamDatum::Changelog("https://hexdocs.pm/jason/changelog.html".to_string()),
+                UpstreamDatum::Archive("Hex".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Flatpak manifests and Flathub.
+//!
+//! See https://docs.flatpak.org/en/latest/manifests.html
+//! and https://docs.flathub.org/docs/for-app-authors/requirements
+
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FlatpakSource {
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub url: Option<String>,
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum FlatpakSourceEntry {
+    Source(FlatpakSource),
+    /// Reference to a separate file with sources
+    Path(String),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FlatpakModule {
+    pub name: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<FlatpakSourceEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+pub enum FlatpakModuleEntry {
+    Module(FlatpakModule),
+    /// Reference to a separate file with a module definition
+    Path(String),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FlatpakManifest {
+    #[serde(rename = "app-id", alias = "id")]
+    pub app_id: String,
+    pub modules: Vec<FlatpakModuleEntry>,
+}
+
+impl FlatpakManifest {
+    /// The module that builds the application itself.
+    ///
+    /// By convention this is the last module; the ones before it are dependencies.
+    pub fn main_module(&self) -> Option<&FlatpakModule> {
+        self.modules.iter().rev().find_map(|m| match m {
+            FlatpakModuleEntry::Module(m) => Some(m),
+            FlatpakModuleEntry::Path(_) => None,
+        })
+    }
+}
+
+fn load_flatpak_manifest(path: &Path) -> Result<FlatpakManifest, ProviderError> {
+    let file = std::fs::File::open(path)?;
+    // YAML is a superset of JSON, so this handles both formats
+    serde_yaml::from_reader(file).map_err(|e| ProviderError::ParseError(e.to_string()))
+}
+
+/// Check whether a file looks like a Flatpak manifest.
+///
+/// Manifests are named after the application ID, e.g. "org.gnome.Maps.json".
+pub fn is_flatpak_manifest(path: &Path) -> bool {
+    let extension = path.extension().and_then(|e| e.to_str());
+    if !matches!(extension, Some("json") | Some("yml") | Some("yaml")) {
+        return false;
+    }
+    let stem = match path.file_stem().and_then(|s| s.to_str()) {
+        Some(stem) => stem,
+        None => return false,
+    };
+    if !is_app_id(stem) {
+        return false;
+    }
+    load_flatpak_manifest(path).is_ok()
+}
+
+/// Check whether a string looks like a reverse-DNS application ID.
+pub fn is_app_id(name: &str) -> bool {
+    name.split('.').count() >= 3
+        && name.split('.').all(|c| {
+            !c.is_empty()
+                && c.chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+pub fn guess_from_flatpak_manifest(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let manifest = load_flatpak_manifest(path)?;
+
+    let mut results = vec![UpstreamDatumWithMetadata {
+        datum: UpstreamDatum::Name(manifest.app_id.clone()),
+        certainty: Some(Certainty::Likely),
+        origin: Some(path.into()),
+    }];
+
+    // flathub.json only appears in Flathub's packaging repositories
+    if let Some(parent) = path.parent() {
+        if parent.join("flathub.json").exists() {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Archive("Flathub".to_string()),
+                certainty: Some(Certainty::Likely),
+                origin: Some(path.into()),
+            });
+        }
+    }
+
+    if let Some(module) = manifest.main_module() {
+        for source in &module.sources {
+            let source = match source {
+                FlatpakSourceEntry::Source(source) => source,
+                FlatpakSourceEntry::Path(_) => continue,
+            };
+            let url = match source.url.as_ref() {
+                Some(url) => url,
+                None => continue,
+            };
+            match source.kind.as_deref() {
+                Some("git") | Some("bzr") | Some("svn") => {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::Repository(url.clone()),
+                        certainty: Some(Certainty::Likely),
+                        origin: Some(path.into()),
+                    });
+                    if let Some(version) =
+                        source.tag.as_deref().and_then(crate::vcs::version_from_tag)
+                    {
+                        results.push(UpstreamDatumWithMetadata {
+                            datum: UpstreamDatum::Version(version),
+                            certainty: Some(Certainty::Possible),
+                            origin: Some(path.into()),
+                        });
+                    }
+                }
+                Some("archive") => {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::Download(url.clone()),
+                        certainty: Some(Certainty::Likely),
+                        origin: Some(path.into()),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct FlathubUrls {
+    pub homepage: Option<String>,
+    pub bugtracker: Option<String>,
+    pub donation: Option<String>,
+    pub help: Option<String>,
+    pub vcs_browser: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FlathubRelease {
+    pub version: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FlathubAppstream {
+    pub id: String,
+    pub name: Option<String>,
+    pub summary: Option<String>,
+    pub project_license: Option<String>,
+    #[serde(default)]
+    pub urls: FlathubUrls,
+    #[serde(default)]
+    pub releases: Vec<FlathubRelease>,
+}
+
+pub fn parse_flathub_appstream(appstream: &FlathubAppstream) -> Vec<UpstreamDatum> {
+    let mut results = Vec::new();
+
+    if let Some(summary) = appstream.summary.as_ref() {
+        results.push(UpstreamDatum::Summary(summary.clone()));
+    }
+    if let Some(license) = appstream.project_license.as_ref() {
+        // Proprietary applications use LicenseRef-proprietary
+        if !license.starts_with("LicenseRef-") {
+            results.push(UpstreamDatum::License(license.clone().into()));
+        }
+    }
+    if let Some(homepage) = appstream.urls.homepage.as_ref() {
+        results.push(UpstreamDatum::Homepage(homepage.clone()));
+    }
+    if let Some(bugtracker) = appstream.urls.bugtracker.as_ref() {
+        results.push(UpstreamDatum::BugDatabase(bugtracker.clone()));
+    }
+    if let Some(donation) = appstream.urls.donation.as_ref() {
+        results.push(UpstreamDatum::Donation(donation.clone()));
+    }
+    if let Some(help) = appstream.urls.help.as_ref() {
+        results.push(UpstreamDatum::Documentation(help.clone()));
+    }
+    if let Some(vcs_browser) = appstream.urls.vcs_browser.as_ref() {
+        results.push(UpstreamDatum::RepositoryBrowse(vcs_browser.clone()));
+    }
+    // Releases are listed newest first
+    if let Some(release) = appstream.releases.first() {
+        results.push(UpstreamDatum::Version(release.version.clone()));
+    }
+
+    results
+}
+
+pub async fn guess_from_flathub(app_id: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!("https://flathub.org/api/v2/appstream/{}", app_id)
+        .parse()
+        .unwrap();
+    let data = match crate::load_json_url(&url, None).await {
+        Ok(data) => data,
+        Err(crate::HTTPJSONError::Error { status: 404, .. }) => {
+            log::warn!("Application {} not found on Flathub", app_id);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let appstream: FlathubAppstream = serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid Flathub appstream: {}", e)))?;
+    Ok(parse_flathub_appstream(&appstream))
+}
+
+pub struct Flathub;
+
+impl Default for Flathub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Flathub {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Flathub {
+    fn name(&self) -> &'static str {
+        "Flathub"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Summary",
+            "License",
+            "Homepage",
+            "Bug-Database",
+            "Donation",
+            "Documentation",
+            "Repository-Browse",
+            "Version",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_flathub(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_app_id() {
+        assert!(is_app_id("org.gnome.Maps"));
+        assert!(is_app_id("io.github.foo_bar.Baz"));
+        assert!(!is_app_id("package"));
+        assert!(!is_app_id("tsconfig.build"));
+        assert!(!is_app_id("org..Maps"));
+    }
+
+    #[test]
+    fn test_guess_from_flatpak_manifest() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("org.example.App.yml");
+        std::fs::write(
+            &path,
+            r#"app-id: org.example.App
+runtime: org.gnome.Platform
+command: app
+modules:
+  - shared-modules/libsecret/libsecret.json
+  - name: libfoo
+    sources:
+      - type: archive
+        url: https://example.com/libfoo-1.0.tar.xz
+  - name: app
+    buildsystem: meson
+    sources:
+      - type: git
+        url: https://gitlab.com/example/app.git
+        tag: v2.1.0
+"#,
+        )
+        .unwrap();
+        std::fs::write(td.path().join("flathub.json"), "{}").unwrap();
+        assert!(is_flatpak_manifest(&path));
+
+        let ret = guess_from_flatpak_manifest(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("org.example.App".to_string()),
+                UpstreamDatum::Archive("Flathub".to_string()),
+                UpstreamDatum::Repository("https://gitlab.com/example/app.git".to_string()),
+                UpstreamDatum::Version("2.1.0".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_flathub_appstream() {
+        let data = include_str!("../testdata/flathub-appstream.json");
+        let appstream: FlathubAppstream = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            parse_flathub_appstream(&appstream),
+            vec![
+                UpstreamDatum::Summary("Find places around the world".to_string()),
+                UpstreamDatum::License("GPL-2.0-or-later".into()),
+                UpstreamDatum::Homepage("https://apps.gnome.org/Maps/".to_string()),
+                UpstreamDatum::BugDatabase(
+                    "https://gitlab.gnome.org/GNOME/gnome-maps/issues".to_string()
+                ),
+                UpstreamDatum::Donation("https://www.gnome.org/donate/".to_string()),
+                UpstreamDatum::Documentation(
+                    "https://help.gnome.org/users/gnome-maps/".to_string()
+                ),
+                UpstreamDatum::RepositoryBrowse(
+                    "https://gitlab.gnome.org/GNOME/gnome-maps/".to_string()
+                ),
+                UpstreamDatum::Version("47.0".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! GitHub sponsor button configuration, in .github/FUNDING.yml.
+//!
+//! See <https://docs.github.com/en/repositories/managing-your-repositorys-settings-and-features/customizing-your-repository/displaying-a-sponsor-button-in-your-repository>
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use std::path::Path;
+
+/// URL templates for the supported platforms; `{}` is replaced by the account name.
+const PLATFORM_URLS: &[(&str, &str)] = &[
+    ("github", "https://github.com/sponsors/{}"),
+    ("patreon", "https://www.patreon.com/{}"),
+    ("open_collective", "https://opencollective.com/{}"),
+    ("ko_fi", "https://ko-fi.com/{}"),
+    ("liberapay", "https://liberapay.com/{}"),
+    ("tidelift", "https://tidelift.com/funding/github/{}"),
+    (
+        "community_bridge",
+        "https://funding.communitybridge.org/projects/{}",
+    ),
+    ("issuehunt", "https://issuehunt.io/r/{}"),
+    ("polar", "https://polar.sh/{}"),
+    ("buy_me_a_coffee", "https://www.buymeacoffee.com/{}"),
+    ("thanks_dev", "https://thanks.dev/{}"),
+];
+
+/// Values may be a single string or a list of strings; empty values are commented-out examples.
+fn values(value: &serde_yaml::Value) -> Vec<&str> {
+    match value {
+        serde_yaml::Value::String(s) => vec![s.as_str()],
+        serde_yaml::Value::Sequence(items) => items.iter().filter_map(|v| v.as_str()).collect(),
+        _ => vec![],
+    }
+    .into_iter()
+    .map(str::trim)
+    .filter(|s| !s.is_empty())
+    .collect()
+}
+
+/// Determine the donation URLs listed in a FUNDING.yml file.
+fn donation_urls(funding: &serde_yaml::Mapping) -> Vec<String> {
+    let mut urls = vec![];
+    for (key, value) in funding {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        if key == "custom" {
+            for url in values(value) {
+                if url.contains("://") {
+                    urls.push(url.to_string());
+                } else {
+                    urls.push(format!("https://{}", url));
+                }
+            }
+        } else if let Some((_, template)) = PLATFORM_URLS.iter().find(|(name, _)| *name == key) {
+            for account in values(value) {
+                urls.push(template.replace("{}", account));
+            }
+        } else {
+            log::debug!("Unknown funding platform {}", key);
+        }
+    }
+    urls
+}
+
+pub fn guess_from_funding_yml(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let funding: Option<serde_yaml::Mapping> =
+        serde_yaml::from_str(&text).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    Ok(donation_urls(&funding.unwrap_or_default())
+        .into_iter()
+        .map(|url| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Donation(url),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_donation_urls() {
+        let funding: serde_yaml::Mapping = serde_yaml::from_str(
+            r#"# These are supported funding model platforms
+
+github: [jelmer, octocat]
+patreon: jelmer
+open_collective: dulwich
+ko_fi: # Replace with a single Ko-fi username
+liberapay: jelmer
+unknown_platform: foo
+custom: ["https://www.paypal.me/jelmer", example.com/donate]
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            donation_urls(&funding),
+            vec![
+                "https://github.com/sponsors/jelmer",
+                "https://github.com/sponsors/octocat",
+                "https://www.patreon.com/jelmer",
+                "https://opencollective.com/dulwich",
+                "https://liberapay.com/jelmer",
+                "https://www.paypal.me/jelmer",
+                "https://example.com/donate",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("FUNDING.yml");
+        std::fs::write(&path, "# github: [username]\n").unwrap();
+        assert_eq!(
+            guess_from_funding_yml(&path, &GuesserSettings::default()).unwrap(),
+            vec![]
+        );
+    }
+}
@@ -1,35 +1,62 @@
+#[cfg(feature = "git-config")]
 use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+#[cfg(feature = "git-config")]
 use std::path::Path;
 
 #[cfg(feature = "git-config")]
-pub fn guess_from_git_config(
+pub async fn guess_from_git_config(
     path: &Path,
     settings: &GuesserSettings,
 ) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
-    let config_file =
-        gix_config::File::from_path_no_includes(path.to_path_buf(), gix_config::Source::Local)
-            .map_err(|e| ProviderError::ParseError(e.to_string()))?;
-    let mut results = Vec::new();
+    let net_access = Some(settings.network.allows_network());
 
     // Check if there's a remote named "upstream"
-    if let Some(remote_upstream) = config_file.string_by("remote", Some("upstream".into()), "url") {
-        let url = remote_upstream.to_string();
-        if !url.starts_with("../") {
-            results.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::Repository(url),
-                certainty: Some(Certainty::Likely),
-                origin: Some(path.into()),
-            });
-        }
-    }
+    let mut remotes = vec![("upstream", Certainty::Likely)];
 
     // Check if there's a remote named "origin"
     if !settings.trust_package {
-        if let Some(remote_origin) = config_file.string_by("remote", Some("origin".into()), "url") {
-            let url = remote_origin.to_string();
-            if !url.starts_with("../") {
+        remotes.push(("origin", Certainty::Possible));
+    }
+
+    let remotes = {
+        let config_file =
+            gix_config::File::from_path_no_includes(path.to_path_buf(), gix_config::Source::Local)
+                .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+        remotes
+            .into_iter()
+            .filter_map(|(name, certainty)| {
+                let url = config_file
+                    .string_by("remote", Some(name.into()), "url")?
+                    .to_string();
+                let push_url = config_file
+                    .string_by("remote", Some(name.into()), "pushurl")
+                    .map(|u| u.to_string());
+                Some((url, push_url, certainty))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut results = Vec::new();
+    for (url, push_url, certainty) in remotes {
+        if url.starts_with("../") {
+            continue;
+        }
+        // A push URL on a different host may point at the canonical repository, but it is
+        // just as likely to be a personal fork or a location that is not publicly accessible
+        let push_url = push_url.filter(|u| {
+            let host = crate::vcs::url_host(u);
+            host.is_some() && host != crate::vcs::url_host(&url)
+        });
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Repository(url),
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        });
+        if let Some(push_url) = push_url {
+            if let Some(public_url) = crate::vcs::find_public_repo_url(&push_url, net_access).await
+            {
                 results.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::Repository(url),
+                    datum: UpstreamDatum::Repository(public_url),
                     certainty: Some(Certainty::Possible),
                     origin: Some(path.into()),
                 });
@@ -39,3 +66,68 @@ pub fn guess_from_git_config(
 
     Ok(results)
 }
+
+#[cfg(test)]
+#[cfg(feature = "git-config")]
+mod tests {
+    use super::*;
+
+    async fn guess(config: &str) -> Vec<(UpstreamDatum, Option<Certainty>)> {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("config");
+        std::fs::write(&path, config).unwrap();
+        guess_from_git_config(
+            &path,
+            &GuesserSettings {
+                trust_package: true,
+                network: crate::NetworkPolicy::Offline,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|d| (d.datum, d.certainty))
+        .collect()
+    }
+
+    #[tokio::test]
+    async fn test_push_url_on_other_host() {
+        assert_eq!(
+            guess(
+                r#"[remote "upstream"]
+	url = https://github.com/example/foo
+	pushurl = git@gitlab.com:example/foo.git
+"#
+            )
+            .await,
+            vec![
+                (
+                    UpstreamDatum::Repository("https://github.com/example/foo".to_string()),
+                    Some(Certainty::Likely)
+                ),
+                (
+                    UpstreamDatum::Repository("https://gitlab.com/example/foo.git".to_string()),
+                    Some(Certainty::Possible)
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_private_push_url() {
+        assert_eq!(
+            guess(
+                r#"[remote "upstream"]
+	url = https://github.com/example/foo
+	pushurl = git@git.example.com:example/foo.git
+"#
+            )
+            .await,
+            vec![(
+                UpstreamDatum::Repository("https://github.com/example/foo".to_string()),
+                Some(Certainty::Likely)
+            )]
+        );
+    }
+}
@@ -4,6 +4,9 @@ use crate::{
     UpstreamMetadata,
 };
 use log::debug;
+use select::document::Document;
+use select::predicate::{Attr, Name, Predicate};
+use serde::Deserialize;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
@@ -30,6 +33,11 @@ pub fn guess_from_go_mod(
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::GoImportPath(modname.to_owned()),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
         }
     }
 
@@ -54,3 +62,221 @@ pub fn remote_go_metadata(package: &str) -> Result<UpstreamMetadata, ProviderErr
     }
     Ok(ret)
 }
+
+/// Escape a module path for use in a module proxy URL.
+///
+/// Upper case letters are replaced by an exclamation mark followed by the lower case letter.
+fn escape_module_path(module: &str) -> String {
+    let mut ret = String::new();
+    for c in module.chars() {
+        if c.is_ascii_uppercase() {
+            ret.push('!');
+            ret.push(c.to_ascii_lowercase());
+        } else {
+            ret.push(c);
+        }
+    }
+    ret
+}
+
+#[derive(Deserialize)]
+pub struct GoModuleOrigin {
+    #[serde(rename = "VCS")]
+    pub vcs: Option<String>,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GoModuleInfo {
+    #[serde(rename = "Version")]
+    pub version: String,
+    #[serde(rename = "Origin")]
+    pub origin: Option<GoModuleOrigin>,
+}
+
+fn parse_go_module_info(info: &GoModuleInfo) -> Vec<UpstreamDatum> {
+    let mut results = vec![];
+
+    // Pseudo-versions (e.g. v0.0.0-20200101000000-abcdef123456) are not releases
+    if !lazy_regex::regex_is_match!(r"-\d{14}-[0-9a-f]{12}$", &info.version) {
+        let version = info.version.strip_prefix('v').unwrap_or(&info.version);
+        let version = version.strip_suffix("+incompatible").unwrap_or(version);
+        results.push(UpstreamDatum::Version(version.to_string()));
+    }
+
+    if let Some(url) = info.origin.as_ref().and_then(|o| o.url.as_ref()) {
+        results.push(UpstreamDatum::Repository(url.clone()));
+    }
+
+    results
+}
+
+/// Find the repository for a module in the go-import meta tag of a `?go-get=1` page.
+///
+/// See https://pkg.go.dev/cmd/go#hdr-Remote_import_paths
+fn parse_go_import_meta(body: &str, module: &str) -> Option<String> {
+    let document = Document::from(body);
+    document
+        .find(Name("meta").and(Attr("name", "go-import")))
+        .filter_map(|node| {
+            let content = node.attr("content")?;
+            let mut parts = content.split_whitespace();
+            let prefix = parts.next()?;
+            let _vcs = parts.next()?;
+            let url = parts.next()?;
+            if module == prefix || module.starts_with(&format!("{}/", prefix)) {
+                Some(url.to_string())
+            } else {
+                None
+            }
+        })
+        .next()
+}
+
+async fn resolve_go_import(module: &str) -> Result<Option<String>, ProviderError> {
+    let url = format!("https://{}?go-get=1", module);
+
+    let client = crate::http::build_client()
+        .build()
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    let response = crate::http::send(client.get(url))
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    if !response.status().is_success() {
+        debug!(
+            "Unable to resolve go-import for {}: {}",
+            module,
+            response.status()
+        );
+        return Ok(None);
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    Ok(parse_go_import_meta(&body, module))
+}
+
+pub async fn guess_from_go_proxy(module: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!(
+        "https://proxy.golang.org/{}/@latest",
+        escape_module_path(module)
+    )
+    .parse()
+    .unwrap();
+    let mut results = match crate::load_json_url(&url, None).await {
+        Ok(data) => {
+            let info: GoModuleInfo = serde_json::from_value(data)
+                .map_err(|e| ProviderError::ParseError(format!("Invalid module info: {}", e)))?;
+            parse_go_module_info(&info)
+        }
+        Err(crate::HTTPJSONError::Error {
+            status: 404 | 410, ..
+        }) => {
+            log::warn!("Module {} not found on the Go module proxy", module);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    if !results
+        .iter()
+        .any(|d| matches!(d, UpstreamDatum::Repository(_)))
+    {
+        if let Some(repository) = resolve_go_import(module).await? {
+            results.push(UpstreamDatum::Repository(repository));
+        }
+    }
+
+    results.push(UpstreamDatum::Documentation(format!(
+        "https://pkg.go.dev/{}",
+        module
+    )));
+
+    Ok(results)
+}
+
+pub struct GoProxy;
+
+impl Default for GoProxy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GoProxy {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for GoProxy {
+    fn name(&self) -> &'static str {
+        "Go module proxy"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &["Repository", "Version", "Documentation"][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_go_proxy(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_module_path() {
+        assert_eq!(
+            escape_module_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+    }
+
+    #[test]
+    fn test_parse_go_module_info() {
+        let info: GoModuleInfo = serde_json::from_str(
+            r#"{"Version":"v1.4.0","Time":"2024-06-12T15:08:39Z","Origin":{"VCS":"git","URL":"https://github.com/BurntSushi/toml","Ref":"refs/tags/v1.4.0","Hash":"e4bcd1ef3ffe5e4f9fbd55e5dd21ef4ab0d3d5f1"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_go_module_info(&info),
+            vec![
+                UpstreamDatum::Version("1.4.0".to_string()),
+                UpstreamDatum::Repository("https://github.com/BurntSushi/toml".to_string()),
+            ]
+        );
+
+        let info: GoModuleInfo = serde_json::from_str(
+            r#"{"Version":"v0.0.0-20240112132812-db7319d0e0e3","Time":"2024-01-12T13:28:12Z"}"#,
+        )
+        .unwrap();
+        assert_eq!(parse_go_module_info(&info), vec![]);
+    }
+
+    #[test]
+    fn test_parse_go_import_meta() {
+        let body = r#"<html><head>
+<meta name="go-import" content="golang.org/x/text git https://go.googlesource.com/text">
+<meta name="go-source" content="golang.org/x/text https://github.com/golang/text/ https://github.com/golang/text/tree/master{/dir} https://github.com/golang/text/blob/master{/dir}/{file}#L{line}">
+</head></html>"#;
+        assert_eq!(
+            parse_go_import_meta(body, "golang.org/x/text/unicode"),
+            Some("https://go.googlesource.com/text".to_string())
+        );
+        assert_eq!(parse_go_import_meta(body, "golang.org/x/net"), None);
+    }
+}
@@ -67,7 +67,7 @@ pub async fn guess_from_gobo(package: &str) -> Result<Vec<UpstreamDatum>, crate:
 
     let mut result = Vec::new();
     let recipe_url = base_url.join("Recipe").unwrap();
-    match client.get(recipe_url.as_ref()).send().await {
+    match crate::http::send(client.get(recipe_url.as_ref())).await {
         Ok(response) => {
             let text = response.text().await.unwrap();
             for line in text.lines() {
@@ -88,7 +88,7 @@ pub async fn guess_from_gobo(package: &str) -> Result<Vec<UpstreamDatum>, crate:
     }
 
     let description_url = base_url.join("Resources/Description").unwrap();
-    match client.get(description_url.as_ref()).send().await {
+    match crate::http::send(client.get(description_url.as_ref())).await {
         Ok(response) => {
             for line in response.text().await.unwrap().lines() {
                 if let Some((_, key, value)) = lazy_regex::regex_captures!("\\[(.*)\\] (.*)", line)
@@ -96,7 +96,7 @@ pub async fn guess_from_gobo(package: &str) -> Result<Vec<UpstreamDatum>, crate:
                     match key {
                         "Name" => result.push(UpstreamDatum::Name(value.to_string())),
                         "Summary" => result.push(UpstreamDatum::Summary(value.to_string())),
-                        "License" => result.push(UpstreamDatum::License(value.to_string())),
+                        "License" => result.push(UpstreamDatum::License(value.to_string().into())),
                         "Description" => result.push(UpstreamDatum::Description(value.to_string())),
                         "Homepage" => result.push(UpstreamDatum::Homepage(value.to_string())),
                         _ => log::warn!("Unknown field {} in gobo Description", key),
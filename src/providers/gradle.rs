@@ -0,0 +1,366 @@
+//! Support for the publishing metadata in Gradle build scripts.
+//!
+//! Documentation: https://docs.gradle.org/current/userguide/publishing_maven.html
+
+use crate::{
+    vcs, Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum,
+    UpstreamDatumWithMetadata,
+};
+use std::path::Path;
+
+/// Remove comments from a Groovy or Kotlin build script, leaving string literals intact.
+fn strip_comments(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut quote: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            ret.push(c);
+            if c == '\\' {
+                if let Some(n) = chars.next() {
+                    ret.push(n);
+                }
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => {
+                quote = Some(c);
+                ret.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for n in chars.by_ref() {
+                    if n == '\n' {
+                        ret.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = None;
+                for n in chars.by_ref() {
+                    if prev == Some('*') && n == '/' {
+                        break;
+                    }
+                    prev = Some(n);
+                }
+            }
+            _ => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Find the offset of the brace that closes the block opened just before `text`.
+fn find_closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find the bodies of all blocks with the specified name, e.g. `pom { ... }`.
+fn find_blocks<'a>(text: &'a str, name: &str) -> Vec<&'a str> {
+    let re = regex::Regex::new(&format!(r"(?m)(^|[^\w.]){}\s*(\([^)]*\)\s*)?\{{", name)).unwrap();
+    let mut ret = Vec::new();
+    for m in re.find_iter(text) {
+        let body = &text[m.end()..];
+        if let Some(end) = find_closing_brace(body) {
+            ret.push(&body[..end]);
+        }
+    }
+    ret
+}
+
+/// Remove nested blocks from a block body, so only its direct properties remain.
+fn direct_statements(body: &str) -> String {
+    let mut ret = String::new();
+    let mut rest = body;
+    while let Some(idx) = rest.find('{') {
+        ret.push_str(&rest[..idx]);
+        match find_closing_brace(&rest[idx + 1..]) {
+            Some(end) => rest = &rest[idx + 1 + end + 1..],
+            None => return ret,
+        }
+    }
+    ret.push_str(rest);
+    ret
+}
+
+/// Find the literal value of a property in a block body.
+///
+/// This supports the various ways of setting properties in Groovy and Kotlin:
+/// `url = "..."`, `url.set("...")`, `url("...")` and `url '...'`.
+fn get_property(body: &str, key: &str) -> Option<String> {
+    let statements = direct_statements(body);
+    let re = regex::Regex::new(&format!(
+        r#"(?m)^\s*{}(?:\s*=\s*|\.set\(\s*|\s*\(\s*|\s+)(?:"([^"$]*)"|'([^'$]*)')"#,
+        regex::escape(key)
+    ))
+    .unwrap();
+    let c = re.captures(&statements)?;
+    c.get(1)
+        .or_else(|| c.get(2))
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn parse_scm_url(url: &str) -> &str {
+    match url.strip_prefix("scm:") {
+        Some(rest) => rest.split_once(':').map_or(rest, |(_, u)| u),
+        None => url,
+    }
+}
+
+pub fn guess_from_gradle(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let text = strip_comments(&text);
+
+    let mut results = Vec::new();
+
+    if let Some(version) = get_property(&text, "version") {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Version(version),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+
+    for pom in find_blocks(&text, "pom") {
+        if let Some(name) = get_property(pom, "name") {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Name(name),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+        }
+
+        if let Some(description) = get_property(pom, "description") {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Summary(description),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+        }
+
+        if let Some(url) = get_property(pom, "url") {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Homepage(url),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+        }
+
+        for scm in find_blocks(pom, "scm") {
+            if let Some(connection) = get_property(scm, "connection") {
+                results.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Repository(parse_scm_url(&connection).to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
+            if let Some(url) = get_property(scm, "url") {
+                let url = parse_scm_url(&url);
+                if vcs::plausible_browse_url(url) {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::RepositoryBrowse(url.to_string()),
+                        certainty: Some(Certainty::Certain),
+                        origin: Some(path.into()),
+                    });
+                }
+            }
+        }
+
+        for issue_management in find_blocks(pom, "issueManagement") {
+            if let Some(url) = get_property(issue_management, "url") {
+                results.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::BugDatabase(url),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
+        }
+
+        for licenses in find_blocks(pom, "licenses") {
+            for license in find_blocks(licenses, "license") {
+                if let Some(name) = get_property(license, "name") {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::License(name.into()),
+                        certainty: Some(Certainty::Certain),
+                        origin: Some(path.into()),
+                    });
+                }
+            }
+        }
+
+        let mut authors = Vec::new();
+        for developers in find_blocks(pom, "developers") {
+            for developer in find_blocks(developers, "developer") {
+                let person = Person {
+                    name: get_property(developer, "name").or_else(|| get_property(developer, "id")),
+                    email: get_property(developer, "email"),
+                    url: get_property(developer, "url"),
+                };
+                if person != Person::default() {
+                    authors.push(person);
+                }
+            }
+        }
+        if !authors.is_empty() {
+            results.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Author(authors),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_property() {
+        assert_eq!(
+            get_property("  url = \"https://example.com\"\n", "url"),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            get_property("  url.set(\"https://example.com\")\n", "url"),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            get_property("  url 'https://example.com'\n", "url"),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(get_property("  url = \"${baseUrl}/foo\"\n", "url"), None);
+        assert_eq!(
+            get_property("  scm {\n url = 'https://example.com'\n }\n", "url"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_guess_from_gradle_kts() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("build.gradle.kts");
+        std::fs::write(
+            &path,
+            r#"
+version = "1.2.3"
+
+publishing {
+    publications {
+        create<MavenPublication>("maven") {
+            pom {
+                name.set("Example Library")
+                description.set("An example library") // not much else to say
+                url.set("https://example.com/lib")
+                licenses {
+                    license {
+                        name.set("The Apache License, Version 2.0")
+                        url.set("https://www.apache.org/licenses/LICENSE-2.0.txt")
+                    }
+                }
+                developers {
+                    developer {
+                        id.set("jdoe")
+                        name.set("John Doe")
+                        email.set("john@example.com")
+                    }
+                }
+                scm {
+                    connection.set("scm:git:https://github.com/example/lib.git")
+                    developerConnection.set("scm:git:ssh://git@github.com/example/lib.git")
+                    url.set("https://github.com/example/lib")
+                }
+            }
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+        let results = guess_from_gradle(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Version("1.2.3".to_string()),
+                UpstreamDatum::Name("Example Library".to_string()),
+                UpstreamDatum::Summary("An example library".to_string()),
+                UpstreamDatum::Homepage("https://example.com/lib".to_string()),
+                UpstreamDatum::Repository("https://github.com/example/lib.git".to_string()),
+                UpstreamDatum::RepositoryBrowse("https://github.com/example/lib".to_string()),
+                UpstreamDatum::License("The Apache License, Version 2.0".into()),
+                UpstreamDatum::Author(vec![Person {
+                    name: Some("John Doe".to_string()),
+                    email: Some("john@example.com".to_string()),
+                    url: None,
+                }]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guess_from_gradle_groovy() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("build.gradle");
+        std::fs::write(
+            &path,
+            r#"
+/* Publishing configuration */
+publishing {
+    publications {
+        mavenJava(MavenPublication) {
+            pom {
+                name = 'example'
+                url = 'https://example.org/'
+                issueManagement {
+                    url = 'https://example.org/issues'
+                }
+            }
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+        let results = guess_from_gradle(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("example".to_string()),
+                UpstreamDatum::Homepage("https://example.org/".to_string()),
+                UpstreamDatum::BugDatabase("https://example.org/issues".to_string()),
+            ]
+        );
+    }
+}
@@ -78,9 +78,10 @@ pub fn guess_from_cabal_lines(
                 UpstreamDatum::Copyright(value.to_owned()),
                 Certainty::Certain,
             )),
-            (None, "license") => {
-                results.push((UpstreamDatum::License(value.to_owned()), Certainty::Certain))
-            }
+            (None, "license") => results.push((
+                UpstreamDatum::License(value.to_owned().into()),
+                Certainty::Certain,
+            )),
             (None, "author") => results.push((
                 UpstreamDatum::Author(vec![Person::from(value.as_str())]),
                 Certainty::Certain,
@@ -159,7 +160,7 @@ pub async fn guess_from_hackage(
     .parse()
     .unwrap();
 
-    match client.get(url).send().await {
+    match crate::http::send(client.get(url)).await {
         Ok(response) => {
             let bytes = response.bytes().await?;
             let reader = BufReader::new(&bytes[..]);
@@ -0,0 +1,94 @@
+//! Haxe libraries, described by a haxelib.json file.
+//!
+//! See <https://lib.haxe.org/documentation/creating-a-haxelib-package/>
+use crate::{
+    Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
+};
+use std::path::Path;
+
+#[derive(serde::Deserialize)]
+struct Haxelib {
+    name: Option<String>,
+    url: Option<String>,
+    license: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    description: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    contributors: Vec<String>,
+}
+
+pub fn guess_from_haxelib_json(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let file = std::fs::File::open(path)?;
+    let haxelib: Haxelib =
+        serde_json::from_reader(file).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    let mut upstream_data: Vec<UpstreamDatumWithMetadata> = Vec::new();
+
+    if let Some(name) = haxelib.name {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name(name),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if let Some(url) = haxelib.url.filter(|u| !u.is_empty()) {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Homepage(url),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if let Some(license) = haxelib.license {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::License(license.into()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if !haxelib.tags.is_empty() {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Keywords(haxelib.tags),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if let Some(description) = haxelib.description {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Summary(description),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if let Some(version) = haxelib.version {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Version(version),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+    if !haxelib.contributors.is_empty() {
+        // Contributors are haxelib user names
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Author(
+                haxelib
+                    .contributors
+                    .into_iter()
+                    .map(|user| Person {
+                        url: Some(format!("https://lib.haxe.org/u/{}", user)),
+                        name: Some(user),
+                        email: None,
+                    })
+                    .collect(),
+            ),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+
+    Ok(upstream_data)
+}
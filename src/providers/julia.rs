@@ -0,0 +1,290 @@
+//! Julia packages, described by a Project.toml file and registered in the General registry.
+//!
+//! See <https://pkgdocs.julialang.org/v1/toml-files/>
+use crate::{
+    Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Location of the General registry
+const GENERAL_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/JuliaRegistries/General/master";
+
+#[derive(Deserialize)]
+struct ProjectToml {
+    name: Option<String>,
+    uuid: Option<String>,
+    version: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+}
+
+pub fn guess_from_project_toml(
+    path: &std::path::Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let project: ProjectToml = toml::from_str(&std::fs::read_to_string(path)?)
+        .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    let mut results = Vec::new();
+
+    // Environments (e.g. docs/Project.toml) have no name or uuid, and don't describe a package
+    let name = match project.name {
+        Some(name) => name,
+        None => {
+            log::debug!("No name in {}, not a package", path.display());
+            return Ok(results);
+        }
+    };
+
+    results.push(UpstreamDatumWithMetadata {
+        datum: UpstreamDatum::Name(name),
+        certainty: Some(Certainty::Certain),
+        origin: Some(path.into()),
+    });
+
+    if let Some(version) = project.version {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Version(version),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+
+    if !project.authors.is_empty() {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Author(
+                project
+                    .authors
+                    .iter()
+                    .map(|a| Person::from(a.as_str()))
+                    .collect(),
+            ),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+
+    // Only packages with a uuid can be registered
+    if project.uuid.is_some() {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Archive("Julia General".to_string()),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+
+    Ok(results)
+}
+
+#[derive(Deserialize)]
+struct RegistryPackage {
+    name: String,
+    repo: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RegistryVersion {
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// Path of a package in the General registry, e.g. "E/Example".
+fn registry_path(name: &str) -> Option<String> {
+    let first = name.chars().next()?.to_ascii_uppercase();
+    // Binary wrappers live in a separate tree
+    if name.ends_with("_jll") {
+        Some(format!("jll/{}/{}", first, name))
+    } else {
+        Some(format!("{}/{}", first, name))
+    }
+}
+
+fn parse_registry_package(
+    package: &str,
+    versions: Option<&str>,
+) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let package: RegistryPackage =
+        toml::from_str(package).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+
+    let mut ret = vec![UpstreamDatum::Name(package.name)];
+
+    if let Some(repo) = package.repo {
+        ret.push(UpstreamDatum::Repository(repo));
+    }
+
+    if let Some(versions) = versions {
+        let versions: HashMap<String, RegistryVersion> =
+            toml::from_str(versions).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+        if let Some(latest) = versions
+            .into_iter()
+            .filter(|(_, v)| !v.yanked)
+            .map(|(version, _)| version)
+            .max_by(|a, b| crate::version::compare_versions(a, b))
+        {
+            ret.push(UpstreamDatum::Version(latest));
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Fetch a file from the General registry, returning None if it does not exist.
+async fn load_registry_file(path: &str) -> Result<Option<String>, ProviderError> {
+    let client = reqwest::Client::builder()
+        .user_agent(crate::USER_AGENT)
+        .build()
+        .unwrap();
+
+    let response = crate::http::send(client.get(format!("{}/{}", GENERAL_REGISTRY_URL, path)))
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        status if !status.is_success() => {
+            Err(ProviderError::Other(format!("HTTP error: {}", status)))
+        }
+        _ => Ok(Some(
+            response
+                .text()
+                .await
+                .map_err(|e| ProviderError::Other(e.to_string()))?,
+        )),
+    }
+}
+
+pub async fn guess_from_julia_registry(name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let path = match registry_path(name) {
+        Some(path) => path,
+        None => return Ok(Vec::new()),
+    };
+    let package = match load_registry_file(&format!("{}/Package.toml", path)).await? {
+        Some(package) => package,
+        None => {
+            log::warn!("Package {} not found in the Julia General registry", name);
+            return Ok(Vec::new());
+        }
+    };
+    let versions = load_registry_file(&format!("{}/Versions.toml", path)).await?;
+    parse_registry_package(&package, versions.as_deref())
+}
+
+pub struct JuliaGeneral;
+
+impl Default for JuliaGeneral {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JuliaGeneral {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for JuliaGeneral {
+    fn name(&self) -> &'static str {
+        "Julia General"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &["Name", "Repository", "Version"][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_julia_registry(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_from_project_toml() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("Project.toml");
+        std::fs::write(
+            &path,
+            r#"name = "Example"
+uuid = "7876af07-990d-54b4-ab0e-23690620f79a"
+authors = ["Jane Doe <jane@example.com>"]
+version = "0.5.4"
+
+[deps]
+Test = "8dfed614-e22c-5e08-85e1-65c5234f0b40"
+"#,
+        )
+        .unwrap();
+        let ret = guess_from_project_toml(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("Example".to_string()),
+                UpstreamDatum::Version("0.5.4".to_string()),
+                UpstreamDatum::Author(vec![Person {
+                    name: Some("Jane Doe".to_string()),
+                    email: Some("jane@example.com".to_string()),
+                    url: None,
+                }]),
+                UpstreamDatum::Archive("Julia General".to_string()),
+            ]
+        );
+
+        // An environment rather than a package
+        std::fs::write(
+            &path,
+            "[deps]\nDocumenter = \"e30172f5-a6a5-5a46-863b-614d45cd2de4\"\n",
+        )
+        .unwrap();
+        assert!(guess_from_project_toml(&path, &GuesserSettings::default())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_registry_path() {
+        assert_eq!(registry_path("Example"), Some("E/Example".to_string()));
+        assert_eq!(
+            registry_path("zlib_jll"),
+            Some("jll/Z/zlib_jll".to_string())
+        );
+        assert_eq!(registry_path(""), None);
+    }
+
+    #[test]
+    fn test_parse_registry_package() {
+        let package = r#"name = "Example"
+uuid = "7876af07-990d-54b4-ab0e-23690620f79a"
+repo = "https://github.com/JuliaLang/Example.jl.git"
+"#;
+        let versions = r#"["0.5.3"]
+git-tree-sha1 = "46e44e869b4d90b96bd8ed1fdcf32244fddfb6cc"
+
+["0.5.10"]
+git-tree-sha1 = "0da4d2a0d6e6d3ad3c6c9e2c51ab3bc2a4c5b2c7"
+
+["0.5.11"]
+git-tree-sha1 = "1da4d2a0d6e6d3ad3c6c9e2c51ab3bc2a4c5b2c7"
+yanked = true
+"#;
+        assert_eq!(
+            parse_registry_package(package, Some(versions)).unwrap(),
+            vec![
+                UpstreamDatum::Name("Example".to_string()),
+                UpstreamDatum::Repository(
+                    "https://github.com/JuliaLang/Example.jl.git".to_string()
+                ),
+                UpstreamDatum::Version("0.5.10".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Sniffing of project manifests for which there is no dedicated provider.
+//!
+//! New ecosystems regularly introduce their own manifest format, but these tend to be
+//! JSON, YAML or TOML files with keys such as "name", "version" and "license". Top-level
+//! files like that are inspected, and keys that obviously match a field are used at
+//! Possible certainty.
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use serde_json::Value;
+use std::path::Path;
+
+/// Files larger than this are not manifests.
+const MAX_SIZE: u64 = 64 * 1024;
+
+/// Structured files that are known not to describe the project.
+const IGNORED_FILES: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "composer.lock",
+    "tsconfig.json",
+    "jsconfig.json",
+    "renovate.json",
+    "lerna.json",
+    "codecov.yml",
+    "mkdocs.yml",
+    "mkdocs.yaml",
+    "docker-compose.yml",
+    "docker-compose.yaml",
+    "environment.yml",
+    "environment.yaml",
+    "expected.yaml",
+];
+
+/// Tables that commonly hold the project metadata, rather than the top level.
+const METADATA_TABLES: &[&str] = &["package", "project", "metadata"];
+
+/// Parse a structured file, based on its extension.
+fn parse(path: &Path) -> Option<Value> {
+    let text = std::fs::read_to_string(path).ok()?;
+    match path.extension()?.to_str()? {
+        "json" => serde_json::from_str(&text).ok(),
+        "yml" | "yaml" => serde_yaml::from_str(&text).ok(),
+        #[cfg(any(feature = "cargo", feature = "julia"))]
+        "toml" => toml::from_str(&text).ok(),
+        _ => None,
+    }
+}
+
+/// Look up a key case-insensitively.
+fn lookup<'a>(table: &'a serde_json::Map<String, Value>, keys: &[&str]) -> Option<&'a Value> {
+    table
+        .iter()
+        .find(|(k, _)| keys.iter().any(|key| k.eq_ignore_ascii_case(key)))
+        .map(|(_, v)| v)
+}
+
+fn string<'a>(table: &'a serde_json::Map<String, Value>, keys: &[&str]) -> Option<&'a str> {
+    lookup(table, keys)?
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty() && !s.contains('\n'))
+}
+
+/// Look up a URL, which may also be stored as a table with a "url" key.
+fn url(table: &serde_json::Map<String, Value>, keys: &[&str]) -> Option<String> {
+    let value = lookup(table, keys)?;
+    let url = match value {
+        Value::String(s) => s.as_str(),
+        Value::Object(o) => o.get("url")?.as_str()?,
+        _ => return None,
+    };
+    let parsed = url::Url::parse(url.trim()).ok()?;
+    matches!(
+        parsed.scheme(),
+        "http" | "https" | "git" | "git+https" | "ssh"
+    )
+    .then(|| url.trim().to_string())
+}
+
+/// Extract the fields from the metadata in a manifest.
+///
+/// Tables without a name are not considered manifests, nor are tables that have nothing
+/// but a name.
+fn sniff(table: &serde_json::Map<String, Value>) -> Vec<UpstreamDatum> {
+    let Some(name) = string(table, &["name"]) else {
+        return vec![];
+    };
+    let mut ret = vec![];
+    if let Some(version) = lookup(table, &["version"]).and_then(|v| match v {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }) {
+        if version.starts_with(|c: char| c.is_ascii_digit()) {
+            ret.push(UpstreamDatum::Version(version));
+        }
+    }
+    if let Some(homepage) = url(table, &["homepage", "home", "website"]) {
+        ret.push(UpstreamDatum::Homepage(homepage));
+    }
+    if let Some(repository) = url(table, &["repository", "repo", "vcs", "source"]) {
+        ret.push(UpstreamDatum::Repository(repository));
+    }
+    if let Some(license) = string(table, &["license", "licence"]) {
+        ret.push(UpstreamDatum::License(license.into()));
+    }
+    if ret.is_empty() {
+        return vec![];
+    }
+    ret.insert(0, UpstreamDatum::Name(name.to_string()));
+    ret
+}
+
+/// Inspect top-level structured files that no dedicated provider reads.
+pub fn guess_from_unknown_manifests(
+    path: &Path,
+    settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let mut entries = std::fs::read_dir(path)?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|e| e.file_name());
+    let mut ret = vec![];
+    for entry in entries {
+        let filename = entry.file_name().to_string_lossy().to_string();
+        if filename.starts_with('.')
+            || IGNORED_FILES.contains(&filename.as_str())
+            || crate::descriptors::has_dedicated_provider(&filename)
+        {
+            continue;
+        }
+        let max_size = settings.max_file_size.map_or(MAX_SIZE, |m| m.min(MAX_SIZE));
+        if !entry
+            .metadata()
+            .is_ok_and(|m| m.is_file() && m.len() <= max_size)
+        {
+            continue;
+        }
+        let Some(Value::Object(document)) = parse(&entry.path()) else {
+            continue;
+        };
+        let mut data = sniff(&document);
+        if data.is_empty() {
+            if let Some(Value::Object(table)) = lookup(&document, METADATA_TABLES) {
+                data = sniff(table);
+            }
+        }
+        if !data.is_empty() {
+            log::debug!("Sniffed metadata from unknown manifest {}", filename);
+        }
+        ret.extend(data.into_iter().map(|datum| UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(Certainty::Possible),
+            origin: Some(entry.path().into()),
+        }));
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guess_from_unknown_manifests() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(
+            td.path().join("gleam.yaml"),
+            r#"name: blah
+version: 1.2.0
+licence: Apache-2.0
+repository:
+  type: github
+  url: https://github.com/example/blah
+"#,
+        )
+        .unwrap();
+        // Has a dedicated provider
+        std::fs::write(td.path().join("package.json"), r#"{"name": "other"}"#).unwrap();
+        // Just a name isn't enough
+        std::fs::write(td.path().join("settings.json"), r#"{"name": "config"}"#).unwrap();
+        std::fs::write(
+            td.path().join("package-lock.json"),
+            r#"{"name": "blah", "version": "1.2.0"}"#,
+        )
+        .unwrap();
+
+        let guessed = guess_from_unknown_manifests(td.path(), &GuesserSettings::default())
+            .unwrap()
+            .into_iter()
+            .map(|d| {
+                assert_eq!(d.certainty, Some(Certainty::Possible));
+                d.datum
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            guessed,
+            vec![
+                UpstreamDatum::Name("blah".to_string()),
+                UpstreamDatum::Version("1.2.0".to_string()),
+                UpstreamDatum::Repository("https://github.com/example/blah".to_string()),
+                UpstreamDatum::License("Apache-2.0".into()),
+            ]
+        );
+    }
+
+    #[cfg(any(feature = "cargo", feature = "julia"))]
+    #[test]
+    fn test_toml_package_table() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(
+            td.path().join("scarb.toml"),
+            r#"[package]
+name = "blah"
+version = "0.3.0"
+homepage = "https://example.com/blah"
+"#,
+        )
+        .unwrap();
+        let guessed = guess_from_unknown_manifests(td.path(), &GuesserSettings::default())
+            .unwrap()
+            .into_iter()
+            .map(|d| d.datum)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            guessed,
+            vec![
+                UpstreamDatum::Name("blah".to_string()),
+                UpstreamDatum::Version("0.3.0".to_string()),
+                UpstreamDatum::Homepage("https://example.com/blah".to_string()),
+            ]
+        );
+    }
+}
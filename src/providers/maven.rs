@@ -63,6 +63,21 @@ pub fn guess_from_pom_xml(
             }
         }
 
+        if let Some(organization_tag) = root.get_child("organization") {
+            if let Some(name) = organization_tag
+                .get_child("name")
+                .and_then(|t| t.get_text())
+            {
+                if !name.contains('$') {
+                    result.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::Organization(name.to_string()),
+                        certainty: Some(Certainty::Certain),
+                        origin: Some(path.into()),
+                    });
+                }
+            }
+        }
+
         if let Some(licenses_tag) = root.get_child("licenses") {
             for license_tag in licenses_tag
                 .children
@@ -73,7 +88,7 @@ pub fn guess_from_pom_xml(
                     if let Some(name_tag) = license_tag.get_child("name") {
                         if let Some(license_name) = name_tag.get_text() {
                             result.push(UpstreamDatumWithMetadata {
-                                datum: UpstreamDatum::License(license_name.to_string()),
+                                datum: UpstreamDatum::License(license_name.to_string().into()),
                                 certainty: Some(Certainty::Certain),
                                 origin: Some(path.into()),
                             });
@@ -64,7 +64,7 @@ pub fn guess_from_metadata_json(
             "license" => {
                 if let Some(license) = value.as_str() {
                     upstream_data.push(UpstreamDatumWithMetadata {
-                        datum: UpstreamDatum::License(license.to_string()),
+                        datum: UpstreamDatum::License(license.to_string().into()),
                         certainty: Some(Certainty::Certain),
                         origin: Some(path.into()),
                     });
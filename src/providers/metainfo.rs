@@ -13,6 +13,7 @@ pub fn guess_from_metainfo(
     let root = Element::parse(file).map_err(|e| ProviderError::ParseError(e.to_string()))?;
 
     let mut results: Vec<UpstreamDatumWithMetadata> = Vec::new();
+    let mut keywords: Vec<String> = Vec::new();
 
     for child in root.children {
         let child = if let Some(element) = child.as_element() {
@@ -29,7 +30,7 @@ pub fn guess_from_metainfo(
         }
         if child.name == "project_license" {
             results.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(child.get_text().unwrap().to_string()),
+                datum: UpstreamDatum::License(child.get_text().unwrap().to_string().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -48,6 +49,22 @@ pub fn guess_from_metainfo(
                         certainty: Some(Certainty::Certain),
                         origin: Some(path.into()),
                     });
+                } else if urltype == "translate" {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::Translations(child.get_text().unwrap().to_string()),
+                        certainty: Some(Certainty::Certain),
+                        origin: Some(path.into()),
+                    });
+                } else if urltype == "contact" {
+                    // Contact URLs can also be e.g. web forms or mailing lists
+                    let url = child.get_text().unwrap().to_string();
+                    if url.parse().is_ok_and(|u: url::Url| crate::is_chat_url(&u)) {
+                        results.push(UpstreamDatumWithMetadata {
+                            datum: UpstreamDatum::Chat(url),
+                            certainty: Some(Certainty::Certain),
+                            origin: Some(path.into()),
+                        });
+                    }
                 }
             }
         }
@@ -65,6 +82,34 @@ pub fn guess_from_metainfo(
                 origin: Some(path.into()),
             });
         }
+        if child.name == "keywords" {
+            for keyword in child.children.iter().filter_map(|c| c.as_element()) {
+                if keyword.name == "keyword" && !keyword.attributes.contains_key("lang") {
+                    if let Some(text) = keyword.get_text() {
+                        let text = text.trim().to_string();
+                        if !keywords.contains(&text) {
+                            keywords.push(text);
+                        }
+                    }
+                }
+            }
+        }
+        if child.name == "categories" {
+            let categories = child
+                .children
+                .iter()
+                .filter_map(|c| c.as_element())
+                .filter(|c| c.name == "category")
+                .filter_map(|c| c.get_text().map(|t| t.to_string()))
+                .collect::<Vec<_>>();
+            for keyword in
+                crate::keywords::keywords_from(&categories, crate::keywords::Vocabulary::AppStream)
+            {
+                if !keywords.contains(&keyword) {
+                    keywords.push(keyword);
+                }
+            }
+        }
         if child.name == "name" {
             results.push(UpstreamDatumWithMetadata {
                 datum: UpstreamDatum::Name(child.get_text().unwrap().to_string()),
@@ -74,5 +119,13 @@ pub fn guess_from_metainfo(
         }
     }
 
+    if !keywords.is_empty() {
+        results.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Keywords(keywords),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        });
+    }
+
     Ok(results)
 }
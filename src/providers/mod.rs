@@ -1,18 +1,29 @@
 pub mod arch;
 pub mod authors;
 pub mod autoconf;
+pub mod community;
 pub mod composer_json;
+pub mod conda;
 pub mod debian;
 pub mod doap;
+pub mod elixir;
+pub mod flatpak;
+pub mod funding;
 pub mod git;
 pub mod go;
 pub mod gobo;
+pub mod gradle;
 pub mod haskell;
+pub mod haxe;
+#[cfg(feature = "julia")]
+pub mod julia;
 pub mod launchpad;
+pub mod manifest;
 pub mod maven;
 pub mod meson;
 pub mod metadata_json;
 pub mod metainfo;
+pub mod nix;
 pub mod node;
 pub mod nuspec;
 #[cfg(feature = "opam")]
@@ -28,7 +39,11 @@ pub mod r;
 pub mod repology;
 pub mod ruby;
 pub mod rust;
+pub mod sbt;
 pub mod security_md;
+pub mod shell;
+pub mod source_headers;
+pub mod transifex;
 pub mod waf;
 
 use crate::{Certainty, GuesserSettings, UpstreamDatum, UpstreamDatumWithMetadata};
@@ -50,18 +65,12 @@ pub async fn guess_from_install(
         let oline = oline?;
         let line = oline.trim();
         let mut cmdline = line.trim().trim_start_matches('$').trim().to_string();
-        if cmdline.starts_with("git clone ") || cmdline.starts_with("fossil clone ") {
+        if crate::vcs_command::is_vcs_command(&cmdline) {
             while cmdline.ends_with('\\') {
                 cmdline.push_str(lines.next().unwrap()?.trim());
                 cmdline = cmdline.trim().to_string();
             }
-            if let Some(url) = if cmdline.starts_with("git clone ") {
-                crate::vcs_command::url_from_git_clone_command(cmdline.as_bytes())
-            } else if cmdline.starts_with("fossil clone ") {
-                crate::vcs_command::url_from_fossil_clone_command(cmdline.as_bytes())
-            } else {
-                None
-            } {
+            if let Some(url) = crate::vcs_command::url_from_vcs_command(cmdline.as_bytes()) {
                 urls.push(url);
             }
         }
@@ -115,5 +124,41 @@ pub async fn guess_from_install(
             }
         }
     }
+
+    // Prefer public URLs over ones that require authentication
+    urls.sort_by_key(|url| url.contains("ssh"));
+
+    if let Some(url) = urls.into_iter().next() {
+        ret.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Repository(url),
+            certainty: Some(Certainty::Possible),
+            origin: Some(path.into()),
+        });
+    }
     Ok(ret)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_guess_from_install_hg_clone() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("INSTALL");
+        std::fs::write(
+            &path,
+            "To get the latest version, run:\n\n  $ hg clone https://hg.example.com/foo\n",
+        )
+        .unwrap();
+        let ret = guess_from_install(&path, &GuesserSettings::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![UpstreamDatum::Repository(
+                "https://hg.example.com/foo".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,442 @@
+//! Nix flakes and nixpkgs-style derivations.
+//!
+//! Nix expressions can't be evaluated without a Nix implementation, so this uses a tolerant
+//! tokenizer and only looks at literal values of well-known attributes.
+//!
+//! See https://nixos.org/manual/nixpkgs/stable/#chap-meta
+
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Identifier or attribute path, e.g. "lib.licenses.mit"
+    Ident(String),
+    /// String literal; None if it contains interpolations
+    Str(Option<String>),
+    Punct(char),
+    Other,
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = None;
+                for c in chars.by_ref() {
+                    if prev == Some('*') && c == '/' {
+                        break;
+                    }
+                    prev = Some(c);
+                }
+            }
+            '"' => {
+                let mut s = String::new();
+                let mut interpolated = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(c) => s.push(c),
+                            None => break,
+                        },
+                        '$' if chars.peek() == Some(&'{') => {
+                            interpolated = true;
+                            s.push(c);
+                        }
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(if interpolated { None } else { Some(s) }));
+            }
+            '\'' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                let mut s = String::new();
+                let mut interpolated = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\'' if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            // ''' and ''$ are escapes
+                            match chars.peek() {
+                                Some('\'') | Some('$') => s.push(chars.next().unwrap()),
+                                _ => break,
+                            }
+                        }
+                        '$' if chars.peek() == Some(&'{') => {
+                            interpolated = true;
+                            s.push(c);
+                        }
+                        c => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(if interpolated {
+                    None
+                } else {
+                    Some(dedent(&s))
+                }));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut s = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' || c == '-' || c == '\'' || c == '.' {
+                        s.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Ident(s));
+            }
+            '{' | '}' | '[' | ']' | '(' | ')' | '=' | ';' => tokens.push(Token::Punct(c)),
+            _ => tokens.push(Token::Other),
+        }
+    }
+    tokens
+}
+
+/// Strip the common indentation of an indented ('' ... '') string, like Nix does.
+fn dedent(s: &str) -> String {
+    let s = s.strip_prefix('\n').unwrap_or(s);
+    let indent = s
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    s.lines()
+        .map(|l| l.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/// The value of an attribute, as far as it can be determined without evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Str(String),
+    Ident(String),
+    List(Vec<Value>),
+    /// Token range of an attribute set, excluding the braces
+    AttrSet(usize, usize),
+    Unknown,
+}
+
+/// Find the index of the token that closes the bracket opened at `start`.
+fn find_closing(tokens: &[Token], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Punct('{') | Token::Punct('[') | Token::Punct('(') => depth += 1,
+            Token::Punct('}') | Token::Punct(']') | Token::Punct(')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Skip "with lib;" clauses, which bring attributes into scope for the following expression.
+fn skip_with(tokens: &[Token], mut start: usize) -> usize {
+    while let [Token::Ident(w), Token::Ident(_), Token::Punct(';'), ..] = &tokens[start..] {
+        if w != "with" {
+            break;
+        }
+        start += 3;
+    }
+    start
+}
+
+/// Parse the value starting at `start`, up to the terminating ';'.
+fn parse_value(tokens: &[Token], start: usize) -> (Value, usize) {
+    let start = skip_with(tokens, start);
+    let end = {
+        let mut i = start;
+        while i < tokens.len() && tokens[i] != Token::Punct(';') {
+            match tokens[i] {
+                Token::Punct('{') | Token::Punct('[') | Token::Punct('(') => {
+                    i = find_closing(tokens, i).unwrap_or(tokens.len());
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        i.min(tokens.len())
+    };
+    let value = match &tokens[start..end] {
+        [Token::Str(Some(s))] => Value::Str(s.clone()),
+        [Token::Ident(i)] => Value::Ident(i.clone()),
+        [Token::Punct('{'), .., Token::Punct('}')] => Value::AttrSet(start + 1, end - 1),
+        [Token::Punct('['), inner @ .., Token::Punct(']')] => Value::List(
+            inner
+                .iter()
+                .map(|t| match t {
+                    Token::Str(Some(s)) => Value::Str(s.clone()),
+                    Token::Ident(i) => Value::Ident(i.clone()),
+                    _ => Value::Unknown,
+                })
+                .collect(),
+        ),
+        _ => Value::Unknown,
+    };
+    (value, end)
+}
+
+/// Iterate over the attributes in the token range of an attribute set.
+fn attributes(tokens: &[Token], start: usize, end: usize) -> Vec<(String, Value)> {
+    let mut ret = Vec::new();
+    let mut i = start;
+    while i < end {
+        match (&tokens[i], tokens.get(i + 1)) {
+            (Token::Ident(name), Some(Token::Punct('='))) => {
+                let (value, value_end) = parse_value(&tokens[..end], i + 2);
+                ret.push((name.clone(), value));
+                i = value_end + 1;
+            }
+            (Token::Punct('{'), _) | (Token::Punct('['), _) | (Token::Punct('('), _) => {
+                i = find_closing(tokens, i).map_or(end, |j| j + 1);
+            }
+            _ => i += 1,
+        }
+    }
+    ret
+}
+
+/// Find the first `meta = { ... }` attribute set anywhere in the expression.
+fn find_meta(tokens: &[Token]) -> Option<Vec<(String, Value)>> {
+    tokens.windows(2).enumerate().find_map(|(i, w)| match w {
+        [Token::Ident(name), Token::Punct('=')] if name == "meta" => {
+            match parse_value(tokens, i + 2).0 {
+                Value::AttrSet(start, end) => Some(attributes(tokens, start, end)),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/// Map a nixpkgs license attribute to an SPDX identifier.
+fn nixpkgs_license(attr: &str) -> String {
+    let name = attr.rsplit('.').next().unwrap_or(attr);
+    match name {
+        "mit" => "MIT",
+        "asl20" => "Apache-2.0",
+        "bsd2" => "BSD-2-Clause",
+        "bsd3" => "BSD-3-Clause",
+        "isc" => "ISC",
+        "mpl20" => "MPL-2.0",
+        "zlib" => "Zlib",
+        "unlicense" => "Unlicense",
+        "cc0" => "CC0-1.0",
+        "artistic2" => "Artistic-2.0",
+        "gpl2Only" | "gpl2" => "GPL-2.0-only",
+        "gpl2Plus" => "GPL-2.0-or-later",
+        "gpl3Only" | "gpl3" => "GPL-3.0-only",
+        "gpl3Plus" => "GPL-3.0-or-later",
+        "lgpl21Only" | "lgpl21" => "LGPL-2.1-only",
+        "lgpl21Plus" => "LGPL-2.1-or-later",
+        "lgpl3Only" | "lgpl3" => "LGPL-3.0-only",
+        "lgpl3Plus" => "LGPL-3.0-or-later",
+        "agpl3Only" | "agpl3" => "AGPL-3.0-only",
+        "agpl3Plus" => "AGPL-3.0-or-later",
+        name => name,
+    }
+    .to_string()
+}
+
+fn license_from_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Ident(attr) => Some(nixpkgs_license(attr)),
+        // A few packages set the SPDX identifier directly
+        Value::Str(s) => Some(s.clone()),
+        Value::List(licenses) => {
+            let licenses = licenses
+                .iter()
+                .map(license_from_value)
+                .collect::<Option<Vec<_>>>()?;
+            // nixpkgs doesn't specify how multiple licenses combine, so assume all apply
+            Some(licenses.join(" AND "))
+        }
+        _ => None,
+    }
+}
+
+fn guess_from_meta(
+    meta: &[(String, Value)],
+    path: &Path,
+    certainty: Certainty,
+) -> Vec<UpstreamDatumWithMetadata> {
+    let mut results = Vec::new();
+    for (name, value) in meta {
+        let datum = match (name.as_str(), value) {
+            ("description", Value::Str(s)) => UpstreamDatum::Summary(s.clone()),
+            ("longDescription", Value::Str(s)) => UpstreamDatum::Description(s.clone()),
+            ("homepage", Value::Str(s)) => UpstreamDatum::Homepage(s.clone()),
+            ("changelog", Value::Str(s)) => UpstreamDatum::Changelog(s.clone()),
+            ("license", value) => match license_from_value(value) {
+                Some(license) => UpstreamDatum::License(license.into()),
+                None => continue,
+            },
+            _ => continue,
+        };
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        });
+    }
+    results
+}
+
+pub fn guess_from_flake_nix(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let tokens = tokenize(&text);
+
+    let mut results = Vec::new();
+
+    if tokens.first() == Some(&Token::Punct('{')) {
+        if let Some(end) = find_closing(&tokens, 0) {
+            for (name, value) in attributes(&tokens, 1, end) {
+                if let ("description", Value::Str(s)) = (name.as_str(), value) {
+                    results.push(UpstreamDatumWithMetadata {
+                        datum: UpstreamDatum::Summary(s),
+                        certainty: Some(Certainty::Certain),
+                        origin: Some(path.into()),
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some(meta) = find_meta(&tokens) {
+        results.extend(guess_from_meta(&meta, path, Certainty::Likely));
+    }
+
+    Ok(results)
+}
+
+pub fn guess_from_default_nix(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let tokens = tokenize(&text);
+
+    Ok(match find_meta(&tokens) {
+        Some(meta) => guess_from_meta(&meta, path, Certainty::Likely),
+        None => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flake_nix() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("flake.nix");
+        std::fs::write(
+            &path,
+            r#"{
+  description = "A fast \"grep\" replacement";
+
+  inputs.nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
+
+  outputs = { self, nixpkgs }: {
+    packages.x86_64-linux.default = nixpkgs.legacyPackages.x86_64-linux.stdenv.mkDerivation {
+      pname = "fgrep";
+      version = "1.0";
+      src = ./.;
+      # Not the real homepage
+      meta = with nixpkgs.lib; {
+        homepage = "https://example.com/fgrep";
+        description = "Fast grep";
+        longDescription = ''
+          fgrep searches files.
+
+          It is fast.
+        '';
+        license = with licenses; [ mit asl20 ];
+        maintainers = [ ];
+        changelog = "https://example.com/fgrep/${version}/NEWS";
+      };
+    };
+  };
+}
+"#,
+        )
+        .unwrap();
+        let ret = guess_from_flake_nix(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Summary("A fast \"grep\" replacement".to_string()),
+                UpstreamDatum::Homepage("https://example.com/fgrep".to_string()),
+                UpstreamDatum::Summary("Fast grep".to_string()),
+                UpstreamDatum::Description("fgrep searches files.\n\nIt is fast.".to_string()),
+                UpstreamDatum::License("MIT AND Apache-2.0".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_nix() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("default.nix");
+        std::fs::write(
+            &path,
+            r#"{ lib, stdenv, fetchurl }:
+
+stdenv.mkDerivation rec {
+  pname = "hello";
+  version = "2.12.1";
+
+  src = fetchurl {
+    url = "mirror://gnu/hello/hello-${version}.tar.gz";
+  };
+
+  /* meta information */
+  meta = {
+    description = "Program that produces a familiar, friendly greeting";
+    homepage = "https://www.gnu.org/software/hello/";
+    license = lib.licenses.gpl3Plus;
+  };
+}
+"#,
+        )
+        .unwrap();
+        let ret = guess_from_default_nix(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            ret.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Summary(
+                    "Program that produces a familiar, friendly greeting".to_string()
+                ),
+                UpstreamDatum::Homepage("https://www.gnu.org/software/hello/".to_string()),
+                UpstreamDatum::License("GPL-3.0-or-later".into()),
+            ]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ProviderError, UpstreamDatum, UpstreamDatumWithMetadata, UpstreamMetadata};
+use crate::{Certainty, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata, UpstreamMetadata};
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -38,6 +38,8 @@ pub struct NpmVersion {
     pub keywords: Option<Vec<String>>,
     #[serde(rename = "license")]
     pub license: Option<String>,
+    /// Deprecation message, set with `npm deprecate`
+    pub deprecated: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -89,10 +91,12 @@ pub struct NpmPackage {
     #[serde(rename = "_rev")]
     pub rev: String,
     pub name: String,
+    #[serde(default)]
     pub description: String,
     #[serde(rename = "dist-tags")]
     pub dist_tags: HashMap<String, String>,
     pub versions: HashMap<String, NpmVersion>,
+    #[serde(default)]
     pub readme: String,
     pub maintainers: Vec<NpmPerson>,
     pub time: HashMap<String, String>,
@@ -169,7 +173,7 @@ impl TryInto<UpstreamMetadata> for NpmPackage {
 
         if let Some(license) = self.license {
             metadata.insert(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license),
+                datum: UpstreamDatum::License(license.into()),
                 certainty: None,
                 origin: None,
             });
@@ -219,7 +223,9 @@ pub async fn load_npm_package(package: &str) -> Result<Option<NpmPackage>, crate
         .parse()
         .unwrap();
     let data = crate::load_json_url(&http_url, None).await?;
-    Ok(serde_json::from_value(data).unwrap())
+    serde_json::from_value(data)
+        .map(Some)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid npm package data: {}", e)))
 }
 
 pub async fn remote_npm_metadata(package: &str) -> Result<UpstreamMetadata, ProviderError> {
@@ -231,6 +237,136 @@ pub async fn remote_npm_metadata(package: &str) -> Result<UpstreamMetadata, Prov
     }
 }
 
+fn parse_npm_package(package: &NpmPackage) -> Vec<UpstreamDatum> {
+    let mut results = vec![UpstreamDatum::Name(package.name.clone())];
+
+    if let Some(homepage) = package.homepage.as_ref() {
+        results.push(UpstreamDatum::Homepage(homepage.clone()));
+    }
+
+    if let Some(repository) = package.repository.as_ref() {
+        let url = match url::Url::parse(&repository.url) {
+            Ok(url) => crate::vcs::drop_vcs_in_scheme(&url)
+                .unwrap_or(url)
+                .to_string(),
+            Err(_) => repository.url.clone(),
+        };
+        results.push(UpstreamDatum::Repository(url));
+    }
+
+    if let Some(bugs) = package.bugs.as_ref() {
+        results.push(UpstreamDatum::BugDatabase(bugs.url.clone()));
+    }
+
+    if let Some(license) = package.license.as_ref() {
+        results.push(UpstreamDatum::License(license.clone().into()));
+    }
+
+    if let Some(version) = package
+        .dist_tags
+        .get("latest")
+        .and_then(|v| package.versions.get(v))
+    {
+        results.push(UpstreamDatum::Version(version.version.clone()));
+        if let Some(deprecated) = version.deprecated.as_ref() {
+            results.push(UpstreamDatum::MaintenanceStatus(if deprecated.is_empty() {
+                "deprecated".to_string()
+            } else {
+                format!("deprecated: {}", deprecated)
+            }));
+        }
+    }
+
+    if let Some(maintainer) = package.maintainers.first() {
+        results.push(UpstreamDatum::Maintainer(crate::Person {
+            name: Some(maintainer.name.clone()),
+            email: Some(maintainer.email.clone()),
+            url: None,
+        }));
+    }
+
+    results
+}
+
+/// Find the name under which the package in a package.json file is published on npm.
+///
+/// Returns `None` for private packages, which are never published.
+pub fn npm_package_name(path: &std::path::Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    let package: serde_json::Value = serde_json::from_reader(file).ok()?;
+    if package.get("private").and_then(|p| p.as_bool()) == Some(true) {
+        return None;
+    }
+    // Unity packages are not published on npm
+    if package
+        .as_object()
+        .is_some_and(crate::providers::package_json::is_upm_package)
+    {
+        return None;
+    }
+    package
+        .get("name")
+        .and_then(|n| n.as_str())
+        .map(|n| n.to_string())
+}
+
+pub struct Npm;
+
+impl Default for Npm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Npm {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Npm {
+    fn name(&self) -> &'static str {
+        "npm"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "Bug-Database",
+            "License",
+            "Version",
+            "Maintainer",
+            "Maintenance-Status",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        let package = match load_npm_package(name).await {
+            Ok(Some(package)) => package,
+            Ok(None) => return Ok(Vec::new()),
+            Err(ProviderError::HttpJsonError(crate::HTTPJSONError::Error {
+                status: 404, ..
+            })) => {
+                log::warn!("Package {} not found on npm", name);
+                return Ok(Vec::new());
+            }
+            Err(ProviderError::ParseError(e)) => {
+                log::warn!("Unable to parse npm data for {}: {}", name, e);
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(parse_npm_package(&package))
+    }
+}
+
 #[cfg(test)]
 mod npm_tests {
     use super::*;
@@ -243,4 +379,41 @@ mod npm_tests {
 
         assert_eq!(npm_data.name, "leftpad");
     }
+
+    #[test]
+    fn test_parse_npm_package() {
+        let data = include_str!(".././testdata/npm.json");
+
+        let npm_data: NpmPackage = serde_json::from_str(data).unwrap();
+
+        assert_eq!(
+            parse_npm_package(&npm_data),
+            vec![
+                UpstreamDatum::Name("leftpad".to_string()),
+                UpstreamDatum::Homepage("https://github.com/tmcw/leftpad#readme".to_string()),
+                UpstreamDatum::Repository("https://github.com/tmcw/leftpad.git".to_string()),
+                UpstreamDatum::BugDatabase("https://github.com/tmcw/leftpad/issues".to_string()),
+                UpstreamDatum::License("BSD-3-Clause".into()),
+                UpstreamDatum::Version("0.0.1".to_string()),
+                UpstreamDatum::MaintenanceStatus(
+                    "deprecated: Use the built-in String.padStart function instead".to_string()
+                ),
+                UpstreamDatum::Maintainer(crate::Person {
+                    name: Some("tmcw".to_string()),
+                    email: Some("tom@macwright.org".to_string()),
+                    url: None,
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_npm_package_name() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("package.json");
+        std::fs::write(&path, r#"{"name": "leftpad", "version": "0.0.1"}"#).unwrap();
+        assert_eq!(npm_package_name(&path), Some("leftpad".to_string()));
+        std::fs::write(&path, r#"{"name": "internal", "private": true}"#).unwrap();
+        assert_eq!(npm_package_name(&path), None);
+    }
 }
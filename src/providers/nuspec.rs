@@ -1,5 +1,6 @@
 use crate::xmlparse_simplify_namespaces;
 use crate::{Certainty, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use serde::Deserialize;
 use std::path::Path;
 
 // Documentation: https://docs.microsoft.com/en-us/nuget/reference/nuspec
@@ -83,7 +84,7 @@ pub async fn guess_from_nuspec(
     if let Some(license_tag) = metadata.get_child("license") {
         if let Some(license) = license_tag.get_text() {
             result.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license.into_owned()),
+                datum: UpstreamDatum::License(license.into_owned().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -100,6 +101,21 @@ pub async fn guess_from_nuspec(
         }
     }
 
+    if let Some(id_tag) = metadata.get_child("id") {
+        if let Some(id) = id_tag.get_text() {
+            result.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Name(id.into_owned()),
+                certainty: Some(Certainty::Certain),
+                origin: Some(path.into()),
+            });
+            result.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Archive("NuGet".to_string()),
+                certainty: Some(Certainty::Likely),
+                origin: Some(path.into()),
+            });
+        }
+    }
+
     if let Some(title_tag) = metadata.get_child("title") {
         if let Some(title) = title_tag.get_text() {
             result.push(UpstreamDatumWithMetadata {
@@ -139,3 +155,165 @@ pub async fn guess_from_nuspec(
 
     Ok(result)
 }
+
+#[derive(Deserialize)]
+pub struct NuGetCatalogEntry {
+    pub id: String,
+    pub version: String,
+    #[serde(rename = "projectUrl")]
+    pub project_url: Option<String>,
+    #[serde(rename = "licenseUrl")]
+    pub license_url: Option<String>,
+    #[serde(rename = "licenseExpression")]
+    pub license_expression: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NuGetRegistrationLeaf {
+    #[serde(rename = "catalogEntry")]
+    pub catalog_entry: NuGetCatalogEntry,
+}
+
+#[derive(Deserialize)]
+pub struct NuGetRegistrationPage {
+    #[serde(rename = "@id")]
+    pub id: String,
+    /// Leaves are only inlined for packages with few versions
+    pub items: Option<Vec<NuGetRegistrationLeaf>>,
+}
+
+#[derive(Deserialize)]
+pub struct NuGetRegistrationIndex {
+    pub items: Vec<NuGetRegistrationPage>,
+}
+
+/// Find the latest stable version in a registration page, falling back to the latest pre-release.
+fn latest_catalog_entry(leaves: &[NuGetRegistrationLeaf]) -> Option<&NuGetCatalogEntry> {
+    // Leaves are ordered by version
+    leaves
+        .iter()
+        .rev()
+        .map(|l| &l.catalog_entry)
+        .find(|e| !e.version.contains('-'))
+        .or_else(|| leaves.last().map(|l| &l.catalog_entry))
+}
+
+async fn parse_nuget_catalog_entry(entry: &NuGetCatalogEntry) -> Vec<UpstreamDatum> {
+    let mut results = vec![
+        UpstreamDatum::Name(entry.id.clone()),
+        UpstreamDatum::Version(entry.version.clone()),
+    ];
+
+    if let Some(project_url) = entry.project_url.as_ref().filter(|u| !u.is_empty()) {
+        results.push(UpstreamDatum::Homepage(project_url.clone()));
+        if let Ok(url) = url::Url::parse(project_url) {
+            if let Some(repo_url) = crate::vcs::guess_repo_from_url(&url, Some(false)).await {
+                results.push(UpstreamDatum::Repository(repo_url));
+            }
+        }
+    }
+
+    if let Some(license) = entry.license_expression.as_ref().filter(|l| !l.is_empty()) {
+        results.push(UpstreamDatum::License(license.clone().into()));
+    } else if let Some(license) = entry
+        .license_url
+        .as_deref()
+        .and_then(|u| u.strip_prefix("https://licenses.nuget.org/"))
+    {
+        results.push(UpstreamDatum::License(license.to_string().into()));
+    }
+
+    results
+}
+
+pub async fn guess_from_nuget(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!(
+        "https://api.nuget.org/v3/registration5-semver1/{}/index.json",
+        package.to_lowercase()
+    )
+    .parse()
+    .unwrap();
+    let data = match crate::load_json_url(&url, None).await {
+        Ok(data) => data,
+        Err(crate::HTTPJSONError::Error { status: 404, .. }) => {
+            log::warn!("Package {} not found on NuGet", package);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let invalid = |e: serde_json::Error| {
+        ProviderError::ParseError(format!("Invalid NuGet registration data: {}", e))
+    };
+    let index: NuGetRegistrationIndex = serde_json::from_value(data).map_err(invalid)?;
+    let page = match index.items.into_iter().last() {
+        Some(page) => page,
+        None => return Ok(Vec::new()),
+    };
+    let leaves = match page.items {
+        Some(leaves) => leaves,
+        None => {
+            let data = crate::load_json_url(&page.id.parse().unwrap(), None).await?;
+            let page: NuGetRegistrationPage = serde_json::from_value(data).map_err(invalid)?;
+            page.items.unwrap_or_default()
+        }
+    };
+    Ok(match latest_catalog_entry(&leaves) {
+        Some(entry) => parse_nuget_catalog_entry(entry).await,
+        None => Vec::new(),
+    })
+}
+
+pub struct NuGet;
+
+impl Default for NuGet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NuGet {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for NuGet {
+    fn name(&self) -> &'static str {
+        "NuGet"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &["Name", "Version", "Homepage", "Repository", "License"][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_nuget(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_parse_nuget_registration() {
+        let data = include_str!("../testdata/nuget-registration.json");
+        let index: NuGetRegistrationIndex = serde_json::from_str(data).unwrap();
+        let leaves = index.items.into_iter().last().unwrap().items.unwrap();
+        let entry = latest_catalog_entry(&leaves).unwrap();
+        assert_eq!(
+            parse_nuget_catalog_entry(entry).await,
+            vec![
+                UpstreamDatum::Name("Newtonsoft.Json".to_string()),
+                UpstreamDatum::Version("13.0.3".to_string()),
+                UpstreamDatum::Homepage("https://www.newtonsoft.com/json".to_string()),
+                UpstreamDatum::License("MIT".into()),
+            ]
+        );
+    }
+}
@@ -53,7 +53,7 @@ pub fn guess_from_opam(
                     }
                 };
                 results.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(value),
+                    datum: UpstreamDatum::License(value.into()),
                     certainty: Some(Certainty::Confident),
                     origin: Some(path.into()),
                 });
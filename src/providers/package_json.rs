@@ -5,6 +5,23 @@ use log::error;
 use std::path::Path;
 use url::Url;
 
+/// Check whether a package.json describes a Unity Package Manager (UPM) package.
+///
+/// UPM packages use the package.json filename too, but have reverse-domain names
+/// (e.g. "com.example.foo") and declare the Unity version that they support.
+///
+/// See <https://docs.unity3d.com/Manual/upm-manifestPkg.html>
+pub fn is_upm_package(package: &serde_json::Map<String, serde_json::Value>) -> bool {
+    if package.contains_key("unity") || package.contains_key("unityRelease") {
+        return true;
+    }
+    package.contains_key("displayName")
+        && package
+            .get("name")
+            .and_then(serde_json::Value::as_str)
+            .is_some_and(|n| n.starts_with("com.") && !n.contains('/'))
+}
+
 pub fn guess_from_package_json(
     path: &Path,
     _settings: &GuesserSettings,
@@ -25,8 +42,23 @@ pub fn guess_from_package_json(
         }
     };
 
+    let upm = is_upm_package(&package);
+
     for (field, value) in package {
         match field.as_str() {
+            "name" if upm => {
+                // The reverse-domain identifier; displayName is the human name
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Name(value.as_str().unwrap().to_string()),
+                    certainty: Some(Certainty::Likely),
+                    origin: Some(path.into()),
+                });
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Aliases(vec![value.as_str().unwrap().to_string()]),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
             "name" => {
                 upstream_data.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::Name(value.as_str().unwrap().to_string()),
@@ -34,6 +66,38 @@ pub fn guess_from_package_json(
                     origin: Some(path.into()),
                 });
             }
+            "displayName" if upm => {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Name(value.as_str().unwrap().to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
+            "documentationUrl" if upm => {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Documentation(value.as_str().unwrap().to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
+            "changelogUrl" if upm => {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Changelog(value.as_str().unwrap().to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+            }
+            "licensesUrl" if upm => {
+                // Only a link to the license text; an SPDX identifier in "license" is preferred
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::License(value.as_str().unwrap().to_string().into()),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+            "unity" | "unityRelease" | "samples" | "hideInEditor" | "type" if upm => {
+                // Unity-specific fields that don't describe the upstream project
+            }
             "homepage" => {
                 upstream_data.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::Homepage(value.as_str().unwrap().to_string()),
@@ -50,7 +114,7 @@ pub fn guess_from_package_json(
             }
             "license" => {
                 upstream_data.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(value.as_str().unwrap().to_string()),
+                    datum: UpstreamDatum::License(value.as_str().unwrap().to_string().into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 });
@@ -146,7 +210,7 @@ pub fn guess_from_package_json(
                 }
             }
             "author" => {
-                if let Some(author) = value.as_object() {
+                let person = if let Some(author) = value.as_object() {
                     let name = author
                         .get("name")
                         .and_then(serde_json::Value::as_str)
@@ -159,21 +223,34 @@ pub fn guess_from_package_json(
                         .get("email")
                         .and_then(serde_json::Value::as_str)
                         .map(String::from);
-                    let person = Person { name, url, email };
+                    Person { name, url, email }
+                } else if let Some(author) = value.as_str() {
+                    Person::from(author)
+                } else {
+                    error!("Unsupported type for author in package.json: {:?}", value);
+                    continue;
+                };
+                if person.is_organization() {
                     upstream_data.push(UpstreamDatumWithMetadata {
-                        datum: UpstreamDatum::Author(vec![person]),
-                        certainty: Some(Certainty::Confident),
+                        datum: UpstreamDatum::Organization(person.name.clone().unwrap()),
+                        certainty: Some(Certainty::Likely),
                         origin: Some(path.into()),
                     });
-                } else if let Some(author) = value.as_str() {
-                    let person = Person::from(author);
+                }
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Author(vec![person]),
+                    certainty: Some(Certainty::Confident),
+                    origin: Some(path.into()),
+                });
+            }
+            "chat" | "discord" | "gitter" | "irc" | "matrix" | "slack" => {
+                // Not part of the npm schema, but used by some projects
+                if let Some(url) = value.as_str() {
                     upstream_data.push(UpstreamDatumWithMetadata {
-                        datum: UpstreamDatum::Author(vec![person]),
-                        certainty: Some(Certainty::Confident),
+                        datum: UpstreamDatum::Chat(url.to_string()),
+                        certainty: Some(Certainty::Likely),
                         origin: Some(path.into()),
                     });
-                } else {
-                    error!("Unsupported type for author in package.json: {:?}", value);
                 }
             }
             "dependencies" | "private" | "devDependencies" | "scripts" | "files" | "main" => {
@@ -230,7 +307,7 @@ mod package_json_tests {
                     origin: Some(path.clone().into()),
                 },
                 UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License("MPL-2.0".to_string()),
+                    datum: UpstreamDatum::License("MPL-2.0".into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.clone().into())
                 },
@@ -242,4 +319,48 @@ mod package_json_tests {
             ]
         );
     }
+
+    #[test]
+    fn test_upm() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("package.json");
+
+        std::fs::write(
+            &path,
+            r#"{
+  "name": "com.example.foo",
+  "version": "1.2.0",
+  "displayName": "Foo",
+  "description": "Does foo things",
+  "unity": "2021.3",
+  "documentationUrl": "https://example.com/foo/docs",
+  "changelogUrl": "https://example.com/foo/changelog",
+  "licensesUrl": "https://example.com/foo/license"
+}
+"#,
+        )
+        .unwrap();
+        let ret = guess_from_package_json(&path, &GuesserSettings::default()).unwrap();
+        let mut metadata = crate::UpstreamMetadata::new();
+        metadata.update(ret.into_iter());
+        assert_eq!(metadata.name(), Some("Foo"));
+        assert_eq!(
+            metadata.aliases(),
+            Some(&vec!["com.example.foo".to_string()])
+        );
+        assert_eq!(metadata.version(), Some("1.2.0"));
+        assert_eq!(
+            metadata.documentation(),
+            Some("https://example.com/foo/docs")
+        );
+        assert_eq!(
+            metadata.get("Changelog").and_then(|d| d.datum.as_str()),
+            Some("https://example.com/foo/changelog")
+        );
+        assert_eq!(
+            metadata.get("License").and_then(|d| d.datum.as_str()),
+            Some("https://example.com/foo/license")
+        );
+        assert_eq!(crate::providers::node::npm_package_name(&path), None);
+    }
 }
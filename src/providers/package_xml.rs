@@ -21,6 +21,8 @@ pub fn guess_from_package_xml(
     assert_eq!(root.name, "package", "root tag is {:?}", root.name);
 
     let mut upstream_data: Vec<UpstreamDatumWithMetadata> = Vec::new();
+    let mut name: Option<String> = None;
+    let mut channel: Option<String> = None;
     let mut leads: Vec<&Element> = Vec::new();
     let mut maintainers: Vec<&Element> = Vec::new();
     let mut authors: Vec<&Element> = Vec::new();
@@ -29,12 +31,16 @@ pub fn guess_from_package_xml(
         if let XMLNode::Element(ref element) = child_element {
             match element.name.as_str() {
                 "name" => {
+                    name = Some(element.get_text().unwrap().to_string());
                     upstream_data.push(UpstreamDatumWithMetadata {
                         datum: UpstreamDatum::Name(element.get_text().unwrap().to_string()),
                         certainty: Some(Certainty::Certain),
                         origin: Some(path.into()),
                     });
                 }
+                "channel" => {
+                    channel = element.get_text().map(|c| c.trim().to_string());
+                }
                 "summary" => {
                     upstream_data.push(UpstreamDatumWithMetadata {
                         datum: UpstreamDatum::Summary(element.get_text().unwrap().to_string()),
@@ -62,7 +68,9 @@ pub fn guess_from_package_xml(
                 }
                 "license" => {
                     upstream_data.push(UpstreamDatumWithMetadata {
-                        datum: UpstreamDatum::License(element.get_text().unwrap().to_string()),
+                        datum: UpstreamDatum::License(
+                            element.get_text().unwrap().to_string().into(),
+                        ),
                         certainty: Some(Certainty::Certain),
                         origin: Some(path.into()),
                     });
@@ -101,9 +109,8 @@ pub fn guess_from_package_xml(
                 "author" => {
                     authors.push(element);
                 }
-                "stability" | "dependencies" | "providesextension" | "extsrcrelease"
-                | "channel" | "notes" | "contents" | "date" | "time" | "depend" | "exec_depend"
-                | "buildtool_depend" => {
+                "stability" | "dependencies" | "providesextension" | "extsrcrelease" | "notes"
+                | "contents" | "date" | "time" | "depend" | "exec_depend" | "buildtool_depend" => {
                     // Do nothing, skip these fields
                 }
                 _ => {
@@ -113,6 +120,35 @@ pub fn guess_from_package_xml(
         }
     }
 
+    // PEAR package.xml files name the channel the package is published on
+    if let Some(name) = name {
+        match channel.as_deref() {
+            Some("pecl.php.net") => {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::PeclPackage(name),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Archive("Pecl".to_string()),
+                    certainty: Some(Certainty::Likely),
+                    origin: Some(path.into()),
+                });
+            }
+            Some("pear.php.net") => {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Homepage(format!(
+                        "https://pear.php.net/package/{}",
+                        name
+                    )),
+                    certainty: Some(Certainty::Likely),
+                    origin: Some(path.into()),
+                });
+            }
+            _ => {}
+        }
+    }
+
     for lead_element in leads.iter().take(1) {
         let name_el = lead_element.get_child("name").unwrap().get_text();
         let email_el = lead_element
@@ -176,3 +212,44 @@ pub fn guess_from_package_xml(
 
     Ok(upstream_data)
 }
+
+/// Parse a PEAR channel definition (channel.xml).
+///
+/// This describes a channel server rather than a package, so only its summary and location
+/// are of interest.
+pub fn guess_from_channel_xml(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    const NAMESPACES: &[&str] = &["http://pear.php.net/channel-1.0"];
+
+    let root = xmlparse_simplify_namespaces(path, NAMESPACES)
+        .ok_or_else(|| ProviderError::ParseError("Unable to parse channel.xml".to_string()))?;
+
+    if root.name != "channel" {
+        return Err(ProviderError::ParseError(format!(
+            "Unexpected root tag {} in channel.xml",
+            root.name
+        )));
+    }
+
+    let mut upstream_data = Vec::new();
+
+    if let Some(name) = root.get_child("name").and_then(|e| e.get_text()) {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Homepage(format!("https://{}/", name.trim())),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+
+    if let Some(summary) = root.get_child("summary").and_then(|e| e.get_text()) {
+        upstream_data.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Summary(summary.trim().to_string()),
+            certainty: Some(Certainty::Likely),
+            origin: Some(path.into()),
+        });
+    }
+
+    Ok(upstream_data)
+}
@@ -96,7 +96,7 @@ pub fn guess_from_package_yaml(
     if let Some(license) = data.get("license") {
         if let Some(license) = license.as_str() {
             ret.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license.to_string()),
+                datum: UpstreamDatum::License(license.to_string().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -11,6 +11,15 @@ use std::io::{BufRead, BufReader, Read};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Turn the bug tracker address from CPAN metadata into a mailto: URL.
+fn mailto_url(address: &str) -> String {
+    if address.starts_with("mailto:") {
+        address.to_string()
+    } else {
+        format!("mailto:{}", address)
+    }
+}
+
 pub fn guess_from_pod(
     contents: &str,
     origin: &Origin,
@@ -166,7 +175,7 @@ pub fn guess_from_dist_ini(
         parser
             .get_from::<&str>(None, "license")
             .map(|license| UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license.to_string()),
+                datum: UpstreamDatum::License(license.to_string().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -263,7 +272,13 @@ pub fn guess_from_meta_json(
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 });
-                // TODO: Support resources["bugtracker"]["mailto"]
+            }
+            if let Some(mailto) = bugtracker.get("mailto").and_then(serde_json::Value::as_str) {
+                upstream_data.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::BugSubmit(mailto_url(mailto)),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(path.into()),
+                });
             }
         }
 
@@ -339,7 +354,7 @@ pub fn guess_from_meta_yml(
     if let Some(license) = data.get("license") {
         if let Some(license) = license.as_str() {
             upstream_data.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license.to_string()),
+                datum: UpstreamDatum::License(license.to_string().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -543,6 +558,166 @@ pub async fn remote_cpan_data(module: &str) -> Result<UpstreamMetadata, crate::P
     }
 }
 
+#[derive(Deserialize)]
+pub struct MetaCpanRepository {
+    pub url: Option<String>,
+    pub web: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MetaCpanBugtracker {
+    pub web: Option<String>,
+    pub mailto: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct MetaCpanResources {
+    pub repository: Option<MetaCpanRepository>,
+    pub bugtracker: Option<MetaCpanBugtracker>,
+    pub homepage: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MetaCpanRelease {
+    pub distribution: String,
+    pub version: Option<String>,
+    #[serde(rename = "abstract")]
+    pub abstract_: Option<String>,
+    #[serde(default)]
+    pub license: Vec<String>,
+    #[serde(default)]
+    pub resources: MetaCpanResources,
+}
+
+fn parse_metacpan_release(release: &MetaCpanRelease) -> Vec<UpstreamDatum> {
+    let mut results = vec![UpstreamDatum::Name(release.distribution.clone())];
+
+    if let Some(version) = release.version.as_ref() {
+        results.push(UpstreamDatum::Version(version.clone()));
+    }
+
+    if let Some(summary) = release.abstract_.as_ref() {
+        results.push(UpstreamDatum::Summary(summary.clone()));
+    }
+
+    let licenses = release
+        .license
+        .iter()
+        .filter(|l| l.as_str() != "unknown")
+        .cloned()
+        .collect::<Vec<_>>();
+    if !licenses.is_empty() {
+        results.push(UpstreamDatum::License(licenses.join(" OR ").into()));
+    }
+
+    if let Some(repository) = release.resources.repository.as_ref() {
+        if let Some(url) = repository.url.as_ref() {
+            results.push(UpstreamDatum::Repository(url.clone()));
+        }
+        if let Some(web) = repository.web.as_ref() {
+            results.push(UpstreamDatum::RepositoryBrowse(web.clone()));
+        }
+    }
+
+    if let Some(bugtracker) = release.resources.bugtracker.as_ref() {
+        if let Some(web) = bugtracker.web.as_ref() {
+            results.push(UpstreamDatum::BugDatabase(web.clone()));
+        }
+        if let Some(mailto) = bugtracker.mailto.as_ref() {
+            results.push(UpstreamDatum::BugSubmit(mailto_url(mailto)));
+        }
+    }
+
+    if let Some(homepage) = release.resources.homepage.as_ref() {
+        results.push(UpstreamDatum::Homepage(homepage.clone()));
+    }
+
+    results
+}
+
+pub async fn load_metacpan_release(dist: &str) -> Result<MetaCpanRelease, ProviderError> {
+    let url = format!("https://fastapi.metacpan.org/v1/release/{}", dist)
+        .parse()
+        .unwrap();
+    let data = crate::load_json_url(&url, None).await?;
+    serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid MetaCPAN release data: {}", e)))
+}
+
+pub async fn guess_from_metacpan(dist: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    // Distribution names use dashes where module names use double colons
+    let dist = dist.replace("::", "-");
+    match load_metacpan_release(&dist).await {
+        Ok(release) => Ok(parse_metacpan_release(&release)),
+        Err(ProviderError::HttpJsonError(crate::HTTPJSONError::Error { status: 404, .. })) => {
+            log::warn!("Distribution {} not found on MetaCPAN", dist);
+            Ok(Vec::new())
+        }
+        Err(ProviderError::ParseError(e)) => {
+            log::warn!("Unable to parse MetaCPAN data for {}: {}", dist, e);
+            Ok(Vec::new())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Check whether the directory contains a Perl distribution.
+pub fn is_perl_dist(path: &Path) -> bool {
+    [
+        "META.json",
+        "MYMETA.json",
+        "META.yml",
+        "MYMETA.yml",
+        "dist.ini",
+        "Makefile.PL",
+        "Build.PL",
+    ]
+    .iter()
+    .any(|f| path.join(f).exists())
+}
+
+pub struct MetaCpan;
+
+impl Default for MetaCpan {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetaCpan {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for MetaCpan {
+    fn name(&self) -> &'static str {
+        "MetaCPAN"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Name",
+            "Version",
+            "Summary",
+            "License",
+            "Repository",
+            "Repository-Browse",
+            "Bug-Database",
+            "Homepage",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_metacpan(name).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,4 +729,26 @@ mod tests {
 
         assert_eq!("Parse-Pidl-0.02", cpan_module.release);
     }
+
+    #[test]
+    fn test_parse_metacpan_release() {
+        let text = include_str!("../testdata/metacpan-release.json");
+        let release: MetaCpanRelease = serde_json::from_str(text).unwrap();
+        assert_eq!(
+            parse_metacpan_release(&release),
+            vec![
+                UpstreamDatum::Name("Moose".to_string()),
+                UpstreamDatum::Version("2.2207".to_string()),
+                UpstreamDatum::Summary("A postmodern object system for Perl 5".to_string()),
+                UpstreamDatum::License("perl_5".into()),
+                UpstreamDatum::Repository("git://github.com/moose/Moose.git".to_string()),
+                UpstreamDatum::RepositoryBrowse("https://github.com/moose/Moose".to_string()),
+                UpstreamDatum::BugDatabase(
+                    "https://rt.cpan.org/Dist/Display.html?Name=Moose".to_string()
+                ),
+                UpstreamDatum::BugSubmit("mailto:bug-Moose@rt.cpan.org".to_string()),
+                UpstreamDatum::Homepage("http://moose.perl.org/".to_string()),
+            ]
+        );
+    }
 }
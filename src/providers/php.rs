@@ -2,19 +2,21 @@ use crate::{ProviderError, UpstreamDatum};
 use select::document::Document;
 use select::predicate::{And, Name, Predicate};
 
-pub async fn guess_from_pecl_package(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
-    let url = format!("https://pecl.php.net/packages/{}", package);
-
-    let client = reqwest::Client::builder()
+fn pecl_client() -> reqwest::Client {
+    reqwest::Client::builder()
         .user_agent(crate::USER_AGENT)
         // PECL is slow
         .timeout(std::time::Duration::from_secs(15))
         .build()
-        .unwrap();
+        .unwrap()
+}
+
+pub async fn guess_from_pecl_package(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!("https://pecl.php.net/packages/{}", package);
+
+    let client = pecl_client();
 
-    let response = client
-        .get(url)
-        .send()
+    let response = crate::http::send(client.get(url))
         .await
         .map_err(|e| ProviderError::Other(e.to_string()))?;
 
@@ -36,6 +38,79 @@ pub async fn guess_from_pecl_package(package: &str) -> Result<Vec<UpstreamDatum>
     guess_from_pecl_page(&body)
 }
 
+/// Fetch a document from the PECL REST interface, returning None if it does not exist.
+async fn load_pecl_rest(path: &str) -> Result<Option<String>, ProviderError> {
+    let url = format!("https://pecl.php.net/rest/{}", path);
+    let response = crate::http::send(pecl_client().get(url))
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(None),
+        status if !status.is_success() => {
+            Err(ProviderError::Other(format!("HTTP error: {}", status)))
+        }
+        _ => response
+            .text()
+            .await
+            .map(Some)
+            .map_err(|e| ProviderError::Other(e.to_string())),
+    }
+}
+
+/// Parse the package information document of the PECL REST interface (`/rest/p/<name>/info.xml`).
+fn parse_pecl_package_info(text: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let root = xmltree::Element::parse(text.as_bytes())
+        .map_err(|e| ProviderError::ParseError(format!("Invalid PECL package info: {}", e)))?;
+    let mut ret = Vec::new();
+
+    let text_of = |tag: &str| {
+        root.get_child(tag)
+            .and_then(|e| e.get_text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+
+    if let Some(name) = text_of("n") {
+        ret.push(UpstreamDatum::Name(name));
+    }
+    if let Some(license) = text_of("l") {
+        ret.push(UpstreamDatum::License(license.into()));
+    }
+    if let Some(summary) = text_of("s") {
+        ret.push(UpstreamDatum::Summary(summary));
+    }
+    if let Some(description) = text_of("d") {
+        ret.push(UpstreamDatum::Description(description));
+    }
+
+    Ok(ret)
+}
+
+/// Look up a package using the PECL REST interface.
+///
+/// Unlike the package page, this works for packages that are no longer maintained.
+pub async fn guess_from_pecl_rest(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let package = package.to_lowercase();
+    let info = match load_pecl_rest(&format!("p/{}/info.xml", package)).await? {
+        Some(info) => info,
+        None => {
+            log::warn!("Package {} not found in PECL", package);
+            return Ok(Vec::new());
+        }
+    };
+    let mut ret = parse_pecl_package_info(&info)?;
+
+    if let Some(version) = load_pecl_rest(&format!("r/{}/latest.txt", package)).await? {
+        let version = version.trim();
+        if !version.is_empty() {
+            ret.push(UpstreamDatum::Version(version.to_string()));
+        }
+    }
+
+    Ok(ret)
+}
+
 struct TextContains<'a>(&'a str);
 
 impl<'a> Predicate for TextContains<'a> {
@@ -121,11 +196,22 @@ impl crate::ThirdPartyRepository for Pecl {
     }
 
     fn supported_fields(&self) -> &'static [&'static str] {
-        &["Homepage", "Repository", "Bug-Database"]
+        &[
+            "Homepage",
+            "Repository-Browse",
+            "Bug-Database",
+            "Name",
+            "License",
+            "Summary",
+            "Description",
+            "Version",
+        ]
     }
 
     async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
-        guess_from_pecl_package(name).await
+        let mut ret = guess_from_pecl_package(name).await?;
+        ret.extend(guess_from_pecl_rest(name).await?);
+        Ok(ret)
     }
 }
 
@@ -150,4 +236,20 @@ mod pecl_tests {
             ]
         );
     }
+
+    #[test]
+    fn test_parse_pecl_package_info() {
+        let text = include_str!("../testdata/pecl-info.xml");
+        assert_eq!(
+            parse_pecl_package_info(text).unwrap(),
+            vec![
+                UpstreamDatum::Name("smbclient".to_string()),
+                UpstreamDatum::License("BSD 2-clause".into()),
+                UpstreamDatum::Summary("A PHP wrapper for libsmbclient".to_string()),
+                UpstreamDatum::Description(
+                    "smbclient is a PHP extension that uses Samba's libsmbclient library to provide\nSamba related functions and 'smb' streams to PHP programs.".to_string()
+                ),
+            ]
+        );
+    }
 }
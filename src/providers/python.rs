@@ -1,6 +1,12 @@
+#[cfg(any(
+    feature = "python-pkginfo",
+    feature = "pyproject-toml",
+    feature = "setup-cfg"
+))]
+use crate::GuesserSettings;
 use crate::{
-    vcs, Certainty, GuesserSettings, Origin, Person, ProviderError, UpstreamDatum,
-    UpstreamDatumWithMetadata, UpstreamMetadata,
+    vcs, Certainty, Origin, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
+    UpstreamMetadata,
 };
 use log::{debug, warn};
 use serde::Deserialize;
@@ -93,7 +99,7 @@ pub async fn guess_from_pkg_info(
 
     if let Some(license) = dist.license {
         ret.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(license),
+            datum: UpstreamDatum::License(license.into()),
             certainty: Some(Certainty::Certain),
             origin: Some(path.into()),
         });
@@ -176,7 +182,7 @@ pub fn guess_from_pyproject_toml(
 
         if let Some(pyproject_toml::License::Spdx(license)) = inner_project.license.as_ref() {
             ret.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license.clone()),
+                datum: UpstreamDatum::License(license.clone().into()),
                 certainty: Some(Certainty::Certain),
                 origin: Some(path.into()),
             });
@@ -255,7 +261,7 @@ pub fn guess_from_pyproject_toml(
 
             if let Some(license) = poetry.license {
                 ret.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(license),
+                    datum: UpstreamDatum::License(license.into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 });
@@ -335,7 +341,7 @@ fn parse_python_project_urls(
                     origin: Some(origin.clone()),
                 });
             }
-            "Bug Tracker" | "Bug Reports" => {
+            "Bug Tracker" | "Bug Reports" | "Issue Tracker" | "Issues" => {
                 ret.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::BugDatabase(url.to_string()),
                     certainty: Some(Certainty::Certain),
@@ -363,6 +369,13 @@ fn parse_python_project_urls(
                     origin: Some(origin.clone()),
                 });
             }
+            "Changelog" | "Change Log" | "Changes" | "Release Notes" => {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Changelog(url.to_string()),
+                    certainty: Some(Certainty::Certain),
+                    origin: Some(origin.clone()),
+                });
+            }
             _u => {
                 debug!("Unknown Python project URL type: {}", url_type);
             }
@@ -371,6 +384,7 @@ fn parse_python_project_urls(
     ret
 }
 
+#[cfg(any(feature = "python-pkginfo", feature = "setup-cfg", feature = "pyo3"))]
 fn parse_python_long_description(
     long_description: &str,
     content_type: Option<&str>,
@@ -578,7 +592,7 @@ pub async fn guess_from_setup_cfg(
             }
             "license" => {
                 ret.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(value.to_string()),
+                    datum: UpstreamDatum::License(value.to_string().into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(origin.clone()),
                 });
@@ -683,9 +697,12 @@ async fn guess_from_setup_py_executed(
             });
         }
 
-        if let Some(license) = result.call_method0("get_license")?.extract()? {
+        if let Some(license) = result
+            .call_method0("get_license")?
+            .extract::<Option<String>>()?
+        {
             ret.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license),
+                datum: UpstreamDatum::License(license.into()),
                 certainty: Some(Certainty::Likely),
                 origin: Some(Origin::Path(path.to_path_buf())),
             });
@@ -946,7 +963,7 @@ async fn guess_from_setup_py_parsed(
                 "license" => {
                     if let Some(license) = get_str_from_expr(value) {
                         ret.push(UpstreamDatumWithMetadata {
-                            datum: UpstreamDatum::License(license),
+                            datum: UpstreamDatum::License(license.into()),
                             certainty: Some(Certainty::Certain),
                             origin: Some(path.into()),
                         });
@@ -1087,9 +1104,16 @@ fn parse_python_classifiers<'a>(
         let mut parts = classifier.split(" :: ");
         let category = parts.next()?;
         let subcategory = parts.next()?;
-        let value = parts.next()?;
         let certainty = Some(Certainty::Certain);
         let origin = Some(origin.clone());
+        if (category, subcategory) == ("Development Status", "7 - Inactive") {
+            return Some(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::MaintenanceStatus("inactive".into()),
+                certainty,
+                origin,
+            });
+        }
+        let value = parts.next()?;
         match (category, subcategory) {
             ("Development Status", _) => None,
             ("Intended Audience", _) => None,
@@ -1125,6 +1149,7 @@ pub struct PypiProjectInfo {
     pub home_page: Option<String>,
     pub keywords: Option<String>,
     pub license: Option<String>,
+    pub license_expression: Option<String>,
     pub maintainer: Option<String>,
     pub maintainer_email: Option<String>,
     pub name: String,
@@ -1227,7 +1252,7 @@ impl TryInto<UpstreamMetadata> for PypiProject {
 
         if let Some(license) = self.info.license {
             metadata.insert(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license),
+                datum: UpstreamDatum::License(license.into()),
                 certainty: Some(Certainty::Certain),
                 origin: None,
             });
@@ -1313,6 +1338,128 @@ pub async fn remote_pypi_metadata(name: &str) -> Result<UpstreamMetadata, Provid
     }
 }
 
+fn parse_pypi_project(project: &PypiProject) -> Vec<UpstreamDatum> {
+    let info = &project.info;
+    let mut ret = vec![UpstreamDatum::Name(info.name.clone())];
+
+    if !info.summary.is_empty() {
+        ret.push(UpstreamDatum::Summary(info.summary.clone()));
+    }
+
+    ret.push(UpstreamDatum::Version(info.version.clone()));
+
+    let origin = Origin::Other("pypi".to_string());
+    let (maintenance_status, classifier_data): (Vec<_>, Vec<_>) =
+        parse_python_classifiers(info.classifiers.iter().map(|c| c.as_str()), &origin)
+            .map(|d| d.datum)
+            .partition(|d| matches!(d, UpstreamDatum::MaintenanceStatus(..)));
+
+    // The license field sometimes contains the full license text
+    if let Some(license) = info.license_expression.as_ref().or(info
+        .license
+        .as_ref()
+        .filter(|l| !l.is_empty() && !l.contains('\n')))
+    {
+        ret.push(UpstreamDatum::License(license.clone().into()));
+    } else {
+        ret.extend(classifier_data);
+    }
+
+    // An explicit yank of the latest release says more than a stale classifier
+    if !project.urls.is_empty() && project.urls.iter().all(|u| u.yanked) {
+        ret.push(UpstreamDatum::MaintenanceStatus(
+            match project.urls.iter().find_map(|u| u.yanked_reason.as_ref()) {
+                Some(reason) => format!("yanked: {}", reason),
+                None => "yanked".to_string(),
+            },
+        ));
+    } else {
+        ret.extend(maintenance_status);
+    }
+
+    if let Some(homepage) = info.home_page.as_ref().filter(|h| !h.is_empty()) {
+        ret.push(UpstreamDatum::Homepage(homepage.clone()));
+    }
+
+    if let Some(urls) = info.project_urls.as_ref() {
+        let mut urls = urls.clone().into_iter().collect::<Vec<_>>();
+        urls.sort();
+        ret.extend(
+            parse_python_project_urls(urls.into_iter(), &Origin::Other("pypi".to_string()))
+                .into_iter()
+                .map(|d| d.datum),
+        );
+    }
+
+    ret
+}
+
+/// Check whether a directory contains a Python project.
+pub fn is_python_project(path: &Path) -> bool {
+    ["PKG-INFO", "pyproject.toml", "setup.cfg", "setup.py"]
+        .iter()
+        .any(|f| path.join(f).exists())
+}
+
+pub struct PyPI;
+
+impl Default for PyPI {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PyPI {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for PyPI {
+    fn name(&self) -> &'static str {
+        "PyPI"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "Bug-Database",
+            "Documentation",
+            "Funding",
+            "Changelog",
+            "License",
+            "Summary",
+            "Version",
+            "Maintenance-Status",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        match load_pypi_project(name).await {
+            Ok(Some(project)) => Ok(parse_pypi_project(&project)),
+            Ok(None) => Ok(Vec::new()),
+            Err(ProviderError::HttpJsonError(crate::HTTPJSONError::Error {
+                status: 404, ..
+            })) => {
+                log::warn!("Project {} not found on PyPI", name);
+                Ok(Vec::new())
+            }
+            Err(ProviderError::Other(e)) => {
+                log::warn!("Unable to parse PyPI data for {}: {}", name, e);
+                Ok(Vec::new())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod pypi_tests {
     use super::*;
@@ -1325,4 +1472,44 @@ mod pypi_tests {
 
         assert_eq!(pypi_data.info.name, "merge3");
     }
+
+    #[test]
+    fn test_parse_pypi_project() {
+        let data = include_str!("../testdata/pypi.json");
+
+        let pypi_data: PypiProject = serde_json::from_str(data).unwrap();
+
+        assert_eq!(
+            parse_pypi_project(&pypi_data),
+            vec![
+                UpstreamDatum::Name("merge3".to_string()),
+                UpstreamDatum::Summary("Python implementation of 3-way merge".to_string()),
+                UpstreamDatum::Version("0.0.15".to_string()),
+                UpstreamDatum::License("GNU GPLv2 or later".into()),
+                UpstreamDatum::Repository("https://github.com/breezy-team/merge3".to_string()),
+                UpstreamDatum::Homepage("https://www.breezy-vcs.org/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pypi_project_inactive() {
+        let data = include_str!("../testdata/pypi.json");
+
+        let mut pypi_data: PypiProject = serde_json::from_str(data).unwrap();
+        pypi_data.info.classifiers[0] = "Development Status :: 7 - Inactive".to_string();
+
+        assert!(parse_pypi_project(&pypi_data)
+            .contains(&UpstreamDatum::MaintenanceStatus("inactive".to_string())));
+
+        for url in pypi_data.urls.iter_mut() {
+            url.yanked = true;
+            url.yanked_reason = Some("Broken build".to_string());
+        }
+        let data = parse_pypi_project(&pypi_data);
+        assert!(data.contains(&UpstreamDatum::MaintenanceStatus(
+            "yanked: Broken build".to_string()
+        )));
+        assert!(!data.contains(&UpstreamDatum::MaintenanceStatus("inactive".to_string())));
+    }
 }
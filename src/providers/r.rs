@@ -1,9 +1,11 @@
 //! See https://r-pkgs.org/description.html
 
-use crate::{
-    vcs, Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum,
-    UpstreamDatumWithMetadata,
-};
+#[cfg(feature = "r-description")]
+use crate::{vcs, GuesserSettings, Person, UpstreamDatumWithMetadata};
+use crate::{Certainty, ProviderError, UpstreamDatum};
+use select::document::Document;
+use select::predicate::{Class, Name, Predicate};
+use serde::Deserialize;
 
 #[cfg(feature = "r-description")]
 pub async fn guess_from_r_description(
@@ -48,7 +50,7 @@ pub async fn guess_from_r_description(
     });
 
     results.push(UpstreamDatumWithMetadata {
-        datum: UpstreamDatum::License(msg.license),
+        datum: UpstreamDatum::License(msg.license.into()),
         certainty: Some(Certainty::Certain),
         origin: Some(path.into()),
     });
@@ -127,6 +129,243 @@ pub async fn guess_from_r_description(
     Ok(results)
 }
 
+/// Split the URL field of a DESCRIPTION file into individual URLs.
+fn split_urls(urls: &str) -> Vec<String> {
+    urls.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|u| u.starts_with("http://") || u.starts_with("https://"))
+        .map(|u| u.to_string())
+        .collect()
+}
+
+#[derive(Deserialize)]
+pub struct CranPackage {
+    #[serde(rename = "Package")]
+    pub package: String,
+    #[serde(rename = "Version")]
+    pub version: Option<String>,
+    #[serde(rename = "License")]
+    pub license: Option<String>,
+    #[serde(rename = "URL")]
+    pub url: Option<String>,
+    #[serde(rename = "BugReports")]
+    pub bug_reports: Option<String>,
+}
+
+fn parse_cran_package(package: &CranPackage) -> Vec<UpstreamDatum> {
+    let mut results = vec![UpstreamDatum::Name(package.package.clone())];
+
+    if let Some(version) = package.version.as_ref() {
+        results.push(UpstreamDatum::Version(version.clone()));
+    }
+
+    if let Some(license) = package.license.as_ref() {
+        results.push(UpstreamDatum::License(license.clone().into()));
+    }
+
+    if let Some(homepage) = package
+        .url
+        .as_deref()
+        .and_then(|u| split_urls(u).into_iter().next())
+    {
+        results.push(UpstreamDatum::Homepage(homepage));
+    }
+
+    if let Some(bug_reports) = package.bug_reports.as_ref() {
+        results.push(UpstreamDatum::BugDatabase(bug_reports.clone()));
+    }
+
+    results
+}
+
+pub async fn guess_from_cran(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!("https://crandb.r-pkg.org/{}", package)
+        .parse()
+        .unwrap();
+    let data = match crate::load_json_url(&url, None).await {
+        Ok(data) => data,
+        Err(crate::HTTPJSONError::Error { status: 404, .. }) => {
+            log::warn!("Package {} not found on CRAN", package);
+            return Ok(Vec::new());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let package: CranPackage = serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid CRAN package data: {}", e)))?;
+    Ok(parse_cran_package(&package))
+}
+
+pub struct Cran;
+
+impl Default for Cran {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cran {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Cran {
+    fn name(&self) -> &'static str {
+        "CRAN"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Confident
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &["Name", "Version", "License", "Homepage", "Bug-Database"][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_cran(name).await
+    }
+}
+
+fn parse_bioconductor_page(body: &str) -> Vec<UpstreamDatum> {
+    let document = Document::from(body);
+    let mut results = Vec::new();
+
+    // Package details are listed in a two-column table
+    for row in document.find(Name("table").and(Class("details")).descendant(Name("tr"))) {
+        let cells = row.find(Name("td")).collect::<Vec<_>>();
+        if cells.len() != 2 {
+            continue;
+        }
+        let value = cells[1].text().trim().to_string();
+        if value.is_empty() {
+            continue;
+        }
+        match cells[0].text().trim() {
+            "Version" => results.push(UpstreamDatum::Version(value)),
+            "License" => results.push(UpstreamDatum::License(value.into())),
+            "URL" => {
+                if let Some(homepage) = split_urls(&value).into_iter().next() {
+                    results.push(UpstreamDatum::Homepage(homepage));
+                }
+            }
+            "BugReports" => {
+                if let Some(bug_reports) = split_urls(&value).into_iter().next() {
+                    results.push(UpstreamDatum::BugDatabase(bug_reports));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    results
+}
+
+pub async fn guess_from_bioconductor(package: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+    let url = format!(
+        "https://bioconductor.org/packages/release/bioc/html/{}.html",
+        package
+    );
+
+    let client = crate::http::build_client()
+        .build()
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    let response = crate::http::send(client.get(url))
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    match response.status() {
+        reqwest::StatusCode::NOT_FOUND => {
+            log::warn!("Package {} not found on Bioconductor", package);
+            return Ok(vec![]);
+        }
+        status if !status.is_success() => {
+            return Err(ProviderError::Other(format!("HTTP error: {}", status)));
+        }
+        _ => {}
+    }
+
+    let body = response
+        .text()
+        .await
+        .map_err(|e| ProviderError::Other(e.to_string()))?;
+
+    let mut results = vec![UpstreamDatum::Name(package.to_string())];
+    results.extend(parse_bioconductor_page(&body));
+    Ok(results)
+}
+
+pub struct Bioconductor;
+
+impl Default for Bioconductor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bioconductor {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for Bioconductor {
+    fn name(&self) -> &'static str {
+        "Bioconductor"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Confident
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &["Name", "Version", "License", "Homepage", "Bug-Database"][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        guess_from_bioconductor(name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cran_package() {
+        let data = include_str!("../testdata/crandb.json");
+        let package: CranPackage = serde_json::from_str(data).unwrap();
+        assert_eq!(
+            parse_cran_package(&package),
+            vec![
+                UpstreamDatum::Name("crul".to_string()),
+                UpstreamDatum::Version("1.5.0".to_string()),
+                UpstreamDatum::License("MIT + file LICENSE".into()),
+                UpstreamDatum::Homepage("https://docs.ropensci.org/crul/".to_string()),
+                UpstreamDatum::BugDatabase("https://github.com/ropensci/crul/issues".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bioconductor_page() {
+        let body = include_str!("../testdata/bioconductor.html");
+        assert_eq!(
+            parse_bioconductor_page(body),
+            vec![
+                UpstreamDatum::Version("2.42.0".to_string()),
+                UpstreamDatum::License("GPL (>= 3)".into()),
+                UpstreamDatum::Homepage("https://github.com/thelovelab/DESeq2".to_string()),
+                UpstreamDatum::BugDatabase(
+                    "https://support.bioconductor.org/tag/DESeq2".to_string()
+                ),
+            ]
+        );
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "r-description")]
 mod description_tests {
@@ -207,7 +446,7 @@ Date/Publication: 2019-08-02 20:30:02 UTC
                     origin: Some(path.clone().into())
                 },
                 UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License("MIT + file LICENSE".to_string()),
+                    datum: UpstreamDatum::License("MIT + file LICENSE".into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.clone().into())
                 },
@@ -1,5 +1,6 @@
-use crate::UpstreamDatum;
+use crate::{names::Ecosystem, UpstreamDatum, UpstreamMetadata};
 use std::collections::HashMap;
+use std::path::Path;
 
 #[allow(dead_code)]
 #[derive(serde::Deserialize)]
@@ -14,15 +15,27 @@ struct Project {
 
 pub async fn guess_from_repology(
     repology_project: &str,
+) -> Result<Vec<UpstreamDatum>, crate::ProviderError> {
+    guess_from_repology_repo(None, repology_project).await
+}
+
+/// Guess metadata from repology, looking the project up by its name in a specific repository.
+///
+/// # Arguments
+/// * `repo`: Repology repository identifier, e.g. "pypi"; defaults to "debian_unstable"
+/// * `name`: Name of the package in that repository
+pub async fn guess_from_repology_repo(
+    repo: Option<&str>,
+    name: &str,
 ) -> Result<Vec<UpstreamDatum>, crate::ProviderError> {
     let metadata: Vec<Project> = serde_json::from_value(
-        if let Some(value) = crate::get_repology_metadata(repology_project, None).await {
+        if let Some(value) = crate::get_repology_metadata(name, repo).await {
             value
         } else {
             return Ok(Vec::new());
         },
     )
-    .unwrap();
+    .map_err(|e| crate::ProviderError::ParseError(e.to_string()))?;
 
     let mut fields = HashMap::new();
 
@@ -72,10 +85,108 @@ pub async fn guess_from_repology(
         })
         .map(|(f, v)| match f.as_str() {
             "Homepage" => UpstreamDatum::Homepage(v),
-            "License" => UpstreamDatum::License(v),
+            "License" => UpstreamDatum::License(v.into()),
             "Summary" => UpstreamDatum::Summary(v),
             "Download" => UpstreamDatum::Download(v),
             _ => unreachable!(),
         })
         .collect())
 }
+
+/// Find the identifiers under which repology may know the project.
+///
+/// Returns pairs of repology repository identifiers and package names, based on the package
+/// registries that the project is published in.
+pub fn repology_identifiers(
+    upstream_metadata: &UpstreamMetadata,
+    path: &Path,
+) -> Vec<(&'static str, String)> {
+    let mut ret = vec![];
+    let archive = upstream_metadata
+        .get("Archive")
+        .and_then(|a| a.datum.as_str());
+    if upstream_metadata.contains_key("Cargo-Crate") || archive == Some("crates.io") {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::Cargo)
+                .map(|n| ("crates_io", n)),
+        );
+    }
+    if crate::providers::python::is_python_project(path) {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::PyPI)
+                .map(|n| ("pypi", n)),
+        );
+    }
+    if let Some(name) = crate::providers::node::npm_package_name(&path.join("package.json")) {
+        ret.push(("npm", name));
+    } else if archive == Some("npm") {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::Npm)
+                .map(|n| ("npm", n)),
+        );
+    }
+    if crate::providers::ruby::has_gemspec(path) {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::RubyGems)
+                .map(|n| ("rubygems", n)),
+        );
+    }
+    if crate::providers::perl::is_perl_dist(path) || archive == Some("CPAN") {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::Cpan)
+                .map(|n| ("cpan", n)),
+        );
+    }
+    if upstream_metadata.contains_key("Haskell-Package") || archive == Some("Hackage") {
+        ret.extend(
+            upstream_metadata
+                .name_for(Ecosystem::Hackage)
+                .map(|n| ("hackage", n)),
+        );
+    }
+    if archive == Some("CRAN") {
+        ret.extend(upstream_metadata.name().map(|n| ("cran", n.to_string())));
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Certainty, UpstreamDatumWithMetadata};
+
+    #[test]
+    fn test_repology_identifiers() {
+        let td = tempfile::tempdir().unwrap();
+        let mut metadata = UpstreamMetadata::new();
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name("Foo_Bar".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+        assert!(repology_identifiers(&metadata, td.path()).is_empty());
+
+        metadata.insert(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::CargoCrate("foo_bar".to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        });
+        std::fs::write(
+            td.path().join("package.json"),
+            r#"{"name": "@foo/bar", "version": "1.0.0"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            repology_identifiers(&metadata, td.path()),
+            vec![
+                ("crates_io", "foo-bar".to_string()),
+                ("npm", "@foo/bar".to_string())
+            ]
+        );
+    }
+}
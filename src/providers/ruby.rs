@@ -108,7 +108,7 @@ pub async fn guess_from_gemspec(
                     origin: Some(path.into()),
                 }),
                 "license" => results.push(UpstreamDatumWithMetadata {
-                    datum: UpstreamDatum::License(val.as_str().unwrap().to_string()),
+                    datum: UpstreamDatum::License(val.as_str().unwrap().to_string().into()),
                     certainty: Some(Certainty::Certain),
                     origin: Some(path.into()),
                 }),
@@ -249,7 +249,7 @@ impl TryFrom<Rubygem> for UpstreamMetadata {
         }
 
         metadata.insert(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(gem.licenses.join(", ")),
+            datum: UpstreamDatum::License(gem.licenses.join(", ").into()),
             certainty: Some(Certainty::Certain),
             origin: None,
         });
@@ -279,7 +279,8 @@ pub async fn load_rubygem(name: &str) -> Result<Option<Rubygem>, ProviderError>
         .parse()
         .unwrap();
     let data = crate::load_json_url(&url, None).await?;
-    let gem: Rubygem = serde_json::from_value(data).unwrap();
+    let gem: Rubygem = serde_json::from_value(data)
+        .map_err(|e| ProviderError::ParseError(format!("Invalid rubygems.org data: {}", e)))?;
     Ok(Some(gem))
 }
 
@@ -292,8 +293,120 @@ pub async fn remote_rubygem_metadata(name: &str) -> Result<UpstreamMetadata, Pro
     }
 }
 
+fn parse_rubygem(gem: &Rubygem) -> Vec<UpstreamDatum> {
+    let mut ret = vec![
+        UpstreamDatum::Name(gem.name.clone()),
+        UpstreamDatum::Version(gem.version.clone()),
+    ];
+
+    if let Some(homepage_uri) = gem.homepage_uri.as_ref() {
+        ret.push(UpstreamDatum::Homepage(homepage_uri.to_string()));
+    }
+
+    if let Some(source_code_uri) = gem.source_code_uri.as_ref() {
+        ret.push(UpstreamDatum::Repository(source_code_uri.to_string()));
+    }
+
+    if let Some(bug_tracker_uri) = gem.bug_tracker_uri.as_ref() {
+        ret.push(UpstreamDatum::BugDatabase(bug_tracker_uri.to_string()));
+    }
+
+    if let Some(changelog_uri) = gem.changelog_uri.as_ref() {
+        ret.push(UpstreamDatum::Changelog(changelog_uri.to_string()));
+    }
+
+    if let Some(documentation_uri) = gem.documentation_uri.as_ref() {
+        ret.push(UpstreamDatum::Documentation(documentation_uri.to_string()));
+    }
+
+    if let Some(funding_uri) = gem.funding_uri.as_ref() {
+        ret.push(UpstreamDatum::Funding(funding_uri.to_string()));
+    }
+
+    if !gem.licenses.is_empty() {
+        ret.push(UpstreamDatum::License(gem.licenses.join(", ").into()));
+    }
+
+    if gem.yanked {
+        ret.push(UpstreamDatum::MaintenanceStatus("yanked".to_string()));
+    }
+
+    ret
+}
+
+/// Check whether a directory contains a gemspec file.
+pub fn has_gemspec(path: &Path) -> bool {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .any(|e| e.file_name().to_string_lossy().ends_with(".gemspec"))
+        })
+        .unwrap_or(false)
+}
+
+pub struct RubyGems;
+
+impl Default for RubyGems {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RubyGems {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::ThirdPartyRepository for RubyGems {
+    fn name(&self) -> &'static str {
+        "RubyGems"
+    }
+
+    fn max_supported_certainty(&self) -> Certainty {
+        Certainty::Certain
+    }
+
+    fn supported_fields(&self) -> &'static [&'static str] {
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "Bug-Database",
+            "Changelog",
+            "Documentation",
+            "Funding",
+            "License",
+            "Version",
+            "Maintenance-Status",
+        ][..]
+    }
+
+    async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
+        match load_rubygem(name).await {
+            Ok(Some(gem)) => Ok(parse_rubygem(&gem)),
+            Ok(None) => Ok(Vec::new()),
+            Err(ProviderError::HttpJsonError(crate::HTTPJSONError::Error {
+                status: 404, ..
+            })) => {
+                log::warn!("Gem {} not found on rubygems.org", name);
+                Ok(Vec::new())
+            }
+            Err(ProviderError::ParseError(e)) => {
+                log::warn!("Unable to parse rubygems.org data for {}: {}", name, e);
+                Ok(Vec::new())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::UpstreamDatum;
+
     #[test]
     fn test_parse_gem() {
         let gemspec = include_str!("../testdata/rubygem.json");
@@ -302,4 +415,25 @@ mod tests {
 
         assert_eq!(gem.name, "bullet");
     }
+
+    #[test]
+    fn test_parse_rubygem() {
+        let gemspec = include_str!("../testdata/rubygem.json");
+
+        let gem: super::Rubygem = serde_json::from_str(gemspec).unwrap();
+
+        assert_eq!(
+            super::parse_rubygem(&gem),
+            vec![
+                UpstreamDatum::Name("bullet".to_string()),
+                UpstreamDatum::Version("7.2.0".to_string()),
+                UpstreamDatum::Homepage("https://github.com/flyerhzm/bullet".to_string()),
+                UpstreamDatum::Repository("https://github.com/flyerhzm/bullet".to_string()),
+                UpstreamDatum::Changelog(
+                    "https://github.com/flyerhzm/bullet/blob/main/CHANGELOG.md".to_string()
+                ),
+                UpstreamDatum::License("MIT".into()),
+            ]
+        );
+    }
 }
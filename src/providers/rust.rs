@@ -1,7 +1,6 @@
-use crate::{
-    Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata,
-    UpstreamMetadata,
-};
+use crate::{Certainty, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata, UpstreamMetadata};
+#[cfg(feature = "cargo")]
+use crate::{GuesserSettings, Person};
 use serde::Deserialize;
 use std::collections::HashMap;
 
@@ -145,7 +144,7 @@ pub fn guess_from_cargo(
 
     if let Some(license) = resolve!(workspace, package, license) {
         results.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(license),
+            datum: UpstreamDatum::License(license.into()),
             certainty: Some(Certainty::Certain),
             origin: Some(path.into()),
         });
@@ -309,7 +308,7 @@ impl TryFrom<CrateInfo> for UpstreamMetadata {
 
         if let Some(license) = value.crate_.license {
             ret.insert(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(license),
+                datum: UpstreamDatum::License(license.into()),
                 certainty: Some(Certainty::Certain),
                 origin: None,
             });
@@ -348,11 +347,14 @@ fn parse_crates_io(data: &CrateInfo) -> Vec<UpstreamDatum> {
         results.push(UpstreamDatum::Summary(description.to_string()));
     }
     if let Some(license) = crate_data.license.as_ref() {
-        results.push(UpstreamDatum::License(license.to_string()));
+        results.push(UpstreamDatum::License(license.to_string().into()));
     }
     results.push(UpstreamDatum::Version(
         crate_data.newest_version.to_string(),
     ));
+    if !data.versions.is_empty() && data.versions.iter().all(|v| v.yanked) {
+        results.push(UpstreamDatum::MaintenanceStatus("yanked".to_string()));
+    }
 
     results
 }
@@ -382,7 +384,14 @@ impl crate::ThirdPartyRepository for CratesIo {
     }
 
     fn supported_fields(&self) -> &'static [&'static str] {
-        &["Homepage", "Name", "Repository", "Version", "Summary"][..]
+        &[
+            "Homepage",
+            "Name",
+            "Repository",
+            "Version",
+            "Summary",
+            "Maintenance-Status",
+        ][..]
     }
 
     async fn guess_metadata(&self, name: &str) -> Result<Vec<UpstreamDatum>, ProviderError> {
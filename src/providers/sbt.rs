@@ -0,0 +1,272 @@
+//! Support for sbt build definitions (build.sbt).
+//!
+//! Documentation: https://www.scala-sbt.org/1.x/docs/Publishing.html
+
+use crate::{
+    vcs, Certainty, GuesserSettings, Person, ProviderError, UpstreamDatum,
+    UpstreamDatumWithMetadata,
+};
+use std::path::Path;
+
+/// Scan an expression until an unbalanced closing parenthesis, ignoring string literals.
+///
+/// If `stop_at_separator` is set, the expression also ends at the first newline or comma
+/// that is not nested inside parentheses.
+fn scan_expression(text: &str, stop_at_separator: bool) -> &str {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '(' => depth += 1,
+            ')' if depth == 0 => return &text[..i],
+            ')' => depth -= 1,
+            '\n' | ',' if depth == 0 && stop_at_separator => return &text[..i],
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Extract the expression assigned to a setting, starting right after the operator.
+///
+/// The expression ends at the first newline or comma that is not nested inside
+/// parentheses, or at an unbalanced closing parenthesis (e.g. the end of an
+/// `inThisBuild(List(...))` block).
+fn extract_expression(text: &str) -> &str {
+    scan_expression(text, true)
+}
+
+/// Extract the arguments of a call, starting right after the opening parenthesis.
+fn call_arguments(text: &str) -> &str {
+    scan_expression(text, false)
+}
+
+/// Find all expressions assigned to a setting, e.g. `homepage := ...` or
+/// `ThisBuild / licenses += ...`.
+fn find_settings<'a>(text: &'a str, key: &str) -> Vec<&'a str> {
+    let re = regex::Regex::new(&format!(
+        r"(?m)(?:^|[\s(,/])\s*{}\s*(?::=|\+\+=|\+=)",
+        regex::escape(key)
+    ))
+    .unwrap();
+    re.find_iter(text)
+        .map(|m| extract_expression(&text[m.end()..]).trim())
+        .collect()
+}
+
+/// Find all string literals in an expression.
+fn string_literals(expr: &str) -> Vec<String> {
+    lazy_regex::regex!(r#""((?:[^"\\]|\\.)*)""#)
+        .captures_iter(expr)
+        .map(|c| c.get(1).unwrap().as_str().replace("\\\"", "\""))
+        .collect()
+}
+
+/// Find all URLs in an expression, e.g. `url("https://example.com")` or `uri("...")`.
+fn urls(expr: &str) -> Vec<String> {
+    lazy_regex::regex!(r#"\b(?:url|uri|new URL)\(\s*"([^"]+)"\s*\)"#)
+        .captures_iter(expr)
+        .map(|c| c.get(1).unwrap().as_str().to_string())
+        .collect()
+}
+
+/// Strip comments from an sbt file.
+fn strip_comments(text: &str) -> String {
+    let text = lazy_regex::regex!(r"(?s)/\*.*?\*/").replace_all(text, "");
+    lazy_regex::regex!(r#"(?m)^((?:[^"/\n]|"(?:[^"\\\n]|\\.)*"|/[^/"\n])*)//.*$"#)
+        .replace_all(&text, "$1")
+        .into_owned()
+}
+
+pub fn guess_from_sbt(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    let text = strip_comments(&text);
+
+    let mut results = Vec::new();
+
+    let mut push = |datum, certainty| {
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        })
+    };
+
+    for expr in find_settings(&text, "name") {
+        if let Some(name) = string_literals(expr).into_iter().next() {
+            push(UpstreamDatum::Name(name), Certainty::Certain);
+        }
+    }
+
+    for expr in find_settings(&text, "version") {
+        if let Some(version) = string_literals(expr).into_iter().next() {
+            push(UpstreamDatum::Version(version), Certainty::Likely);
+        }
+    }
+
+    for expr in find_settings(&text, "description") {
+        if let Some(description) = string_literals(expr).into_iter().next() {
+            push(UpstreamDatum::Summary(description), Certainty::Certain);
+        }
+    }
+
+    for expr in find_settings(&text, "homepage") {
+        if let Some(url) = urls(expr).into_iter().next() {
+            push(UpstreamDatum::Homepage(url), Certainty::Certain);
+        }
+    }
+
+    for expr in find_settings(&text, "organizationHomepage") {
+        if let Some(url) = urls(expr).into_iter().next() {
+            push(UpstreamDatum::Homepage(url), Certainty::Possible);
+        }
+    }
+
+    for expr in find_settings(&text, "scmInfo") {
+        // ScmInfo(browseUrl, connection, devConnection)
+        if let Some(browse_url) = urls(expr).into_iter().next() {
+            if vcs::plausible_browse_url(&browse_url) {
+                push(
+                    UpstreamDatum::RepositoryBrowse(browse_url),
+                    Certainty::Certain,
+                );
+            }
+        }
+        if let Some(connection) = string_literals(expr)
+            .into_iter()
+            .find(|s| s.starts_with("scm:"))
+        {
+            // Maven-style connection strings, e.g. scm:git:https://github.com/foo/bar.git;
+            // some projects leave out the VCS name.
+            let connection = connection.trim_start_matches("scm:");
+            let url = match connection.split_once(':') {
+                Some((vcs, url)) if vcs::VCSES.contains(&vcs) || vcs == "svn" => url,
+                _ => connection,
+            };
+            push(
+                UpstreamDatum::Repository(url.to_string()),
+                Certainty::Certain,
+            );
+        }
+    }
+
+    for expr in find_settings(&text, "licenses") {
+        for c in lazy_regex::regex!(r#""([^"]+)"\s*->"#).captures_iter(expr) {
+            push(
+                UpstreamDatum::License(c.get(1).unwrap().as_str().to_string().into()),
+                Certainty::Certain,
+            );
+        }
+        // Predefined licenses, e.g. License.Apache2 or License.MIT
+        for c in lazy_regex::regex!(r"\bLicense\.([A-Za-z0-9]+)").captures_iter(expr) {
+            push(
+                UpstreamDatum::License(c.get(1).unwrap().as_str().to_string().into()),
+                Certainty::Likely,
+            );
+        }
+    }
+
+    for expr in find_settings(&text, "developers") {
+        // Developer(id, name, email, url)
+        let mut authors = Vec::new();
+        for m in lazy_regex::regex!(r"\bDeveloper\s*\(").find_iter(expr) {
+            let args = call_arguments(&expr[m.end()..]);
+            let strings = string_literals(args);
+            let person = Person {
+                name: strings.get(1).or(strings.first()).cloned(),
+                email: strings.get(2).cloned().filter(|e| !e.is_empty()),
+                url: urls(args).into_iter().next(),
+            };
+            if person.name.is_some() {
+                authors.push(person);
+            }
+        }
+        if !authors.is_empty() {
+            push(UpstreamDatum::Author(authors), Certainty::Certain);
+        }
+    }
+
+    // The organization is the group id, e.g. "org.example"; there is no field to store it.
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_expression() {
+        assert_eq!(extract_expression(" \"foo\"\nversion := 1"), " \"foo\"");
+        assert_eq!(
+            extract_expression(" Some(url(\"https://example.com\")),\n"),
+            " Some(url(\"https://example.com\"))"
+        );
+        assert_eq!(extract_expression(" \"foo\"))\n"), " \"foo\"");
+    }
+
+    #[test]
+    fn test_guess_from_sbt() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("build.sbt");
+        std::fs::write(
+            &path,
+            r#"// The library
+name := "example"
+
+ThisBuild / version := "0.4.1"
+
+inThisBuild(List(
+  organization := "org.example",
+  homepage := Some(url("https://example.org/")),
+  licenses := List("Apache-2.0" -> url("http://www.apache.org/licenses/LICENSE-2.0")),
+  developers := List(
+    Developer(
+      "jdoe",
+      "John Doe",
+      "john@example.com",
+      url("https://example.com/~jdoe")
+    )
+  ),
+  scmInfo := Some(ScmInfo(url("https://github.com/example/example"), "scm:git@github.com:example/example.git"))
+))
+
+description := "An example library" /* for testing */
+"#,
+        )
+        .unwrap();
+        let results = guess_from_sbt(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Name("example".to_string()),
+                UpstreamDatum::Version("0.4.1".to_string()),
+                UpstreamDatum::Summary("An example library".to_string()),
+                UpstreamDatum::Homepage("https://example.org/".to_string()),
+                UpstreamDatum::RepositoryBrowse("https://github.com/example/example".to_string()),
+                UpstreamDatum::Repository("git@github.com:example/example.git".to_string()),
+                UpstreamDatum::License("Apache-2.0".into()),
+                UpstreamDatum::Author(vec![Person {
+                    name: Some("John Doe".to_string()),
+                    email: Some("john@example.com".to_string()),
+                    url: Some("https://example.com/~jdoe".to_string()),
+                }]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,291 @@
+//! Mining of shell scripts and Makefile recipes that fetch or release upstream sources,
+//! such as debian/get-orig-source.sh, debian/orig-tar.sh or release.sh.
+
+pub use crate::version::version_from_tarball_name;
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Makefile targets that typically fetch or publish upstream sources.
+const SOURCE_TARGETS: &[&str] = &[
+    "get-orig-source",
+    "get-source",
+    "get-tarball",
+    "fetch",
+    "download",
+    "release",
+    "dist-upload",
+];
+
+/// Check whether a variable assignment sets the version, e.g. `VERSION=1.0`.
+fn version_from_assignment(name: &str, value: &str) -> Option<String> {
+    if lazy_regex::regex_is_match!(r"^(?i)(pkg_|package_|upstream_|release_)?version$", name)
+        && lazy_regex::regex_is_match!(r"^v?[0-9][0-9A-Za-z.+~-]*$", value)
+    {
+        Some(value.trim_start_matches('v').to_string())
+    } else {
+        None
+    }
+}
+
+/// Substitute references to literal variables, e.g. `$VERSION`, `${VERSION}` or `$(VERSION)`.
+fn substitute_variables(text: &str, variables: &HashMap<String, String>) -> String {
+    lazy_regex::regex!(
+        r"\$(?:\{([A-Za-z_][A-Za-z0-9_]*)\}|\(([A-Za-z_][A-Za-z0-9_]*)\)|([A-Za-z_][A-Za-z0-9_]*))"
+    )
+    .replace_all(text, |c: &regex::Captures| {
+        let name = c
+            .get(1)
+            .or_else(|| c.get(2))
+            .or_else(|| c.get(3))
+            .unwrap()
+            .as_str();
+        variables
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| c.get(0).unwrap().as_str().to_string())
+    })
+    .into_owned()
+}
+
+/// Parse a literal variable assignment, e.g. `VERSION=1.0`, `VERSION="1.0"` or `VERSION := 1.0`.
+fn parse_assignment(line: &str) -> Option<(&str, &str)> {
+    let (_, name, value) = lazy_regex::regex_captures!(
+        r#"^(?:export\s+)?([A-Za-z_][A-Za-z0-9_]*)\s*(?:=|:=|\?=|\s=)\s*(.*)$"#,
+        line.trim()
+    )?;
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+        .unwrap_or(value);
+    if value.is_empty() || value.contains('$') || value.contains('`') {
+        return None;
+    }
+    Some((name, value))
+}
+
+fn is_download_url(url: &url::Url) -> bool {
+    let basename = url.path_segments().and_then(|mut s| s.next_back());
+    basename.and_then(version_from_tarball_name).is_some()
+        || url.path().contains("/download")
+        || url.path().contains("/releases/")
+}
+
+/// Mine a set of shell command lines for repository, download and version information.
+fn mine_lines<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    path: &Path,
+    variables: &mut HashMap<String, String>,
+) -> Vec<UpstreamDatumWithMetadata> {
+    let mut results = Vec::new();
+    let mut push = |datum, certainty| {
+        results.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        });
+    };
+
+    for line in lines {
+        let line = line.trim().trim_start_matches('@');
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((name, value)) = parse_assignment(line) {
+            if let Some(version) = version_from_assignment(name, value) {
+                push(UpstreamDatum::Version(version), Certainty::Possible);
+            }
+            variables.insert(name.to_string(), value.to_string());
+            continue;
+        }
+
+        if let Some(url) = crate::vcs_command::url_from_vcs_command(line.as_bytes()) {
+            let certainty = if url.contains('$') {
+                Certainty::Possible
+            } else {
+                Certainty::Likely
+            };
+            push(UpstreamDatum::Repository(url), certainty);
+            continue;
+        }
+
+        if !lazy_regex::regex_is_match!(r"(^|[\s;&|(])(wget|curl)\s", line) {
+            continue;
+        }
+
+        let line = substitute_variables(line, variables);
+        for m in lazy_regex::regex!(r#"(?:https?|ftp)://[^\s"'<>|;)]+"#).find_iter(&line) {
+            if m.as_str().contains('$') {
+                continue;
+            }
+            let url = match url::Url::parse(m.as_str()) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+            if !is_download_url(&url) {
+                continue;
+            }
+            if let Some(version) = url
+                .path_segments()
+                .and_then(|mut s| s.next_back())
+                .and_then(version_from_tarball_name)
+            {
+                push(UpstreamDatum::Version(version), Certainty::Possible);
+            }
+            push(
+                UpstreamDatum::Download(url.to_string()),
+                Certainty::Possible,
+            );
+        }
+    }
+
+    results
+}
+
+/// Guess upstream metadata from a shell script that fetches or releases upstream sources.
+pub fn guess_from_shell_script(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read(path)?;
+    let text = String::from_utf8_lossy(&text);
+
+    Ok(mine_lines(text.lines(), path, &mut HashMap::new()))
+}
+
+/// Guess upstream metadata from the recipes of Makefile targets that fetch upstream sources,
+/// such as `get-orig-source`.
+pub fn guess_from_makefile(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read(path)?;
+    let text = String::from_utf8_lossy(&text);
+
+    let mut variables = HashMap::new();
+    let mut results = Vec::new();
+    let mut recipe: Option<Vec<&str>> = None;
+
+    for line in text.lines() {
+        if let Some(command) = line.strip_prefix('\t') {
+            if let Some(recipe) = recipe.as_mut() {
+                recipe.push(command);
+            }
+            continue;
+        }
+        if let Some(lines) = recipe.take() {
+            results.extend(mine_lines(lines.into_iter(), path, &mut variables));
+        }
+        if let Some((name, value)) = parse_assignment(line) {
+            if let Some(version) = version_from_assignment(name, value) {
+                results.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Version(version),
+                    certainty: Some(Certainty::Possible),
+                    origin: Some(path.into()),
+                });
+            }
+            variables.insert(name.to_string(), value.to_string());
+            continue;
+        }
+        if let Some((_, targets, _)) =
+            lazy_regex::regex_captures!(r"^([^:=\s][^:=]*):([^=]|$)", line)
+        {
+            if targets
+                .split_whitespace()
+                .any(|t| SOURCE_TARGETS.contains(&t))
+            {
+                recipe = Some(Vec::new());
+            }
+        }
+    }
+    if let Some(lines) = recipe.take() {
+        results.extend(mine_lines(lines.into_iter(), path, &mut variables));
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_from_tarball_name() {
+        assert_eq!(
+            Some("1.2.3".to_string()),
+            version_from_tarball_name("foo-1.2.3.tar.gz")
+        );
+        assert_eq!(
+            Some("0.9".to_string()),
+            version_from_tarball_name("foo-bar_0.9.orig.tar.xz")
+        );
+        assert_eq!(
+            Some("2.0rc1".to_string()),
+            version_from_tarball_name("foo-v2.0rc1.zip")
+        );
+        assert_eq!(None, version_from_tarball_name("foo.tar.gz"));
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let mut variables = HashMap::new();
+        variables.insert("VERSION".to_string(), "1.0".to_string());
+        assert_eq!(
+            "foo-1.0-1.0-1.0-$OTHER",
+            substitute_variables("foo-$VERSION-${VERSION}-$(VERSION)-$OTHER", &variables)
+        );
+    }
+
+    #[test]
+    fn test_guess_from_shell_script() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("orig-tar.sh");
+        std::fs::write(
+            &path,
+            r#"#!/bin/sh
+VERSION=2.4.1
+# wget https://example.com/old/foo-1.0.tar.gz
+wget -O foo.tar.gz "https://example.com/releases/foo-${VERSION}.tar.gz"
+curl -X POST https://api.example.com/notify
+"#,
+        )
+        .unwrap();
+        let results = guess_from_shell_script(&path, &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Version("2.4.1".to_string()),
+                UpstreamDatum::Version("2.4.1".to_string()),
+                UpstreamDatum::Download(
+                    "https://example.com/releases/foo-2.4.1.tar.gz".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guess_from_makefile() {
+        let td = tempfile::tempdir().unwrap();
+        let path = td.path().join("Makefile");
+        std::fs::write(
+            &path,
+            "VERSION := 0.3\n\nall:\n\tcurl -O https://example.com/unrelated-9.9.tar.gz\n\nget-orig-source:\n\thg clone https://hg.example.com/foo\n\tcurl -LO https://example.com/dl/foo-$(VERSION).tar.gz\n",
+        )
+        .unwrap();
+        let results = guess_from_makefile(&path, &GuesserSettings::default()).unwrap();
+        // Any Makefile may set VERSION, not necessarily to the upstream version
+        assert_eq!(results[0].certainty, Some(Certainty::Possible));
+        assert_eq!(
+            results.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![
+                UpstreamDatum::Version("0.3".to_string()),
+                UpstreamDatum::Repository("https://hg.example.com/foo".to_string()),
+                UpstreamDatum::Version("0.3".to_string()),
+                UpstreamDatum::Download("https://example.com/dl/foo-0.3.tar.gz".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Harvesting of copyright statements from the headers of source files.
+//!
+//! A bounded sample of source files is read, and the copyright statements in their
+//! headers are consolidated into a single Copyright value, e.g.
+//! "2011-2024 Jane Doe and contributors". This is useful as a starting point for
+//! e.g. debian/copyright, but less reliable than what the project itself declares.
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use lazy_regex::regex;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Default for the maximum number of source files that are scanned.
+pub const DEFAULT_MAX_FILES: usize = 200;
+
+/// Number of lines at the start of a file that are considered the header.
+const HEADER_LINES: usize = 40;
+
+/// Extensions of source files that commonly carry copyright headers.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "c", "h", "cc", "cpp", "cxx", "hh", "hpp", "m", "mm", "rs", "go", "py", "pl", "pm", "rb",
+    "php", "java", "kt", "scala", "cs", "swift", "js", "ts", "jsx", "tsx", "sh", "el", "lisp",
+    "scm", "hs", "ml", "mli", "lua", "vala", "tcl", "f90", "ads", "adb",
+];
+
+/// Directories that are not scanned, because they don't contain upstream code or contain
+/// code from other projects.
+const SKIPPED_DIRECTORIES: &[&str] = &[
+    "debian",
+    "vendor",
+    "third_party",
+    "third-party",
+    "node_modules",
+    "target",
+    "build",
+    "dist",
+    "_build",
+];
+
+/// Pick the source files to scan, in breadth-first order so that top-level files come first.
+fn source_files(root: &Path, max_files: usize) -> Vec<PathBuf> {
+    let mut ret = vec![];
+    let mut queue = VecDeque::from([root.to_path_buf()]);
+    while let Some(dir) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries = entries.filter_map(Result::ok).collect::<Vec<_>>();
+        entries.sort_by_key(|e| e.file_name());
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                    queue.push_back(entry.path());
+                }
+            } else if file_type.is_file()
+                && entry
+                    .path()
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| SOURCE_EXTENSIONS.contains(&e))
+            {
+                ret.push(entry.path());
+                if ret.len() >= max_files {
+                    return ret;
+                }
+            }
+        }
+    }
+    ret
+}
+
+/// A copyright statement, e.g. "Copyright (C) 2011-2013 Jane Doe <jane@example.com>".
+#[derive(Debug, PartialEq, Eq)]
+struct Statement {
+    years: Vec<u32>,
+    holder: String,
+}
+
+/// Parse a line from a file header as a copyright statement.
+fn parse_statement(line: &str) -> Option<Statement> {
+    let c = regex!(
+        r"(?i)^[\s/*#;%!-]*copyright\s*(?:(?:\(c\)|©)\s*)?((?:\d{4}(?:\s*[-,]\s*|\s+))+)(?:by\s+)?(.+)$"
+    )
+    .captures(line)?;
+    let mut years = vec![];
+    for range in c[1].split(',') {
+        let bounds = range
+            .split('-')
+            .filter_map(|y| y.trim().parse::<u32>().ok())
+            .collect::<Vec<_>>();
+        match bounds.as_slice() {
+            [year] => years.push(*year),
+            [from, to] if from <= to => years.extend([*from, *to]),
+            _ => {}
+        }
+    }
+    let holder = regex!(r"<[^>]*>").replace_all(&c[2], "");
+    let holder = regex!(r"(?i)\s*all rights reserved.*$").replace(&holder, "");
+    let holder = holder
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(['.', ',', '*', '/'])
+        .trim()
+        .to_string();
+    if years.is_empty() || holder.is_empty() {
+        return None;
+    }
+    Some(Statement { years, holder })
+}
+
+fn header_statements(path: &Path) -> Vec<Statement> {
+    let Ok(file) = File::open(path) else {
+        return vec![];
+    };
+    BufReader::new(file)
+        .lines()
+        .take(HEADER_LINES)
+        .map_while(Result::ok)
+        .filter_map(|line| parse_statement(&line))
+        .collect()
+}
+
+/// Consolidate copyright statements into a single value.
+///
+/// The holder that appears in the most statements is named; others are summarized as
+/// "contributors". The years cover all statements.
+fn consolidate(statements: &[Statement]) -> Option<String> {
+    let mut holders: Vec<(&str, usize)> = vec![];
+    for statement in statements {
+        match holders
+            .iter_mut()
+            .find(|(h, _)| h.eq_ignore_ascii_case(&statement.holder))
+        {
+            Some((_, count)) => *count += 1,
+            None => holders.push((&statement.holder, 1)),
+        }
+    }
+    // Ties are broken by the order in which holders were first seen
+    let (holder, _) = holders
+        .iter()
+        .copied()
+        .reduce(|a, b| if b.1 > a.1 { b } else { a })?;
+    let first = statements.iter().flat_map(|s| &s.years).min()?;
+    let last = statements.iter().flat_map(|s| &s.years).max()?;
+    let years = if first == last {
+        first.to_string()
+    } else {
+        format!("{}-{}", first, last)
+    };
+    Some(if holders.len() > 1 {
+        format!("{} {} and contributors", years, holder)
+    } else {
+        format!("{} {}", years, holder)
+    })
+}
+
+/// Build a Copyright value from the copyright statements in the headers of source files.
+///
+/// At most [`GuesserSettings::max_copyright_files`] files are scanned, or
+/// [`GuesserSettings::max_files`] if that is not set.
+pub fn guess_from_source_headers(
+    path: &Path,
+    settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let max_files = settings
+        .max_copyright_files
+        .or(settings.max_files)
+        .unwrap_or(DEFAULT_MAX_FILES);
+    let statements = source_files(path, max_files)
+        .iter()
+        .flat_map(|f| header_statements(f))
+        .collect::<Vec<_>>();
+    Ok(consolidate(&statements)
+        .map(|copyright| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Copyright(copyright),
+            certainty: Some(Certainty::Possible),
+            origin: Some(path.into()),
+        })
+        .into_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_statement() {
+        assert_eq!(
+            parse_statement(" * Copyright (C) 2011-2013, 2015 Jane Doe <jane@example.com>"),
+            Some(Statement {
+                years: vec![2011, 2013, 2015],
+                holder: "Jane Doe".to_string()
+            })
+        );
+        assert_eq!(
+            parse_statement("# Copyright © 2024 by Example Ltd. All rights reserved."),
+            Some(Statement {
+                years: vec![2024],
+                holder: "Example Ltd".to_string()
+            })
+        );
+        assert_eq!(
+            parse_statement("// Copyright holders are listed in AUTHORS"),
+            None
+        );
+        assert_eq!(parse_statement("let copyright = 2024;"), None);
+    }
+
+    #[test]
+    fn test_guess_from_source_headers() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(td.path().join("src")).unwrap();
+        std::fs::create_dir_all(td.path().join("vendor")).unwrap();
+        std::fs::write(
+            td.path().join("main.c"),
+            "/* Copyright (C) 2011 Jane Doe <jane@example.com> */\n",
+        )
+        .unwrap();
+        std::fs::write(
+            td.path().join("src/lib.rs"),
+            "// Copyright 2020-2024 Jane Doe\n// Copyright 2022 John Smith\n",
+        )
+        .unwrap();
+        std::fs::write(
+            td.path().join("vendor/other.c"),
+            "/* Copyright 1999 Someone Else */\n",
+        )
+        .unwrap();
+        std::fs::write(td.path().join("README"), "Copyright 1990 Nobody\n").unwrap();
+
+        let guessed = guess_from_source_headers(td.path(), &GuesserSettings::default()).unwrap();
+        assert_eq!(
+            guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![UpstreamDatum::Copyright(
+                "2011-2024 Jane Doe and contributors".to_string()
+            )]
+        );
+
+        // Only the top-level file is scanned
+        let settings = GuesserSettings {
+            max_copyright_files: Some(1),
+            ..Default::default()
+        };
+        let guessed = guess_from_source_headers(td.path(), &settings).unwrap();
+        assert_eq!(
+            guessed.into_iter().map(|d| d.datum).collect::<Vec<_>>(),
+            vec![UpstreamDatum::Copyright("2011 Jane Doe".to_string())]
+        );
+    }
+}
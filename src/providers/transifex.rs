@@ -0,0 +1,101 @@
+//! Transifex client configuration, in .tx/config.
+//!
+//! See <https://developers.transifex.com/docs/using-the-client>
+use crate::{Certainty, GuesserSettings, ProviderError, UpstreamDatum, UpstreamDatumWithMetadata};
+use std::path::Path;
+
+/// Determine the project URL from the contents of a .tx/config file.
+fn project_url_from_tx_config(text: &str) -> Option<(String, Certainty)> {
+    let mut host = None;
+    let mut section = None;
+    let mut resources = vec![];
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.trim());
+            if name.trim() != "main" {
+                resources.push(name.trim());
+            }
+        } else if section == Some("main") {
+            if let Some((key, value)) = line.split_once('=') {
+                if key.trim() == "host" {
+                    host = Some(value.trim().trim_end_matches('/'));
+                }
+            }
+        }
+    }
+
+    for resource in resources {
+        // Current format: o:<organization>:p:<project>:r:<resource>
+        if let Some((_, organization, project)) =
+            lazy_regex::regex_captures!(r"^o:([^:]+):p:([^:]+):r:", resource)
+        {
+            return Some((
+                format!("https://app.transifex.com/{}/{}/", organization, project),
+                Certainty::Certain,
+            ));
+        }
+        // Legacy format: <project>.<resource>; the organization is not known
+        if let Some((project, _)) = resource.split_once('.') {
+            return Some((
+                format!(
+                    "{}/projects/p/{}/",
+                    host.unwrap_or("https://www.transifex.com"),
+                    project
+                ),
+                Certainty::Likely,
+            ));
+        }
+    }
+    None
+}
+
+pub fn guess_from_tx_config(
+    path: &Path,
+    _settings: &GuesserSettings,
+) -> std::result::Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+    let text = std::fs::read_to_string(path)?;
+    Ok(project_url_from_tx_config(&text)
+        .map(|(url, certainty)| UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Translations(url),
+            certainty: Some(certainty),
+            origin: Some(path.into()),
+        })
+        .into_iter()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_url_from_tx_config() {
+        assert_eq!(
+            project_url_from_tx_config(
+                r#"[main]
+host = https://app.transifex.com
+
+[o:dulwich:p:dulwich-docs:r:index]
+file_filter = locale/<lang>/LC_MESSAGES/index.po
+source_file = locale/index.pot
+type = PO
+"#
+            ),
+            Some((
+                "https://app.transifex.com/dulwich/dulwich-docs/".to_string(),
+                Certainty::Certain
+            ))
+        );
+        assert_eq!(
+            project_url_from_tx_config(
+                "[main]\nhost = https://www.transifex.com\n\n[dulwich.messages]\nsource_lang = en\n"
+            ),
+            Some((
+                "https://www.transifex.com/projects/p/dulwich/".to_string(),
+                Certainty::Likely
+            ))
+        );
+        assert_eq!(project_url_from_tx_config("[main]\n"), None);
+    }
+}
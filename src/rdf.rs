@@ -0,0 +1,332 @@
+//! Export of upstream metadata as RDF.
+//!
+//! The project is described as a schema.org `SoftwareSourceCode`, so that the metadata can be
+//! loaded directly into knowledge graphs. Fields that schema.org has no terms for (such as the
+//! bug database or mailing list) use the DOAP vocabulary instead.
+//!
+//! Both Turtle and JSON-LD are supported.
+
+use crate::{Person, UpstreamDatum, UpstreamMetadata};
+
+pub const SCHEMA_NS: &str = "https://schema.org/";
+pub const DOAP_NS: &str = "http://usefulinc.com/ns/doap#";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Vocabulary {
+    Schema,
+    Doap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Predicate(Vocabulary, &'static str);
+
+impl Predicate {
+    fn turtle(&self) -> String {
+        match self.0 {
+            Vocabulary::Schema => format!("schema:{}", self.1),
+            Vocabulary::Doap => format!("doap:{}", self.1),
+        }
+    }
+
+    fn jsonld(&self) -> String {
+        match self.0 {
+            Vocabulary::Schema => self.1.to_string(),
+            Vocabulary::Doap => format!("doap:{}", self.1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Object {
+    Iri(String),
+    Literal(String),
+    /// A blank node of the given schema.org type
+    Node(&'static str, Vec<(Predicate, Object)>),
+}
+
+const fn schema(name: &'static str) -> Predicate {
+    Predicate(Vocabulary::Schema, name)
+}
+
+const fn doap(name: &'static str) -> Predicate {
+    Predicate(Vocabulary::Doap, name)
+}
+
+/// Use an IRI for URL-valued fields, unless the value is not actually a URL.
+fn iri_or_literal(datum: &UpstreamDatum, value: &str) -> Object {
+    match datum.to_url() {
+        Some(url) if !url.cannot_be_a_base() => Object::Iri(url.to_string()),
+        _ => Object::Literal(value.to_string()),
+    }
+}
+
+fn person_node(person: &Person) -> Option<Object> {
+    let mut properties = vec![];
+    if let Some(name) = person.name.as_ref() {
+        properties.push((schema("name"), Object::Literal(name.clone())));
+    }
+    if let Some(email) = person.email.as_ref() {
+        properties.push((schema("email"), Object::Literal(email.clone())));
+    }
+    if let Some(url) = person.url.as_ref() {
+        properties.push((schema("url"), Object::Iri(url.clone())));
+    }
+    if properties.is_empty() {
+        return None;
+    }
+    let kind = if person.is_organization() {
+        "Organization"
+    } else {
+        "Person"
+    };
+    Some(Object::Node(kind, properties))
+}
+
+fn license_object(license: &str) -> Object {
+    if !license.is_empty()
+        && license
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '+')
+    {
+        Object::Iri(format!("https://spdx.org/licenses/{}", license))
+    } else {
+        Object::Literal(license.to_string())
+    }
+}
+
+fn properties(metadata: &UpstreamMetadata) -> Vec<(Predicate, Object)> {
+    let mut ret = vec![];
+    for item in metadata.iter() {
+        let datum = &item.datum;
+        match datum {
+            UpstreamDatum::Name(n) => ret.push((schema("name"), Object::Literal(n.clone()))),
+            UpstreamDatum::Aliases(aliases) => ret.extend(
+                aliases
+                    .iter()
+                    .map(|a| (schema("alternateName"), Object::Literal(a.clone()))),
+            ),
+            UpstreamDatum::Summary(s) => ret.push((schema("abstract"), Object::Literal(s.clone()))),
+            UpstreamDatum::Description(d) => {
+                ret.push((schema("description"), Object::Literal(d.clone())))
+            }
+            UpstreamDatum::Version(v) => ret.push((schema("version"), Object::Literal(v.clone()))),
+            UpstreamDatum::Homepage(h) => ret.push((schema("url"), iri_or_literal(datum, h))),
+            UpstreamDatum::Repository(r) => {
+                ret.push((schema("codeRepository"), iri_or_literal(datum, r)))
+            }
+            UpstreamDatum::Download(d) => {
+                ret.push((schema("downloadUrl"), iri_or_literal(datum, d)))
+            }
+            UpstreamDatum::Documentation(d) => {
+                ret.push((schema("softwareHelp"), iri_or_literal(datum, d)))
+            }
+            UpstreamDatum::Changelog(c) => {
+                ret.push((schema("releaseNotes"), iri_or_literal(datum, c)))
+            }
+            UpstreamDatum::License(l) => ret.push((schema("license"), license_object(l.as_str()))),
+            UpstreamDatum::Keywords(keywords) => ret.extend(
+                keywords
+                    .iter()
+                    .map(|k| (schema("keywords"), Object::Literal(k.clone()))),
+            ),
+            UpstreamDatum::Screenshots(screenshots) => ret.extend(
+                screenshots
+                    .iter()
+                    .map(|s| (schema("screenshot"), Object::Iri(s.clone()))),
+            ),
+            UpstreamDatum::Author(authors) => ret.extend(
+                authors
+                    .iter()
+                    .filter_map(person_node)
+                    .map(|a| (schema("author"), a)),
+            ),
+            UpstreamDatum::Maintainer(maintainer) => {
+                if let Some(node) = person_node(maintainer) {
+                    ret.push((schema("maintainer"), node));
+                }
+            }
+            UpstreamDatum::Organization(o) => ret.push((
+                schema("publisher"),
+                Object::Node(
+                    "Organization",
+                    vec![(schema("name"), Object::Literal(o.clone()))],
+                ),
+            )),
+            UpstreamDatum::MaintenanceStatus(s) => {
+                ret.push((schema("creativeWorkStatus"), Object::Literal(s.clone())))
+            }
+            UpstreamDatum::BugDatabase(b) => {
+                ret.push((doap("bug-database"), iri_or_literal(datum, b)))
+            }
+            UpstreamDatum::MailingList(m) => {
+                ret.push((doap("mailing-list"), iri_or_literal(datum, m)))
+            }
+            UpstreamDatum::Wiki(w) => ret.push((doap("wiki"), iri_or_literal(datum, w))),
+            _ => {}
+        }
+    }
+    ret
+}
+
+fn turtle_escape(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => ret.push_str("\\\\"),
+            '"' => ret.push_str("\\\""),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+fn write_turtle_properties(
+    out: &mut String,
+    kind: &str,
+    properties: &[(Predicate, Object)],
+    indent: usize,
+) {
+    let prefix = " ".repeat(indent);
+    out.push_str(&format!("{}a schema:{}", prefix, kind));
+    for (predicate, object) in properties {
+        out.push_str(" ;\n");
+        out.push_str(&format!("{}{} ", prefix, predicate.turtle()));
+        match object {
+            Object::Iri(iri) => out.push_str(&format!("<{}>", iri.replace('>', "%3E"))),
+            Object::Literal(l) => out.push_str(&format!("\"{}\"", turtle_escape(l))),
+            Object::Node(kind, properties) => {
+                out.push_str("[\n");
+                write_turtle_properties(out, kind, properties, indent + 4);
+                out.push_str(&format!("\n{}]", prefix));
+            }
+        }
+    }
+}
+
+/// Serialize metadata as Turtle.
+pub fn to_turtle(metadata: &UpstreamMetadata) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("@prefix schema: <{}> .\n", SCHEMA_NS));
+    out.push_str(&format!("@prefix doap: <{}> .\n", DOAP_NS));
+    out.push_str("\n[]\n");
+    write_turtle_properties(&mut out, "SoftwareSourceCode", &properties(metadata), 4);
+    out.push_str(" .\n");
+    out
+}
+
+fn jsonld_object(kind: &str, properties: &[(Predicate, Object)]) -> serde_json::Value {
+    let mut map = serde_json::Map::new();
+    map.insert("@type".to_string(), kind.into());
+    for (predicate, object) in properties {
+        let value = match object {
+            Object::Iri(iri) => serde_json::json!({ "@id": iri }),
+            Object::Literal(l) => l.clone().into(),
+            Object::Node(kind, properties) => jsonld_object(kind, properties),
+        };
+        // Repeated properties become arrays
+        match map.get_mut(&predicate.jsonld()) {
+            Some(serde_json::Value::Array(values)) => values.push(value),
+            Some(existing) => *existing = serde_json::Value::Array(vec![existing.take(), value]),
+            None => {
+                map.insert(predicate.jsonld(), value);
+            }
+        }
+    }
+    serde_json::Value::Object(map)
+}
+
+/// Serialize metadata as a JSON-LD document.
+pub fn to_jsonld(metadata: &UpstreamMetadata) -> serde_json::Value {
+    let mut ret = jsonld_object("SoftwareSourceCode", &properties(metadata));
+    ret.as_object_mut().unwrap().insert(
+        "@context".to_string(),
+        serde_json::json!({
+            "@vocab": SCHEMA_NS,
+            "doap": DOAP_NS,
+        }),
+    );
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Certainty, UpstreamDatumWithMetadata};
+
+    fn metadata() -> UpstreamMetadata {
+        let mut metadata = UpstreamMetadata::new();
+        for datum in [
+            UpstreamDatum::Name("foo".to_string()),
+            UpstreamDatum::Summary("A \"foo\" library".to_string()),
+            UpstreamDatum::Repository("https://github.com/example/foo.git".to_string()),
+            UpstreamDatum::License("GPL-2.0-or-later".into()),
+            UpstreamDatum::Author(vec![Person {
+                name: Some("Jane Doe".to_string()),
+                email: Some("jane@example.com".to_string()),
+                url: None,
+            }]),
+            UpstreamDatum::Keywords(vec!["foo".to_string(), "bar".to_string()]),
+            UpstreamDatum::BugDatabase("https://github.com/example/foo/issues".to_string()),
+        ] {
+            metadata.insert(UpstreamDatumWithMetadata {
+                datum,
+                certainty: Some(Certainty::Certain),
+                origin: None,
+            });
+        }
+        metadata
+    }
+
+    #[test]
+    fn test_to_turtle() {
+        assert_eq!(
+            to_turtle(&metadata()),
+            r#"@prefix schema: <https://schema.org/> .
+@prefix doap: <http://usefulinc.com/ns/doap#> .
+
+[]
+    a schema:SoftwareSourceCode ;
+    schema:name "foo" ;
+    schema:abstract "A \"foo\" library" ;
+    schema:codeRepository <https://github.com/example/foo.git> ;
+    schema:license <https://spdx.org/licenses/GPL-2.0-or-later> ;
+    schema:author [
+        a schema:Person ;
+        schema:name "Jane Doe" ;
+        schema:email "jane@example.com"
+    ] ;
+    schema:keywords "foo" ;
+    schema:keywords "bar" ;
+    doap:bug-database <https://github.com/example/foo/issues> .
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_jsonld() {
+        assert_eq!(
+            to_jsonld(&metadata()),
+            serde_json::json!({
+                "@context": {
+                    "@vocab": "https://schema.org/",
+                    "doap": "http://usefulinc.com/ns/doap#",
+                },
+                "@type": "SoftwareSourceCode",
+                "name": "foo",
+                "abstract": "A \"foo\" library",
+                "codeRepository": {"@id": "https://github.com/example/foo.git"},
+                "license": {"@id": "https://spdx.org/licenses/GPL-2.0-or-later"},
+                "author": {
+                    "@type": "Person",
+                    "name": "Jane Doe",
+                    "email": "jane@example.com",
+                },
+                "keywords": ["foo", "bar"],
+                "doap:bug-database": {"@id": "https://github.com/example/foo/issues"},
+            })
+        );
+    }
+}
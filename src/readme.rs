@@ -27,7 +27,7 @@ pub fn skip_paragraph(para: &str) -> (bool, Vec<UpstreamDatumWithMetadata>) {
 
     if let Some(m) = regex!(r"(?ms)^It is licensed under (.*)").captures(para) {
         ret.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string()),
+            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string().into()),
             certainty: Some(Certainty::Possible),
             origin: None,
         });
@@ -36,7 +36,7 @@ pub fn skip_paragraph(para: &str) -> (bool, Vec<UpstreamDatumWithMetadata>) {
 
     if let Some(m) = regex!(r"(?ms)^License: (.*)").captures(para) {
         ret.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string()),
+            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string().into()),
             certainty: Some(Certainty::Likely),
             origin: None,
         });
@@ -107,7 +107,7 @@ pub fn skip_paragraph(para: &str) -> (bool, Vec<UpstreamDatumWithMetadata>) {
         .captures(para)
     {
         ret.push(UpstreamDatumWithMetadata {
-            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string()),
+            datum: UpstreamDatum::License(m.get(1).unwrap().as_str().to_string().into()),
             certainty: Some(Certainty::Likely),
             origin: None,
         });
@@ -196,48 +196,232 @@ pub fn skip_paragraph(para: &str) -> (bool, Vec<UpstreamDatumWithMetadata>) {
     (false, ret)
 }
 
-pub fn description_from_readme_rst(
-    long_description: &str,
-) -> Result<(Option<String>, Vec<UpstreamDatumWithMetadata>), ProviderError> {
-    // Work around https://github.com/flying-sheep/rust-rst/issues/55
+/// Directives that the reStructuredText renderer supports.
+///
+/// Other directives (e.g. Sphinx's "toctree" or "automodule", or "raw" HTML) are dropped
+/// before rendering, as are generic admonitions, which the parser accepts but can not convert.
+const RST_SUPPORTED_DIRECTIVES: &[&str] = &[
+    "image",
+    "code",
+    "code-block",
+    "replace",
+    "attention",
+    "caution",
+    "danger",
+    "error",
+    "hint",
+    "important",
+    "note",
+    "tip",
+    "warning",
+];
+
+/// A reStructuredText document, with the constructs that the renderer does not handle taken
+/// out.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct RstDocument<'a> {
+    /// The text that is left to render
+    text: String,
+
+    /// Field lists, e.g. ":Homepage: https://example.com/"
+    fields: Vec<(&'a str, String)>,
+
+    /// Hyperlink targets, e.g. ".. _homepage: https://example.com/", by normalized name
+    targets: std::collections::HashMap<String, String>,
+
+    /// The subtitle of the document
+    subtitle: Option<String>,
+}
+
+/// Normalize the name of a hyperlink target, for matching references against it.
+fn normalize_rst_target_name(name: &str) -> String {
+    name.trim()
+        .trim_matches('`')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// The title in the overline style at the start of `lines`, and its adornment character.
+fn rst_overlined_title<'a>(lines: &[&'a str]) -> Option<(&'a str, char)> {
+    let [overline, title, underline, ..] = lines else {
+        return None;
+    };
+    let adornment = overline.trim_end();
+    let c = adornment.chars().next()?;
+    if adornment.len() < 3
+        || !c.is_ascii_punctuation()
+        || adornment.chars().any(|x| x != c)
+        || underline.trim_end() != adornment
+        || title.trim().is_empty()
+    {
+        return None;
+    }
+    Some((title.trim(), c))
+}
+
+/// Replace interpreted text with roles (e.g. ":ref:`usage <usage>`", as used by Sphinx) by its
+/// text, since the renderer doesn't know about roles.
+fn strip_rst_roles(line: &str) -> std::borrow::Cow<'_, str> {
+    regex!(r":[\w:+.-]+:`([^`<]*?)\s*(?:<[^>`]*>)?`").replace_all(line, |c: &regex::Captures| {
+        c[1].trim_start_matches(['~', '!']).to_string()
+    })
+}
+
+/// Separate the constructs that the reStructuredText renderer can't handle from a document.
+fn preprocess_rst(text: &str) -> RstDocument<'_> {
+    let mut doc = RstDocument::default();
+    let lines = text.lines().collect::<Vec<_>>();
+
+    // A title and subtitle, both with overlines, at the start of the document
+    let mut start = lines.iter().take_while(|l| l.trim().is_empty()).count();
+    if let Some((_, title_char)) = rst_overlined_title(&lines[start..]) {
+        let next = start
+            + 3
+            + lines[start + 3..]
+                .iter()
+                .take_while(|l| l.trim().is_empty())
+                .count();
+        if let Some((subtitle, c)) = rst_overlined_title(&lines[next..]) {
+            if c != title_char {
+                doc.subtitle = Some(subtitle.to_string());
+                doc.text
+                    .extend(lines[start..start + 3].iter().map(|l| format!("{}\n", l)));
+                start = next + 3;
+            }
+        }
+    }
 
-    let mut fields: Vec<(&str, String)> = Vec::new();
     let mut in_field = false;
+    // The indentation of the directive whose body is being dropped
+    let mut dropping: Option<usize> = None;
+    let mut in_table = false;
+
+    for line in &lines[start..] {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(directive_indent) = dropping {
+            if line.trim().is_empty() || indent > directive_indent {
+                continue;
+            }
+            dropping = None;
+        }
+        if in_table {
+            if !line.trim().is_empty() {
+                continue;
+            }
+            in_table = false;
+        }
 
-    let long_description = long_description
-        .lines()
-        .filter(|line| {
-            // Filter out field lists. Syntax is:
-            // :field: value
-            // with possible continuation lines that are indented.
-            // field can contain any character except a colon followed by a space unless
-            // it is escaped with a backslash.
-            if line.starts_with([' ', '\t'].as_ref()) && in_field {
-                if in_field {
-                    fields.last_mut().unwrap().1.push_str(line.trim());
-                    return false;
-                }
-                return true;
-            } else {
-                in_field = false;
+        // Field lists: ":field: value", with possible continuation lines that are indented.
+        // field can contain any character except a colon followed by a space unless it is
+        // escaped with a backslash.
+        if line.starts_with([' ', '\t']) && in_field {
+            doc.fields
+                .last_mut()
+                .unwrap()
+                .1
+                .push_str(&format!(" {}", line.trim()));
+            continue;
+        }
+        in_field = false;
+        if let Some((_, field, value)) = lazy_regex::regex_captures!(r"^:([^:]+): (.*)", line) {
+            doc.fields.push((field, value.trim().to_string()));
+            in_field = true;
+            continue;
+        }
+
+        if let Some((_, name)) =
+            lazy_regex::regex_captures!(r"^\s*\.\.\s+(?:\|[^|]+\|\s+)?([A-Za-z][\w:+.-]*)::", line)
+        {
+            if !RST_SUPPORTED_DIRECTIVES.contains(&name.to_lowercase().as_str()) {
+                dropping = Some(indent);
+                continue;
             }
-            if let Some((_, field, value)) = lazy_regex::regex_captures!(r"^:([^:]+): (.*)", line) {
-                fields.push((field, value.to_string()));
-                in_field = true;
-                false
-            } else {
-                line != &"----"
+        } else if let Some((_, name, url)) =
+            lazy_regex::regex_captures!(r"^\s*\.\.\s+_(`[^`]+`|[^:`]+):\s+(\S+)\s*$", line)
+        {
+            doc.targets
+                .insert(normalize_rst_target_name(name), url.to_string());
+        } else if regex!(r"^\s*\+[-=+]+\+\s*$").is_match(line) {
+            // Grid tables
+            in_table = true;
+            continue;
+        } else if line.trim_end().ends_with("::") && !line.trim_start().starts_with("..") {
+            // "Paragraph::" introduces a literal block; the renderer only supports "::" on
+            // its own.
+            let text = line.trim_end().strip_suffix("::").unwrap();
+            if !text.trim().is_empty() {
+                let text = strip_rst_roles(text);
+                if text.ends_with(char::is_whitespace) {
+                    doc.text.push_str(&format!("{}\n\n", text.trim_end()));
+                } else {
+                    doc.text.push_str(&format!("{}:\n\n", text));
+                }
+                doc.text.push_str(&format!("{}::\n", &line[..indent]));
+                continue;
             }
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
-        + "\n";
+        } else if line.trim_end() == "----" {
+            continue;
+        }
+        doc.text.push_str(&strip_rst_roles(line));
+        doc.text.push('\n');
+    }
+    // The parser doesn't accept more than one blank line at the end
+    doc.text.truncate(doc.text.trim_end().len());
+    doc.text.push('\n');
+    doc
+}
+
+/// Resolve a field value that is a reference (e.g. "`GitHub <https://...>`_" or "`docs`_")
+/// to the URL it points at.
+fn resolve_rst_reference(
+    value: &str,
+    targets: &std::collections::HashMap<String, String>,
+) -> String {
+    let value = value.trim();
+    if let Some((_, target)) = lazy_regex::regex_captures!(r"^`[^`<]*<([^>]+)>`__?$", value) {
+        return match target.strip_suffix('_') {
+            Some(name) => targets.get(&normalize_rst_target_name(name)).cloned(),
+            None => Some(target.to_string()),
+        }
+        .unwrap_or_else(|| value.to_string());
+    }
+    if let Some((_, name)) = lazy_regex::regex_captures!(r"^(`[^`]+`|[\w.-]+)__?$", value) {
+        if let Some(url) = targets.get(&normalize_rst_target_name(name)) {
+            return url.clone();
+        }
+    }
+    if let Some((_, url)) = lazy_regex::regex_captures!(r"^<(\S+)>$", value) {
+        return url.to_string();
+    }
+    value.to_string()
+}
+
+pub fn description_from_readme_rst(
+    long_description: &str,
+) -> Result<(Option<String>, Vec<UpstreamDatumWithMetadata>), ProviderError> {
+    // Work around https://github.com/flying-sheep/rust-rst/issues/55
+    let doc = preprocess_rst(long_description);
 
-    let html = rst_to_html(&long_description);
+    let (description, mut md) = match rst_to_html(&doc.text) {
+        Ok(html) => description_from_readme_html(&html)?,
+        Err(e) => {
+            log::debug!("Unable to render README: {}", e);
+            (None, vec![])
+        }
+    };
 
-    let (description, mut md) = description_from_readme_html(&html)?;
+    if let Some(subtitle) = doc.subtitle {
+        md.push(UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Summary(subtitle),
+            certainty: Some(Certainty::Likely),
+            origin: None,
+        });
+    }
 
-    for (field, value) in fields {
+    for (field, value) in doc.fields {
+        let value = resolve_rst_reference(&value, &doc.targets);
         md.extend(parse_field(field, &NodeOrText::Text(&value)));
     }
 
@@ -255,6 +439,198 @@ pub fn description_from_readme_md(
     description_from_readme_html(&html_output)
 }
 
+/// Find chat channels mentioned in a line of text.
+fn chat_urls_from_line(line: &str) -> Vec<String> {
+    let mut ret = vec![];
+    for m in lazy_regex::regex!(r#"(?:ircs?|https?)://[^\s<>()\[\]"'`]+"#).find_iter(line) {
+        let url = m.as_str().trim_end_matches(['.', ',']);
+        if url.parse().is_ok_and(|u: url::Url| crate::is_chat_url(&u)) {
+            ret.push(url.to_string());
+        }
+    }
+    // e.g. "#dulwich on irc.libera.chat" or "the #dulwich channel on OFTC"
+    if let Some((_, channel, network)) = lazy_regex::regex_captures!(
+        r"(#[\w.-]*\w)\s+(?:channel\s+)?on\s+(?:the\s+)?(irc\.[\w.-]*\w|(?i:libera\.chat|oftc))",
+        line
+    ) {
+        let host = match network.to_lowercase().as_str() {
+            "libera.chat" => "irc.libera.chat".to_string(),
+            "oftc" => "irc.oftc.net".to_string(),
+            host => host.to_string(),
+        };
+        let url = format!("irc://{}/{}", host, channel);
+        if !ret.contains(&url) {
+            ret.push(url);
+        }
+    }
+    ret
+}
+
+/// The image and link URLs of the badges in a Markdown README.
+///
+/// Badges are images that are optionally wrapped in a link, either in Markdown syntax
+/// (`[![CI](image)](target)`) or as inline HTML.
+fn badge_urls_from_md(text: &str) -> Vec<Url> {
+    let mut urls = vec![];
+    for c in regex!(r#"!\[[^\]]*\]\(\s*<?([^)\s>]+)>?[^)]*\)(?:\]\(\s*<?([^)\s>]+)>?[^)]*\))?"#)
+        .captures_iter(text)
+    {
+        urls.extend(c.iter().skip(1).flatten().map(|m| m.as_str()));
+    }
+    for c in regex!(r#"(?i)<a\s[^>]*href=["']([^"']+)["'][^>]*>\s*<img\s[^>]*src=["']([^"']+)["']"#)
+        .captures_iter(text)
+    {
+        urls.extend(c.iter().skip(1).flatten().map(|m| m.as_str()));
+    }
+    urls.into_iter().filter_map(|u| u.parse().ok()).collect()
+}
+
+/// The image and link URLs of the badges in a reStructuredText README.
+///
+/// Badges are image directives (possibly substitution definitions), with an optional
+/// `:target:` option.
+fn badge_urls_from_rst(text: &str) -> Vec<Url> {
+    let mut urls = vec![];
+    let mut in_image = false;
+    for line in text.lines() {
+        if let Some((_, image)) =
+            lazy_regex::regex_captures!(r"^\s*\.\. (?:\|[^|]+\|\s+)?image::\s+(\S+)", line)
+        {
+            urls.push(image);
+            in_image = true;
+        } else if !in_image || !line.starts_with([' ', '\t']) {
+            in_image = false;
+        } else if let Some((_, target)) = lazy_regex::regex_captures!(r"^\s+:target:\s+(\S+)", line)
+        {
+            urls.push(target);
+        }
+    }
+    urls.into_iter().filter_map(|u| u.parse().ok()).collect()
+}
+
+/// Derive metadata from the image or link URL of a badge.
+///
+/// Badge services (shields.io, codecov, GitHub Actions, ...) encode the repository or the
+/// name of the package in a registry in their URLs.
+fn data_from_badge_url(url: &Url) -> Vec<(UpstreamDatum, Certainty)> {
+    let Some(host) = url.host_str() else {
+        return vec![];
+    };
+    let segments = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let github = |owner: &str, repo: &str| {
+        (
+            UpstreamDatum::Repository(format!("https://github.com/{}/{}", owner, repo)),
+            Certainty::Likely,
+        )
+    };
+    let archive = |name: &str| {
+        (
+            UpstreamDatum::Archive(name.to_string()),
+            Certainty::Possible,
+        )
+    };
+    let crate_ = |name: &str| {
+        vec![
+            (
+                UpstreamDatum::CargoCrate(name.to_string()),
+                Certainty::Likely,
+            ),
+            archive("crates.io"),
+        ]
+    };
+    let readthedocs = |project: &str| {
+        (
+            UpstreamDatum::Documentation(format!("https://{}.readthedocs.io/", project)),
+            Certainty::Likely,
+        )
+    };
+    // Badge images often have a file extension, e.g. "/crates/v/foo.svg"
+    let strip_extension = |s: &str| {
+        s.strip_suffix(".svg")
+            .or_else(|| s.strip_suffix(".png"))
+            .unwrap_or(s)
+            .to_string()
+    };
+    match (host, segments.as_slice()) {
+        ("img.shields.io" | "shields.io", ["crates", _, name, ..]) => {
+            crate_(&strip_extension(name))
+        }
+        ("img.shields.io" | "shields.io", ["docsrs", name, ..]) => {
+            let name = strip_extension(name);
+            let mut ret = vec![(
+                UpstreamDatum::Documentation(format!("https://docs.rs/{}", name)),
+                Certainty::Likely,
+            )];
+            ret.extend(crate_(&name));
+            ret
+        }
+        ("img.shields.io" | "shields.io", ["pypi", ..]) => vec![archive("PyPI")],
+        ("img.shields.io" | "shields.io", ["npm", ..]) => vec![archive("npm")],
+        ("img.shields.io" | "shields.io", ["readthedocs", project, ..]) => {
+            vec![readthedocs(&strip_extension(project))]
+        }
+        ("img.shields.io" | "shields.io", ["github", rest @ ..]) => {
+            let rest = match rest {
+                ["actions", "workflow", "status", rest @ ..] => rest,
+                ["workflow", "status", rest @ ..] | ["v", _, rest @ ..] => rest,
+                [_, rest @ ..] => rest,
+                [] => rest,
+            };
+            match rest {
+                [owner, repo, ..] => vec![github(owner, &strip_extension(repo))],
+                _ => vec![],
+            }
+        }
+        ("img.shields.io" | "shields.io", ["codecov", "c", "github" | "gh", owner, repo, ..])
+        | ("codecov.io", ["gh" | "github", owner, repo, ..])
+        | ("github.com", [owner, repo, "actions" | "workflows", ..]) => {
+            vec![github(owner, &strip_extension(repo))]
+        }
+        ("crates.io", ["crates", name, ..]) => crate_(name),
+        ("docs.rs", ["crate", name, ..] | [name, ..]) if !name.starts_with('-') => {
+            let mut ret = vec![(
+                UpstreamDatum::Documentation(format!("https://docs.rs/{}", name)),
+                Certainty::Likely,
+            )];
+            ret.extend(crate_(name));
+            ret
+        }
+        ("pypi.org", ["project", ..]) | ("pypi.python.org", ["pypi", ..]) => {
+            vec![archive("PyPI")]
+        }
+        ("badge.fury.io", ["py", ..]) => vec![archive("PyPI")],
+        ("badge.fury.io", ["js", ..]) | ("www.npmjs.com" | "npmjs.com", ["package", ..]) => {
+            vec![archive("npm")]
+        }
+        ("readthedocs.org", ["projects", project, ..]) => vec![readthedocs(project)],
+        (host, _) if host.ends_with(".readthedocs.io") || host.ends_with(".readthedocs.org") => {
+            let project = host.split('.').next().unwrap();
+            vec![readthedocs(project)]
+        }
+        _ => vec![],
+    }
+}
+
+/// Derive metadata from the badges in a README.
+fn guess_from_badges(urls: &[Url], origin: &Origin) -> Vec<UpstreamDatumWithMetadata> {
+    let mut ret: Vec<UpstreamDatumWithMetadata> = vec![];
+    for (datum, certainty) in urls.iter().flat_map(data_from_badge_url) {
+        // The image and the link of a badge usually yield the same data
+        if ret.iter().any(|d| d.datum == datum) {
+            continue;
+        }
+        ret.push(UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(origin.clone()),
+        });
+    }
+    ret
+}
+
 pub async fn guess_from_readme(
     path: &std::path::Path,
     _trust_package: bool,
@@ -278,19 +654,16 @@ pub async fn guess_from_readme(
 
         let mut cmdline = line.strip_prefix('$').unwrap_or(line).trim().to_string();
 
-        if cmdline.starts_with("git clone ")
-            || cmdline.starts_with("fossil clone ")
-            || cmdline.starts_with("hg clone ")
-            || cmdline.starts_with("bzr co ")
-            || cmdline.starts_with("bzr branch ")
-        {
+        if crate::vcs_command::is_vcs_command(&cmdline) {
             while cmdline.ends_with('\\') {
                 let next_line = line_iter.next().unwrap()?;
                 cmdline = format!("{} {}", cmdline, next_line.trim());
             }
 
             if let Some(url) = crate::vcs_command::url_from_vcs_command(cmdline.as_bytes()) {
-                urls.push(url.parse().unwrap());
+                if let Ok(url) = url.parse() {
+                    urls.push(url);
+                }
             }
         }
         for m in lazy_regex::regex!("[\"'`](git clone.*)[\"`']").captures_iter(line) {
@@ -312,6 +685,33 @@ pub async fn guess_from_readme(
                 urls.push(url.parse().unwrap());
             }
         }
+        for m in lazy_regex::regex!(r#"https?://[^\s<>()\[\]"'`]+"#).find_iter(line) {
+            let url = m.as_str().trim_end_matches(['.', ',']);
+            if url
+                .parse()
+                .is_ok_and(|u: url::Url| crate::is_translation_url(&u))
+            {
+                ret.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Translations(url.to_string()),
+                    certainty: Some(Certainty::Likely),
+                    origin: Some(path.into()),
+                });
+            }
+        }
+        for url in chat_urls_from_line(line) {
+            ret.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Chat(url),
+                certainty: Some(Certainty::Likely),
+                origin: Some(path.into()),
+            });
+        }
+        if let Some(url) = crate::vcs::mirror_source_from_text(line) {
+            ret.push(UpstreamDatumWithMetadata {
+                datum: UpstreamDatum::Repository(url),
+                certainty: Some(Certainty::Likely),
+                origin: Some(path.into()),
+            });
+        }
         const PROJECT_RE: &str = "([^/]+)/([^/?.()\"#>\\s]*[^-,/?.()\"#>\\s])";
         for m in regex::Regex::new(format!("https://travis-ci.org/{}", PROJECT_RE).as_str())
             .unwrap()
@@ -398,10 +798,18 @@ pub async fn guess_from_readme(
     let (description, extra_metadata) = match path.extension().and_then(|s| s.to_str()) {
         Some("md") => {
             let contents = std::fs::read_to_string(path)?;
+            ret.extend(guess_from_badges(
+                &badge_urls_from_md(&contents),
+                &Origin::Path(path.to_path_buf()),
+            ));
             description_from_readme_md(&contents)
         }
         Some("rst") => {
             let contents = std::fs::read_to_string(path)?;
+            ret.extend(guess_from_badges(
+                &badge_urls_from_rst(&contents),
+                &Origin::Path(path.to_path_buf()),
+            ));
             description_from_readme_rst(&contents)
         }
         None => {
@@ -714,7 +1122,7 @@ fn skip_paragraph_block(para: &Node) -> (bool, Vec<UpstreamDatumWithMetadata>) {
                         let re = Regex::new(r"(.*) License").unwrap();
                         if let Some(caps) = re.captures(name) {
                             extra_metadata.push(UpstreamDatumWithMetadata {
-                                datum: UpstreamDatum::License(caps[1].to_string()),
+                                datum: UpstreamDatum::License(caps[1].to_string().into()),
                                 certainty: Some(Certainty::Likely),
                                 origin: None,
                             });
@@ -908,8 +1316,8 @@ fn parse_field(name: &str, body: &NodeOrText) -> Vec<UpstreamDatumWithMetadata>
         }
     };
 
-    match name {
-        "Homepage" | "Home" => {
+    match name.trim().to_lowercase().as_str() {
+        "homepage" | "home" | "home page" | "website" => {
             if let Some(link) = get_link() {
                 metadata.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::Homepage(link),
@@ -919,7 +1327,7 @@ fn parse_field(name: &str, body: &NodeOrText) -> Vec<UpstreamDatumWithMetadata>
             }
         }
 
-        "Issues" => {
+        "issues" | "bugs" | "bug tracker" | "issue tracker" => {
             if let Some(link) = get_link() {
                 metadata.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::BugDatabase(link),
@@ -929,7 +1337,7 @@ fn parse_field(name: &str, body: &NodeOrText) -> Vec<UpstreamDatumWithMetadata>
             }
         }
 
-        "Documentation" => {
+        "documentation" | "docs" => {
             if let Some(link) = get_link() {
                 metadata.push(UpstreamDatumWithMetadata {
                     datum: UpstreamDatum::Documentation(link),
@@ -939,12 +1347,25 @@ fn parse_field(name: &str, body: &NodeOrText) -> Vec<UpstreamDatumWithMetadata>
             }
         }
 
-        "License" => {
+        "source" | "source code" | "code" | "repository" => {
+            if let Some(link) = get_link() {
+                metadata.push(UpstreamDatumWithMetadata {
+                    datum: UpstreamDatum::Repository(link),
+                    certainty: Some(Certainty::Confident),
+                    origin: None,
+                });
+            }
+        }
+
+        "license" | "licence" => {
             metadata.push(UpstreamDatumWithMetadata {
-                datum: UpstreamDatum::License(match body {
-                    NodeOrText::Node(body) => render(body),
-                    NodeOrText::Text(text) => text.to_string(),
-                }),
+                datum: UpstreamDatum::License(
+                    match body {
+                        NodeOrText::Node(body) => render(body),
+                        NodeOrText::Text(text) => text.to_string(),
+                    }
+                    .into(),
+                ),
                 certainty: Some(Certainty::Confident),
                 origin: None,
             });
@@ -1078,13 +1499,22 @@ pub fn description_from_readme_html(
     Ok(description_from_basic_soup(&soup))
 }
 
-fn rst_to_html(rst_text: &str) -> String {
+fn rst_to_html(rst_text: &str) -> Result<String, ProviderError> {
     use rst_renderer::render_html;
     use uo_rst_parser::parse;
-    let document = parse(rst_text).unwrap();
-    let mut output = Vec::new();
-    render_html(&document, &mut std::io::Cursor::new(&mut output), true).unwrap();
-    String::from_utf8(output).unwrap()
+    // The parser and renderer panic on constructs that they don't implement yet
+    std::panic::catch_unwind(|| {
+        let document = parse(rst_text).map_err(|e| ProviderError::ParseError(e.to_string()))?;
+        let mut output = Vec::new();
+        render_html(&document, &mut std::io::Cursor::new(&mut output), true)
+            .map_err(|e| ProviderError::ParseError(e.to_string()))?;
+        String::from_utf8(output).map_err(|e| ProviderError::ParseError(e.to_string()))
+    })
+    .unwrap_or_else(|_| {
+        Err(ProviderError::ParseError(
+            "unsupported reStructuredText construct".to_string(),
+        ))
+    })
 }
 
 #[cfg(test)]
@@ -1099,13 +1529,191 @@ RST
 ===
 
 This is a test of RST to HTML conversion."#;
-        let html = rst_to_html(rst);
+        let html = rst_to_html(rst).unwrap();
         assert_eq!(
             html,
             "<!doctype html><html>\n\n<section id=\"rst\">\n<h1>RST</h1>\n<p>This is a test of RST to HTML conversion.</p>\n</section>\n</html>\n"
         );
     }
 
+    #[test]
+    fn test_preprocess_rst() {
+        let doc = preprocess_rst(
+            r#"===
+foo
+===
+----------
+Do things
+----------
+
+:Home page: `site`_
+
+.. toctree::
+   :maxdepth: 2
+
+   usage
+
+Use :func:`foo.bar` like this::
+
+   foo.bar()
+
+.. _site: https://example.com/
+"#,
+        );
+        assert_eq!(
+            doc.text,
+            r#"===
+foo
+===
+
+
+Use foo.bar like this:
+
+::
+
+   foo.bar()
+
+.. _site: https://example.com/
+"#
+        );
+        assert_eq!(doc.subtitle.as_deref(), Some("Do things"));
+        assert_eq!(doc.fields, vec![("Home page", "`site`_".to_string())]);
+        assert_eq!(
+            resolve_rst_reference(&doc.fields[0].1, &doc.targets),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn test_resolve_rst_reference() {
+        let targets = std::collections::HashMap::from([(
+            "issue tracker".to_string(),
+            "https://example.com/issues".to_string(),
+        )]);
+        assert_eq!(
+            resolve_rst_reference("`GitHub <https://github.com/foo/bar>`_", &targets),
+            "https://github.com/foo/bar"
+        );
+        assert_eq!(
+            resolve_rst_reference("`Issue  Tracker`_", &targets),
+            "https://example.com/issues"
+        );
+        assert_eq!(
+            resolve_rst_reference("<https://example.com/>", &targets),
+            "https://example.com/"
+        );
+        assert_eq!(resolve_rst_reference("`unknown`_", &targets), "`unknown`_");
+        assert_eq!(resolve_rst_reference("MIT", &targets), "MIT");
+    }
+
+    #[test]
+    fn test_chat_urls_from_line() {
+        assert_eq!(
+            super::chat_urls_from_line("Join us on [Discord](https://discord.gg/abcdef)."),
+            vec!["https://discord.gg/abcdef".to_string()]
+        );
+        assert_eq!(
+            super::chat_urls_from_line("Chat with us in #dulwich on irc.libera.chat"),
+            vec!["irc://irc.libera.chat/#dulwich".to_string()]
+        );
+        assert_eq!(
+            super::chat_urls_from_line("There is a #dulwich channel on Libera.Chat."),
+            vec!["irc://irc.libera.chat/#dulwich".to_string()]
+        );
+        assert_eq!(
+            super::chat_urls_from_line("See https://github.com/jelmer/dulwich"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_badge_urls() {
+        let md = r#"[![Crates.io](https://img.shields.io/crates/v/foo.svg)](https://crates.io/crates/foo)
+![CI](https://github.com/owner/foo/actions/workflows/ci.yml/badge.svg)
+<a href="https://codecov.io/gh/owner/foo"><img src="https://codecov.io/gh/owner/foo/branch/main/graph/badge.svg"></a>"#;
+        assert_eq!(
+            badge_urls_from_md(md)
+                .iter()
+                .map(|u| u.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "https://img.shields.io/crates/v/foo.svg",
+                "https://crates.io/crates/foo",
+                "https://github.com/owner/foo/actions/workflows/ci.yml/badge.svg",
+                "https://codecov.io/gh/owner/foo",
+                "https://codecov.io/gh/owner/foo/branch/main/graph/badge.svg",
+            ]
+        );
+        let rst = r#".. image:: https://img.shields.io/pypi/v/foo.svg
+   :target: https://pypi.org/project/foo/
+   :alt: PyPI
+
+.. |docs| image:: https://readthedocs.org/projects/foo/badge/?version=latest
+    :target: https://foo.readthedocs.io/en/latest/
+
+:target: https://example.com/
+"#;
+        assert_eq!(
+            badge_urls_from_rst(rst)
+                .iter()
+                .map(|u| u.as_str())
+                .collect::<Vec<_>>(),
+            vec![
+                "https://img.shields.io/pypi/v/foo.svg",
+                "https://pypi.org/project/foo/",
+                "https://readthedocs.org/projects/foo/badge/?version=latest",
+                "https://foo.readthedocs.io/en/latest/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_guess_from_badges() {
+        let urls = [
+            "https://img.shields.io/crates/v/foo.svg",
+            "https://crates.io/crates/foo",
+            "https://docs.rs/foo/badge.svg",
+            "https://img.shields.io/github/actions/workflow/status/owner/foo/ci.yml",
+            "https://codecov.io/gh/owner/foo",
+            "https://foo.readthedocs.io/en/latest/",
+            "https://example.com/logo.png",
+        ]
+        .iter()
+        .map(|u| u.parse().unwrap())
+        .collect::<Vec<Url>>();
+        let origin = Origin::Path("README.md".into());
+        let datum = |datum, certainty| UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(certainty),
+            origin: Some(origin.clone()),
+        };
+        assert_eq!(
+            guess_from_badges(&urls, &origin),
+            vec![
+                datum(
+                    UpstreamDatum::CargoCrate("foo".to_string()),
+                    Certainty::Likely
+                ),
+                datum(
+                    UpstreamDatum::Archive("crates.io".to_string()),
+                    Certainty::Possible
+                ),
+                datum(
+                    UpstreamDatum::Documentation("https://docs.rs/foo".to_string()),
+                    Certainty::Likely
+                ),
+                datum(
+                    UpstreamDatum::Repository("https://github.com/owner/foo".to_string()),
+                    Certainty::Likely
+                ),
+                datum(
+                    UpstreamDatum::Documentation("https://foo.readthedocs.io/".to_string()),
+                    Certainty::Likely
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn test_parse_first_header_text() {
         assert_eq!(
@@ -1172,7 +1780,7 @@ This is a test of RST to HTML conversion."#;
         assert_eq!(
             super::parse_field("License", &"MIT".into()),
             vec![super::UpstreamDatumWithMetadata {
-                datum: super::UpstreamDatum::License("MIT".to_string()),
+                datum: super::UpstreamDatum::License("MIT".into()),
                 certainty: Some(super::Certainty::Confident),
                 origin: None,
             }]
@@ -1262,7 +1870,7 @@ This is a test of RST to HTML conversion."#;
                     origin: None,
                 },
                 super::UpstreamDatumWithMetadata {
-                    datum: super::UpstreamDatum::License("MIT".to_string()),
+                    datum: super::UpstreamDatum::License("MIT".into()),
                     certainty: Some(super::Certainty::Confident),
                     origin: None,
                 }
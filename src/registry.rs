@@ -0,0 +1,209 @@
+//! Registration of the guessers that are run on a source tree.
+//!
+//! By default, the guessers for all file formats that upstream-ontologist knows about are
+//! run. Downstream crates can add guessers for e.g. in-house file formats, and remove the
+//! default guessers they don't want, and then pass the registry to
+//! [`crate::MetadataGuesserBuilder::registry`]:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), upstream_ontologist::ProviderError> {
+//! use upstream_ontologist::{
+//!     Certainty, GuesserRegistry, MetadataGuesser, UpstreamDatum, UpstreamDatumWithMetadata,
+//! };
+//!
+//! let mut registry = GuesserRegistry::new();
+//! registry.register_file("project.info", |path, _settings| {
+//!     let name = std::fs::read_to_string(path)?;
+//!     Ok(vec![UpstreamDatumWithMetadata {
+//!         datum: UpstreamDatum::Name(name.trim().to_string()),
+//!         certainty: Some(Certainty::Certain),
+//!         origin: Some(path.into()),
+//!     }])
+//! });
+//! registry.deregister("source headers");
+//!
+//! let metadata = MetadataGuesser::builder()
+//!     .registry(registry)
+//!     .build()
+//!     .guess(std::path::Path::new("."))
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::{Guesser, GuesserSettings, PathGuesser, ProviderError, UpstreamDatumWithMetadata};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Creates the guessers for a source tree, given the path to its root.
+pub type GuesserFactory = dyn Fn(&Path) -> Vec<Box<dyn Guesser>> + Send + Sync;
+
+/// Guesses metadata from a single file, given its path.
+pub type FileGuesser =
+    fn(&Path, &GuesserSettings) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError>;
+
+/// The set of guessers that are run on a source tree.
+#[derive(Clone)]
+pub struct GuesserRegistry {
+    defaults: bool,
+    factories: Vec<(String, Arc<GuesserFactory>)>,
+    disabled: HashSet<String>,
+}
+
+impl GuesserRegistry {
+    /// A registry with the default guessers.
+    pub fn new() -> Self {
+        Self {
+            defaults: true,
+            factories: vec![],
+            disabled: HashSet::new(),
+        }
+    }
+
+    /// A registry without any guessers.
+    pub fn empty() -> Self {
+        Self {
+            defaults: false,
+            ..Self::new()
+        }
+    }
+
+    /// Register a factory for guessers.
+    ///
+    /// `name` is used to deregister the factory again. The factory is called with the
+    /// root of the source tree, and should only return guessers for files that exist.
+    pub fn register(
+        &mut self,
+        name: &str,
+        factory: impl Fn(&Path) -> Vec<Box<dyn Guesser>> + Send + Sync + 'static,
+    ) {
+        self.disabled.remove(name);
+        self.factories.push((name.to_string(), Arc::new(factory)));
+    }
+
+    /// Register a guesser for a file, relative to the root of the source tree.
+    ///
+    /// The guesser is only run if the file exists. Its name is the name of the file.
+    pub fn register_file(&mut self, filename: &str, guess: FileGuesser) {
+        let filename = filename.to_string();
+        self.register(&filename.clone(), move |root| {
+            let subpath = root.join(&filename);
+            if !subpath.exists() {
+                return vec![];
+            }
+            vec![Box::new(PathGuesser {
+                name: filename.clone(),
+                subpath,
+                cb: Box::new(move |p, s| Box::pin(async move { guess(&p, &s) })),
+            }) as Box<dyn Guesser>]
+        });
+    }
+
+    /// Stop running a guesser.
+    ///
+    /// `name` is either the name a factory was registered under, or the name of a guesser.
+    /// The default guessers are named after the file they read, relative to the root of the
+    /// source tree (e.g. "setup.py" or "debian/control"), except for "environment", "."
+    /// (the name of the directory), "manifest sniffing" and "source headers".
+    pub fn deregister(&mut self, name: &str) {
+        self.factories.retain(|(n, _)| n != name);
+        self.disabled.insert(name.to_string());
+    }
+
+    /// Find the guessers to run on the source tree at `path`.
+    pub(crate) fn find_guessers(&self, path: &Path) -> Vec<Box<dyn Guesser>> {
+        let mut ret = if self.defaults {
+            crate::find_guessers(path)
+        } else {
+            vec![]
+        };
+        let root = path.canonicalize().unwrap();
+        for (_, factory) in &self.factories {
+            ret.extend(factory(&root));
+        }
+        ret.retain(|g| !self.disabled.contains(g.name()));
+        ret
+    }
+
+    /// Names of the guessers that would be run on the source tree at `path`.
+    pub fn guesser_names(&self, path: &Path) -> Vec<String> {
+        self.find_guessers(path)
+            .iter()
+            .map(|g| g.name().to_string())
+            .collect()
+    }
+}
+
+impl Default for GuesserRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for GuesserRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuesserRegistry")
+            .field("defaults", &self.defaults)
+            .field(
+                "factories",
+                &self.factories.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            )
+            .field("disabled", &self.disabled)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Certainty, MetadataGuesser, UpstreamDatum};
+
+    fn guess_from_project_info(
+        path: &Path,
+        _settings: &GuesserSettings,
+    ) -> Result<Vec<UpstreamDatumWithMetadata>, ProviderError> {
+        Ok(vec![UpstreamDatumWithMetadata {
+            datum: UpstreamDatum::Name(std::fs::read_to_string(path)?.trim().to_string()),
+            certainty: Some(Certainty::Certain),
+            origin: Some(path.into()),
+        }])
+    }
+
+    #[tokio::test]
+    async fn test_registry() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(td.path().join("project.info"), "blah\n").unwrap();
+        std::fs::write(td.path().join("configure.ac"), "AC_INIT([foo], [1.0])\n").unwrap();
+
+        let mut registry = GuesserRegistry::new();
+        registry.register_file("project.info", guess_from_project_info);
+        registry.register_file("missing.info", guess_from_project_info);
+        registry.deregister("configure.ac");
+        let names = registry.guesser_names(td.path());
+        assert!(names.contains(&"project.info".to_string()));
+        assert!(!names.contains(&"missing.info".to_string()));
+        assert!(!names.contains(&"configure.ac".to_string()));
+        assert!(names.contains(&"environment".to_string()));
+
+        let metadata = MetadataGuesser::builder()
+            .registry(registry)
+            .build()
+            .guess(td.path())
+            .await
+            .unwrap();
+        assert_eq!(metadata.name(), Some("blah"));
+        assert!(metadata.get("Version").is_none());
+    }
+
+    #[test]
+    fn test_empty_registry() {
+        let td = tempfile::tempdir().unwrap();
+        std::fs::write(td.path().join("project.info"), "blah\n").unwrap();
+        let mut registry = GuesserRegistry::empty();
+        assert!(registry.guesser_names(td.path()).is_empty());
+        registry.register_file("project.info", guess_from_project_info);
+        assert_eq!(registry.guesser_names(td.path()), vec!["project.info"]);
+        registry.deregister("project.info");
+        assert!(registry.guesser_names(td.path()).is_empty());
+    }
+}
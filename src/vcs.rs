@@ -1,6 +1,6 @@
 use crate::with_path_segments;
 use lazy_regex::regex;
-use log::{debug, warn};
+use log::debug;
 use std::borrow::Cow;
 
 use std::collections::HashMap;
@@ -8,8 +8,19 @@ use url::Url;
 
 pub const VCSES: &[&str] = &["git", "bzr", "hg"];
 
+/// GitLab sites that can not be recognized by their name.
+///
+/// See [`crate::data::DataTables::known_gitlab_sites`] for the up-to-date list.
 pub const KNOWN_GITLAB_SITES: &[&str] = &["salsa.debian.org", "invent.kde.org", "0xacab.org"];
 
+/// Gitea and Forgejo sites that can not be recognized by their name.
+///
+/// See [`crate::data::DataTables::known_gitea_sites`] for the up-to-date list.
+pub const KNOWN_GITEA_SITES: &[&str] = &["codeberg.org", "gitea.com"];
+
+/// Sites that run Forgejo rather than Gitea.
+const KNOWN_FORGEJO_SITES: &[&str] = &["codeberg.org"];
+
 pub const SECURE_SCHEMES: &[&str] = &["https", "git+ssh", "bzr+ssh", "hg+ssh", "ssh", "svn+ssh"];
 
 const KNOWN_HOSTING_SITES: &[&str] = &[
@@ -23,6 +34,43 @@ pub fn plausible_url(url: &str) -> bool {
     url.contains(':')
 }
 
+/// Hosting sites for version control systems that are not supported by breezy.
+const ALTERNATIVE_VCS_HOSTING_SITES: &[(&str, &str)] = &[
+    ("nest.pijul.com", "pijul"),
+    ("hub.darcs.net", "darcs"),
+    ("app.radicle.xyz", "radicle"),
+];
+
+/// Determine the version control system for URLs of version control systems that are not
+/// supported by breezy, such as Radicle, Pijul, Darcs and Monotone.
+///
+/// These URLs can not be probed, but should not be considered bad guesses either.
+pub fn alternative_vcs_for_url(url: &str) -> Option<&'static str> {
+    if url.starts_with("rad:") {
+        return Some("radicle");
+    }
+    let url = Url::parse(url).ok()?;
+    match url.scheme() {
+        "pijul" | "pijul+http" | "pijul+https" | "pijul+ssh" => return Some("pijul"),
+        "darcs" | "darcs+http" | "darcs+https" | "darcs+ssh" => return Some("darcs"),
+        "mtn" | "mtn+ssh" => return Some("monotone"),
+        _ => {}
+    }
+    let host = url.host_str()?;
+    if let Some((_, vcs)) = ALTERNATIVE_VCS_HOSTING_SITES
+        .iter()
+        .find(|(site, _)| *site == host)
+    {
+        return Some(vcs);
+    }
+    if url.path().contains("/rad:") {
+        // Radicle seed nodes and web interfaces, e.g.
+        // https://radicle.example.com/nodes/seed.example.com/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5
+        return Some("radicle");
+    }
+    None
+}
+
 pub fn drop_vcs_in_scheme(url: &Url) -> Option<Url> {
     let scheme = url.scheme();
     match scheme {
@@ -31,6 +79,12 @@ pub fn drop_vcs_in_scheme(url: &Url) -> Option<Url> {
         }
         "hg+http" | "hg+https" => Some(derive_with_scheme(url, scheme.trim_start_matches("hg+"))),
         "bzr+lp" | "bzr+http" => Some(derive_with_scheme(url, scheme.trim_start_matches("bzr+"))),
+        "pijul+http" | "pijul+https" => {
+            Some(derive_with_scheme(url, scheme.trim_start_matches("pijul+")))
+        }
+        "darcs+http" | "darcs+https" => {
+            Some(derive_with_scheme(url, scheme.trim_start_matches("darcs+")))
+        }
         _ => None,
     }
 }
@@ -73,7 +127,7 @@ pub fn plausible_browse_url(url: &str) -> bool {
 }
 
 pub fn strip_vcs_prefixes(url: &str) -> &str {
-    let prefixes = ["git", "hg"];
+    let prefixes = ["git", "hg", "pijul", "darcs"];
 
     for prefix in prefixes.iter() {
         if url.starts_with(&format!("{}+", prefix)) {
@@ -84,24 +138,19 @@ pub fn strip_vcs_prefixes(url: &str) -> &str {
     url
 }
 
+#[cfg(feature = "net")]
 async fn probe_upstream_github_branch_url(url: &url::Url, version: Option<&str>) -> Option<bool> {
     let path = url.path();
     let path = path.strip_suffix(".git").unwrap_or(path);
-    let api_url = url::Url::parse(
-        format!(
-            "https://api.github.com/repos/{}/tags",
-            path.trim_start_matches('/')
-        )
-        .as_str(),
-    )
-    .unwrap();
-    match crate::load_json_url(&api_url, None).await {
-        Ok(json) => {
+    let tags: Result<Vec<serde_json::Value>, _> = crate::forges::api::ApiClient::github()
+        .get(&format!("repos/{}/tags", path.trim_start_matches('/')))
+        .await;
+    match tags {
+        Ok(tags) => {
             if let Some(version) = version {
-                let tags = json.as_array()?;
                 let tag_names = tags
                     .iter()
-                    .map(|x| x["name"].as_str().unwrap())
+                    .filter_map(|x| x["name"].as_str())
                     .collect::<Vec<_>>();
                 if tag_names.is_empty() {
                     // Uhm, hmm
@@ -117,40 +166,58 @@ async fn probe_upstream_github_branch_url(url: &url::Url, version: Option<&str>)
             None
         }
         Err(e) => {
-            warn!("failed to probe github api: {:?}", e);
+            log::warn!("failed to probe github api: {:?}", e);
             None
         }
     }
 }
 
 fn version_in_tags(version: &str, tag_names: &[&str]) -> bool {
-    if tag_names.contains(&version) {
-        return true;
-    }
-    if tag_names.contains(&format!("v{}", version).as_str()) {
-        return true;
-    }
-    if tag_names.contains(&format!("release/{}", version).as_str()) {
-        return true;
-    }
-    if tag_names.contains(&version.replace('.', "_").as_str()) {
-        return true;
-    }
-    for tag_name in tag_names {
-        if tag_name.ends_with(&format!("_{}", version)) {
-            return true;
-        }
-        if tag_name.ends_with(&format!("-{}", version)) {
-            return true;
-        }
-        if tag_name.ends_with(&format!("_{}", version.replace('.', "_"))) {
-            return true;
-        }
+    let version = match crate::version::Version::parse(version) {
+        Some(version) => version,
+        None => return tag_names.contains(&version),
+    };
+    tag_names.iter().any(|tag| {
+        version_from_tag(tag)
+            .and_then(|v| crate::version::Version::parse(&v))
+            .as_ref()
+            == Some(&version)
+    })
+}
+
+/// List the names of the tags in a repository.
+///
+/// Returns None if the tags could not be retrieved.
+pub async fn list_tags(url: &url::Url) -> Option<Vec<String>> {
+    #[cfg(feature = "net")]
+    if url.host_str() == Some("github.com") {
+        let path = url.path();
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        let tags: Result<Vec<serde_json::Value>, _> = crate::forges::api::ApiClient::github()
+            .get_paginated(
+                &format!("repos/{}/tags?per_page=100", path.trim_matches('/')),
+                None,
+            )
+            .await;
+        return match tags {
+            Ok(tags) => Some(
+                tags.iter()
+                    .filter_map(|x| x["name"].as_str().map(|s| s.to_string()))
+                    .collect(),
+            ),
+            Err(e) => {
+                debug!("failed to list tags for {}: {}", url, e);
+                None
+            }
+        };
     }
-    false
+
+    branch_tags(url).await
 }
 
-fn probe_upstream_breezy_branch_url(url: &url::Url, version: Option<&str>) -> Option<bool> {
+/// List the tags in a branch, using Breezy.
+#[cfg(feature = "breezy")]
+async fn branch_tags(url: &url::Url) -> Option<Vec<String>> {
     let tags: HashMap<String, breezyshim::RevisionId> = breezyshim::ui::with_silent_ui_factory(
         || -> Result<HashMap<String, breezyshim::RevisionId>, breezyshim::error::Error> {
             let branch = breezyshim::branch::open(url)?;
@@ -158,12 +225,243 @@ fn probe_upstream_breezy_branch_url(url: &url::Url, version: Option<&str>) -> Op
         },
     )
     .map_err(|e| {
-        warn!("failed to probe breezy branch: {:?}", e);
+        log::warn!("failed to probe breezy branch: {:?}", e);
         e
     })
     .ok()?;
 
-    let tag_names = tags.keys().map(|x| x.as_str()).collect::<Vec<_>>();
+    Some(tags.into_keys().collect())
+}
+
+/// List the tags in a branch, assuming it is a Git repository served over HTTP(S).
+#[cfg(all(feature = "net", not(feature = "breezy")))]
+async fn branch_tags(url: &url::Url) -> Option<Vec<String>> {
+    let refs = git_http_refs(url).await?;
+    let mut tags: Vec<String> = vec![];
+    for (_, name) in refs {
+        if let Some(tag) = name.strip_prefix("refs/tags/") {
+            // Annotated tags are advertised twice; the second time peeled, with a ^{} suffix
+            let tag = tag.trim_end_matches("^{}");
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+    Some(tags)
+}
+
+#[cfg(not(any(feature = "net", feature = "breezy")))]
+async fn branch_tags(url: &url::Url) -> Option<Vec<String>> {
+    debug!("unable to list tags for {} without network support", url);
+    None
+}
+
+/// Parse the references advertised by a Git server, as (object id, ref name) pairs.
+///
+/// See <https://git-scm.com/docs/http-protocol#_smart_clients> for the format.
+#[cfg(all(feature = "net", any(test, not(feature = "breezy"))))]
+fn parse_ref_advertisement(body: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut refs = vec![];
+    let mut rest = body;
+    while rest.len() >= 4 {
+        let len = usize::from_str_radix(std::str::from_utf8(&rest[..4]).ok()?, 16).ok()?;
+        if len == 0 {
+            // Flush packet
+            rest = &rest[4..];
+            continue;
+        }
+        if len < 4 || len > rest.len() {
+            return None;
+        }
+        let line = std::str::from_utf8(&rest[4..len]).ok()?;
+        rest = &rest[len..];
+        let line = line.trim_end_matches('\n');
+        if line.starts_with("# service=") {
+            continue;
+        }
+        // The first reference is followed by the server capabilities
+        let line = line.split('\0').next().unwrap_or(line);
+        let (oid, name) = line.split_once(' ')?;
+        // An empty repository advertises only its capabilities
+        if name == "capabilities^{}" {
+            continue;
+        }
+        refs.push((oid.to_string(), name.to_string()));
+    }
+    Some(refs)
+}
+
+/// Retrieve the references in a Git repository, using the smart HTTP protocol.
+///
+/// Returns None if the URL does not point at a Git repository that is served over HTTP(S).
+#[cfg(all(feature = "net", not(feature = "breezy")))]
+async fn git_http_refs(url: &url::Url) -> Option<Vec<(String, String)>> {
+    if !["http", "https"].contains(&url.scheme()) {
+        return None;
+    }
+    let refs_url = format!(
+        "{}/info/refs?service=git-upload-pack",
+        url.as_str().trim_end_matches('/')
+    );
+    let client = crate::http::build_client().build().ok()?;
+    let response = crate::http::send(client.get(&refs_url))
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| debug!("failed to retrieve refs from {}: {}", url, e))
+        .ok()?;
+    // Servers that only support the dumb protocol, or that are not Git servers at all,
+    // respond with something else
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if !content_type.starts_with("application/x-git-upload-pack-advertisement") {
+        debug!("{} is not a smart Git HTTP server", url);
+        return None;
+    }
+    let body = response.bytes().await.ok()?;
+    parse_ref_advertisement(&body)
+}
+
+/// Extract the version from a tag name, e.g. "v1.2.3" or "foo-1_2_3".
+pub fn version_from_tag(tag: &str) -> Option<String> {
+    let tag = tag.rsplit('/').next().unwrap_or(tag);
+    // The version starts at the first digit that follows a separator or a "v"
+    let start = tag.char_indices().find_map(|(i, c)| {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        match tag[..i].chars().last() {
+            None | Some('-' | '_' | 'v' | 'V') => Some(i),
+            _ => None,
+        }
+    })?;
+    let version = &tag[start..];
+    if version.contains('.') {
+        Some(version.to_string())
+    } else {
+        Some(version.replace('_', "."))
+    }
+}
+
+/// Actions that cgit appends to the repository path in browse URLs.
+const CGIT_ACTIONS: &[&str] = &[
+    "about", "summary", "refs", "log", "tree", "commit", "diff", "stats", "plain", "snapshot",
+    "tag", "patch", "blame", "atom",
+];
+
+/// Find the clone URL for a cgit browse URL, e.g. "https://example.com/cgit/foo.git/about/".
+///
+/// cgit serves clones from the repository URL itself, except that repositories under
+/// "/cgit/" are conventionally cloned from "/git/".
+pub fn repo_url_from_cgit_url(url: &Url) -> Option<Url> {
+    let segments = url.path_segments()?.collect::<Vec<_>>();
+    let cgit_index = segments
+        .iter()
+        .position(|s| *s == "cgit" || *s == "cgit.cgi");
+    let repo_end = match segments.iter().position(|s| s.ends_with(".git")) {
+        // Without a cgit prefix, only trust paths that are followed by a cgit action
+        Some(i)
+            if cgit_index.is_some()
+                || segments
+                    .get(i + 1)
+                    .is_some_and(|a| CGIT_ACTIONS.contains(a)) =>
+        {
+            i + 1
+        }
+        Some(_) => return None,
+        None => {
+            let start = cgit_index? + 1;
+            start
+                + segments[start..]
+                    .iter()
+                    .position(|s| s.is_empty() || CGIT_ACTIONS.contains(s))
+                    .unwrap_or(segments.len() - start)
+        }
+    };
+    if cgit_index.is_some_and(|i| repo_end <= i + 1) {
+        return None;
+    }
+    let mut repo_segments = segments[..repo_end].to_vec();
+    if let Some(i) = cgit_index {
+        repo_segments[i] = "git";
+    }
+    let mut repo_url = with_path_segments(url, &repo_segments).ok()?;
+    repo_url.set_query(None);
+    repo_url.set_fragment(None);
+    Some(repo_url)
+}
+
+/// Find the clone URL for a gitweb browse URL, e.g. "https://example.com/gitweb/?p=foo.git;a=summary".
+///
+/// Repositories shown by a gitweb at "/gitweb/" are conventionally cloned from "/git/".
+pub fn repo_url_from_gitweb_url(url: &Url) -> Option<Url> {
+    // gitweb separates parameters with ';' rather than '&'
+    let project = url
+        .query()?
+        .split([';', '&'])
+        .find_map(|p| p.strip_prefix("p="))?;
+    let project = percent_encoding::percent_decode_str(project)
+        .decode_utf8()
+        .ok()?;
+    let mut segments = url
+        .path_segments()?
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>();
+    if segments
+        .last()
+        .is_some_and(|s| s.ends_with(".cgi") || s.ends_with(".pl"))
+    {
+        segments.pop();
+    }
+    if !project.ends_with(".git") && !segments.contains(&"gitweb") {
+        return None;
+    }
+    if let Some(last) = segments.last_mut() {
+        if *last == "gitweb" {
+            *last = "git";
+        }
+    }
+    segments.extend(project.split('/').filter(|s| !s.is_empty()));
+    let mut repo_url = with_path_segments(url, &segments).ok()?;
+    repo_url.set_query(None);
+    repo_url.set_fragment(None);
+    Some(repo_url)
+}
+
+/// Find the clone URL for a cgit or gitweb browse URL.
+pub fn repo_url_from_browse_url(url: &Url) -> Option<Url> {
+    repo_url_from_gitweb_url(url).or_else(|| repo_url_from_cgit_url(url))
+}
+
+/// Find the canonical repository in a notice like "This is a read-only mirror of <url>".
+///
+/// Such notices commonly appear in READMEs and repository descriptions of mirrors.
+pub fn mirror_source_from_text(text: &str) -> Option<String> {
+    let (_, url) = lazy_regex::regex_captures!(
+        r#"(?i)\b(?:mirror\s+of|mirrored\s+from|canonical\s+(?:repository|repo|source|location))\b[^\n]{0,80}?(https?://[^\s<>()\[\]"'`]+)"#,
+        text
+    )?;
+    Some(url.trim_end_matches(['.', ',', ';', ':']).to_string())
+}
+
+/// Extract the host name from a URL, including rcp-style URLs like "git@host:path".
+#[cfg(feature = "git-config")]
+pub(crate) fn url_host(url: &str) -> Option<String> {
+    match Url::parse(url) {
+        Ok(url) => url.host_str().map(|h| h.to_string()),
+        Err(_) => {
+            let (_, host) = lazy_regex::regex_captures!(r"^(?:[^@/:]+@)?([^/:]+):", url)?;
+            Some(host.to_string())
+        }
+    }
+}
+
+async fn probe_upstream_vcs_branch_url(url: &url::Url, version: Option<&str>) -> Option<bool> {
+    let tags = branch_tags(url).await?;
+
+    let tag_names = tags.iter().map(|x| x.as_str()).collect::<Vec<_>>();
     if let Some(version) = version {
         Some(version_in_tags(version, tag_names.as_slice()))
     } else {
@@ -177,13 +475,20 @@ pub async fn probe_upstream_branch_url(url: &url::Url, version: Option<&str>) ->
         return None;
     }
 
+    if let Some(vcs) = alternative_vcs_for_url(url.as_str()) {
+        debug!("Unable to probe {} repository {}", vcs, url);
+        return None;
+    }
+
+    #[cfg(feature = "net")]
     if url.host() == Some(url::Host::Domain("github.com")) {
-        probe_upstream_github_branch_url(url, version).await
-    } else {
-        probe_upstream_breezy_branch_url(url, version)
+        return probe_upstream_github_branch_url(url, version).await;
     }
+
+    probe_upstream_vcs_branch_url(url, version).await
 }
 
+#[cfg(feature = "net")]
 pub async fn check_repository_url_canonical(
     mut url: url::Url,
     version: Option<&str>,
@@ -205,11 +510,10 @@ pub async fn check_repository_url_canonical(
         }
 
         segments[1] = segments[1].trim_end_matches(".git");
-        let api_url = format!(
-            "https://api.github.com/repos/{}/{}",
-            segments[0], segments[1]
-        );
-        url = match crate::load_json_url(&url::Url::parse(api_url.as_str()).unwrap(), None).await {
+        url = match crate::forges::api::ApiClient::github()
+            .get::<serde_json::Value>(&format!("repos/{}/{}", segments[0], segments[1]))
+            .await
+        {
             Ok(data) => {
                 if data["archived"].as_bool().unwrap_or(false) {
                     return Err(crate::CanonicalizeError::InvalidUrl(
@@ -308,8 +612,9 @@ pub async fn check_repository_url_canonical(
     ))
 }
 
+#[cfg_attr(not(feature = "net"), allow(unused_variables))]
 pub async fn is_gitlab_site(hostname: &str, net_access: Option<bool>) -> bool {
-    if KNOWN_GITLAB_SITES.contains(&hostname) {
+    if crate::data::tables().is_known_gitlab_site(hostname) {
         return true;
     }
 
@@ -317,16 +622,20 @@ pub async fn is_gitlab_site(hostname: &str, net_access: Option<bool>) -> bool {
         return true;
     }
 
+    #[cfg(feature = "net")]
     if net_access.unwrap_or(false) {
-        probe_gitlab_host(hostname).await
-    } else {
-        false
+        return probe_gitlab_host(hostname).await;
     }
+
+    false
 }
 
+#[cfg(feature = "net")]
 pub async fn probe_gitlab_host(hostname: &str) -> bool {
-    let url = format!("https://{}/api/v4/version", hostname);
-    match crate::load_json_url(&url::Url::parse(url.as_str()).unwrap(), None).await {
+    let Ok(client) = crate::forges::api::ApiClient::gitlab(hostname) else {
+        return false;
+    };
+    match client.get::<serde_json::Value>("version").await {
         Ok(_data) => true,
         Err(crate::HTTPJSONError::Error {
             status: 401,
@@ -357,10 +666,84 @@ pub async fn probe_gitlab_host(hostname: &str) -> bool {
     }
 }
 
+/// Check whether a host runs Gitea or Forgejo, which share their API.
+#[cfg_attr(not(feature = "net"), allow(unused_variables))]
+pub async fn is_gitea_site(hostname: &str, net_access: Option<bool>) -> bool {
+    if crate::data::tables().is_known_gitea_site(hostname) {
+        return true;
+    }
+
+    if hostname.starts_with("gitea.") || hostname.starts_with("forgejo.") {
+        return true;
+    }
+
+    #[cfg(feature = "net")]
+    if net_access.unwrap_or(false) {
+        return probe_gitea_host(hostname).await;
+    }
+
+    false
+}
+
+/// Check whether a host runs Forgejo.
+#[cfg_attr(not(feature = "net"), allow(unused_variables))]
+pub async fn is_forgejo_site(hostname: &str, net_access: Option<bool>) -> bool {
+    if KNOWN_FORGEJO_SITES.contains(&hostname) || hostname.starts_with("forgejo.") {
+        return true;
+    }
+
+    #[cfg(feature = "net")]
+    if net_access.unwrap_or(false) {
+        // Only Forgejo has its own version endpoint, next to the one shared with Gitea
+        let Ok(base_url) = format!("https://{}/api/forgejo/v1/", hostname).parse() else {
+            return false;
+        };
+        return crate::forges::api::ApiClient::new(base_url)
+            .get::<serde_json::Value>("version")
+            .await
+            .is_ok();
+    }
+
+    false
+}
+
+#[cfg(feature = "net")]
+pub async fn probe_gitea_host(hostname: &str) -> bool {
+    let Ok(client) = crate::forges::api::ApiClient::gitea(hostname) else {
+        return false;
+    };
+    match client.get::<serde_json::Value>("version").await {
+        // GitLab and others may serve JSON here too, but not with a version string
+        Ok(data) => data.get("version").is_some_and(|v| v.is_string()),
+        Err(e) => {
+            debug!("failed to probe Gitea host: {:?}", e);
+            false
+        }
+    }
+}
+
 pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Option<String> {
     let net_access = net_access.unwrap_or(false);
-    let path_segments = url.path_segments().unwrap().collect::<Vec<_>>();
+    if url.scheme() == "rad" {
+        return Some(url.to_string());
+    }
+    let path_segments = url.path_segments()?.collect::<Vec<_>>();
     match url.host_str()? {
+        "nest.pijul.com" | "hub.darcs.net" => {
+            if path_segments.len() < 2 || path_segments[1].is_empty() {
+                return None;
+            }
+
+            Some(
+                with_path_segments(url, &path_segments[0..2])
+                    .unwrap()
+                    .to_string(),
+            )
+        }
+        "app.radicle.xyz" => path_segments
+            .iter()
+            .find(|s| s.starts_with("rad:"))
+            .map(|s| s.to_string()),
         "github.com" => {
             if path_segments.len() < 2 {
                 return None;
@@ -372,6 +755,51 @@ pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Op
                     .to_string(),
             )
         }
+        "gitee.com" => {
+            if path_segments.len() < 2 || path_segments[1].is_empty() {
+                return None;
+            }
+
+            Some(format!(
+                "https://gitee.com/{}/{}",
+                path_segments[0],
+                path_segments[1].trim_end_matches(".git")
+            ))
+        }
+        "osdn.net" | "osdn.jp" => {
+            // https://osdn.net/projects/<project>/scm/git/<repository>/
+            if path_segments.len() < 5
+                || path_segments[0] != "projects"
+                || path_segments[2] != "scm"
+                || path_segments[3] != "git"
+            {
+                return None;
+            }
+            Some(format!(
+                "https://scm.osdn.net/gitroot/{}/{}.git",
+                path_segments[1], path_segments[4]
+            ))
+        }
+        "scm.osdn.net" => {
+            if path_segments.len() < 3 || path_segments[0] != "gitroot" {
+                return None;
+            }
+            Some(
+                with_path_segments(url, &path_segments[0..3])
+                    .unwrap()
+                    .to_string(),
+            )
+        }
+        "api.bitbucket.org" => {
+            // https://api.bitbucket.org/2.0/repositories/<workspace>/<repository>
+            if path_segments.len() < 4 || path_segments[1] != "repositories" {
+                return None;
+            }
+            Some(format!(
+                "https://bitbucket.org/{}/{}",
+                path_segments[2], path_segments[3]
+            ))
+        }
         "travis-ci.org" => {
             if path_segments.len() < 2 {
                 return None;
@@ -403,6 +831,9 @@ pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Op
             if path_segments.len() < 2 {
                 return None;
             }
+            if path_segments[0] == "cgit" {
+                return repo_url_from_cgit_url(url).map(|u| u.to_string());
+            }
             if path_segments[0] != "git" {
                 return None;
             }
@@ -505,7 +936,11 @@ pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Op
             }
         }
         "bitbucket.org" => {
-            if path_segments.len() < 2 {
+            if path_segments.len() < 2 || path_segments[1].is_empty() {
+                return None;
+            }
+            // Workspace pages, e.g. https://bitbucket.org/<workspace>/workspace/projects/
+            if path_segments[1] == "workspace" || path_segments[0] == "account" {
                 return None;
             }
 
@@ -545,6 +980,33 @@ pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Op
                 .to_string(),
             )
         }
+        "git.sr.ht" | "hg.sr.ht" => {
+            // Repositories are owned by a user, e.g. https://git.sr.ht/~sircmpwn/scdoc
+            if path_segments.len() < 2
+                || !path_segments[0].starts_with('~')
+                || path_segments[1].is_empty()
+            {
+                return None;
+            }
+            Some(
+                with_path_segments(url, &path_segments[0..2])
+                    .unwrap()
+                    .to_string(),
+            )
+        }
+        u if is_gitea_site(u, Some(net_access)).await => {
+            if path_segments.len() < 2 || path_segments[1].is_empty() {
+                return None;
+            }
+            let repo = path_segments[1]
+                .strip_suffix(".git")
+                .unwrap_or(path_segments[1]);
+            Some(
+                with_path_segments(url, &[path_segments[0], repo])
+                    .unwrap()
+                    .to_string(),
+            )
+        }
         u if is_gitlab_site(u, Some(net_access)).await => {
             if path_segments.is_empty() {
                 return None;
@@ -583,21 +1045,22 @@ pub async fn guess_repo_from_url(url: &url::Url, net_access: Option<bool>) -> Op
             Some(url.to_string())
         }
         _ => {
+            if let Some(repo_url) = repo_url_from_browse_url(url) {
+                return Some(repo_url.to_string());
+            }
+            #[cfg(feature = "net")]
             if net_access {
                 match check_repository_url_canonical(url.clone(), None).await {
-                    Ok(url) => Some(url.to_string()),
-                    Err(_) => {
-                        debug!("Failed to canonicalize URL: {}", url);
-                        None
-                    }
+                    Ok(url) => return Some(url.to_string()),
+                    Err(_) => debug!("Failed to canonicalize URL: {}", url),
                 }
-            } else {
-                None
             }
+            None
         }
     }
 }
 
+#[cfg(test)]
 #[tokio::test]
 async fn test_guess_repo_url() {
     assert_eq!(
@@ -724,6 +1187,61 @@ pub async fn browse_url_from_repo_url(
                 .join(&path)
                 .unwrap(),
         )
+    } else if location.url.host_str() == Some("gitee.com") {
+        let segments = location.url.path_segments()?.take(2).collect::<Vec<&str>>();
+        if segments.len() < 2 {
+            return None;
+        }
+        let mut path = format!("{}/{}", segments[0], segments[1].trim_end_matches(".git"));
+        if location.subpath.is_some() || location.branch.is_some() {
+            path.push_str(&format!(
+                "/tree/{}",
+                location.branch.as_deref().unwrap_or("master")
+            ));
+        }
+        if let Some(subpath_str) = location.subpath.as_deref() {
+            path.push_str(&format!("/{}", subpath_str));
+        }
+        Url::parse("https://gitee.com").unwrap().join(&path).ok()
+    } else if location.url.host_str() == Some("bitbucket.org") {
+        let segments = location.url.path_segments()?.take(2).collect::<Vec<&str>>();
+        if segments.len() < 2 {
+            return None;
+        }
+        let mut path = format!("{}/{}", segments[0], segments[1].trim_end_matches(".git"));
+        if location.subpath.is_some() || location.branch.is_some() {
+            path.push_str(&format!(
+                "/src/{}",
+                location.branch.as_deref().unwrap_or("HEAD")
+            ));
+        }
+        if let Some(subpath_str) = location.subpath.as_deref() {
+            path.push_str(&format!("/{}", subpath_str));
+        }
+        Url::parse("https://bitbucket.org")
+            .unwrap()
+            .join(&path)
+            .ok()
+    } else if location.url.host_str() == Some("scm.osdn.net") {
+        let segments = location.url.path_segments()?.collect::<Vec<&str>>();
+        if segments.len() < 3 || segments[0] != "gitroot" {
+            return None;
+        }
+        let mut path = format!(
+            "projects/{}/scm/git/{}/",
+            segments[1],
+            segments[2].trim_end_matches(".git")
+        );
+        if location.subpath.is_some() || location.branch.is_some() {
+            path.push_str(&format!(
+                "tree/{}/",
+                location.branch.as_deref().unwrap_or("master")
+            ));
+        }
+        if let Some(subpath_str) = location.subpath.as_deref() {
+            path.push_str(&format!("{}/", subpath_str));
+        }
+        Url::parse("https://osdn.net").unwrap().join(&path).ok()
     } else if location.url.host_str() == Some("gopkg.in") {
         let mut els = location
             .url
@@ -799,6 +1317,8 @@ pub async fn browse_url_from_repo_url(
             Url::parse(format!("https://git.savannah.gnu.org{}", path_elements.join("/")).as_str())
                 .unwrap(),
         )
+    } else if let Some(frontend) = crate::git_web::known_frontend(&location.url) {
+        Some(frontend.browse_url(location))
     } else if location.url.host_str().is_some()
         && is_gitlab_site(location.url.host_str().unwrap(), net_access).await
     {
@@ -813,24 +1333,61 @@ pub async fn browse_url_from_repo_url(
             Url::parse(format!("https://{}{}", location.url.host_str().unwrap(), path).as_str())
                 .unwrap(),
         )
-    } else {
-        None
-    }
-}
-
-pub async fn find_public_repo_url(repo_url: &str, net_access: Option<bool>) -> Option<String> {
-    let parsed = match Url::parse(repo_url) {
-        Ok(parsed) => parsed,
-        Err(_) => {
-            if repo_url.contains(':') {
-                let re = regex!(r"^(?P<user>[^@:/]+@)?(?P<host>[^/:]+):(?P<path>.*)$");
-                if let Some(captures) = re.captures(repo_url) {
-                    let host = captures.name("host").unwrap().as_str();
-                    let path = captures.name("path").unwrap().as_str();
-                    if host == "github.com" || is_gitlab_site(host, net_access).await {
-                        return Some(format!("https://{}/{}", host, path));
-                    }
-                }
+    } else if location.url.host_str() == Some("git.sr.ht") {
+        let mut path = location.url.path().to_string();
+        if path.ends_with(".git") {
+            path = path[..path.len() - 4].to_string();
+        }
+        if let Some(subpath_str) = location.subpath.as_deref() {
+            path.push_str(&format!(
+                "/tree/{}/item/{}",
+                location.branch.as_deref().unwrap_or("HEAD"),
+                subpath_str
+            ));
+        }
+        Some(Url::parse(format!("https://git.sr.ht{}", path).as_str()).unwrap())
+    } else if location.url.host_str().is_some()
+        && is_gitea_site(location.url.host_str().unwrap(), net_access).await
+    {
+        let mut path = location.url.path().to_string();
+        if path.ends_with(".git") {
+            path = path[..path.len() - 4].to_string();
+        }
+        // Gitea can't browse files without knowing whether the ref is a branch, tag or commit
+        if let (Some(subpath_str), Some(branch)) =
+            (location.subpath.as_deref(), location.branch.as_deref())
+        {
+            path.push_str(&format!("/src/branch/{}/{}", branch, subpath_str));
+        }
+        Some(
+            Url::parse(format!("https://{}{}", location.url.host_str().unwrap(), path).as_str())
+                .unwrap(),
+        )
+    } else {
+        // Self-hosted repositories are often shown by cgit or gitweb
+        #[cfg(feature = "net")]
+        if net_access.unwrap_or(false) {
+            if let Some(frontend) = crate::git_web::probe_frontend(&location.url).await {
+                return Some(frontend.browse_url(location));
+            }
+        }
+        None
+    }
+}
+
+pub async fn find_public_repo_url(repo_url: &str, net_access: Option<bool>) -> Option<String> {
+    let parsed = match Url::parse(repo_url) {
+        Ok(parsed) => parsed,
+        Err(_) => {
+            if repo_url.contains(':') {
+                let re = regex!(r"^(?P<user>[^@:/]+@)?(?P<host>[^/:]+):(?P<path>.*)$");
+                if let Some(captures) = re.captures(repo_url) {
+                    let host = captures.name("host").unwrap().as_str();
+                    let path = captures.name("path").unwrap().as_str();
+                    if host == "github.com" || is_gitlab_site(host, net_access).await {
+                        return Some(format!("https://{}/{}", host, path));
+                    }
+                }
             }
             return None;
         }
@@ -884,10 +1441,62 @@ pub async fn find_public_repo_url(repo_url: &str, net_access: Option<bool>) -> O
     revised_url
 }
 
+/// Convert a rcp-style location (e.g. "git@example.com:foo/bar") to a ssh URL.
+#[cfg(feature = "breezy")]
 pub fn fixup_rcp_style_git_repo_url(url: &str) -> Option<Url> {
     breezyshim::location::rcp_location_to_url(url).ok()
 }
 
+/// Convert a rcp-style location (e.g. "git@example.com:foo/bar") to a ssh URL.
+#[cfg(not(feature = "breezy"))]
+pub fn fixup_rcp_style_git_repo_url(url: &str) -> Option<Url> {
+    if url.contains("://") {
+        return None;
+    }
+    let (_, user, host, path) =
+        lazy_regex::regex_captures!(r"^(?:([^@:/]+)@)?([^/:]{2,}):(.*)$", url)?;
+    let user = if user.is_empty() {
+        String::new()
+    } else {
+        format!("{}@", user)
+    };
+    Url::parse(&format!(
+        "ssh://{}{}/{}",
+        user,
+        host,
+        path.trim_start_matches('/')
+    ))
+    .ok()
+}
+
+/// Convert a CVSROOT (e.g. ":pserver:anonymous@cvs.example.com:/cvsroot") to a URL.
+#[cfg(feature = "breezy")]
+pub(crate) fn cvs_to_url(cvsroot: &str) -> Option<Url> {
+    Some(breezyshim::location::cvs_to_url(cvsroot))
+}
+
+/// Convert a CVSROOT (e.g. ":pserver:anonymous@cvs.example.com:/cvsroot") to a URL.
+#[cfg(not(feature = "breezy"))]
+pub(crate) fn cvs_to_url(cvsroot: &str) -> Option<Url> {
+    let parts = cvsroot.split(':').collect::<Vec<_>>();
+    if parts.len() < 4 || !parts[0].is_empty() {
+        return None;
+    }
+    let scheme = match parts[1] {
+        "pserver" => "pserver",
+        "ssh" | "extssh" => "ssh",
+        _ => return None,
+    };
+    Url::parse(&format!(
+        "cvs+{}://{}/{}",
+        scheme,
+        parts[2],
+        parts[3].trim_start_matches('/')
+    ))
+    .ok()
+}
+
+#[cfg(feature = "breezy")]
 pub fn try_open_branch(
     url: &url::Url,
     branch_name: Option<&str>,
@@ -912,6 +1521,27 @@ pub fn try_open_branch(
     rev
 }
 
+/// Determine the last revision of a branch, using Breezy.
+#[cfg(feature = "breezy")]
+async fn branch_revision(url: &url::Url, branch_name: Option<&str>) -> Option<String> {
+    try_open_branch(url, branch_name).map(|b| b.last_revision().to_string())
+}
+
+/// Determine the last revision of a branch, assuming it is a Git repository served over
+/// HTTP(S).
+#[cfg(all(feature = "net", not(feature = "breezy")))]
+async fn branch_revision(url: &url::Url, branch_name: Option<&str>) -> Option<String> {
+    let refs = git_http_refs(url).await?;
+    let name = branch_name.map_or_else(|| "HEAD".to_string(), |b| format!("refs/heads/{}", b));
+    refs.into_iter()
+        .find_map(|(oid, n)| if n == name { Some(oid) } else { None })
+}
+
+#[cfg(not(any(feature = "net", feature = "breezy")))]
+async fn branch_revision(_url: &url::Url, _branch_name: Option<&str>) -> Option<String> {
+    None
+}
+
 pub async fn find_secure_repo_url(
     mut url: url::Url,
     branch: Option<&str>,
@@ -924,11 +1554,14 @@ pub async fn find_secure_repo_url(
     // Sites we know to be available over https
     if let Some(hostname) = url.host_str() {
         if is_gitlab_site(hostname, net_access).await
+            || is_gitea_site(hostname, net_access).await
             || [
                 "github.com",
                 "git.launchpad.net",
                 "bazaar.launchpad.net",
                 "code.launchpad.net",
+                "git.sr.ht",
+                "hg.sr.ht",
             ]
             .contains(&hostname)
         {
@@ -956,12 +1589,10 @@ pub async fn find_secure_repo_url(
 
     if net_access.unwrap_or(true) {
         let secure_repo_url = derive_with_scheme(&url, "https");
-        let insecure_branch = try_open_branch(&url, branch);
-        let secure_branch = try_open_branch(&secure_repo_url, branch);
-        if let Some(secure_branch) = secure_branch {
-            if insecure_branch.is_none()
-                || secure_branch.last_revision() == insecure_branch.unwrap().last_revision()
-            {
+        let insecure_revision = branch_revision(&url, branch).await;
+        let secure_revision = branch_revision(&secure_repo_url, branch).await;
+        if let Some(secure_revision) = secure_revision {
+            if insecure_revision.is_none() || insecure_revision == Some(secure_revision) {
                 url = secure_repo_url;
             }
         }
@@ -1051,6 +1682,17 @@ async fn fix_gitlab_scheme(url: &str) -> Option<String> {
     None
 }
 
+async fn fix_gitea_scheme(url: &str) -> Option<String> {
+    if let Ok(url) = url::Url::parse(url) {
+        if let Some(host) = url.host_str() {
+            if is_gitea_site(host, None).await {
+                return Some(derive_with_scheme(&url, "https").to_string());
+            }
+        }
+    }
+    None
+}
+
 fn fix_github_scheme(url: &str) -> Option<String> {
     // GitHub no longer supports the git:// scheme
     if let Ok(url) = url::Url::parse(url) {
@@ -1061,6 +1703,40 @@ fn fix_github_scheme(url: &str) -> Option<String> {
     None
 }
 
+fn fix_gitee_scheme(url: &str) -> Option<String> {
+    // Gitee does not serve the git:// scheme
+    if let Ok(url) = url::Url::parse(url) {
+        if url.host_str() == Some("gitee.com") && url.scheme() != "https" {
+            return Some(derive_with_scheme(&url, "https").to_string());
+        }
+    }
+    None
+}
+
+fn fix_osdn_git_url(url: &str) -> Option<String> {
+    // Anonymous access moved from git.osdn.net (and git.sourceforge.jp) to scm.osdn.net
+    if let Ok(url) = url::Url::parse(url) {
+        if matches!(
+            url.host_str(),
+            Some("git.osdn.net") | Some("git.osdn.jp") | Some("git.sourceforge.jp")
+        ) && url.path().starts_with("/gitroot/")
+        {
+            let mut url = derive_with_scheme(&url, "https");
+            url.set_host(Some("scm.osdn.net")).unwrap();
+            return Some(url.to_string());
+        }
+    }
+    None
+}
+
+/// Rewrite URLs on hosts that have moved, keeping the path.
+fn fix_migrated_host(url: &str) -> Option<String> {
+    let mut url = Url::parse(url).ok()?;
+    let new_host = crate::data::tables().migrated_host(url.host_str()?)?;
+    url.set_host(Some(new_host)).ok()?;
+    Some(url.to_string())
+}
+
 fn fix_salsa_cgit_url(url: &str) -> Option<String> {
     if let Ok(url) = url::Url::parse(url) {
         if url.host_str() == Some("salsa.debian.org") {
@@ -1127,7 +1803,15 @@ fn fix_extra_colon(url: &str) -> Option<String> {
 fn drop_git_username(url: &str) -> Option<String> {
     if let Ok(mut url) = url::Url::parse(url) {
         if let Some(host) = url.host_str() {
-            if !["github.com", "salsa.debian.org", "gitlab.com"].contains(&host) {
+            if ![
+                "github.com",
+                "salsa.debian.org",
+                "gitlab.com",
+                "gitee.com",
+                "bitbucket.org",
+            ]
+            .contains(&host)
+            {
                 return None;
             }
         } else {
@@ -1237,7 +1921,10 @@ type AsyncFixer = for<'a> fn(
 const URL_FIXERS: &[AsyncFixer] = &[
     |url| Box::pin(async move { fix_path_in_port(url) }),
     |url| Box::pin(async move { fix_gitlab_scheme(url).await }),
+    |url| Box::pin(async move { fix_gitea_scheme(url).await }),
     |url| Box::pin(async move { fix_github_scheme(url) }),
+    |url| Box::pin(async move { fix_gitee_scheme(url) }),
+    |url| Box::pin(async move { fix_osdn_git_url(url) }),
     |url| Box::pin(async move { fix_salsa_cgit_url(url) }),
     |url| Box::pin(async move { fix_double_slash(url) }),
     |url| Box::pin(async move { fix_extra_colon(url) }),
@@ -1245,6 +1932,7 @@ const URL_FIXERS: &[AsyncFixer] = &[
     |url| Box::pin(async move { fix_freedesktop_org_url(url) }),
     |url| Box::pin(async move { fix_kde_anongit_url(url) }),
     |url| Box::pin(async move { fix_git_gnome_org_url(url) }),
+    |url| Box::pin(async move { fix_migrated_host(url) }),
 ];
 
 pub async fn fixup_git_url(url: &str) -> String {
@@ -1257,7 +1945,7 @@ pub async fn fixup_git_url(url: &str) -> String {
 
 pub fn convert_cvs_list_to_str(urls: &[&str]) -> Option<String> {
     if urls[0].starts_with(":extssh:") || urls[0].starts_with(":pserver:") {
-        let url = breezyshim::location::cvs_to_url(urls[0]);
+        let url = cvs_to_url(urls[0])?;
         Some(format!("{}#{}", url, urls[1]))
     } else {
         None
@@ -1294,7 +1982,141 @@ pub const SANITIZERS: &[AsyncSanitizer] = &[
     |url| Box::pin(async move { find_secure_repo_url(url.parse().ok()?, None, Some(false)).await }),
 ];
 
+/// Preferred format for repository URLs in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RepositoryUrlPolicy {
+    /// Leave repository URLs in the format they were found in.
+    #[default]
+    Preserve,
+
+    /// Prefer plain https URLs, e.g. https://github.com/foo/bar
+    Https,
+
+    /// Prefer https URLs with the VCS in the scheme, e.g. git+https://github.com/foo/bar
+    VcsHttps,
+
+    /// Prefer ssh URLs that can be pushed to, e.g. ssh://git@github.com/foo/bar
+    Ssh,
+}
+
+impl std::str::FromStr for RepositoryUrlPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "preserve" => Ok(RepositoryUrlPolicy::Preserve),
+            "https" => Ok(RepositoryUrlPolicy::Https),
+            "git+https" | "vcs+https" => Ok(RepositoryUrlPolicy::VcsHttps),
+            "ssh" => Ok(RepositoryUrlPolicy::Ssh),
+            _ => Err(format!("unknown repository URL policy: {}", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for RepositoryUrlPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RepositoryUrlPolicy::Preserve => write!(f, "preserve"),
+            RepositoryUrlPolicy::Https => write!(f, "https"),
+            RepositoryUrlPolicy::VcsHttps => write!(f, "git+https"),
+            RepositoryUrlPolicy::Ssh => write!(f, "ssh"),
+        }
+    }
+}
+
+/// Find the plain https variant of a repository URL.
+async fn https_repo_url(url: &str, branch: Option<&str>, net_access: Option<bool>) -> Option<Url> {
+    let mut parsed = match Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(_) => Url::parse(&find_public_repo_url(url, net_access).await?).ok()?,
+    };
+    if let Some(dropped) = drop_vcs_in_scheme(&parsed) {
+        parsed = dropped;
+    }
+    if parsed.scheme() == "https" {
+        return Some(parsed);
+    }
+    if let Some(public) = find_public_repo_url(parsed.as_str(), net_access).await {
+        parsed = Url::parse(&public).ok()?;
+    }
+    let secure = find_secure_repo_url(parsed, branch, net_access).await?;
+    if secure.scheme() == "https" {
+        Some(secure)
+    } else {
+        None
+    }
+}
+
+/// Check whether a https repository URL is known to point at a git repository.
+async fn is_known_git_url(url: &Url, net_access: Option<bool>) -> bool {
+    match url.host_str() {
+        Some("github.com") | Some("git.launchpad.net") | Some("git.sr.ht") => true,
+        Some(host) if is_gitlab_site(host, net_access).await => true,
+        _ => url.path().ends_with(".git"),
+    }
+}
+
+/// Rewrite a repository URL according to the specified policy.
+///
+/// Returns the URL unchanged if it can not be converted.
+pub async fn apply_repository_url_policy(
+    location: &str,
+    policy: RepositoryUrlPolicy,
+    net_access: Option<bool>,
+) -> String {
+    if policy == RepositoryUrlPolicy::Preserve {
+        return location.to_string();
+    }
+    let (url, branch, subpath) = split_vcs_url(location);
+    let https_url = match https_repo_url(&url, branch.as_deref(), net_access).await {
+        Some(url) => url,
+        None => {
+            debug!("Unable to apply policy {} to {}", policy, location);
+            return location.to_string();
+        }
+    };
+    let new_url = match policy {
+        RepositoryUrlPolicy::Preserve => unreachable!(),
+        RepositoryUrlPolicy::Https => https_url.to_string(),
+        RepositoryUrlPolicy::VcsHttps => {
+            if is_known_git_url(&https_url, net_access).await {
+                format!("git+{}", https_url)
+            } else {
+                https_url.to_string()
+            }
+        }
+        RepositoryUrlPolicy::Ssh => match https_url.host_str() {
+            Some("github.com") => format!("ssh://git@github.com{}", https_url.path()),
+            Some("git.launchpad.net") => format!("git+ssh://git.launchpad.net{}", https_url.path()),
+            Some("code.launchpad.net") | Some("bazaar.launchpad.net") => {
+                format!("bzr+ssh://bazaar.launchpad.net{}", https_url.path())
+            }
+            Some(host) if is_gitlab_site(host, net_access).await => {
+                format!("ssh://git@{}{}", host, https_url.path())
+            }
+            _ => {
+                debug!("No known ssh URL for {}", https_url);
+                return location.to_string();
+            }
+        },
+    };
+    unsplit_vcs_url(&VcsLocation {
+        url: new_url.parse().unwrap(),
+        branch,
+        subpath,
+    })
+}
+
 pub async fn sanitize_url(url: &str) -> String {
+    if alternative_vcs_for_url(url).is_some() {
+        // The sanitizers are mostly geared towards git; leave URLs for other version control
+        // systems alone, other than dropping the VCS from the scheme.
+        return url
+            .parse()
+            .ok()
+            .and_then(|u| drop_vcs_in_scheme(&u))
+            .map_or_else(|| url.to_string(), |u| u.to_string());
+    }
     let mut url: Cow<'_, str> = Cow::Borrowed(url);
     for sanitizer in SANITIZERS {
         url = sanitizer(url.as_ref())
@@ -1306,7 +2128,31 @@ pub async fn sanitize_url(url: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::fixup_git_url;
+    use super::{
+        fixup_git_url, mirror_source_from_text, url_host, version_from_tag, version_in_tags,
+    };
+
+    #[test]
+    fn test_version_from_tag() {
+        assert_eq!(version_from_tag("v1.2.3"), Some("1.2.3".to_string()));
+        assert_eq!(version_from_tag("foo-1.2"), Some("1.2".to_string()));
+        assert_eq!(version_from_tag("release/1.0"), Some("1.0".to_string()));
+        assert_eq!(version_from_tag("foo_1_2_3"), Some("1.2.3".to_string()));
+        assert_eq!(version_from_tag("latest"), None);
+        assert_eq!(version_from_tag("r2d2"), None);
+        assert_eq!(version_from_tag("foo2-1.0"), Some("1.0".to_string()));
+    }
+
+    #[test]
+    fn test_version_in_tags() {
+        assert!(version_in_tags("1.0", &["0.9", "1.0"]));
+        assert!(version_in_tags("1.0", &["v1.0.0"]));
+        assert!(version_in_tags("1.2.3", &["release/1.2.3"]));
+        assert!(version_in_tags("1.2.3", &["foo_1_2_3"]));
+        assert!(version_in_tags("1.2.3", &["foo-1.2.3"]));
+        assert!(!version_in_tags("1.2.3", &["1.2.30", "11.2.3"]));
+        assert!(version_in_tags("snapshot", &["snapshot"]));
+    }
 
     async fn fixup_git_location(url: &str) -> String {
         super::fixup_git_location(&super::VcsLocation::from_str(url).await)
@@ -1324,6 +2170,133 @@ mod tests {
         assert!(plausible_url("https://foo/blah"));
     }
 
+    #[test]
+    fn test_alternative_vcs_for_url() {
+        use super::alternative_vcs_for_url;
+        assert_eq!(
+            Some("radicle"),
+            alternative_vcs_for_url("rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5")
+        );
+        assert_eq!(
+            Some("radicle"),
+            alternative_vcs_for_url(
+                "https://app.radicle.xyz/nodes/seed.radicle.garden/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5"
+            )
+        );
+        assert_eq!(
+            Some("pijul"),
+            alternative_vcs_for_url("https://nest.pijul.com/pijul/pijul")
+        );
+        assert_eq!(
+            Some("darcs"),
+            alternative_vcs_for_url("https://hub.darcs.net/simon/darcsden")
+        );
+        assert_eq!(
+            Some("monotone"),
+            alternative_vcs_for_url("mtn://code.monotone.ca/monotone?net.venge.monotone")
+        );
+        assert_eq!(
+            None,
+            alternative_vcs_for_url("https://github.com/jelmer/dulwich")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sanitize_alternative_vcs_url() {
+        use super::sanitize_url;
+        assert_eq!(
+            "rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5",
+            sanitize_url("rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5").await
+        );
+        assert_eq!(
+            "https://nest.pijul.com/pijul/pijul",
+            sanitize_url("pijul+https://nest.pijul.com/pijul/pijul").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_guess_repo_from_alternative_vcs_url() {
+        use super::guess_repo_from_url;
+        assert_eq!(
+            Some("https://hub.darcs.net/simon/darcsden".to_string()),
+            guess_repo_from_url(
+                &"https://hub.darcs.net/simon/darcsden/browse/README.md"
+                    .parse()
+                    .unwrap(),
+                Some(false)
+            )
+            .await
+        );
+        assert_eq!(
+            Some("rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5".to_string()),
+            guess_repo_from_url(
+                &"https://app.radicle.xyz/nodes/seed.radicle.garden/rad:z3gqcJUoA1n9HaHKufZs5FCSGazv5/tree"
+                    .parse()
+                    .unwrap(),
+                Some(false)
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_repository_url_policy() {
+        use super::{apply_repository_url_policy, RepositoryUrlPolicy};
+
+        async fn apply(url: &str, policy: RepositoryUrlPolicy) -> String {
+            apply_repository_url_policy(url, policy, Some(false)).await
+        }
+
+        assert_eq!(
+            "git@github.com:jelmer/dulwich",
+            apply(
+                "git@github.com:jelmer/dulwich",
+                RepositoryUrlPolicy::Preserve
+            )
+            .await
+        );
+        assert_eq!(
+            "https://github.com/jelmer/dulwich",
+            apply("git@github.com:jelmer/dulwich", RepositoryUrlPolicy::Https).await
+        );
+        assert_eq!(
+            "https://github.com/jelmer/dulwich",
+            apply(
+                "git://github.com/jelmer/dulwich",
+                RepositoryUrlPolicy::Https
+            )
+            .await
+        );
+        assert_eq!(
+            "git+https://github.com/jelmer/dulwich",
+            apply(
+                "https://github.com/jelmer/dulwich",
+                RepositoryUrlPolicy::VcsHttps
+            )
+            .await
+        );
+        assert_eq!(
+            "https://github.com/jelmer/dulwich",
+            apply(
+                "git+https://github.com/jelmer/dulwich",
+                RepositoryUrlPolicy::Https
+            )
+            .await
+        );
+        assert_eq!(
+            "ssh://git@salsa.debian.org/jelmer/dulwich.git -b debian/main",
+            apply(
+                "https://salsa.debian.org/jelmer/dulwich.git -b debian/main",
+                RepositoryUrlPolicy::Ssh
+            )
+            .await
+        );
+        assert_eq!(
+            "https://example.com/foo",
+            apply("https://example.com/foo", RepositoryUrlPolicy::Ssh).await
+        );
+    }
+
     #[tokio::test]
     async fn test_is_gitlab_site() {
         use super::is_gitlab_site;
@@ -1335,6 +2308,18 @@ mod tests {
         assert!(!is_gitlab_site("foo.example.com", Some(false)).await);
     }
 
+    #[tokio::test]
+    async fn test_is_gitea_site() {
+        use super::{is_forgejo_site, is_gitea_site};
+
+        assert!(is_gitea_site("codeberg.org", Some(false)).await);
+        assert!(is_gitea_site("gitea.com", Some(false)).await);
+        assert!(is_gitea_site("forgejo.example.com", Some(false)).await);
+        assert!(!is_gitea_site("gitlab.com", Some(false)).await);
+        assert!(is_forgejo_site("codeberg.org", Some(false)).await);
+        assert!(!is_forgejo_site("gitea.com", Some(false)).await);
+    }
+
     #[tokio::test]
     async fn test_canonicalize_github() {
         use super::canonical_git_repo_url;
@@ -1444,6 +2429,62 @@ mod tests {
         );
     }
 
+    #[cfg(not(feature = "breezy"))]
+    #[test]
+    fn test_cvs_to_url() {
+        use super::cvs_to_url;
+        assert_eq!(
+            Some(
+                "cvs+pserver://anonymous@cvs.example.com/cvsroot/foo"
+                    .parse()
+                    .unwrap()
+            ),
+            cvs_to_url(":pserver:anonymous@cvs.example.com:/cvsroot/foo")
+        );
+        assert_eq!(
+            Some("cvs+ssh://cvs.example.com/cvsroot".parse().unwrap()),
+            cvs_to_url(":extssh:cvs.example.com:/cvsroot")
+        );
+        assert_eq!(None, cvs_to_url("/var/lib/cvs"));
+        assert_eq!(None, cvs_to_url(":local:/var/lib/cvs"));
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn test_parse_ref_advertisement() {
+        use super::parse_ref_advertisement;
+        let oid = "1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d";
+        let peeled = "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c";
+        let pkt = |line: String| format!("{:04x}{}", line.len() + 4, line);
+        let body = [
+            pkt("# service=git-upload-pack\n".to_string()),
+            "0000".to_string(),
+            pkt(format!("{} HEAD\0multi_ack side-band-64k\n", oid)),
+            pkt(format!("{} refs/heads/main\n", oid)),
+            pkt(format!("{} refs/tags/v1.0\n", oid)),
+            pkt(format!("{} refs/tags/v1.0^{{}}\n", peeled)),
+            "0000".to_string(),
+        ]
+        .concat();
+        assert_eq!(
+            parse_ref_advertisement(body.as_bytes()),
+            Some(vec![
+                (oid.to_string(), "HEAD".to_string()),
+                (oid.to_string(), "refs/heads/main".to_string()),
+                (oid.to_string(), "refs/tags/v1.0".to_string()),
+                (peeled.to_string(), "refs/tags/v1.0^{}".to_string()),
+            ])
+        );
+
+        let empty = [
+            pkt(format!("{} capabilities^{{}}\0multi_ack\n", "0".repeat(40))),
+            "0000".to_string(),
+        ]
+        .concat();
+        assert_eq!(parse_ref_advertisement(empty.as_bytes()), Some(vec![]));
+        assert_eq!(parse_ref_advertisement(b"<html></html>"), None);
+    }
+
     #[test]
     fn test_fixup_rcp_leave() {
         use super::fixup_rcp_style_git_repo_url;
@@ -1508,6 +2549,162 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_guess_repo_url_gitee_osdn_bitbucket() {
+        use super::guess_repo_from_url;
+        for (url, expected) in [
+            (
+                "https://gitee.com/openeuler/kernel/tree/master/fs",
+                Some("https://gitee.com/openeuler/kernel"),
+            ),
+            (
+                "https://gitee.com/openeuler/kernel.git",
+                Some("https://gitee.com/openeuler/kernel"),
+            ),
+            (
+                "https://osdn.net/projects/ttssh2/scm/git/ttssh2/tree/main/",
+                Some("https://scm.osdn.net/gitroot/ttssh2/ttssh2.git"),
+            ),
+            ("https://osdn.net/projects/ttssh2/", None),
+            (
+                "https://api.bitbucket.org/2.0/repositories/fenics-project/dolfin",
+                Some("https://bitbucket.org/fenics-project/dolfin"),
+            ),
+            (
+                "https://bitbucket.org/fenics-project/workspace/projects/",
+                None,
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                guess_repo_from_url(&url.parse().unwrap(), Some(false))
+                    .await
+                    .as_deref(),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_browse_url_from_repo_gitee_osdn_bitbucket() {
+        use super::{browse_url_from_repo_url, VcsLocation};
+        for (url, branch, expected) in [
+            (
+                "https://gitee.com/openeuler/kernel.git",
+                None,
+                "https://gitee.com/openeuler/kernel",
+            ),
+            (
+                "https://bitbucket.org/fenics-project/dolfin",
+                Some("next"),
+                "https://bitbucket.org/fenics-project/dolfin/src/next",
+            ),
+            (
+                "https://scm.osdn.net/gitroot/ttssh2/ttssh2.git",
+                None,
+                "https://osdn.net/projects/ttssh2/scm/git/ttssh2/",
+            ),
+        ] {
+            assert_eq!(
+                Some(expected.parse().unwrap()),
+                browse_url_from_repo_url(
+                    &VcsLocation {
+                        url: url.parse().unwrap(),
+                        branch: branch.map(|b| b.to_string()),
+                        subpath: None,
+                    },
+                    Some(false)
+                )
+                .await,
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sourcehut() {
+        use super::{browse_url_from_repo_url, guess_repo_from_url, VcsLocation};
+        assert_eq!(
+            Some("https://git.sr.ht/~sircmpwn/scdoc".to_string()),
+            guess_repo_from_url(
+                &"https://git.sr.ht/~sircmpwn/scdoc/tree/master/item/README.md"
+                    .parse()
+                    .unwrap(),
+                Some(false)
+            )
+            .await
+        );
+        assert_eq!(
+            None,
+            guess_repo_from_url(&"https://git.sr.ht/~sircmpwn".parse().unwrap(), Some(false)).await
+        );
+        assert_eq!(
+            Some(
+                "https://git.sr.ht/~sircmpwn/scdoc/tree/HEAD/item/scdoc.5.scd"
+                    .parse()
+                    .unwrap()
+            ),
+            browse_url_from_repo_url(
+                &VcsLocation {
+                    url: "https://git.sr.ht/~sircmpwn/scdoc".parse().unwrap(),
+                    branch: None,
+                    subpath: Some("scdoc.5.scd".to_string()),
+                },
+                Some(false)
+            )
+            .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gitea() {
+        use super::{browse_url_from_repo_url, guess_repo_from_url, VcsLocation};
+        assert_eq!(
+            Some("https://codeberg.org/forgejo/forgejo".to_string()),
+            guess_repo_from_url(
+                &"https://codeberg.org/forgejo/forgejo/src/branch/forgejo/README.md"
+                    .parse()
+                    .unwrap(),
+                Some(false)
+            )
+            .await
+        );
+        assert_eq!(
+            Some(
+                "https://codeberg.org/forgejo/forgejo/src/branch/forgejo/docs"
+                    .parse()
+                    .unwrap()
+            ),
+            browse_url_from_repo_url(
+                &VcsLocation {
+                    url: "https://codeberg.org/forgejo/forgejo.git".parse().unwrap(),
+                    branch: Some("forgejo".to_string()),
+                    subpath: Some("docs".to_string()),
+                },
+                Some(false)
+            )
+            .await
+        );
+        assert_eq!(
+            "https://codeberg.org/forgejo/forgejo",
+            super::fixup_git_url("git://codeberg.org/forgejo/forgejo").await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixup_gitee_osdn() {
+        assert_eq!(
+            "https://gitee.com/openeuler/kernel",
+            super::fixup_git_url("git://gitee.com/openeuler/kernel").await
+        );
+        assert_eq!(
+            "https://scm.osdn.net/gitroot/ttssh2/ttssh2.git",
+            super::fixup_git_url("git://git.osdn.net/gitroot/ttssh2/ttssh2.git").await
+        );
+    }
+
     #[tokio::test]
     async fn test_fixup_git_location() {
         use super::{fixup_git_location, VcsLocation};
@@ -1702,6 +2899,14 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_migrated_host() {
+        assert_eq!(
+            "https://opendev.org/openstack/nova",
+            fixup_git_url("https://git.openstack.org/openstack/nova").await
+        );
+    }
+
     #[tokio::test]
     async fn test_anongit() {
         assert_eq!(
@@ -1717,4 +2922,111 @@ mod tests {
             fixup_git_url("https://git.gnome.org/browse/alacarte").await
         );
     }
+
+    #[test]
+    fn test_mirror_source_from_text() {
+        assert_eq!(
+            mirror_source_from_text(
+                "This is a read-only mirror of https://gitlab.gnome.org/GNOME/gnome-maps."
+            ),
+            Some("https://gitlab.gnome.org/GNOME/gnome-maps".to_string())
+        );
+        assert_eq!(
+            mirror_source_from_text(
+                "Mirror of [the upstream repository](https://git.example.com/foo.git)"
+            ),
+            Some("https://git.example.com/foo.git".to_string())
+        );
+        assert_eq!(
+            mirror_source_from_text("The canonical repository is at <https://sr.ht/~foo/bar>"),
+            Some("https://sr.ht/~foo/bar".to_string())
+        );
+        assert_eq!(
+            mirror_source_from_text("See https://example.com/ for details"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://github.com/foo/bar"),
+            Some("github.com".to_string())
+        );
+        assert_eq!(
+            url_host("git@gitlab.com:foo/bar.git"),
+            Some("gitlab.com".to_string())
+        );
+        assert_eq!(url_host("../bar"), None);
+    }
+
+    #[test]
+    fn test_repo_url_from_browse_url() {
+        use super::repo_url_from_browse_url;
+        let repo = |u: &str| repo_url_from_browse_url(&u.parse().unwrap()).map(|u| u.to_string());
+        assert_eq!(
+            repo("https://git.savannah.gnu.org/cgit/emacs.git/about/"),
+            Some("https://git.savannah.gnu.org/git/emacs.git".to_string())
+        );
+        assert_eq!(
+            repo("https://git.kernel.org/pub/scm/git/git.git/tree/README.md"),
+            Some("https://git.kernel.org/pub/scm/git/git.git".to_string())
+        );
+        assert_eq!(
+            repo("https://example.com/cgit/foo/log/?h=main"),
+            Some("https://example.com/git/foo".to_string())
+        );
+        assert_eq!(
+            repo("https://sourceware.org/git/?p=glibc.git;a=summary"),
+            Some("https://sourceware.org/git/glibc.git".to_string())
+        );
+        assert_eq!(
+            repo("https://git.postgresql.org/gitweb/?p=postgresql.git;a=tree"),
+            Some("https://git.postgresql.org/git/postgresql.git".to_string())
+        );
+        assert_eq!(
+            repo("https://example.com/gitweb.cgi?p=tools/foo.git"),
+            Some("https://example.com/tools/foo.git".to_string())
+        );
+        assert_eq!(repo("https://example.com/foo.git"), None);
+        assert_eq!(repo("https://example.com/search?p=foo"), None);
+        assert_eq!(repo("https://example.com/cgit/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_browse_url_from_self_hosted_repo() {
+        use super::browse_url_from_repo_url;
+        let browse = |u: &str, subpath: Option<&str>| {
+            let location = super::VcsLocation {
+                url: u.parse().unwrap(),
+                branch: None,
+                subpath: subpath.map(|s| s.to_string()),
+            };
+            async move {
+                browse_url_from_repo_url(&location, Some(false))
+                    .await
+                    .map(|u| u.to_string())
+            }
+        };
+        assert_eq!(
+            browse("https://git.kernel.org/pub/scm/git/git.git", None).await,
+            Some("https://git.kernel.org/pub/scm/git/git.git".to_string())
+        );
+        assert_eq!(
+            browse(
+                "https://git.kernel.org/pub/scm/git/git.git",
+                Some("Documentation")
+            )
+            .await,
+            Some("https://git.kernel.org/pub/scm/git/git.git/tree/Documentation".to_string())
+        );
+        assert_eq!(
+            browse("https://git.postgresql.org/git/postgresql.git", None).await,
+            Some("https://git.postgresql.org/gitweb/?p=postgresql.git;a=summary".to_string())
+        );
+        assert_eq!(
+            browse("https://sourceware.org/git/glibc.git", Some("elf")).await,
+            Some("https://sourceware.org/git/?p=glibc.git;a=tree;f=elf".to_string())
+        );
+    }
 }
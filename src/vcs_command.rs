@@ -1,4 +1,5 @@
 use crate::vcs;
+#[cfg(feature = "providers")]
 use crate::GuesserSettings;
 use log::warn;
 
@@ -32,6 +33,32 @@ fn parse_command_bytes(command: &[u8]) -> Option<Vec<String>> {
     }
 }
 
+/// Command prefixes that indicate a command that retrieves a copy of a repository.
+pub const VCS_COMMAND_PREFIXES: &[&str] = &[
+    "git clone ",
+    "fossil clone ",
+    "hg clone ",
+    "bzr co ",
+    "bzr checkout ",
+    "bzr branch ",
+    "brz co ",
+    "brz checkout ",
+    "brz branch ",
+    "darcs clone ",
+    "darcs get ",
+    "svn co ",
+    "svn checkout ",
+    "cvs ",
+    "repo init ",
+];
+
+/// Check whether a command line looks like it retrieves a copy of a repository.
+pub fn is_vcs_command(cmdline: &str) -> bool {
+    VCS_COMMAND_PREFIXES
+        .iter()
+        .any(|prefix| cmdline.starts_with(prefix))
+}
+
 pub fn url_from_vcs_command(command: &[u8]) -> Option<String> {
     if let Some(url) = url_from_git_clone_command(command) {
         return Some(url);
@@ -39,15 +66,174 @@ pub fn url_from_vcs_command(command: &[u8]) -> Option<String> {
     if let Some(url) = url_from_fossil_clone_command(command) {
         return Some(url);
     }
+    if let Some(url) = url_from_hg_clone_command(command) {
+        return Some(url);
+    }
+    if let Some(url) = url_from_bzr_branch_command(command) {
+        return Some(url);
+    }
+    if let Some(url) = url_from_darcs_clone_command(command) {
+        return Some(url);
+    }
     if let Some(url) = url_from_cvs_co_command(command) {
         return Some(url);
     }
     if let Some(url) = url_from_svn_co_command(command) {
         return Some(url);
     }
+    if let Some(url) = url_from_repo_init_command(command) {
+        return Some(url);
+    }
+    None
+}
+
+/// Find the URL in a command of the form `<program> <subcommand> [OPTIONS] URL [TARGET]`.
+fn url_from_simple_clone_command(
+    command: &[u8],
+    programs: &[&str],
+    subcommands: &[&str],
+    options_with_argument: &[&str],
+) -> Option<String> {
+    let mut args = parse_command_bytes(command)?.into_iter();
+    if !programs.contains(&args.next()?.as_str()) {
+        return None;
+    }
+    if !subcommands.contains(&args.next()?.as_str()) {
+        return None;
+    }
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        if arg.starts_with('-') {
+            if options_with_argument.contains(&arg.as_str()) {
+                args.next();
+            }
+            continue;
+        }
+        positional.push(arg);
+    }
+    let url = positional.into_iter().next()?;
+    if vcs::plausible_url(&url) {
+        Some(url)
+    } else {
+        None
+    }
+}
+
+pub fn url_from_hg_clone_command(command: &[u8]) -> Option<String> {
+    url_from_simple_clone_command(
+        command,
+        &["hg"],
+        &["clone"],
+        &[
+            "-r",
+            "--rev",
+            "-b",
+            "--branch",
+            "-u",
+            "--updaterev",
+            "-e",
+            "--ssh",
+            "--remotecmd",
+        ],
+    )
+}
+
+#[test]
+fn test_url_from_hg_clone_command() {
+    assert_eq!(
+        Some("https://www.mercurial-scm.org/repo/hg".to_string()),
+        url_from_hg_clone_command(b"hg clone https://www.mercurial-scm.org/repo/hg"),
+    );
+    assert_eq!(
+        Some("https://foss.heptapod.net/mercurial/evolve".to_string()),
+        url_from_hg_clone_command(
+            b"hg clone -u stable https://foss.heptapod.net/mercurial/evolve evolve"
+        ),
+    );
+    assert_eq!(None, url_from_hg_clone_command(b"hg clone"));
+}
+
+pub fn url_from_bzr_branch_command(command: &[u8]) -> Option<String> {
+    url_from_simple_clone_command(
+        command,
+        &["bzr", "brz"],
+        &["branch", "co", "checkout", "get", "clone"],
+        &["-r", "--revision"],
+    )
+}
+
+#[test]
+fn test_url_from_bzr_branch_command() {
+    assert_eq!(
+        Some("lp:brz".to_string()),
+        url_from_bzr_branch_command(b"bzr branch lp:brz"),
+    );
+    assert_eq!(
+        Some("https://code.launchpad.net/bzr-svn".to_string()),
+        url_from_bzr_branch_command(b"brz co --lightweight https://code.launchpad.net/bzr-svn"),
+    );
+    assert_eq!(None, url_from_bzr_branch_command(b"bzr log lp:brz"));
+}
+
+pub fn url_from_darcs_clone_command(command: &[u8]) -> Option<String> {
+    url_from_simple_clone_command(
+        command,
+        &["darcs"],
+        &["clone", "get"],
+        &["-t", "--tag", "-p", "--patches", "--context"],
+    )
+}
+
+#[test]
+fn test_url_from_darcs_clone_command() {
+    assert_eq!(
+        Some("https://hub.darcs.net/darcs/darcs-screened".to_string()),
+        url_from_darcs_clone_command(
+            b"darcs clone --lazy https://hub.darcs.net/darcs/darcs-screened darcs"
+        ),
+    );
+    assert_eq!(
+        Some("http://darcs.net/".to_string()),
+        url_from_darcs_clone_command(b"darcs get http://darcs.net/"),
+    );
+}
+
+/// Find the manifest URL in a `repo init -u URL` command.
+pub fn url_from_repo_init_command(command: &[u8]) -> Option<String> {
+    let mut args = parse_command_bytes(command)?.into_iter();
+    if args.next()? != "repo" || args.next()? != "init" {
+        return None;
+    }
+    while let Some(arg) = args.next() {
+        let url = if arg == "-u" || arg == "--manifest-url" {
+            args.next()?
+        } else if let Some(url) = arg.strip_prefix("--manifest-url=") {
+            url.to_string()
+        } else {
+            continue;
+        };
+        if vcs::plausible_url(&url) {
+            return Some(url);
+        }
+    }
     None
 }
 
+#[test]
+fn test_url_from_repo_init_command() {
+    assert_eq!(
+        Some("https://android.googlesource.com/platform/manifest".to_string()),
+        url_from_repo_init_command(
+            b"repo init -u https://android.googlesource.com/platform/manifest -b main"
+        ),
+    );
+    assert_eq!(
+        Some("https://github.com/foo/manifest".to_string()),
+        url_from_repo_init_command(b"repo init --manifest-url=https://github.com/foo/manifest"),
+    );
+    assert_eq!(None, url_from_repo_init_command(b"repo sync"));
+}
+
 pub fn url_from_git_clone_command(command: &[u8]) -> Option<String> {
     let mut args = parse_command_bytes(command)?;
     if args.remove(0) != "git" || args.remove(0) != "clone" {
@@ -170,7 +356,7 @@ pub fn url_from_cvs_co_command(command: &[u8]) -> Option<String> {
         args.remove(i);
     }
     if let Some(cvsroot) = cvsroot {
-        let url = breezyshim::location::cvs_to_url(&cvsroot);
+        let url = crate::vcs::cvs_to_url(&cvsroot)?;
         if let Some(module) = module {
             return Some(url.join(module.as_str()).unwrap().to_string());
         }
@@ -181,7 +367,7 @@ pub fn url_from_cvs_co_command(command: &[u8]) -> Option<String> {
 
 pub fn url_from_svn_co_command(command: &[u8]) -> Option<String> {
     let args = parse_command_bytes(command)?;
-    if args[0] != "svn" || args[1] != "co" {
+    if args[0] != "svn" || !matches!(args.get(1).map(|s| s.as_str()), Some("co" | "checkout")) {
         return None;
     }
     let url_schemes = ["svn+ssh", "http", "https", "svn"];
@@ -192,28 +378,10 @@ pub fn url_from_svn_co_command(command: &[u8]) -> Option<String> {
     })
 }
 
+#[cfg(feature = "providers")]
 pub fn guess_from_get_orig_source(
     path: &std::path::Path,
-    _settings: &GuesserSettings,
+    settings: &GuesserSettings,
 ) -> Result<Vec<crate::UpstreamDatumWithMetadata>, crate::ProviderError> {
-    let text = std::fs::read(path)?;
-    let mut result = Vec::new();
-
-    for line in text.split(|b| *b == b'\n') {
-        if let Some(url) = url_from_vcs_command(line) {
-            let certainty = if url.contains('$') {
-                crate::Certainty::Possible
-            } else {
-                crate::Certainty::Likely
-            };
-
-            result.push(crate::UpstreamDatumWithMetadata {
-                datum: crate::UpstreamDatum::Repository(url),
-                certainty: Some(certainty),
-                origin: Some(path.into()),
-            });
-        }
-    }
-
-    Ok(result)
+    crate::providers::shell::guess_from_shell_script(path, settings)
 }
@@ -0,0 +1,275 @@
+//! Lenient parsing and ordering of version strings.
+//!
+//! Upstream projects use a wide variety of versioning schemes. This module understands
+//! enough of semantic versioning, PEP 440, Debian-style and date-based versions to order
+//! them sensibly, e.g. "1.0~rc1" < "1.0" < "1.0.post1" and "1.0" == "1.0.0".
+
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Kind of pre-release, in increasing order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PreRelease {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+/// A parsed version.
+#[derive(Debug, Clone)]
+pub struct Version {
+    original: String,
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(PreRelease, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+    suffix: Option<String>,
+}
+
+impl Version {
+    /// Parse a version string.
+    ///
+    /// A leading "v" is ignored. Returns None if the string does not start with a number.
+    pub fn parse(s: &str) -> Option<Self> {
+        let original = s.trim();
+        let s = original.to_lowercase();
+        let s = s
+            .strip_prefix('v')
+            .filter(|s| s.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(&s);
+
+        // Date-based versions, e.g. 2024-01-15
+        if let Some((_, y, m, d)) = lazy_regex::regex_captures!(r"^(\d{4})-(\d{2})-(\d{2})$", s) {
+            return Some(Self {
+                original: original.to_string(),
+                epoch: 0,
+                release: vec![y.parse().ok()?, m.parse().ok()?, d.parse().ok()?],
+                pre: None,
+                post: None,
+                dev: None,
+                suffix: None,
+            });
+        }
+
+        // Debian (N:) and PEP 440 (N!) epochs
+        let (epoch, s) = match lazy_regex::regex_captures!(r"^(\d+)[:!](.*)$", s) {
+            Some((_, epoch, rest)) => (epoch.parse().ok()?, rest),
+            None => (0, s),
+        };
+
+        let (_, release, mut rest) = lazy_regex::regex_captures!(r"^(\d+(?:[._]\d+)*)(.*)$", s)?;
+        let release = release
+            .split(['.', '_'])
+            .map(|c| c.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        // Build metadata (semver) and local versions (PEP 440) don't affect ordering
+        if let Some((r, _)) = rest.split_once('+') {
+            rest = r;
+        }
+
+        let number = |n: &str| n.parse::<u64>().unwrap_or(0);
+
+        let mut pre = None;
+        let mut post = None;
+        let mut dev = None;
+
+        if let Some((m, kind, n)) = lazy_regex::regex_captures!(
+            r"^[-._~]?(alpha|a|beta|b|preview|pre|rc|c)[-._]?(\d*)",
+            rest
+        ) {
+            let kind = match kind {
+                "alpha" | "a" => PreRelease::Alpha,
+                "beta" | "b" => PreRelease::Beta,
+                _ => PreRelease::Rc,
+            };
+            pre = Some((kind, number(n)));
+            rest = &rest[m.len()..];
+        }
+
+        if let Some((m, n)) = lazy_regex::regex_captures!(r"^[-._]?(?:post|rev|r)[-._]?(\d*)", rest)
+            .or_else(|| lazy_regex::regex_captures!(r"^-(\d+)", rest).map(|(m, n)| (m, n)))
+        {
+            // Explicit post-releases, and Debian revisions or implicit PEP 440 post-releases
+            post = Some(number(n));
+            rest = &rest[m.len()..];
+        }
+
+        if let Some((m, n)) = lazy_regex::regex_captures!(r"^[-._~]?dev[-._]?(\d*)", rest) {
+            dev = Some(number(n));
+            rest = &rest[m.len()..];
+        }
+
+        Some(Self {
+            original: original.to_string(),
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            suffix: if rest.is_empty() {
+                None
+            } else {
+                Some(rest.to_string())
+            },
+        })
+    }
+
+    /// The release components, e.g. [1, 2, 3] for "1.2.3rc1".
+    pub fn release(&self) -> &[u64] {
+        &self.release
+    }
+
+    /// The major version, i.e. the first release component.
+    pub fn major(&self) -> u64 {
+        self.release[0]
+    }
+
+    /// Whether this is a pre-release or development version.
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    /// Sort key for everything after the release components.
+    fn pre_key(&self) -> (u8, u64) {
+        match (self.pre, self.post, self.dev) {
+            (Some((kind, n)), _, _) => (kind as u8 + 1, n),
+            // Development versions of a final release sort before its pre-releases
+            (None, None, Some(n)) => (0, n),
+            (None, _, _) => (u8::MAX, 0),
+        }
+    }
+
+    fn dev_key(&self) -> (u8, u64) {
+        match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) | (_, _, None) => (1, 0),
+            (_, _, Some(n)) => (0, n),
+        }
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid version: {}", s))
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| {
+                let len = self.release.len().max(other.release.len());
+                let component = |v: &Version, i: usize| v.release.get(i).copied().unwrap_or(0);
+                (0..len)
+                    .map(|i| component(self, i).cmp(&component(other, i)))
+                    .find(|o| *o != Ordering::Equal)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .then_with(|| self.pre_key().cmp(&other.pre_key()))
+            .then_with(|| self.post.cmp(&other.post))
+            .then_with(|| self.dev_key().cmp(&other.dev_key()))
+            .then_with(|| self.suffix.cmp(&other.suffix))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// Compare two version strings.
+///
+/// Versions that can't be parsed sort before versions that can, and are compared as strings
+/// amongst themselves.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+/// Find the version in a tarball name, e.g. `foo-1.2.tar.gz`.
+pub fn version_from_tarball_name(name: &str) -> Option<String> {
+    let (_, _, version) = lazy_regex::regex_captures!(
+        r"([A-Za-z][A-Za-z0-9_.+]*?)[-_]v?([0-9][0-9A-Za-z.~+]*?)(?:\.orig)?\.(?:tar\.(?:gz|bz2|xz|lz|lzma|zst)|tgz|tbz2|zip)$",
+        name
+    )?;
+    Some(version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(v("1.2.3").release(), &[1, 2, 3]);
+        assert_eq!(v("v1.2").release(), &[1, 2]);
+        assert_eq!(v("1_2_3").release(), &[1, 2, 3]);
+        assert_eq!(v("2024-01-15").release(), &[2024, 1, 15]);
+        assert_eq!(v("2:1.0-3").major(), 1);
+        assert!(v("1.0rc1").is_prerelease());
+        assert!(v("1.0-alpha.1").is_prerelease());
+        assert!(v("1.0.dev3").is_prerelease());
+        assert!(!v("1.0.post1").is_prerelease());
+        assert!(Version::parse("latest").is_none());
+        assert!(Version::parse("").is_none());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let ordered = [
+            "0.9",
+            "1.0.dev1",
+            "1.0a1",
+            "1.0-beta.2",
+            "1.0~rc1",
+            "1.0",
+            "1.0.post1",
+            "1.0.1",
+            "1.10",
+            "2.0",
+            "1:0.1",
+        ];
+        for w in ordered.windows(2) {
+            assert!(v(w[0]) < v(w[1]), "{} < {}", w[0], w[1]);
+        }
+        assert_eq!(v("1.0"), v("1.0.0"));
+        assert_eq!(v("v1.0"), v("1.0"));
+        assert_eq!(v("1.0+build5"), v("1.0"));
+        assert!(v("1.0-1") < v("1.0-2"));
+        assert!(v("2024.01.15") < v("2024.02.01"));
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("latest", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+    }
+}
@@ -0,0 +1,361 @@
+//! Keep upstream metadata up to date while a source tree changes.
+//!
+//! This is intended for e.g. IDE integrations that display project metadata live. The tree
+//! is scanned once, after which only the guessers that read changed files are re-run (see
+//! [`crate::refresh`]).
+
+use crate::{FieldSet, ProviderError, UpstreamDatumWithMetadata, UpstreamMetadata};
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::StreamExt;
+use ignore::gitignore::Gitignore;
+use notify::Watcher;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Time to wait for more changes before re-scanning, so that e.g. a checkout of a
+/// different branch results in a single update.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Maximum time to keep waiting for more changes, so that a steady stream of changes
+/// (e.g. from a build) doesn't postpone re-scanning indefinitely.
+const MAX_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// A change to the value of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataChange {
+    /// Name of the field, e.g. "Version"
+    pub field: &'static str,
+
+    /// The previous value, if any
+    pub old: Option<UpstreamDatumWithMetadata>,
+
+    /// The new value, if any
+    pub new: Option<UpstreamDatumWithMetadata>,
+}
+
+/// Find the changes between two snapshots, for the given fields.
+///
+/// For multi-valued fields such as Donation, there is a change for every value that was
+/// added, removed or updated.
+pub fn diff_metadata(
+    old: &UpstreamMetadata,
+    new: &UpstreamMetadata,
+    fields: &FieldSet,
+) -> Vec<MetadataChange> {
+    let mut changes = vec![];
+    for field in fields.iter() {
+        let old = old.get_all(field).collect::<Vec<_>>();
+        let new = new.get_all(field).collect::<Vec<_>>();
+        if old == new {
+            continue;
+        }
+        if !crate::descriptors::is_multi_valued(field) {
+            changes.push(MetadataChange {
+                field,
+                old: old.first().copied().cloned(),
+                new: new.first().copied().cloned(),
+            });
+            continue;
+        }
+        // Values of multi-valued fields are matched up by their value
+        for o in &old {
+            let n = new.iter().find(|n| n.datum == o.datum);
+            if n != Some(o) {
+                changes.push(MetadataChange {
+                    field,
+                    old: Some((*o).clone()),
+                    new: n.copied().cloned(),
+                });
+            }
+        }
+        for n in &new {
+            if !old.iter().any(|o| o.datum == n.datum) {
+                changes.push(MetadataChange {
+                    field,
+                    old: None,
+                    new: Some((*n).clone()),
+                });
+            }
+        }
+    }
+    changes
+}
+
+/// Handle for a watched source tree.
+///
+/// The tree is no longer watched once this is dropped.
+pub struct MetadataWatcher {
+    snapshot: Arc<Mutex<UpstreamMetadata>>,
+    _watcher: notify::RecommendedWatcher,
+}
+
+impl MetadataWatcher {
+    /// The current metadata for the tree.
+    pub fn snapshot(&self) -> UpstreamMetadata {
+        self.snapshot.lock().unwrap().clone()
+    }
+}
+
+/// Load the patterns of files to ignore from the .gitignore file at the root of the tree.
+fn load_gitignore(root: &Path) -> Gitignore {
+    let (gitignore, err) = Gitignore::new(root.join(".gitignore"));
+    if let Some(e) = err {
+        if root.join(".gitignore").exists() {
+            log::warn!("Unable to parse .gitignore in {}: {}", root.display(), e);
+        }
+    }
+    gitignore
+}
+
+fn is_ignored(path: &Path, root: &Path, gitignore: &Gitignore) -> bool {
+    let relpath = path.strip_prefix(root).unwrap_or(path);
+    // Of the version control metadata, only .git/config is of interest
+    if relpath.starts_with(".bzr")
+        || relpath.starts_with(".hg")
+        || relpath.starts_with(".svn")
+        || relpath.starts_with("_darcs")
+        || (relpath.starts_with(".git") && relpath != Path::new(".git/config"))
+    {
+        return true;
+    }
+    // Build artifacts and the like, e.g. target/ or node_modules/
+    path.starts_with(root)
+        && gitignore
+            .matched_path_or_any_parents(path, path.is_dir())
+            .is_ignore()
+}
+
+fn process_changes(
+    root: &Path,
+    trust_package: Option<bool>,
+    changes: mpsc::Receiver<notify::Result<notify::Event>>,
+    snapshot: Arc<Mutex<UpstreamMetadata>>,
+    sender: UnboundedSender<MetadataChange>,
+) {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            log::error!(
+                "Unable to start runtime for watching {}: {}",
+                root.display(),
+                e
+            );
+            return;
+        }
+    };
+
+    let mut gitignore = load_gitignore(root);
+
+    // Returns when the watcher is dropped
+    while let Ok(event) = changes.recv() {
+        let mut changed_paths = vec![];
+        let mut event = Some(event);
+        let deadline = Instant::now() + MAX_DEBOUNCE;
+        while let Some(e) = event {
+            match e {
+                Ok(e) => changed_paths.extend(
+                    e.paths
+                        .into_iter()
+                        .filter(|p| !is_ignored(p, root, &gitignore)),
+                ),
+                Err(e) => log::warn!("Error watching {}: {}", root.display(), e),
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            event = changes.recv_timeout(DEBOUNCE.min(remaining)).ok();
+        }
+        changed_paths.sort();
+        changed_paths.dedup();
+        if changed_paths.is_empty() {
+            continue;
+        }
+        if changed_paths.contains(&root.join(".gitignore")) {
+            gitignore = load_gitignore(root);
+        }
+
+        let old = snapshot.lock().unwrap().clone();
+        let mut new = old.clone();
+        let affected = match runtime.block_on(crate::refresh(
+            &mut new,
+            root,
+            &changed_paths,
+            trust_package,
+        )) {
+            Ok(affected) => affected,
+            Err(e) => {
+                log::warn!("Unable to refresh metadata for {}: {}", root.display(), e);
+                continue;
+            }
+        };
+        *snapshot.lock().unwrap() = new.clone();
+
+        for change in diff_metadata(&old, &new, &affected) {
+            if sender.unbounded_send(change).is_err() {
+                // Nobody is listening anymore
+                return;
+            }
+        }
+    }
+}
+
+/// Scan a source tree and watch it for changes.
+///
+/// # Arguments
+/// * `path`: Path to the root of the tree
+/// * `trust_package`: Whether to trust the package contents and i.e. run executables in it
+///
+/// # Returns
+/// A handle to retrieve the current metadata from, and a stream of changes to it
+pub async fn watch(
+    path: &Path,
+    trust_package: Option<bool>,
+) -> Result<(MetadataWatcher, UnboundedReceiver<MetadataChange>), ProviderError> {
+    let root: PathBuf = path.canonicalize()?;
+
+    let (events_tx, events_rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(events_tx)
+        .map_err(|e| ProviderError::Other(format!("Unable to watch {}: {}", path.display(), e)))?;
+
+    // Start watching before the initial scan, so that no changes are missed
+    watcher
+        .watch(&root, notify::RecursiveMode::Recursive)
+        .map_err(|e| ProviderError::Other(format!("Unable to watch {}: {}", path.display(), e)))?;
+
+    let mut metadata = UpstreamMetadata::new();
+    metadata.update(
+        crate::MetadataGuesser::builder()
+            .trust(trust_package.unwrap_or(false))
+            .build()
+            .stream(&root)
+            .filter_map(|x| async {
+                match x {
+                    Ok(x) => Some(x),
+                    Err(e) => {
+                        log::error!("{}", e);
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .await
+            .into_iter(),
+    );
+    let snapshot = Arc::new(Mutex::new(metadata));
+
+    let (changes_tx, changes_rx) = unbounded();
+    std::thread::spawn({
+        let snapshot = snapshot.clone();
+        move || process_changes(&root, trust_package, events_rx, snapshot, changes_tx)
+    });
+
+    Ok((
+        MetadataWatcher {
+            snapshot,
+            _watcher: watcher,
+        },
+        changes_rx,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Certainty, UpstreamDatum};
+
+    fn datum(datum: UpstreamDatum) -> UpstreamDatumWithMetadata {
+        UpstreamDatumWithMetadata {
+            datum,
+            certainty: Some(Certainty::Certain),
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_metadata() {
+        let mut old = UpstreamMetadata::new();
+        old.insert(datum(UpstreamDatum::Name("foo".to_string())));
+        old.insert(datum(UpstreamDatum::Version("1.0".to_string())));
+        old.insert(datum(UpstreamDatum::Homepage(
+            "https://example.com/".to_string(),
+        )));
+        let mut new = UpstreamMetadata::new();
+        new.insert(datum(UpstreamDatum::Name("foo".to_string())));
+        new.insert(datum(UpstreamDatum::Version("1.1".to_string())));
+
+        let fields = ["Name", "Version", "Homepage"]
+            .into_iter()
+            .collect::<FieldSet>();
+        assert_eq!(
+            diff_metadata(&old, &new, &fields),
+            vec![
+                MetadataChange {
+                    field: "Homepage",
+                    old: Some(datum(UpstreamDatum::Homepage(
+                        "https://example.com/".to_string()
+                    ))),
+                    new: None,
+                },
+                MetadataChange {
+                    field: "Version",
+                    old: Some(datum(UpstreamDatum::Version("1.0".to_string()))),
+                    new: Some(datum(UpstreamDatum::Version("1.1".to_string()))),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_metadata_multi_valued() {
+        let donation = |url: &str| datum(UpstreamDatum::Donation(url.to_string()));
+        let mut old = UpstreamMetadata::new();
+        old.insert(donation("https://example.com/a"));
+        old.insert(donation("https://example.com/b"));
+        let mut new = UpstreamMetadata::new();
+        new.insert(donation("https://example.com/a"));
+        new.insert(donation("https://example.com/c"));
+
+        let fields = ["Donation"].into_iter().collect::<FieldSet>();
+        assert_eq!(
+            diff_metadata(&old, &new, &fields),
+            vec![
+                MetadataChange {
+                    field: "Donation",
+                    old: Some(donation("https://example.com/b")),
+                    new: None,
+                },
+                MetadataChange {
+                    field: "Donation",
+                    old: None,
+                    new: Some(donation("https://example.com/c")),
+                },
+            ]
+        );
+        assert!(diff_metadata(&old, &old, &fields).is_empty());
+    }
+
+    #[test]
+    fn test_is_ignored() {
+        let td = tempfile::tempdir().unwrap();
+        let root = td.path();
+        std::fs::write(root.join(".gitignore"), "/target\nnode_modules/\n*.o\n").unwrap();
+        std::fs::create_dir_all(root.join("node_modules/foo")).unwrap();
+        let gitignore = load_gitignore(root);
+        assert!(is_ignored(&root.join(".git/HEAD"), root, &gitignore));
+        assert!(!is_ignored(&root.join(".git/config"), root, &gitignore));
+        assert!(!is_ignored(&root.join("setup.py"), root, &gitignore));
+        assert!(is_ignored(&root.join("target/debug/foo"), root, &gitignore));
+        assert!(is_ignored(
+            &root.join("node_modules/foo/package.json"),
+            root,
+            &gitignore
+        ));
+        assert!(is_ignored(&root.join("src/foo.o"), root, &gitignore));
+        assert!(!is_ignored(&root.join("src/target"), root, &gitignore));
+    }
+}